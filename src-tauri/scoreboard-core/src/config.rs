@@ -0,0 +1,6461 @@
+use crate::expr::{Expression, ValueType};
+use crate::state::resolve_action_name;
+use serde::de::{self, MapAccess, Visitor};
+use serde::de::value::MapAccessDeserializer;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+pub const DEFAULT_CANVAS_WIDTH: i32 = 640;
+pub const DEFAULT_CANVAS_HEIGHT: i32 = 480;
+pub const MAX_CANVAS_SIDE: i32 = 8_192;
+/// Quarter turns `global.rotate` accepts, in clockwise degrees.
+const ROTATIONS: [u16; 4] = [0, 90, 180, 270];
+const DEFAULT_PERIOD_LABELS: [&str; 5] = ["1", "2", "3", "4", "OT"];
+const DEFAULT_RELAY_BAUD_RATE: u32 = 9_600;
+pub const DEFAULT_SERIAL_INPUT_BAUD_RATE: u32 = 19_200;
+/// Longest field a `daktronics` mapping can read; RTD fields are short.
+pub const MAX_SERIAL_FIELD_LENGTH: usize = 64;
+const DEFAULT_CONFIRM_TIMEOUT_MS: u64 = 3_000;
+const DEFAULT_CONFIRM_RESET_TIMEOUT_MS: u64 = 2_000;
+const DEFAULT_ANNOUNCEMENT_DURATION_MS: u64 = 5_000;
+/// Sound settings starting with this name a `[global.horn_pattern]` entry.
+pub const SOUND_PATTERN_PREFIX: &str = "pattern:";
+const DEFAULT_HORN_GAP_MS: u64 = 250;
+const MAX_HORN_BLASTS: usize = 16;
+const MAX_HORN_STEP_MS: u64 = 10_000;
+/// Stands in for secrets in `ScoreboardConfig::redacted`.
+pub const REDACTED: &str = "<redacted>";
+pub const DEFAULT_REMOTE_BIND: &str = "127.0.0.1";
+pub const DEFAULT_OSC_BIND: &str = "127.0.0.1";
+pub const DEFAULT_OSC_PREFIX: &str = "/scoreboard";
+pub const MAX_OSC_TARGETS: usize = 16;
+pub const DEFAULT_OBS_URL: &str = "ws://127.0.0.1:4455";
+pub const DEFAULT_SYNC_BIND: &str = "0.0.0.0";
+/// `[global.sync] primary` value that follows whichever primary mDNS finds first.
+pub const ANY_SYNC_PRIMARY: &str = "auto";
+/// Longest `[global.discovery] name`: one DNS label.
+pub const MAX_DISCOVERY_NAME_BYTES: usize = 63;
+pub const MAX_NOTE_CHECKLIST_ITEMS: usize = 100;
+const DEFAULT_IDLE_DIM: f32 = 0.5;
+/// Kinds of sound that `[global.audio] device.<cue>` can send to their own output.
+pub const SOUND_CUES: [&str; 6] = ["action", "expire", "tick", "announcement", "aux_timer", "music"];
+const DEFAULT_DUCK_VOLUME: f32 = 0.2;
+pub const MAX_CROSSFADE_MS: u64 = 10_000;
+const DEFAULT_LED_MATRIX_BAUD_RATE: u32 = 115_200;
+pub const MAX_LED_MATRIX_SIDE: u32 = 256;
+pub const MAX_LED_MATRIX_SCALE: u32 = 8;
+pub const MAX_LAYER: i32 = 1000;
+/// Keybind name that shows or hides a component; valid for every type.
+pub const VISIBILITY_ACTION: &str = "visibility";
+pub const MIN_GROUP_SCALE: f32 = 0.1;
+pub const MAX_GROUP_SCALE: f32 = 10.0;
+/// Layers used when a component sets none: images sit behind text.
+pub const DEFAULT_IMAGE_LAYER: i32 = 10;
+pub const DEFAULT_TEXT_LAYER: i32 = 30;
+pub const MAX_TIMER_DISPLAY_OFFSET_MS: i64 = 10_000;
+pub const MAX_KEYBIND_COOLDOWN_MS: u64 = 10_000;
+/// Steps one `[macro.<name>]` may list.
+pub const MAX_MACRO_STEPS: usize = 32;
+/// Longest wait a macro step may have before it fires.
+pub const MAX_MACRO_DELAY_MS: u64 = 60_000;
+pub const DEFAULT_SCRIPT_TICK_MS: u64 = 1_000;
+pub const MIN_SCRIPT_TICK_MS: u64 = 100;
+pub const MAX_SCRIPT_TICK_MS: u64 = 60_000;
+pub const DEFAULT_REPEAT_DELAY_MS: u64 = 400;
+pub const MIN_REPEAT_MS: u64 = 20;
+pub const MAX_REPEAT_MS: u64 = 5_000;
+/// Actions that step a value, the only ones that may repeat while held.
+pub const REPEATABLE_ACTIONS: [&str; 4] = ["increase", "decrease", "forward", "backward"];
+pub const DEFAULT_TICKER_SPEED: i32 = 60;
+pub const MAX_TICKER_SPEED: i32 = 1000;
+pub const MAX_TICKER_MESSAGES: usize = 50;
+pub const MAX_EDIT_RATE_LIMIT_MS: u64 = 3_600_000;
+/// Pads that `Gamepad<n>:` keys can name, numbered from 1.
+pub const MAX_GAMEPADS: u8 = 8;
+pub const DEFAULT_GAMEPAD_DEADZONE: f32 = 0.5;
+pub const DEFAULT_GAMEPAD_TRIGGER_THRESHOLD: f32 = 0.9;
+pub const MIN_GAMEPAD_THRESHOLD: f32 = 0.05;
+pub const MAX_GAMEPAD_THRESHOLD: f32 = 1.0;
+pub const MIN_ROTATION_INTERVAL_MS: u64 = 100;
+pub const MAX_ROTATION_INTERVAL_MS: u64 = 3_600_000;
+pub const MAX_HISTORY_POINTS: usize = 10_000;
+const DEFAULT_WEBHOOK_BATCH_MS: u64 = 2_000;
+const MIN_LOCK_PIN_LENGTH: usize = 4;
+const MAX_LOCK_PIN_LENGTH: usize = 12;
+pub const TRANSPARENT_BACKGROUND: &str = "transparent";
+const DEFAULT_FIXTURE_LEAD_MS: i64 = 30 * 60 * 1000;
+const MAX_FIXTURE_LEAD_MS: i64 = 24 * 60 * 60 * 1000;
+const DEFAULT_FIXTURE_REFRESH_MS: i64 = 15 * 60 * 1000;
+pub const DEFAULT_FONT_WEIGHT: u16 = 700;
+pub const MAX_LETTER_SPACING: i32 = 100;
+pub const MAX_OUTLINE_WIDTH: i32 = 20;
+pub const MAX_SHADOW_OFFSET: i32 = 100;
+pub const MAX_SHADOW_BLUR: i32 = 50;
+const DEFAULT_POSSESSION_LABELS: [&str; 2] = ["\u{25C0}", "\u{25B6}"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreboardConfig {
+    pub global: GlobalSettings,
+    pub groups: Vec<ComponentGroup>,
+    pub gamepad: GamepadSettings,
+    pub components: Vec<ComponentConfig>,
+    /// `[macro.<name>]` tables, sorted by name.
+    pub macros: Vec<MacroSettings>,
+    /// Names of the file's `[layout.<name>]` variants.
+    pub layouts: Vec<String>,
+    /// The layout applied to `components`; `None` for the positions outside any layout.
+    pub layout: Option<String>,
+    /// The file as written, for switching layouts without reading it again.
+    #[serde(skip)]
+    source: Arc<ConfigSource>,
+}
+
+/// Several actions fired in order by one keybind or by `macro.<name>`.
+#[derive(Debug, Clone, Serialize)]
+pub struct MacroSettings {
+    pub name: String,
+    pub steps: Vec<MacroStep>,
+    pub keybind: Option<KeybindSpec>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MacroStep {
+    /// The action's name as `list_actions` reports it, e.g. `period.forward`.
+    pub action: String,
+    /// Wait after the previous step before this one fires.
+    pub delay_ms: u64,
+}
+
+#[derive(Debug)]
+struct ConfigSource {
+    root: toml::Table,
+    base_dir: PathBuf,
+}
+
+impl ScoreboardConfig {
+    /// Builds the config again with another of its layouts, or with none.
+    pub fn with_layout(&self, layout: Option<&str>) -> Result<Self, String> {
+        if let Some(name) = layout.filter(|name| !self.layouts.iter().any(|layout| layout == name)) {
+            return Err(format!("Unknown layout '{name}'"));
+        }
+        build_config(&self.source.root, &self.source.base_dir, layout)
+    }
+
+    /// The folder relative paths in the file are resolved against.
+    pub fn base_dir(&self) -> &Path {
+        &self.source.base_dir
+    }
+
+    /// A copy safe to send off the machine, such as in a crash report, with
+    /// every secret replaced by `REDACTED`. The global settings are taken
+    /// apart field by field, so a new setting does not compile until it is
+    /// sorted into a secret or not here.
+    pub fn redacted(&self) -> Self {
+        let mut config = self.clone();
+        let GlobalSettings {
+            lock,
+            webhooks,
+            crash_report,
+            fixtures,
+            obs,
+            sync,
+            remote,
+            canvas: _,
+            rotate: _,
+            background_color: _,
+            chroma_key: _,
+            timer_display_offset_ms: _,
+            freeze_on_error: _,
+            import_images: _,
+            keyboard_mode: _,
+            font: _,
+            keybind: _,
+            player_actions: _,
+            relays: _,
+            announcement: _,
+            aux_timers: _,
+            horn_patterns: _,
+            audio: _,
+            playlist: _,
+            osc: _,
+            serial_input: _,
+            discovery: _,
+            led_matrix: _,
+            themes: _,
+            summary: _,
+            updates: _,
+            splash: _,
+            notes: _,
+            idle: _,
+            script: _,
+        } = &mut config.global;
+        let redact = |secret: &mut Option<String>| {
+            if secret.is_some() {
+                *secret = Some(REDACTED.to_string());
+            }
+        };
+        if let Some(lock) = lock {
+            lock.pin = REDACTED.to_string();
+        }
+        // Webhook URLs carry their tokens.
+        for webhook in webhooks {
+            webhook.url = REDACTED.to_string();
+        }
+        if let Some(crash_report) = crash_report {
+            redact(&mut crash_report.endpoint);
+        }
+        if let Some(fixtures) = fixtures {
+            if let Some((url, _)) = fixtures.source.split_once('?') {
+                fixtures.source = format!("{url}?{REDACTED}");
+            }
+        }
+        if let Some(obs) = obs {
+            redact(&mut obs.password);
+        }
+        if let Some(sync) = sync {
+            redact(&mut sync.token);
+        }
+        if let Some(remote) = remote {
+            redact(&mut remote.token);
+            for entry in &mut remote.tokens {
+                entry.token = REDACTED.to_string();
+            }
+        }
+        config
+    }
+}
+
+/// `[gamepad]`: how far sticks, axis D-pads, and analog triggers must move
+/// before they press their virtual buttons (`LS_UP`, `DPAD_LEFT`, `RT_FULL`, ...).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct GamepadSettings {
+    /// Share of full travel, from 0.05 to 1, for sticks and axis D-pads.
+    pub deadzone: f32,
+    /// Share of full travel, from 0.05 to 1, for `LT_FULL` and `RT_FULL`.
+    pub trigger_threshold: f32,
+}
+
+impl Default for GamepadSettings {
+    fn default() -> Self {
+        Self {
+            deadzone: DEFAULT_GAMEPAD_DEADZONE,
+            trigger_threshold: DEFAULT_GAMEPAD_TRIGGER_THRESHOLD,
+        }
+    }
+}
+
+/// A `[group.<name>]` block of components that move, scale, and hide together.
+/// Member positions are relative to `offset` and multiplied by `scale`, as are
+/// their font and image sizes.
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentGroup {
+    pub name: String,
+    pub offset: Position,
+    pub visible: bool,
+    pub scale: f32,
+}
+
+impl ComponentGroup {
+    /// Where a member at `position` ends up on the canvas.
+    pub fn resolve_position(&self, position: &Position) -> Position {
+        Position {
+            x: self.offset.x + self.scale_length(position.x),
+            y: self.offset.y + self.scale_length(position.y),
+        }
+    }
+
+    pub fn scale_length(&self, length: i32) -> i32 {
+        (length as f32 * self.scale).round() as i32
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GlobalSettings {
+    pub canvas: CanvasSize,
+    /// Clockwise degrees the output is turned by, for displays mounted on
+    /// their side; the canvas itself is laid out unrotated.
+    pub rotate: u16,
+    /// `#RRGGBB`, or `transparent` to let the desktop or capture software show
+    /// through, e.g. for an OBS overlay window.
+    pub background_color: String,
+    /// Solid key color filling the whole window for chroma-key capture. It
+    /// replaces `background_color` and is never changed by themes.
+    pub chroma_key: Option<String>,
+    /// Subtracted from running timers before display, to make up for latency
+    /// in the video chain behind the scoreboard output.
+    pub timer_display_offset_ms: i64,
+    /// Keeps the last good board on screen, with its clocks running, when
+    /// updating the live board fails.
+    pub freeze_on_error: bool,
+    /// Copies images picked for editable images into the config's `assets`
+    /// folder instead of pointing at them where they are.
+    pub import_images: bool,
+    /// Where keyboard bindings are listened for.
+    pub keyboard_mode: KeyboardMode,
+    pub font: Font,
+    pub keybind: Option<GlobalKeybind>,
+    pub player_actions: Vec<PlayerAction>,
+    pub relays: Vec<RelayOutput>,
+    pub announcement: Option<AnnouncementSettings>,
+    pub aux_timers: Vec<AuxTimerSettings>,
+    pub horn_patterns: Vec<HornPattern>,
+    pub audio: Option<AudioSettings>,
+    pub playlist: Option<PlaylistSettings>,
+    pub remote: Option<RemoteSettings>,
+    pub osc: Option<OscSettings>,
+    pub serial_input: Option<SerialInputSettings>,
+    pub obs: Option<ObsSettings>,
+    pub sync: Option<SyncSettings>,
+    pub discovery: Option<DiscoverySettings>,
+    pub led_matrix: Option<LedMatrixSettings>,
+    pub themes: Vec<Theme>,
+    pub lock: Option<LockSettings>,
+    pub webhooks: Vec<WebhookSettings>,
+    pub summary: Option<SummarySettings>,
+    pub fixtures: Option<FixtureSettings>,
+    pub updates: Option<UpdateSettings>,
+    pub crash_report: Option<CrashReportSettings>,
+    pub splash: Option<SplashSettings>,
+    pub notes: Option<OperatorNotes>,
+    pub idle: Option<IdleSettings>,
+    pub script: Option<ScriptSettings>,
+}
+
+/// Mirroring one board onto other machines: a primary serves every snapshot
+/// it draws, and replicas draw those instead of their own.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SyncSettings {
+    pub role: SyncRole,
+    /// Sent by replicas and checked by the primary.
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "role", rename_all = "lowercase")]
+pub enum SyncRole {
+    Primary { bind: String, port: u16 },
+    /// `host:port` of the primary, or the name it advertises over mDNS, or
+    /// `ANY_SYNC_PRIMARY`.
+    Replica { primary: String },
+}
+
+/// How this board announces its remote and sync endpoints over mDNS.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiscoverySettings {
+    /// Instance name shown to controllers; the computer's name when unset.
+    pub name: Option<String>,
+    pub advertise: bool,
+}
+
+/// An obs-websocket (OBS Studio 28 and later) connection and the cues that
+/// switch scenes or toggle sources on game events.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ObsSettings {
+    /// `ws://host:port`; OBS serves no TLS of its own.
+    pub url: String,
+    #[serde(skip_serializing)]
+    pub password: Option<String>,
+    pub triggers: Vec<ObsTrigger>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ObsTrigger {
+    pub name: String,
+    pub event: ObsEvent,
+    /// Scene to make the program scene.
+    pub scene: Option<String>,
+    pub source: Option<ObsSourceVisibility>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum ObsEvent {
+    /// A timer reached zero.
+    TimerExpired { timer: String },
+    /// A period moved to a later label, or to `to` when set.
+    PeriodAdvanced { period: String, to: Option<String> },
+}
+
+/// Shows or hides a source within one scene.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ObsSourceVisibility {
+    pub source: String,
+    pub scene: String,
+    pub visible: bool,
+}
+
+/// Run-of-show instructions for the operator, shown in the console and never
+/// on the output.
+#[derive(Debug, Clone, Serialize)]
+pub struct OperatorNotes {
+    pub title: Option<String>,
+    pub text: Option<String>,
+    pub checklist: Vec<String>,
+}
+
+/// Low-power mode for boards left on around the clock: after `after_ms` with
+/// no input and nothing counting down, the board ticks rarely, is drawn at
+/// `dim` opacity, and hides `hide`, until the next input.
+#[derive(Debug, Clone, Serialize)]
+pub struct IdleSettings {
+    pub after_ms: i64,
+    pub dim: f32,
+    pub hide: Vec<String>,
+}
+
+/// A rules script for sport logic the config alone cannot express, such as
+/// volleyball rotation. Its `on_action`, `on_timer_expire`, and `on_tick`
+/// functions run on those events and may read and change component values.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptSettings {
+    /// Path of the `.rhai` file; relative paths are resolved against the config's folder.
+    pub file: String,
+    /// How often `on_tick` runs.
+    pub tick_ms: u64,
+}
+
+/// A scene shown as soon as a config loads, before any operator input, until
+/// `keybind.ready` (or `show_live_scene`) switches to the live board.
+#[derive(Debug, Clone, Serialize)]
+pub struct SplashSettings {
+    /// Image covering the whole canvas.
+    pub image: Option<String>,
+    /// A `[group.<name>]` shown only while the splash is up.
+    pub group: Option<String>,
+}
+
+/// Opts in to saving a crash report when the app panics, optionally also
+/// posting it to `endpoint`.
+#[derive(Debug, Clone, Serialize)]
+pub struct CrashReportSettings {
+    pub endpoint: Option<String>,
+}
+
+/// The HTTP endpoint that lets Stream Deck, Companion, and custom panels
+/// trigger actions by name.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RemoteSettings {
+    pub bind: String,
+    pub port: u16,
+    /// Required from callers as `Authorization: Bearer <token>` or `?token=`.
+    /// Grants full control.
+    pub token: Option<String>,
+    /// Further tokens, each limited to a role, sorted by name.
+    pub tokens: Vec<RemoteToken>,
+}
+
+impl RemoteSettings {
+    /// Whether callers must present a token at all. Without one every caller
+    /// on the board machine has full control, which is only allowed on a
+    /// loopback `bind`.
+    pub fn requires_token(&self) -> bool {
+        self.token.is_some() || !self.tokens.is_empty()
+    }
+
+    /// What a caller presenting `token` may do, or `None` for an unknown token.
+    /// Every token is compared, so the time taken does not tell which entry
+    /// matched or how close a guess came.
+    pub fn role_for(&self, token: &str) -> Option<RemoteRole> {
+        let mut role = None;
+        if let Some(expected) = &self.token {
+            if tokens_match(expected, token) {
+                role = Some(RemoteRole::Full);
+            }
+        }
+        for entry in &self.tokens {
+            if tokens_match(&entry.token, token) && role.is_none() {
+                role = Some(entry.role);
+            }
+        }
+        role
+    }
+}
+
+/// Compares the whole token even after a mismatch, so how long a refusal
+/// takes does not tell a guesser how much of the token was right.
+pub fn tokens_match(expected: &str, given: &str) -> bool {
+    let (expected, given) = (expected.as_bytes(), given.as_bytes());
+    let mut difference = expected.len() ^ given.len();
+    for (index, byte) in expected.iter().enumerate() {
+        difference |= usize::from(byte ^ given.get(index).copied().unwrap_or(0));
+    }
+    difference == 0
+}
+
+/// A `[global.remote.tokens.<name>]` entry.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RemoteToken {
+    pub name: String,
+    pub token: String,
+    pub role: RemoteRole,
+}
+
+/// What a remote token or OSC host may do. Each role includes the ones before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteRole {
+    /// Reads values and the page, fires nothing.
+    Read,
+    /// Also fires timer and aux timer actions.
+    Timer,
+    /// Fires every action.
+    Full,
+}
+
+impl RemoteRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RemoteRole::Read => "read",
+            RemoteRole::Timer => "timer",
+            RemoteRole::Full => "full",
+        }
+    }
+}
+
+/// OSC over UDP for lighting and audio consoles: actions come in as
+/// `<prefix>/<id>/<action>` and value changes go out to `send_to`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OscSettings {
+    pub bind: String,
+    /// Listening port; without one the board only sends.
+    pub port: Option<u16>,
+    pub prefix: String,
+    pub send_to: Vec<SocketAddr>,
+    /// Hosts other than this machine that may send messages, each limited
+    /// to a role, sorted by address.
+    pub allow: Vec<OscHost>,
+}
+
+impl OscSettings {
+    /// What a message from `address` may do, or `None` when it comes from
+    /// neither this machine nor a host in `allow`.
+    pub fn role_for(&self, address: IpAddr) -> Option<RemoteRole> {
+        let address = address.to_canonical();
+        if address.is_loopback() {
+            return Some(RemoteRole::Full);
+        }
+        self.allow.iter().find(|host| host.address == address).map(|host| host.role)
+    }
+}
+
+/// A `[global.osc.allow]` entry.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OscHost {
+    pub address: IpAddr,
+    pub role: RemoteRole,
+}
+
+/// A hardware scoreboard console read over a serial port. Each mapped field
+/// sets its component whenever the console sends a new value.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SerialInputSettings {
+    pub port: String,
+    pub baud_rate: u32,
+    pub protocol: SerialProtocol,
+    pub fields: Vec<SerialField>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SerialProtocol {
+    /// Daktronics All Sport real-time data: fixed-width text written at positions.
+    Daktronics,
+    /// One `name=value` pair per line, for custom consoles and bridges.
+    Lines,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SerialField {
+    pub component: String,
+    pub source: SerialFieldSource,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum SerialFieldSource {
+    /// Characters of the `daktronics` data, from its 0-based `offset`.
+    Position { offset: usize, length: usize },
+    /// A `lines` field name.
+    Name(String),
+}
+
+/// Where `check_for_updates` looks for new releases, and whether it also runs
+/// when the app starts.
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateSettings {
+    /// A GitHub-style releases API URL; the project's own releases when unset.
+    pub url: Option<String>,
+    pub check_on_startup: bool,
+}
+
+/// A calendar of upcoming games. `lead_ms` before each event starts, the
+/// matching config is loaded, the team labels are filled in from the event
+/// title, and `countdown` runs down to the start time.
+#[derive(Debug, Clone, Serialize)]
+pub struct FixtureSettings {
+    /// A local `.ics` file or an `http(s)://` URL.
+    pub source: String,
+    pub countdown: String,
+    pub lead_ms: i64,
+    pub refresh_ms: i64,
+    /// Applied to event times that are not in UTC, since calendars name time
+    /// zones the app cannot look up.
+    pub utc_offset_minutes: i32,
+    pub home_label: Option<String>,
+    pub away_label: Option<String>,
+    /// Configs keyed by a word in the event title, checked in file order.
+    pub configs: Vec<(String, String)>,
+}
+
+/// Which components make up the score in the `generate_summary` report and
+/// the `generate_scoresheet` PDF, optional templates replacing the built-in
+/// Markdown and plain-text layouts, and team rosters keyed by two-digit
+/// jersey number.
+#[derive(Debug, Clone, Serialize)]
+pub struct SummarySettings {
+    pub home: String,
+    pub away: String,
+    pub home_name: String,
+    pub away_name: String,
+    pub period: Option<String>,
+    pub clock: Option<String>,
+    pub home_fouls: Option<String>,
+    pub away_fouls: Option<String>,
+    pub home_timeouts: Option<String>,
+    pub away_timeouts: Option<String>,
+    pub home_roster: BTreeMap<String, String>,
+    pub away_roster: BTreeMap<String, String>,
+    pub markdown: Option<String>,
+    pub text: Option<String>,
+}
+
+/// An HTTP endpoint notified of score changes. Changes are collected for
+/// `batch_ms` and posted together; undelivered ones are kept on disk.
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookSettings {
+    pub name: String,
+    pub url: String,
+    pub format: WebhookFormat,
+    pub batch_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyboardMode {
+    /// Global shortcuts, or keys while the window is focused when the
+    /// session has no way to grab keys globally.
+    Auto,
+    /// Global shortcuts only; a key that cannot be registered fails the load.
+    Global,
+    /// Keys only while the window is focused, for sessions where global
+    /// shortcuts register but never fire, such as XWayland under Wayland.
+    Focused,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    /// `{"events": [...]}` with one object per change.
+    Json,
+    /// `{"content": "..."}` with one line per change, as Discord and most chat
+    /// incoming webhooks expect.
+    Discord,
+}
+
+/// PIN that unlocks a board locked with `keybind.lock` or the `lock_board` command.
+#[derive(Debug, Clone, Serialize)]
+pub struct LockSettings {
+    #[serde(skip_serializing)]
+    pub pin: String,
+}
+
+/// A named palette that replaces configured colors at runtime, e.g. for a
+/// high-contrast or color-blind safe look. Keys are upper-case `#RRGGBB`;
+/// colors without an entry are shown unchanged.
+#[derive(Debug, Clone, Serialize)]
+pub struct Theme {
+    pub name: String,
+    pub colors: BTreeMap<String, String>,
+}
+
+/// Size of the design in pixels. Component positions are relative to it and
+/// the frontend scales the whole canvas to fit the window.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CanvasSize {
+    pub width: i32,
+    pub height: i32,
+}
+
+/// A low-resolution RGB LED matrix fed over a serial bridge. Only the listed
+/// components are drawn, as text in a small built-in pixel font.
+#[derive(Debug, Clone, Serialize)]
+pub struct LedMatrixSettings {
+    pub port: String,
+    pub baud_rate: u32,
+    pub width: u32,
+    pub height: u32,
+    pub protocol: LedMatrixProtocol,
+    pub brightness: f32,
+    pub items: Vec<LedMatrixItem>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LedMatrixProtocol {
+    Glediator,
+    Adalight,
+}
+
+/// Where a component's text goes on the matrix. `color` defaults to the
+/// component's font color.
+#[derive(Debug, Clone, Serialize)]
+pub struct LedMatrixItem {
+    pub component: String,
+    pub x: u32,
+    pub y: u32,
+    pub color: Option<String>,
+    pub scale: u32,
+}
+
+/// Messages that temporarily replace the text of a label component.
+#[derive(Debug, Clone, Serialize)]
+pub struct AnnouncementSettings {
+    pub target: String,
+    pub default_duration_ms: u64,
+    pub messages: Vec<AnnouncementMessage>,
+}
+
+/// A predefined announcement, shown when its keybind is pressed and, with an
+/// interval, repeatedly while a config is loaded.
+#[derive(Debug, Clone, Serialize)]
+pub struct AnnouncementMessage {
+    pub name: String,
+    pub text: String,
+    pub sound: Option<String>,
+    pub priority: i32,
+    pub duration_ms: Option<u64>,
+    pub interval_ms: Option<i64>,
+    pub keybind: Option<KeybindSpec>,
+}
+
+/// A countdown kept off the canvas for back-of-house timing, such as a media
+/// cooling-off period. Driven by its keybinds and the `*_aux_timer` commands.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuxTimerSettings {
+    pub name: String,
+    /// Shown to operators instead of the name.
+    pub label: Option<String>,
+    pub duration_ms: i64,
+    /// Played when the countdown reaches zero.
+    pub sound: Option<String>,
+    pub keybind: Option<AuxTimerKeybind>,
+}
+
+/// Output devices for sounds, by name as `list_audio_devices` reports them.
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioSettings {
+    /// Used by every sound without its own device; the system default when unset.
+    pub output_device: Option<String>,
+    /// Overrides keyed by one of `SOUND_CUES`.
+    pub devices: BTreeMap<String, String>,
+}
+
+impl AudioSettings {
+    pub fn device_for(&self, cue: Option<&str>) -> Option<&str> {
+        cue.and_then(|cue| self.devices.get(cue))
+            .or(self.output_device.as_ref())
+            .map(String::as_str)
+    }
+}
+
+/// A named sequence of horn blasts. Any sound setting written as
+/// `pattern:<name>` plays it instead of a file.
+#[derive(Debug, Clone, Serialize)]
+pub struct HornPattern {
+    pub name: String,
+    /// Looped for each blast; a generated tone when unset.
+    pub sound: Option<String>,
+    pub blasts_ms: Vec<u64>,
+    pub gap_ms: u64,
+}
+
+/// Background music played from the audio files in a folder.
+#[derive(Debug, Clone, Serialize)]
+pub struct PlaylistSettings {
+    pub folder: String,
+    pub shuffle: bool,
+    pub crossfade_ms: u64,
+    pub volume: f32,
+    /// Timer whose start ducks or stops the music.
+    pub clock: Option<String>,
+    pub on_clock_start: PlaylistClockAction,
+    /// Share of `volume` kept while ducked.
+    pub duck_volume: f32,
+    /// Label showing the title of the track that is playing.
+    pub now_playing: Option<String>,
+    pub keybind: Option<PlaylistKeybind>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaylistClockAction {
+    Duck,
+    Stop,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlaylistKeybind {
+    pub play: Option<KeybindSpec>,
+    pub pause: Option<KeybindSpec>,
+    pub next: Option<KeybindSpec>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuxTimerKeybind {
+    pub start: Option<KeybindSpec>,
+    pub stop: Option<KeybindSpec>,
+    pub reset: Option<KeybindSpec>,
+}
+
+/// A serial relay board channel, e.g. wired to a horn. `on` and `off` are the
+/// raw command bytes the board expects.
+#[derive(Debug, Clone, Serialize)]
+pub struct RelayOutput {
+    pub name: String,
+    pub port: String,
+    pub baud_rate: u32,
+    pub on: Vec<u8>,
+    pub off: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GlobalKeybind {
+    pub fullscreen: Option<KeybindSpec>,
+    pub player_select: Option<KeybindSpec>,
+    pub theme: Option<KeybindSpec>,
+    pub lock: Option<KeybindSpec>,
+    pub ready: Option<KeybindSpec>,
+}
+
+/// An action key offered after a jersey number has been entered in player select mode.
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayerAction {
+    pub name: String,
+    pub keybind: KeybindSpec,
+    pub apply: Option<ComponentActionRef>,
+}
+
+/// A component action referenced by name, written as `"<component>.<action>"` in config.
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentActionRef {
+    pub component: String,
+    pub action: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentConfig {
+    pub id: String,
+    pub position: Position,
+    pub alignment: Option<ComponentAlignment>,
+    pub font: Font,
+    pub kind: ComponentKind,
+    pub sounds: BTreeMap<String, String>,
+    pub confirm: Option<ConfirmSettings>,
+    /// Stacking order as configured; see [`ComponentConfig::layer`] for the default.
+    pub layer: Option<i32>,
+    /// Name of the `[group.<name>]` this component belongs to.
+    pub group: Option<String>,
+    /// Whether the component is shown when the config loads.
+    pub visible: bool,
+    /// `keybind.visibility`, accepted by every component type.
+    pub visibility_keybind: Option<KeybindSpec>,
+    /// Minimum time between runtime edits (label text, image source, ticker
+    /// messages), so a misbehaving integration cannot flood the display.
+    pub rate_limit_ms: Option<u64>,
+    /// Restore the last value on startup instead of starting from `default`.
+    pub persistent: bool,
+    /// Shrinks the text to stay inside this box; text components only.
+    pub fit: Option<TextFit>,
+    /// Gives every digit the same width, so changing values do not shift
+    /// the text sideways; text components only.
+    pub monospace_digits: bool,
+}
+
+impl ComponentConfig {
+    /// The configured layer, or the default that keeps images behind text.
+    pub fn layer(&self) -> i32 {
+        self.layer.unwrap_or(match &self.kind {
+            ComponentKind::Image { .. }
+            | ComponentKind::ImageToggle { .. }
+            | ComponentKind::Video { .. }
+            | ComponentKind::Possession {
+                display: PossessionDisplay::Images { .. },
+                ..
+            } => DEFAULT_IMAGE_LAYER,
+            _ => DEFAULT_TEXT_LAYER,
+        })
+    }
+}
+
+/// Actions that need a second press to take effect. The first press only arms
+/// the action; if no second press arrives within `timeout_ms` it is cancelled,
+/// or committed when `commit_on_timeout` is set.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfirmSettings {
+    pub actions: Vec<String>,
+    pub timeout_ms: u64,
+    pub commit_on_timeout: bool,
+}
+
+/// Which point of a text component sits at its `position`. Without an
+/// alignment the position is the top-left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComponentAlignment {
+    /// Center of the text.
+    Center,
+    /// Left edge, centered vertically.
+    Left,
+    /// Right edge, centered vertically, so numbers grow to the left.
+    Right,
+    /// Top edge, centered horizontally.
+    Top,
+    /// Bottom edge, centered horizontally.
+    Bottom,
+    /// Text baseline, centered horizontally.
+    Baseline,
+}
+
+impl ComponentAlignment {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ComponentAlignment::Center => "center",
+            ComponentAlignment::Left => "left",
+            ComponentAlignment::Right => "right",
+            ComponentAlignment::Top => "top",
+            ComponentAlignment::Bottom => "bottom",
+            ComponentAlignment::Baseline => "baseline",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ComponentKind {
+    Number {
+        default: i32,
+        keybind: Option<NumberKeybind>,
+        period_budget: Option<PeriodBudget>,
+        /// How many past values to keep, with timestamps, for score charts.
+        history: Option<usize>,
+        /// Counts as a score in the per-period breakdown.
+        score: bool,
+    },
+    Timer {
+        default_ms: i64,
+        keybind: Option<TimerKeybind>,
+        rounding: TimerRounding,
+        on_expire_sound: Option<String>,
+        on_expire_relay: Option<String>,
+        /// How long the expiry sound loops and the relay stays closed. Without
+        /// it the sound plays once and the relay pulses for a default length.
+        on_expire_duration_ms: Option<u64>,
+        /// Below this remaining time the timer ticks once per second, with
+        /// `tick_sound` or a generated beep.
+        tick_below_ms: Option<i64>,
+        tick_sound: Option<String>,
+        /// Remaining times at which the countdown is spoken, longest first.
+        voice_cues_ms: Vec<i64>,
+        /// Remaining times at which a T-minus event is sent, longest first.
+        t_minus_ms: Vec<i64>,
+        /// Keep showing the value the timer stopped at until it is started or
+        /// reset, even if it is adjusted in the meantime.
+        freeze_on_stop: bool,
+        /// Primary timer this one mirrors. A mirror shows the primary's time in
+        /// its own style and position, and has no value or actions of its own.
+        mirror: Option<String>,
+    },
+    Label {
+        default: String,
+        edit: bool,
+        /// Accepts and shows line breaks. Single-line labels refuse them.
+        multiline: bool,
+        /// Wraps lines longer than this many pixels; multiline labels only.
+        max_width: Option<i32>,
+    },
+    Image {
+        source: String,
+        width: i32,
+        height: i32,
+        opacity: f32,
+        edit: bool,
+    },
+    ImageToggle {
+        sources: Vec<String>,
+        default_index: usize,
+        width: i32,
+        height: i32,
+        opacity: f32,
+        /// Advances to the next source every this many milliseconds while rotating.
+        interval_ms: Option<u64>,
+        keybind: Option<ImageToggleKeybind>,
+    },
+    Period {
+        labels: Vec<String>,
+        default_index: usize,
+        wrap: bool,
+        keybind: Option<PeriodKeybind>,
+        on_advance: Option<PeriodAdvance>,
+    },
+    Fouls {
+        default: i32,
+        bonus: Option<i32>,
+        double_bonus: Option<i32>,
+        reset_period: Option<String>,
+        keybind: Option<NumberKeybind>,
+    },
+    Possession {
+        display: PossessionDisplay,
+        default_index: usize,
+        keybind: Option<PossessionKeybind>,
+    },
+    /// Time of possession: counts up for whichever side has the ball, but only
+    /// while `clock` runs.
+    PossessionTime {
+        clock: String,
+        keybind: Option<PossessionTimeKeybind>,
+    },
+    /// A video clip that starts playing when the config loads.
+    Video {
+        source: String,
+        width: i32,
+        height: i32,
+        opacity: f32,
+        looped: bool,
+        muted: bool,
+        keybind: Option<VideoKeybind>,
+    },
+    /// Messages scrolling right to left through a strip `width` pixels wide,
+    /// at `speed` pixels per second.
+    Ticker {
+        messages: Vec<String>,
+        speed: i32,
+        width: i32,
+    },
+    /// Text computed from other components by `expr`, such as a lead or a
+    /// bonus marker, so frontends do not each repeat the rule.
+    Derived {
+        expr: Expression,
+    },
+}
+
+/// How a possession indicator shows its two sides: as text labels or as images.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PossessionDisplay {
+    Labels(Vec<String>),
+    Images {
+        sources: Vec<String>,
+        width: i32,
+        height: i32,
+        opacity: f32,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimerRounding {
+    Standard,
+    Basketball,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NumberKeybind {
+    pub increase: Option<KeybindSpec>,
+    pub decrease: Option<KeybindSpec>,
+    pub reset: Option<KeybindSpec>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimerKeybind {
+    pub start: Option<KeybindSpec>,
+    pub stop: Option<KeybindSpec>,
+    pub reset: Option<KeybindSpec>,
+    pub increase: Option<KeybindSpec>,
+    pub decrease: Option<KeybindSpec>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageToggleKeybind {
+    pub forward: Option<KeybindSpec>,
+    pub backward: Option<KeybindSpec>,
+    /// `set_<index>` keybinds, each jumping straight to that source.
+    pub set: BTreeMap<usize, KeybindSpec>,
+    /// Resume and pause automatic rotation; only with `interval_ms`.
+    pub start: Option<KeybindSpec>,
+    pub stop: Option<KeybindSpec>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodKeybind {
+    pub forward: Option<KeybindSpec>,
+    pub backward: Option<KeybindSpec>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PossessionKeybind {
+    pub toggle: Option<KeybindSpec>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VideoKeybind {
+    pub play: Option<KeybindSpec>,
+    pub pause: Option<KeybindSpec>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PossessionTimeKeybind {
+    pub home: Option<KeybindSpec>,
+    pub away: Option<KeybindSpec>,
+    pub reset: Option<KeybindSpec>,
+}
+
+/// Values a number is set to when a period component advances onto the
+/// given labels, e.g. a fresh timeout allowance for the second half.
+/// Labels without an entry keep the current value.
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodBudget {
+    pub period: String,
+    pub values: BTreeMap<String, i32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawPeriodBudget {
+    period: String,
+    values: BTreeMap<String, i32>,
+}
+
+/// Timer reset applied when a period component moves forward.
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodAdvance {
+    pub reset_timer: String,
+    /// Per-period timer durations, one per label. `None` resets to the timer's own default.
+    pub durations_ms: Option<Vec<i64>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawConfirm {
+    actions: Vec<String>,
+    timeout_ms: Option<u64>,
+    on_timeout: Option<String>,
+}
+
+/// A component's `confirm`, either a `RawConfirm` table or `true`.
+#[derive(Debug, Clone)]
+enum RawConfirmSetting {
+    /// `confirm = true`: the component's reset needs a second press.
+    Reset,
+    Actions(RawConfirm),
+}
+
+/// Reads `confirm = true` or a `confirm` table. The table is read as a
+/// `RawConfirm` of its own, so its errors still name the field at fault.
+fn deserialize_confirm<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<RawConfirmSetting>, D::Error> {
+    struct ConfirmVisitor;
+
+    impl<'de> Visitor<'de> for ConfirmVisitor {
+        type Value = Option<RawConfirmSetting>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a boolean or a table with actions")
+        }
+
+        fn visit_bool<E: de::Error>(self, value: bool) -> Result<Self::Value, E> {
+            Ok(value.then_some(RawConfirmSetting::Reset))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+            let confirm = RawConfirm::deserialize(MapAccessDeserializer::new(map))?;
+            Ok(Some(RawConfirmSetting::Actions(confirm)))
+        }
+    }
+
+    deserializer.deserialize_any(ConfirmVisitor)
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawPeriodAdvance {
+    reset_timer: String,
+    durations: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "RawKeybindSpec")]
+pub struct KeybindSpec {
+    pub key: String,
+    #[serde(default)]
+    pub ctrl: bool,
+    #[serde(default)]
+    pub alt: bool,
+    #[serde(default)]
+    pub shift: bool,
+    #[serde(default)]
+    pub win: bool,
+    /// Presses of this binding within this many milliseconds of the last
+    /// applied one are ignored, to absorb key bounce. Also read as
+    /// `debounce_ms`.
+    #[serde(default)]
+    pub cooldown_ms: Option<u64>,
+    /// While held, the action repeats every this many milliseconds after
+    /// `repeat_delay_ms`.
+    #[serde(default)]
+    pub repeat_ms: Option<u64>,
+    #[serde(default)]
+    pub repeat_delay_ms: Option<u64>,
+}
+
+/// A `KeybindSpec` as written, where `debounce_ms` is still its own key so
+/// setting it next to `cooldown_ms` can be reported by name.
+#[derive(Deserialize)]
+struct RawKeybindSpec {
+    key: String,
+    #[serde(default)]
+    ctrl: bool,
+    #[serde(default)]
+    alt: bool,
+    #[serde(default)]
+    shift: bool,
+    #[serde(default)]
+    win: bool,
+    cooldown_ms: Option<u64>,
+    debounce_ms: Option<u64>,
+    repeat_ms: Option<u64>,
+    repeat_delay_ms: Option<u64>,
+}
+
+impl TryFrom<RawKeybindSpec> for KeybindSpec {
+    type Error = String;
+
+    fn try_from(raw: RawKeybindSpec) -> Result<Self, String> {
+        if raw.cooldown_ms.is_some() && raw.debounce_ms.is_some() {
+            return Err(
+                "cooldown_ms and debounce_ms are two names for the same setting; set only one of them".to_string(),
+            );
+        }
+        Ok(Self {
+            key: raw.key,
+            ctrl: raw.ctrl,
+            alt: raw.alt,
+            shift: raw.shift,
+            win: raw.win,
+            cooldown_ms: raw.cooldown_ms.or(raw.debounce_ms),
+            repeat_ms: raw.repeat_ms,
+            repeat_delay_ms: raw.repeat_delay_ms,
+        })
+    }
+}
+
+/// How a held binding repeats its action.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyRepeat {
+    pub delay_ms: u64,
+    pub interval_ms: u64,
+}
+
+impl KeybindSpec {
+    pub fn repeat(&self) -> Option<KeyRepeat> {
+        Some(KeyRepeat {
+            delay_ms: self.repeat_delay_ms.unwrap_or(DEFAULT_REPEAT_DELAY_MS),
+            interval_ms: self.repeat_ms?,
+        })
+    }
+
+    pub fn to_shortcut(&self) -> String {
+        if let Some((pad, button)) = parse_gamepad_key(&self.key) {
+            let pad = pad.map(|pad| pad.to_string()).unwrap_or_default();
+            return format!("Gamepad{pad}:{button}");
+        }
+
+        let mut parts: Vec<&str> = Vec::new();
+        if self.ctrl {
+            parts.push("Ctrl");
+        }
+        if self.alt {
+            parts.push("Alt");
+        }
+        if self.shift {
+            parts.push("Shift");
+        }
+        if self.win {
+            parts.push("Super");
+        }
+        parts.push(self.key.trim());
+        parts.join("+")
+    }
+}
+
+/// True for shortcuts made by [`KeybindSpec::to_shortcut`] from a gamepad key,
+/// such as `Gamepad:A` or `Gamepad2:A`.
+pub fn is_gamepad_shortcut(shortcut: &str) -> bool {
+    shortcut.starts_with("Gamepad") && shortcut.contains(':')
+}
+
+fn looks_like_gamepad_key(raw: &str) -> bool {
+    let normalized = raw.trim().to_ascii_uppercase();
+    normalized.starts_with("XBOX:")
+        || normalized.split_once(':').is_some_and(|(prefix, _)| {
+            prefix
+                .strip_prefix("GAMEPAD")
+                .is_some_and(|number| number.bytes().all(|b| b.is_ascii_digit()))
+        })
+}
+
+/// A gamepad key as the pad it is limited to (`None` for any pad) and the
+/// button. `Gamepad:A` and `Xbox:A` match every pad, `Gamepad2:A` only pad 2.
+fn parse_gamepad_key(raw: &str) -> Option<(Option<u8>, &'static str)> {
+    let normalized = raw.trim().to_ascii_uppercase();
+    let (prefix, token) = normalized.split_once(':')?;
+    let pad = match prefix.strip_prefix("GAMEPAD") {
+        Some("") => None,
+        Some(number) => Some(number.parse::<u8>().ok().filter(|pad| (1..=MAX_GAMEPADS).contains(pad))?),
+        None if prefix == "XBOX" => None,
+        None => return None,
+    };
+    Some((pad, normalize_gamepad_button(token)?))
+}
+
+fn normalize_gamepad_button(token: &str) -> Option<&'static str> {
+    let clean = token.replace(['-', ' '], "_");
+    match clean.as_str() {
+        "A" | "SOUTH" => Some("A"),
+        "B" | "EAST" => Some("B"),
+        "X" | "WEST" => Some("X"),
+        "Y" | "NORTH" => Some("Y"),
+        "LB" | "LEFT_BUMPER" | "LEFT_SHOULDER" => Some("LB"),
+        "RB" | "RIGHT_BUMPER" | "RIGHT_SHOULDER" => Some("RB"),
+        "LT" | "LEFT_TRIGGER" => Some("LT"),
+        "RT" | "RIGHT_TRIGGER" => Some("RT"),
+        "BACK" | "SELECT" | "VIEW" => Some("BACK"),
+        "START" | "MENU" => Some("START"),
+        "GUIDE" | "XBOX" | "MODE" => Some("GUIDE"),
+        "L3" | "LEFT_STICK" | "LEFT_THUMB" => Some("L3"),
+        "R3" | "RIGHT_STICK" | "RIGHT_THUMB" => Some("R3"),
+        "DPAD_UP" => Some("DPAD_UP"),
+        "DPAD_DOWN" => Some("DPAD_DOWN"),
+        "DPAD_LEFT" => Some("DPAD_LEFT"),
+        "DPAD_RIGHT" => Some("DPAD_RIGHT"),
+        "LS_UP" => Some("LS_UP"),
+        "LS_DOWN" => Some("LS_DOWN"),
+        "LS_LEFT" => Some("LS_LEFT"),
+        "LS_RIGHT" => Some("LS_RIGHT"),
+        "RS_UP" => Some("RS_UP"),
+        "RS_DOWN" => Some("RS_DOWN"),
+        "RS_LEFT" => Some("RS_LEFT"),
+        "RS_RIGHT" => Some("RS_RIGHT"),
+        "LT_FULL" => Some("LT_FULL"),
+        "RT_FULL" => Some("RT_FULL"),
+        _ => None,
+    }
+}
+
+fn validate_keybind_spec(id: &str, key: &str, spec: &KeybindSpec) -> Result<(), String> {
+    let key_value = spec.key.trim();
+    if key_value.is_empty() {
+        return Err(format!("'{id}' keybind.{key}.key cannot be empty"));
+    }
+
+    if looks_like_gamepad_key(key_value) {
+        let Some(_) = parse_gamepad_key(key_value) else {
+            return Err(format!(
+                "'{id}' keybind.{key}.key has unsupported gamepad button '{key_value}' (pads are numbered 1 to {MAX_GAMEPADS})"
+            ));
+        };
+
+        if spec.ctrl || spec.alt || spec.shift || spec.win {
+            return Err(format!(
+                "'{id}' keybind.{key} gamepad bindings do not support ctrl/alt/shift/win modifiers"
+            ));
+        }
+    }
+
+    if let Some(cooldown_ms) = spec.cooldown_ms {
+        if cooldown_ms > MAX_KEYBIND_COOLDOWN_MS {
+            return Err(format!(
+                "'{id}' keybind.{key}.cooldown_ms must be at most {MAX_KEYBIND_COOLDOWN_MS}"
+            ));
+        }
+    }
+
+    if spec.repeat_delay_ms.is_some() && spec.repeat_ms.is_none() {
+        return Err(format!("'{id}' keybind.{key}.repeat_delay_ms requires repeat_ms"));
+    }
+    if spec.repeat_ms.is_some() && !REPEATABLE_ACTIONS.contains(&key) {
+        return Err(format!(
+            "'{id}' keybind.{key} cannot repeat; only {} can",
+            REPEATABLE_ACTIONS.join(", ")
+        ));
+    }
+    for (field, value) in [("repeat_ms", spec.repeat_ms), ("repeat_delay_ms", spec.repeat_delay_ms)] {
+        if value.is_some_and(|value| !(MIN_REPEAT_MS..=MAX_REPEAT_MS).contains(&value)) {
+            return Err(format!(
+                "'{id}' keybind.{key}.{field} must be between {MIN_REPEAT_MS} and {MAX_REPEAT_MS}"
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Position {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// A position as written in the file: each side in pixels or as a
+/// percentage of the canvas, such as `x = "50%"`.
+#[derive(Debug, Clone, Deserialize)]
+struct RawPosition {
+    x: RawCoordinate,
+    y: RawCoordinate,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawMacro {
+    actions: Vec<RawMacroStep>,
+    keybind: Option<KeybindSpec>,
+}
+
+/// A bare action name, or a table that also sets a delay.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum RawMacroStep {
+    Name(String),
+    Step { action: String, delay_ms: Option<u64> },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum RawCoordinate {
+    Pixels(i32),
+    Percent(String),
+}
+
+impl RawPosition {
+    /// Pixels on `canvas`; percentages are rounded to the nearest pixel.
+    fn resolve(&self, id: &str, key: &str, canvas: CanvasSize) -> Result<Position, String> {
+        Ok(Position {
+            x: resolve_coordinate(id, key, "x", &self.x, canvas.width)?,
+            y: resolve_coordinate(id, key, "y", &self.y, canvas.height)?,
+        })
+    }
+}
+
+fn resolve_coordinate(id: &str, key: &str, axis: &str, value: &RawCoordinate, side: i32) -> Result<i32, String> {
+    match value {
+        RawCoordinate::Pixels(pixels) => Ok(*pixels),
+        RawCoordinate::Percent(text) => {
+            let percent = text
+                .trim()
+                .strip_suffix('%')
+                .and_then(|number| number.trim().parse::<f64>().ok())
+                .filter(|percent| (0.0..=100.0).contains(percent))
+                .ok_or_else(|| {
+                    format!("'{id}' {key}.{axis} must be a pixel count or a percentage from \"0%\" to \"100%\"")
+                })?;
+            Ok((f64::from(side) * percent / 100.0).round() as i32)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Font {
+    /// The family text is drawn with: the first of `families` that is
+    /// installed once the app has checked, otherwise the first listed.
+    pub family: String,
+    /// `font.family` as configured, most preferred first.
+    pub families: Vec<String>,
+    pub size: i32,
+    pub color: String,
+    /// From the component's `opacity`; image-like components keep their own.
+    pub opacity: f32,
+    /// CSS weight from 100 to 900; `normal` is 400 and `bold` (the default) 700.
+    pub weight: u16,
+    pub italic: bool,
+    /// Extra pixels between characters; negative values tighten the text.
+    pub letter_spacing: i32,
+    pub outline: Option<TextOutline>,
+    pub shadow: Option<TextShadow>,
+}
+
+/// A stroke around each glyph, drawn behind the fill so thin strokes do not
+/// eat into the characters.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextOutline {
+    pub color: String,
+    pub width: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextShadow {
+    pub color: String,
+    #[serde(default = "default_shadow_offset")]
+    pub x: i32,
+    #[serde(default = "default_shadow_offset")]
+    pub y: i32,
+    #[serde(default)]
+    pub blur: i32,
+}
+
+fn default_shadow_offset() -> i32 {
+    2
+}
+
+/// A box that text shrinks into when it would overflow it. The configured
+/// font size is the largest size used.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TextFit {
+    pub max_width: Option<i32>,
+    pub max_height: Option<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct FontOverride {
+    family: Option<toml::Value>,
+    size: Option<i32>,
+    color: Option<String>,
+    weight: Option<toml::Value>,
+    italic: Option<bool>,
+    letter_spacing: Option<i32>,
+    outline: Option<TextOutline>,
+    shadow: Option<TextShadow>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawGlobal {
+    canvas: Option<CanvasSize>,
+    rotate: Option<u16>,
+    background_color: Option<String>,
+    chroma_key: Option<String>,
+    timer_display_offset_ms: Option<i64>,
+    freeze_on_error: Option<bool>,
+    import_images: Option<bool>,
+    keyboard_mode: Option<String>,
+    font: Option<FontOverride>,
+    keybind: Option<BTreeMap<String, KeybindSpec>>,
+    player_action: Option<BTreeMap<String, RawPlayerAction>>,
+    relay: Option<BTreeMap<String, RawRelay>>,
+    announcement: Option<RawAnnouncement>,
+    aux_timer: Option<BTreeMap<String, RawAuxTimer>>,
+    horn_pattern: Option<BTreeMap<String, RawHornPattern>>,
+    audio: Option<RawAudio>,
+    playlist: Option<RawPlaylist>,
+    remote: Option<RawRemote>,
+    osc: Option<RawOsc>,
+    serial_input: Option<RawSerialInput>,
+    obs: Option<RawObs>,
+    sync: Option<RawSync>,
+    discovery: Option<RawDiscovery>,
+    led_matrix: Option<RawLedMatrix>,
+    theme: Option<BTreeMap<String, RawTheme>>,
+    lock: Option<RawLock>,
+    webhook: Option<BTreeMap<String, RawWebhook>>,
+    summary: Option<RawSummary>,
+    fixtures: Option<RawFixtures>,
+    updates: Option<RawUpdates>,
+    crash_report: Option<RawCrashReport>,
+    splash: Option<RawSplash>,
+    notes: Option<RawNotes>,
+    idle: Option<RawIdle>,
+    script: Option<RawScript>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawObs {
+    url: Option<String>,
+    password: Option<String>,
+    trigger: Option<BTreeMap<String, RawObsTrigger>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawObsTrigger {
+    on_expire: Option<String>,
+    on_period: Option<String>,
+    to: Option<String>,
+    scene: Option<String>,
+    source: Option<String>,
+    source_scene: Option<String>,
+    visible: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawNotes {
+    title: Option<String>,
+    text: Option<String>,
+    checklist: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawDiscovery {
+    name: Option<String>,
+    advertise: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawSync {
+    listen: Option<u16>,
+    bind: Option<String>,
+    primary: Option<String>,
+    token: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawIdle {
+    after: String,
+    dim: Option<f32>,
+    hide: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawScript {
+    file: String,
+    tick_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawCrashReport {
+    endpoint: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawSplash {
+    image: Option<String>,
+    group: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawRemote {
+    bind: Option<String>,
+    port: u16,
+    token: Option<String>,
+    #[serde(default)]
+    tokens: BTreeMap<String, RawRemoteToken>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawRemoteToken {
+    token: String,
+    role: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawOsc {
+    bind: Option<String>,
+    port: Option<u16>,
+    prefix: Option<String>,
+    send_to: Option<Vec<String>>,
+    #[serde(default)]
+    allow: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawSerialInput {
+    port: String,
+    baud_rate: Option<u32>,
+    protocol: String,
+    map: Option<BTreeMap<String, RawSerialField>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawSerialField {
+    offset: Option<usize>,
+    length: Option<usize>,
+    field: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawUpdates {
+    url: Option<String>,
+    check_on_startup: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawFixtures {
+    source: String,
+    countdown: String,
+    lead: Option<String>,
+    refresh: Option<String>,
+    utc_offset: Option<String>,
+    home_label: Option<String>,
+    away_label: Option<String>,
+    config: Option<toml::Table>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawSummary {
+    home: String,
+    away: String,
+    home_name: Option<String>,
+    away_name: Option<String>,
+    period: Option<String>,
+    clock: Option<String>,
+    home_fouls: Option<String>,
+    away_fouls: Option<String>,
+    home_timeouts: Option<String>,
+    away_timeouts: Option<String>,
+    home_roster: Option<BTreeMap<String, String>>,
+    away_roster: Option<BTreeMap<String, String>>,
+    markdown: Option<String>,
+    text: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawWebhook {
+    url: String,
+    format: Option<String>,
+    batch_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawLock {
+    pin: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawTheme {
+    colors: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawLedMatrix {
+    port: String,
+    baud_rate: Option<u32>,
+    width: u32,
+    height: u32,
+    protocol: Option<String>,
+    brightness: Option<f32>,
+    component: Option<BTreeMap<String, RawLedMatrixItem>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawLedMatrixItem {
+    x: u32,
+    y: u32,
+    color: Option<String>,
+    scale: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawAnnouncement {
+    target: String,
+    duration_ms: Option<u64>,
+    message: Option<BTreeMap<String, RawAnnouncementMessage>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawAnnouncementMessage {
+    text: String,
+    sound: Option<String>,
+    priority: Option<i32>,
+    duration_ms: Option<u64>,
+    interval: Option<String>,
+    keybind: Option<KeybindSpec>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawAuxTimer {
+    duration: String,
+    label: Option<String>,
+    sound: Option<String>,
+    keybind: Option<BTreeMap<String, KeybindSpec>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawPlaylist {
+    folder: String,
+    shuffle: Option<bool>,
+    crossfade_ms: Option<u64>,
+    volume: Option<f32>,
+    clock: Option<String>,
+    on_clock_start: Option<String>,
+    duck_volume: Option<f32>,
+    now_playing: Option<String>,
+    keybind: Option<BTreeMap<String, KeybindSpec>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawAudio {
+    output_device: Option<String>,
+    device: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawHornPattern {
+    sound: Option<String>,
+    blasts_ms: Vec<u64>,
+    gap_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawRelay {
+    port: String,
+    baud_rate: Option<u32>,
+    on: String,
+    off: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawPlayerAction {
+    keybind: KeybindSpec,
+    apply: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawComponent {
+    #[serde(rename = "type")]
+    component_type: toml::Value,
+    default: Option<toml::Value>,
+    position: RawPosition,
+    alignment: Option<String>,
+    layer: Option<i32>,
+    group: Option<String>,
+    visible: Option<bool>,
+    font: Option<FontOverride>,
+    keybind: Option<BTreeMap<String, KeybindSpec>>,
+    source: Option<String>,
+    sources: Option<Vec<String>>,
+    size: Option<ImageSize>,
+    opacity: Option<f32>,
+    rounding: Option<String>,
+    edit: Option<bool>,
+    on_expire_sound: Option<String>,
+    on_expire_relay: Option<String>,
+    on_expire_duration_ms: Option<u64>,
+    tick_below: Option<String>,
+    tick_sound: Option<String>,
+    freeze_on_stop: Option<bool>,
+    voice_cues: Option<Vec<String>>,
+    t_minus: Option<Vec<String>>,
+    sound: Option<BTreeMap<String, String>>,
+    #[serde(default, deserialize_with = "deserialize_confirm")]
+    confirm: Option<RawConfirmSetting>,
+    period_budget: Option<RawPeriodBudget>,
+    bonus: Option<i32>,
+    double_bonus: Option<i32>,
+    reset_period: Option<String>,
+    mirror: Option<String>,
+    labels: Option<Vec<String>>,
+    clock: Option<String>,
+    wrap: Option<bool>,
+    on_advance: Option<RawPeriodAdvance>,
+    multiline: Option<bool>,
+    max_width: Option<i32>,
+    messages: Option<Vec<String>>,
+    speed: Option<i32>,
+    width: Option<i32>,
+    expr: Option<String>,
+    rate_limit_ms: Option<u64>,
+    interval_ms: Option<u64>,
+    persistent: Option<bool>,
+    fit: Option<TextFit>,
+    monospace_digits: Option<bool>,
+    history: Option<usize>,
+    score: Option<bool>,
+    #[serde(rename = "loop")]
+    looped: Option<bool>,
+    mute: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ImageSize {
+    width: i32,
+    height: i32,
+}
+
+pub fn load_config_from_path(path: &Path) -> Result<ScoreboardConfig, String> {
+    let config_path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map_err(|e| format!("Failed resolving current directory: {e}"))?
+            .join(path)
+    };
+    let content = fs::read_to_string(&config_path)
+        .map_err(|e| format!("Failed reading config {}: {e}", config_path.display()))?;
+    let base_dir = config_path.parent().unwrap_or_else(|| Path::new("."));
+    load_config_from_str_with_base(&content, base_dir)
+}
+
+pub fn load_config_from_str(content: &str) -> Result<ScoreboardConfig, String> {
+    let base = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    load_config_from_str_with_base(content, &base)
+}
+
+fn load_config_from_str_with_base(content: &str, base_dir: &Path) -> Result<ScoreboardConfig, String> {
+    let root: toml::Value = toml::from_str(content).map_err(|e| format!("TOML parse error: {e}"))?;
+    let table = root
+        .as_table()
+        .ok_or_else(|| "Config root must be a TOML table".to_string())?;
+    let layout = default_layout(table)?;
+    build_config(table, base_dir, layout.as_deref())
+}
+
+/// Parses the config with `layout`'s overrides applied.
+fn build_config(root: &toml::Table, base_dir: &Path, layout: Option<&str>) -> Result<ScoreboardConfig, String> {
+    let layouts = layout_names(root)?;
+    let merged;
+    let table = match layout {
+        Some(name) => {
+            merged = apply_layout(root, name)?;
+            &merged
+        }
+        None => root,
+    };
+
+    let global = parse_global_settings(table.get("global"), base_dir)?;
+    let groups = parse_groups(table.get("group"), global.canvas)?;
+    let gamepad = parse_gamepad_settings(table.get("gamepad"))?;
+    let macros = parse_macros(table.get("macro"))?;
+
+    let mut components: Vec<ComponentConfig> = Vec::new();
+    for (id, value) in table {
+        if RESERVED_TABLES.contains(&id.as_str()) {
+            continue;
+        }
+        components.push(parse_component(id, value, &global, base_dir)?);
+    }
+
+    validate_groups(&global, &groups, &components)?;
+    validate_period_links(&components)?;
+    validate_timer_mirrors(&components)?;
+    validate_possession_clocks(&components)?;
+    validate_derived_components(&components)?;
+    validate_player_actions(&global, &components)?;
+    validate_announcement_target(&global, &components)?;
+    validate_led_matrix_items(&global, &components)?;
+    validate_summary_components(&global, &components)?;
+    validate_fixture_components(&global, &components)?;
+    validate_playlist_components(&global, &components)?;
+    validate_serial_input_components(&global, &components)?;
+    validate_obs_components(&global, &components)?;
+    validate_idle_components(&global, &components)?;
+
+    let config = ScoreboardConfig {
+        global,
+        groups,
+        gamepad,
+        components,
+        macros,
+        layouts,
+        layout: layout.map(str::to_string),
+        source: Arc::new(ConfigSource {
+            root: root.clone(),
+            base_dir: base_dir.to_path_buf(),
+        }),
+    };
+    validate_macro_actions(&config)?;
+    Ok(config)
+}
+
+/// The layout named by `global.layout`, applied when the config loads.
+fn default_layout(root: &toml::Table) -> Result<Option<String>, String> {
+    match root.get("global").and_then(|global| global.get("layout")) {
+        Some(toml::Value::String(name)) => {
+            if root.get("layout").and_then(|layouts| layouts.get(name)).is_none() {
+                return Err(format!("'global' layout '{name}' has no [layout.{name}]"));
+            }
+            Ok(Some(name.clone()))
+        }
+        Some(_) => Err("'global' layout must be the name of a [layout.<name>]".to_string()),
+        None => Ok(None),
+    }
+}
+
+/// Names of every layout, after checking that each one applies, so a mistake
+/// in a layout shows up when the file loads rather than when it is selected.
+fn layout_names(root: &toml::Table) -> Result<Vec<String>, String> {
+    let Some(layouts) = root.get("layout") else {
+        return Ok(Vec::new());
+    };
+    let layouts = layouts
+        .as_table()
+        .ok_or_else(|| "'layout' must be a table of [layout.<name>] tables".to_string())?;
+    for name in layouts.keys() {
+        apply_layout(root, name)?;
+    }
+    Ok(layouts.keys().cloned().collect())
+}
+
+/// Copies `[layout.<name>]` over the components it names. `canvas` and
+/// `rotate` replace their `global` settings, and a `font` table is merged so a layout can change the
+/// size without repeating the family.
+fn apply_layout(root: &toml::Table, name: &str) -> Result<toml::Table, String> {
+    let layout = root
+        .get("layout")
+        .and_then(|layouts| layouts.get(name))
+        .ok_or_else(|| format!("Unknown layout '{name}'"))?
+        .as_table()
+        .ok_or_else(|| format!("'layout.{name}' must be a table"))?;
+    let mut merged = root.clone();
+    for (key, value) in layout {
+        let id = format!("layout.{name}.{key}");
+        if key == "canvas" || key == "rotate" {
+            let global = merged
+                .entry("global")
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+                .as_table_mut()
+                .ok_or_else(|| "'global' must be a table".to_string())?;
+            global.insert(key.clone(), value.clone());
+            continue;
+        }
+        let overrides = value.as_table().ok_or_else(|| format!("'{id}' must be a table"))?;
+        let component = merged
+            .get_mut(key)
+            .filter(|_| !RESERVED_TABLES.contains(&key.as_str()))
+            .and_then(toml::Value::as_table_mut)
+            .ok_or_else(|| format!("'{id}' references unknown component '{key}'"))?;
+        for (field, field_value) in overrides {
+            if !LAYOUT_KEYS.contains(&field.as_str()) {
+                return Err(format!(
+                    "'{id}' cannot set '{field}'; layouts only change {}",
+                    LAYOUT_KEYS.join(", ")
+                ));
+            }
+            match (component.get_mut(field), field_value) {
+                (Some(toml::Value::Table(existing)), toml::Value::Table(patch)) if field == "font" => {
+                    existing.extend(patch.clone());
+                }
+                _ => {
+                    component.insert(field.clone(), field_value.clone());
+                }
+            }
+        }
+    }
+    Ok(merged)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+/// One problem found while validating config text. `component` is the table
+/// the problem is in (`"global"` for the global section) and `field` the key
+/// within it, when they can be determined.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigDiagnostic {
+    pub component: Option<String>,
+    pub field: Option<String>,
+    pub message: String,
+    pub severity: DiagnosticSeverity,
+}
+
+/// Top-level tables that are not components.
+const RESERVED_TABLES: [&str; 5] = ["global", "group", "gamepad", "layout", "macro"];
+/// Component keys a `[layout.<name>.<id>]` table can change.
+pub const LAYOUT_KEYS: [&str; 9] = [
+    "position",
+    "alignment",
+    "layer",
+    "visible",
+    "font",
+    "size",
+    "width",
+    "max_width",
+    "opacity",
+];
+const GROUP_KEYS: [&str; 3] = ["offset", "visible", "scale"];
+const GAMEPAD_KEYS: [&str; 2] = ["deadzone", "trigger_threshold"];
+const MACRO_KEYS: [&str; 2] = ["actions", "keybind"];
+const GLOBAL_KEYS: [&str; 36] = [
+    "canvas",
+    "rotate",
+    "background_color",
+    "chroma_key",
+    "timer_display_offset_ms",
+    "freeze_on_error",
+    "import_images",
+    "keyboard_mode",
+    "font",
+    "keybind",
+    "player_action",
+    "relay",
+    "announcement",
+    "aux_timer",
+    "horn_pattern",
+    "audio",
+    "playlist",
+    "remote",
+    "osc",
+    "serial_input",
+    "obs",
+    "sync",
+    "discovery",
+    "led_matrix",
+    "theme",
+    "lock",
+    "webhook",
+    "summary",
+    "fixtures",
+    "updates",
+    "crash_report",
+    "splash",
+    "notes",
+    "idle",
+    "script",
+    "layout",
+];
+const COMPONENT_KEYS: [&str; 49] = [
+    "type",
+    "default",
+    "position",
+    "alignment",
+    "layer",
+    "group",
+    "visible",
+    "font",
+    "keybind",
+    "source",
+    "sources",
+    "size",
+    "opacity",
+    "rounding",
+    "edit",
+    "on_expire_sound",
+    "on_expire_relay",
+    "on_expire_duration_ms",
+    "tick_below",
+    "tick_sound",
+    "freeze_on_stop",
+    "voice_cues",
+    "t_minus",
+    "sound",
+    "confirm",
+    "period_budget",
+    "bonus",
+    "double_bonus",
+    "reset_period",
+    "mirror",
+    "labels",
+    "clock",
+    "wrap",
+    "on_advance",
+    "multiline",
+    "max_width",
+    "messages",
+    "speed",
+    "width",
+    "expr",
+    "rate_limit_ms",
+    "interval_ms",
+    "persistent",
+    "fit",
+    "monospace_digits",
+    "history",
+    "score",
+    "loop",
+    "mute",
+];
+
+/// Validates config text like the loader does, but keeps going after an
+/// error: every component is checked on its own, and unknown keys are
+/// reported as warnings instead of being ignored.
+pub fn diagnose_config_str(content: &str) -> Vec<ConfigDiagnostic> {
+    let base_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    diagnose_config_str_with_base(content, &base_dir)
+}
+
+/// `diagnose_config_str` for a file, with relative paths resolved next to it.
+pub fn diagnose_config_path(path: &Path) -> Result<Vec<ConfigDiagnostic>, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed reading config {}: {e}", path.display()))?;
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    Ok(diagnose_config_str_with_base(&content, base_dir))
+}
+
+fn diagnose_config_str_with_base(content: &str, base_dir: &Path) -> Vec<ConfigDiagnostic> {
+    let root: toml::Value = match toml::from_str(content) {
+        Ok(root) => root,
+        Err(e) => return vec![error_diagnostic(None, format!("TOML parse error: {e}"))],
+    };
+    let Some(table) = root.as_table() else {
+        return vec![error_diagnostic(None, "Config root must be a TOML table".to_string())];
+    };
+
+    let mut diagnostics = Vec::new();
+    // Checked the way it loads, with the default layout applied.
+    let merged = match layout_names(table).and_then(|_| default_layout(table)) {
+        Ok(Some(name)) => apply_layout(table, &name)
+            .map_err(|e| diagnostics.push(error_diagnostic(None, e)))
+            .ok(),
+        Ok(None) => None,
+        Err(e) => {
+            diagnostics.push(error_diagnostic(None, e));
+            None
+        }
+    };
+    let table = merged.as_ref().unwrap_or(table);
+    if let Some(global_table) = table.get("global").and_then(|value| value.as_table()) {
+        diagnostics.extend(unknown_key_warnings("global", global_table, &GLOBAL_KEYS));
+    }
+    let global = match parse_global_settings(table.get("global"), base_dir) {
+        Ok(global) => global,
+        Err(e) => {
+            diagnostics.push(error_diagnostic(Some("global"), e));
+            match parse_global_settings(None, base_dir) {
+                Ok(global) => global,
+                Err(_) => return diagnostics,
+            }
+        }
+    };
+
+    if let Some(group_table) = table.get("group").and_then(|value| value.as_table()) {
+        for (name, value) in group_table {
+            if let Some(settings) = value.as_table() {
+                diagnostics.extend(unknown_key_warnings(&format!("group.{name}"), settings, &GROUP_KEYS));
+            }
+        }
+    }
+    let groups = match parse_groups(table.get("group"), global.canvas) {
+        Ok(groups) => groups,
+        Err(e) => {
+            diagnostics.push(error_diagnostic(Some("group"), e));
+            Vec::new()
+        }
+    };
+
+    if let Some(gamepad_table) = table.get("gamepad").and_then(|value| value.as_table()) {
+        diagnostics.extend(unknown_key_warnings("gamepad", gamepad_table, &GAMEPAD_KEYS));
+    }
+    let gamepad = parse_gamepad_settings(table.get("gamepad")).unwrap_or_else(|e| {
+        diagnostics.push(error_diagnostic(Some("gamepad"), e));
+        GamepadSettings::default()
+    });
+
+    if let Some(macro_table) = table.get("macro").and_then(|value| value.as_table()) {
+        for (name, value) in macro_table {
+            if let Some(settings) = value.as_table() {
+                diagnostics.extend(unknown_key_warnings(&format!("macro.{name}"), settings, &MACRO_KEYS));
+            }
+        }
+    }
+    let macros = parse_macros(table.get("macro")).unwrap_or_else(|e| {
+        diagnostics.push(error_diagnostic(Some("macro"), e));
+        Vec::new()
+    });
+
+    let mut components = Vec::new();
+    let mut component_errors = false;
+    for (id, value) in table {
+        if RESERVED_TABLES.contains(&id.as_str()) {
+            continue;
+        }
+        if let Some(component_table) = value.as_table() {
+            diagnostics.extend(unknown_key_warnings(id, component_table, &COMPONENT_KEYS));
+        }
+        match parse_component(id, value, &global, base_dir) {
+            Ok(component) => {
+                diagnostics.extend(unknown_keybind_warnings(&component, value));
+                components.push(component);
+            }
+            Err(e) => {
+                component_errors = true;
+                diagnostics.push(error_diagnostic(Some(id), e));
+            }
+        }
+    }
+
+    // Links between components are only meaningful once every component parsed;
+    // otherwise a broken component would also be reported as missing.
+    if !component_errors {
+        let checks = [
+            validate_groups(&global, &groups, &components),
+            validate_period_links(&components),
+            validate_timer_mirrors(&components),
+            validate_possession_clocks(&components),
+            validate_derived_components(&components),
+            validate_player_actions(&global, &components),
+            validate_announcement_target(&global, &components),
+            validate_led_matrix_items(&global, &components),
+            validate_summary_components(&global, &components),
+            validate_fixture_components(&global, &components),
+            validate_playlist_components(&global, &components),
+            validate_serial_input_components(&global, &components),
+            validate_obs_components(&global, &components),
+            validate_idle_components(&global, &components),
+        ];
+        for e in checks.into_iter().filter_map(Result::err) {
+            diagnostics.push(error_diagnostic(None, e));
+        }
+        let config = ScoreboardConfig {
+            global,
+            groups,
+            gamepad,
+            components,
+            macros,
+            layouts: Vec::new(),
+            layout: None,
+            source: Arc::new(ConfigSource {
+                root: table.clone(),
+                base_dir: base_dir.to_path_buf(),
+            }),
+        };
+        if let Err(e) = validate_macro_actions(&config) {
+            diagnostics.push(error_diagnostic(Some("macro"), e));
+        }
+    }
+    diagnostics
+}
+
+/// Builds an error diagnostic from a loader message. Loader messages start
+/// with the quoted id (`'clock' default must be ...`, `'clock.font.color' must
+/// be ...`), which is where the component and field are taken from.
+fn error_diagnostic(component: Option<&str>, message: String) -> ConfigDiagnostic {
+    let quoted = message
+        .strip_prefix('\'')
+        .and_then(|rest| rest.split_once('\''))
+        .map(|(quoted, rest)| (quoted.to_string(), rest.trim_start()));
+
+    let mut field = None;
+    let mut quoted_component = None;
+    if let Some((quoted, rest)) = &quoted {
+        let (name, path) = match quoted.split_once('.') {
+            Some((name, path)) => (name, Some(path)),
+            None => (quoted.as_str(), None),
+        };
+        quoted_component = Some(name.to_string());
+        field = path.map(|path| path.to_string()).or_else(|| {
+            let word = rest.split_whitespace().next()?.trim_end_matches([',', ':']);
+            let root = word.split('.').next()?;
+            let known: &[&str] = if name == "global" { &GLOBAL_KEYS } else { &COMPONENT_KEYS };
+            known.contains(&root).then(|| word.to_string())
+        });
+    }
+    // Deserialization errors name the missing or invalid key in backticks.
+    if field.is_none() {
+        field = message
+            .split_once('`')
+            .and_then(|(_, rest)| rest.split_once('`'))
+            .map(|(key, _)| key.to_string());
+    }
+
+    ConfigDiagnostic {
+        component: component.map(|id| id.to_string()).or(quoted_component),
+        field,
+        message: message.trim_end().to_string(),
+        severity: DiagnosticSeverity::Error,
+    }
+}
+
+fn unknown_key_warnings(id: &str, table: &toml::Table, known: &[&str]) -> Vec<ConfigDiagnostic> {
+    table
+        .keys()
+        .filter(|key| !known.contains(&key.as_str()))
+        .map(|key| ConfigDiagnostic {
+            component: Some(id.to_string()),
+            field: Some(key.clone()),
+            message: format!("'{id}' has unknown key '{key}', which is ignored"),
+            severity: DiagnosticSeverity::Warning,
+        })
+        .collect()
+}
+
+fn unknown_keybind_warnings(component: &ComponentConfig, value: &toml::Value) -> Vec<ConfigDiagnostic> {
+    let Some(binds) = value.get("keybind").and_then(|keybind| keybind.as_table()) else {
+        return Vec::new();
+    };
+    let id = &component.id;
+    let allowed = component_action_names(&component.kind);
+    let jumps = matches!(component.kind, ComponentKind::ImageToggle { .. });
+    binds
+        .keys()
+        .filter(|name| name.as_str() != VISIBILITY_ACTION && !allowed.contains(&name.as_str()))
+        .filter(|name| !(jumps && image_toggle_set_index(name).is_some()))
+        .map(|name| ConfigDiagnostic {
+            component: Some(id.clone()),
+            field: Some(format!("keybind.{name}")),
+            message: format!("'{id}' keybind.{name} is not an action of this component and is ignored"),
+            severity: DiagnosticSeverity::Warning,
+        })
+        .collect()
+}
+
+fn parse_component(id: &str, value: &toml::Value, global: &GlobalSettings, base_dir: &Path) -> Result<ComponentConfig, String> {
+    let raw: RawComponent = value
+        .clone()
+        .try_into()
+        .map_err(|e| format!("Invalid component '{id}': {e}"))?;
+    let mut font = resolve_font(id, &global.font, raw.font.as_ref())?;
+    validate_id(id)?;
+    let position = raw.position.resolve(id, "position", global.canvas)?;
+    validate_position(id, &position, global.canvas)?;
+    validate_font(id, &font)?;
+
+    let (component_type, type_rounding) = parse_component_type(id, &raw.component_type)?;
+    let alignment = parse_alignment(id, raw.alignment.as_deref())?;
+    if let Some(layer) = raw.layer {
+        if layer.abs() > MAX_LAYER {
+            return Err(format!("'{id}' layer must be between -{MAX_LAYER} and {MAX_LAYER}"));
+        }
+    }
+    let visibility_keybind = match raw.keybind.as_ref() {
+        Some(binds) => parse_optional_keybind(id, binds, VISIBILITY_ACTION)?,
+        None => None,
+    };
+    let has_expire_trigger =
+        raw.on_expire_sound.is_some() || raw.on_expire_relay.is_some() || raw.on_expire_duration_ms.is_some();
+    if has_expire_trigger && component_type != "timer" {
+        return Err(format!(
+            "'{id}' on_expire_sound, on_expire_relay, and on_expire_duration_ms are only supported for timer components"
+        ));
+    }
+    if (raw.tick_below.is_some() || raw.tick_sound.is_some()) && component_type != "timer" {
+        return Err(format!("'{id}' tick_below and tick_sound are only supported for timer components"));
+    }
+    if raw.freeze_on_stop.is_some() && component_type != "timer" {
+        return Err(format!("'{id}' freeze_on_stop is only supported for timer components"));
+    }
+    if raw.voice_cues.is_some() && component_type != "timer" {
+        return Err(format!("'{id}' voice_cues is only supported for timer components"));
+    }
+    if raw.t_minus.is_some() && component_type != "timer" {
+        return Err(format!("'{id}' t_minus is only supported for timer components"));
+    }
+    if (raw.wrap.is_some() || raw.on_advance.is_some()) && component_type != "period" {
+        return Err(format!("'{id}' wrap and on_advance are only supported for period components"));
+    }
+    if raw.period_budget.is_some() && component_type != "number" {
+        return Err(format!("'{id}' period_budget is only supported for number components"));
+    }
+    if raw.history.is_some() && component_type != "number" {
+        return Err(format!("'{id}' history is only supported for number components"));
+    }
+    if raw.score.is_some() && component_type != "number" {
+        return Err(format!("'{id}' score is only supported for number components"));
+    }
+    if raw.score == Some(true) && raw.period_budget.is_some() {
+        return Err(format!("'{id}' counts timeouts with period_budget, so it cannot also be a score"));
+    }
+    if raw.interval_ms.is_some() && component_type != "image-toggle" {
+        return Err(format!("'{id}' interval_ms is only supported for image-toggle components"));
+    }
+    if (raw.looped.is_some() || raw.mute.is_some()) && component_type != "video" {
+        return Err(format!("'{id}' loop and mute are only supported for video components"));
+    }
+    if (raw.bonus.is_some() || raw.double_bonus.is_some() || raw.reset_period.is_some()) && component_type != "fouls" {
+        return Err(format!(
+            "'{id}' bonus, double_bonus, and reset_period are only supported for fouls components"
+        ));
+    }
+    if raw.clock.is_some() && component_type != "possession-time" {
+        return Err(format!("'{id}' clock is only supported for possession-time components"));
+    }
+    if raw.mirror.is_some() && component_type != "timer" {
+        return Err(format!("'{id}' mirror is only supported for timer components"));
+    }
+    if (raw.messages.is_some() || raw.speed.is_some() || raw.width.is_some()) && component_type != "ticker" {
+        return Err(format!("'{id}' messages, speed, and width are only supported for ticker components"));
+    }
+    if raw.expr.is_some() && component_type != "derived" {
+        return Err(format!("'{id}' expr is only supported for derived components"));
+    }
+    if (raw.multiline.is_some() || raw.max_width.is_some()) && component_type != "label" {
+        return Err(format!("'{id}' multiline and max_width are only supported for label components"));
+    }
+    if raw.labels.is_some() && component_type != "period" && component_type != "possession" {
+        return Err(format!("'{id}' labels are only supported for period and possession components"));
+    }
+    let kind = match component_type.as_str() {
+        "number" => {
+            if raw.edit.is_some() {
+                return Err(format!("'{id}' edit is only supported for label and image components"));
+            }
+            let default = raw
+                .default
+                .as_ref()
+                .and_then(|v| v.as_integer())
+                .ok_or_else(|| format!("'{id}' default must be an integer"))?
+                as i32;
+
+            let keybind = if let Some(binds) = raw.keybind.as_ref() {
+                Some(NumberKeybind {
+                    increase: parse_optional_keybind(id, binds, "increase")?,
+                    decrease: parse_optional_keybind(id, binds, "decrease")?,
+                    reset: parse_optional_keybind(id, binds, "reset")?,
+                })
+            } else {
+                None
+            };
+
+            let period_budget = raw.period_budget.as_ref().map(|budget| PeriodBudget {
+                period: budget.period.clone(),
+                values: budget.values.clone(),
+            });
+
+            if let Some(history) = raw.history {
+                if !(1..=MAX_HISTORY_POINTS).contains(&history) {
+                    return Err(format!("'{id}' history must be between 1 and {MAX_HISTORY_POINTS} values"));
+                }
+            }
+
+            ComponentKind::Number {
+                default,
+                keybind,
+                period_budget,
+                history: raw.history,
+                score: raw.score.unwrap_or(false),
+            }
+        }
+        "timer" if raw.mirror.is_some() => {
+            // Value, actions, and cues all belong to the primary timer.
+            let primary_owned = [
+                ("default", raw.default.is_some()),
+                (
+                    "keybind",
+                    raw.keybind
+                        .as_ref()
+                        .is_some_and(|binds| binds.keys().any(|name| name != VISIBILITY_ACTION)),
+                ),
+                ("sound", raw.sound.is_some()),
+                ("confirm", raw.confirm.is_some()),
+                ("edit", raw.edit.is_some()),
+                ("on_expire_sound", raw.on_expire_sound.is_some()),
+                ("on_expire_relay", raw.on_expire_relay.is_some()),
+                ("on_expire_duration_ms", raw.on_expire_duration_ms.is_some()),
+                ("tick_below", raw.tick_below.is_some()),
+                ("tick_sound", raw.tick_sound.is_some()),
+                ("freeze_on_stop", raw.freeze_on_stop.is_some()),
+                ("voice_cues", raw.voice_cues.is_some()),
+                ("t_minus", raw.t_minus.is_some()),
+            ];
+            if let Some((key, _)) = primary_owned.iter().find(|(_, set)| *set) {
+                return Err(format!(
+                    "'{id}' mirrors another timer and cannot set {key}; configure it on the primary timer"
+                ));
+            }
+            ComponentKind::Timer {
+                default_ms: 0,
+                keybind: None,
+                rounding: parse_timer_rounding(id, type_rounding.as_deref(), raw.rounding.as_deref())?,
+                on_expire_sound: None,
+                on_expire_relay: None,
+                on_expire_duration_ms: None,
+                tick_below_ms: None,
+                tick_sound: None,
+                voice_cues_ms: Vec::new(),
+                t_minus_ms: Vec::new(),
+                freeze_on_stop: false,
+                mirror: raw.mirror.clone(),
+            }
+        }
+        "timer" => {
+            if raw.edit.is_some() {
+                return Err(format!("'{id}' edit is only supported for label and image components"));
+            }
+            let raw_default = raw
+                .default
+                .as_ref()
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| format!("'{id}' default must be a timer string HH:MM:SS"))?;
+
+            let keybind = if let Some(binds) = raw.keybind.as_ref() {
+                Some(TimerKeybind {
+                    start: parse_optional_keybind(id, binds, "start")?,
+                    stop: parse_optional_keybind(id, binds, "stop")?,
+                    reset: parse_optional_keybind(id, binds, "reset")?,
+                    increase: parse_optional_keybind(id, binds, "increase")?,
+                    decrease: parse_optional_keybind(id, binds, "decrease")?,
+                })
+            } else {
+                None
+            };
+
+            let rounding = parse_timer_rounding(id, type_rounding.as_deref(), raw.rounding.as_deref())?;
+            let on_expire_sound = match raw.on_expire_sound.as_deref() {
+                Some(sound) => Some(parse_sound(id, "on_expire_sound", sound, &global.horn_patterns, base_dir)?),
+                None => None,
+            };
+            if let Some(relay) = raw.on_expire_relay.as_deref() {
+                if !global.relays.iter().any(|output| output.name == relay) {
+                    return Err(format!("'{id}' on_expire_relay references unknown relay '{relay}'"));
+                }
+            }
+            if raw.on_expire_duration_ms == Some(0) {
+                return Err(format!("'{id}' on_expire_duration_ms must be > 0"));
+            }
+            let tick_below_ms = match raw.tick_below.as_deref() {
+                Some(threshold) => {
+                    let threshold_ms =
+                        parse_timer_default(threshold).map_err(|e| format!("'{id}' tick_below: {e}"))?;
+                    if threshold_ms <= 0 {
+                        return Err(format!("'{id}' tick_below must be greater than 00:00:00"));
+                    }
+                    Some(threshold_ms)
+                }
+                None => None,
+            };
+            let tick_sound = match raw.tick_sound.as_deref() {
+                Some(_) if tick_below_ms.is_none() => {
+                    return Err(format!("'{id}' tick_sound requires tick_below"));
+                }
+                Some(sound) if sound.trim().is_empty() => {
+                    return Err(format!("'{id}' tick_sound cannot be empty"));
+                }
+                Some(sound) => Some(resolve_asset_path(base_dir, sound)),
+                None => None,
+            };
+            let voice_cues_ms = parse_timer_offsets(id, "voice_cues", raw.voice_cues.as_deref())?;
+            let t_minus_ms = parse_timer_offsets(id, "t_minus", raw.t_minus.as_deref())?;
+            ComponentKind::Timer {
+                default_ms: parse_timer_default(raw_default)
+                    .map_err(|e| format!("'{id}' default: {e}"))?,
+                keybind,
+                rounding,
+                on_expire_sound,
+                on_expire_relay: raw.on_expire_relay.clone(),
+                on_expire_duration_ms: raw.on_expire_duration_ms,
+                tick_below_ms,
+                tick_sound,
+                voice_cues_ms,
+                t_minus_ms,
+                freeze_on_stop: raw.freeze_on_stop.unwrap_or(false),
+                mirror: None,
+            }
+        }
+        "label" => {
+            let default = raw
+                .default
+                .as_ref()
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| format!("'{id}' default must be a string"))?
+                .to_string();
+            let multiline = raw.multiline.unwrap_or(false);
+            if !multiline && default.contains(['\n', '\r']) {
+                return Err(format!("'{id}' default has a line break; set multiline = true"));
+            }
+            if let Some(max_width) = raw.max_width {
+                if !multiline {
+                    return Err(format!("'{id}' max_width requires multiline = true"));
+                }
+                if max_width <= 0 || max_width > global.canvas.width {
+                    return Err(format!(
+                        "'{id}' max_width must be > 0 and at most the {}px canvas width",
+                        global.canvas.width
+                    ));
+                }
+            }
+            ComponentKind::Label {
+                default,
+                edit: raw.edit.unwrap_or(false),
+                multiline,
+                max_width: raw.max_width,
+            }
+        }
+        "image" => {
+            if alignment.is_some() {
+                return Err(format!("'{id}' alignment is only supported for number, fouls, timer, label, period, possession-time, derived, and text possession components"));
+            }
+            let source = raw
+                .source
+                .as_ref()
+                .ok_or_else(|| format!("'{id}' image requires source"))?;
+            let size = raw
+                .size
+                .as_ref()
+                .ok_or_else(|| format!("'{id}' image requires size.width and size.height"))?;
+            if size.width <= 0 || size.height <= 0 {
+                return Err(format!("'{id}' image size must be > 0"));
+            }
+            let opacity = raw.opacity.unwrap_or(1.0);
+            if !(0.0..=1.0).contains(&opacity) {
+                return Err(format!("'{id}' opacity must be between 0.0 and 1.0"));
+            }
+
+            let source_path = resolve_image_path(base_dir, source);
+            ComponentKind::Image {
+                source: source_path,
+                width: size.width,
+                height: size.height,
+                opacity,
+                edit: raw.edit.unwrap_or(false),
+            }
+        }
+        "image-toggle" => {
+            if alignment.is_some() {
+                return Err(format!("'{id}' alignment is only supported for number, fouls, timer, label, period, possession-time, derived, and text possession components"));
+            }
+            if raw.edit.is_some() {
+                return Err(format!("'{id}' edit is only supported for label and image components"));
+            }
+            let sources = raw
+                .sources
+                .as_ref()
+                .ok_or_else(|| format!("'{id}' image-toggle requires sources"))?;
+            if sources.is_empty() {
+                return Err(format!("'{id}' image-toggle sources must contain at least one entry"));
+            }
+            let resolved_sources: Vec<String> = sources
+                .iter()
+                .map(|source| {
+                    if source.trim().is_empty() {
+                        Err(format!("'{id}' image-toggle sources entries cannot be empty"))
+                    } else {
+                        Ok(resolve_image_path(base_dir, source))
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let size = raw
+                .size
+                .as_ref()
+                .ok_or_else(|| format!("'{id}' image-toggle requires size.width and size.height"))?;
+            if size.width <= 0 || size.height <= 0 {
+                return Err(format!("'{id}' image-toggle size must be > 0"));
+            }
+            let opacity = raw.opacity.unwrap_or(1.0);
+            if !(0.0..=1.0).contains(&opacity) {
+                return Err(format!("'{id}' opacity must be between 0.0 and 1.0"));
+            }
+
+            let default_index = match raw.default.as_ref() {
+                Some(value) => {
+                    let index = value
+                        .as_integer()
+                        .ok_or_else(|| format!("'{id}' image-toggle default must be an integer index"))?;
+                    if index < 0 || index as usize >= resolved_sources.len() {
+                        return Err(format!(
+                            "'{id}' image-toggle default index {index} is out of range for {} sources",
+                            resolved_sources.len()
+                        ));
+                    }
+                    index as usize
+                }
+                None => 0,
+            };
+
+            let interval_ms = raw.interval_ms;
+            if let Some(interval_ms) = interval_ms {
+                if !(MIN_ROTATION_INTERVAL_MS..=MAX_ROTATION_INTERVAL_MS).contains(&interval_ms) {
+                    return Err(format!(
+                        "'{id}' interval_ms must be between {MIN_ROTATION_INTERVAL_MS} and {MAX_ROTATION_INTERVAL_MS}"
+                    ));
+                }
+            }
+
+            let keybind = if let Some(binds) = raw.keybind.as_ref() {
+                if interval_ms.is_none() {
+                    if let Some(name) = ["start", "stop"].into_iter().find(|name| binds.contains_key(*name)) {
+                        return Err(format!("'{id}' keybind.{name} requires interval_ms"));
+                    }
+                }
+                let mut set = BTreeMap::new();
+                for (name, spec) in binds {
+                    let Some(index) = image_toggle_set_index(name) else {
+                        continue;
+                    };
+                    if index >= resolved_sources.len() {
+                        return Err(format!(
+                            "'{id}' keybind.{name} is out of range for {} sources",
+                            resolved_sources.len()
+                        ));
+                    }
+                    validate_keybind_spec(id, name, spec)?;
+                    set.insert(index, spec.clone());
+                }
+                Some(ImageToggleKeybind {
+                    forward: parse_optional_keybind(id, binds, "forward")?,
+                    backward: parse_optional_keybind(id, binds, "backward")?,
+                    set,
+                    start: parse_optional_keybind(id, binds, "start")?,
+                    stop: parse_optional_keybind(id, binds, "stop")?,
+                })
+            } else {
+                None
+            };
+
+            ComponentKind::ImageToggle {
+                sources: resolved_sources,
+                default_index,
+                width: size.width,
+                height: size.height,
+                opacity,
+                interval_ms,
+                keybind,
+            }
+        }
+        "video" => {
+            if alignment.is_some() {
+                return Err(format!("'{id}' alignment is only supported for number, fouls, timer, label, period, possession-time, derived, and text possession components"));
+            }
+            if raw.edit.is_some() {
+                return Err(format!("'{id}' edit is only supported for label and image components"));
+            }
+            if raw.default.is_some() {
+                return Err(format!("'{id}' video always starts playing and takes no default"));
+            }
+            let source = raw
+                .source
+                .as_ref()
+                .filter(|source| !source.trim().is_empty())
+                .ok_or_else(|| format!("'{id}' video requires source"))?;
+            let size = raw
+                .size
+                .as_ref()
+                .ok_or_else(|| format!("'{id}' video requires size.width and size.height"))?;
+            if size.width <= 0 || size.height <= 0 {
+                return Err(format!("'{id}' video size must be > 0"));
+            }
+            let opacity = raw.opacity.unwrap_or(1.0);
+            if !(0.0..=1.0).contains(&opacity) {
+                return Err(format!("'{id}' opacity must be between 0.0 and 1.0"));
+            }
+
+            let keybind = if let Some(binds) = raw.keybind.as_ref() {
+                Some(VideoKeybind {
+                    play: parse_optional_keybind(id, binds, "play")?,
+                    pause: parse_optional_keybind(id, binds, "pause")?,
+                })
+            } else {
+                None
+            };
+
+            ComponentKind::Video {
+                source: resolve_asset_path(base_dir, source),
+                width: size.width,
+                height: size.height,
+                opacity,
+                looped: raw.looped.unwrap_or(false),
+                muted: raw.mute.unwrap_or(false),
+                keybind,
+            }
+        }
+        "period" => {
+            if raw.edit.is_some() {
+                return Err(format!("'{id}' edit is only supported for label and image components"));
+            }
+            let labels = match raw.labels.as_ref() {
+                Some(labels) => labels.clone(),
+                None => DEFAULT_PERIOD_LABELS.iter().map(|label| label.to_string()).collect(),
+            };
+            if labels.is_empty() {
+                return Err(format!("'{id}' period labels must contain at least one entry"));
+            }
+
+            let default_index = match raw.default.as_ref() {
+                Some(value) => {
+                    let period = value
+                        .as_integer()
+                        .ok_or_else(|| format!("'{id}' period default must be an integer"))?;
+                    if period < 1 || period as usize > labels.len() {
+                        return Err(format!(
+                            "'{id}' period default {period} must be between 1 and {}",
+                            labels.len()
+                        ));
+                    }
+                    period as usize - 1
+                }
+                None => 0,
+            };
+
+            let keybind = if let Some(binds) = raw.keybind.as_ref() {
+                Some(PeriodKeybind {
+                    forward: parse_optional_keybind(id, binds, "forward")?,
+                    backward: parse_optional_keybind(id, binds, "backward")?,
+                })
+            } else {
+                None
+            };
+
+            let on_advance = match raw.on_advance.as_ref() {
+                Some(advance) => {
+                    let durations_ms = match advance.durations.as_ref() {
+                        Some(durations) => {
+                            if durations.len() != labels.len() {
+                                return Err(format!(
+                                    "'{id}' on_advance.durations must have one entry per label ({})",
+                                    labels.len()
+                                ));
+                            }
+                            Some(
+                                durations
+                                    .iter()
+                                    .map(|duration| parse_timer_default(duration))
+                                    .collect::<Result<Vec<_>, _>>()
+                                    .map_err(|e| format!("'{id}' on_advance.durations: {e}"))?,
+                            )
+                        }
+                        None => None,
+                    };
+                    Some(PeriodAdvance {
+                        reset_timer: advance.reset_timer.clone(),
+                        durations_ms,
+                    })
+                }
+                None => None,
+            };
+
+            ComponentKind::Period {
+                labels,
+                default_index,
+                wrap: raw.wrap.unwrap_or(false),
+                keybind,
+                on_advance,
+            }
+        }
+        "fouls" => {
+            if raw.edit.is_some() {
+                return Err(format!("'{id}' edit is only supported for label and image components"));
+            }
+            let default = match raw.default.as_ref() {
+                Some(value) => value
+                    .as_integer()
+                    .ok_or_else(|| format!("'{id}' default must be an integer"))?
+                    as i32,
+                None => 0,
+            };
+            for (name, threshold) in [("bonus", raw.bonus), ("double_bonus", raw.double_bonus)] {
+                if threshold.is_some_and(|threshold| threshold <= 0) {
+                    return Err(format!("'{id}' {name} must be > 0"));
+                }
+            }
+            if let (Some(bonus), Some(double_bonus)) = (raw.bonus, raw.double_bonus) {
+                if double_bonus <= bonus {
+                    return Err(format!("'{id}' double_bonus must be greater than bonus"));
+                }
+            }
+
+            let keybind = if let Some(binds) = raw.keybind.as_ref() {
+                Some(NumberKeybind {
+                    increase: parse_optional_keybind(id, binds, "increase")?,
+                    decrease: parse_optional_keybind(id, binds, "decrease")?,
+                    reset: parse_optional_keybind(id, binds, "reset")?,
+                })
+            } else {
+                None
+            };
+
+            ComponentKind::Fouls {
+                default,
+                bonus: raw.bonus,
+                double_bonus: raw.double_bonus,
+                reset_period: raw.reset_period.clone(),
+                keybind,
+            }
+        }
+        "possession" => {
+            if raw.edit.is_some() {
+                return Err(format!("'{id}' edit is only supported for label and image components"));
+            }
+            if raw.labels.is_some() && raw.sources.is_some() {
+                return Err(format!("'{id}' possession accepts either labels or sources, not both"));
+            }
+
+            let display = if let Some(sources) = raw.sources.as_ref() {
+                if alignment.is_some() {
+                    return Err(format!("'{id}' alignment is only supported for number, fouls, timer, label, period, possession-time, derived, and text possession components"));
+                }
+                if sources.len() != 2 {
+                    return Err(format!("'{id}' possession sources must contain exactly two entries"));
+                }
+                let resolved_sources: Vec<String> = sources
+                    .iter()
+                    .map(|source| {
+                        if source.trim().is_empty() {
+                            Err(format!("'{id}' possession sources entries cannot be empty"))
+                        } else {
+                            Ok(resolve_image_path(base_dir, source))
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let size = raw
+                    .size
+                    .as_ref()
+                    .ok_or_else(|| format!("'{id}' possession sources require size.width and size.height"))?;
+                if size.width <= 0 || size.height <= 0 {
+                    return Err(format!("'{id}' possession size must be > 0"));
+                }
+                let opacity = raw.opacity.unwrap_or(1.0);
+                if !(0.0..=1.0).contains(&opacity) {
+                    return Err(format!("'{id}' opacity must be between 0.0 and 1.0"));
+                }
+                PossessionDisplay::Images {
+                    sources: resolved_sources,
+                    width: size.width,
+                    height: size.height,
+                    opacity,
+                }
+            } else {
+                let labels = match raw.labels.as_ref() {
+                    Some(labels) => labels.clone(),
+                    None => DEFAULT_POSSESSION_LABELS.iter().map(|label| label.to_string()).collect(),
+                };
+                if labels.len() != 2 {
+                    return Err(format!("'{id}' possession labels must contain exactly two entries"));
+                }
+                PossessionDisplay::Labels(labels)
+            };
+
+            let default_index = match raw.default.as_ref() {
+                Some(value) => match value.as_integer() {
+                    Some(side @ 1..=2) => side as usize - 1,
+                    _ => return Err(format!("'{id}' possession default must be 1 or 2")),
+                },
+                None => 0,
+            };
+
+            let keybind = if let Some(binds) = raw.keybind.as_ref() {
+                Some(PossessionKeybind {
+                    toggle: parse_optional_keybind(id, binds, "toggle")?,
+                })
+            } else {
+                None
+            };
+
+            ComponentKind::Possession {
+                display,
+                default_index,
+                keybind,
+            }
+        }
+        "possession-time" => {
+            if raw.edit.is_some() {
+                return Err(format!("'{id}' edit is only supported for label and image components"));
+            }
+            if raw.default.is_some() {
+                return Err(format!("'{id}' possession-time always starts from zero and takes no default"));
+            }
+            let clock = raw
+                .clock
+                .clone()
+                .filter(|clock| !clock.trim().is_empty())
+                .ok_or_else(|| format!("'{id}' possession-time requires clock, the id of the game clock timer"))?;
+
+            let keybind = if let Some(binds) = raw.keybind.as_ref() {
+                Some(PossessionTimeKeybind {
+                    home: parse_optional_keybind(id, binds, "home")?,
+                    away: parse_optional_keybind(id, binds, "away")?,
+                    reset: parse_optional_keybind(id, binds, "reset")?,
+                })
+            } else {
+                None
+            };
+
+            ComponentKind::PossessionTime { clock, keybind }
+        }
+        "ticker" => {
+            if raw.edit.is_some() {
+                return Err(format!("'{id}' edit is only supported for label and image components"));
+            }
+            if raw.default.is_some() {
+                return Err(format!("'{id}' ticker takes messages instead of a default"));
+            }
+            if alignment.is_some() {
+                return Err(format!("'{id}' alignment is only supported for number, fouls, timer, label, period, possession-time, derived, and text possession components"));
+            }
+            let messages = raw.messages.clone().unwrap_or_default();
+            if messages.len() > MAX_TICKER_MESSAGES {
+                return Err(format!("'{id}' ticker can hold at most {MAX_TICKER_MESSAGES} messages"));
+            }
+            for message in &messages {
+                validate_ticker_message(message).map_err(|e| format!("'{id}' {e}"))?;
+            }
+            let speed = raw.speed.unwrap_or(DEFAULT_TICKER_SPEED);
+            if !(1..=MAX_TICKER_SPEED).contains(&speed) {
+                return Err(format!("'{id}' speed must be between 1 and {MAX_TICKER_SPEED} pixels per second"));
+            }
+            let width = raw
+                .width
+                .ok_or_else(|| format!("'{id}' ticker requires width, the visible strip in pixels"))?;
+            if width <= 0 || position.x + width > global.canvas.width {
+                return Err(format!(
+                    "'{id}' width must be > 0 and fit inside the {}px canvas width",
+                    global.canvas.width
+                ));
+            }
+
+            ComponentKind::Ticker { messages, speed, width }
+        }
+        "derived" => {
+            if raw.edit.is_some() {
+                return Err(format!("'{id}' edit is only supported for label and image components"));
+            }
+            if raw.default.is_some() {
+                return Err(format!("'{id}' derived takes expr instead of a default"));
+            }
+            let expr = raw.expr.as_deref().ok_or_else(|| format!("'{id}' derived requires expr"))?;
+            let expr = Expression::parse(expr).map_err(|e| format!("'{id}' expr {e}"))?;
+            ComponentKind::Derived { expr }
+        }
+        other => return Err(format!("'{id}' has unsupported type '{other}'")),
+    };
+
+    if let Some(rate_limit_ms) = raw.rate_limit_ms {
+        if !matches!(
+            kind,
+            ComponentKind::Label { .. } | ComponentKind::Image { .. } | ComponentKind::Ticker { .. }
+        ) {
+            return Err(format!("'{id}' rate_limit_ms is only supported for label, image, and ticker components"));
+        }
+        if rate_limit_ms == 0 || rate_limit_ms > MAX_EDIT_RATE_LIMIT_MS {
+            return Err(format!("'{id}' rate_limit_ms must be between 1 and {MAX_EDIT_RATE_LIMIT_MS}"));
+        }
+    }
+
+    if raw.persistent.is_some() && !matches!(kind, ComponentKind::Number { .. } | ComponentKind::Label { .. }) {
+        return Err(format!("'{id}' persistent is only supported for number and label components"));
+    }
+
+    let shows_image = matches!(
+        &kind,
+        ComponentKind::Image { .. }
+            | ComponentKind::ImageToggle { .. }
+            | ComponentKind::Video { .. }
+            | ComponentKind::Possession {
+                display: PossessionDisplay::Images { .. },
+                ..
+            }
+    );
+    if let Some(opacity) = raw.opacity.filter(|_| !shows_image) {
+        if !(0.0..=1.0).contains(&opacity) {
+            return Err(format!("'{id}' opacity must be between 0.0 and 1.0"));
+        }
+        font.opacity = opacity;
+    }
+    if raw.monospace_digits.is_some() && !shows_text(&kind) {
+        return Err(format!("'{id}' monospace_digits is only supported for components that show text"));
+    }
+    if let Some(fit) = &raw.fit {
+        validate_text_fit(id, fit, &kind, global.canvas)?;
+    }
+
+    let sounds = parse_action_sounds(id, &kind, raw.sound.as_ref(), &global.horn_patterns, base_dir)?;
+    let confirm = parse_confirm(id, &kind, raw.confirm.as_ref())?;
+
+    let allow_alignment = matches!(
+        &kind,
+        ComponentKind::Number { .. }
+            | ComponentKind::Fouls { .. }
+            | ComponentKind::Timer { .. }
+            | ComponentKind::Label { .. }
+            | ComponentKind::Period { .. }
+            | ComponentKind::Possession {
+                display: PossessionDisplay::Labels(_),
+                ..
+            }
+            | ComponentKind::PossessionTime { .. }
+            | ComponentKind::Derived { .. }
+    );
+
+    Ok(ComponentConfig {
+        id: id.to_string(),
+        position,
+        alignment: if allow_alignment { alignment } else { None },
+        font,
+        kind,
+        sounds,
+        confirm,
+        layer: raw.layer,
+        group: raw.group,
+        visible: raw.visible.unwrap_or(true),
+        visibility_keybind,
+        rate_limit_ms: raw.rate_limit_ms,
+        persistent: raw.persistent.unwrap_or(false),
+        fit: raw.fit,
+        monospace_digits: raw.monospace_digits.unwrap_or(false),
+    })
+}
+
+/// True for component types drawn as text rather than as an image or video.
+fn shows_text(kind: &ComponentKind) -> bool {
+    !matches!(
+        kind,
+        ComponentKind::Image { .. }
+            | ComponentKind::ImageToggle { .. }
+            | ComponentKind::Video { .. }
+            | ComponentKind::Possession {
+                display: PossessionDisplay::Images { .. },
+                ..
+            }
+    )
+}
+
+fn validate_text_fit(id: &str, fit: &TextFit, kind: &ComponentKind, canvas: CanvasSize) -> Result<(), String> {
+    let shows_text = !matches!(
+        kind,
+        ComponentKind::Image { .. }
+            | ComponentKind::ImageToggle { .. }
+            | ComponentKind::Video { .. }
+            | ComponentKind::Ticker { .. }
+            | ComponentKind::Possession {
+                display: PossessionDisplay::Images { .. },
+                ..
+            }
+    );
+    if !shows_text {
+        return Err(format!("'{id}' fit is only supported for components that show text, except tickers"));
+    }
+    if fit.max_width.is_none() && fit.max_height.is_none() {
+        return Err(format!("'{id}' fit needs max_width, max_height, or both"));
+    }
+    for (key, value, limit) in [("max_width", fit.max_width, canvas.width), ("max_height", fit.max_height, canvas.height)] {
+        if let Some(value) = value {
+            if value <= 0 || value > limit {
+                return Err(format!("'{id}' fit {key} must be between 1 and {limit}"));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Serializes a config back into the TOML layout accepted by the loader.
+pub fn serialize_config(config: &ScoreboardConfig) -> Result<String, String> {
+    let mut root = toml::Table::new();
+    root.insert("global".to_string(), toml::Value::Table(global_to_toml(&config.global)));
+    if !config.groups.is_empty() {
+        let groups = config
+            .groups
+            .iter()
+            .map(|group| (group.name.clone(), toml::Value::Table(group_to_toml(group))))
+            .collect();
+        root.insert("group".to_string(), toml::Value::Table(groups));
+    }
+    if config.gamepad != GamepadSettings::default() {
+        let mut gamepad = toml::Table::new();
+        gamepad.insert("deadzone".to_string(), toml::Value::Float(config.gamepad.deadzone.into()));
+        gamepad.insert(
+            "trigger_threshold".to_string(),
+            toml::Value::Float(config.gamepad.trigger_threshold.into()),
+        );
+        root.insert("gamepad".to_string(), toml::Value::Table(gamepad));
+    }
+    if !config.macros.is_empty() {
+        let macros = config
+            .macros
+            .iter()
+            .map(|settings| (settings.name.clone(), toml::Value::Table(macro_to_toml(settings))))
+            .collect();
+        root.insert("macro".to_string(), toml::Value::Table(macros));
+    }
+    for component in &config.components {
+        root.insert(component.id.clone(), toml::Value::Table(component_to_toml(component)));
+    }
+    toml::to_string(&root).map_err(|e| format!("Failed serializing config: {e}"))
+}
+
+fn macro_to_toml(settings: &MacroSettings) -> toml::Table {
+    let mut table = toml::Table::new();
+    let actions = settings
+        .steps
+        .iter()
+        .map(|step| match step.delay_ms {
+            0 => toml::Value::String(step.action.clone()),
+            delay_ms => {
+                let mut step_table = toml::Table::new();
+                step_table.insert("action".to_string(), toml::Value::String(step.action.clone()));
+                step_table.insert("delay_ms".to_string(), toml::Value::Integer(delay_ms as i64));
+                toml::Value::Table(step_table)
+            }
+        })
+        .collect();
+    table.insert("actions".to_string(), toml::Value::Array(actions));
+    if let Some(keybind) = &settings.keybind {
+        table.insert("keybind".to_string(), keybind_to_toml(keybind));
+    }
+    table
+}
+
+fn group_to_toml(group: &ComponentGroup) -> toml::Table {
+    let mut table = toml::Table::new();
+    let mut offset = toml::Table::new();
+    offset.insert("x".to_string(), toml::Value::Integer(group.offset.x.into()));
+    offset.insert("y".to_string(), toml::Value::Integer(group.offset.y.into()));
+    table.insert("offset".to_string(), toml::Value::Table(offset));
+    if !group.visible {
+        table.insert("visible".to_string(), toml::Value::Boolean(false));
+    }
+    if group.scale != 1.0 {
+        table.insert("scale".to_string(), toml::Value::Float(group.scale.into()));
+    }
+    table
+}
+
+fn global_to_toml(global: &GlobalSettings) -> toml::Table {
+    let mut table = toml::Table::new();
+    let mut canvas = toml::Table::new();
+    canvas.insert("width".to_string(), toml::Value::Integer(global.canvas.width.into()));
+    canvas.insert("height".to_string(), toml::Value::Integer(global.canvas.height.into()));
+    table.insert("canvas".to_string(), toml::Value::Table(canvas));
+    if global.rotate != 0 {
+        table.insert("rotate".to_string(), toml::Value::Integer(global.rotate.into()));
+    }
+    match &global.chroma_key {
+        Some(chroma_key) => table.insert("chroma_key".to_string(), toml::Value::String(chroma_key.clone())),
+        None => table.insert(
+            "background_color".to_string(),
+            toml::Value::String(global.background_color.clone()),
+        ),
+    };
+    table.insert("font".to_string(), font_to_toml(&global.font));
+    if global.timer_display_offset_ms != 0 {
+        table.insert(
+            "timer_display_offset_ms".to_string(),
+            toml::Value::Integer(global.timer_display_offset_ms),
+        );
+    }
+    if global.freeze_on_error {
+        table.insert("freeze_on_error".to_string(), toml::Value::Boolean(true));
+    }
+    if global.import_images {
+        table.insert("import_images".to_string(), toml::Value::Boolean(true));
+    }
+    let keyboard_mode = match global.keyboard_mode {
+        KeyboardMode::Auto => None,
+        KeyboardMode::Global => Some("global"),
+        KeyboardMode::Focused => Some("focused"),
+    };
+    if let Some(keyboard_mode) = keyboard_mode {
+        table.insert("keyboard_mode".to_string(), toml::Value::String(keyboard_mode.to_string()));
+    }
+    if let Some(keybind) = &global.keybind {
+        let binds = keybinds_to_toml(&[
+            ("fullscreen", &keybind.fullscreen),
+            ("player_select", &keybind.player_select),
+            ("theme", &keybind.theme),
+            ("lock", &keybind.lock),
+            ("ready", &keybind.ready),
+        ]);
+        if !binds.is_empty() {
+            table.insert("keybind".to_string(), toml::Value::Table(binds));
+        }
+    }
+    if !global.player_actions.is_empty() {
+        let mut actions = toml::Table::new();
+        for player_action in &global.player_actions {
+            let mut action = toml::Table::new();
+            action.insert("keybind".to_string(), keybind_to_toml(&player_action.keybind));
+            if let Some(apply) = &player_action.apply {
+                action.insert(
+                    "apply".to_string(),
+                    toml::Value::String(format!("{}.{}", apply.component, apply.action)),
+                );
+            }
+            actions.insert(player_action.name.clone(), toml::Value::Table(action));
+        }
+        table.insert("player_action".to_string(), toml::Value::Table(actions));
+    }
+    if !global.relays.is_empty() {
+        let mut relays = toml::Table::new();
+        for relay in &global.relays {
+            let mut relay_table = toml::Table::new();
+            relay_table.insert("port".to_string(), toml::Value::String(relay.port.clone()));
+            relay_table.insert("baud_rate".to_string(), toml::Value::Integer(relay.baud_rate.into()));
+            relay_table.insert("on".to_string(), toml::Value::String(format_hex_bytes(&relay.on)));
+            relay_table.insert("off".to_string(), toml::Value::String(format_hex_bytes(&relay.off)));
+            relays.insert(relay.name.clone(), toml::Value::Table(relay_table));
+        }
+        table.insert("relay".to_string(), toml::Value::Table(relays));
+    }
+    if let Some(announcement) = &global.announcement {
+        table.insert("announcement".to_string(), announcement_to_toml(announcement));
+    }
+    if !global.aux_timers.is_empty() {
+        let mut aux_timers = toml::Table::new();
+        for aux_timer in &global.aux_timers {
+            let mut aux_table = toml::Table::new();
+            aux_table.insert(
+                "duration".to_string(),
+                toml::Value::String(format_timer_default(aux_timer.duration_ms)),
+            );
+            if let Some(label) = &aux_timer.label {
+                aux_table.insert("label".to_string(), toml::Value::String(label.clone()));
+            }
+            if let Some(sound) = &aux_timer.sound {
+                aux_table.insert("sound".to_string(), toml::Value::String(sound.clone()));
+            }
+            if let Some(k) = &aux_timer.keybind {
+                let keybind = keybinds_to_toml(&[("start", &k.start), ("stop", &k.stop), ("reset", &k.reset)]);
+                aux_table.insert("keybind".to_string(), toml::Value::Table(keybind));
+            }
+            aux_timers.insert(aux_timer.name.clone(), toml::Value::Table(aux_table));
+        }
+        table.insert("aux_timer".to_string(), toml::Value::Table(aux_timers));
+    }
+    if !global.horn_patterns.is_empty() {
+        let mut patterns = toml::Table::new();
+        for pattern in &global.horn_patterns {
+            let mut pattern_table = toml::Table::new();
+            if let Some(sound) = &pattern.sound {
+                pattern_table.insert("sound".to_string(), toml::Value::String(sound.clone()));
+            }
+            let blasts = pattern.blasts_ms.iter().map(|&blast_ms| toml::Value::Integer(blast_ms as i64)).collect();
+            pattern_table.insert("blasts_ms".to_string(), toml::Value::Array(blasts));
+            pattern_table.insert("gap_ms".to_string(), toml::Value::Integer(pattern.gap_ms as i64));
+            patterns.insert(pattern.name.clone(), toml::Value::Table(pattern_table));
+        }
+        table.insert("horn_pattern".to_string(), toml::Value::Table(patterns));
+    }
+    if let Some(audio) = &global.audio {
+        let mut audio_table = toml::Table::new();
+        if let Some(device) = &audio.output_device {
+            audio_table.insert("output_device".to_string(), toml::Value::String(device.clone()));
+        }
+        if !audio.devices.is_empty() {
+            let devices = audio
+                .devices
+                .iter()
+                .map(|(cue, device)| (cue.clone(), toml::Value::String(device.clone())))
+                .collect();
+            audio_table.insert("device".to_string(), toml::Value::Table(devices));
+        }
+        table.insert("audio".to_string(), toml::Value::Table(audio_table));
+    }
+    if let Some(playlist) = &global.playlist {
+        table.insert("playlist".to_string(), playlist_to_toml(playlist));
+    }
+    if let Some(led_matrix) = &global.led_matrix {
+        table.insert("led_matrix".to_string(), led_matrix_to_toml(led_matrix));
+    }
+    if !global.webhooks.is_empty() {
+        let mut webhooks = toml::Table::new();
+        for webhook in &global.webhooks {
+            let mut webhook_table = toml::Table::new();
+            webhook_table.insert("url".to_string(), toml::Value::String(webhook.url.clone()));
+            let format = match webhook.format {
+                WebhookFormat::Json => "json",
+                WebhookFormat::Discord => "discord",
+            };
+            webhook_table.insert("format".to_string(), toml::Value::String(format.to_string()));
+            webhook_table.insert("batch_ms".to_string(), toml::Value::Integer(webhook.batch_ms as i64));
+            webhooks.insert(webhook.name.clone(), toml::Value::Table(webhook_table));
+        }
+        table.insert("webhook".to_string(), toml::Value::Table(webhooks));
+    }
+    if let Some(fixtures) = &global.fixtures {
+        let mut fixtures_table = toml::Table::new();
+        fixtures_table.insert("source".to_string(), toml::Value::String(fixtures.source.clone()));
+        fixtures_table.insert("countdown".to_string(), toml::Value::String(fixtures.countdown.clone()));
+        fixtures_table.insert("lead".to_string(), toml::Value::String(format_timer_default(fixtures.lead_ms)));
+        fixtures_table.insert(
+            "refresh".to_string(),
+            toml::Value::String(format_timer_default(fixtures.refresh_ms)),
+        );
+        fixtures_table.insert(
+            "utc_offset".to_string(),
+            toml::Value::String(format_utc_offset(fixtures.utc_offset_minutes)),
+        );
+        for (key, value) in [("home_label", &fixtures.home_label), ("away_label", &fixtures.away_label)] {
+            if let Some(value) = value {
+                fixtures_table.insert(key.to_string(), toml::Value::String(value.clone()));
+            }
+        }
+        if !fixtures.configs.is_empty() {
+            let configs: toml::Table = fixtures
+                .configs
+                .iter()
+                .map(|(keyword, path)| (keyword.clone(), toml::Value::String(path.clone())))
+                .collect();
+            fixtures_table.insert("config".to_string(), toml::Value::Table(configs));
+        }
+        table.insert("fixtures".to_string(), toml::Value::Table(fixtures_table));
+    }
+    if let Some(remote) = &global.remote {
+        let mut remote_table = toml::Table::new();
+        remote_table.insert("bind".to_string(), toml::Value::String(remote.bind.clone()));
+        remote_table.insert("port".to_string(), toml::Value::Integer(remote.port.into()));
+        if let Some(token) = &remote.token {
+            remote_table.insert("token".to_string(), toml::Value::String(token.clone()));
+        }
+        if !remote.tokens.is_empty() {
+            let mut tokens = toml::Table::new();
+            for entry in &remote.tokens {
+                let mut token_table = toml::Table::new();
+                token_table.insert("token".to_string(), toml::Value::String(entry.token.clone()));
+                token_table.insert("role".to_string(), toml::Value::String(entry.role.as_str().to_string()));
+                tokens.insert(entry.name.clone(), toml::Value::Table(token_table));
+            }
+            remote_table.insert("tokens".to_string(), toml::Value::Table(tokens));
+        }
+        table.insert("remote".to_string(), toml::Value::Table(remote_table));
+    }
+    if let Some(osc) = &global.osc {
+        let mut osc_table = toml::Table::new();
+        osc_table.insert("bind".to_string(), toml::Value::String(osc.bind.clone()));
+        if let Some(port) = osc.port {
+            osc_table.insert("port".to_string(), toml::Value::Integer(port.into()));
+        }
+        osc_table.insert("prefix".to_string(), toml::Value::String(osc.prefix.clone()));
+        if !osc.send_to.is_empty() {
+            let targets = osc
+                .send_to
+                .iter()
+                .map(|target| toml::Value::String(target.to_string()))
+                .collect();
+            osc_table.insert("send_to".to_string(), toml::Value::Array(targets));
+        }
+        if !osc.allow.is_empty() {
+            let allow: toml::Table = osc
+                .allow
+                .iter()
+                .map(|host| (host.address.to_string(), toml::Value::String(host.role.as_str().to_string())))
+                .collect();
+            osc_table.insert("allow".to_string(), toml::Value::Table(allow));
+        }
+        table.insert("osc".to_string(), toml::Value::Table(osc_table));
+    }
+    if let Some(serial_input) = &global.serial_input {
+        let mut serial_table = toml::Table::new();
+        serial_table.insert("port".to_string(), toml::Value::String(serial_input.port.clone()));
+        serial_table.insert("baud_rate".to_string(), toml::Value::Integer(serial_input.baud_rate.into()));
+        let protocol = match serial_input.protocol {
+            SerialProtocol::Daktronics => "daktronics",
+            SerialProtocol::Lines => "lines",
+        };
+        serial_table.insert("protocol".to_string(), toml::Value::String(protocol.to_string()));
+        let mut map = toml::Table::new();
+        for field in &serial_input.fields {
+            let mut field_table = toml::Table::new();
+            match &field.source {
+                SerialFieldSource::Position { offset, length } => {
+                    field_table.insert("offset".to_string(), toml::Value::Integer(*offset as i64));
+                    field_table.insert("length".to_string(), toml::Value::Integer(*length as i64));
+                }
+                SerialFieldSource::Name(name) => {
+                    field_table.insert("field".to_string(), toml::Value::String(name.clone()));
+                }
+            }
+            map.insert(field.component.clone(), toml::Value::Table(field_table));
+        }
+        serial_table.insert("map".to_string(), toml::Value::Table(map));
+        table.insert("serial_input".to_string(), toml::Value::Table(serial_table));
+    }
+    if let Some(obs) = &global.obs {
+        let mut obs_table = toml::Table::new();
+        obs_table.insert("url".to_string(), toml::Value::String(obs.url.clone()));
+        if let Some(password) = &obs.password {
+            obs_table.insert("password".to_string(), toml::Value::String(password.clone()));
+        }
+        let mut triggers = toml::Table::new();
+        for trigger in &obs.triggers {
+            let mut trigger_table = toml::Table::new();
+            match &trigger.event {
+                ObsEvent::TimerExpired { timer } => {
+                    trigger_table.insert("on_expire".to_string(), toml::Value::String(timer.clone()));
+                }
+                ObsEvent::PeriodAdvanced { period, to } => {
+                    trigger_table.insert("on_period".to_string(), toml::Value::String(period.clone()));
+                    if let Some(to) = to {
+                        trigger_table.insert("to".to_string(), toml::Value::String(to.clone()));
+                    }
+                }
+            }
+            if let Some(scene) = &trigger.scene {
+                trigger_table.insert("scene".to_string(), toml::Value::String(scene.clone()));
+            }
+            if let Some(source) = &trigger.source {
+                trigger_table.insert("source".to_string(), toml::Value::String(source.source.clone()));
+                trigger_table.insert("source_scene".to_string(), toml::Value::String(source.scene.clone()));
+                trigger_table.insert("visible".to_string(), toml::Value::Boolean(source.visible));
+            }
+            triggers.insert(trigger.name.clone(), toml::Value::Table(trigger_table));
+        }
+        obs_table.insert("trigger".to_string(), toml::Value::Table(triggers));
+        table.insert("obs".to_string(), toml::Value::Table(obs_table));
+    }
+    if let Some(sync) = &global.sync {
+        let mut sync_table = toml::Table::new();
+        match &sync.role {
+            SyncRole::Primary { bind, port } => {
+                sync_table.insert("listen".to_string(), toml::Value::Integer((*port).into()));
+                sync_table.insert("bind".to_string(), toml::Value::String(bind.clone()));
+            }
+            SyncRole::Replica { primary } => {
+                sync_table.insert("primary".to_string(), toml::Value::String(primary.clone()));
+            }
+        }
+        if let Some(token) = &sync.token {
+            sync_table.insert("token".to_string(), toml::Value::String(token.clone()));
+        }
+        table.insert("sync".to_string(), toml::Value::Table(sync_table));
+    }
+    if let Some(discovery) = &global.discovery {
+        let mut discovery_table = toml::Table::new();
+        if let Some(name) = &discovery.name {
+            discovery_table.insert("name".to_string(), toml::Value::String(name.clone()));
+        }
+        discovery_table.insert("advertise".to_string(), toml::Value::Boolean(discovery.advertise));
+        table.insert("discovery".to_string(), toml::Value::Table(discovery_table));
+    }
+    if let Some(updates) = &global.updates {
+        let mut updates_table = toml::Table::new();
+        if let Some(url) = &updates.url {
+            updates_table.insert("url".to_string(), toml::Value::String(url.clone()));
+        }
+        updates_table.insert("check_on_startup".to_string(), toml::Value::Boolean(updates.check_on_startup));
+        table.insert("updates".to_string(), toml::Value::Table(updates_table));
+    }
+    if let Some(crash_report) = &global.crash_report {
+        let mut crash_report_table = toml::Table::new();
+        if let Some(endpoint) = &crash_report.endpoint {
+            crash_report_table.insert("endpoint".to_string(), toml::Value::String(endpoint.clone()));
+        }
+        table.insert("crash_report".to_string(), toml::Value::Table(crash_report_table));
+    }
+    if let Some(splash) = &global.splash {
+        let mut splash_table = toml::Table::new();
+        if let Some(image) = &splash.image {
+            splash_table.insert("image".to_string(), toml::Value::String(image.clone()));
+        }
+        if let Some(group) = &splash.group {
+            splash_table.insert("group".to_string(), toml::Value::String(group.clone()));
+        }
+        table.insert("splash".to_string(), toml::Value::Table(splash_table));
+    }
+    if let Some(notes) = &global.notes {
+        let mut notes_table = toml::Table::new();
+        if let Some(title) = &notes.title {
+            notes_table.insert("title".to_string(), toml::Value::String(title.clone()));
+        }
+        if let Some(text) = &notes.text {
+            notes_table.insert("text".to_string(), toml::Value::String(text.clone()));
+        }
+        if !notes.checklist.is_empty() {
+            let items = notes.checklist.iter().cloned().map(toml::Value::String).collect();
+            notes_table.insert("checklist".to_string(), toml::Value::Array(items));
+        }
+        table.insert("notes".to_string(), toml::Value::Table(notes_table));
+    }
+    if let Some(idle) = &global.idle {
+        let mut idle_table = toml::Table::new();
+        idle_table.insert("after".to_string(), toml::Value::String(format_timer_default(idle.after_ms)));
+        idle_table.insert("dim".to_string(), toml::Value::Float(f64::from(idle.dim)));
+        if !idle.hide.is_empty() {
+            let hide = idle.hide.iter().cloned().map(toml::Value::String).collect();
+            idle_table.insert("hide".to_string(), toml::Value::Array(hide));
+        }
+        table.insert("idle".to_string(), toml::Value::Table(idle_table));
+    }
+    if let Some(script) = &global.script {
+        let mut script_table = toml::Table::new();
+        script_table.insert("file".to_string(), toml::Value::String(script.file.clone()));
+        script_table.insert("tick_ms".to_string(), toml::Value::Integer(script.tick_ms as i64));
+        table.insert("script".to_string(), toml::Value::Table(script_table));
+    }
+    if let Some(summary) = &global.summary {
+        let mut summary_table = toml::Table::new();
+        summary_table.insert("home".to_string(), toml::Value::String(summary.home.clone()));
+        summary_table.insert("away".to_string(), toml::Value::String(summary.away.clone()));
+        summary_table.insert("home_name".to_string(), toml::Value::String(summary.home_name.clone()));
+        summary_table.insert("away_name".to_string(), toml::Value::String(summary.away_name.clone()));
+        let optional = [
+            ("period", &summary.period),
+            ("clock", &summary.clock),
+            ("home_fouls", &summary.home_fouls),
+            ("away_fouls", &summary.away_fouls),
+            ("home_timeouts", &summary.home_timeouts),
+            ("away_timeouts", &summary.away_timeouts),
+            ("markdown", &summary.markdown),
+            ("text", &summary.text),
+        ];
+        for (key, value) in optional {
+            if let Some(value) = value {
+                summary_table.insert(key.to_string(), toml::Value::String(value.clone()));
+            }
+        }
+        for (key, roster) in [("home_roster", &summary.home_roster), ("away_roster", &summary.away_roster)] {
+            if !roster.is_empty() {
+                let players: toml::Table = roster
+                    .iter()
+                    .map(|(jersey, name)| (jersey.clone(), toml::Value::String(name.clone())))
+                    .collect();
+                summary_table.insert(key.to_string(), toml::Value::Table(players));
+            }
+        }
+        table.insert("summary".to_string(), toml::Value::Table(summary_table));
+    }
+    if let Some(lock) = &global.lock {
+        let mut lock_table = toml::Table::new();
+        lock_table.insert("pin".to_string(), toml::Value::String(lock.pin.clone()));
+        table.insert("lock".to_string(), toml::Value::Table(lock_table));
+    }
+    if !global.themes.is_empty() {
+        let mut themes = toml::Table::new();
+        for theme in &global.themes {
+            let colors: toml::Table = theme
+                .colors
+                .iter()
+                .map(|(from, to)| (from.clone(), toml::Value::String(to.clone())))
+                .collect();
+            let mut theme_table = toml::Table::new();
+            theme_table.insert("colors".to_string(), toml::Value::Table(colors));
+            themes.insert(theme.name.clone(), toml::Value::Table(theme_table));
+        }
+        table.insert("theme".to_string(), toml::Value::Table(themes));
+    }
+    table
+}
+
+fn component_to_toml(component: &ComponentConfig) -> toml::Table {
+    let mut table = toml::Table::new();
+    let mut position = toml::Table::new();
+    position.insert("x".to_string(), toml::Value::Integer(component.position.x.into()));
+    position.insert("y".to_string(), toml::Value::Integer(component.position.y.into()));
+
+    let keybinds = match &component.kind {
+        ComponentKind::Number {
+            default,
+            keybind,
+            period_budget,
+            history,
+            score,
+        } => {
+            table.insert("type".to_string(), toml::Value::String("number".to_string()));
+            table.insert("default".to_string(), toml::Value::Integer((*default).into()));
+            if let Some(budget) = period_budget {
+                let mut budget_table = toml::Table::new();
+                budget_table.insert("period".to_string(), toml::Value::String(budget.period.clone()));
+                let values = budget
+                    .values
+                    .iter()
+                    .map(|(label, value)| (label.clone(), toml::Value::Integer((*value).into())))
+                    .collect();
+                budget_table.insert("values".to_string(), toml::Value::Table(values));
+                table.insert("period_budget".to_string(), toml::Value::Table(budget_table));
+            }
+            if let Some(history) = history {
+                table.insert("history".to_string(), toml::Value::Integer(*history as i64));
+            }
+            if *score {
+                table.insert("score".to_string(), toml::Value::Boolean(true));
+            }
+            keybind.as_ref().map(|k| {
+                keybinds_to_toml(&[("increase", &k.increase), ("decrease", &k.decrease), ("reset", &k.reset)])
+            })
+        }
+        ComponentKind::Timer {
+            default_ms,
+            keybind,
+            rounding,
+            on_expire_sound,
+            on_expire_relay,
+            on_expire_duration_ms,
+            tick_below_ms,
+            tick_sound,
+            voice_cues_ms,
+            t_minus_ms,
+            freeze_on_stop,
+            mirror,
+        } => {
+            table.insert("type".to_string(), toml::Value::String("timer".to_string()));
+            match mirror {
+                Some(primary) => table.insert("mirror".to_string(), toml::Value::String(primary.clone())),
+                None => table.insert("default".to_string(), toml::Value::String(format_timer_default(*default_ms))),
+            };
+            if let TimerRounding::Basketball = rounding {
+                table.insert("rounding".to_string(), toml::Value::String("basketball".to_string()));
+            }
+            if let Some(sound) = on_expire_sound {
+                table.insert("on_expire_sound".to_string(), toml::Value::String(sound.clone()));
+            }
+            if let Some(relay) = on_expire_relay {
+                table.insert("on_expire_relay".to_string(), toml::Value::String(relay.clone()));
+            }
+            if let Some(duration_ms) = on_expire_duration_ms {
+                table.insert("on_expire_duration_ms".to_string(), toml::Value::Integer(*duration_ms as i64));
+            }
+            if let Some(threshold_ms) = tick_below_ms {
+                table.insert(
+                    "tick_below".to_string(),
+                    toml::Value::String(format_timer_default(*threshold_ms)),
+                );
+            }
+            if let Some(sound) = tick_sound {
+                table.insert("tick_sound".to_string(), toml::Value::String(sound.clone()));
+            }
+            if !voice_cues_ms.is_empty() {
+                let cues = voice_cues_ms
+                    .iter()
+                    .map(|&cue_ms| toml::Value::String(format_timer_default(cue_ms)))
+                    .collect();
+                table.insert("voice_cues".to_string(), toml::Value::Array(cues));
+            }
+            if !t_minus_ms.is_empty() {
+                let offsets = t_minus_ms
+                    .iter()
+                    .map(|&offset_ms| toml::Value::String(format_timer_default(offset_ms)))
+                    .collect();
+                table.insert("t_minus".to_string(), toml::Value::Array(offsets));
+            }
+            if *freeze_on_stop {
+                table.insert("freeze_on_stop".to_string(), toml::Value::Boolean(true));
+            }
+            keybind.as_ref().map(|k| {
+                keybinds_to_toml(&[
+                    ("start", &k.start),
+                    ("stop", &k.stop),
+                    ("reset", &k.reset),
+                    ("increase", &k.increase),
+                    ("decrease", &k.decrease),
+                ])
+            })
+        }
+        ComponentKind::Label {
+            default,
+            edit,
+            multiline,
+            max_width,
+        } => {
+            table.insert("type".to_string(), toml::Value::String("label".to_string()));
+            table.insert("default".to_string(), toml::Value::String(default.clone()));
+            if *edit {
+                table.insert("edit".to_string(), toml::Value::Boolean(true));
+            }
+            if *multiline {
+                table.insert("multiline".to_string(), toml::Value::Boolean(true));
+            }
+            if let Some(max_width) = max_width {
+                table.insert("max_width".to_string(), toml::Value::Integer(i64::from(*max_width)));
+            }
+            None
+        }
+        ComponentKind::Image {
+            source,
+            width,
+            height,
+            opacity,
+            edit,
+        } => {
+            table.insert("type".to_string(), toml::Value::String("image".to_string()));
+            table.insert("source".to_string(), toml::Value::String(source.clone()));
+            table.insert("size".to_string(), size_to_toml(*width, *height));
+            table.insert("opacity".to_string(), toml::Value::Float((*opacity).into()));
+            if *edit {
+                table.insert("edit".to_string(), toml::Value::Boolean(true));
+            }
+            None
+        }
+        ComponentKind::Video {
+            source,
+            width,
+            height,
+            opacity,
+            looped,
+            muted,
+            keybind,
+        } => {
+            table.insert("type".to_string(), toml::Value::String("video".to_string()));
+            table.insert("source".to_string(), toml::Value::String(source.clone()));
+            table.insert("size".to_string(), size_to_toml(*width, *height));
+            table.insert("opacity".to_string(), toml::Value::Float((*opacity).into()));
+            if *looped {
+                table.insert("loop".to_string(), toml::Value::Boolean(true));
+            }
+            if *muted {
+                table.insert("mute".to_string(), toml::Value::Boolean(true));
+            }
+            keybind
+                .as_ref()
+                .map(|k| keybinds_to_toml(&[("play", &k.play), ("pause", &k.pause)]))
+        }
+        ComponentKind::ImageToggle {
+            sources,
+            default_index,
+            width,
+            height,
+            opacity,
+            interval_ms,
+            keybind,
+        } => {
+            table.insert("type".to_string(), toml::Value::String("image-toggle".to_string()));
+            table.insert(
+                "sources".to_string(),
+                toml::Value::Array(sources.iter().cloned().map(toml::Value::String).collect()),
+            );
+            table.insert("default".to_string(), toml::Value::Integer(*default_index as i64));
+            table.insert("size".to_string(), size_to_toml(*width, *height));
+            table.insert("opacity".to_string(), toml::Value::Float((*opacity).into()));
+            if let Some(interval_ms) = interval_ms {
+                table.insert("interval_ms".to_string(), toml::Value::Integer(*interval_ms as i64));
+            }
+            keybind.as_ref().map(|k| {
+                let mut binds = keybinds_to_toml(&[
+                    ("forward", &k.forward),
+                    ("backward", &k.backward),
+                    ("start", &k.start),
+                    ("stop", &k.stop),
+                ]);
+                for (index, spec) in &k.set {
+                    binds.insert(format!("set_{index}"), keybind_to_toml(spec));
+                }
+                binds
+            })
+        }
+        ComponentKind::Period {
+            labels,
+            default_index,
+            wrap,
+            keybind,
+            on_advance,
+        } => {
+            table.insert("type".to_string(), toml::Value::String("period".to_string()));
+            table.insert(
+                "labels".to_string(),
+                toml::Value::Array(labels.iter().cloned().map(toml::Value::String).collect()),
+            );
+            table.insert("default".to_string(), toml::Value::Integer(*default_index as i64 + 1));
+            if *wrap {
+                table.insert("wrap".to_string(), toml::Value::Boolean(true));
+            }
+            if let Some(advance) = on_advance {
+                let mut advance_table = toml::Table::new();
+                advance_table.insert("reset_timer".to_string(), toml::Value::String(advance.reset_timer.clone()));
+                if let Some(durations_ms) = &advance.durations_ms {
+                    advance_table.insert(
+                        "durations".to_string(),
+                        toml::Value::Array(
+                            durations_ms
+                                .iter()
+                                .map(|ms| toml::Value::String(format_timer_default(*ms)))
+                                .collect(),
+                        ),
+                    );
+                }
+                table.insert("on_advance".to_string(), toml::Value::Table(advance_table));
+            }
+            keybind
+                .as_ref()
+                .map(|k| keybinds_to_toml(&[("forward", &k.forward), ("backward", &k.backward)]))
+        }
+        ComponentKind::Fouls {
+            default,
+            bonus,
+            double_bonus,
+            reset_period,
+            keybind,
+        } => {
+            table.insert("type".to_string(), toml::Value::String("fouls".to_string()));
+            table.insert("default".to_string(), toml::Value::Integer((*default).into()));
+            if let Some(bonus) = bonus {
+                table.insert("bonus".to_string(), toml::Value::Integer((*bonus).into()));
+            }
+            if let Some(double_bonus) = double_bonus {
+                table.insert("double_bonus".to_string(), toml::Value::Integer((*double_bonus).into()));
+            }
+            if let Some(reset_period) = reset_period {
+                table.insert("reset_period".to_string(), toml::Value::String(reset_period.clone()));
+            }
+            keybind.as_ref().map(|k| {
+                keybinds_to_toml(&[("increase", &k.increase), ("decrease", &k.decrease), ("reset", &k.reset)])
+            })
+        }
+        ComponentKind::Possession {
+            display,
+            default_index,
+            keybind,
+        } => {
+            table.insert("type".to_string(), toml::Value::String("possession".to_string()));
+            match display {
+                PossessionDisplay::Labels(labels) => {
+                    table.insert(
+                        "labels".to_string(),
+                        toml::Value::Array(labels.iter().cloned().map(toml::Value::String).collect()),
+                    );
+                }
+                PossessionDisplay::Images {
+                    sources,
+                    width,
+                    height,
+                    opacity,
+                } => {
+                    table.insert(
+                        "sources".to_string(),
+                        toml::Value::Array(sources.iter().cloned().map(toml::Value::String).collect()),
+                    );
+                    table.insert("size".to_string(), size_to_toml(*width, *height));
+                    table.insert("opacity".to_string(), toml::Value::Float((*opacity).into()));
+                }
+            }
+            table.insert("default".to_string(), toml::Value::Integer(*default_index as i64 + 1));
+            keybind.as_ref().map(|k| keybinds_to_toml(&[("toggle", &k.toggle)]))
+        }
+        ComponentKind::PossessionTime { clock, keybind } => {
+            table.insert("type".to_string(), toml::Value::String("possession-time".to_string()));
+            table.insert("clock".to_string(), toml::Value::String(clock.clone()));
+            keybind.as_ref().map(|k| {
+                keybinds_to_toml(&[("home", &k.home), ("away", &k.away), ("reset", &k.reset)])
+            })
+        }
+        ComponentKind::Ticker { messages, speed, width } => {
+            table.insert("type".to_string(), toml::Value::String("ticker".to_string()));
+            table.insert(
+                "messages".to_string(),
+                toml::Value::Array(messages.iter().cloned().map(toml::Value::String).collect()),
+            );
+            table.insert("speed".to_string(), toml::Value::Integer((*speed).into()));
+            table.insert("width".to_string(), toml::Value::Integer((*width).into()));
+            None
+        }
+        ComponentKind::Derived { expr } => {
+            table.insert("type".to_string(), toml::Value::String("derived".to_string()));
+            table.insert("expr".to_string(), toml::Value::String(expr.source().to_string()));
+            None
+        }
+    };
+
+    table.insert("position".to_string(), toml::Value::Table(position));
+    if let Some(alignment) = component.alignment {
+        table.insert("alignment".to_string(), toml::Value::String(alignment.as_str().to_string()));
+    }
+    if let Some(layer) = component.layer {
+        table.insert("layer".to_string(), toml::Value::Integer(layer.into()));
+    }
+    if let Some(group) = &component.group {
+        table.insert("group".to_string(), toml::Value::String(group.clone()));
+    }
+    if !component.visible {
+        table.insert("visible".to_string(), toml::Value::Boolean(false));
+    }
+    if let Some(rate_limit_ms) = component.rate_limit_ms {
+        table.insert("rate_limit_ms".to_string(), toml::Value::Integer(rate_limit_ms as i64));
+    }
+    if component.persistent {
+        table.insert("persistent".to_string(), toml::Value::Boolean(true));
+    }
+    if component.monospace_digits {
+        table.insert("monospace_digits".to_string(), toml::Value::Boolean(true));
+    }
+    if let Some(fit) = &component.fit {
+        let mut fit_table = toml::Table::new();
+        for (key, value) in [("max_width", fit.max_width), ("max_height", fit.max_height)] {
+            if let Some(value) = value {
+                fit_table.insert(key.to_string(), toml::Value::Integer(value.into()));
+            }
+        }
+        table.insert("fit".to_string(), toml::Value::Table(fit_table));
+    }
+    table.insert("font".to_string(), font_to_toml(&component.font));
+    if component.font.opacity < 1.0 {
+        table.insert("opacity".to_string(), toml::Value::Float(component.font.opacity.into()));
+    }
+    let mut keybinds = keybinds.unwrap_or_default();
+    keybinds.extend(keybinds_to_toml(&[(VISIBILITY_ACTION, &component.visibility_keybind)]));
+    if !keybinds.is_empty() {
+        table.insert("keybind".to_string(), toml::Value::Table(keybinds));
+    }
+    if !component.sounds.is_empty() {
+        let sounds = component
+            .sounds
+            .iter()
+            .map(|(action, path)| (action.clone(), toml::Value::String(path.clone())))
+            .collect();
+        table.insert("sound".to_string(), toml::Value::Table(sounds));
+    }
+    if let Some(confirm) = &component.confirm {
+        let mut confirm_table = toml::Table::new();
+        confirm_table.insert(
+            "actions".to_string(),
+            toml::Value::Array(confirm.actions.iter().cloned().map(toml::Value::String).collect()),
+        );
+        confirm_table.insert("timeout_ms".to_string(), toml::Value::Integer(confirm.timeout_ms as i64));
+        let on_timeout = if confirm.commit_on_timeout { "commit" } else { "cancel" };
+        confirm_table.insert("on_timeout".to_string(), toml::Value::String(on_timeout.to_string()));
+        table.insert("confirm".to_string(), toml::Value::Table(confirm_table));
+    }
+    table
+}
+
+fn size_to_toml(width: i32, height: i32) -> toml::Value {
+    let mut size = toml::Table::new();
+    size.insert("width".to_string(), toml::Value::Integer(width.into()));
+    size.insert("height".to_string(), toml::Value::Integer(height.into()));
+    toml::Value::Table(size)
+}
+
+fn font_to_toml(font: &Font) -> toml::Value {
+    let mut table = toml::Table::new();
+    let family = match font.families.as_slice() {
+        [family] => toml::Value::String(family.clone()),
+        families => toml::Value::Array(families.iter().cloned().map(toml::Value::String).collect()),
+    };
+    table.insert("family".to_string(), family);
+    table.insert("size".to_string(), toml::Value::Integer(font.size.into()));
+    table.insert("color".to_string(), toml::Value::String(font.color.clone()));
+    if font.weight != DEFAULT_FONT_WEIGHT {
+        table.insert("weight".to_string(), toml::Value::Integer(font.weight.into()));
+    }
+    if font.italic {
+        table.insert("italic".to_string(), toml::Value::Boolean(true));
+    }
+    if font.letter_spacing != 0 {
+        table.insert("letter_spacing".to_string(), toml::Value::Integer(font.letter_spacing.into()));
+    }
+    if let Some(outline) = &font.outline {
+        let mut outline_table = toml::Table::new();
+        outline_table.insert("color".to_string(), toml::Value::String(outline.color.clone()));
+        outline_table.insert("width".to_string(), toml::Value::Integer(outline.width.into()));
+        table.insert("outline".to_string(), toml::Value::Table(outline_table));
+    }
+    if let Some(shadow) = &font.shadow {
+        let mut shadow_table = toml::Table::new();
+        shadow_table.insert("color".to_string(), toml::Value::String(shadow.color.clone()));
+        shadow_table.insert("x".to_string(), toml::Value::Integer(shadow.x.into()));
+        shadow_table.insert("y".to_string(), toml::Value::Integer(shadow.y.into()));
+        shadow_table.insert("blur".to_string(), toml::Value::Integer(shadow.blur.into()));
+        table.insert("shadow".to_string(), toml::Value::Table(shadow_table));
+    }
+    toml::Value::Table(table)
+}
+
+fn keybinds_to_toml(binds: &[(&str, &Option<KeybindSpec>)]) -> toml::Table {
+    let mut table = toml::Table::new();
+    for (name, spec) in binds {
+        let Some(spec) = spec else {
+            continue;
+        };
+        table.insert(name.to_string(), keybind_to_toml(spec));
+    }
+    table
+}
+
+fn playlist_to_toml(playlist: &PlaylistSettings) -> toml::Value {
+    let mut table = toml::Table::new();
+    table.insert("folder".to_string(), toml::Value::String(playlist.folder.clone()));
+    table.insert("shuffle".to_string(), toml::Value::Boolean(playlist.shuffle));
+    table.insert("crossfade_ms".to_string(), toml::Value::Integer(playlist.crossfade_ms as i64));
+    table.insert("volume".to_string(), toml::Value::Float(playlist.volume.into()));
+    if let Some(clock) = &playlist.clock {
+        table.insert("clock".to_string(), toml::Value::String(clock.clone()));
+        let on_clock_start = match playlist.on_clock_start {
+            PlaylistClockAction::Duck => "duck",
+            PlaylistClockAction::Stop => "stop",
+        };
+        table.insert("on_clock_start".to_string(), toml::Value::String(on_clock_start.to_string()));
+    }
+    table.insert("duck_volume".to_string(), toml::Value::Float(playlist.duck_volume.into()));
+    if let Some(now_playing) = &playlist.now_playing {
+        table.insert("now_playing".to_string(), toml::Value::String(now_playing.clone()));
+    }
+    if let Some(k) = &playlist.keybind {
+        let keybind = keybinds_to_toml(&[("play", &k.play), ("pause", &k.pause), ("next", &k.next)]);
+        table.insert("keybind".to_string(), toml::Value::Table(keybind));
+    }
+    toml::Value::Table(table)
+}
+
+fn announcement_to_toml(announcement: &AnnouncementSettings) -> toml::Value {
+    let mut table = toml::Table::new();
+    table.insert("target".to_string(), toml::Value::String(announcement.target.clone()));
+    table.insert(
+        "duration_ms".to_string(),
+        toml::Value::Integer(announcement.default_duration_ms as i64),
+    );
+    if !announcement.messages.is_empty() {
+        let mut messages = toml::Table::new();
+        for message in &announcement.messages {
+            let mut message_table = toml::Table::new();
+            message_table.insert("text".to_string(), toml::Value::String(message.text.clone()));
+            if let Some(sound) = &message.sound {
+                message_table.insert("sound".to_string(), toml::Value::String(sound.clone()));
+            }
+            if message.priority != 0 {
+                message_table.insert("priority".to_string(), toml::Value::Integer(message.priority.into()));
+            }
+            if let Some(duration_ms) = message.duration_ms {
+                message_table.insert("duration_ms".to_string(), toml::Value::Integer(duration_ms as i64));
+            }
+            if let Some(interval_ms) = message.interval_ms {
+                message_table.insert(
+                    "interval".to_string(),
+                    toml::Value::String(format_timer_default(interval_ms)),
+                );
+            }
+            if let Some(keybind) = &message.keybind {
+                message_table.insert("keybind".to_string(), keybind_to_toml(keybind));
+            }
+            messages.insert(message.name.clone(), toml::Value::Table(message_table));
+        }
+        table.insert("message".to_string(), toml::Value::Table(messages));
+    }
+    toml::Value::Table(table)
+}
+
+fn led_matrix_to_toml(led_matrix: &LedMatrixSettings) -> toml::Value {
+    let mut table = toml::Table::new();
+    table.insert("port".to_string(), toml::Value::String(led_matrix.port.clone()));
+    table.insert("baud_rate".to_string(), toml::Value::Integer(led_matrix.baud_rate.into()));
+    table.insert("width".to_string(), toml::Value::Integer(led_matrix.width.into()));
+    table.insert("height".to_string(), toml::Value::Integer(led_matrix.height.into()));
+    let protocol = match led_matrix.protocol {
+        LedMatrixProtocol::Glediator => "glediator",
+        LedMatrixProtocol::Adalight => "adalight",
+    };
+    table.insert("protocol".to_string(), toml::Value::String(protocol.to_string()));
+    table.insert("brightness".to_string(), toml::Value::Float(led_matrix.brightness.into()));
+    if !led_matrix.items.is_empty() {
+        let mut items = toml::Table::new();
+        for item in &led_matrix.items {
+            let mut item_table = toml::Table::new();
+            item_table.insert("x".to_string(), toml::Value::Integer(item.x.into()));
+            item_table.insert("y".to_string(), toml::Value::Integer(item.y.into()));
+            if let Some(color) = &item.color {
+                item_table.insert("color".to_string(), toml::Value::String(color.clone()));
+            }
+            if item.scale != 1 {
+                item_table.insert("scale".to_string(), toml::Value::Integer(item.scale.into()));
+            }
+            items.insert(item.component.clone(), toml::Value::Table(item_table));
+        }
+        table.insert("component".to_string(), toml::Value::Table(items));
+    }
+    toml::Value::Table(table)
+}
+
+fn keybind_to_toml(spec: &KeybindSpec) -> toml::Value {
+    let mut bind = toml::Table::new();
+    bind.insert("key".to_string(), toml::Value::String(spec.key.clone()));
+    for (modifier, enabled) in [("ctrl", spec.ctrl), ("alt", spec.alt), ("shift", spec.shift), ("win", spec.win)] {
+        if enabled {
+            bind.insert(modifier.to_string(), toml::Value::Boolean(true));
+        }
+    }
+    if let Some(cooldown_ms) = spec.cooldown_ms {
+        bind.insert("cooldown_ms".to_string(), toml::Value::Integer(cooldown_ms as i64));
+    }
+    if let Some(repeat_ms) = spec.repeat_ms {
+        bind.insert("repeat_ms".to_string(), toml::Value::Integer(repeat_ms as i64));
+    }
+    if let Some(repeat_delay_ms) = spec.repeat_delay_ms {
+        bind.insert("repeat_delay_ms".to_string(), toml::Value::Integer(repeat_delay_ms as i64));
+    }
+    toml::Value::Table(bind)
+}
+
+fn parse_component_type(id: &str, raw_type: &toml::Value) -> Result<(String, Option<String>), String> {
+    if let Some(component_type) = raw_type.as_str() {
+        return Ok((component_type.to_string(), None));
+    }
+
+    let table = raw_type
+        .as_table()
+        .ok_or_else(|| format!("'{id}' type must be a string or table"))?;
+
+    let component_type = table
+        .get("name")
+        .or_else(|| table.get("kind"))
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| format!("'{id}' type table requires 'name' or 'kind' as a string"))?;
+
+    let rounding = table
+        .get("rounding")
+        .and_then(|v| v.as_str())
+        .map(|v| v.to_string());
+
+    Ok((component_type.to_string(), rounding))
+}
+
+fn parse_timer_rounding(
+    id: &str,
+    type_rounding: Option<&str>,
+    component_rounding: Option<&str>,
+) -> Result<TimerRounding, String> {
+    let rounding = type_rounding.or(component_rounding).unwrap_or("standard");
+    match rounding.to_ascii_lowercase().as_str() {
+        "standard" => Ok(TimerRounding::Standard),
+        "basketball" => Ok(TimerRounding::Basketball),
+        other => Err(format!(
+            "'{id}' has unsupported timer rounding '{other}' (expected 'standard' or 'basketball')"
+        )),
+    }
+}
+
+fn parse_alignment(id: &str, raw_alignment: Option<&str>) -> Result<Option<ComponentAlignment>, String> {
+    let Some(alignment) = raw_alignment else {
+        return Ok(None);
+    };
+
+    match alignment.to_ascii_lowercase().as_str() {
+        "center" => Ok(Some(ComponentAlignment::Center)),
+        "left" => Ok(Some(ComponentAlignment::Left)),
+        "right" => Ok(Some(ComponentAlignment::Right)),
+        "top" => Ok(Some(ComponentAlignment::Top)),
+        "bottom" => Ok(Some(ComponentAlignment::Bottom)),
+        "baseline" => Ok(Some(ComponentAlignment::Baseline)),
+        other => Err(format!(
+            "'{id}' has unsupported alignment '{other}' (expected 'center', 'left', 'right', 'top', 'bottom', or 'baseline')"
+        )),
+    }
+}
+
+fn parse_global_settings(raw_global: Option<&toml::Value>, base_dir: &Path) -> Result<GlobalSettings, String> {
+    let fallback_font = Font {
+        family: "Segoe UI".to_string(),
+        families: vec!["Segoe UI".to_string()],
+        size: 28,
+        color: "#FFFFFF".to_string(),
+        opacity: 1.0,
+        weight: DEFAULT_FONT_WEIGHT,
+        italic: false,
+        letter_spacing: 0,
+        outline: None,
+        shadow: None,
+    };
+    let fallback_bg = "#000000".to_string();
+
+    let parsed = match raw_global {
+        Some(value) => value
+            .clone()
+            .try_into::<RawGlobal>()
+            .map_err(|e| format!("Invalid [global] section: {e}"))?,
+        None => RawGlobal {
+            canvas: None,
+            rotate: None,
+            background_color: None,
+            chroma_key: None,
+            timer_display_offset_ms: None,
+            freeze_on_error: None,
+            import_images: None,
+            keyboard_mode: None,
+            font: None,
+            keybind: None,
+            player_action: None,
+            relay: None,
+            announcement: None,
+            aux_timer: None,
+            horn_pattern: None,
+            audio: None,
+            playlist: None,
+            remote: None,
+            osc: None,
+            serial_input: None,
+            obs: None,
+            sync: None,
+            discovery: None,
+            led_matrix: None,
+            theme: None,
+            lock: None,
+            webhook: None,
+            summary: None,
+            fixtures: None,
+            updates: None,
+            crash_report: None,
+            splash: None,
+            notes: None,
+            idle: None,
+            script: None,
+        },
+    };
+
+    let font = resolve_font("global", &fallback_font, parsed.font.as_ref())?;
+    validate_font("global", &font)?;
+
+    let canvas = parsed.canvas.unwrap_or(CanvasSize {
+        width: DEFAULT_CANVAS_WIDTH,
+        height: DEFAULT_CANVAS_HEIGHT,
+    });
+    for (name, side) in [("width", canvas.width), ("height", canvas.height)] {
+        if !(1..=MAX_CANVAS_SIDE).contains(&side) {
+            return Err(format!("'global.canvas' {name} must be between 1 and {MAX_CANVAS_SIDE}"));
+        }
+    }
+    let rotate = parsed.rotate.unwrap_or(0);
+    if !ROTATIONS.contains(&rotate) {
+        return Err("'global' rotate must be 0, 90, 180, or 270".to_string());
+    }
+
+    let timer_display_offset_ms = parsed.timer_display_offset_ms.unwrap_or(0);
+    validate_timer_display_offset(timer_display_offset_ms)?;
+    let keyboard_mode = match parsed.keyboard_mode.as_deref().unwrap_or("auto").to_ascii_lowercase().as_str() {
+        "auto" => KeyboardMode::Auto,
+        "global" => KeyboardMode::Global,
+        "focused" => KeyboardMode::Focused,
+        other => {
+            return Err(format!(
+                "'global' keyboard_mode '{other}' is not supported (expected 'auto', 'global', or 'focused')"
+            ))
+        }
+    };
+
+    if parsed.background_color.is_some() && parsed.chroma_key.is_some() {
+        return Err("'global' chroma_key replaces background_color; set only one of them".to_string());
+    }
+    let background_color = match parsed.background_color {
+        Some(color) if color.trim().eq_ignore_ascii_case(TRANSPARENT_BACKGROUND) => TRANSPARENT_BACKGROUND.to_string(),
+        Some(color) => {
+            validate_color("global.background_color", &color).map_err(|_| {
+                format!("'global.background_color' must be #RRGGBB, #RRGGBBAA, or \"{TRANSPARENT_BACKGROUND}\"")
+            })?;
+            color
+        }
+        None => fallback_bg,
+    };
+    let chroma_key = parsed.chroma_key;
+    if let Some(chroma_key) = &chroma_key {
+        validate_color("global.chroma_key", chroma_key)?;
+        // A see-through key color would blend with whatever is behind the window.
+        if chroma_key.trim().len() != 7 {
+            return Err("'global.chroma_key' must be an opaque #RRGGBB color".to_string());
+        }
+    }
+
+    let keybind = if let Some(binds) = parsed.keybind.as_ref() {
+        Some(GlobalKeybind {
+            fullscreen: parse_optional_keybind("global", binds, "fullscreen")?,
+            player_select: parse_optional_keybind("global", binds, "player_select")?,
+            theme: parse_optional_keybind("global", binds, "theme")?,
+            lock: parse_optional_keybind("global", binds, "lock")?,
+            ready: parse_optional_keybind("global", binds, "ready")?,
+        })
+    } else {
+        None
+    };
+
+    let mut player_actions = Vec::new();
+    for (name, raw_action) in parsed.player_action.iter().flatten() {
+        let id = format!("global.player_action.{name}");
+        validate_keybind_spec(&id, "key", &raw_action.keybind)?;
+        if is_gamepad_shortcut(&raw_action.keybind.to_shortcut()) {
+            return Err(format!("'{id}' keybind must be a keyboard key"));
+        }
+        let apply = match raw_action.apply.as_deref() {
+            Some(apply) => {
+                let (component, action) = apply
+                    .rsplit_once('.')
+                    .ok_or_else(|| format!("'{id}' apply must be written as \"<component>.<action>\""))?;
+                Some(ComponentActionRef {
+                    component: component.to_string(),
+                    action: action.to_string(),
+                })
+            }
+            None => None,
+        };
+        player_actions.push(PlayerAction {
+            name: name.clone(),
+            keybind: raw_action.keybind.clone(),
+            apply,
+        });
+    }
+
+    let mut relays = Vec::new();
+    for (name, raw_relay) in parsed.relay.iter().flatten() {
+        let id = format!("global.relay.{name}");
+        if raw_relay.port.trim().is_empty() {
+            return Err(format!("'{id}' port cannot be empty"));
+        }
+        relays.push(RelayOutput {
+            name: name.clone(),
+            port: raw_relay.port.clone(),
+            baud_rate: raw_relay.baud_rate.unwrap_or(DEFAULT_RELAY_BAUD_RATE),
+            on: parse_hex_bytes(&id, "on", &raw_relay.on)?,
+            off: parse_hex_bytes(&id, "off", &raw_relay.off)?,
+        });
+    }
+
+    let mut horn_patterns = Vec::new();
+    for (name, raw_pattern) in parsed.horn_pattern.iter().flatten() {
+        horn_patterns.push(parse_horn_pattern(name, raw_pattern, base_dir)?);
+    }
+
+    let audio = match parsed.audio.as_ref() {
+        Some(raw_audio) => Some(parse_audio(raw_audio)?),
+        None => None,
+    };
+
+    let playlist = match parsed.playlist.as_ref() {
+        Some(raw_playlist) => Some(parse_playlist(raw_playlist, base_dir)?),
+        None => None,
+    };
+
+    let announcement = match parsed.announcement.as_ref() {
+        Some(raw_announcement) => Some(parse_announcement(raw_announcement, &horn_patterns, base_dir)?),
+        None => None,
+    };
+
+    let mut aux_timers = Vec::new();
+    for (name, raw_aux_timer) in parsed.aux_timer.iter().flatten() {
+        aux_timers.push(parse_aux_timer(name, raw_aux_timer, &horn_patterns, base_dir)?);
+    }
+
+    let led_matrix = match parsed.led_matrix.as_ref() {
+        Some(raw_led_matrix) => Some(parse_led_matrix(raw_led_matrix)?),
+        None => None,
+    };
+
+    let mut themes = Vec::new();
+    for (name, raw_theme) in parsed.theme.iter().flatten() {
+        let id = format!("global.theme.{name}");
+        let mut colors = BTreeMap::new();
+        for (from, to) in &raw_theme.colors {
+            validate_color(&format!("{id}.colors"), from)?;
+            validate_color(&format!("{id}.colors.\"{from}\""), to)?;
+            colors.insert(from.trim().to_ascii_uppercase(), to.trim().to_string());
+        }
+        themes.push(Theme {
+            name: name.clone(),
+            colors,
+        });
+    }
+    if keybind.as_ref().is_some_and(|keybind| keybind.theme.is_some()) && themes.is_empty() {
+        return Err("'global' keybind.theme requires at least one [global.theme.<name>]".to_string());
+    }
+
+    let lock = match parsed.lock.as_ref() {
+        Some(raw_lock) => {
+            let pin = raw_lock.pin.trim();
+            let valid_length = (MIN_LOCK_PIN_LENGTH..=MAX_LOCK_PIN_LENGTH).contains(&pin.len());
+            if !valid_length || !pin.chars().all(|c| c.is_ascii_digit()) {
+                return Err(format!(
+                    "'global.lock' pin must be {MIN_LOCK_PIN_LENGTH} to {MAX_LOCK_PIN_LENGTH} digits"
+                ));
+            }
+            Some(LockSettings { pin: pin.to_string() })
+        }
+        None => None,
+    };
+    if keybind.as_ref().is_some_and(|keybind| keybind.lock.is_some()) && lock.is_none() {
+        return Err("'global' keybind.lock requires [global.lock] with a pin".to_string());
+    }
+
+    let mut webhooks = Vec::new();
+    for (name, raw_webhook) in parsed.webhook.iter().flatten() {
+        webhooks.push(parse_webhook(name, raw_webhook)?);
+    }
+
+    let summary = match parsed.summary.as_ref() {
+        Some(raw_summary) => Some(parse_summary(raw_summary)?),
+        None => None,
+    };
+
+    let fixtures = match parsed.fixtures.as_ref() {
+        Some(raw_fixtures) => Some(parse_fixtures(raw_fixtures, base_dir)?),
+        None => None,
+    };
+
+    let remote = match parsed.remote.as_ref() {
+        Some(raw_remote) => Some(parse_remote(raw_remote)?),
+        None => None,
+    };
+
+    let osc = match parsed.osc.as_ref() {
+        Some(raw_osc) => Some(parse_osc(raw_osc)?),
+        None => None,
+    };
+
+    let serial_input = match parsed.serial_input.as_ref() {
+        Some(raw_serial_input) => Some(parse_serial_input(raw_serial_input)?),
+        None => None,
+    };
+
+    let obs = match parsed.obs.as_ref() {
+        Some(raw_obs) => Some(parse_obs(raw_obs)?),
+        None => None,
+    };
+    let sync = match parsed.sync.as_ref() {
+        Some(raw_sync) => Some(parse_sync(raw_sync)?),
+        None => None,
+    };
+    let discovery = match parsed.discovery.as_ref() {
+        Some(raw_discovery) => Some(parse_discovery(raw_discovery)?),
+        None => None,
+    };
+
+    let updates = match parsed.updates.as_ref() {
+        Some(raw_updates) => Some(parse_updates(raw_updates)?),
+        None => None,
+    };
+
+    let crash_report = match parsed.crash_report.as_ref() {
+        Some(raw_crash_report) => Some(parse_crash_report(raw_crash_report)?),
+        None => None,
+    };
+
+    let splash = match parsed.splash.as_ref() {
+        Some(raw_splash) => Some(parse_splash(raw_splash, base_dir)?),
+        None => None,
+    };
+    if keybind.as_ref().is_some_and(|keybind| keybind.ready.is_some()) && splash.is_none() {
+        return Err("'global' keybind.ready requires [global.splash]".to_string());
+    }
+
+    let notes = match parsed.notes.as_ref() {
+        Some(raw_notes) => Some(parse_notes(raw_notes)?),
+        None => None,
+    };
+    let idle = match parsed.idle.as_ref() {
+        Some(raw_idle) => Some(parse_idle(raw_idle)?),
+        None => None,
+    };
+    let script = match parsed.script.as_ref() {
+        Some(raw_script) => Some(parse_script(raw_script, base_dir)?),
+        None => None,
+    };
+
+    Ok(GlobalSettings {
+        canvas,
+        rotate,
+        background_color,
+        chroma_key,
+        timer_display_offset_ms,
+        freeze_on_error: parsed.freeze_on_error.unwrap_or(false),
+        import_images: parsed.import_images.unwrap_or(false),
+        keyboard_mode,
+        font,
+        keybind,
+        player_actions,
+        relays,
+        announcement,
+        aux_timers,
+        horn_patterns,
+        audio,
+        playlist,
+        remote,
+        osc,
+        serial_input,
+        obs,
+        sync,
+        discovery,
+        led_matrix,
+        themes,
+        lock,
+        webhooks,
+        summary,
+        fixtures,
+        updates,
+        crash_report,
+        splash,
+        notes,
+        idle,
+        script,
+    })
+}
+
+pub fn validate_timer_display_offset(offset_ms: i64) -> Result<(), String> {
+    if offset_ms.abs() > MAX_TIMER_DISPLAY_OFFSET_MS {
+        return Err(format!(
+            "Timer display offset must be between -{MAX_TIMER_DISPLAY_OFFSET_MS} and {MAX_TIMER_DISPLAY_OFFSET_MS} ms"
+        ));
+    }
+    Ok(())
+}
+
+fn parse_webhook(name: &str, raw: &RawWebhook) -> Result<WebhookSettings, String> {
+    let id = format!("global.webhook.{name}");
+    // The name becomes the queue's file name, so it cannot reach outside the queue folder.
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err(format!("'{id}' name may only contain letters, digits, '_' and '-'"));
+    }
+    let url = raw.url.trim();
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err(format!("'{id}' url must start with http:// or https://"));
+    }
+    let format = match raw.format.as_deref().unwrap_or("json").to_ascii_lowercase().as_str() {
+        "json" => WebhookFormat::Json,
+        "discord" => WebhookFormat::Discord,
+        other => {
+            return Err(format!(
+                "'{id}' has unsupported format '{other}' (expected 'json' or 'discord')"
+            ))
+        }
+    };
+    let batch_ms = raw.batch_ms.unwrap_or(DEFAULT_WEBHOOK_BATCH_MS);
+    if batch_ms == 0 {
+        return Err(format!("'{id}' batch_ms must be > 0"));
+    }
+    Ok(WebhookSettings {
+        name: name.to_string(),
+        url: url.to_string(),
+        format,
+        batch_ms,
+    })
+}
+
+fn parse_remote(raw: &RawRemote) -> Result<RemoteSettings, String> {
+    if raw.port == 0 {
+        return Err("'global.remote' port must be between 1 and 65535".to_string());
+    }
+    let bind = raw.bind.as_deref().map(str::trim).unwrap_or(DEFAULT_REMOTE_BIND);
+    if bind.parse::<std::net::IpAddr>().is_err() {
+        return Err(format!("'global.remote' bind '{bind}' is not an IP address"));
+    }
+    if raw.token.as_deref().is_some_and(|token| token.trim().is_empty()) {
+        return Err("'global.remote' token cannot be empty".to_string());
+    }
+    let mut tokens: Vec<RemoteToken> = Vec::new();
+    for (name, entry) in &raw.tokens {
+        let id = format!("global.remote.tokens.{name}");
+        if entry.token.trim().is_empty() {
+            return Err(format!("'{id}' token cannot be empty"));
+        }
+        if raw.token.as_deref() == Some(entry.token.as_str()) || tokens.iter().any(|other| other.token == entry.token) {
+            return Err(format!("'{id}' reuses a token that is already listed"));
+        }
+        let role = parse_remote_role(&id, &entry.role)?;
+        tokens.push(RemoteToken {
+            name: name.clone(),
+            token: entry.token.clone(),
+            role,
+        });
+    }
+    let settings = RemoteSettings {
+        bind: bind.to_string(),
+        port: raw.port,
+        token: raw.token.clone(),
+        tokens,
+    };
+    // Anyone on the venue network could otherwise run the board.
+    let loopback = bind.parse::<std::net::IpAddr>().is_ok_and(|address| address.is_loopback());
+    if !loopback && !settings.requires_token() {
+        return Err(format!(
+            "'global.remote' listens on {bind}, which other machines can reach, so it needs a token or tokens"
+        ));
+    }
+    Ok(settings)
+}
+
+fn parse_remote_role(id: &str, role: &str) -> Result<RemoteRole, String> {
+    match role.trim().to_ascii_lowercase().as_str() {
+        "read" => Ok(RemoteRole::Read),
+        "timer" => Ok(RemoteRole::Timer),
+        "full" => Ok(RemoteRole::Full),
+        other => Err(format!(
+            "'{id}' has unsupported role '{other}' (expected 'read', 'timer', or 'full')"
+        )),
+    }
+}
+
+fn parse_osc(raw: &RawOsc) -> Result<OscSettings, String> {
+    if raw.port == Some(0) {
+        return Err("'global.osc' port must be between 1 and 65535".to_string());
+    }
+    let bind = raw.bind.as_deref().map(str::trim).unwrap_or(DEFAULT_OSC_BIND);
+    if bind.parse::<std::net::IpAddr>().is_err() {
+        return Err(format!("'global.osc' bind '{bind}' is not an IP address"));
+    }
+    let prefix = raw.prefix.as_deref().map(str::trim).unwrap_or(DEFAULT_OSC_PREFIX);
+    let prefix = prefix.trim_end_matches('/');
+    if !prefix.starts_with('/') || prefix.contains(['#', '*', '?', ' ', ',']) {
+        return Err(format!(
+            "'global.osc' prefix '{prefix}' must start with '/' and cannot contain spaces, ',', '#', '*', or '?'"
+        ));
+    }
+    let send_to = raw
+        .send_to
+        .iter()
+        .flatten()
+        .map(|target| {
+            target
+                .trim()
+                .parse::<SocketAddr>()
+                .map_err(|_| format!("'global.osc' send_to '{target}' must be an IP address and port like 10.0.0.5:8000"))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    if send_to.len() > MAX_OSC_TARGETS {
+        return Err(format!("'global.osc' send_to can list at most {MAX_OSC_TARGETS} targets"));
+    }
+    if raw.port.is_none() && send_to.is_empty() {
+        return Err("'global.osc' needs a port to listen on, send_to targets, or both".to_string());
+    }
+    let mut allow: Vec<OscHost> = Vec::new();
+    for (address, role) in &raw.allow {
+        let id = format!("global.osc.allow.{address}");
+        let address = address
+            .trim()
+            .parse::<IpAddr>()
+            .map_err(|_| format!("'{id}' must be keyed by an IP address like 10.0.0.5"))?
+            .to_canonical();
+        if allow.iter().any(|host| host.address == address) {
+            return Err(format!("'{id}' lists {address} a second time"));
+        }
+        allow.push(OscHost {
+            address,
+            role: parse_remote_role(&id, role)?,
+        });
+    }
+    allow.sort_by_key(|host| host.address);
+    // One packet from anyone on the venue network could otherwise run the board.
+    let loopback = bind.parse::<IpAddr>().is_ok_and(|address| address.is_loopback());
+    if raw.port.is_some() && !loopback && allow.is_empty() {
+        return Err(format!(
+            "'global.osc' listens on {bind}, which other machines can reach, so it needs allow to list the hosts that may send"
+        ));
+    }
+    Ok(OscSettings {
+        bind: bind.to_string(),
+        port: raw.port,
+        prefix: prefix.to_string(),
+        send_to,
+        allow,
+    })
+}
+
+fn parse_sync(raw: &RawSync) -> Result<SyncSettings, String> {
+    let id = "global.sync";
+    let role = match (raw.listen, raw.primary.as_deref().map(str::trim)) {
+        (Some(_), Some(_)) => return Err(format!("'{id}' sets either listen (primary) or primary (replica), not both")),
+        (None, None) => return Err(format!("'{id}' needs listen on the primary or primary on a replica")),
+        (Some(0), None) => return Err(format!("'{id}' listen must be a port between 1 and 65535")),
+        (Some(port), None) => {
+            let bind = raw.bind.as_deref().map(str::trim).unwrap_or(DEFAULT_SYNC_BIND);
+            if bind.parse::<std::net::IpAddr>().is_err() {
+                return Err(format!("'{id}' bind '{bind}' is not an IP address"));
+            }
+            SyncRole::Primary {
+                bind: bind.to_string(),
+                port,
+            }
+        }
+        (None, Some(primary)) => {
+            if raw.bind.is_some() {
+                return Err(format!("'{id}' bind only applies to the primary"));
+            }
+            if primary.is_empty() {
+                return Err(format!("'{id}' primary cannot be empty"));
+            }
+            // Without a port it is a name to look up over mDNS; names have no dots,
+            // so an address missing its port is still caught.
+            let valid_address = primary
+                .rsplit_once(':')
+                .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok_and(|port| port > 0));
+            if !valid_address && (primary.contains(':') || primary.contains('.')) {
+                return Err(format!("'{id}' primary '{primary}' must be host:port, such as 192.168.1.20:7400"));
+            }
+            if !valid_address && primary.len() > MAX_DISCOVERY_NAME_BYTES {
+                return Err(format!("'{id}' primary name must be at most {MAX_DISCOVERY_NAME_BYTES} bytes"));
+            }
+            SyncRole::Replica {
+                primary: primary.to_string(),
+            }
+        }
+    };
+    if raw.token.as_deref().is_some_and(|token| token.trim().is_empty()) {
+        return Err(format!("'{id}' token cannot be empty"));
+    }
+    Ok(SyncSettings {
+        role,
+        token: raw.token.clone(),
+    })
+}
+
+fn parse_discovery(raw: &RawDiscovery) -> Result<DiscoverySettings, String> {
+    let id = "global.discovery";
+    let name = match raw.name.as_deref().map(str::trim) {
+        Some("") => return Err(format!("'{id}' name cannot be empty")),
+        Some(name) if name.len() > MAX_DISCOVERY_NAME_BYTES => {
+            return Err(format!("'{id}' name must be at most {MAX_DISCOVERY_NAME_BYTES} bytes"))
+        }
+        Some(name) if name.contains('.') => return Err(format!("'{id}' name cannot contain '.'")),
+        Some(name) => Some(name.to_string()),
+        None => None,
+    };
+    Ok(DiscoverySettings {
+        name,
+        advertise: raw.advertise.unwrap_or(true),
+    })
+}
+
+fn parse_obs(raw: &RawObs) -> Result<ObsSettings, String> {
+    let url = raw.url.as_deref().map(str::trim).unwrap_or(DEFAULT_OBS_URL);
+    let host = url.strip_prefix("ws://").map(|rest| rest.split('/').next().unwrap_or(""));
+    if host.is_none_or(str::is_empty) {
+        return Err(format!("'global.obs' url '{url}' must look like ws://127.0.0.1:4455"));
+    }
+    let password = match raw.password.as_deref() {
+        Some("") => return Err("'global.obs' password cannot be empty".to_string()),
+        password => password.map(str::to_string),
+    };
+    let triggers = raw
+        .trigger
+        .iter()
+        .flatten()
+        .map(|(name, raw_trigger)| parse_obs_trigger(name, raw_trigger))
+        .collect::<Result<Vec<_>, String>>()?;
+    if triggers.is_empty() {
+        return Err("'global.obs' needs at least one [global.obs.trigger.<name>]".to_string());
+    }
+    Ok(ObsSettings {
+        url: url.to_string(),
+        password,
+        triggers,
+    })
+}
+
+fn parse_obs_trigger(name: &str, raw: &RawObsTrigger) -> Result<ObsTrigger, String> {
+    let id = format!("global.obs.trigger.{name}");
+    let non_empty = |key: &str, value: Option<&String>| match value.map(|value| value.trim()) {
+        Some("") => Err(format!("'{id}' {key} cannot be empty")),
+        value => Ok(value.map(str::to_string)),
+    };
+    let event = match (non_empty("on_expire", raw.on_expire.as_ref())?, non_empty("on_period", raw.on_period.as_ref())?) {
+        (Some(timer), None) => {
+            if raw.to.is_some() {
+                return Err(format!("'{id}' to is only supported with on_period"));
+            }
+            ObsEvent::TimerExpired { timer }
+        }
+        (None, Some(period)) => ObsEvent::PeriodAdvanced {
+            period,
+            to: non_empty("to", raw.to.as_ref())?,
+        },
+        _ => return Err(format!("'{id}' needs exactly one of on_expire or on_period")),
+    };
+    let scene = non_empty("scene", raw.scene.as_ref())?;
+    let source = match (non_empty("source", raw.source.as_ref())?, non_empty("source_scene", raw.source_scene.as_ref())?) {
+        (Some(source), Some(scene)) => Some(ObsSourceVisibility {
+            source,
+            scene,
+            visible: raw.visible.unwrap_or(true),
+        }),
+        (None, None) if raw.visible.is_none() => None,
+        (Some(_), None) => return Err(format!("'{id}' source needs source_scene, the scene it is in")),
+        _ => return Err(format!("'{id}' source_scene and visible need a source")),
+    };
+    if scene.is_none() && source.is_none() {
+        return Err(format!("'{id}' needs a scene, a source, or both"));
+    }
+    Ok(ObsTrigger {
+        name: name.to_string(),
+        event,
+        scene,
+        source,
+    })
+}
+
+fn parse_serial_input(raw: &RawSerialInput) -> Result<SerialInputSettings, String> {
+    if raw.port.trim().is_empty() {
+        return Err("'global.serial_input' port cannot be empty".to_string());
+    }
+    if raw.baud_rate == Some(0) {
+        return Err("'global.serial_input' baud_rate must be greater than 0".to_string());
+    }
+    let protocol = match raw.protocol.trim() {
+        "daktronics" => SerialProtocol::Daktronics,
+        "lines" => SerialProtocol::Lines,
+        other => {
+            return Err(format!(
+                "'global.serial_input' protocol '{other}' is not supported; use daktronics or lines"
+            ))
+        }
+    };
+    let mut fields = Vec::new();
+    for (component, raw_field) in raw.map.iter().flatten() {
+        let id = format!("global.serial_input.map.{component}");
+        let source = match (protocol, raw_field) {
+            (
+                SerialProtocol::Daktronics,
+                RawSerialField {
+                    offset: Some(offset),
+                    length: Some(length),
+                    field: None,
+                },
+            ) => {
+                if !(1..=MAX_SERIAL_FIELD_LENGTH).contains(length) {
+                    return Err(format!("'{id}' length must be between 1 and {MAX_SERIAL_FIELD_LENGTH}"));
+                }
+                SerialFieldSource::Position {
+                    offset: *offset,
+                    length: *length,
+                }
+            }
+            (SerialProtocol::Daktronics, _) => {
+                return Err(format!("'{id}' needs offset and length (and no field) for the daktronics protocol"));
+            }
+            (
+                SerialProtocol::Lines,
+                RawSerialField {
+                    offset: None,
+                    length: None,
+                    field: Some(field),
+                },
+            ) => {
+                let field = field.trim();
+                if field.is_empty() || field.contains('=') {
+                    return Err(format!("'{id}' field cannot be empty or contain '='"));
+                }
+                SerialFieldSource::Name(field.to_string())
+            }
+            (SerialProtocol::Lines, _) => {
+                return Err(format!("'{id}' needs a field name (and no offset or length) for the lines protocol"));
+            }
+        };
+        fields.push(SerialField {
+            component: component.clone(),
+            source,
+        });
+    }
+    if fields.is_empty() {
+        return Err("'global.serial_input' needs at least one map entry".to_string());
+    }
+    Ok(SerialInputSettings {
+        port: raw.port.clone(),
+        baud_rate: raw.baud_rate.unwrap_or(DEFAULT_SERIAL_INPUT_BAUD_RATE),
+        protocol,
+        fields,
+    })
+}
+
+fn parse_updates(raw: &RawUpdates) -> Result<UpdateSettings, String> {
+    let url = raw.url.as_deref().map(str::trim);
+    if url.is_some_and(|url| !(url.starts_with("http://") || url.starts_with("https://"))) {
+        return Err("'global.updates' url must start with http:// or https://".to_string());
+    }
+    Ok(UpdateSettings {
+        url: url.map(str::to_string),
+        check_on_startup: raw.check_on_startup.unwrap_or(false),
+    })
+}
+
+fn parse_crash_report(raw: &RawCrashReport) -> Result<CrashReportSettings, String> {
+    let endpoint = raw.endpoint.as_deref().map(str::trim);
+    if endpoint.is_some_and(|endpoint| !(endpoint.starts_with("http://") || endpoint.starts_with("https://"))) {
+        return Err("'global.crash_report' endpoint must start with http:// or https://".to_string());
+    }
+    Ok(CrashReportSettings {
+        endpoint: endpoint.map(str::to_string),
+    })
+}
+
+fn parse_splash(raw: &RawSplash, base_dir: &Path) -> Result<SplashSettings, String> {
+    let id = "global.splash";
+    let image = match raw.image.as_deref().map(str::trim) {
+        Some("") => return Err(format!("'{id}' image cannot be empty")),
+        Some(image) => Some(resolve_image_path(base_dir, image)),
+        None => None,
+    };
+    let group = match raw.group.as_deref().map(str::trim) {
+        Some("") => return Err(format!("'{id}' group cannot be empty")),
+        group => group.map(str::to_string),
+    };
+    if image.is_none() && group.is_none() {
+        return Err(format!("'{id}' needs an image, a group, or both"));
+    }
+    Ok(SplashSettings { image, group })
+}
+
+fn parse_idle(raw: &RawIdle) -> Result<IdleSettings, String> {
+    let id = "global.idle";
+    let after_ms = parse_timer_default(&raw.after).map_err(|e| format!("'{id}' after: {e}"))?;
+    if after_ms <= 0 {
+        return Err(format!("'{id}' after must be longer than zero"));
+    }
+    let dim = raw.dim.unwrap_or(DEFAULT_IDLE_DIM);
+    if !(0.0..=1.0).contains(&dim) {
+        return Err(format!("'{id}' dim must be between 0.0 and 1.0"));
+    }
+    let mut hide: Vec<String> = Vec::new();
+    for component in raw.hide.iter().flatten() {
+        let component = component.trim();
+        if component.is_empty() {
+            return Err(format!("'{id}' hide cannot list an empty component id"));
+        }
+        if !hide.iter().any(|hidden| hidden == component) {
+            hide.push(component.to_string());
+        }
+    }
+    Ok(IdleSettings { after_ms, dim, hide })
+}
+
+fn parse_script(raw: &RawScript, base_dir: &Path) -> Result<ScriptSettings, String> {
+    let id = "global.script";
+    let file = raw.file.trim();
+    if file.is_empty() {
+        return Err(format!("'{id}' file cannot be empty"));
+    }
+    let tick_ms = raw.tick_ms.unwrap_or(DEFAULT_SCRIPT_TICK_MS);
+    if !(MIN_SCRIPT_TICK_MS..=MAX_SCRIPT_TICK_MS).contains(&tick_ms) {
+        return Err(format!("'{id}' tick_ms must be between {MIN_SCRIPT_TICK_MS} and {MAX_SCRIPT_TICK_MS}"));
+    }
+    Ok(ScriptSettings { file: resolve_asset_path(base_dir, file), tick_ms })
+}
+
+fn parse_notes(raw: &RawNotes) -> Result<OperatorNotes, String> {
+    let id = "global.notes";
+    let title = match raw.title.as_deref().map(str::trim) {
+        Some("") => return Err(format!("'{id}' title cannot be empty")),
+        title => title.map(str::to_string),
+    };
+    // Multiline text keeps its line breaks; only the ends are trimmed.
+    let text = match raw.text.as_deref().map(str::trim) {
+        Some("") => return Err(format!("'{id}' text cannot be empty")),
+        text => text.map(str::to_string),
+    };
+    let checklist = raw.checklist.clone().unwrap_or_default();
+    if checklist.len() > MAX_NOTE_CHECKLIST_ITEMS {
+        return Err(format!("'{id}' checklist can have at most {MAX_NOTE_CHECKLIST_ITEMS} items"));
+    }
+    let checklist = checklist
+        .iter()
+        .map(|item| {
+            let item = item.trim();
+            if item.is_empty() {
+                return Err(format!("'{id}' checklist items cannot be empty"));
+            }
+            if item.contains('\n') || item.contains('\r') {
+                return Err(format!("'{id}' checklist items must be single-line strings"));
+            }
+            Ok(item.to_string())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if text.is_none() && checklist.is_empty() {
+        return Err(format!("'{id}' needs text, a checklist, or both"));
+    }
+    Ok(OperatorNotes { title, text, checklist })
+}
+
+fn parse_led_matrix(raw: &RawLedMatrix) -> Result<LedMatrixSettings, String> {
+    let id = "global.led_matrix";
+    if raw.port.trim().is_empty() {
+        return Err(format!("'{id}' port cannot be empty"));
+    }
+    for (name, side) in [("width", raw.width), ("height", raw.height)] {
+        if side == 0 || side > MAX_LED_MATRIX_SIDE {
+            return Err(format!("'{id}' {name} must be between 1 and {MAX_LED_MATRIX_SIDE}"));
+        }
+    }
+    let protocol = match raw.protocol.as_deref().unwrap_or("glediator").to_ascii_lowercase().as_str() {
+        "glediator" => LedMatrixProtocol::Glediator,
+        "adalight" => LedMatrixProtocol::Adalight,
+        other => {
+            return Err(format!(
+                "'{id}' has unsupported protocol '{other}' (expected 'glediator' or 'adalight')"
+            ))
+        }
+    };
+    let brightness = raw.brightness.unwrap_or(1.0);
+    if !(0.0..=1.0).contains(&brightness) {
+        return Err(format!("'{id}' brightness must be between 0.0 and 1.0"));
+    }
+
+    let mut items = Vec::new();
+    for (component, raw_item) in raw.component.iter().flatten() {
+        let item_id = format!("{id}.component.{component}");
+        if raw_item.x >= raw.width || raw_item.y >= raw.height {
+            return Err(format!(
+                "'{item_id}' position ({}, {}) is outside {}x{}",
+                raw_item.x, raw_item.y, raw.width, raw.height
+            ));
+        }
+        let scale = raw_item.scale.unwrap_or(1);
+        if scale == 0 || scale > MAX_LED_MATRIX_SCALE {
+            return Err(format!("'{item_id}' scale must be between 1 and {MAX_LED_MATRIX_SCALE}"));
+        }
+        if let Some(color) = &raw_item.color {
+            validate_color(&format!("{item_id}.color"), color)?;
+        }
+        items.push(LedMatrixItem {
+            component: component.clone(),
+            x: raw_item.x,
+            y: raw_item.y,
+            color: raw_item.color.clone(),
+            scale,
+        });
+    }
+
+    Ok(LedMatrixSettings {
+        port: raw.port.clone(),
+        baud_rate: raw.baud_rate.unwrap_or(DEFAULT_LED_MATRIX_BAUD_RATE),
+        width: raw.width,
+        height: raw.height,
+        protocol,
+        brightness,
+        items,
+    })
+}
+
+fn parse_horn_pattern(name: &str, raw: &RawHornPattern, base_dir: &Path) -> Result<HornPattern, String> {
+    let id = format!("global.horn_pattern.{name}");
+    if raw.blasts_ms.is_empty() || raw.blasts_ms.len() > MAX_HORN_BLASTS {
+        return Err(format!("'{id}' blasts_ms must list 1 to {MAX_HORN_BLASTS} blasts"));
+    }
+    if raw.blasts_ms.iter().any(|&blast_ms| blast_ms == 0 || blast_ms > MAX_HORN_STEP_MS) {
+        return Err(format!("'{id}' blasts_ms entries must be between 1 and {MAX_HORN_STEP_MS}"));
+    }
+    let gap_ms = raw.gap_ms.unwrap_or(DEFAULT_HORN_GAP_MS);
+    if gap_ms > MAX_HORN_STEP_MS {
+        return Err(format!("'{id}' gap_ms must be at most {MAX_HORN_STEP_MS}"));
+    }
+    let sound = match raw.sound.as_deref() {
+        Some(sound) if sound.trim().is_empty() => return Err(format!("'{id}' sound cannot be empty")),
+        Some(sound) => Some(resolve_asset_path(base_dir, sound)),
+        None => None,
+    };
+    Ok(HornPattern {
+        name: name.to_string(),
+        sound,
+        blasts_ms: raw.blasts_ms.clone(),
+        gap_ms,
+    })
+}
+
+fn parse_audio(raw: &RawAudio) -> Result<AudioSettings, String> {
+    if raw.output_device.as_deref().is_some_and(|device| device.trim().is_empty()) {
+        return Err("'global.audio' output_device cannot be empty".to_string());
+    }
+    let devices = raw.device.clone().unwrap_or_default();
+    for (cue, device) in &devices {
+        if !SOUND_CUES.contains(&cue.as_str()) {
+            return Err(format!(
+                "'global.audio' device.{cue} is not a sound cue (expected one of: {})",
+                SOUND_CUES.join(", ")
+            ));
+        }
+        if device.trim().is_empty() {
+            return Err(format!("'global.audio' device.{cue} cannot be empty"));
+        }
+    }
+    Ok(AudioSettings {
+        output_device: raw.output_device.clone(),
+        devices,
+    })
+}
+
+fn parse_playlist(raw: &RawPlaylist, base_dir: &Path) -> Result<PlaylistSettings, String> {
+    let id = "global.playlist";
+    if raw.folder.trim().is_empty() {
+        return Err(format!("'{id}' folder cannot be empty"));
+    }
+    let crossfade_ms = raw.crossfade_ms.unwrap_or(0);
+    if crossfade_ms > MAX_CROSSFADE_MS {
+        return Err(format!("'{id}' crossfade_ms must be at most {MAX_CROSSFADE_MS}"));
+    }
+    let volume = raw.volume.unwrap_or(1.0);
+    let duck_volume = raw.duck_volume.unwrap_or(DEFAULT_DUCK_VOLUME);
+    for (key, value) in [("volume", volume), ("duck_volume", duck_volume)] {
+        if !(0.0..=1.0).contains(&value) {
+            return Err(format!("'{id}' {key} must be between 0.0 and 1.0"));
+        }
+    }
+    if raw.clock.as_deref().is_some_and(|clock| clock.trim().is_empty()) {
+        return Err(format!("'{id}' clock cannot be empty"));
+    }
+    let on_clock_start = match raw.on_clock_start.as_deref() {
+        Some(_) if raw.clock.is_none() => return Err(format!("'{id}' on_clock_start requires clock")),
+        Some("duck") | None => PlaylistClockAction::Duck,
+        Some("stop") => PlaylistClockAction::Stop,
+        Some(other) => {
+            return Err(format!(
+                "'{id}' has unsupported on_clock_start '{other}' (expected 'duck' or 'stop')"
+            ))
+        }
+    };
+    let keybind = match raw.keybind.as_ref() {
+        Some(binds) => Some(PlaylistKeybind {
+            play: parse_optional_keybind(id, binds, "play")?,
+            pause: parse_optional_keybind(id, binds, "pause")?,
+            next: parse_optional_keybind(id, binds, "next")?,
+        }),
+        None => None,
+    };
+    Ok(PlaylistSettings {
+        folder: resolve_asset_path(base_dir, &raw.folder),
+        shuffle: raw.shuffle.unwrap_or(false),
+        crossfade_ms,
+        volume,
+        clock: raw.clock.clone(),
+        on_clock_start,
+        duck_volume,
+        now_playing: raw.now_playing.clone(),
+        keybind,
+    })
+}
+
+/// Resolves a sound setting: a file path relative to the config, or a
+/// `pattern:<name>` reference that is kept as written.
+fn parse_sound(id: &str, key: &str, sound: &str, patterns: &[HornPattern], base_dir: &Path) -> Result<String, String> {
+    if sound.trim().is_empty() {
+        return Err(format!("'{id}' {key} cannot be empty"));
+    }
+    let Some(name) = sound.strip_prefix(SOUND_PATTERN_PREFIX) else {
+        return Ok(resolve_asset_path(base_dir, sound));
+    };
+    if !patterns.iter().any(|pattern| pattern.name == name) {
+        return Err(format!("'{id}' {key} references unknown horn pattern '{name}'"));
+    }
+    Ok(sound.to_string())
+}
+
+fn parse_aux_timer(
+    name: &str,
+    raw: &RawAuxTimer,
+    patterns: &[HornPattern],
+    base_dir: &Path,
+) -> Result<AuxTimerSettings, String> {
+    let id = format!("global.aux_timer.{name}");
+    let duration_ms = parse_timer_default(&raw.duration).map_err(|e| format!("'{id}' duration: {e}"))?;
+    if duration_ms <= 0 {
+        return Err(format!("'{id}' duration must be greater than 00:00:00"));
+    }
+    if raw.label.as_deref().is_some_and(|label| label.trim().is_empty()) {
+        return Err(format!("'{id}' label cannot be empty"));
+    }
+    let sound = match raw.sound.as_deref() {
+        Some(sound) => Some(parse_sound(&id, "sound", sound, patterns, base_dir)?),
+        None => None,
+    };
+    let keybind = match raw.keybind.as_ref() {
+        Some(binds) => Some(AuxTimerKeybind {
+            start: parse_optional_keybind(&id, binds, "start")?,
+            stop: parse_optional_keybind(&id, binds, "stop")?,
+            reset: parse_optional_keybind(&id, binds, "reset")?,
+        }),
+        None => None,
+    };
+    Ok(AuxTimerSettings {
+        name: name.to_string(),
+        label: raw.label.clone(),
+        duration_ms,
+        sound,
+        keybind,
+    })
+}
+
+fn parse_announcement(
+    raw: &RawAnnouncement,
+    patterns: &[HornPattern],
+    base_dir: &Path,
+) -> Result<AnnouncementSettings, String> {
+    if raw.target.trim().is_empty() {
+        return Err("'global.announcement' target cannot be empty".to_string());
+    }
+    let default_duration_ms = raw.duration_ms.unwrap_or(DEFAULT_ANNOUNCEMENT_DURATION_MS);
+    if default_duration_ms == 0 {
+        return Err("'global.announcement' duration_ms must be > 0".to_string());
+    }
+
+    let mut messages = Vec::new();
+    for (name, raw_message) in raw.message.iter().flatten() {
+        let id = format!("global.announcement.message.{name}");
+        if raw_message.duration_ms == Some(0) {
+            return Err(format!("'{id}' duration_ms must be > 0"));
+        }
+        let sound = match raw_message.sound.as_deref() {
+            Some(sound) => Some(parse_sound(&id, "sound", sound, patterns, base_dir)?),
+            None => None,
+        };
+        let interval_ms = match raw_message.interval.as_deref() {
+            Some(interval) => {
+                let interval_ms = parse_timer_default(interval).map_err(|e| format!("'{id}' interval: {e}"))?;
+                if interval_ms <= 0 {
+                    return Err(format!("'{id}' interval must be greater than 00:00:00"));
+                }
+                Some(interval_ms)
+            }
+            None => None,
+        };
+        if let Some(keybind) = &raw_message.keybind {
+            validate_keybind_spec(&id, "key", keybind)?;
+        }
+        messages.push(AnnouncementMessage {
+            name: name.clone(),
+            text: raw_message.text.clone(),
+            sound,
+            priority: raw_message.priority.unwrap_or(0),
+            duration_ms: raw_message.duration_ms,
+            interval_ms,
+            keybind: raw_message.keybind.clone(),
+        });
+    }
+
+    Ok(AnnouncementSettings {
+        target: raw.target.clone(),
+        default_duration_ms,
+        messages,
+    })
+}
+
+/// Parses space-separated hex bytes such as `"A0 01 01 A2"`.
+fn parse_hex_bytes(id: &str, key: &str, value: &str) -> Result<Vec<u8>, String> {
+    let bytes = value
+        .split_whitespace()
+        .map(|byte| {
+            let digits = byte.trim_start_matches("0x").trim_start_matches("0X");
+            u8::from_str_radix(digits, 16).map_err(|_| format!("'{id}' {key} has invalid hex byte '{byte}'"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if bytes.is_empty() {
+        return Err(format!("'{id}' {key} cannot be empty"));
+    }
+    Ok(bytes)
+}
+
+fn format_hex_bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02X}")).collect::<Vec<_>>().join(" ")
+}
+
+fn validate_period_links(components: &[ComponentConfig]) -> Result<(), String> {
+    let is_period = |id: &str| {
+        components
+            .iter()
+            .any(|other| other.id == id && matches!(other.kind, ComponentKind::Period { .. }))
+    };
+    for component in components {
+        if let ComponentKind::Fouls {
+            reset_period: Some(period),
+            ..
+        } = &component.kind
+        {
+            if !is_period(period) {
+                return Err(format!(
+                    "'{}' reset_period references unknown period '{period}'",
+                    component.id
+                ));
+            }
+        }
+    }
+
+    for component in components {
+        let ComponentKind::Number {
+            period_budget: Some(budget),
+            ..
+        } = &component.kind
+        else {
+            continue;
+        };
+        let labels = components
+            .iter()
+            .find_map(|other| match &other.kind {
+                ComponentKind::Period { labels, .. } if other.id == budget.period => Some(labels),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                format!(
+                    "'{}' period_budget.period references unknown period '{}'",
+                    component.id, budget.period
+                )
+            })?;
+        for (label, value) in &budget.values {
+            if !labels.contains(label) {
+                return Err(format!(
+                    "'{}' period_budget.values has unknown period label '{label}'",
+                    component.id
+                ));
+            }
+            if *value < 0 {
+                return Err(format!(
+                    "'{}' period_budget.values.{label} must be >= 0",
+                    component.id
+                ));
+            }
+        }
+    }
+
+    for component in components {
+        let ComponentKind::Period {
+            on_advance: Some(advance),
+            ..
+        } = &component.kind
+        else {
+            continue;
+        };
+        let links_timer = components.iter().any(|other| {
+            other.id == advance.reset_timer && matches!(other.kind, ComponentKind::Timer { mirror: None, .. })
+        });
+        if !links_timer {
+            return Err(format!(
+                "'{}' on_advance.reset_timer references unknown timer '{}'",
+                component.id, advance.reset_timer
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+struct RawGamepad {
+    deadzone: Option<f32>,
+    trigger_threshold: Option<f32>,
+}
+
+fn parse_gamepad_settings(raw_gamepad: Option<&toml::Value>) -> Result<GamepadSettings, String> {
+    let Some(raw_gamepad) = raw_gamepad else {
+        return Ok(GamepadSettings::default());
+    };
+    let raw: RawGamepad = raw_gamepad
+        .clone()
+        .try_into()
+        .map_err(|e| format!("Invalid [gamepad] section: {e}"))?;
+    let settings = GamepadSettings {
+        deadzone: raw.deadzone.unwrap_or(DEFAULT_GAMEPAD_DEADZONE),
+        trigger_threshold: raw.trigger_threshold.unwrap_or(DEFAULT_GAMEPAD_TRIGGER_THRESHOLD),
+    };
+    for (key, value) in [("deadzone", settings.deadzone), ("trigger_threshold", settings.trigger_threshold)] {
+        if !(MIN_GAMEPAD_THRESHOLD..=MAX_GAMEPAD_THRESHOLD).contains(&value) {
+            return Err(format!(
+                "'gamepad' {key} must be between {MIN_GAMEPAD_THRESHOLD} and {MAX_GAMEPAD_THRESHOLD}"
+            ));
+        }
+    }
+    Ok(settings)
+}
+
+#[derive(Debug, Deserialize)]
+struct RawGroup {
+    offset: Option<RawPosition>,
+    visible: Option<bool>,
+    scale: Option<f32>,
+}
+
+fn parse_groups(raw_groups: Option<&toml::Value>, canvas: CanvasSize) -> Result<Vec<ComponentGroup>, String> {
+    let Some(raw_groups) = raw_groups else {
+        return Ok(Vec::new());
+    };
+    let table = raw_groups
+        .as_table()
+        .ok_or_else(|| "'group' must be a table of named groups".to_string())?;
+
+    let mut groups = Vec::new();
+    for (name, value) in table {
+        let id = format!("group.{name}");
+        validate_id(name)?;
+        let raw: RawGroup = value
+            .clone()
+            .try_into()
+            .map_err(|e| format!("Invalid group '{name}': {e}"))?;
+        let scale = raw.scale.unwrap_or(1.0);
+        if !(MIN_GROUP_SCALE..=MAX_GROUP_SCALE).contains(&scale) {
+            return Err(format!(
+                "'{id}' scale must be between {MIN_GROUP_SCALE} and {MAX_GROUP_SCALE}"
+            ));
+        }
+        let offset = match &raw.offset {
+            Some(offset) => offset.resolve(&id, "offset", canvas)?,
+            None => Position { x: 0, y: 0 },
+        };
+        groups.push(ComponentGroup {
+            name: name.clone(),
+            offset,
+            visible: raw.visible.unwrap_or(true),
+            scale,
+        });
+    }
+    Ok(groups)
+}
+
+fn parse_macros(raw_macros: Option<&toml::Value>) -> Result<Vec<MacroSettings>, String> {
+    let Some(raw_macros) = raw_macros else {
+        return Ok(Vec::new());
+    };
+    let table = raw_macros
+        .as_table()
+        .ok_or_else(|| "'macro' must be a table of [macro.<name>] tables".to_string())?;
+
+    let mut macros = Vec::new();
+    for (name, value) in table {
+        let id = format!("macro.{name}");
+        if name.trim().is_empty() || name.contains('.') {
+            return Err(format!("'{id}' name cannot be empty or contain '.'"));
+        }
+        let raw: RawMacro = value
+            .clone()
+            .try_into()
+            .map_err(|e| format!("Invalid macro '{name}': {e}"))?;
+        if raw.actions.is_empty() {
+            return Err(format!("'{id}' actions cannot be empty"));
+        }
+        if raw.actions.len() > MAX_MACRO_STEPS {
+            return Err(format!("'{id}' can list at most {MAX_MACRO_STEPS} actions"));
+        }
+        let steps = raw
+            .actions
+            .iter()
+            .map(|step| {
+                let (action, delay_ms) = match step {
+                    RawMacroStep::Name(action) => (action, 0),
+                    RawMacroStep::Step { action, delay_ms } => (action, delay_ms.unwrap_or(0)),
+                };
+                if delay_ms > MAX_MACRO_DELAY_MS {
+                    return Err(format!("'{id}' delay_ms must be at most {MAX_MACRO_DELAY_MS}"));
+                }
+                Ok(MacroStep {
+                    action: action.trim().to_string(),
+                    delay_ms,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        if let Some(keybind) = &raw.keybind {
+            validate_keybind_spec(&id, "key", keybind)?;
+        }
+        macros.push(MacroSettings {
+            name: name.clone(),
+            steps,
+            keybind: raw.keybind.clone(),
+        });
+    }
+    Ok(macros)
+}
+
+/// Checks that every macro step names an action the config has. Macros cannot
+/// fire other macros, so one key can never start a loop.
+fn validate_macro_actions(config: &ScoreboardConfig) -> Result<(), String> {
+    for settings in &config.macros {
+        for step in &settings.steps {
+            let id = format!("macro.{}", settings.name);
+            if step.action.starts_with("macro.") {
+                return Err(format!("'{id}' cannot fire another macro ('{}')", step.action));
+            }
+            if resolve_action_name(config, &step.action).is_none() {
+                return Err(format!("'{id}' action '{}' is not an action this config has", step.action));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks group membership and that every member still lands on the canvas
+/// once its group's offset and scale are applied.
+fn validate_groups(global: &GlobalSettings, groups: &[ComponentGroup], components: &[ComponentConfig]) -> Result<(), String> {
+    for component in components {
+        let Some(name) = &component.group else {
+            continue;
+        };
+        let group = groups
+            .iter()
+            .find(|group| &group.name == name)
+            .ok_or_else(|| format!("'{}' group references unknown group '{name}'", component.id))?;
+        validate_position(&component.id, &group.resolve_position(&component.position), global.canvas)
+            .map_err(|e| format!("{e} once group '{name}' is applied"))?;
+    }
+    if let Some(name) = global.splash.as_ref().and_then(|splash| splash.group.as_ref()) {
+        if !groups.iter().any(|group| &group.name == name) {
+            return Err(format!("'global.splash' group references unknown group '{name}'"));
+        }
+    }
+    Ok(())
+}
+
+fn validate_possession_clocks(components: &[ComponentConfig]) -> Result<(), String> {
+    for component in components {
+        let ComponentKind::PossessionTime { clock, .. } = &component.kind else {
+            continue;
+        };
+        match components.iter().find(|other| &other.id == clock).map(|other| &other.kind) {
+            Some(ComponentKind::Timer { mirror: None, .. }) => {}
+            Some(ComponentKind::Timer { mirror: Some(primary), .. }) => {
+                return Err(format!(
+                    "'{}' clock '{clock}' is a mirror; use its primary timer '{primary}'",
+                    component.id
+                ));
+            }
+            Some(_) => return Err(format!("'{}' clock '{clock}' must be a timer component", component.id)),
+            None => return Err(format!("'{}' clock references unknown timer '{clock}'", component.id)),
+        }
+    }
+    Ok(())
+}
+
+/// Derived components read numbers, fouls, timers, periods, and labels, but
+/// not each other, so no expression can end up depending on itself.
+fn validate_derived_components(components: &[ComponentConfig]) -> Result<(), String> {
+    for component in components {
+        let ComponentKind::Derived { expr } = &component.kind else {
+            continue;
+        };
+        for id in expr.components() {
+            let Some(other) = components.iter().find(|other| other.id == id) else {
+                return Err(format!("'{}' expr references unknown component '{id}'", component.id));
+            };
+            if expression_value_type(&other.kind).is_none() {
+                return Err(format!(
+                    "'{}' expr cannot read '{id}'; only number, fouls, timer, period, and label components can be used",
+                    component.id
+                ));
+            }
+        }
+        expr.check(&|id| {
+            components
+                .iter()
+                .find(|other| other.id == id)
+                .and_then(|other| expression_value_type(&other.kind))
+        })
+        .map_err(|e| format!("'{}' expr {e}", component.id))?;
+    }
+    Ok(())
+}
+
+/// What a component is worth in a derived expression: timers count in milliseconds.
+fn expression_value_type(kind: &ComponentKind) -> Option<ValueType> {
+    match kind {
+        ComponentKind::Number { .. } | ComponentKind::Fouls { .. } | ComponentKind::Timer { .. } => {
+            Some(ValueType::Number)
+        }
+        ComponentKind::Period { .. } | ComponentKind::Label { .. } => Some(ValueType::Text),
+        _ => None,
+    }
+}
+
+fn validate_timer_mirrors(components: &[ComponentConfig]) -> Result<(), String> {
+    for component in components {
+        let ComponentKind::Timer {
+            mirror: Some(primary), ..
+        } = &component.kind
+        else {
+            continue;
+        };
+        match components.iter().find(|other| &other.id == primary).map(|other| &other.kind) {
+            Some(ComponentKind::Timer { mirror: None, .. }) => {}
+            Some(ComponentKind::Timer { mirror: Some(_), .. }) => {
+                return Err(format!(
+                    "'{}' mirror '{primary}' is itself a mirror; point it at the primary timer",
+                    component.id
+                ));
+            }
+            Some(_) => return Err(format!("'{}' mirror '{primary}' must be a timer component", component.id)),
+            None => return Err(format!("'{}' mirror references unknown timer '{primary}'", component.id)),
+        }
+    }
+    Ok(())
+}
+
+fn validate_player_actions(global: &GlobalSettings, components: &[ComponentConfig]) -> Result<(), String> {
+    if global.player_actions.is_empty() {
+        return Ok(());
+    }
+    let has_select_key = global
+        .keybind
+        .as_ref()
+        .is_some_and(|keybind| keybind.player_select.is_some());
+    if !has_select_key {
+        return Err("'global' player_action requires keybind.player_select".to_string());
+    }
+
+    for player_action in &global.player_actions {
+        let Some(apply) = &player_action.apply else {
+            continue;
+        };
+        let id = format!("global.player_action.{}", player_action.name);
+        let component = components
+            .iter()
+            .find(|component| component.id == apply.component)
+            .ok_or_else(|| format!("'{id}' apply references unknown component '{}'", apply.component))?;
+        let allowed = component_action_names(&component.kind);
+        if !allowed.contains(&apply.action.as_str()) {
+            return Err(format!(
+                "'{id}' apply action '{}' is not valid for '{}'",
+                apply.action, apply.component
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_announcement_target(global: &GlobalSettings, components: &[ComponentConfig]) -> Result<(), String> {
+    let Some(announcement) = &global.announcement else {
+        return Ok(());
+    };
+    let target = &announcement.target;
+    match components.iter().find(|component| &component.id == target) {
+        Some(ComponentConfig {
+            kind: ComponentKind::Label { .. },
+            ..
+        }) => Ok(()),
+        Some(_) => Err(format!("'global.announcement' target '{target}' must be a label component")),
+        None => Err(format!("'global.announcement' target references unknown component '{target}'")),
+    }
+}
+
+/// Placeholders the `[global.summary]` templates may use.
+pub const SUMMARY_PLACEHOLDERS: [&str; 7] = ["home_name", "away_name", "home", "away", "period", "line_score", "events"];
+
+fn parse_summary(raw: &RawSummary) -> Result<SummarySettings, String> {
+    let id = "global.summary";
+    let required_id = |key: &str, value: &str| {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(format!("'{id}' {key} cannot be empty"));
+        }
+        Ok(value.to_string())
+    };
+    let optional_id = |key: &str, value: &Option<String>| value.as_deref().map(|value| required_id(key, value)).transpose();
+    let team_name = |key: &str, value: &Option<String>, fallback: &str| match value.as_deref().map(str::trim) {
+        Some("") => Err(format!("'{id}' {key} cannot be empty")),
+        Some(name) => Ok(name.to_string()),
+        None => Ok(fallback.to_string()),
+    };
+    for (key, template) in [("markdown", &raw.markdown), ("text", &raw.text)] {
+        if let Some(template) = template {
+            validate_summary_template(&format!("{id}.{key}"), template)?;
+        }
+    }
+    Ok(SummarySettings {
+        home: required_id("home", &raw.home)?,
+        away: required_id("away", &raw.away)?,
+        home_name: team_name("home_name", &raw.home_name, "Home")?,
+        away_name: team_name("away_name", &raw.away_name, "Away")?,
+        period: optional_id("period", &raw.period)?,
+        clock: optional_id("clock", &raw.clock)?,
+        home_fouls: optional_id("home_fouls", &raw.home_fouls)?,
+        away_fouls: optional_id("away_fouls", &raw.away_fouls)?,
+        home_timeouts: optional_id("home_timeouts", &raw.home_timeouts)?,
+        away_timeouts: optional_id("away_timeouts", &raw.away_timeouts)?,
+        home_roster: parse_roster("home_roster", raw.home_roster.as_ref())?,
+        away_roster: parse_roster("away_roster", raw.away_roster.as_ref())?,
+        markdown: raw.markdown.clone(),
+        text: raw.text.clone(),
+    })
+}
+
+/// Jersey numbers are one or two digits and stored with two, the way player
+/// select mode enters them, so `"7"` and `"07"` are the same player.
+fn parse_roster(key: &str, raw: Option<&BTreeMap<String, String>>) -> Result<BTreeMap<String, String>, String> {
+    let mut roster = BTreeMap::new();
+    for (jersey, name) in raw.into_iter().flatten() {
+        let id = format!("global.summary.{key}");
+        let jersey = jersey.trim();
+        if jersey.is_empty() || jersey.len() > 2 || !jersey.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("'{id}' jersey '{jersey}' must be one or two digits"));
+        }
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(format!("'{id}' name for jersey {jersey} cannot be empty"));
+        }
+        if roster.insert(format!("{jersey:0>2}"), name.to_string()).is_some() {
+            return Err(format!("'{id}' lists jersey {jersey} more than once"));
+        }
+    }
+    Ok(roster)
+}
+
+/// Rejects `{name}` placeholders the summary cannot fill, which are usually typos.
+fn validate_summary_template(id: &str, template: &str) -> Result<(), String> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        let name = &after[..end];
+        if !SUMMARY_PLACEHOLDERS.contains(&name) {
+            return Err(format!(
+                "'{id}' uses unknown placeholder {{{name}}}; expected one of {}",
+                SUMMARY_PLACEHOLDERS.map(|placeholder| format!("{{{placeholder}}}")).join(", ")
+            ));
+        }
+        rest = &after[end + 1..];
+    }
+    Ok(())
+}
+
+fn parse_fixtures(raw: &RawFixtures, base_dir: &Path) -> Result<FixtureSettings, String> {
+    let id = "global.fixtures";
+    let source = raw.source.trim();
+    if source.is_empty() {
+        return Err(format!("'{id}' source cannot be empty"));
+    }
+    let source = if source.starts_with("http://") || source.starts_with("https://") {
+        source.to_string()
+    } else {
+        resolve_asset_path(base_dir, source)
+    };
+    let countdown = raw.countdown.trim();
+    if countdown.is_empty() {
+        return Err(format!("'{id}' countdown cannot be empty"));
+    }
+    let duration = |key: &str, value: Option<&str>, default_ms: i64| -> Result<i64, String> {
+        let Some(value) = value else {
+            return Ok(default_ms);
+        };
+        let ms = parse_timer_default(value).map_err(|e| format!("'{id}' {key}: {e}"))?;
+        if ms <= 0 {
+            return Err(format!("'{id}' {key} must be longer than zero"));
+        }
+        Ok(ms)
+    };
+    let lead_ms = duration("lead", raw.lead.as_deref(), DEFAULT_FIXTURE_LEAD_MS)?;
+    if lead_ms > MAX_FIXTURE_LEAD_MS {
+        return Err(format!("'{id}' lead must be at most 24:00:00"));
+    }
+    let refresh_ms = duration("refresh", raw.refresh.as_deref(), DEFAULT_FIXTURE_REFRESH_MS)?;
+    let utc_offset_minutes = match raw.utc_offset.as_deref() {
+        Some(offset) => parse_utc_offset(offset).ok_or_else(|| format!("'{id}' utc_offset must be +HH:MM or -HH:MM"))?,
+        None => 0,
+    };
+    let label = |value: &Option<String>| value.as_deref().map(str::trim).map(str::to_string);
+    let mut configs = Vec::new();
+    for (keyword, path) in raw.config.iter().flatten() {
+        let Some(path) = path.as_str().map(str::trim).filter(|path| !path.is_empty()) else {
+            return Err(format!("'{id}.config' entry '{keyword}' must be a config file path"));
+        };
+        if keyword.trim().is_empty() {
+            return Err(format!("'{id}.config' keywords cannot be empty"));
+        }
+        configs.push((keyword.trim().to_string(), resolve_asset_path(base_dir, path)));
+    }
+    Ok(FixtureSettings {
+        source,
+        countdown: countdown.to_string(),
+        lead_ms,
+        refresh_ms,
+        utc_offset_minutes,
+        home_label: label(&raw.home_label),
+        away_label: label(&raw.away_label),
+        configs,
+    })
+}
+
+/// Parses `+HH:MM` / `-HH:MM` into minutes east of UTC.
+fn parse_utc_offset(value: &str) -> Option<i32> {
+    let value = value.trim();
+    let (sign, rest) = match value.split_at_checked(1)? {
+        ("+", rest) => (1, rest),
+        ("-", rest) => (-1, rest),
+        _ => return None,
+    };
+    let (hours, minutes) = rest.split_once(':')?;
+    let (hours, minutes): (i32, i32) = (hours.parse().ok()?, minutes.parse().ok()?);
+    if hours > 14 || !(0..60).contains(&minutes) || hours < 0 {
+        return None;
+    }
+    Some(sign * (hours * 60 + minutes))
+}
+
+fn format_utc_offset(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    format!("{sign}{:02}:{:02}", minutes.abs() / 60, minutes.abs() % 60)
+}
+
+fn validate_fixture_components(global: &GlobalSettings, components: &[ComponentConfig]) -> Result<(), String> {
+    let Some(fixtures) = &global.fixtures else {
+        return Ok(());
+    };
+    let find = |key: &str, target: &str| {
+        components
+            .iter()
+            .find(|component| component.id == target)
+            .ok_or_else(|| format!("'global.fixtures' {key} references unknown component '{target}'"))
+    };
+    match &find("countdown", &fixtures.countdown)?.kind {
+        ComponentKind::Timer { mirror: None, .. } => {}
+        ComponentKind::Timer { mirror: Some(primary), .. } => {
+            return Err(format!(
+                "'global.fixtures' countdown '{}' is a mirror; use its primary timer '{primary}'",
+                fixtures.countdown
+            ))
+        }
+        _ => {
+            return Err(format!(
+                "'global.fixtures' countdown '{}' must be a timer component",
+                fixtures.countdown
+            ))
+        }
+    }
+    for (key, target) in [("home_label", &fixtures.home_label), ("away_label", &fixtures.away_label)] {
+        if let Some(target) = target {
+            if !matches!(find(key, target)?.kind, ComponentKind::Label { .. }) {
+                return Err(format!("'global.fixtures' {key} '{target}' must be a label component"));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_playlist_components(global: &GlobalSettings, components: &[ComponentConfig]) -> Result<(), String> {
+    let Some(playlist) = &global.playlist else {
+        return Ok(());
+    };
+    let find = |key: &str, target: &str| {
+        components
+            .iter()
+            .find(|component| component.id == target)
+            .ok_or_else(|| format!("'global.playlist' {key} references unknown component '{target}'"))
+    };
+    if let Some(clock) = &playlist.clock {
+        match &find("clock", clock)?.kind {
+            ComponentKind::Timer { mirror: None, .. } => {}
+            ComponentKind::Timer { mirror: Some(primary), .. } => {
+                return Err(format!(
+                    "'global.playlist' clock '{clock}' is a mirror; use its primary timer '{primary}'"
+                ))
+            }
+            _ => return Err(format!("'global.playlist' clock '{clock}' must be a timer component")),
+        }
+    }
+    if let Some(now_playing) = &playlist.now_playing {
+        if !matches!(find("now_playing", now_playing)?.kind, ComponentKind::Label { .. }) {
+            return Err(format!("'global.playlist' now_playing '{now_playing}' must be a label component"));
+        }
+    }
+    Ok(())
+}
+
+fn validate_serial_input_components(global: &GlobalSettings, components: &[ComponentConfig]) -> Result<(), String> {
+    let Some(serial_input) = &global.serial_input else {
+        return Ok(());
+    };
+    for field in &serial_input.fields {
+        let id = &field.component;
+        let component = components
+            .iter()
+            .find(|component| &component.id == id)
+            .ok_or_else(|| format!("'global.serial_input' map references unknown component '{id}'"))?;
+        match &component.kind {
+            ComponentKind::Number { .. }
+            | ComponentKind::Fouls { .. }
+            | ComponentKind::Label { .. }
+            | ComponentKind::Period { .. }
+            | ComponentKind::Timer { mirror: None, .. } => {}
+            ComponentKind::Timer { mirror: Some(primary), .. } => {
+                return Err(format!(
+                    "'global.serial_input' map entry '{id}' is a mirror; map its primary timer '{primary}'"
+                ))
+            }
+            _ => {
+                return Err(format!(
+                    "'global.serial_input' map entry '{id}' must be a number, fouls, timer, label, or period component"
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_idle_components(global: &GlobalSettings, components: &[ComponentConfig]) -> Result<(), String> {
+    let Some(idle) = &global.idle else {
+        return Ok(());
+    };
+    for hidden in &idle.hide {
+        if !components.iter().any(|component| &component.id == hidden) {
+            return Err(format!("'global.idle' hide references unknown component '{hidden}'"));
+        }
+    }
+    Ok(())
+}
+
+fn validate_obs_components(global: &GlobalSettings, components: &[ComponentConfig]) -> Result<(), String> {
+    let Some(obs) = &global.obs else {
+        return Ok(());
+    };
+    for trigger in &obs.triggers {
+        let id = format!("global.obs.trigger.{}", trigger.name);
+        match &trigger.event {
+            ObsEvent::TimerExpired { timer } => {
+                let is_timer = components
+                    .iter()
+                    .any(|component| &component.id == timer && matches!(component.kind, ComponentKind::Timer { .. }));
+                if !is_timer {
+                    return Err(format!("'{id}' on_expire references unknown timer '{timer}'"));
+                }
+            }
+            ObsEvent::PeriodAdvanced { period, to } => {
+                let labels = components.iter().find_map(|component| match &component.kind {
+                    ComponentKind::Period { labels, .. } if &component.id == period => Some(labels),
+                    _ => None,
+                });
+                let Some(labels) = labels else {
+                    return Err(format!("'{id}' on_period references unknown period '{period}'"));
+                };
+                if let Some(to) = to.as_ref().filter(|to| !labels.contains(to)) {
+                    return Err(format!("'{id}' to '{to}' is not one of the labels of '{period}'"));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_summary_components(global: &GlobalSettings, components: &[ComponentConfig]) -> Result<(), String> {
+    let Some(summary) = &global.summary else {
+        return Ok(());
+    };
+    let find = |key: &str, target: &str| {
+        components
+            .iter()
+            .find(|component| component.id == target)
+            .ok_or_else(|| format!("'global.summary' {key} references unknown component '{target}'"))
+    };
+    for (key, target) in [("home", &summary.home), ("away", &summary.away)] {
+        if !matches!(find(key, target)?.kind, ComponentKind::Number { .. }) {
+            return Err(format!("'global.summary' {key} '{target}' must be a number component"));
+        }
+    }
+    if let Some(target) = &summary.period {
+        if !matches!(find("period", target)?.kind, ComponentKind::Period { .. }) {
+            return Err(format!("'global.summary' period '{target}' must be a period component"));
+        }
+        // The line score reads the per-period points, which only scoring numbers have.
+        for (key, target) in [("home", &summary.home), ("away", &summary.away)] {
+            if !matches!(find(key, target)?.kind, ComponentKind::Number { score: true, .. }) {
+                return Err(format!("'global.summary' {key} '{target}' needs score = true for the line score"));
+            }
+        }
+    }
+    if let Some(target) = &summary.clock {
+        if !matches!(find("clock", target)?.kind, ComponentKind::Timer { .. }) {
+            return Err(format!("'global.summary' clock '{target}' must be a timer component"));
+        }
+    }
+    for (key, target) in [("home_fouls", &summary.home_fouls), ("away_fouls", &summary.away_fouls)] {
+        if let Some(target) = target {
+            if !matches!(find(key, target)?.kind, ComponentKind::Fouls { .. } | ComponentKind::Number { .. }) {
+                return Err(format!("'global.summary' {key} '{target}' must be a fouls or number component"));
+            }
+        }
+    }
+    for (key, target) in [("home_timeouts", &summary.home_timeouts), ("away_timeouts", &summary.away_timeouts)] {
+        if let Some(target) = target {
+            if !matches!(find(key, target)?.kind, ComponentKind::Number { .. }) {
+                return Err(format!("'global.summary' {key} '{target}' must be a number component"));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_led_matrix_items(global: &GlobalSettings, components: &[ComponentConfig]) -> Result<(), String> {
+    let Some(led_matrix) = &global.led_matrix else {
+        return Ok(());
+    };
+    for item in &led_matrix.items {
+        let id = format!("global.led_matrix.component.{}", item.component);
+        let component = components
+            .iter()
+            .find(|component| component.id == item.component)
+            .ok_or_else(|| format!("'{id}' references unknown component '{}'", item.component))?;
+        let shows_text = !matches!(
+            component.kind,
+            ComponentKind::Image { .. }
+                | ComponentKind::ImageToggle { .. }
+                | ComponentKind::Video { .. }
+                | ComponentKind::Possession {
+                    display: PossessionDisplay::Images { .. },
+                    ..
+                }
+        );
+        if !shows_text {
+            return Err(format!("'{id}' must reference a component that shows text"));
+        }
+    }
+    Ok(())
+}
+
+fn resolve_font(id: &str, base: &Font, override_font: Option<&FontOverride>) -> Result<Font, String> {
+    let families = match override_font.and_then(|f| f.family.as_ref()) {
+        Some(family) => parse_font_families(family).map_err(|e| format!("'{id}' {e}"))?,
+        None => base.families.clone(),
+    };
+    let family = families.first().cloned().unwrap_or_default();
+    let size = override_font.and_then(|f| f.size).unwrap_or(base.size);
+    let color = override_font
+        .and_then(|f| f.color.clone())
+        .unwrap_or_else(|| base.color.clone());
+    let weight = match override_font.and_then(|f| f.weight.as_ref()) {
+        Some(weight) => parse_font_weight(weight).map_err(|e| format!("'{id}' {e}"))?,
+        None => base.weight,
+    };
+
+    Ok(Font {
+        family,
+        families,
+        size,
+        color,
+        opacity: base.opacity,
+        weight,
+        italic: override_font.and_then(|f| f.italic).unwrap_or(base.italic),
+        letter_spacing: override_font.and_then(|f| f.letter_spacing).unwrap_or(base.letter_spacing),
+        outline: override_font
+            .and_then(|f| f.outline.clone())
+            .or_else(|| base.outline.clone()),
+        shadow: override_font
+            .and_then(|f| f.shadow.clone())
+            .or_else(|| base.shadow.clone()),
+    })
+}
+
+/// `font.family` as a single name or a list of fallbacks, most preferred first.
+fn parse_font_families(value: &toml::Value) -> Result<Vec<String>, String> {
+    let invalid = || "font.family must be a font name or a list of font names".to_string();
+    match value {
+        toml::Value::String(family) => Ok(vec![family.clone()]),
+        toml::Value::Array(families) => families
+            .iter()
+            .map(|family| family.as_str().map(str::to_string).ok_or_else(invalid))
+            .collect(),
+        _ => Err(invalid()),
+    }
+}
+
+fn parse_font_weight(value: &toml::Value) -> Result<u16, String> {
+    let invalid = || "font.weight must be \"normal\", \"bold\", or a multiple of 100 from 100 to 900".to_string();
+    match value {
+        toml::Value::String(name) => match name.trim().to_ascii_lowercase().as_str() {
+            "normal" => Ok(400),
+            "bold" => Ok(700),
+            _ => Err(invalid()),
+        },
+        toml::Value::Integer(weight) if (100..=900).contains(weight) && weight % 100 == 0 => Ok(*weight as u16),
+        _ => Err(invalid()),
+    }
+}
+
+fn parse_optional_keybind(
+    id: &str,
+    binds: &BTreeMap<String, KeybindSpec>,
+    key: &str,
+) -> Result<Option<KeybindSpec>, String> {
+    let Some(spec) = binds.get(key) else {
+        return Ok(None);
+    };
+    validate_keybind_spec(id, key, spec)?;
+    Ok(Some(spec.clone()))
+}
+
+/// The source index of an image toggle's `set_<index>` keybind.
+pub fn image_toggle_set_index(name: &str) -> Option<usize> {
+    let index = name.strip_prefix("set_")?;
+    // Rejects `set_+1`, which `parse` would accept.
+    if index.is_empty() || !index.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    index.parse().ok()
+}
+
+/// Action names a component accepts in `keybind.<name>` and `sound.<name>`.
+/// Image toggles also accept `set_<index>` keybinds; see [`image_toggle_set_index`].
+pub fn component_action_names(kind: &ComponentKind) -> &'static [&'static str] {
+    match kind {
+        ComponentKind::Number { .. } | ComponentKind::Fouls { .. } => &["increase", "decrease", "reset"],
+        // A mirror follows its primary and cannot be edited on its own.
+        ComponentKind::Timer { mirror: Some(_), .. } => &[],
+        ComponentKind::Timer { .. } => &["start", "stop", "reset", "increase", "decrease"],
+        ComponentKind::ImageToggle { interval_ms: Some(_), .. } => &["forward", "backward", "start", "stop"],
+        ComponentKind::ImageToggle { .. } | ComponentKind::Period { .. } => &["forward", "backward"],
+        ComponentKind::Possession { .. } => &["toggle"],
+        ComponentKind::PossessionTime { .. } => &["home", "away", "reset"],
+        ComponentKind::Video { .. } => &["play", "pause"],
+        ComponentKind::Label { .. }
+        | ComponentKind::Image { .. }
+        | ComponentKind::Ticker { .. }
+        | ComponentKind::Derived { .. } => &[],
+    }
+}
+
+fn parse_confirm(
+    id: &str,
+    kind: &ComponentKind,
+    raw_confirm: Option<&RawConfirmSetting>,
+) -> Result<Option<ConfirmSettings>, String> {
+    let Some(raw_confirm) = raw_confirm else {
+        return Ok(None);
+    };
+
+    let allowed = component_action_names(kind);
+    if allowed.is_empty() {
+        return Err(format!(
+            "'{id}' confirm is only supported for number, fouls, timer, image-toggle, period, possession, and possession-time components"
+        ));
+    }
+    let (actions, timeout_ms, on_timeout) = match raw_confirm {
+        RawConfirmSetting::Reset => {
+            if !allowed.contains(&"reset") {
+                return Err(format!(
+                    "'{id}' has no reset action for confirm = true; list the actions in confirm.actions instead"
+                ));
+            }
+            (vec!["reset".to_string()], Some(DEFAULT_CONFIRM_RESET_TIMEOUT_MS), None)
+        }
+        RawConfirmSetting::Actions(confirm) => (
+            confirm.actions.clone(),
+            confirm.timeout_ms,
+            confirm.on_timeout.as_deref(),
+        ),
+    };
+    for action in &actions {
+        if !allowed.contains(&action.as_str()) {
+            return Err(format!(
+                "'{id}' confirm.actions entry '{action}' is not a valid action (expected one of: {})",
+                allowed.join(", ")
+            ));
+        }
+    }
+
+    let timeout_ms = timeout_ms.unwrap_or(DEFAULT_CONFIRM_TIMEOUT_MS);
+    if timeout_ms == 0 {
+        return Err(format!("'{id}' confirm.timeout_ms must be > 0"));
+    }
+    let commit_on_timeout = match on_timeout.unwrap_or("cancel") {
+        "cancel" => false,
+        "commit" => true,
+        other => {
+            return Err(format!(
+                "'{id}' has unsupported confirm.on_timeout '{other}' (expected 'cancel' or 'commit')"
+            ))
+        }
+    };
+
+    Ok(Some(ConfirmSettings {
+        actions,
+        timeout_ms,
+        commit_on_timeout,
+    }))
+}
+
+fn parse_action_sounds(
+    id: &str,
+    kind: &ComponentKind,
+    raw_sounds: Option<&BTreeMap<String, String>>,
+    patterns: &[HornPattern],
+    base_dir: &Path,
+) -> Result<BTreeMap<String, String>, String> {
+    let Some(raw_sounds) = raw_sounds else {
+        return Ok(BTreeMap::new());
+    };
+
+    let allowed = component_action_names(kind);
+    if allowed.is_empty() {
+        return Err(format!(
+            "'{id}' sound is only supported for number, fouls, timer, image-toggle, period, possession, and possession-time components"
+        ));
+    }
+
+    let mut sounds = BTreeMap::new();
+    for (action, path) in raw_sounds {
+        if !allowed.contains(&action.as_str()) {
+            return Err(format!(
+                "'{id}' sound.{action} is not a valid action (expected one of: {})",
+                allowed.join(", ")
+            ));
+        }
+        let sound = parse_sound(id, &format!("sound.{action}"), path, patterns, base_dir)?;
+        sounds.insert(action.clone(), sound);
+    }
+    Ok(sounds)
+}
+
+fn resolve_asset_path(base_dir: &Path, source: &str) -> String {
+    let p = PathBuf::from(source);
+    if p.is_absolute() {
+        return p.to_string_lossy().to_string();
+    }
+    base_dir.join(p).to_string_lossy().to_string()
+}
+
+/// Like `resolve_asset_path`, but keeps `http(s)://` URLs, which are
+/// downloaded into the asset cache when the config is applied.
+fn resolve_image_path(base_dir: &Path, source: &str) -> String {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        return source.to_string();
+    }
+    resolve_asset_path(base_dir, source)
+}
+
+fn validate_id(id: &str) -> Result<(), String> {
+    if id.trim().is_empty() {
+        return Err("Component id cannot be empty".to_string());
+    }
+    Ok(())
+}
+
+/// Checks one ticker message, from the config or added at runtime.
+pub fn validate_ticker_message(message: &str) -> Result<(), String> {
+    if message.trim().is_empty() {
+        return Err("ticker messages cannot be empty".to_string());
+    }
+    if message.contains('\n') || message.contains('\r') {
+        return Err("ticker messages must be single-line strings".to_string());
+    }
+    Ok(())
+}
+
+fn validate_position(id: &str, p: &Position, canvas: CanvasSize) -> Result<(), String> {
+    if p.x < 0 || p.x >= canvas.width || p.y < 0 || p.y >= canvas.height {
+        return Err(format!(
+            "'{id}' position ({}, {}) is outside {}x{}",
+            p.x, p.y, canvas.width, canvas.height
+        ));
+    }
+    Ok(())
+}
+
+fn validate_font(id: &str, font: &Font) -> Result<(), String> {
+    if font.families.is_empty() {
+        return Err(format!("'{id}' font.family must list at least one family"));
+    }
+    if font.families.iter().any(|family| family.trim().is_empty()) {
+        return Err(format!("'{id}' font.family cannot be empty"));
+    }
+    if font.size <= 0 {
+        return Err(format!("'{id}' font.size must be > 0"));
+    }
+    validate_color(&format!("{id}.font.color"), &font.color)?;
+    if font.letter_spacing.abs() > MAX_LETTER_SPACING {
+        return Err(format!(
+            "'{id}' font.letter_spacing must be between -{MAX_LETTER_SPACING} and {MAX_LETTER_SPACING}"
+        ));
+    }
+    if let Some(outline) = &font.outline {
+        validate_color(&format!("{id}.font.outline.color"), &outline.color)?;
+        if !(1..=MAX_OUTLINE_WIDTH).contains(&outline.width) {
+            return Err(format!("'{id}' font.outline.width must be between 1 and {MAX_OUTLINE_WIDTH}"));
+        }
+    }
+    if let Some(shadow) = &font.shadow {
+        validate_color(&format!("{id}.font.shadow.color"), &shadow.color)?;
+        if shadow.x.abs() > MAX_SHADOW_OFFSET || shadow.y.abs() > MAX_SHADOW_OFFSET {
+            return Err(format!(
+                "'{id}' font.shadow x and y must be between -{MAX_SHADOW_OFFSET} and {MAX_SHADOW_OFFSET}"
+            ));
+        }
+        if !(0..=MAX_SHADOW_BLUR).contains(&shadow.blur) {
+            return Err(format!("'{id}' font.shadow.blur must be between 0 and {MAX_SHADOW_BLUR}"));
+        }
+    }
+    Ok(())
+}
+
+fn validate_color(name: &str, color: &str) -> Result<(), String> {
+    let trimmed = color.trim();
+    if !((trimmed.len() == 7 || trimmed.len() == 9) && trimmed.starts_with('#')) {
+        return Err(format!("'{name}' must be #RRGGBB or #RRGGBBAA"));
+    }
+    if !trimmed.chars().skip(1).all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("'{name}' must be #RRGGBB or #RRGGBBAA"));
+    }
+    Ok(())
+}
+
+fn format_timer_default(ms: i64) -> String {
+    let total_seconds = ms.max(0) / 1000;
+    format!(
+        "{:02}:{:02}:{:02}",
+        total_seconds / 3600,
+        (total_seconds % 3600) / 60,
+        total_seconds % 60
+    )
+}
+
+/// Remaining times such as `voice_cues`, sorted longest first without duplicates.
+fn parse_timer_offsets(id: &str, key: &str, values: Option<&[String]>) -> Result<Vec<i64>, String> {
+    let mut offsets_ms = Vec::new();
+    for value in values.unwrap_or_default() {
+        let offset_ms = parse_timer_default(value).map_err(|e| format!("'{id}' {key}: {e}"))?;
+        if offset_ms <= 0 {
+            return Err(format!("'{id}' {key} must be greater than 00:00:00"));
+        }
+        offsets_ms.push(offset_ms);
+    }
+    offsets_ms.sort_unstable_by(|a, b| b.cmp(a));
+    offsets_ms.dedup();
+    Ok(offsets_ms)
+}
+
+fn parse_timer_default(value: &str) -> Result<i64, String> {
+    let parts: Vec<&str> = value.split(':').collect();
+    if parts.len() != 3 {
+        return Err(format!("Timer default '{value}' must be HH:MM:SS"));
+    }
+    let h: i64 = parts[0]
+        .parse()
+        .map_err(|_| format!("Timer default '{value}' has invalid hours"))?;
+    let m: i64 = parts[1]
+        .parse()
+        .map_err(|_| format!("Timer default '{value}' has invalid minutes"))?;
+    let s: i64 = parts[2]
+        .parse()
+        .map_err(|_| format!("Timer default '{value}' has invalid seconds"))?;
+    if !(0..60).contains(&m) || !(0..60).contains(&s) || h < 0 {
+        return Err(format!("Timer default '{value}' must be HH:MM:SS"));
+    }
+    Ok(((h * 3600) + (m * 60) + s) * 1000)
+}
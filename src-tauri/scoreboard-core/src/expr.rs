@@ -0,0 +1,620 @@
+//! Expressions for `derived` components: arithmetic, comparisons, and
+//! `if … then … else …` over other components' values, such as
+//! `home_score - away_score` or `if home_fouls >= 7 then "BONUS" else ""`.
+
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// Longest expression accepted, to keep a typo from building a huge tree.
+pub const MAX_EXPRESSION_LENGTH: usize = 1_000;
+const MAX_NESTING: usize = 32;
+const FUNCTIONS: [&str; 3] = ["abs", "min", "max"];
+
+/// What an expression or one of its parts evaluates to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    Number,
+    Text,
+    Bool,
+}
+
+impl ValueType {
+    fn as_str(self) -> &'static str {
+        match self {
+            ValueType::Number => "a number",
+            ValueType::Text => "text",
+            ValueType::Bool => "a condition",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(i64),
+    Text(String),
+    Bool(bool),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Number(n) => write!(f, "{n}"),
+            Value::Text(text) => f.write_str(text),
+            Value::Bool(b) => write!(f, "{b}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Literal(Value),
+    Component(String),
+    Neg(Box<Node>),
+    Not(Box<Node>),
+    Binary(BinaryOp, Box<Node>, Box<Node>),
+    If(Box<Node>, Box<Node>, Box<Node>),
+    Call(String, Vec<Node>),
+}
+
+/// A parsed expression. It serializes back to the text it was parsed from.
+#[derive(Debug, Clone)]
+pub struct Expression {
+    source: String,
+    root: Node,
+}
+
+impl Serialize for Expression {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl Expression {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        if source.len() > MAX_EXPRESSION_LENGTH {
+            return Err(format!("is longer than {MAX_EXPRESSION_LENGTH} characters"));
+        }
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, position: 0, depth: 0 };
+        let root = parser.expression()?;
+        if let Some(token) = parser.tokens.get(parser.position) {
+            return Err(format!("has unexpected {} at the end", token.describe()));
+        }
+        Ok(Self { source: source.to_string(), root })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Component ids the expression reads, in order of first use.
+    pub fn components(&self) -> Vec<&str> {
+        let mut ids = Vec::new();
+        collect_components(&self.root, &mut ids);
+        ids
+    }
+
+    /// Checks operand types, given the type of each component it reads, and
+    /// returns the type of the result.
+    pub fn check(&self, component_type: &impl Fn(&str) -> Option<ValueType>) -> Result<ValueType, String> {
+        check_node(&self.root, component_type)
+    }
+
+    /// Evaluates against current values. `None` means the result is
+    /// undefined, e.g. after a division by zero, and is shown as blank.
+    pub fn eval(&self, component_value: &impl Fn(&str) -> Option<Value>) -> Option<Value> {
+        eval_node(&self.root, component_value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(i64),
+    Text(String),
+    Word(String),
+    Symbol(&'static str),
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Number(n) => format!("'{n}'"),
+            Token::Text(text) => format!("\"{text}\""),
+            Token::Word(word) => format!("'{word}'"),
+            Token::Symbol(symbol) => format!("'{symbol}'"),
+        }
+    }
+}
+
+const SYMBOLS: [&str; 14] = ["==", "!=", "<=", ">=", "<", ">", "+", "-", "*", "/", "%", "(", ")", ","];
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    loop {
+        rest = rest.trim_start();
+        let Some(c) = rest.chars().next() else {
+            return Ok(tokens);
+        };
+        if c.is_ascii_digit() {
+            let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            let number = rest[..end]
+                .parse()
+                .map_err(|_| format!("has a number too large: {}", &rest[..end]))?;
+            tokens.push(Token::Number(number));
+            rest = &rest[end..];
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let end = rest
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            tokens.push(Token::Word(rest[..end].to_string()));
+            rest = &rest[end..];
+        } else if c == '"' || c == '\'' {
+            let end = rest[1..]
+                .find(c)
+                .ok_or_else(|| format!("has a string without a closing {c}"))?;
+            tokens.push(Token::Text(rest[1..=end].to_string()));
+            rest = &rest[end + 2..];
+        } else if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol)) {
+            tokens.push(Token::Symbol(symbol));
+            rest = &rest[symbol.len()..];
+        } else {
+            return Err(format!("has unexpected '{c}'"));
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn accept_word(&mut self, word: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Word(w)) if w == word);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn accept_symbol(&mut self, symbols: &[&'static str]) -> Option<&'static str> {
+        let Some(Token::Symbol(symbol)) = self.peek() else {
+            return None;
+        };
+        let symbol = *symbol;
+        symbols.contains(&symbol).then(|| {
+            self.position += 1;
+            symbol
+        })
+    }
+
+    fn expect_word(&mut self, word: &str) -> Result<(), String> {
+        if self.accept_word(word) {
+            return Ok(());
+        }
+        Err(match self.peek() {
+            Some(token) => format!("expects '{word}' but has {}", token.describe()),
+            None => format!("expects '{word}' but ends"),
+        })
+    }
+
+    fn expect_symbol(&mut self, symbol: &'static str) -> Result<(), String> {
+        if self.accept_symbol(&[symbol]).is_some() {
+            return Ok(());
+        }
+        Err(match self.peek() {
+            Some(token) => format!("expects '{symbol}' but has {}", token.describe()),
+            None => format!("expects '{symbol}' but ends"),
+        })
+    }
+
+    fn expression(&mut self) -> Result<Node, String> {
+        self.depth += 1;
+        if self.depth > MAX_NESTING {
+            return Err(format!("is nested more than {MAX_NESTING} levels deep"));
+        }
+        let node = if self.accept_word("if") {
+            let condition = self.expression()?;
+            self.expect_word("then")?;
+            let then = self.expression()?;
+            self.expect_word("else")?;
+            let otherwise = self.expression()?;
+            Node::If(Box::new(condition), Box::new(then), Box::new(otherwise))
+        } else {
+            self.or()?
+        };
+        self.depth -= 1;
+        Ok(node)
+    }
+
+    fn or(&mut self) -> Result<Node, String> {
+        let mut node = self.and()?;
+        while self.accept_word("or") {
+            node = Node::Binary(BinaryOp::Or, Box::new(node), Box::new(self.and()?));
+        }
+        Ok(node)
+    }
+
+    fn and(&mut self) -> Result<Node, String> {
+        let mut node = self.comparison()?;
+        while self.accept_word("and") {
+            node = Node::Binary(BinaryOp::And, Box::new(node), Box::new(self.comparison()?));
+        }
+        Ok(node)
+    }
+
+    fn comparison(&mut self) -> Result<Node, String> {
+        let node = self.sum()?;
+        let Some(symbol) = self.accept_symbol(&["==", "!=", "<=", ">=", "<", ">"]) else {
+            return Ok(node);
+        };
+        let op = match symbol {
+            "==" => BinaryOp::Eq,
+            "!=" => BinaryOp::Ne,
+            "<=" => BinaryOp::Le,
+            ">=" => BinaryOp::Ge,
+            "<" => BinaryOp::Lt,
+            _ => BinaryOp::Gt,
+        };
+        Ok(Node::Binary(op, Box::new(node), Box::new(self.sum()?)))
+    }
+
+    fn sum(&mut self) -> Result<Node, String> {
+        let mut node = self.product()?;
+        while let Some(symbol) = self.accept_symbol(&["+", "-"]) {
+            let op = if symbol == "+" { BinaryOp::Add } else { BinaryOp::Sub };
+            node = Node::Binary(op, Box::new(node), Box::new(self.product()?));
+        }
+        Ok(node)
+    }
+
+    fn product(&mut self) -> Result<Node, String> {
+        let mut node = self.unary()?;
+        while let Some(symbol) = self.accept_symbol(&["*", "/", "%"]) {
+            let op = match symbol {
+                "*" => BinaryOp::Mul,
+                "/" => BinaryOp::Div,
+                _ => BinaryOp::Rem,
+            };
+            node = Node::Binary(op, Box::new(node), Box::new(self.unary()?));
+        }
+        Ok(node)
+    }
+
+    fn unary(&mut self) -> Result<Node, String> {
+        if self.accept_symbol(&["-"]).is_some() {
+            return Ok(Node::Neg(Box::new(self.unary()?)));
+        }
+        if self.accept_word("not") {
+            return Ok(Node::Not(Box::new(self.unary()?)));
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Node, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Node::Literal(Value::Number(n))),
+            Some(Token::Text(text)) => Ok(Node::Literal(Value::Text(text))),
+            Some(Token::Symbol("(")) => {
+                let node = self.expression()?;
+                self.expect_symbol(")")?;
+                Ok(node)
+            }
+            Some(Token::Word(word)) => match word.as_str() {
+                "true" => Ok(Node::Literal(Value::Bool(true))),
+                "false" => Ok(Node::Literal(Value::Bool(false))),
+                "if" | "then" | "else" | "and" | "or" | "not" => Err(format!("has '{word}' where a value belongs")),
+                _ if self.accept_symbol(&["("]).is_some() => {
+                    if !FUNCTIONS.contains(&word.as_str()) {
+                        return Err(format!("calls unknown function '{word}' (expected abs, min, or max)"));
+                    }
+                    let mut args = vec![self.expression()?];
+                    while self.accept_symbol(&[","]).is_some() {
+                        args.push(self.expression()?);
+                    }
+                    self.expect_symbol(")")?;
+                    Ok(Node::Call(word, args))
+                }
+                _ => Ok(Node::Component(word)),
+            },
+            Some(token) => Err(format!("has {} where a value belongs", token.describe())),
+            None => Err("ends where a value belongs".to_string()),
+        }
+    }
+}
+
+fn collect_components<'a>(node: &'a Node, ids: &mut Vec<&'a str>) {
+    match node {
+        Node::Literal(_) => {}
+        Node::Component(id) => {
+            if !ids.contains(&id.as_str()) {
+                ids.push(id);
+            }
+        }
+        Node::Neg(inner) | Node::Not(inner) => collect_components(inner, ids),
+        Node::Binary(_, left, right) => {
+            collect_components(left, ids);
+            collect_components(right, ids);
+        }
+        Node::If(condition, then, otherwise) => {
+            collect_components(condition, ids);
+            collect_components(then, ids);
+            collect_components(otherwise, ids);
+        }
+        Node::Call(_, args) => args.iter().for_each(|arg| collect_components(arg, ids)),
+    }
+}
+
+fn check_node(node: &Node, component_type: &impl Fn(&str) -> Option<ValueType>) -> Result<ValueType, String> {
+    let require = |found: ValueType, expected: ValueType, role: &str| -> Result<(), String> {
+        if found != expected {
+            return Err(format!("uses {} as {role}, which must be {}", found.as_str(), expected.as_str()));
+        }
+        Ok(())
+    };
+    match node {
+        Node::Literal(Value::Number(_)) => Ok(ValueType::Number),
+        Node::Literal(Value::Text(_)) => Ok(ValueType::Text),
+        Node::Literal(Value::Bool(_)) => Ok(ValueType::Bool),
+        Node::Component(id) => component_type(id).ok_or_else(|| format!("references unknown component '{id}'")),
+        Node::Neg(inner) => {
+            require(check_node(inner, component_type)?, ValueType::Number, "the operand of '-'")?;
+            Ok(ValueType::Number)
+        }
+        Node::Not(inner) => {
+            require(check_node(inner, component_type)?, ValueType::Bool, "the operand of 'not'")?;
+            Ok(ValueType::Bool)
+        }
+        Node::Binary(op, left, right) => {
+            let left = check_node(left, component_type)?;
+            let right = check_node(right, component_type)?;
+            match op {
+                // Adding anything to text joins them, e.g. `"+" + lead`.
+                BinaryOp::Add if left == ValueType::Text || right == ValueType::Text => Ok(ValueType::Text),
+                BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => {
+                    require(left, ValueType::Number, "an arithmetic operand")?;
+                    require(right, ValueType::Number, "an arithmetic operand")?;
+                    Ok(ValueType::Number)
+                }
+                BinaryOp::Eq | BinaryOp::Ne | BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => {
+                    if left != right {
+                        return Err(format!("compares {} with {}", left.as_str(), right.as_str()));
+                    }
+                    if left == ValueType::Bool && !matches!(op, BinaryOp::Eq | BinaryOp::Ne) {
+                        return Err("orders conditions, which can only be compared with == or !=".to_string());
+                    }
+                    Ok(ValueType::Bool)
+                }
+                BinaryOp::And | BinaryOp::Or => {
+                    require(left, ValueType::Bool, "an operand of 'and' or 'or'")?;
+                    require(right, ValueType::Bool, "an operand of 'and' or 'or'")?;
+                    Ok(ValueType::Bool)
+                }
+            }
+        }
+        Node::If(condition, then, otherwise) => {
+            require(check_node(condition, component_type)?, ValueType::Bool, "the condition of 'if'")?;
+            let then = check_node(then, component_type)?;
+            let otherwise = check_node(otherwise, component_type)?;
+            // Branches of different types are both shown as text.
+            Ok(if then == otherwise { then } else { ValueType::Text })
+        }
+        Node::Call(name, args) => {
+            if name == "abs" && args.len() != 1 {
+                return Err("calls abs with more than one value".to_string());
+            }
+            for arg in args {
+                require(check_node(arg, component_type)?, ValueType::Number, &format!("a value of {name}"))?;
+            }
+            Ok(ValueType::Number)
+        }
+    }
+}
+
+fn eval_node(node: &Node, component_value: &impl Fn(&str) -> Option<Value>) -> Option<Value> {
+    let number = |node: &Node| match eval_node(node, component_value)? {
+        Value::Number(n) => Some(n),
+        _ => None,
+    };
+    let condition = |node: &Node| match eval_node(node, component_value)? {
+        Value::Bool(b) => Some(b),
+        _ => None,
+    };
+    Some(match node {
+        Node::Literal(value) => value.clone(),
+        Node::Component(id) => component_value(id)?,
+        Node::Neg(inner) => Value::Number(number(inner)?.checked_neg()?),
+        Node::Not(inner) => Value::Bool(!condition(inner)?),
+        // Evaluated lazily so `a and b` skips `b` like the operator suggests.
+        Node::Binary(BinaryOp::And, left, right) => Value::Bool(condition(left)? && condition(right)?),
+        Node::Binary(BinaryOp::Or, left, right) => Value::Bool(condition(left)? || condition(right)?),
+        Node::Binary(op, left, right) => {
+            let left = eval_node(left, component_value)?;
+            let right = eval_node(right, component_value)?;
+            match (op, left, right) {
+                (BinaryOp::Add, Value::Number(a), Value::Number(b)) => Value::Number(a.checked_add(b)?),
+                (BinaryOp::Add, a, b) => Value::Text(format!("{a}{b}")),
+                (BinaryOp::Sub, Value::Number(a), Value::Number(b)) => Value::Number(a.checked_sub(b)?),
+                (BinaryOp::Mul, Value::Number(a), Value::Number(b)) => Value::Number(a.checked_mul(b)?),
+                (BinaryOp::Div, Value::Number(a), Value::Number(b)) => Value::Number(a.checked_div(b)?),
+                (BinaryOp::Rem, Value::Number(a), Value::Number(b)) => Value::Number(a.checked_rem(b)?),
+                (BinaryOp::Eq, a, b) => Value::Bool(a == b),
+                (BinaryOp::Ne, a, b) => Value::Bool(a != b),
+                (op, Value::Number(a), Value::Number(b)) => Value::Bool(compare(*op, a.cmp(&b))?),
+                (op, Value::Text(a), Value::Text(b)) => Value::Bool(compare(*op, a.cmp(&b))?),
+                _ => return None,
+            }
+        }
+        Node::If(cond, then, otherwise) => {
+            if condition(cond)? {
+                eval_node(then, component_value)?
+            } else {
+                eval_node(otherwise, component_value)?
+            }
+        }
+        Node::Call(name, args) => {
+            let values = args.iter().map(number).collect::<Option<Vec<i64>>>()?;
+            Value::Number(match name.as_str() {
+                "abs" => values.first()?.checked_abs()?,
+                "min" => values.into_iter().min()?,
+                _ => values.into_iter().max()?,
+            })
+        }
+    })
+}
+
+fn compare(op: BinaryOp, ordering: std::cmp::Ordering) -> Option<bool> {
+    Some(match op {
+        BinaryOp::Lt => ordering.is_lt(),
+        BinaryOp::Le => ordering.is_le(),
+        BinaryOp::Gt => ordering.is_gt(),
+        BinaryOp::Ge => ordering.is_ge(),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(source: &str, values: &[(&str, Value)]) -> Option<Value> {
+        let expression = Expression::parse(source).unwrap();
+        expression.eval(&|id| values.iter().find(|(name, _)| *name == id).map(|(_, value)| value.clone()))
+    }
+
+    fn check(source: &str) -> Result<ValueType, String> {
+        let component_type = |id: &str| match id {
+            "home" | "away" => Some(ValueType::Number),
+            "team" => Some(ValueType::Text),
+            _ => None,
+        };
+        Expression::parse(source)?.check(&component_type)
+    }
+
+    #[test]
+    fn tokenizes_numbers_words_text_and_symbols() {
+        assert_eq!(
+            tokenize("home_1 >= 12 + 'a b'").unwrap(),
+            vec![
+                Token::Word("home_1".to_string()),
+                Token::Symbol(">="),
+                Token::Number(12),
+                Token::Symbol("+"),
+                Token::Text("a b".to_string()),
+            ]
+        );
+        assert_eq!(tokenize("\"it's\"").unwrap(), vec![Token::Text("it's".to_string())]);
+        assert_eq!(tokenize("  ").unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn rejects_bad_tokens() {
+        assert_eq!(tokenize("home & away").unwrap_err(), "has unexpected '&'");
+        assert_eq!(tokenize("\"open").unwrap_err(), "has a string without a closing \"");
+        assert!(tokenize("99999999999999999999").unwrap_err().starts_with("has a number too large"));
+    }
+
+    #[test]
+    fn parses_with_precedence() {
+        assert_eq!(eval("1 + 2 * 3", &[]), Some(Value::Number(7)));
+        assert_eq!(eval("(1 + 2) * 3", &[]), Some(Value::Number(9)));
+        assert_eq!(eval("-2 - -3", &[]), Some(Value::Number(1)));
+        assert_eq!(eval("1 < 2 and not false or false", &[]), Some(Value::Bool(true)));
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        assert_eq!(Expression::parse("1 +").unwrap_err(), "ends where a value belongs");
+        assert_eq!(Expression::parse("1 2").unwrap_err(), "has unexpected '2' at the end");
+        assert_eq!(Expression::parse("(1").unwrap_err(), "expects ')' but ends");
+        assert_eq!(Expression::parse("if 1 else 2").unwrap_err(), "expects 'then' but has 'else'");
+        assert_eq!(Expression::parse("then").unwrap_err(), "has 'then' where a value belongs");
+        assert!(Expression::parse("sqrt(4)").unwrap_err().starts_with("calls unknown function 'sqrt'"));
+    }
+
+    #[test]
+    fn limits_length_and_nesting() {
+        let long = "1+".repeat(MAX_EXPRESSION_LENGTH / 2) + "1";
+        assert_eq!(
+            Expression::parse(&long).unwrap_err(),
+            format!("is longer than {MAX_EXPRESSION_LENGTH} characters")
+        );
+        let nested = |depth: usize| format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(Expression::parse(&nested(MAX_NESTING - 1)).is_ok());
+        assert_eq!(
+            Expression::parse(&nested(MAX_NESTING)).unwrap_err(),
+            format!("is nested more than {MAX_NESTING} levels deep")
+        );
+    }
+
+    #[test]
+    fn lists_components_once_in_order() {
+        let expression = Expression::parse("if home > away then home - away else away").unwrap();
+        assert_eq!(expression.components(), vec!["home", "away"]);
+        assert_eq!(expression.source(), "if home > away then home - away else away");
+    }
+
+    #[test]
+    fn checks_types() {
+        assert_eq!(check("home - away"), Ok(ValueType::Number));
+        assert_eq!(check("\"+\" + home"), Ok(ValueType::Text));
+        assert_eq!(check("if home > 7 then \"BONUS\" else 0"), Ok(ValueType::Text));
+        assert_eq!(check("home == team").unwrap_err(), "compares a number with text");
+        assert_eq!(check("fouls + 1").unwrap_err(), "references unknown component 'fouls'");
+        assert_eq!(check("abs(home, away)").unwrap_err(), "calls abs with more than one value");
+        assert_eq!(check("true < false").unwrap_err(), "orders conditions, which can only be compared with == or !=");
+    }
+
+    #[test]
+    fn evaluates_against_component_values() {
+        let values = [
+            ("home", Value::Number(52)),
+            ("away", Value::Number(48)),
+            ("team", Value::Text("Lions".to_string())),
+        ];
+        assert_eq!(eval("home - away", &values), Some(Value::Number(4)));
+        assert_eq!(eval("team + \" lead\"", &values), Some(Value::Text("Lions lead".to_string())));
+        assert_eq!(eval("max(home, away, 60) + min(1, 2) + abs(away - home)", &values), Some(Value::Number(65)));
+        assert_eq!(eval("if home >= away then \"HOME\" else \"AWAY\"", &values), Some(Value::Text("HOME".to_string())));
+        assert_eq!(eval("home + missing", &values), None);
+    }
+
+    #[test]
+    fn leaves_undefined_results_blank() {
+        assert_eq!(eval("1 / 0", &[]), None);
+        assert_eq!(eval("1 % 0", &[]), None);
+        assert_eq!(eval(&format!("{} + 1", i64::MAX), &[]), None);
+        assert_eq!(eval(&format!("-(0 - {} - 1)", i64::MAX), &[]), None);
+        // `and` skips its right side once the left is false.
+        assert_eq!(eval("false and 1 / 0 > 0", &[]), Some(Value::Bool(false)));
+    }
+}
@@ -0,0 +1,47 @@
+//! The board's logic without the desktop app: loading configs, running the
+//! board's values and clocks, and turning them into the snapshot every
+//! frontend draws. It has no Tauri types, so a CLI renderer, an LED driver
+//! or a test can use the same code as the app.
+//!
+//! The stable API is:
+//!
+//! - [`config::load_config_from_path`] and [`config::load_config_from_str`]
+//!   parse and validate a config, resolving its layout and relative paths.
+//!   [`config::ScoreboardConfig::with_layout`] switches to another layout of
+//!   the same file, and [`config::diagnose_config_str`] lists every problem
+//!   instead of stopping at the first.
+//! - [`config::serialize_config`] writes a config back to TOML.
+//! - [`state::RuntimeState`] holds a loaded config and the board's values.
+//!   [`state::RuntimeState::replace_config`] loads a config,
+//!   [`state::RuntimeState::resolve_action`] and
+//!   [`state::RuntimeState::apply_action`] change values,
+//!   [`state::RuntimeState::tick_timers`] advances running clocks, and
+//!   [`state::RuntimeState::export_values`] and
+//!   [`state::RuntimeState::restore_values`] save and restore them.
+//! - [`state::RuntimeState::snapshot`] returns a [`state::UiSnapshot`]: every
+//!   visible component with its position, style and formatted value, ready to
+//!   draw. It serializes to the JSON the app's window receives.
+//! - [`state::format_ms`] formats a clock reading as a timer shows it.
+//!
+//! Everything else that is `pub` may change between versions.
+//!
+//! ```no_run
+//! use scoreboard_core::config::load_config_from_path;
+//! use scoreboard_core::state::RuntimeState;
+//! use std::path::Path;
+//!
+//! let config = load_config_from_path(Path::new("scoreboard.toml"))?;
+//! let mut runtime = RuntimeState::new();
+//! runtime.replace_config(config);
+//! if let Some(action) = runtime.resolve_action("home_score.increase") {
+//!     runtime.apply_action(&action);
+//! }
+//! for component in runtime.snapshot().components {
+//!     println!("{}: {}", component.id, component.text.unwrap_or_default());
+//! }
+//! # Ok::<(), String>(())
+//! ```
+
+pub mod config;
+pub mod expr;
+pub mod state;
@@ -0,0 +1,3347 @@
+use crate::config::{
+    ComponentConfig, ComponentKind, ConfirmSettings, KeyRepeat, PeriodAdvance, PossessionDisplay, RelayOutput, ScoreboardConfig, SummarySettings,
+    TextFit, TextOutline, TextShadow, TimerRounding, DEFAULT_CANVAS_HEIGHT, DEFAULT_CANVAS_WIDTH, MAX_TICKER_MESSAGES, validate_ticker_message,
+    validate_timer_display_offset, component_action_names, image_toggle_set_index, VISIBILITY_ACTION,
+};
+use crate::expr::Value;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// The event log keeps one entry per applied action, so it is capped well
+/// above the summary's list of moments; past this the oldest action goes.
+/// The two stay separate because the summary's moments (lead changes,
+/// timeouts called, player actions) come from comparing whole snapshots,
+/// which a single logged action does not say anything about.
+const MAX_EVENT_LOG: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    NumberIncrease { id: String },
+    NumberDecrease { id: String },
+    NumberReset { id: String },
+    TimerStart { id: String },
+    TimerStop { id: String },
+    TimerReset { id: String },
+    TimerIncrease { id: String },
+    TimerDecrease { id: String },
+    ImageToggleForward { id: String },
+    ImageToggleBackward { id: String },
+    ImageToggleSet { id: String, index: usize },
+    ImageToggleStart { id: String },
+    ImageToggleStop { id: String },
+    PeriodForward { id: String },
+    PeriodBackward { id: String },
+    PossessionToggle { id: String },
+    PossessionTimeHome { id: String },
+    PossessionTimeAway { id: String },
+    PossessionTimeReset { id: String },
+    VideoPlay { id: String },
+    VideoPause { id: String },
+    ToggleVisibility { id: String },
+    ToggleFullscreen,
+    PlayerSelect,
+    Announce { name: String },
+    AuxTimerStart { name: String },
+    AuxTimerStop { name: String },
+    AuxTimerReset { name: String },
+    /// Fires the steps of `[macro.<name>]`.
+    Macro { name: String },
+    PlaylistPlay,
+    PlaylistPause,
+    PlaylistNext,
+    CycleTheme,
+    LockBoard,
+    ShowLiveScene,
+}
+
+impl Action {
+    /// Returns the component id and the config action name (as used in
+    /// `keybind.<name>` and `sound.<name>`) for component-level actions.
+    pub fn component_action(&self) -> Option<(&str, &'static str)> {
+        match self {
+            Action::NumberIncrease { id } => Some((id, "increase")),
+            Action::NumberDecrease { id } => Some((id, "decrease")),
+            Action::NumberReset { id } => Some((id, "reset")),
+            Action::TimerStart { id } => Some((id, "start")),
+            Action::TimerStop { id } => Some((id, "stop")),
+            Action::TimerReset { id } => Some((id, "reset")),
+            Action::TimerIncrease { id } => Some((id, "increase")),
+            Action::TimerDecrease { id } => Some((id, "decrease")),
+            Action::ImageToggleForward { id } => Some((id, "forward")),
+            Action::ImageToggleBackward { id } => Some((id, "backward")),
+            Action::ImageToggleStart { id } => Some((id, "start")),
+            Action::ImageToggleStop { id } => Some((id, "stop")),
+            Action::PeriodForward { id } => Some((id, "forward")),
+            Action::PeriodBackward { id } => Some((id, "backward")),
+            Action::PossessionToggle { id } => Some((id, "toggle")),
+            Action::PossessionTimeHome { id } => Some((id, "home")),
+            Action::PossessionTimeAway { id } => Some((id, "away")),
+            Action::PossessionTimeReset { id } => Some((id, "reset")),
+            Action::VideoPlay { id } => Some((id, "play")),
+            Action::VideoPause { id } => Some((id, "pause")),
+            // Jumps are bound per index, so they take no sounds or confirmation.
+            Action::ImageToggleSet { .. }
+            | Action::ToggleVisibility { .. }
+            | Action::ToggleFullscreen
+            | Action::PlayerSelect
+            | Action::Announce { .. }
+            | Action::AuxTimerStart { .. }
+            | Action::AuxTimerStop { .. }
+            | Action::AuxTimerReset { .. }
+            | Action::Macro { .. }
+            | Action::PlaylistPlay
+            | Action::PlaylistPause
+            | Action::PlaylistNext
+            | Action::CycleTheme
+            | Action::LockBoard
+            | Action::ShowLiveScene => None,
+        }
+    }
+
+    /// The action as it is bound in the config, e.g. `home_score.increase`
+    /// or `fullscreen`, for showing to the operator.
+    pub fn describe(&self) -> String {
+        if let Some((id, name)) = self.component_action() {
+            return format!("{id}.{name}");
+        }
+        match self {
+            Action::ImageToggleSet { id, index } => format!("{id}.set_{index}"),
+            Action::ToggleVisibility { id } => format!("{id}.visibility"),
+            Action::ToggleFullscreen => "fullscreen".to_string(),
+            Action::PlayerSelect => "player_select".to_string(),
+            Action::Announce { name } => format!("announcement.{name}"),
+            Action::AuxTimerStart { name } => format!("aux_timer.{name}.start"),
+            Action::AuxTimerStop { name } => format!("aux_timer.{name}.stop"),
+            Action::AuxTimerReset { name } => format!("aux_timer.{name}.reset"),
+            Action::Macro { name } => format!("macro.{name}"),
+            Action::PlaylistPlay => "playlist.play".to_string(),
+            Action::PlaylistPause => "playlist.pause".to_string(),
+            Action::PlaylistNext => "playlist.next".to_string(),
+            Action::CycleTheme => "theme".to_string(),
+            Action::LockBoard => "lock".to_string(),
+            Action::ShowLiveScene => "ready".to_string(),
+            _ => String::new(),
+        }
+    }
+
+    /// The component the action changes, if it changes one.
+    pub fn component_id(&self) -> Option<&str> {
+        match self {
+            Action::ImageToggleSet { id, .. } | Action::ToggleVisibility { id } => Some(id),
+            action => action.component_action().map(|(id, _)| id),
+        }
+    }
+
+    /// Whether the action runs a clock: a timer component's or an aux timer's.
+    pub fn is_timer(&self) -> bool {
+        matches!(
+            self,
+            Action::TimerStart { .. }
+                | Action::TimerStop { .. }
+                | Action::TimerReset { .. }
+                | Action::TimerIncrease { .. }
+                | Action::TimerDecrease { .. }
+                | Action::AuxTimerStart { .. }
+                | Action::AuxTimerStop { .. }
+                | Action::AuxTimerReset { .. }
+        )
+    }
+
+    /// Resolves a config action name for a component; the inverse of `component_action`.
+    pub fn from_component_action(kind: &ComponentKind, id: &str, name: &str) -> Option<Action> {
+        let id = id.to_string();
+        let action = match (kind, name) {
+            (ComponentKind::Number { .. } | ComponentKind::Fouls { .. }, "increase") => Action::NumberIncrease { id },
+            (ComponentKind::Number { .. } | ComponentKind::Fouls { .. }, "decrease") => Action::NumberDecrease { id },
+            (ComponentKind::Number { .. } | ComponentKind::Fouls { .. }, "reset") => Action::NumberReset { id },
+            (ComponentKind::Timer { .. }, "start") => Action::TimerStart { id },
+            (ComponentKind::Timer { .. }, "stop") => Action::TimerStop { id },
+            (ComponentKind::Timer { .. }, "reset") => Action::TimerReset { id },
+            (ComponentKind::Timer { .. }, "increase") => Action::TimerIncrease { id },
+            (ComponentKind::Timer { .. }, "decrease") => Action::TimerDecrease { id },
+            (ComponentKind::ImageToggle { .. }, "forward") => Action::ImageToggleForward { id },
+            (ComponentKind::ImageToggle { .. }, "backward") => Action::ImageToggleBackward { id },
+            (ComponentKind::ImageToggle { interval_ms: Some(_), .. }, "start") => Action::ImageToggleStart { id },
+            (ComponentKind::ImageToggle { interval_ms: Some(_), .. }, "stop") => Action::ImageToggleStop { id },
+            (ComponentKind::Period { .. }, "forward") => Action::PeriodForward { id },
+            (ComponentKind::Period { .. }, "backward") => Action::PeriodBackward { id },
+            (ComponentKind::Possession { .. }, "toggle") => Action::PossessionToggle { id },
+            (ComponentKind::PossessionTime { .. }, "home") => Action::PossessionTimeHome { id },
+            (ComponentKind::PossessionTime { .. }, "away") => Action::PossessionTimeAway { id },
+            (ComponentKind::PossessionTime { .. }, "reset") => Action::PossessionTimeReset { id },
+            (ComponentKind::Video { .. }, "play") => Action::VideoPlay { id },
+            (ComponentKind::Video { .. }, "pause") => Action::VideoPause { id },
+            _ => return None,
+        };
+        Some(action)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TimerTick {
+    pub changed: bool,
+    pub expired: Vec<String>,
+    /// Armed actions that were committed because their confirmation timed out.
+    pub committed: Vec<Action>,
+    /// Running timers that passed a whole second below their `tick_below` threshold.
+    pub ticked: Vec<String>,
+    /// Running timers that reached one of their `voice_cues`, with the cue in ms.
+    pub voiced: Vec<(String, i64)>,
+    /// Running timers that reached one of their `t_minus` offsets.
+    pub t_minus: Vec<TMinusCue>,
+}
+
+/// A timer passing one of its `t_minus` offsets before expiry.
+#[derive(Debug, Clone)]
+pub struct TMinusCue {
+    pub id: String,
+    pub offset_ms: i64,
+    /// Time actually left when the cue was noticed, at most one tick below `offset_ms`.
+    pub remaining_ms: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExpireTrigger {
+    pub sound: Option<String>,
+    pub relay: Option<RelayOutput>,
+    pub duration_ms: Option<u64>,
+}
+
+/// One applied action in the event log, with the board as it stood right after.
+#[derive(Debug, Clone, Serialize)]
+pub struct LoggedEvent {
+    /// Unix time in milliseconds.
+    pub timestamp_ms: u64,
+    /// The action as `Action::describe` names it, e.g. `home_score.increase`.
+    pub action: String,
+    pub component: Option<String>,
+    /// The component's displayed value after the action, or its image path.
+    pub value: Option<String>,
+    /// The period label and game clock at the time, from `[global.summary]`
+    /// or else the config's first period and timer.
+    pub period: Option<String>,
+    pub clock: Option<String>,
+}
+
+/// What the `number` components gained in one period; losses count negative.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PeriodScore {
+    /// The period component's label, e.g. `3rd`.
+    pub period: String,
+    /// Component id to its net change during the period.
+    pub points: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HotkeyBinding {
+    pub shortcut: String,
+    pub cooldown_ms: Option<u64>,
+    pub repeat: Option<KeyRepeat>,
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UiSnapshot {
+    pub sequence: u64,
+    pub timestamp_ms: u64,
+    pub canvas_width: i32,
+    pub canvas_height: i32,
+    /// Clockwise degrees the frontend turns the canvas by.
+    pub rotate: u16,
+    /// `#RRGGBB`, or `transparent` when the window should render with alpha.
+    pub background_color: String,
+    /// Set when the whole window, not just the canvas, is filled with a key color.
+    pub chroma_key: Option<String>,
+    pub components: Vec<UiComponent>,
+    /// Present while the config's splash scene is shown instead of the live board.
+    pub splash: Option<UiSplash>,
+    /// Opacity the whole board is drawn at while it idles; `None` when awake.
+    pub dim: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UiSplash {
+    /// Image covering the canvas.
+    pub image: Option<String>,
+}
+
+/// Assigns monotonic sequence numbers and timestamps to emitted snapshots so
+/// downstream consumers can order them and detect dropped updates.
+#[derive(Debug)]
+pub struct SnapshotClock {
+    epoch: Instant,
+    sequence: u64,
+}
+
+impl Default for SnapshotClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SnapshotClock {
+    pub fn new() -> Self {
+        Self {
+            epoch: Instant::now(),
+            sequence: 0,
+        }
+    }
+
+    pub fn stamp(&mut self, snapshot: &mut UiSnapshot) {
+        (snapshot.sequence, snapshot.timestamp_ms) = self.next_stamp();
+    }
+
+    /// The next sequence number and timestamp, for snapshots built elsewhere.
+    pub fn next_stamp(&mut self) -> (u64, u64) {
+        self.sequence += 1;
+        (self.sequence, self.epoch.elapsed().as_millis() as u64)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UiComponent {
+    pub id: String,
+    pub component_type: String,
+    pub x: i32,
+    pub y: i32,
+    pub alignment: Option<String>,
+    /// Stacking order; components are listed topmost first.
+    pub layer: i32,
+    /// False while the component or its group is hidden. Hidden components
+    /// keep their value but are not drawn.
+    pub visible: bool,
+    pub font_family: String,
+    pub font_size: i32,
+    pub font_color: String,
+    pub font_weight: u16,
+    pub font_italic: bool,
+    pub letter_spacing: i32,
+    pub outline: Option<TextOutline>,
+    pub shadow: Option<TextShadow>,
+    pub text: Option<String>,
+    /// A timer's actual remaining time. `text` differs from it while a
+    /// running timer is shown ahead or a stopped one is frozen.
+    pub raw_text: Option<String>,
+    pub source: Option<String>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub opacity: Option<f32>,
+    pub editable: bool,
+    /// Name of an action armed on this component and waiting for confirmation.
+    pub pending: Option<String>,
+    /// Set on fouls components once their count reaches the configured thresholds.
+    pub bonus: bool,
+    pub double_bonus: bool,
+    /// Totals for possession-time components.
+    pub possession_time: Option<UiPossessionTime>,
+    /// Messages and scroll speed for ticker components.
+    pub ticker: Option<UiTicker>,
+    /// Playback state for video components.
+    pub video: Option<UiVideo>,
+    /// Line layout for multiline labels.
+    pub wrap: Option<UiWrap>,
+    /// Box the text shrinks into, already scaled with the component's group.
+    /// `font_size` is the largest size to use.
+    pub fit: Option<TextFit>,
+    /// Draw digits with equal widths (tabular figures).
+    pub monospace_digits: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UiWrap {
+    /// Pixels before lines wrap, already scaled with the component's group.
+    /// Without it, lines only break where the text has line breaks.
+    pub max_width: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UiVideo {
+    pub playing: bool,
+    pub looped: bool,
+    pub muted: bool,
+    /// Grows each time `play` restarts the clip from the beginning.
+    pub restarts: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UiTicker {
+    pub messages: Vec<String>,
+    /// Pixels per second, already scaled with the component's group.
+    pub speed: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UiPossessionTime {
+    pub side: Option<PossessionSide>,
+    pub home_ms: i64,
+    pub away_ms: i64,
+    /// Whole percentages that add up to 100, or both 0 before any time is counted.
+    pub home_percent: u32,
+    pub away_percent: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PossessionSide {
+    Home,
+    Away,
+}
+
+#[derive(Debug, Clone)]
+pub struct RuntimeState {
+    pub config: Option<ScoreboardConfig>,
+    number_values: HashMap<String, i32>,
+    timer_values: HashMap<String, TimerRuntime>,
+    label_values: HashMap<String, String>,
+    image_values: HashMap<String, String>,
+    image_toggle_indices: HashMap<String, usize>,
+    /// Automatic rotation of image toggles with `interval_ms`.
+    image_rotations: HashMap<String, ImageRotation>,
+    period_indices: HashMap<String, usize>,
+    possession_indices: HashMap<String, usize>,
+    visibility: HashMap<String, bool>,
+    possession_times: HashMap<String, PossessionTimeRuntime>,
+    ticker_messages: HashMap<String, Vec<String>>,
+    videos: HashMap<String, VideoRuntime>,
+    /// When each component last took a runtime edit, for `rate_limit_ms`.
+    last_edits: HashMap<String, Instant>,
+    pending_actions: HashMap<String, PendingAction>,
+    /// Text shown in place of a label's own value, e.g. while an announcement runs.
+    label_overrides: HashMap<String, String>,
+    /// Name of the `[global.theme.<name>]` palette applied to emitted colors.
+    active_theme: Option<String>,
+    /// Live replacement for `timer_display_offset_ms`, kept across hot reloads.
+    timer_display_offset_override: Option<i64>,
+    /// True from loading a config with `[global.splash]` until going live.
+    splash_shown: bool,
+    /// True while `[global.idle]` has the board in low-power mode.
+    idle: bool,
+    /// Key that `persistent` component values are saved under (the config
+    /// path), or `None` while they are not saved at all.
+    persistent_scope: Option<String>,
+    /// Every applied action since the config was loaded, oldest first.
+    event_log: VecDeque<LoggedEvent>,
+    /// Number changes per period label, in the order periods were first scored in.
+    period_scores: Vec<PeriodScore>,
+}
+
+#[derive(Debug, Clone)]
+struct PendingAction {
+    action: Action,
+    deadline: Instant,
+    commit_on_timeout: bool,
+}
+
+/// Plain runtime values keyed by component id, used to persist and restore
+/// a session independently of the config that produced it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuntimeValues {
+    #[serde(default)]
+    pub numbers: HashMap<String, i32>,
+    #[serde(default)]
+    pub timers: HashMap<String, i64>,
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    #[serde(default)]
+    pub images: HashMap<String, String>,
+    #[serde(default)]
+    pub image_toggle_indices: HashMap<String, usize>,
+    #[serde(default)]
+    pub period_indices: HashMap<String, usize>,
+    #[serde(default)]
+    pub possession_indices: HashMap<String, usize>,
+    #[serde(default)]
+    pub visibility: HashMap<String, bool>,
+    #[serde(default)]
+    pub possession_times: HashMap<String, PossessionTimeValues>,
+    #[serde(default)]
+    pub tickers: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PossessionTimeValues {
+    pub home_ms: i64,
+    pub away_ms: i64,
+}
+
+#[derive(Debug, Clone)]
+struct TimerRuntime {
+    remaining_ms: i64,
+    /// Value restored by `TimerReset`; a linked period can change it per period.
+    reset_ms: i64,
+    running: bool,
+    last_tick: Option<Instant>,
+    /// Total time the timer has counted down while running. Only grows, so
+    /// possession time can follow the clock regardless of manual adjustments.
+    run_ms: i64,
+    /// Value shown while stopped, for `freeze_on_stop` timers. Cleared when
+    /// the timer starts or resets.
+    frozen_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+struct ImageRotation {
+    interval: Duration,
+    /// When the next source comes up, or `None` while rotation is stopped.
+    next_advance: Option<Instant>,
+}
+
+impl ImageRotation {
+    /// Gives the current source a full interval again, e.g. after the
+    /// operator moved the toggle by hand.
+    fn restart(&mut self, now: Instant) {
+        if self.next_advance.is_some() {
+            self.next_advance = Some(now + self.interval);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct VideoRuntime {
+    playing: bool,
+    restarts: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+struct PossessionTimeRuntime {
+    side: Option<PossessionSide>,
+    home_ms: i64,
+    away_ms: i64,
+    /// The clock's `run_ms` when time was last attributed.
+    clock_mark: i64,
+}
+
+impl Default for RuntimeState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RuntimeState {
+    pub fn new() -> Self {
+        Self {
+            config: None,
+            number_values: HashMap::new(),
+            timer_values: HashMap::new(),
+            label_values: HashMap::new(),
+            image_values: HashMap::new(),
+            image_toggle_indices: HashMap::new(),
+            image_rotations: HashMap::new(),
+            period_indices: HashMap::new(),
+            possession_indices: HashMap::new(),
+            visibility: HashMap::new(),
+            possession_times: HashMap::new(),
+            ticker_messages: HashMap::new(),
+            videos: HashMap::new(),
+            last_edits: HashMap::new(),
+            pending_actions: HashMap::new(),
+            label_overrides: HashMap::new(),
+            active_theme: None,
+            timer_display_offset_override: None,
+            splash_shown: false,
+            idle: false,
+            persistent_scope: None,
+            event_log: VecDeque::new(),
+            period_scores: Vec::new(),
+        }
+    }
+
+    pub fn replace_config(&mut self, config: ScoreboardConfig) {
+        self.number_values.clear();
+        self.timer_values.clear();
+        self.label_values.clear();
+        self.image_values.clear();
+        self.image_toggle_indices.clear();
+        self.image_rotations.clear();
+        self.period_indices.clear();
+        self.possession_indices.clear();
+        self.visibility.clear();
+        self.possession_times.clear();
+        self.ticker_messages.clear();
+        self.videos.clear();
+        self.last_edits.clear();
+        self.pending_actions.clear();
+        self.label_overrides.clear();
+        self.active_theme = None;
+        self.timer_display_offset_override = None;
+        self.splash_shown = config.global.splash.is_some();
+        self.idle = false;
+        self.persistent_scope = None;
+        self.event_log.clear();
+        self.period_scores.clear();
+
+        for component in &config.components {
+            self.visibility.insert(component.id.clone(), component.visible);
+            match &component.kind {
+                ComponentKind::Number { default, .. } | ComponentKind::Fouls { default, .. } => {
+                    self.number_values.insert(component.id.clone(), *default);
+                }
+                // Mirrors read their primary's runtime, so there is nothing to keep in sync.
+                ComponentKind::Timer { mirror: Some(_), .. } => {}
+                ComponentKind::Timer { default_ms, .. } => {
+                    self.timer_values.insert(
+                        component.id.clone(),
+                        TimerRuntime {
+                            remaining_ms: *default_ms,
+                            reset_ms: *default_ms,
+                            running: false,
+                            last_tick: None,
+                            run_ms: 0,
+                            frozen_ms: None,
+                        },
+                    );
+                }
+                ComponentKind::Label { default, .. } => {
+                    self.label_values.insert(component.id.clone(), default.clone());
+                }
+                ComponentKind::Image { source, .. } => {
+                    self.image_values
+                        .insert(component.id.clone(), source.clone());
+                }
+                ComponentKind::ImageToggle {
+                    default_index,
+                    interval_ms,
+                    ..
+                } => {
+                    self.image_toggle_indices
+                        .insert(component.id.clone(), *default_index);
+                    // Rotation starts with the config, like a video's playback.
+                    if let Some(interval_ms) = interval_ms {
+                        let interval = Duration::from_millis(*interval_ms);
+                        self.image_rotations.insert(
+                            component.id.clone(),
+                            ImageRotation {
+                                interval,
+                                next_advance: Some(Instant::now() + interval),
+                            },
+                        );
+                    }
+                }
+                ComponentKind::Period { default_index, .. } => {
+                    self.period_indices.insert(component.id.clone(), *default_index);
+                }
+                ComponentKind::Possession { default_index, .. } => {
+                    self.possession_indices.insert(component.id.clone(), *default_index);
+                }
+                ComponentKind::PossessionTime { .. } => {
+                    self.possession_times
+                        .insert(component.id.clone(), PossessionTimeRuntime::default());
+                }
+                ComponentKind::Ticker { messages, .. } => {
+                    self.ticker_messages.insert(component.id.clone(), messages.clone());
+                }
+                ComponentKind::Video { .. } => {
+                    self.videos.insert(
+                        component.id.clone(),
+                        VideoRuntime {
+                            playing: true,
+                            restarts: 0,
+                        },
+                    );
+                }
+                // Derived components are computed in every snapshot and keep no value.
+                ComponentKind::Derived { .. } => {}
+            }
+        }
+
+        self.config = Some(config);
+        self.sync_period_timer_resets();
+    }
+
+    /// Like `replace_config`, but components that keep their id and type also
+    /// keep their current values, so editing the layout mid-game does not
+    /// reset scores or running timers.
+    pub fn reload_config(&mut self, config: ScoreboardConfig) {
+        let mut previous = std::mem::take(self);
+        previous.accrue_possession_time();
+        self.replace_config(config);
+        // A layout edit mid-game is still the same game.
+        self.event_log = std::mem::take(&mut previous.event_log);
+        self.period_scores = std::mem::take(&mut previous.period_scores);
+        // Editing the layout mid-game must not bring the splash back.
+        if previous.config.is_some() {
+            self.splash_shown &= previous.splash_shown;
+        }
+        let (Some(previous_config), Some(config)) = (&previous.config, &self.config) else {
+            return;
+        };
+
+        for component in &config.components {
+            // Shown or hidden by the operator, whatever the component's type now.
+            if let Some(visible) = previous.visibility.get(&component.id) {
+                self.visibility.insert(component.id.clone(), *visible);
+            }
+            let same_type = previous_config.components.iter().any(|old| {
+                old.id == component.id && std::mem::discriminant(&old.kind) == std::mem::discriminant(&component.kind)
+            });
+            if !same_type {
+                continue;
+            }
+            let id = &component.id;
+            match &component.kind {
+                ComponentKind::Number { .. } | ComponentKind::Fouls { .. } => {
+                    if let Some(value) = previous.number_values.get(id) {
+                        self.number_values.insert(id.clone(), *value);
+                    }
+                }
+                ComponentKind::Timer { freeze_on_stop, .. } => {
+                    if let (Some(old), Some(timer)) = (previous.timer_values.get(id), self.timer_values.get_mut(id)) {
+                        timer.remaining_ms = old.remaining_ms;
+                        timer.running = old.running;
+                        timer.last_tick = old.last_tick;
+                        timer.run_ms = old.run_ms;
+                        timer.frozen_ms = old.frozen_ms.filter(|_| *freeze_on_stop);
+                    }
+                }
+                ComponentKind::Label { .. } => {
+                    if let Some(value) = previous.label_values.get(id) {
+                        self.label_values.insert(id.clone(), value.clone());
+                    }
+                }
+                ComponentKind::Image { .. } => {
+                    if let Some(value) = previous.image_values.get(id) {
+                        self.image_values.insert(id.clone(), value.clone());
+                    }
+                }
+                ComponentKind::ImageToggle { sources, .. } => {
+                    if let Some(index) = previous.image_toggle_indices.get(id).filter(|index| **index < sources.len()) {
+                        self.image_toggle_indices.insert(id.clone(), *index);
+                    }
+                    // A stopped rotation stays stopped; a running one keeps its
+                    // place unless the interval changed.
+                    if let (Some(old), Some(rotation)) = (previous.image_rotations.get(id), self.image_rotations.get_mut(id)) {
+                        if old.next_advance.is_none() {
+                            rotation.next_advance = None;
+                        } else if old.interval == rotation.interval {
+                            rotation.next_advance = old.next_advance;
+                        }
+                    }
+                }
+                ComponentKind::Period { labels, .. } => {
+                    if let Some(index) = previous.period_indices.get(id).filter(|index| **index < labels.len()) {
+                        self.period_indices.insert(id.clone(), *index);
+                    }
+                }
+                ComponentKind::Possession { .. } => {
+                    if let Some(index) = previous.possession_indices.get(id) {
+                        self.possession_indices.insert(id.clone(), *index);
+                    }
+                }
+                ComponentKind::PossessionTime { .. } => {
+                    if let Some(tracker) = previous.possession_times.get(id) {
+                        // The clock may have changed too; counting resumes from its current run time.
+                        let tracker = PossessionTimeRuntime {
+                            clock_mark: i64::MAX,
+                            ..tracker.clone()
+                        };
+                        self.possession_times.insert(id.clone(), tracker);
+                    }
+                }
+                ComponentKind::Ticker { .. } => {
+                    if let Some(messages) = previous.ticker_messages.get(id) {
+                        self.ticker_messages.insert(id.clone(), messages.clone());
+                    }
+                }
+                ComponentKind::Video { .. } => {
+                    if let Some(video) = previous.videos.get(id) {
+                        self.videos.insert(id.clone(), video.clone());
+                    }
+                }
+                ComponentKind::Derived { .. } => {}
+            }
+        }
+        // Linked timers reset to the duration of the period they kept.
+        self.sync_period_timer_resets();
+        self.last_edits = previous.last_edits;
+        self.persistent_scope = previous.persistent_scope;
+        self.timer_display_offset_override = previous.timer_display_offset_override;
+        if let Some(theme) = previous.active_theme {
+            let _ = self.set_theme(Some(&theme));
+        }
+    }
+
+    pub fn export_values(&self) -> RuntimeValues {
+        let now = Instant::now();
+        RuntimeValues {
+            numbers: self.number_values.clone(),
+            timers: self
+                .timer_values
+                .iter()
+                .map(|(id, timer)| {
+                    let mut timer = timer.clone();
+                    sync_timer(&mut timer, now);
+                    (id.clone(), timer.remaining_ms)
+                })
+                .collect(),
+            labels: self.label_values.clone(),
+            images: self.image_values.clone(),
+            image_toggle_indices: self.image_toggle_indices.clone(),
+            period_indices: self.period_indices.clone(),
+            possession_indices: self.possession_indices.clone(),
+            visibility: self.visibility.clone(),
+            possession_times: self
+                .possession_times
+                .iter()
+                .map(|(id, tracker)| {
+                    let values = PossessionTimeValues {
+                        home_ms: tracker.home_ms,
+                        away_ms: tracker.away_ms,
+                    };
+                    (id.clone(), values)
+                })
+                .collect(),
+            tickers: self.ticker_messages.clone(),
+        }
+    }
+
+    /// Applies saved values to components that still exist with the same type.
+    /// Restored timers are left stopped so the operator decides when to resume.
+    pub fn restore_values(&mut self, values: &RuntimeValues) {
+        self.pending_actions.clear();
+        // A restored session is a game in progress.
+        self.splash_shown = false;
+        for (id, value) in &values.numbers {
+            if let Some(current) = self.number_values.get_mut(id) {
+                *current = *value;
+            }
+        }
+        for (id, remaining_ms) in &values.timers {
+            if let Some(timer) = self.timer_values.get_mut(id) {
+                timer.remaining_ms = (*remaining_ms).max(0);
+                timer.running = false;
+                timer.last_tick = None;
+                timer.frozen_ms = None;
+            }
+        }
+        for (id, value) in &values.labels {
+            if let Some(current) = self.label_values.get_mut(id) {
+                *current = value.clone();
+            }
+        }
+        for (id, value) in &values.images {
+            if let Some(current) = self.image_values.get_mut(id) {
+                *current = value.clone();
+            }
+        }
+        for (id, visible) in &values.visibility {
+            if let Some(current) = self.visibility.get_mut(id) {
+                *current = *visible;
+            }
+        }
+        for (id, saved) in &values.possession_times {
+            if let Some(tracker) = self.possession_times.get_mut(id) {
+                tracker.home_ms = saved.home_ms.max(0);
+                tracker.away_ms = saved.away_ms.max(0);
+                tracker.side = None;
+            }
+        }
+        for (id, messages) in &values.tickers {
+            if let Some(current) = self.ticker_messages.get_mut(id) {
+                *current = messages.clone();
+            }
+        }
+        let Some(config) = &self.config else {
+            return;
+        };
+        for component in &config.components {
+            match &component.kind {
+                ComponentKind::ImageToggle { sources, .. } => {
+                    if let Some(index) = values.image_toggle_indices.get(&component.id) {
+                        if *index < sources.len() {
+                            self.image_toggle_indices.insert(component.id.clone(), *index);
+                        }
+                    }
+                }
+                ComponentKind::Period { labels, .. } => {
+                    if let Some(index) = values.period_indices.get(&component.id) {
+                        if *index < labels.len() {
+                            self.period_indices.insert(component.id.clone(), *index);
+                        }
+                    }
+                }
+                ComponentKind::Possession { .. } => {
+                    if let Some(index) = values.possession_indices.get(&component.id) {
+                        if *index < 2 {
+                            self.possession_indices.insert(component.id.clone(), *index);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.sync_period_timer_resets();
+    }
+
+    /// Starts saving `persistent` components under `scope`, first restoring
+    /// the values last saved there.
+    pub fn restore_persistent_values(&mut self, scope: String, saved: Option<&RuntimeValues>) {
+        self.persistent_scope = Some(scope);
+        let (Some(saved), Some(config)) = (saved, &self.config) else {
+            return;
+        };
+        for component in config.components.iter().filter(|component| component.persistent) {
+            let id = &component.id;
+            match &component.kind {
+                ComponentKind::Number { .. } => {
+                    if let (Some(value), Some(current)) = (saved.numbers.get(id), self.number_values.get_mut(id)) {
+                        *current = *value;
+                    }
+                }
+                ComponentKind::Label { .. } => {
+                    if let (Some(value), Some(current)) = (saved.labels.get(id), self.label_values.get_mut(id)) {
+                        current.clone_from(value);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Current values of the `persistent` components and the scope they are
+    /// saved under, or `None` if nothing is being saved.
+    pub fn persistent_values(&self) -> Option<(String, RuntimeValues)> {
+        let scope = self.persistent_scope.clone()?;
+        let config = self.config.as_ref()?;
+        let mut values = RuntimeValues::default();
+        for component in config.components.iter().filter(|component| component.persistent) {
+            let id = &component.id;
+            match &component.kind {
+                ComponentKind::Number { .. } => {
+                    if let Some(value) = self.number_values.get(id) {
+                        values.numbers.insert(id.clone(), *value);
+                    }
+                }
+                ComponentKind::Label { .. } => {
+                    if let Some(value) = self.label_values.get(id) {
+                        values.labels.insert(id.clone(), value.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some((scope, values))
+    }
+
+    /// Returns a copy of the active config with each component's default replaced
+    /// by its current runtime value, suitable for saving as a checkpoint.
+    pub fn export_config(&self) -> Option<ScoreboardConfig> {
+        let mut config = self.config.clone()?;
+        for component in &mut config.components {
+            let id = &component.id;
+            if let Some(visible) = self.visibility.get(id) {
+                component.visible = *visible;
+            }
+            match &mut component.kind {
+                ComponentKind::Number { default, .. } | ComponentKind::Fouls { default, .. } => {
+                    if let Some(value) = self.number_values.get(id) {
+                        *default = *value;
+                    }
+                }
+                ComponentKind::Timer { default_ms, .. } => {
+                    if let Some(timer) = self.timer_values.get(id) {
+                        let mut timer = timer.clone();
+                        sync_timer(&mut timer, Instant::now());
+                        *default_ms = timer.remaining_ms;
+                    }
+                }
+                ComponentKind::Label { default, .. } => {
+                    if let Some(value) = self.label_values.get(id) {
+                        *default = value.clone();
+                    }
+                }
+                ComponentKind::Image { source, .. } => {
+                    if let Some(value) = self.image_values.get(id) {
+                        *source = value.clone();
+                    }
+                }
+                ComponentKind::ImageToggle {
+                    sources,
+                    default_index,
+                    ..
+                } => {
+                    if let Some(index) = self.image_toggle_indices.get(id) {
+                        *default_index = *index % sources.len();
+                    }
+                }
+                ComponentKind::Period {
+                    labels,
+                    default_index,
+                    ..
+                } => {
+                    if let Some(index) = self.period_indices.get(id) {
+                        *default_index = (*index).min(labels.len() - 1);
+                    }
+                }
+                ComponentKind::Possession { default_index, .. } => {
+                    if let Some(index) = self.possession_indices.get(id) {
+                        *default_index = *index % 2;
+                    }
+                }
+                // Totals always start from zero when a config is loaded.
+                ComponentKind::PossessionTime { .. } => {}
+                ComponentKind::Ticker { messages, .. } => {
+                    if let Some(current) = self.ticker_messages.get(id) {
+                        *messages = current.clone();
+                    }
+                }
+                // Videos always start playing when a config is loaded.
+                ComponentKind::Video { .. } => {}
+                ComponentKind::Derived { .. } => {}
+            }
+        }
+        Some(config)
+    }
+
+    pub fn set_label_value(&mut self, id: &str, value: String) -> Result<bool, String> {
+        let Some(config) = &self.config else {
+            return Err("No config loaded".to_string());
+        };
+
+        let Some(component) = config.components.iter().find(|c| c.id == id) else {
+            return Err(format!("Unknown component '{id}'"));
+        };
+
+        let ComponentKind::Label { edit, multiline, .. } = &component.kind else {
+            return Err(format!("Component '{id}' is not a label"));
+        };
+
+        if !edit {
+            return Err(format!("Component '{id}' is not editable"));
+        }
+        let value = if *multiline {
+            value.replace("\r\n", "\n").replace('\r', "\n")
+        } else if value.contains('\n') || value.contains('\r') {
+            return Err(format!("Label '{id}' is single-line; set multiline = true to allow line breaks"));
+        } else {
+            value
+        };
+        let rate_limit_ms = component.rate_limit_ms;
+
+        let current = self.label_values.get(id).cloned().unwrap_or_default();
+        if current == value {
+            return Ok(false);
+        }
+        let now = Instant::now();
+        self.ensure_edit_allowed(id, rate_limit_ms, now)?;
+        self.label_values.insert(id.to_string(), value);
+        self.last_edits.insert(id.to_string(), now);
+        Ok(true)
+    }
+
+    pub fn set_image_source(&mut self, id: &str, source: String) -> Result<bool, String> {
+        let source_trimmed = source.trim();
+        if source_trimmed.is_empty() {
+            return Err("Image source path cannot be empty".to_string());
+        }
+
+        let Some(config) = &self.config else {
+            return Err("No config loaded".to_string());
+        };
+
+        let Some(component) = config.components.iter().find(|c| c.id == id) else {
+            return Err(format!("Unknown component '{id}'"));
+        };
+
+        let ComponentKind::Image { edit, .. } = &component.kind else {
+            return Err(format!("Component '{id}' is not an image"));
+        };
+
+        if !edit {
+            return Err(format!("Component '{id}' is not editable"));
+        }
+        let rate_limit_ms = component.rate_limit_ms;
+
+        let next_source = source_trimmed.to_string();
+        let current = self.image_values.get(id).cloned().unwrap_or_default();
+        if current == next_source {
+            return Ok(false);
+        }
+        let now = Instant::now();
+        self.ensure_edit_allowed(id, rate_limit_ms, now)?;
+        self.image_values.insert(id.to_string(), next_source);
+        self.last_edits.insert(id.to_string(), now);
+        Ok(true)
+    }
+
+    /// Adds a message to the end of a ticker's rotation.
+    pub fn append_ticker_message(&mut self, id: &str, message: String) -> Result<bool, String> {
+        validate_ticker_message(&message)?;
+        let rate_limit_ms = self.ticker_rate_limit(id)?;
+        let messages = self.ticker_messages.get(id).map_or(0, Vec::len);
+        if messages >= MAX_TICKER_MESSAGES {
+            return Err(format!("Ticker '{id}' already holds {MAX_TICKER_MESSAGES} messages"));
+        }
+        let now = Instant::now();
+        self.ensure_edit_allowed(id, rate_limit_ms, now)?;
+        self.ticker_messages.entry(id.to_string()).or_default().push(message);
+        self.last_edits.insert(id.to_string(), now);
+        Ok(true)
+    }
+
+    /// Replaces all of a ticker's messages; an empty list clears it.
+    pub fn set_ticker_messages(&mut self, id: &str, messages: Vec<String>) -> Result<bool, String> {
+        if messages.len() > MAX_TICKER_MESSAGES {
+            return Err(format!("A ticker can hold at most {MAX_TICKER_MESSAGES} messages"));
+        }
+        for message in &messages {
+            validate_ticker_message(message)?;
+        }
+        let rate_limit_ms = self.ticker_rate_limit(id)?;
+        if self.ticker_messages.get(id) == Some(&messages) {
+            return Ok(false);
+        }
+        let now = Instant::now();
+        self.ensure_edit_allowed(id, rate_limit_ms, now)?;
+        self.ticker_messages.insert(id.to_string(), messages);
+        self.last_edits.insert(id.to_string(), now);
+        Ok(true)
+    }
+
+    /// Checks that `id` names a ticker and returns its `rate_limit_ms`.
+    fn ticker_rate_limit(&self, id: &str) -> Result<Option<u64>, String> {
+        let Some(config) = &self.config else {
+            return Err("No config loaded".to_string());
+        };
+        let Some(component) = config.components.iter().find(|c| c.id == id) else {
+            return Err(format!("Unknown component '{id}'"));
+        };
+        if !matches!(component.kind, ComponentKind::Ticker { .. }) {
+            return Err(format!("Component '{id}' is not a ticker"));
+        }
+        Ok(component.rate_limit_ms)
+    }
+
+    /// Refuses a runtime edit that arrives within the component's
+    /// `rate_limit_ms` of the last one that was applied.
+    fn ensure_edit_allowed(&self, id: &str, rate_limit_ms: Option<u64>, now: Instant) -> Result<(), String> {
+        let (Some(rate_limit_ms), Some(last_edit)) = (rate_limit_ms, self.last_edits.get(id)) else {
+            return Ok(());
+        };
+        let elapsed = now.saturating_duration_since(*last_edit);
+        let limit = Duration::from_millis(rate_limit_ms);
+        if elapsed < limit {
+            return Err(format!(
+                "'{id}' accepts one edit every {rate_limit_ms} ms; try again in {} ms",
+                (limit - elapsed).as_millis()
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn collect_hotkeys(&self) -> Vec<HotkeyBinding> {
+        let mut bindings = Vec::new();
+        let Some(config) = &self.config else {
+            return bindings;
+        };
+
+        if let Some(keybind) = &config.global.keybind {
+            if let Some(fullscreen) = &keybind.fullscreen {
+                bindings.push(HotkeyBinding {
+                    shortcut: fullscreen.to_shortcut(),
+                    cooldown_ms: fullscreen.cooldown_ms,
+                    repeat: fullscreen.repeat(),
+                    action: Action::ToggleFullscreen,
+                });
+            }
+            if let Some(player_select) = &keybind.player_select {
+                bindings.push(HotkeyBinding {
+                    shortcut: player_select.to_shortcut(),
+                    cooldown_ms: player_select.cooldown_ms,
+                    repeat: player_select.repeat(),
+                    action: Action::PlayerSelect,
+                });
+            }
+            if let Some(theme) = &keybind.theme {
+                bindings.push(HotkeyBinding {
+                    shortcut: theme.to_shortcut(),
+                    cooldown_ms: theme.cooldown_ms,
+                    repeat: theme.repeat(),
+                    action: Action::CycleTheme,
+                });
+            }
+            if let Some(lock) = &keybind.lock {
+                bindings.push(HotkeyBinding {
+                    shortcut: lock.to_shortcut(),
+                    cooldown_ms: lock.cooldown_ms,
+                    repeat: lock.repeat(),
+                    action: Action::LockBoard,
+                });
+            }
+            if let Some(ready) = &keybind.ready {
+                bindings.push(HotkeyBinding {
+                    shortcut: ready.to_shortcut(),
+                    cooldown_ms: ready.cooldown_ms,
+                    repeat: ready.repeat(),
+                    action: Action::ShowLiveScene,
+                });
+            }
+        }
+        for message in config.global.announcement.iter().flat_map(|a| &a.messages) {
+            if let Some(keybind) = &message.keybind {
+                bindings.push(HotkeyBinding {
+                    shortcut: keybind.to_shortcut(),
+                    cooldown_ms: keybind.cooldown_ms,
+                    repeat: keybind.repeat(),
+                    action: Action::Announce {
+                        name: message.name.clone(),
+                    },
+                });
+            }
+        }
+        for aux_timer in &config.global.aux_timers {
+            let Some(keybind) = &aux_timer.keybind else {
+                continue;
+            };
+            let name = &aux_timer.name;
+            let actions = [
+                (&keybind.start, Action::AuxTimerStart { name: name.clone() }),
+                (&keybind.stop, Action::AuxTimerStop { name: name.clone() }),
+                (&keybind.reset, Action::AuxTimerReset { name: name.clone() }),
+            ];
+            for (spec, action) in actions {
+                if let Some(spec) = spec {
+                    bindings.push(HotkeyBinding {
+                        shortcut: spec.to_shortcut(),
+                        cooldown_ms: spec.cooldown_ms,
+                        repeat: spec.repeat(),
+                        action,
+                    });
+                }
+            }
+        }
+        for settings in &config.macros {
+            if let Some(keybind) = &settings.keybind {
+                bindings.push(HotkeyBinding {
+                    shortcut: keybind.to_shortcut(),
+                    cooldown_ms: keybind.cooldown_ms,
+                    repeat: keybind.repeat(),
+                    action: Action::Macro {
+                        name: settings.name.clone(),
+                    },
+                });
+            }
+        }
+        if let Some(keybind) = config.global.playlist.as_ref().and_then(|playlist| playlist.keybind.as_ref()) {
+            let actions = [
+                (&keybind.play, Action::PlaylistPlay),
+                (&keybind.pause, Action::PlaylistPause),
+                (&keybind.next, Action::PlaylistNext),
+            ];
+            for (spec, action) in actions {
+                if let Some(spec) = spec {
+                    bindings.push(HotkeyBinding {
+                        shortcut: spec.to_shortcut(),
+                        cooldown_ms: spec.cooldown_ms,
+                        repeat: spec.repeat(),
+                        action,
+                    });
+                }
+            }
+        }
+
+        for component in &config.components {
+            match &component.kind {
+                ComponentKind::Number {
+                    keybind: Some(keybind),
+                    ..
+                }
+                | ComponentKind::Fouls {
+                    keybind: Some(keybind),
+                    ..
+                } => {
+                    if let Some(increase) = &keybind.increase {
+                        bindings.push(HotkeyBinding {
+                            shortcut: increase.to_shortcut(),
+                            cooldown_ms: increase.cooldown_ms,
+                            repeat: increase.repeat(),
+                            action: Action::NumberIncrease {
+                                id: component.id.clone(),
+                            },
+                        });
+                    }
+                    if let Some(decrease) = &keybind.decrease {
+                        bindings.push(HotkeyBinding {
+                            shortcut: decrease.to_shortcut(),
+                            cooldown_ms: decrease.cooldown_ms,
+                            repeat: decrease.repeat(),
+                            action: Action::NumberDecrease {
+                                id: component.id.clone(),
+                            },
+                        });
+                    }
+                    if let Some(reset) = &keybind.reset {
+                        bindings.push(HotkeyBinding {
+                            shortcut: reset.to_shortcut(),
+                            cooldown_ms: reset.cooldown_ms,
+                            repeat: reset.repeat(),
+                            action: Action::NumberReset {
+                                id: component.id.clone(),
+                            },
+                        });
+                    }
+                }
+                ComponentKind::Timer {
+                    keybind: Some(keybind),
+                    ..
+                } => {
+                    if let Some(start) = &keybind.start {
+                        bindings.push(HotkeyBinding {
+                            shortcut: start.to_shortcut(),
+                            cooldown_ms: start.cooldown_ms,
+                            repeat: start.repeat(),
+                            action: Action::TimerStart {
+                                id: component.id.clone(),
+                            },
+                        });
+                    }
+                    if let Some(stop) = &keybind.stop {
+                        bindings.push(HotkeyBinding {
+                            shortcut: stop.to_shortcut(),
+                            cooldown_ms: stop.cooldown_ms,
+                            repeat: stop.repeat(),
+                            action: Action::TimerStop {
+                                id: component.id.clone(),
+                            },
+                        });
+                    }
+                    if let Some(reset) = &keybind.reset {
+                        bindings.push(HotkeyBinding {
+                            shortcut: reset.to_shortcut(),
+                            cooldown_ms: reset.cooldown_ms,
+                            repeat: reset.repeat(),
+                            action: Action::TimerReset {
+                                id: component.id.clone(),
+                            },
+                        });
+                    }
+                    if let Some(increase) = &keybind.increase {
+                        bindings.push(HotkeyBinding {
+                            shortcut: increase.to_shortcut(),
+                            cooldown_ms: increase.cooldown_ms,
+                            repeat: increase.repeat(),
+                            action: Action::TimerIncrease {
+                                id: component.id.clone(),
+                            },
+                        });
+                    }
+                    if let Some(decrease) = &keybind.decrease {
+                        bindings.push(HotkeyBinding {
+                            shortcut: decrease.to_shortcut(),
+                            cooldown_ms: decrease.cooldown_ms,
+                            repeat: decrease.repeat(),
+                            action: Action::TimerDecrease {
+                                id: component.id.clone(),
+                            },
+                        });
+                    }
+                }
+                ComponentKind::ImageToggle {
+                    keybind: Some(keybind),
+                    ..
+                } => {
+                    if let Some(forward) = &keybind.forward {
+                        bindings.push(HotkeyBinding {
+                            shortcut: forward.to_shortcut(),
+                            cooldown_ms: forward.cooldown_ms,
+                            repeat: forward.repeat(),
+                            action: Action::ImageToggleForward {
+                                id: component.id.clone(),
+                            },
+                        });
+                    }
+                    if let Some(backward) = &keybind.backward {
+                        bindings.push(HotkeyBinding {
+                            shortcut: backward.to_shortcut(),
+                            cooldown_ms: backward.cooldown_ms,
+                            repeat: backward.repeat(),
+                            action: Action::ImageToggleBackward {
+                                id: component.id.clone(),
+                            },
+                        });
+                    }
+                    for (index, spec) in &keybind.set {
+                        bindings.push(HotkeyBinding {
+                            shortcut: spec.to_shortcut(),
+                            cooldown_ms: spec.cooldown_ms,
+                            repeat: spec.repeat(),
+                            action: Action::ImageToggleSet {
+                                id: component.id.clone(),
+                                index: *index,
+                            },
+                        });
+                    }
+                    if let Some(start) = &keybind.start {
+                        bindings.push(HotkeyBinding {
+                            shortcut: start.to_shortcut(),
+                            cooldown_ms: start.cooldown_ms,
+                            repeat: start.repeat(),
+                            action: Action::ImageToggleStart {
+                                id: component.id.clone(),
+                            },
+                        });
+                    }
+                    if let Some(stop) = &keybind.stop {
+                        bindings.push(HotkeyBinding {
+                            shortcut: stop.to_shortcut(),
+                            cooldown_ms: stop.cooldown_ms,
+                            repeat: stop.repeat(),
+                            action: Action::ImageToggleStop {
+                                id: component.id.clone(),
+                            },
+                        });
+                    }
+                }
+                ComponentKind::Period {
+                    keybind: Some(keybind),
+                    ..
+                } => {
+                    if let Some(forward) = &keybind.forward {
+                        bindings.push(HotkeyBinding {
+                            shortcut: forward.to_shortcut(),
+                            cooldown_ms: forward.cooldown_ms,
+                            repeat: forward.repeat(),
+                            action: Action::PeriodForward {
+                                id: component.id.clone(),
+                            },
+                        });
+                    }
+                    if let Some(backward) = &keybind.backward {
+                        bindings.push(HotkeyBinding {
+                            shortcut: backward.to_shortcut(),
+                            cooldown_ms: backward.cooldown_ms,
+                            repeat: backward.repeat(),
+                            action: Action::PeriodBackward {
+                                id: component.id.clone(),
+                            },
+                        });
+                    }
+                }
+                ComponentKind::Number { keybind: None, .. } => {}
+                ComponentKind::Fouls { keybind: None, .. } => {}
+                ComponentKind::Timer { keybind: None, .. } => {}
+                ComponentKind::ImageToggle { keybind: None, .. } => {}
+                ComponentKind::Period { keybind: None, .. } => {}
+                ComponentKind::Possession {
+                    keybind: Some(keybind),
+                    ..
+                } => {
+                    if let Some(toggle) = &keybind.toggle {
+                        bindings.push(HotkeyBinding {
+                            shortcut: toggle.to_shortcut(),
+                            cooldown_ms: toggle.cooldown_ms,
+                            repeat: toggle.repeat(),
+                            action: Action::PossessionToggle {
+                                id: component.id.clone(),
+                            },
+                        });
+                    }
+                }
+                ComponentKind::Possession { keybind: None, .. } => {}
+                ComponentKind::PossessionTime {
+                    keybind: Some(keybind),
+                    ..
+                } => {
+                    if let Some(home) = &keybind.home {
+                        bindings.push(HotkeyBinding {
+                            shortcut: home.to_shortcut(),
+                            cooldown_ms: home.cooldown_ms,
+                            repeat: home.repeat(),
+                            action: Action::PossessionTimeHome {
+                                id: component.id.clone(),
+                            },
+                        });
+                    }
+                    if let Some(away) = &keybind.away {
+                        bindings.push(HotkeyBinding {
+                            shortcut: away.to_shortcut(),
+                            cooldown_ms: away.cooldown_ms,
+                            repeat: away.repeat(),
+                            action: Action::PossessionTimeAway {
+                                id: component.id.clone(),
+                            },
+                        });
+                    }
+                    if let Some(reset) = &keybind.reset {
+                        bindings.push(HotkeyBinding {
+                            shortcut: reset.to_shortcut(),
+                            cooldown_ms: reset.cooldown_ms,
+                            repeat: reset.repeat(),
+                            action: Action::PossessionTimeReset {
+                                id: component.id.clone(),
+                            },
+                        });
+                    }
+                }
+                ComponentKind::PossessionTime { keybind: None, .. } => {}
+                ComponentKind::Video {
+                    keybind: Some(keybind),
+                    ..
+                } => {
+                    if let Some(play) = &keybind.play {
+                        bindings.push(HotkeyBinding {
+                            shortcut: play.to_shortcut(),
+                            cooldown_ms: play.cooldown_ms,
+                            repeat: play.repeat(),
+                            action: Action::VideoPlay {
+                                id: component.id.clone(),
+                            },
+                        });
+                    }
+                    if let Some(pause) = &keybind.pause {
+                        bindings.push(HotkeyBinding {
+                            shortcut: pause.to_shortcut(),
+                            cooldown_ms: pause.cooldown_ms,
+                            repeat: pause.repeat(),
+                            action: Action::VideoPause {
+                                id: component.id.clone(),
+                            },
+                        });
+                    }
+                }
+                ComponentKind::Video { keybind: None, .. } => {}
+                ComponentKind::Label { .. } => {}
+                ComponentKind::Image { .. } => {}
+                ComponentKind::Ticker { .. } => {}
+                ComponentKind::Derived { .. } => {}
+            }
+            if let Some(visibility) = &component.visibility_keybind {
+                bindings.push(HotkeyBinding {
+                    shortcut: visibility.to_shortcut(),
+                    cooldown_ms: visibility.cooldown_ms,
+                    repeat: visibility.repeat(),
+                    action: Action::ToggleVisibility {
+                        id: component.id.clone(),
+                    },
+                });
+            }
+        }
+
+        bindings
+    }
+
+    /// Applies an action, or arms it when the component requires confirmation
+    /// for it. Pressing the same action again while armed commits it. Returns
+    /// true if anything visible changed, including arming.
+    pub fn apply_action(&mut self, action: &Action) -> bool {
+        let Some((id, name)) = action.component_action() else {
+            return self.apply_committed(action);
+        };
+        let Some(confirm) = self.confirm_settings(id, name) else {
+            return self.apply_committed(action);
+        };
+
+        if self.pending_actions.get(id).is_some_and(|pending| pending.action == *action) {
+            self.pending_actions.remove(id);
+            self.apply_committed(action);
+            // Clearing the pending indicator is a visible change on its own.
+            return true;
+        }
+        self.pending_actions.insert(
+            id.to_string(),
+            PendingAction {
+                action: action.clone(),
+                deadline: Instant::now() + Duration::from_millis(confirm.timeout_ms),
+                commit_on_timeout: confirm.commit_on_timeout,
+            },
+        );
+        true
+    }
+
+    /// Returns true when the action is armed and waiting for confirmation.
+    pub fn is_pending(&self, action: &Action) -> bool {
+        action
+            .component_action()
+            .and_then(|(id, _)| self.pending_actions.get(id))
+            .is_some_and(|pending| pending.action == *action)
+    }
+
+    fn confirm_settings(&self, id: &str, name: &str) -> Option<ConfirmSettings> {
+        let config = self.config.as_ref()?;
+        let component = config.components.iter().find(|c| c.id == id)?;
+        let confirm = component.confirm.as_ref()?;
+        confirm.actions.iter().any(|action| action == name).then(|| confirm.clone())
+    }
+
+    /// Applies an action plus any actions linked to it in config (such as a
+    /// period's `on_advance` timer reset). Returns true if the primary action
+    /// changed anything.
+    fn apply_committed(&mut self, action: &Action) -> bool {
+        // Only the action's own component can score; linked actions move
+        // periods and clocks along.
+        let before = action.component_id().and_then(|id| Some((id, *self.number_values.get(id)?)));
+        let changed = self.apply_single_action(action);
+        if changed {
+            for linked in self.linked_actions(action) {
+                self.apply_single_action(&linked);
+            }
+            if let Some((id, before)) = before {
+                self.track_period_score(id, before);
+            }
+            self.record_event(action);
+        }
+        changed
+    }
+
+    /// Adds `action` to the event log with the board's values as they are
+    /// now. Board actions are recorded as they apply; the app records the
+    /// ones it runs itself, such as aux timers.
+    pub fn record_event(&mut self, action: &Action) {
+        self.record(action.describe(), action.component_id().map(str::to_string));
+    }
+
+    /// Adds a value set directly, by a hardware console or a rules script,
+    /// to the event log as `<id>.set`.
+    pub fn record_set(&mut self, id: &str) {
+        self.record(format!("{id}.set"), Some(id.to_string()));
+    }
+
+    fn record(&mut self, action: String, component: Option<String>) {
+        let text_of = |id: &str| self.logged_text(id);
+        let period_id = self.game_component(
+            |summary| summary.period.as_ref(),
+            |kind| matches!(kind, ComponentKind::Period { .. }),
+        );
+        let clock_id = self.game_component(
+            |summary| summary.clock.as_ref(),
+            |kind| matches!(kind, ComponentKind::Timer { mirror: None, .. }),
+        );
+        let event = LoggedEvent {
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_millis() as u64)
+                .unwrap_or(0),
+            action,
+            value: component.as_deref().and_then(text_of),
+            component,
+            period: period_id.as_deref().and_then(text_of),
+            clock: clock_id.as_deref().and_then(text_of),
+        };
+        if self.event_log.len() >= MAX_EVENT_LOG {
+            self.event_log.pop_front();
+        }
+        self.event_log.push_back(event);
+    }
+
+    /// A component's value as the event log records it: a timer's exact
+    /// time, otherwise what the board shows. Only that component is built,
+    /// since this runs on every action.
+    fn logged_text(&self, id: &str) -> Option<String> {
+        let config = self.config.as_ref()?;
+        let component = config.components.iter().find(|component| component.id == id)?;
+        let component = self.ui_component(config, component);
+        component.raw_text.or(component.text).or(component.source)
+    }
+
+    /// Every recorded action since the config was loaded, oldest first.
+    pub fn event_log(&self) -> &VecDeque<LoggedEvent> {
+        &self.event_log
+    }
+
+    /// The game's period or clock: the one `[global.summary]` names, or else
+    /// the config's first component that `matches`.
+    fn game_component(
+        &self,
+        named: fn(&SummarySettings) -> Option<&String>,
+        matches: fn(&ComponentKind) -> bool,
+    ) -> Option<String> {
+        let config = self.config.as_ref()?;
+        if let Some(id) = config.global.summary.as_ref().and_then(named) {
+            return Some(id.clone());
+        }
+        config
+            .components
+            .iter()
+            .find(|component| matches(&component.kind))
+            .map(|component| component.id.clone())
+    }
+
+    /// The label the game's period shows now.
+    fn period_label(&self) -> Option<String> {
+        let id = self.game_component(
+            |summary| summary.period.as_ref(),
+            |kind| matches!(kind, ComponentKind::Period { .. }),
+        )?;
+        let config = self.config.as_ref()?;
+        config.components.iter().find_map(|component| match &component.kind {
+            ComponentKind::Period { labels, .. } if component.id == id => {
+                labels.get(self.period_indices.get(&id).copied().unwrap_or(0)).cloned()
+            }
+            _ => None,
+        })
+    }
+
+    /// Adds the change in `id` since it was `before` to the current period's
+    /// totals, when `id` is a `number` component with `score = true`. Nothing
+    /// is tracked without a period component.
+    fn track_period_score(&mut self, id: &str, before: i32) {
+        let Some(config) = &self.config else {
+            return;
+        };
+        let scored = config.components.iter().any(|component| {
+            component.id == id && matches!(component.kind, ComponentKind::Number { score: true, .. })
+        });
+        let Some(after) = self.number_values.get(id).copied() else {
+            return;
+        };
+        if !scored || after == before {
+            return;
+        }
+        let Some(period) = self.period_label() else {
+            return;
+        };
+        let index = match self.period_scores.iter().position(|score| score.period == period) {
+            Some(index) => index,
+            None => {
+                self.period_scores.push(PeriodScore {
+                    period,
+                    points: BTreeMap::new(),
+                });
+                self.period_scores.len() - 1
+            }
+        };
+        *self.period_scores[index].points.entry(id.to_string()).or_insert(0) += i64::from(after) - i64::from(before);
+    }
+
+    /// How much each scoring `number` component changed in each period, in
+    /// the order the periods were first scored in.
+    pub fn score_by_period(&self) -> &[PeriodScore] {
+        &self.period_scores
+    }
+
+    fn linked_actions(&mut self, action: &Action) -> Vec<Action> {
+        match action {
+            Action::PeriodForward { id } => {
+                self.sync_period_timer_resets();
+                self.apply_period_resets(id);
+                match self.period_advance(id) {
+                    Some(advance) => vec![
+                        Action::TimerStop {
+                            id: advance.reset_timer.clone(),
+                        },
+                        Action::TimerReset {
+                            id: advance.reset_timer,
+                        },
+                    ],
+                    None => Vec::new(),
+                }
+            }
+            Action::PeriodBackward { .. } => {
+                self.sync_period_timer_resets();
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn period_advance(&self, id: &str) -> Option<PeriodAdvance> {
+        let config = self.config.as_ref()?;
+        config.components.iter().find_map(|c| match &c.kind {
+            ComponentKind::Period { on_advance, .. } if c.id == id => on_advance.clone(),
+            _ => None,
+        })
+    }
+
+    /// Resets fouls linked to this period and sets numbers with a
+    /// `period_budget` on it to the value for its new label, if that label has one.
+    fn apply_period_resets(&mut self, period_id: &str) {
+        let Some(config) = &self.config else {
+            return;
+        };
+        let Some(label) = config.components.iter().find_map(|c| match &c.kind {
+            ComponentKind::Period { labels, .. } if c.id == period_id => {
+                let index = self.period_indices.get(period_id).copied().unwrap_or(0);
+                labels.get(index)
+            }
+            _ => None,
+        }) else {
+            return;
+        };
+        for component in &config.components {
+            let reset_value = match &component.kind {
+                ComponentKind::Number {
+                    period_budget: Some(budget),
+                    ..
+                } if budget.period == period_id => budget.values.get(label).copied(),
+                ComponentKind::Fouls {
+                    default,
+                    reset_period: Some(reset_period),
+                    ..
+                } if reset_period == period_id => Some(*default),
+                _ => None,
+            };
+            if let (Some(value), Some(reset_value)) = (self.number_values.get_mut(&component.id), reset_value) {
+                *value = reset_value;
+            }
+        }
+    }
+
+    /// Points each linked timer's reset value at the duration for its period's
+    /// current label.
+    fn sync_period_timer_resets(&mut self) {
+        let Some(config) = &self.config else {
+            return;
+        };
+        for component in &config.components {
+            let ComponentKind::Period {
+                on_advance: Some(advance),
+                ..
+            } = &component.kind
+            else {
+                continue;
+            };
+            let Some(durations_ms) = &advance.durations_ms else {
+                continue;
+            };
+            let index = self.period_indices.get(&component.id).copied().unwrap_or(0);
+            if let (Some(timer), Some(duration)) =
+                (self.timer_values.get_mut(&advance.reset_timer), durations_ms.get(index))
+            {
+                timer.reset_ms = *duration;
+            }
+        }
+    }
+
+    fn apply_single_action(&mut self, action: &Action) -> bool {
+        match action {
+            Action::NumberIncrease { id } => {
+                if let Some(value) = self.number_values.get_mut(id) {
+                    *value += 1;
+                    return true;
+                }
+            }
+            Action::NumberDecrease { id } => {
+                if let Some(value) = self.number_values.get_mut(id) {
+                    *value = (*value - 1).max(0);
+                    return true;
+                }
+            }
+            Action::NumberReset { id } => {
+                if let Some(config) = &self.config {
+                    if let Some(default) = config.components.iter().find_map(|c| match &c.kind {
+                        ComponentKind::Number { default, .. } | ComponentKind::Fouls { default, .. }
+                            if c.id == *id =>
+                        {
+                            Some(*default)
+                        }
+                        _ => None,
+                    }) {
+                        if let Some(value) = self.number_values.get_mut(id) {
+                            *value = default;
+                            return true;
+                        }
+                    }
+                }
+            }
+            Action::TimerStart { id } => {
+                if let Some(timer) = self.timer_values.get_mut(id) {
+                    if timer.remaining_ms > 0 && !timer.running {
+                        timer.running = true;
+                        timer.last_tick = Some(Instant::now());
+                        timer.frozen_ms = None;
+                        return true;
+                    }
+                }
+            }
+            Action::TimerStop { id } => {
+                let freeze = timer_freezes_on_stop(self.config.as_ref(), id);
+                if let Some(timer) = self.timer_values.get_mut(id) {
+                    if timer.running {
+                        sync_timer(timer, Instant::now());
+                        timer.running = false;
+                        timer.last_tick = None;
+                        timer.frozen_ms = freeze.then_some(timer.remaining_ms);
+                        return true;
+                    }
+                }
+            }
+            Action::TimerReset { id } => {
+                if let Some(timer) = self.timer_values.get_mut(id) {
+                    let now = Instant::now();
+                    if timer.running {
+                        sync_timer(timer, now);
+                    }
+                    timer.remaining_ms = timer.reset_ms;
+                    timer.frozen_ms = None;
+                    if timer.running {
+                        if timer.remaining_ms > 0 {
+                            timer.last_tick = Some(now);
+                        } else {
+                            timer.running = false;
+                            timer.last_tick = None;
+                        }
+                    }
+                    return true;
+                }
+            }
+            Action::TimerIncrease { id } => {
+                if let Some(timer) = self.timer_values.get_mut(id) {
+                    let now = Instant::now();
+                    if timer.running {
+                        sync_timer(timer, now);
+                    }
+                    timer.remaining_ms += 1_000;
+                    if timer.running {
+                        timer.last_tick = Some(now);
+                    }
+                    return true;
+                }
+            }
+            Action::TimerDecrease { id } => {
+                if let Some(timer) = self.timer_values.get_mut(id) {
+                    let now = Instant::now();
+                    if timer.running {
+                        sync_timer(timer, now);
+                    }
+                    timer.remaining_ms = (timer.remaining_ms - 1_000).max(0);
+                    if timer.running {
+                        if timer.remaining_ms > 0 {
+                            timer.last_tick = Some(now);
+                        } else {
+                            timer.running = false;
+                            timer.last_tick = None;
+                        }
+                    }
+                    return true;
+                }
+            }
+            Action::ImageToggleForward { id } => {
+                if let Some(config) = &self.config {
+                    if let Some(source_count) = config.components.iter().find_map(|c| match &c.kind {
+                        ComponentKind::ImageToggle { sources, .. } if c.id == *id => Some(sources.len()),
+                        _ => None,
+                    }) {
+                        if source_count > 0 {
+                            let index = self.image_toggle_indices.entry(id.clone()).or_insert(0);
+                            *index = (*index + 1) % source_count;
+                            if let Some(rotation) = self.image_rotations.get_mut(id) {
+                                rotation.restart(Instant::now());
+                            }
+                            return true;
+                        }
+                    }
+                }
+            }
+            Action::ImageToggleBackward { id } => {
+                if let Some(config) = &self.config {
+                    if let Some(source_count) = config.components.iter().find_map(|c| match &c.kind {
+                        ComponentKind::ImageToggle { sources, .. } if c.id == *id => Some(sources.len()),
+                        _ => None,
+                    }) {
+                        if source_count > 0 {
+                            let index = self.image_toggle_indices.entry(id.clone()).or_insert(0);
+                            *index = (*index + source_count - 1) % source_count;
+                            if let Some(rotation) = self.image_rotations.get_mut(id) {
+                                rotation.restart(Instant::now());
+                            }
+                            return true;
+                        }
+                    }
+                }
+            }
+            Action::ImageToggleSet { id, index } => {
+                if let Some(config) = &self.config {
+                    let in_range = config.components.iter().any(|c| match &c.kind {
+                        ComponentKind::ImageToggle { sources, .. } => c.id == *id && *index < sources.len(),
+                        _ => false,
+                    });
+                    if in_range {
+                        if let Some(rotation) = self.image_rotations.get_mut(id) {
+                            rotation.restart(Instant::now());
+                        }
+                        return self.image_toggle_indices.insert(id.clone(), *index) != Some(*index);
+                    }
+                }
+            }
+            Action::ImageToggleStart { id } => {
+                if let Some(rotation) = self.image_rotations.get_mut(id).filter(|rotation| rotation.next_advance.is_none()) {
+                    rotation.next_advance = Some(Instant::now() + rotation.interval);
+                    return true;
+                }
+            }
+            Action::ImageToggleStop { id } => {
+                if let Some(rotation) = self.image_rotations.get_mut(id).filter(|rotation| rotation.next_advance.is_some()) {
+                    rotation.next_advance = None;
+                    return true;
+                }
+            }
+            Action::PeriodForward { id } => {
+                if let Some((count, wrap)) = self.period_bounds(id) {
+                    if let Some(index) = self.period_indices.get_mut(id) {
+                        let next = if *index + 1 < count {
+                            *index + 1
+                        } else if wrap {
+                            0
+                        } else {
+                            *index
+                        };
+                        if next != *index {
+                            *index = next;
+                            return true;
+                        }
+                    }
+                }
+            }
+            Action::PeriodBackward { id } => {
+                if let Some((count, wrap)) = self.period_bounds(id) {
+                    if let Some(index) = self.period_indices.get_mut(id) {
+                        let next = if *index > 0 {
+                            *index - 1
+                        } else if wrap {
+                            count - 1
+                        } else {
+                            *index
+                        };
+                        if next != *index {
+                            *index = next;
+                            return true;
+                        }
+                    }
+                }
+            }
+            Action::PossessionToggle { id } => {
+                if let Some(index) = self.possession_indices.get_mut(id) {
+                    *index = 1 - *index % 2;
+                    return true;
+                }
+            }
+            Action::PossessionTimeHome { id } => return self.set_possession_side(id, PossessionSide::Home),
+            Action::PossessionTimeAway { id } => return self.set_possession_side(id, PossessionSide::Away),
+            Action::PossessionTimeReset { id } => {
+                self.accrue_possession_time();
+                if let Some(tracker) = self.possession_times.get_mut(id) {
+                    *tracker = PossessionTimeRuntime {
+                        clock_mark: tracker.clock_mark,
+                        ..PossessionTimeRuntime::default()
+                    };
+                    return true;
+                }
+            }
+            // Playing resumes a paused clip; pressing it again restarts the clip,
+            // which is also how a finished clip that does not loop plays again.
+            Action::VideoPlay { id } => {
+                if let Some(video) = self.videos.get_mut(id) {
+                    if video.playing {
+                        video.restarts += 1;
+                    } else {
+                        video.playing = true;
+                    }
+                    return true;
+                }
+            }
+            Action::VideoPause { id } => {
+                if let Some(video) = self.videos.get_mut(id).filter(|video| video.playing) {
+                    video.playing = false;
+                    return true;
+                }
+            }
+            Action::ToggleVisibility { id } => {
+                if let Some(visible) = self.visibility.get_mut(id) {
+                    *visible = !*visible;
+                    return true;
+                }
+            }
+            Action::CycleTheme => return self.cycle_theme(),
+            Action::ShowLiveScene => return self.show_live_scene(),
+            // Window-level actions are dispatched by the app, not the runtime state.
+            Action::ToggleFullscreen
+            | Action::PlayerSelect
+            | Action::Announce { .. }
+            | Action::AuxTimerStart { .. }
+            | Action::AuxTimerStop { .. }
+            | Action::AuxTimerReset { .. }
+            | Action::Macro { .. }
+            | Action::PlaylistPlay
+            | Action::PlaylistPause
+            | Action::PlaylistNext
+            | Action::LockBoard => {}
+        }
+        false
+    }
+
+    /// Looks up a component by id and resolves one of its config action names.
+    pub fn resolve_component_action(&self, id: &str, name: &str) -> Option<Action> {
+        let config = self.config.as_ref()?;
+        let component = config.components.iter().find(|c| c.id == id)?;
+        Action::from_component_action(&component.kind, id, name)
+    }
+
+    /// Resolves an action written the way `Action::describe` writes it, e.g.
+    /// `home_score.increase`, `aux_timer.break.start`, or `fullscreen`. Only
+    /// actions the loaded config can perform resolve, bound to a key or not.
+    pub fn resolve_action(&self, name: &str) -> Option<Action> {
+        resolve_action_name(self.config.as_ref()?, name)
+    }
+
+    /// Every name `resolve_action` accepts for the loaded config, for
+    /// control panels to offer.
+    pub fn action_names(&self) -> Vec<String> {
+        let Some(config) = self.config.as_ref() else {
+            return Vec::new();
+        };
+        let global = &config.global;
+        let mut names = vec!["fullscreen".to_string(), "player_select".to_string()];
+        if !global.themes.is_empty() {
+            names.push("theme".to_string());
+        }
+        if global.lock.is_some() {
+            names.push("lock".to_string());
+        }
+        if global.splash.is_some() {
+            names.push("ready".to_string());
+        }
+        if let Some(announcement) = &global.announcement {
+            names.extend(announcement.messages.iter().map(|m| format!("announcement.{}", m.name)));
+        }
+        for aux_timer in &global.aux_timers {
+            names.extend(["start", "stop", "reset"].map(|verb| format!("aux_timer.{}.{verb}", aux_timer.name)));
+        }
+        if global.playlist.is_some() {
+            names.extend(["play", "pause", "next"].map(|verb| format!("playlist.{verb}")));
+        }
+        names.extend(config.macros.iter().map(|settings| format!("macro.{}", settings.name)));
+        for component in &config.components {
+            let id = &component.id;
+            names.extend(component_action_names(&component.kind).iter().map(|action| format!("{id}.{action}")));
+            if let ComponentKind::ImageToggle { sources, .. } = &component.kind {
+                names.extend((0..sources.len()).map(|index| format!("{id}.set_{index}")));
+            }
+            names.push(format!("{id}.{VISIBILITY_ACTION}"));
+        }
+        names
+    }
+
+    pub fn tick_timers(&mut self) -> TimerTick {
+        let mut tick = TimerTick::default();
+        let now = Instant::now();
+        for (id, timer) in self.timer_values.iter_mut() {
+            if !timer.running {
+                continue;
+            }
+
+            let last = timer.last_tick.unwrap_or(now);
+            let elapsed_ms = now.duration_since(last).as_millis() as i64;
+            if elapsed_ms <= 0 {
+                continue;
+            }
+
+            timer.last_tick = Some(now);
+            let new_value = (timer.remaining_ms - elapsed_ms).max(0);
+            if new_value > 0
+                && timer_tick_below(self.config.as_ref(), id).is_some_and(|threshold| new_value <= threshold)
+                && crossed_second(timer.remaining_ms, new_value)
+            {
+                tick.ticked.push(id.clone());
+            }
+            // Only the shortest cue is spoken if one tick passes several.
+            if let Some(cue_ms) = timer_voice_cues(self.config.as_ref(), id)
+                .iter()
+                .rev()
+                .find(|&&cue_ms| timer.remaining_ms > cue_ms && new_value <= cue_ms)
+            {
+                tick.voiced.push((id.clone(), *cue_ms));
+            }
+            // Unlike voice cues, every offset passed is sent, since each may start something different.
+            for &offset_ms in timer_t_minus(self.config.as_ref(), id).iter().rev() {
+                if timer.remaining_ms > offset_ms && new_value <= offset_ms {
+                    tick.t_minus.push(TMinusCue {
+                        id: id.clone(),
+                        offset_ms,
+                        remaining_ms: new_value,
+                    });
+                }
+            }
+            if new_value != timer.remaining_ms {
+                timer.run_ms += timer.remaining_ms - new_value;
+                timer.remaining_ms = new_value;
+                tick.changed = true;
+            }
+            if timer.remaining_ms == 0 && timer.running {
+                timer.running = false;
+                timer.last_tick = None;
+                tick.expired.push(id.clone());
+            }
+        }
+        if self.accrue_possession_time() {
+            tick.changed = true;
+        }
+        if self.advance_image_rotations(now) {
+            tick.changed = true;
+        }
+
+        let timed_out: Vec<String> = self
+            .pending_actions
+            .iter()
+            .filter(|(_, pending)| pending.deadline <= now)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in timed_out {
+            let Some(pending) = self.pending_actions.remove(&id) else {
+                continue;
+            };
+            tick.changed = true;
+            if pending.commit_on_timeout && self.apply_committed(&pending.action) {
+                tick.committed.push(pending.action);
+            }
+        }
+        tick
+    }
+
+    /// Moves every rotating image toggle whose interval has passed on to its
+    /// next source, wrapping around. Returns true if any of them moved.
+    fn advance_image_rotations(&mut self, now: Instant) -> bool {
+        let Some(config) = &self.config else {
+            return false;
+        };
+        let mut changed = false;
+        for component in &config.components {
+            let ComponentKind::ImageToggle { sources, .. } = &component.kind else {
+                continue;
+            };
+            let Some(rotation) = self.image_rotations.get_mut(&component.id) else {
+                continue;
+            };
+            let Some(next_advance) = rotation.next_advance.filter(|next_advance| *next_advance <= now) else {
+                continue;
+            };
+            // A late tick skips the missed deadlines instead of flipping through sources.
+            let mut next = next_advance + rotation.interval;
+            if next <= now {
+                next = now + rotation.interval;
+            }
+            rotation.next_advance = Some(next);
+            let index = self.image_toggle_indices.entry(component.id.clone()).or_insert(0);
+            *index = (*index + 1) % sources.len();
+            changed = true;
+        }
+        changed
+    }
+
+    pub fn action_sound(&self, action: &Action) -> Option<String> {
+        let (id, name) = action.component_action()?;
+        let config = self.config.as_ref()?;
+        let component = config.components.iter().find(|c| c.id == id)?;
+        component.sounds.get(name).cloned()
+    }
+
+    fn period_bounds(&self, id: &str) -> Option<(usize, bool)> {
+        let config = self.config.as_ref()?;
+        config.components.iter().find_map(|c| match &c.kind {
+            ComponentKind::Period { labels, wrap, .. } if c.id == id => Some((labels.len(), *wrap)),
+            _ => None,
+        })
+    }
+
+    fn set_possession_side(&mut self, id: &str, side: PossessionSide) -> bool {
+        // Time up to now still belongs to the side that had the ball.
+        self.accrue_possession_time();
+        match self.possession_times.get_mut(id) {
+            Some(tracker) if tracker.side != Some(side) => {
+                tracker.side = Some(side);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Credits the time each possession clock ran since the last call to the
+    /// side in possession. Returns whether any total changed.
+    fn accrue_possession_time(&mut self) -> bool {
+        let Some(config) = &self.config else {
+            return false;
+        };
+        let mut changed = false;
+        for component in &config.components {
+            let ComponentKind::PossessionTime { clock, .. } = &component.kind else {
+                continue;
+            };
+            let (Some(tracker), Some(timer)) = (self.possession_times.get_mut(&component.id), self.timer_values.get(clock))
+            else {
+                continue;
+            };
+            // A replaced clock restarts its count; nothing is credited for the jump.
+            let elapsed_ms = (timer.run_ms - tracker.clock_mark).max(0);
+            tracker.clock_mark = timer.run_ms;
+            if elapsed_ms == 0 {
+                continue;
+            }
+            match tracker.side {
+                Some(PossessionSide::Home) => tracker.home_ms += elapsed_ms,
+                Some(PossessionSide::Away) => tracker.away_ms += elapsed_ms,
+                None => continue,
+            }
+            changed = true;
+        }
+        changed
+    }
+
+    /// Switches to a configured theme, or back to the base colors with `None`.
+    /// Returns whether the applied theme changed.
+    pub fn set_theme(&mut self, name: Option<&str>) -> Result<bool, String> {
+        if let Some(name) = name {
+            let known = self
+                .config
+                .as_ref()
+                .is_some_and(|config| config.global.themes.iter().any(|theme| theme.name == name));
+            if !known {
+                return Err(format!("Unknown theme '{name}'"));
+            }
+        }
+        let next = name.map(str::to_string);
+        if next == self.active_theme {
+            return Ok(false);
+        }
+        self.active_theme = next;
+        Ok(true)
+    }
+
+    pub fn active_theme(&self) -> Option<&str> {
+        self.active_theme.as_deref()
+    }
+
+    /// Leaves the splash scene for the live board. Returns false when it was
+    /// not showing.
+    pub fn show_live_scene(&mut self) -> bool {
+        std::mem::take(&mut self.splash_shown)
+    }
+
+    /// Whether the splash scene is up in place of the board.
+    pub fn splash_showing(&self) -> bool {
+        self.splash_shown
+    }
+
+    /// Puts the splash scene up or takes it down without touching values,
+    /// for a board restored behind the audience's back. Only a config with a
+    /// splash can show one.
+    pub fn set_splash_showing(&mut self, showing: bool) {
+        self.splash_shown = showing && self.config.as_ref().is_some_and(|config| config.global.splash.is_some());
+    }
+
+    /// Steps through the base colors and then each theme in config order.
+    fn cycle_theme(&mut self) -> bool {
+        let Some(config) = &self.config else {
+            return false;
+        };
+        let themes = &config.global.themes;
+        if themes.is_empty() {
+            return false;
+        }
+        let next = match &self.active_theme {
+            None => Some(themes[0].name.clone()),
+            Some(active) => themes
+                .iter()
+                .position(|theme| &theme.name == active)
+                .and_then(|index| themes.get(index + 1))
+                .map(|theme| theme.name.clone()),
+        };
+        self.active_theme = next;
+        true
+    }
+
+    pub fn timer_display_offset(&self) -> i64 {
+        self.timer_display_offset_override
+            .or_else(|| Some(self.config.as_ref()?.global.timer_display_offset_ms))
+            .unwrap_or(0)
+    }
+
+    /// Overrides the configured timer display offset until another config is loaded.
+    pub fn set_timer_display_offset(&mut self, offset_ms: i64) -> Result<bool, String> {
+        validate_timer_display_offset(offset_ms)?;
+        let changed = self.timer_display_offset() != offset_ms;
+        self.timer_display_offset_override = Some(offset_ms);
+        Ok(changed)
+    }
+
+    fn themed_color(&self, color: &str) -> String {
+        let theme = self.active_theme.as_ref().and_then(|name| {
+            self.config
+                .as_ref()?
+                .global
+                .themes
+                .iter()
+                .find(|theme| &theme.name == name)
+        });
+        theme
+            .and_then(|theme| theme.colors.get(&color.trim().to_ascii_uppercase()))
+            .cloned()
+            .unwrap_or_else(|| color.to_string())
+    }
+
+    /// Sets a label's value from the config's own automation, such as the team
+    /// names of a scheduled game. Unlike `set_label_value`, the label does not
+    /// need `edit = true` and edits are not rate limited.
+    pub fn set_label_text(&mut self, id: &str, text: String) -> bool {
+        if !self.label_values.contains_key(id) {
+            return false;
+        }
+        self.label_values.insert(id.to_string(), text.clone()) != Some(text)
+    }
+
+    /// Sets a timer to `remaining_ms` and starts it, e.g. for a pre-game countdown.
+    pub fn start_countdown(&mut self, id: &str, remaining_ms: i64) -> bool {
+        let Some(timer) = self.timer_values.get_mut(id) else {
+            return false;
+        };
+        timer.remaining_ms = remaining_ms.max(0);
+        timer.frozen_ms = None;
+        timer.running = timer.remaining_ms > 0;
+        timer.last_tick = timer.running.then(Instant::now);
+        true
+    }
+
+    /// Sets a component from text sent by a hardware console. Scores and fouls
+    /// take whole numbers, periods a label or 1-based position, and timers a
+    /// time such as `12:00`, `1:05:00`, or `9.8`. The console runs its own
+    /// clock, so a timer set this way is left stopped. Blank fields are ignored.
+    /// Changes other than to timers, which a console sends many times a
+    /// second, go in the event log.
+    pub fn set_console_value(&mut self, id: &str, text: &str) -> Result<bool, String> {
+        let is_timer = self.timer_values.contains_key(id);
+        let changed = self.apply_console_value(id, text)?;
+        if changed && !is_timer {
+            self.record_set(id);
+        }
+        Ok(changed)
+    }
+
+    fn apply_console_value(&mut self, id: &str, text: &str) -> Result<bool, String> {
+        let Some(config) = &self.config else {
+            return Err("No config loaded".to_string());
+        };
+        let Some(component) = config.components.iter().find(|c| c.id == id) else {
+            return Err(format!("Unknown component '{id}'"));
+        };
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(false);
+        }
+        match &component.kind {
+            ComponentKind::Number { .. } | ComponentKind::Fouls { .. } => {
+                let value: i32 = text
+                    .parse()
+                    .map_err(|_| format!("'{id}' got '{text}' from the console, which is not a number"))?;
+                let before = self.number_values.insert(id.to_string(), value);
+                if let Some(before) = before {
+                    self.track_period_score(id, before);
+                }
+                Ok(before != Some(value))
+            }
+            ComponentKind::Period { labels, .. } => {
+                let index = labels
+                    .iter()
+                    .position(|label| label == text)
+                    .or_else(|| text.parse::<usize>().ok().filter(|n| (1..=labels.len()).contains(n)).map(|n| n - 1))
+                    .ok_or_else(|| format!("'{id}' got '{text}' from the console, which is not one of its periods"))?;
+                Ok(self.period_indices.insert(id.to_string(), index) != Some(index))
+            }
+            ComponentKind::Label { .. } => Ok(self.set_label_text(id, text.to_string())),
+            ComponentKind::Timer { .. } => {
+                let remaining_ms = parse_console_time(text)
+                    .ok_or_else(|| format!("'{id}' got '{text}' from the console, which is not a time"))?;
+                let Some(timer) = self.timer_values.get_mut(id) else {
+                    return Ok(false);
+                };
+                let changed = timer.remaining_ms != remaining_ms || timer.running;
+                timer.remaining_ms = remaining_ms;
+                timer.running = false;
+                timer.last_tick = None;
+                timer.frozen_ms = None;
+                Ok(changed)
+            }
+            _ => Err(format!("'{id}' cannot be set from a console")),
+        }
+    }
+
+    /// Sets a timer's remaining time from a rules script. Unlike a console,
+    /// a script shares the board's clock, so a running timer keeps running.
+    pub fn set_timer_remaining(&mut self, id: &str, remaining_ms: i64) -> Result<bool, String> {
+        let Some(timer) = self.timer_values.get_mut(id) else {
+            return Err(format!("'{id}' is not a timer with a value of its own"));
+        };
+        let remaining_ms = remaining_ms.max(0);
+        sync_timer(timer, Instant::now());
+        let changed = timer.remaining_ms != remaining_ms || timer.frozen_ms.is_some();
+        timer.remaining_ms = remaining_ms;
+        timer.frozen_ms = None;
+        if remaining_ms == 0 {
+            timer.running = false;
+            timer.last_tick = None;
+        }
+        Ok(changed)
+    }
+
+    pub fn timer_running(&self, id: &str) -> bool {
+        self.timer_values.get(id).is_some_and(|timer| timer.running)
+    }
+
+    pub fn any_timer_running(&self) -> bool {
+        self.timer_values.values().any(|timer| timer.running)
+    }
+
+    /// Enters or leaves low-power mode. Returns false when nothing changed.
+    pub fn set_idle(&mut self, idle: bool) -> bool {
+        std::mem::replace(&mut self.idle, idle) != idle
+    }
+
+    /// Shows `text` in place of the label's value, or restores the value when `None`.
+    pub fn set_label_override(&mut self, id: &str, text: Option<String>) -> bool {
+        match text {
+            Some(text) => self.label_overrides.insert(id.to_string(), text.clone()) != Some(text),
+            None => self.label_overrides.remove(id).is_some(),
+        }
+    }
+
+    /// Sound file for the timer's countdown ticks; `None` means the generated beep.
+    pub fn timer_tick_sound(&self, id: &str) -> Option<String> {
+        let config = self.config.as_ref()?;
+        config.components.iter().find_map(|c| match &c.kind {
+            ComponentKind::Timer { tick_sound, .. } if c.id == id => tick_sound.clone(),
+            _ => None,
+        })
+    }
+
+    /// What should happen when the timer reaches zero, if anything is configured.
+    pub fn timer_expire_trigger(&self, id: &str) -> Option<ExpireTrigger> {
+        let config = self.config.as_ref()?;
+        let (sound, relay, duration_ms) = config.components.iter().find_map(|c| match &c.kind {
+            ComponentKind::Timer {
+                on_expire_sound,
+                on_expire_relay,
+                on_expire_duration_ms,
+                ..
+            } if c.id == id => Some((on_expire_sound, on_expire_relay, on_expire_duration_ms)),
+            _ => None,
+        })?;
+        let relay = relay
+            .as_ref()
+            .and_then(|name| config.global.relays.iter().find(|output| &output.name == name))
+            .cloned();
+        if sound.is_none() && relay.is_none() {
+            return None;
+        }
+        Some(ExpireTrigger {
+            sound: sound.clone(),
+            relay,
+            duration_ms: *duration_ms,
+        })
+    }
+
+    /// A component's value as a derived expression reads it: numbers and
+    /// fouls as numbers, timers as remaining milliseconds, periods and labels
+    /// as text. Announcements shown on a label do not count as its value.
+    fn expression_value(&self, config: &ScoreboardConfig, id: &str) -> Option<Value> {
+        let component = config.components.iter().find(|component| component.id == id)?;
+        Some(match &component.kind {
+            ComponentKind::Number { .. } | ComponentKind::Fouls { .. } => {
+                Value::Number(i64::from(self.number_values.get(id).copied().unwrap_or_default()))
+            }
+            ComponentKind::Timer { mirror, .. } => {
+                let timer = self.timer_values.get(mirror.as_deref().unwrap_or(id));
+                Value::Number(timer.map(|timer| timer.remaining_ms).unwrap_or_default())
+            }
+            ComponentKind::Period { labels, .. } => {
+                let index = self.period_indices.get(id).copied().unwrap_or(0).min(labels.len() - 1);
+                Value::Text(labels[index].clone())
+            }
+            ComponentKind::Label { .. } => Value::Text(self.label_values.get(id).cloned().unwrap_or_default()),
+            _ => return None,
+        })
+    }
+
+    pub fn snapshot(&self) -> UiSnapshot {
+        let Some(config) = &self.config else {
+            return UiSnapshot {
+                sequence: 0,
+                timestamp_ms: 0,
+                canvas_width: DEFAULT_CANVAS_WIDTH,
+                canvas_height: DEFAULT_CANVAS_HEIGHT,
+                rotate: 0,
+                background_color: "#000000".to_string(),
+                chroma_key: None,
+                components: Vec::new(),
+                splash: None,
+                dim: None,
+            };
+        };
+
+        let mut components = config
+            .components
+            .iter()
+            .map(|component| self.ui_component(config, component))
+            .collect::<Vec<_>>();
+        // Stable, so components sharing a layer keep their config order.
+        components.sort_by_key(|component| std::cmp::Reverse(component.layer));
+
+        UiSnapshot {
+            sequence: 0,
+            timestamp_ms: 0,
+            canvas_width: config.global.canvas.width,
+            canvas_height: config.global.canvas.height,
+            rotate: config.global.rotate,
+            background_color: match &config.global.chroma_key {
+                Some(chroma_key) => chroma_key.clone(),
+                None => self.themed_color(&config.global.background_color),
+            },
+            chroma_key: config.global.chroma_key.clone(),
+            components,
+            splash: config.global.splash.as_ref().filter(|_| self.splash_shown).map(|splash| UiSplash {
+                image: splash.image.clone(),
+            }),
+            dim: config.global.idle.as_ref().filter(|_| self.idle).map(|idle| idle.dim),
+        }
+    }
+
+    /// One component as the frontend draws it.
+    fn ui_component(&self, config: &ScoreboardConfig, component: &ComponentConfig) -> UiComponent {
+        let splash_group = config.global.splash.as_ref().and_then(|splash| splash.group.as_ref());
+        let idle_hidden: &[String] = match &config.global.idle {
+            Some(idle) if self.idle => &idle.hide,
+            _ => &[],
+        };
+        let mut bonus = false;
+        let mut double_bonus = false;
+        let mut possession_time = None;
+        let mut ticker = None;
+        let mut video = None;
+        let mut wrap = None;
+        let mut raw_text = None;
+        let (component_type, text, source, width, height, opacity, editable) = match &component.kind {
+            ComponentKind::Number { .. } => (
+                "number".to_string(),
+                Some(
+                    self.number_values
+                        .get(&component.id)
+                        .copied()
+                        .unwrap_or_default()
+                        .to_string(),
+                ),
+                None,
+                None,
+                None,
+                None,
+                false,
+            ),
+            ComponentKind::Timer { rounding, mirror, .. } => {
+                let timer = self.timer_values.get(mirror.as_ref().unwrap_or(&component.id));
+                raw_text = Some(format_ms(timer.map(|t| t.remaining_ms).unwrap_or_default(), rounding));
+                (
+                    "timer".to_string(),
+                    Some(format_ms(
+                        timer
+                            .map(|t| {
+                                // A stopped clock shows its exact value, or the one it froze at;
+                                // only a running one is ahead.
+                                if t.running {
+                                    (t.remaining_ms - self.timer_display_offset()).max(0)
+                                } else {
+                                    t.frozen_ms.unwrap_or(t.remaining_ms)
+                                }
+                            })
+                            .unwrap_or_default(),
+                        rounding,
+                    )),
+                    None,
+                    None,
+                    None,
+                    None,
+                    false,
+                )
+            }
+            ComponentKind::Label {
+                edit,
+                multiline,
+                max_width,
+                ..
+            } => {
+                if *multiline {
+                    wrap = Some(*max_width);
+                }
+                let overridden = self.label_overrides.get(&component.id);
+                (
+                    "label".to_string(),
+                    Some(
+                        overridden
+                            .or_else(|| self.label_values.get(&component.id))
+                            .cloned()
+                            .unwrap_or_default(),
+                    ),
+                    None,
+                    None,
+                    None,
+                    None,
+                    // Editing would show the override text instead of the label's own value.
+                    *edit && overridden.is_none(),
+                )
+            }
+            ComponentKind::Image {
+                source: default_source,
+                width,
+                height,
+                opacity,
+                edit,
+            } => (
+                "image".to_string(),
+                None,
+                Some(
+                    self.image_values
+                        .get(&component.id)
+                        .cloned()
+                        .unwrap_or_else(|| default_source.clone()),
+                ),
+                Some(*width),
+                Some(*height),
+                Some(*opacity),
+                *edit,
+            ),
+            ComponentKind::ImageToggle {
+                sources,
+                width,
+                height,
+                opacity,
+                ..
+            } => {
+                let index = self
+                    .image_toggle_indices
+                    .get(&component.id)
+                    .copied()
+                    .unwrap_or(0)
+                    % sources.len();
+                (
+                    "image-toggle".to_string(),
+                    None,
+                    Some(sources[index].clone()),
+                    Some(*width),
+                    Some(*height),
+                    Some(*opacity),
+                    false,
+                )
+            }
+            ComponentKind::Period { labels, .. } => {
+                let index = self
+                    .period_indices
+                    .get(&component.id)
+                    .copied()
+                    .unwrap_or(0)
+                    .min(labels.len() - 1);
+                (
+                    "period".to_string(),
+                    Some(labels[index].clone()),
+                    None,
+                    None,
+                    None,
+                    None,
+                    false,
+                )
+            }
+            ComponentKind::Fouls {
+                bonus: bonus_threshold,
+                double_bonus: double_bonus_threshold,
+                ..
+            } => {
+                let value = self.number_values.get(&component.id).copied().unwrap_or_default();
+                bonus = bonus_threshold.is_some_and(|threshold| value >= threshold);
+                double_bonus = double_bonus_threshold.is_some_and(|threshold| value >= threshold);
+                (
+                    "fouls".to_string(),
+                    Some(value.to_string()),
+                    None,
+                    None,
+                    None,
+                    None,
+                    false,
+                )
+            }
+            ComponentKind::Possession { display, .. } => {
+                let index = self.possession_indices.get(&component.id).copied().unwrap_or(0) % 2;
+                match display {
+                    PossessionDisplay::Labels(labels) => (
+                        "possession".to_string(),
+                        Some(labels[index].clone()),
+                        None,
+                        None,
+                        None,
+                        None,
+                        false,
+                    ),
+                    PossessionDisplay::Images {
+                        sources,
+                        width,
+                        height,
+                        opacity,
+                    } => (
+                        "possession".to_string(),
+                        None,
+                        Some(sources[index].clone()),
+                        Some(*width),
+                        Some(*height),
+                        Some(*opacity),
+                        false,
+                    ),
+                }
+            }
+            ComponentKind::PossessionTime { .. } => {
+                let totals = self
+                    .possession_times
+                    .get(&component.id)
+                    .map(possession_time_totals);
+                let text = totals
+                    .as_ref()
+                    .map(|totals| format!("{}% - {}%", totals.home_percent, totals.away_percent))
+                    .unwrap_or_default();
+                possession_time = totals;
+                ("possession-time".to_string(), Some(text), None, None, None, None, false)
+            }
+            ComponentKind::Ticker { speed, width, .. } => {
+                let messages = self.ticker_messages.get(&component.id).cloned().unwrap_or_default();
+                let text = messages.join(TICKER_SEPARATOR);
+                ticker = Some((messages, *speed));
+                ("ticker".to_string(), Some(text), None, Some(*width), None, None, false)
+            }
+            ComponentKind::Video {
+                source,
+                width,
+                height,
+                opacity,
+                looped,
+                muted,
+                ..
+            } => {
+                let runtime = self.videos.get(&component.id);
+                video = Some(UiVideo {
+                    playing: runtime.is_some_and(|runtime| runtime.playing),
+                    looped: *looped,
+                    muted: *muted,
+                    restarts: runtime.map_or(0, |runtime| runtime.restarts),
+                });
+                (
+                    "video".to_string(),
+                    None,
+                    Some(source.clone()),
+                    Some(*width),
+                    Some(*height),
+                    Some(*opacity),
+                    false,
+                )
+            }
+            ComponentKind::Derived { expr } => {
+                let text = expr
+                    .eval(&|id| self.expression_value(config, id))
+                    .map(|value| value.to_string())
+                    .unwrap_or_default();
+                ("derived".to_string(), Some(text), None, None, None, None, false)
+            }
+        };
+
+        let group = component
+            .group
+            .as_ref()
+            .and_then(|name| config.groups.iter().find(|group| &group.name == name));
+        let position = group.map_or(component.position, |group| group.resolve_position(&component.position));
+        let scale_length = |length: i32| group.map_or(length, |group| group.scale_length(length));
+
+        UiComponent {
+            id: component.id.clone(),
+            component_type,
+            x: position.x,
+            y: position.y,
+            alignment: component.alignment.map(|alignment| alignment.as_str().to_string()),
+            layer: component.layer(),
+            // The splash shows only its own group; the live board everything else.
+            visible: (splash_group.is_some() && component.group.as_ref() == splash_group) == self.splash_shown
+                && group.is_none_or(|group| group.visible)
+                && self.visibility.get(&component.id).copied().unwrap_or(true)
+                && !idle_hidden.contains(&component.id),
+            font_family: component.font.family.clone(),
+            font_size: scale_length(component.font.size).max(1),
+            font_color: self.themed_color(&component.font.color),
+            font_weight: component.font.weight,
+            font_italic: component.font.italic,
+            letter_spacing: scale_length(component.font.letter_spacing),
+            outline: component.font.outline.as_ref().map(|outline| TextOutline {
+                color: self.themed_color(&outline.color),
+                width: scale_length(outline.width).max(1),
+            }),
+            shadow: component.font.shadow.as_ref().map(|shadow| TextShadow {
+                color: self.themed_color(&shadow.color),
+                x: scale_length(shadow.x),
+                y: scale_length(shadow.y),
+                blur: scale_length(shadow.blur),
+            }),
+            text,
+            raw_text,
+            source,
+            width: width.map(scale_length),
+            height: height.map(scale_length),
+            // Text components carry their opacity on the font.
+            opacity: opacity.or(Some(component.font.opacity)),
+            editable,
+            pending: self
+                .pending_actions
+                .get(&component.id)
+                .and_then(|pending| pending.action.component_action())
+                .map(|(_, name)| name.to_string()),
+            bonus,
+            double_bonus,
+            possession_time,
+            ticker: ticker.map(|(messages, speed)| UiTicker {
+                messages,
+                speed: scale_length(speed).max(1),
+            }),
+            video,
+            wrap: wrap.map(|max_width| UiWrap {
+                max_width: max_width.map(|max_width| scale_length(max_width).max(1)),
+            }),
+            fit: component.fit.map(|fit| TextFit {
+                max_width: fit.max_width.map(|max_width| scale_length(max_width).max(1)),
+                max_height: fit.max_height.map(|max_height| scale_length(max_height).max(1)),
+            }),
+            monospace_digits: component.monospace_digits,
+        }
+    }
+}
+
+/// Joins ticker messages into one line for outputs that cannot scroll.
+const TICKER_SEPARATOR: &str = " \u{2022} ";
+
+/// Resolves an action written the way `Action::describe` writes it, e.g.
+/// `home_score.increase`, `aux_timer.break.start`, or `fullscreen`. Only
+/// actions `config` can perform resolve, bound to a key or not.
+pub fn resolve_action_name(config: &ScoreboardConfig, name: &str) -> Option<Action> {
+    let global = &config.global;
+    match name {
+        "fullscreen" => return Some(Action::ToggleFullscreen),
+        "player_select" => return Some(Action::PlayerSelect),
+        "theme" if !global.themes.is_empty() => return Some(Action::CycleTheme),
+        "lock" if global.lock.is_some() => return Some(Action::LockBoard),
+        "ready" if global.splash.is_some() => return Some(Action::ShowLiveScene),
+        _ => {}
+    }
+
+    if let Some(message) = name.strip_prefix("announcement.") {
+        let announcement = global.announcement.as_ref()?;
+        return announcement
+            .messages
+            .iter()
+            .any(|m| m.name == message)
+            .then(|| Action::Announce { name: message.to_string() });
+    }
+    if let Some(name) = name.strip_prefix("macro.") {
+        return config
+            .macros
+            .iter()
+            .any(|settings| settings.name == name)
+            .then(|| Action::Macro { name: name.to_string() });
+    }
+    if let Some(rest) = name.strip_prefix("aux_timer.") {
+        let (timer, verb) = rest.rsplit_once('.')?;
+        global.aux_timers.iter().find(|t| t.name == timer)?;
+        let name = timer.to_string();
+        return match verb {
+            "start" => Some(Action::AuxTimerStart { name }),
+            "stop" => Some(Action::AuxTimerStop { name }),
+            "reset" => Some(Action::AuxTimerReset { name }),
+            _ => None,
+        };
+    }
+    if let Some(verb) = name.strip_prefix("playlist.") {
+        global.playlist.as_ref()?;
+        return match verb {
+            "play" => Some(Action::PlaylistPlay),
+            "pause" => Some(Action::PlaylistPause),
+            "next" => Some(Action::PlaylistNext),
+            _ => None,
+        };
+    }
+
+    let (id, action) = name.rsplit_once('.')?;
+    let component = config.components.iter().find(|c| c.id == id)?;
+    let id = id.to_string();
+    if action == VISIBILITY_ACTION {
+        return Some(Action::ToggleVisibility { id });
+    }
+    if let ComponentKind::ImageToggle { sources, .. } = &component.kind {
+        if let Some(index) = image_toggle_set_index(action) {
+            return (index < sources.len()).then_some(Action::ImageToggleSet { id, index });
+        }
+    }
+    if !component_action_names(&component.kind).contains(&action) {
+        return None;
+    }
+    Action::from_component_action(&component.kind, &id, action)
+}
+
+fn possession_time_totals(tracker: &PossessionTimeRuntime) -> UiPossessionTime {
+    let total_ms = tracker.home_ms + tracker.away_ms;
+    let home_percent = if total_ms > 0 {
+        ((tracker.home_ms * 100 + total_ms / 2) / total_ms) as u32
+    } else {
+        0
+    };
+    UiPossessionTime {
+        side: tracker.side,
+        home_ms: tracker.home_ms,
+        away_ms: tracker.away_ms,
+        home_percent,
+        away_percent: if total_ms > 0 { 100 - home_percent } else { 0 },
+    }
+}
+
+/// Formats a clock reading the way a timer with `rounding` shows it, such as
+/// `12:00` or `4.2` in the last basketball minute. Negative readings show as zero.
+pub fn format_ms(ms: i64, rounding: &TimerRounding) -> String {
+    match rounding {
+        TimerRounding::Standard => format_ms_standard(ms),
+        TimerRounding::Basketball => format_ms_basketball(ms),
+    }
+}
+
+fn timer_tick_below(config: Option<&ScoreboardConfig>, id: &str) -> Option<i64> {
+    config?.components.iter().find_map(|c| match &c.kind {
+        ComponentKind::Timer { tick_below_ms, .. } if c.id == id => *tick_below_ms,
+        _ => None,
+    })
+}
+
+fn timer_voice_cues<'a>(config: Option<&'a ScoreboardConfig>, id: &str) -> &'a [i64] {
+    config
+        .and_then(|config| {
+            config.components.iter().find_map(|c| match &c.kind {
+                ComponentKind::Timer { voice_cues_ms, .. } if c.id == id => Some(voice_cues_ms.as_slice()),
+                _ => None,
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// Reads `H:MM:SS`, `M:SS`, or `SS`, each optionally with tenths (`9.8`).
+fn parse_console_time(text: &str) -> Option<i64> {
+    let (whole, tenths) = match text.split_once('.') {
+        Some((whole, tenths)) if tenths.len() == 1 => (whole, tenths.parse::<i64>().ok()?),
+        Some(_) => return None,
+        None => (text, 0),
+    };
+    let parts: Vec<&str> = whole.split(':').collect();
+    if parts.len() > 3 {
+        return None;
+    }
+    let mut seconds = 0;
+    for part in parts {
+        // Consoles leave leading digits blank, as in ` :45`.
+        let part = part.trim();
+        let value: i64 = if part.is_empty() { 0 } else { part.parse().ok()? };
+        seconds = seconds * 60 + value;
+    }
+    Some(seconds * 1000 + tenths * 100)
+}
+
+fn timer_t_minus<'a>(config: Option<&'a ScoreboardConfig>, id: &str) -> &'a [i64] {
+    config
+        .and_then(|config| {
+            config.components.iter().find_map(|c| match &c.kind {
+                ComponentKind::Timer { t_minus_ms, .. } if c.id == id => Some(t_minus_ms.as_slice()),
+                _ => None,
+            })
+        })
+        .unwrap_or_default()
+}
+
+fn timer_freezes_on_stop(config: Option<&ScoreboardConfig>, id: &str) -> bool {
+    config.is_some_and(|config| {
+        config.components.iter().any(|c| {
+            matches!(c.kind, ComponentKind::Timer { freeze_on_stop: true, .. }) && c.id == id
+        })
+    })
+}
+
+/// Whether counting down from `from_ms` to `to_ms` passed a whole second.
+fn crossed_second(from_ms: i64, to_ms: i64) -> bool {
+    to_ms / 1000 < from_ms / 1000
+}
+
+fn sync_timer(timer: &mut TimerRuntime, now: Instant) {
+    if !timer.running {
+        return;
+    }
+
+    let last = timer.last_tick.unwrap_or(now);
+    let elapsed_ms = now.duration_since(last).as_millis() as i64;
+    if elapsed_ms > 0 {
+        let new_value = (timer.remaining_ms - elapsed_ms).max(0);
+        timer.run_ms += timer.remaining_ms - new_value;
+        timer.remaining_ms = new_value;
+    }
+    if timer.remaining_ms > 0 {
+        timer.last_tick = Some(now);
+    } else {
+        timer.running = false;
+        timer.last_tick = None;
+    }
+}
+
+fn format_ms_standard(ms: i64) -> String {
+    let total_seconds = ms.max(0) / 1000;
+    let hours = total_seconds / 3600;
+    let minutes = (total_seconds % 3600) / 60;
+    let seconds = total_seconds % 60;
+    if hours > 0 {
+        format!("{hours:02}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes:02}:{seconds:02}")
+    }
+}
+
+fn format_ms_basketball(ms: i64) -> String {
+    let clamped_ms = ms.max(0);
+
+    if clamped_ms < 60_000 {
+        let tenths_total = (clamped_ms + 50) / 100;
+        let seconds = tenths_total / 10;
+        let tenths = tenths_total % 10;
+        return format!("{seconds}.{tenths}");
+    }
+
+    let rounded_seconds = (clamped_ms + 500) / 1000;
+    let hours = rounded_seconds / 3600;
+    let minutes = (rounded_seconds % 3600) / 60;
+    let seconds = rounded_seconds % 60;
+    if hours > 0 {
+        format!("{hours}:{minutes}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::load_config_from_str;
+
+    const CONFIG: &str = r#"
+[home_score]
+type = "number"
+default = 0
+position = { x = 10, y = 10 }
+score = true
+confirm = true
+
+[timeouts]
+type = "number"
+default = 2
+position = { x = 10, y = 40 }
+period_budget.period = "period"
+period_budget.values = { "2nd" = 3 }
+
+[home_fouls]
+type = "fouls"
+position = { x = 10, y = 70 }
+bonus = 5
+double_bonus = 7
+reset_period = "period"
+
+[period]
+type = "period"
+labels = ["1st", "2nd"]
+position = { x = 10, y = 100 }
+on_advance.reset_timer = "clock"
+
+[clock]
+type = "timer"
+default = "00:10:00"
+position = { x = 10, y = 130 }
+
+[bench_clock]
+type = "timer"
+mirror = "clock"
+position = { x = 10, y = 160 }
+"#;
+
+    fn runtime() -> RuntimeState {
+        let mut runtime = RuntimeState::new();
+        runtime.replace_config(load_config_from_str(CONFIG).unwrap());
+        runtime
+    }
+
+    fn apply(runtime: &mut RuntimeState, name: &str) -> bool {
+        let action = runtime.resolve_action(name).unwrap();
+        runtime.apply_action(&action)
+    }
+
+    fn text(runtime: &RuntimeState, id: &str) -> Option<String> {
+        runtime.snapshot().components.into_iter().find(|component| component.id == id)?.text
+    }
+
+    #[test]
+    fn confirms_a_guarded_action_on_the_second_press() {
+        let mut runtime = runtime();
+        apply(&mut runtime, "home_score.increase");
+        apply(&mut runtime, "home_score.increase");
+
+        let reset = runtime.resolve_action("home_score.reset").unwrap();
+        assert!(runtime.apply_action(&reset));
+        assert!(runtime.is_pending(&reset));
+        assert_eq!(text(&runtime, "home_score").as_deref(), Some("2"));
+
+        assert!(runtime.apply_action(&reset));
+        assert!(!runtime.is_pending(&reset));
+        assert_eq!(text(&runtime, "home_score").as_deref(), Some("0"));
+    }
+
+    #[test]
+    fn rejects_confirm_true_on_a_component_without_reset() {
+        let config = r#"
+[period]
+type = "period"
+labels = ["1st", "2nd"]
+position = { x = 10, y = 10 }
+confirm = true
+"#;
+        let error = load_config_from_str(config).unwrap_err();
+        assert!(error.contains("'period' has no reset action for confirm = true"), "{error}");
+    }
+
+    #[test]
+    fn names_the_field_of_a_malformed_confirm_table() {
+        let config = r#"
+[home_score]
+type = "number"
+position = { x = 10, y = 10 }
+confirm = { actions = "reset" }
+"#;
+        let error = load_config_from_str(config).unwrap_err();
+        assert!(error.contains("expected a sequence"), "{error}");
+        assert!(error.contains("confirm.actions"), "{error}");
+    }
+
+    #[test]
+    fn rejects_both_names_for_a_cooldown() {
+        let config = r#"
+[home_score]
+type = "number"
+default = 0
+position = { x = 10, y = 10 }
+keybind.increase = { key = "Q", cooldown_ms = 300, debounce_ms = 200 }
+"#;
+        let error = load_config_from_str(config).unwrap_err();
+        assert!(error.contains("cooldown_ms and debounce_ms"), "{error}");
+
+        let config = config.replace("cooldown_ms = 300, ", "");
+        let config = load_config_from_str(&config).unwrap();
+        let ComponentKind::Number { keybind: Some(keybind), .. } = &config.components[0].kind else {
+            panic!("expected a number with a keybind");
+        };
+        assert_eq!(keybind.increase.as_ref().and_then(|spec| spec.cooldown_ms), Some(200));
+    }
+
+    #[test]
+    fn resets_counters_and_the_clock_when_the_period_advances() {
+        let mut runtime = runtime();
+        apply(&mut runtime, "home_fouls.increase");
+        apply(&mut runtime, "timeouts.decrease");
+        apply(&mut runtime, "clock.start");
+        runtime.set_timer_remaining("clock", 65_000).unwrap();
+
+        apply(&mut runtime, "period.forward");
+        assert_eq!(text(&runtime, "period").as_deref(), Some("2nd"));
+        assert_eq!(text(&runtime, "home_fouls").as_deref(), Some("0"));
+        assert_eq!(text(&runtime, "timeouts").as_deref(), Some("3"));
+        assert_eq!(text(&runtime, "clock").as_deref(), Some("10:00"));
+        assert!(!runtime.timer_running("clock"));
+
+        // Moving back does not hand out the budget again.
+        apply(&mut runtime, "timeouts.decrease");
+        apply(&mut runtime, "period.backward");
+        assert_eq!(text(&runtime, "timeouts").as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn flags_the_bonus_once_fouls_reach_each_threshold() {
+        let mut runtime = runtime();
+        let flags = |runtime: &RuntimeState| {
+            let snapshot = runtime.snapshot();
+            let fouls = snapshot.components.iter().find(|component| component.id == "home_fouls").unwrap();
+            (fouls.bonus, fouls.double_bonus)
+        };
+        for _ in 0..4 {
+            apply(&mut runtime, "home_fouls.increase");
+        }
+        assert_eq!(flags(&runtime), (false, false));
+        apply(&mut runtime, "home_fouls.increase");
+        assert_eq!(flags(&runtime), (true, false));
+        apply(&mut runtime, "home_fouls.increase");
+        apply(&mut runtime, "home_fouls.increase");
+        assert_eq!(flags(&runtime), (true, true));
+    }
+
+    #[test]
+    fn mirrors_show_the_primary_timer() {
+        let mut runtime = runtime();
+        runtime.set_timer_remaining("clock", 65_000).unwrap();
+        assert_eq!(text(&runtime, "bench_clock"), text(&runtime, "clock"));
+        assert_eq!(text(&runtime, "bench_clock").as_deref(), Some("01:05"));
+        assert!(runtime.resolve_action("bench_clock.start").is_none());
+    }
+
+    #[test]
+    fn keeps_values_of_components_that_keep_their_id_and_type_on_reload() {
+        let mut runtime = runtime();
+        apply(&mut runtime, "home_score.increase");
+        apply(&mut runtime, "home_fouls.increase");
+        apply(&mut runtime, "period.forward");
+
+        let resized = CONFIG.replace("position = { x = 10, y = 10 }", "position = { x = 20, y = 10 }");
+        runtime.reload_config(load_config_from_str(&resized).unwrap());
+        assert_eq!(text(&runtime, "home_score").as_deref(), Some("1"));
+        assert_eq!(text(&runtime, "period").as_deref(), Some("2nd"));
+        assert_eq!(runtime.event_log().len(), 3);
+
+        let retyped = CONFIG
+            .replace("[home_fouls]\ntype = \"fouls\"", "[home_fouls]\ntype = \"number\"\ndefault = 0")
+            .replace("bonus = 5\ndouble_bonus = 7\nreset_period = \"period\"\n", "");
+        runtime.reload_config(load_config_from_str(&retyped).unwrap());
+        assert_eq!(text(&runtime, "home_fouls").as_deref(), Some("0"));
+        assert_eq!(text(&runtime, "home_score").as_deref(), Some("1"));
+
+        runtime.replace_config(load_config_from_str(CONFIG).unwrap());
+        assert_eq!(text(&runtime, "home_score").as_deref(), Some("0"));
+        assert!(runtime.event_log().is_empty());
+    }
+
+    #[test]
+    fn records_each_action_with_the_period_and_clock() {
+        let mut runtime = runtime();
+        runtime.set_timer_remaining("clock", 65_000).unwrap();
+        apply(&mut runtime, "home_score.increase");
+        apply(&mut runtime, "home_score.reset");
+        runtime.set_console_value("timeouts", "1").unwrap();
+
+        let events: Vec<&LoggedEvent> = runtime.event_log().iter().collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].action, "home_score.increase");
+        assert_eq!(events[0].component.as_deref(), Some("home_score"));
+        assert_eq!(events[0].value.as_deref(), Some("1"));
+        assert_eq!(events[0].period.as_deref(), Some("1st"));
+        assert_eq!(events[0].clock.as_deref(), Some("01:05"));
+        // The armed reset is not logged until it commits.
+        assert_eq!(events[1].action, "timeouts.set");
+        assert_eq!(events[1].value.as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn totals_scoring_numbers_per_period() {
+        let mut runtime = runtime();
+        apply(&mut runtime, "home_score.increase");
+        apply(&mut runtime, "home_score.increase");
+        apply(&mut runtime, "timeouts.decrease");
+        apply(&mut runtime, "period.forward");
+        apply(&mut runtime, "home_score.increase");
+        runtime.set_console_value("home_score", "5").unwrap();
+        apply(&mut runtime, "home_score.decrease");
+
+        let scores = runtime.score_by_period();
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores[0].period, "1st");
+        assert_eq!(scores[0].points, BTreeMap::from([("home_score".to_string(), 2)]));
+        assert_eq!(scores[1].period, "2nd");
+        assert_eq!(scores[1].points, BTreeMap::from([("home_score".to_string(), 2)]));
+    }
+}
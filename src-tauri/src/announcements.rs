@@ -0,0 +1,210 @@
+use crate::config::{AnnouncementMessage, AnnouncementSettings};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct Announcement {
+    pub text: String,
+    pub sound: Option<String>,
+    pub priority: i32,
+    pub duration: Duration,
+}
+
+/// What the target label should change to after a scheduler tick.
+#[derive(Debug, Clone)]
+pub enum AnnouncementUpdate {
+    Show {
+        target: String,
+        text: String,
+        sound: Option<String>,
+    },
+    Restore {
+        target: String,
+    },
+}
+
+struct Queued {
+    announcement: Announcement,
+    sequence: u64,
+}
+
+struct Showing {
+    announcement: Announcement,
+    sequence: u64,
+    ends_at: Instant,
+}
+
+struct Recurring {
+    name: String,
+    interval: Duration,
+    next_at: Instant,
+}
+
+/// Queues announcements and shows them one at a time on the configured label.
+/// Higher priorities go first; a higher-priority announcement interrupts the
+/// current one, which is queued again with the time it had left.
+pub struct AnnouncementScheduler {
+    target: Option<String>,
+    default_duration: Duration,
+    messages: HashMap<String, Announcement>,
+    recurring: Vec<Recurring>,
+    queue: Vec<Queued>,
+    current: Option<Showing>,
+    next_sequence: u64,
+}
+
+impl AnnouncementScheduler {
+    pub fn new() -> Self {
+        Self {
+            target: None,
+            default_duration: Duration::ZERO,
+            messages: HashMap::new(),
+            recurring: Vec::new(),
+            queue: Vec::new(),
+            current: None,
+            next_sequence: 0,
+        }
+    }
+
+    /// Replaces the configured messages and drops anything queued or showing.
+    /// The caller is responsible for the target label, which a config reload resets anyway.
+    pub fn configure(&mut self, settings: Option<&AnnouncementSettings>, now: Instant) {
+        self.queue.clear();
+        self.current = None;
+        self.messages.clear();
+        self.recurring.clear();
+        self.target = settings.map(|settings| settings.target.clone());
+        let Some(settings) = settings else {
+            return;
+        };
+
+        self.default_duration = Duration::from_millis(settings.default_duration_ms);
+        for message in &settings.messages {
+            self.messages
+                .insert(message.name.clone(), self.announcement_from(message));
+            if let Some(interval_ms) = message.interval_ms {
+                let interval = Duration::from_millis(interval_ms as u64);
+                self.recurring.push(Recurring {
+                    name: message.name.clone(),
+                    interval,
+                    next_at: now + interval,
+                });
+            }
+        }
+    }
+
+    pub fn default_duration(&self) -> Duration {
+        self.default_duration
+    }
+
+    /// Queues a configured message by name.
+    pub fn enqueue_message(&mut self, name: &str) -> Result<(), String> {
+        let announcement = self
+            .messages
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("Unknown announcement '{name}'"))?;
+        self.enqueue(announcement)
+    }
+
+    pub fn enqueue(&mut self, announcement: Announcement) -> Result<(), String> {
+        if self.target.is_none() {
+            return Err("No announcement target is configured".to_string());
+        }
+        self.queue.push(Queued {
+            announcement,
+            sequence: self.next_sequence,
+        });
+        self.next_sequence += 1;
+        Ok(())
+    }
+
+    /// Drops every queued announcement and ends the current one. Returns the
+    /// label to restore if something was showing.
+    pub fn clear(&mut self) -> Option<String> {
+        self.queue.clear();
+        self.current.take()?;
+        self.target.clone()
+    }
+
+    pub fn tick(&mut self, now: Instant) -> Option<AnnouncementUpdate> {
+        let target = self.target.clone()?;
+
+        let due: Vec<String> = self
+            .recurring
+            .iter_mut()
+            .filter(|recurring| recurring.next_at <= now)
+            .map(|recurring| {
+                recurring.next_at = now + recurring.interval;
+                recurring.name.clone()
+            })
+            .collect();
+        for name in due {
+            let _ = self.enqueue_message(&name);
+        }
+
+        let ended = self.current.as_ref().is_some_and(|showing| showing.ends_at <= now);
+        if ended {
+            self.current = None;
+        }
+
+        let Some(next_index) = self.next_index() else {
+            return ended.then_some(AnnouncementUpdate::Restore { target });
+        };
+        let next_priority = self.queue[next_index].announcement.priority;
+        if let Some(showing) = &self.current {
+            if next_priority <= showing.announcement.priority {
+                return None;
+            }
+        }
+        let next = self.queue.remove(next_index);
+
+        if let Some(interrupted) = self.current.take() {
+            let mut announcement = interrupted.announcement;
+            announcement.duration = interrupted.ends_at.saturating_duration_since(now);
+            announcement.sound = None;
+            // Keeps its place ahead of later announcements of the same priority.
+            self.queue.push(Queued {
+                announcement,
+                sequence: interrupted.sequence,
+            });
+        }
+
+        let update = AnnouncementUpdate::Show {
+            target,
+            text: next.announcement.text.clone(),
+            sound: next.announcement.sound.clone(),
+        };
+        self.current = Some(Showing {
+            ends_at: now + next.announcement.duration,
+            announcement: next.announcement,
+            sequence: next.sequence,
+        });
+        Some(update)
+    }
+
+    fn next_index(&self) -> Option<usize> {
+        self.queue
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| {
+                a.announcement
+                    .priority
+                    .cmp(&b.announcement.priority)
+                    .then(b.sequence.cmp(&a.sequence))
+            })
+            .map(|(index, _)| index)
+    }
+
+    fn announcement_from(&self, message: &AnnouncementMessage) -> Announcement {
+        Announcement {
+            text: message.text.clone(),
+            sound: message.sound.clone(),
+            priority: message.priority,
+            duration: message
+                .duration_ms
+                .map(Duration::from_millis)
+                .unwrap_or(self.default_duration),
+        }
+    }
+}
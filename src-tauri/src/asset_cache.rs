@@ -0,0 +1,285 @@
+use crate::assets::{visit_image_paths, IMAGE_EXTENSIONS};
+use crate::config::ScoreboardConfig;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Folder under the cache holding unpacked template packs.
+pub const PACKAGES_DIR: &str = "packages";
+/// Folder under the cache holding downloaded files, named by their SHA-256.
+const OBJECTS_DIR: &str = "objects";
+const INDEX_FILE: &str = "index.json";
+/// Least recently used entries are removed once the cache grows past this.
+pub const MAX_CACHE_BYTES: u64 = 512 * 1024 * 1024;
+const MAX_DOWNLOAD_BYTES: u64 = 64 * 1024 * 1024;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+pub fn is_remote_asset(source: &str) -> bool {
+    source.starts_with("http://") || source.starts_with("https://")
+}
+
+/// Payload of `clear_asset_cache` and `get_asset_cache_usage`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CacheUsage {
+    pub entries: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Index {
+    /// Downloaded URL to its entry under `objects`.
+    urls: HashMap<String, String>,
+    /// Entry, such as `objects/<sha256>.png` or `packages/<pack>-<hash>`, to
+    /// the Unix time in seconds it was last loaded.
+    last_used: HashMap<String, u64>,
+}
+
+/// Keeps unpacked template packs and downloaded images in the app data
+/// folder so loading the same config again needs neither, and removes the
+/// least recently used ones to stay under `MAX_CACHE_BYTES`. Entries the
+/// loaded config uses are never removed.
+pub struct AssetCache {
+    dir: Option<PathBuf>,
+    index: Index,
+    in_use: HashSet<String>,
+}
+
+impl AssetCache {
+    pub fn new() -> Self {
+        Self {
+            dir: None,
+            index: Index::default(),
+            in_use: HashSet::new(),
+        }
+    }
+
+    pub fn set_dir(&mut self, dir: PathBuf) {
+        self.index = fs::read_to_string(dir.join(INDEX_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        self.dir = Some(dir);
+    }
+
+    fn dir(&self) -> Result<&Path, String> {
+        self.dir.as_deref().ok_or_else(|| "Asset cache folder is not available".to_string())
+    }
+
+    /// Where template packs are unpacked.
+    pub fn package_dir(&self) -> Result<PathBuf, String> {
+        Ok(self.dir()?.join(PACKAGES_DIR))
+    }
+
+    /// Replaces image URLs in `config` with downloaded copies and returns the
+    /// entries it uses, for `mark_in_use` once the config is installed. A URL
+    /// that was downloaded before is not fetched again.
+    pub fn prepare(&mut self, config: &mut ScoreboardConfig) -> Result<HashSet<String>, String> {
+        let mut in_use = HashSet::new();
+        if let Ok(packages) = self.package_dir() {
+            if let Ok(relative) = config.base_dir().strip_prefix(&packages) {
+                if let Some(pack) = relative.components().next() {
+                    in_use.insert(format!("{PACKAGES_DIR}/{}", pack.as_os_str().to_string_lossy()));
+                }
+            }
+        }
+        let mut result = Ok(());
+        visit_image_paths(config, |source| {
+            if result.is_err() || !is_remote_asset(source) {
+                return;
+            }
+            match self.fetch(source) {
+                Ok((entry, path)) => {
+                    in_use.insert(entry);
+                    *source = path.to_string_lossy().to_string();
+                }
+                Err(e) => result = Err(e),
+            }
+        });
+        result?;
+        Ok(in_use)
+    }
+
+    /// Records the entries the installed config uses and evicts old ones.
+    /// Until then, the previous config's entries are kept.
+    pub fn mark_in_use(&mut self, in_use: HashSet<String>) {
+        let now = unix_seconds();
+        for entry in &in_use {
+            self.index.last_used.insert(entry.clone(), now);
+        }
+        self.in_use = in_use;
+        self.evict(MAX_CACHE_BYTES);
+        self.save_index();
+    }
+
+    /// Puts back the URLs `prepare` replaced, so a saved config still downloads them.
+    pub fn restore_urls(&self, config: &mut ScoreboardConfig) {
+        let Ok(dir) = self.dir() else {
+            return;
+        };
+        let urls: HashMap<String, &String> = self
+            .index
+            .urls
+            .iter()
+            .map(|(url, entry)| (dir.join(entry).to_string_lossy().to_string(), url))
+            .collect();
+        visit_image_paths(config, |source| {
+            if let Some(url) = urls.get(source.as_str()) {
+                *source = url.to_string();
+            }
+        });
+    }
+
+    /// The cached copy of `url`, downloading it when missing or damaged.
+    fn fetch(&mut self, url: &str) -> Result<(String, PathBuf), String> {
+        let dir = self.dir()?.to_path_buf();
+        if let Some(entry) = self.index.urls.get(url) {
+            let path = dir.join(entry);
+            if fs::read(&path).is_ok_and(|bytes| entry_for(&bytes, extension_of(entry)) == *entry) {
+                return Ok((entry.clone(), path));
+            }
+        }
+
+        let extension = url
+            .split(['?', '#'])
+            .next()
+            .and_then(|path| path.rsplit_once('.'))
+            .map(|(_, extension)| extension.to_ascii_lowercase())
+            .filter(|extension| IMAGE_EXTENSIONS.contains(&extension.as_str()))
+            .ok_or_else(|| format!("{url} is not an image (expected one of: {})", IMAGE_EXTENSIONS.join(", ")))?;
+        let response = ureq::AgentBuilder::new()
+            .timeout(FETCH_TIMEOUT)
+            .build()
+            .get(url)
+            .call()
+            // ureq's errors already name the URL.
+            .map_err(|e| format!("Failed downloading {e}"))?;
+        let mut bytes = Vec::new();
+        response
+            .into_reader()
+            .take(MAX_DOWNLOAD_BYTES + 1)
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("Failed downloading {url}: {e}"))?;
+        if bytes.len() as u64 > MAX_DOWNLOAD_BYTES {
+            return Err(format!("{url} is larger than {} MB", MAX_DOWNLOAD_BYTES / (1024 * 1024)));
+        }
+
+        let entry = entry_for(&bytes, &extension);
+        let path = dir.join(&entry);
+        let objects = dir.join(OBJECTS_DIR);
+        fs::create_dir_all(&objects).map_err(|e| format!("Failed creating asset cache {}: {e}", objects.display()))?;
+        // Written beside the target and renamed, so a partial file never has a valid name.
+        let partial = path.with_extension("partial");
+        fs::write(&partial, &bytes)
+            .and_then(|_| fs::rename(&partial, &path))
+            .map_err(|e| format!("Failed caching {url}: {e}"))?;
+        self.index.urls.insert(url.to_string(), entry.clone());
+        Ok((entry, path))
+    }
+
+    /// Removes every entry the loaded config does not use.
+    /// Returns what was freed.
+    pub fn clear(&mut self) -> CacheUsage {
+        let before = self.usage();
+        self.evict(0);
+        self.save_index();
+        let after = self.usage();
+        CacheUsage {
+            entries: before.entries.saturating_sub(after.entries),
+            bytes: before.bytes.saturating_sub(after.bytes),
+        }
+    }
+
+    pub fn usage(&self) -> CacheUsage {
+        let entries = self.entries();
+        CacheUsage {
+            entries: entries.len(),
+            bytes: entries.iter().map(|(_, bytes)| bytes).sum(),
+        }
+    }
+
+    /// Every entry on disk with its size.
+    fn entries(&self) -> Vec<(String, u64)> {
+        let Ok(dir) = self.dir() else {
+            return Vec::new();
+        };
+        let mut entries = Vec::new();
+        for folder in [OBJECTS_DIR, PACKAGES_DIR] {
+            let Ok(items) = fs::read_dir(dir.join(folder)) else {
+                continue;
+            };
+            for item in items.flatten() {
+                let name = format!("{folder}/{}", item.file_name().to_string_lossy());
+                entries.push((name, disk_size(&item.path())));
+            }
+        }
+        entries
+    }
+
+    /// Removes least recently used entries until the rest fit in `max_bytes`.
+    fn evict(&mut self, max_bytes: u64) {
+        let Ok(dir) = self.dir().map(Path::to_path_buf) else {
+            return;
+        };
+        let mut entries = self.entries();
+        let mut total: u64 = entries.iter().map(|(_, bytes)| bytes).sum();
+        entries.sort_by_key(|(entry, _)| self.index.last_used.get(entry).copied().unwrap_or(0));
+        for (entry, bytes) in entries {
+            if total <= max_bytes {
+                break;
+            }
+            if self.in_use.contains(&entry) {
+                continue;
+            }
+            let path = dir.join(&entry);
+            let removed = if path.is_dir() {
+                fs::remove_dir_all(&path)
+            } else {
+                fs::remove_file(&path)
+            };
+            if removed.is_ok() {
+                total -= bytes;
+                self.index.last_used.remove(&entry);
+                self.index.urls.retain(|_, cached| *cached != entry);
+            }
+        }
+    }
+
+    fn save_index(&self) {
+        let Ok(dir) = self.dir() else {
+            return;
+        };
+        if let Ok(content) = serde_json::to_string(&self.index) {
+            let _ = fs::create_dir_all(dir).and_then(|_| fs::write(dir.join(INDEX_FILE), content));
+        }
+    }
+}
+
+fn entry_for(bytes: &[u8], extension: &str) -> String {
+    let digest: String = Sha256::digest(bytes).iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("{OBJECTS_DIR}/{digest}.{extension}")
+}
+
+fn extension_of(entry: &str) -> &str {
+    entry.rsplit_once('.').map(|(_, extension)| extension).unwrap_or_default()
+}
+
+fn disk_size(path: &Path) -> u64 {
+    if path.is_dir() {
+        fs::read_dir(path)
+            .map(|items| items.flatten().map(|item| disk_size(&item.path())).sum())
+            .unwrap_or(0)
+    } else {
+        fs::metadata(path).map(|metadata| metadata.len()).unwrap_or(0)
+    }
+}
+
+fn unix_seconds() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
@@ -0,0 +1,94 @@
+use crate::config::{ComponentKind, PossessionDisplay, ScoreboardConfig};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Extensions accepted for image sources picked or imported at runtime.
+pub const IMAGE_EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "gif", "webp", "bmp"];
+/// Folder, next to the active config, that imported images are copied into.
+pub const ASSETS_DIR: &str = "assets";
+
+/// Refuses paths that are not an existing file with an image extension, so a
+/// typo does not leave an editable image blank on air.
+pub fn validate_image_file(path: &Path) -> Result<(), String> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase);
+    if !extension.is_some_and(|extension| IMAGE_EXTENSIONS.contains(&extension.as_str())) {
+        return Err(format!(
+            "{} is not an image (expected one of: {})",
+            path.display(),
+            IMAGE_EXTENSIONS.join(", ")
+        ));
+    }
+    if !path.is_file() {
+        return Err(format!("Image {} does not exist", path.display()));
+    }
+    Ok(())
+}
+
+/// Copies an image into `assets_dir` and returns the copy's path. A file that
+/// is already there with the same content is reused; a different file with
+/// the same name gets a numbered name instead of being overwritten.
+pub fn import_image(source: &Path, assets_dir: &Path) -> Result<PathBuf, String> {
+    validate_image_file(source)?;
+    if source.parent() == Some(assets_dir) {
+        return Ok(source.to_path_buf());
+    }
+    fs::create_dir_all(assets_dir)
+        .map_err(|e| format!("Failed creating assets folder {}: {e}", assets_dir.display()))?;
+    let content = fs::read(source).map_err(|e| format!("Failed reading image {}: {e}", source.display()))?;
+
+    let stem = source.file_stem().and_then(|stem| stem.to_str()).unwrap_or("image");
+    let extension = source.extension().and_then(|extension| extension.to_str()).unwrap_or_default();
+    for attempt in 0.. {
+        let name = match attempt {
+            0 => format!("{stem}.{extension}"),
+            attempt => format!("{stem}-{attempt}.{extension}"),
+        };
+        let target = assets_dir.join(name);
+        match fs::read(&target) {
+            Ok(existing) if existing == content => return Ok(target),
+            Ok(_) => continue,
+            Err(_) => {
+                fs::write(&target, &content)
+                    .map_err(|e| format!("Failed copying image to {}: {e}", target.display()))?;
+                return Ok(target);
+            }
+        }
+    }
+    unreachable!("numbered names never run out")
+}
+
+/// Rewrites image paths inside `base_dir` relative to it, so a config saved
+/// there keeps working when the folder moves to another machine. Paths
+/// elsewhere stay absolute.
+pub fn relativize_image_paths(config: &mut ScoreboardConfig, base_dir: &Path) {
+    visit_image_paths(config, |path: &mut String| {
+        if let Ok(stripped) = Path::new(path.as_str()).strip_prefix(base_dir) {
+            *path = stripped
+                .components()
+                .map(|part| part.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+        }
+    });
+}
+
+/// Calls `visit` with the configured path of each image the board shows.
+pub fn visit_image_paths(config: &mut ScoreboardConfig, mut visit: impl FnMut(&mut String)) {
+    if let Some(image) = config.global.splash.as_mut().and_then(|splash| splash.image.as_mut()) {
+        visit(image);
+    }
+    for component in &mut config.components {
+        match &mut component.kind {
+            ComponentKind::Image { source, .. } => visit(source),
+            ComponentKind::ImageToggle { sources, .. }
+            | ComponentKind::Possession {
+                display: PossessionDisplay::Images { sources, .. },
+                ..
+            } => sources.iter_mut().for_each(&mut visit),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use crate::config::{AudioSettings, HornPattern, SOUND_PATTERN_PREFIX};
+use rodio::cpal::traits::HostTrait;
+use rodio::source::SineWave;
+use rodio::{Decoder, DeviceTrait, OutputStream, OutputStreamHandle, Sink, Source};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+type SoundSource = Box<dyn Source<Item = i16> + Send>;
+
+const BEEP_FREQUENCY_HZ: f32 = 880.0;
+const BEEP_DURATION: Duration = Duration::from_millis(120);
+const BEEP_VOLUME: f32 = 0.25;
+const HORN_FREQUENCY_HZ: f32 = 330.0;
+
+/// What a sound is for, so `[global.audio] device.<cue>` can route it.
+#[derive(Debug, Clone, Copy)]
+pub enum SoundCue {
+    Action,
+    Expire,
+    Tick,
+    Announcement,
+    AuxTimer,
+    Music,
+}
+
+impl SoundCue {
+    pub fn name(self) -> &'static str {
+        match self {
+            SoundCue::Action => "action",
+            SoundCue::Expire => "expire",
+            SoundCue::Tick => "tick",
+            SoundCue::Announcement => "announcement",
+            SoundCue::AuxTimer => "aux_timer",
+            SoundCue::Music => "music",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            SoundCue::Action,
+            SoundCue::Expire,
+            SoundCue::Tick,
+            SoundCue::Announcement,
+            SoundCue::AuxTimer,
+            SoundCue::Music,
+        ]
+        .into_iter()
+        .find(|cue| cue.name() == name)
+    }
+}
+
+/// An output device as reported by `list_audio_devices`.
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioDevice {
+    pub name: String,
+    pub default: bool,
+}
+
+pub fn list_devices() -> Result<Vec<AudioDevice>, String> {
+    let host = rodio::cpal::default_host();
+    let default_name = host.default_output_device().and_then(|device| device.name().ok());
+    let devices = host
+        .output_devices()
+        .map_err(|e| format!("Failed listing audio devices: {e}"))?;
+    Ok(devices
+        .filter_map(|device| device.name().ok())
+        .map(|name| AudioDevice {
+            default: default_name.as_deref() == Some(name.as_str()),
+            name,
+        })
+        .collect())
+}
+
+enum AudioCommand {
+    Play {
+        /// Played one after another.
+        sources: Vec<SoundSource>,
+        /// The system default output when unset.
+        device: Option<String>,
+        reply: Sender<Result<(), String>>,
+    },
+}
+
+/// Open output streams, keyed by device name with `None` for the system default.
+type Outputs = HashMap<Option<String>, (OutputStream, OutputStreamHandle)>;
+
+/// Owns a dedicated playback thread. rodio output streams are not `Send`, so the
+/// stream lives on that thread and sounds are handed over already decoded.
+pub struct AudioPlayer {
+    commands: Mutex<Sender<AudioCommand>>,
+    patterns: Mutex<Vec<HornPattern>>,
+    routing: Mutex<Option<AudioSettings>>,
+}
+
+impl AudioPlayer {
+    pub fn new() -> Self {
+        let (commands, receiver) = mpsc::channel::<AudioCommand>();
+        thread::spawn(move || {
+            let mut outputs = Outputs::new();
+            for command in receiver {
+                match command {
+                    AudioCommand::Play { sources, device, reply } => {
+                        let _ = reply.send(play_sources(&mut outputs, device, sources));
+                    }
+                }
+            }
+        });
+
+        Self {
+            commands: Mutex::new(commands),
+            patterns: Mutex::new(Vec::new()),
+            routing: Mutex::new(None),
+        }
+    }
+
+    /// Replaces the `[global.audio]` device choices used by later sounds.
+    pub fn set_routing(&self, routing: Option<AudioSettings>) {
+        if let Ok(mut current) = self.routing.lock() {
+            *current = routing;
+        }
+    }
+
+    /// Replaces the horn patterns that `pattern:<name>` sounds refer to.
+    pub fn set_patterns(&self, patterns: Vec<HornPattern>) {
+        if let Ok(mut current) = self.patterns.lock() {
+            *current = patterns;
+        }
+    }
+
+    /// Starts playing a sound file on the device routed for `cue` and
+    /// returns once playback has begun.
+    pub fn play(&self, path: &str, cue: Option<SoundCue>) -> Result<(), String> {
+        self.play_for(path, None, cue)
+    }
+
+    /// Like `play`, but with a duration the sound loops until that much time
+    /// has passed and is then cut off. Horn patterns keep their own timing.
+    pub fn play_for(&self, path: &str, duration: Option<Duration>, cue: Option<SoundCue>) -> Result<(), String> {
+        if let Some(name) = path.strip_prefix(SOUND_PATTERN_PREFIX) {
+            return self.play_pattern(name, cue);
+        }
+        let file = File::open(path).map_err(|e| format!("Failed opening sound {path}: {e}"))?;
+        let decoder =
+            Decoder::new(BufReader::new(file)).map_err(|e| format!("Failed decoding sound {path}: {e}"))?;
+        let source: SoundSource = match duration {
+            Some(duration) => Box::new(decoder.buffered().repeat_infinite().take_duration(duration)),
+            None => Box::new(decoder),
+        };
+        self.send(vec![source], cue)
+    }
+
+    fn play_pattern(&self, name: &str, cue: Option<SoundCue>) -> Result<(), String> {
+        let pattern = self
+            .patterns
+            .lock()
+            .map_err(|_| "Audio pattern lock poisoned".to_string())?
+            .iter()
+            .find(|pattern| pattern.name == name)
+            .cloned()
+            .ok_or_else(|| format!("Unknown horn pattern '{name}'"))?;
+        let horn = match &pattern.sound {
+            Some(path) => {
+                let file = File::open(path).map_err(|e| format!("Failed opening sound {path}: {e}"))?;
+                let decoder = Decoder::new(BufReader::new(file))
+                    .map_err(|e| format!("Failed decoding sound {path}: {e}"))?;
+                Some(decoder.buffered())
+            }
+            None => None,
+        };
+
+        let gap = Duration::from_millis(pattern.gap_ms);
+        let sources = pattern
+            .blasts_ms
+            .iter()
+            .enumerate()
+            .map(|(index, &blast_ms)| {
+                let length = Duration::from_millis(blast_ms);
+                let blast: SoundSource = match &horn {
+                    Some(horn) => Box::new(horn.clone().repeat_infinite().take_duration(length)),
+                    None => Box::new(
+                        SineWave::new(HORN_FREQUENCY_HZ)
+                            .take_duration(length)
+                            .amplify(BEEP_VOLUME)
+                            .convert_samples::<i16>(),
+                    ),
+                };
+                if index == 0 {
+                    blast
+                } else {
+                    Box::new(blast.delay(gap))
+                }
+            })
+            .collect();
+        self.send(sources, cue)
+    }
+
+    /// Plays a short generated tone, for countdown ticks without a sound file.
+    pub fn play_beep(&self, cue: Option<SoundCue>) -> Result<(), String> {
+        let source = SineWave::new(BEEP_FREQUENCY_HZ)
+            .take_duration(BEEP_DURATION)
+            .amplify(BEEP_VOLUME)
+            .convert_samples::<i16>();
+        self.send(vec![Box::new(source)], cue)
+    }
+
+    fn send(&self, sources: Vec<SoundSource>, cue: Option<SoundCue>) -> Result<(), String> {
+        let device = self
+            .routing
+            .lock()
+            .map_err(|_| "Audio routing lock poisoned".to_string())?
+            .as_ref()
+            .and_then(|routing| routing.device_for(cue.map(SoundCue::name)))
+            .map(str::to_string);
+        let (reply, response) = mpsc::channel();
+        self.commands
+            .lock()
+            .map_err(|_| "Audio command lock poisoned".to_string())?
+            .send(AudioCommand::Play { sources, device, reply })
+            .map_err(|_| "Audio thread is not running".to_string())?;
+        response
+            .recv()
+            .map_err(|_| "Audio thread is not running".to_string())?
+    }
+}
+
+pub fn open_output(device: Option<&str>) -> Result<(OutputStream, OutputStreamHandle), String> {
+    let Some(name) = device else {
+        return OutputStream::try_default().map_err(|e| format!("No audio output device available: {e}"));
+    };
+    let device = rodio::cpal::default_host()
+        .output_devices()
+        .map_err(|e| format!("Failed listing audio devices: {e}"))?
+        .find(|device| device.name().is_ok_and(|device_name| device_name == name))
+        .ok_or_else(|| format!("Audio output device '{name}' not found"))?;
+    OutputStream::try_from_device(&device).map_err(|e| format!("Failed opening audio device '{name}': {e}"))
+}
+
+fn play_sources(outputs: &mut Outputs, device: Option<String>, sources: Vec<SoundSource>) -> Result<(), String> {
+    if !outputs.contains_key(&device) {
+        let stream = open_output(device.as_deref())?;
+        outputs.insert(device.clone(), stream);
+    }
+    let Some((_, handle)) = outputs.get(&device) else {
+        return Err("No audio output device available".to_string());
+    };
+
+    match Sink::try_new(handle) {
+        Ok(sink) => {
+            for source in sources {
+                sink.append(source);
+            }
+            sink.detach();
+            Ok(())
+        }
+        Err(e) => {
+            // Drop the stream so the next sound reopens the device, which may
+            // have been unplugged or, for the default, changed.
+            outputs.remove(&device);
+            Err(format!("Failed starting audio playback: {e}"))
+        }
+    }
+}
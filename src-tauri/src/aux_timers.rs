@@ -0,0 +1,136 @@
+use crate::config::AuxTimerSettings;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// An auxiliary timer as reported by `list_aux_timers` and `scoreboard://aux-timers`.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuxTimerStatus {
+    pub name: String,
+    /// The configured label, or the name when there is none.
+    pub label: String,
+    pub duration_ms: i64,
+    pub remaining_ms: i64,
+    pub running: bool,
+}
+
+struct AuxTimer {
+    settings: AuxTimerSettings,
+    /// Time left while stopped; a running timer counts toward `ends_at` instead.
+    remaining_ms: i64,
+    ends_at: Option<Instant>,
+}
+
+impl AuxTimer {
+    fn remaining_ms(&self, now: Instant) -> i64 {
+        match self.ends_at {
+            Some(ends_at) => ends_at.saturating_duration_since(now).as_millis() as i64,
+            None => self.remaining_ms,
+        }
+    }
+}
+
+/// Countdowns kept apart from the board: they are not drawn and not saved
+/// with the session.
+pub struct AuxTimers {
+    timers: Vec<AuxTimer>,
+}
+
+impl AuxTimers {
+    pub fn new() -> Self {
+        Self { timers: Vec::new() }
+    }
+
+    /// Replaces the configured timers. A timer that keeps its name and
+    /// duration carries on where it was.
+    pub fn configure(&mut self, settings: &[AuxTimerSettings]) {
+        let mut previous = std::mem::take(&mut self.timers);
+        for settings in settings {
+            let kept = previous.iter().position(|timer| {
+                timer.settings.name == settings.name && timer.settings.duration_ms == settings.duration_ms
+            });
+            let (remaining_ms, ends_at) = match kept {
+                Some(index) => {
+                    let timer = previous.swap_remove(index);
+                    (timer.remaining_ms, timer.ends_at)
+                }
+                None => (settings.duration_ms, None),
+            };
+            self.timers.push(AuxTimer {
+                settings: settings.clone(),
+                remaining_ms,
+                ends_at,
+            });
+        }
+    }
+
+    pub fn list(&self, now: Instant) -> Vec<AuxTimerStatus> {
+        self.timers
+            .iter()
+            .map(|timer| AuxTimerStatus {
+                name: timer.settings.name.clone(),
+                label: timer.settings.label.clone().unwrap_or_else(|| timer.settings.name.clone()),
+                duration_ms: timer.settings.duration_ms,
+                remaining_ms: timer.remaining_ms(now),
+                running: timer.ends_at.is_some(),
+            })
+            .collect()
+    }
+
+    pub fn any_running(&self) -> bool {
+        self.timers.iter().any(|timer| timer.ends_at.is_some())
+    }
+
+    /// Starts the countdown, from the full duration again if it had run out.
+    /// Returns false when it was already running.
+    pub fn start(&mut self, name: &str, now: Instant) -> Result<bool, String> {
+        let timer = self.get_mut(name)?;
+        if timer.ends_at.is_some() {
+            return Ok(false);
+        }
+        if timer.remaining_ms <= 0 {
+            timer.remaining_ms = timer.settings.duration_ms;
+        }
+        timer.ends_at = Some(now + Duration::from_millis(timer.remaining_ms as u64));
+        Ok(true)
+    }
+
+    /// Returns false when it was not running.
+    pub fn stop(&mut self, name: &str, now: Instant) -> Result<bool, String> {
+        let timer = self.get_mut(name)?;
+        if timer.ends_at.is_none() {
+            return Ok(false);
+        }
+        timer.remaining_ms = timer.remaining_ms(now);
+        timer.ends_at = None;
+        Ok(true)
+    }
+
+    /// Stops the timer and puts it back to its full duration.
+    pub fn reset(&mut self, name: &str) -> Result<bool, String> {
+        let timer = self.get_mut(name)?;
+        let changed = timer.ends_at.is_some() || timer.remaining_ms != timer.settings.duration_ms;
+        timer.remaining_ms = timer.settings.duration_ms;
+        timer.ends_at = None;
+        Ok(changed)
+    }
+
+    /// Stops the timers that ran out and returns their settings.
+    pub fn tick(&mut self, now: Instant) -> Vec<AuxTimerSettings> {
+        let mut expired = Vec::new();
+        for timer in &mut self.timers {
+            if timer.ends_at.is_some_and(|ends_at| ends_at <= now) {
+                timer.remaining_ms = 0;
+                timer.ends_at = None;
+                expired.push(timer.settings.clone());
+            }
+        }
+        expired
+    }
+
+    fn get_mut(&mut self, name: &str) -> Result<&mut AuxTimer, String> {
+        self.timers
+            .iter_mut()
+            .find(|timer| timer.settings.name == name)
+            .ok_or_else(|| format!("Unknown aux timer '{name}'"))
+    }
+}
@@ -0,0 +1,159 @@
+use crate::config::{self, ConfigDiagnostic, DiagnosticSeverity};
+use crate::fonts::{bundled_fonts, choose_font_families};
+use crate::package::{extract_package, is_package};
+use crate::profile::profile_config;
+use crate::state::RuntimeState;
+use crate::TIMER_TICK;
+use std::path::{Path, PathBuf};
+
+const USAGE: &str = "Usage:
+  aolot-scoreboard validate <config.toml|pack.scoreboardpkg>...
+  aolot-scoreboard dump-snapshot [--layout <name>] <config.toml|pack.scoreboardpkg>
+  aolot-scoreboard profile <config.toml|pack.scoreboardpkg>
+
+Without a subcommand the scoreboard window opens.";
+
+/// Runs a command-line subcommand when `args` (without the program name)
+/// names one, returning the exit code; `None` leaves them to the window.
+pub fn run(args: &[String]) -> Option<i32> {
+    let (command, rest) = args.split_first()?;
+    let subcommand: fn(&[String]) -> Result<i32, String> = match command.as_str() {
+        "validate" => validate,
+        "dump-snapshot" => dump_snapshot,
+        "profile" => profile,
+        "help" | "--help" | "-h" => usage,
+        _ => return None,
+    };
+    attach_parent_console();
+    Some(subcommand(rest).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        2
+    }))
+}
+
+fn usage(_args: &[String]) -> Result<i32, String> {
+    println!("{USAGE}");
+    Ok(0)
+}
+
+/// Release builds on Windows start without a console, so what the
+/// subcommands print would go nowhere; this writes it to the terminal that
+/// started the app instead. Redirected output keeps its file or pipe.
+#[cfg(windows)]
+fn attach_parent_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    // Fails harmlessly when there is no parent console or one is attached already.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_parent_console() {}
+
+/// Prints every problem in each file; fails when any file has an error.
+fn validate(args: &[String]) -> Result<i32, String> {
+    if args.is_empty() || args.iter().any(|arg| arg.starts_with('-')) {
+        return Err(USAGE.to_string());
+    }
+    let mut failed = false;
+    for path in args {
+        let diagnostics = config::diagnose_config_path(&config_path(path)?)?;
+        let errors = diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+            .count();
+        for diagnostic in &diagnostics {
+            println!("{path}: {}", describe(diagnostic));
+        }
+        let warnings = diagnostics.len() - errors;
+        println!("{path}: {errors} error(s), {warnings} warning(s)");
+        failed |= errors > 0;
+    }
+    Ok(i32::from(failed))
+}
+
+/// The config to read for `path`; template packs are unpacked to a temporary folder.
+fn config_path(path: &str) -> Result<PathBuf, String> {
+    let path = Path::new(path);
+    if is_package(path) {
+        return extract_package(path, &std::env::temp_dir().join("aolot-scoreboard-packages"));
+    }
+    Ok(path.to_path_buf())
+}
+
+fn describe(diagnostic: &ConfigDiagnostic) -> String {
+    let severity = match diagnostic.severity {
+        DiagnosticSeverity::Error => "error",
+        DiagnosticSeverity::Warning => "warning",
+    };
+    let location = match (&diagnostic.component, &diagnostic.field) {
+        (Some(component), Some(field)) => format!(" [{component}.{field}]"),
+        (Some(component), None) => format!(" [{component}]"),
+        _ => String::new(),
+    };
+    format!("{severity}{location}: {}", diagnostic.message)
+}
+
+/// Prints the snapshot the window would draw right after loading the file.
+fn dump_snapshot(args: &[String]) -> Result<i32, String> {
+    let (layout, path) = match args {
+        [path] if !path.starts_with('-') => (None, path),
+        [flag, name, path] | [path, flag, name] if flag == "--layout" => (Some(name.as_str()), path),
+        _ => return Err(USAGE.to_string()),
+    };
+    let mut config = config::load_config_from_path(&config_path(path)?)?;
+    if layout.is_some() {
+        config = config.with_layout(layout)?;
+    }
+    let bundled = bundled_fonts(config.base_dir());
+    for choice in choose_font_families(&mut config, &bundled).iter().filter(|choice| !choice.installed) {
+        let owner = choice.component.as_deref().unwrap_or("global");
+        eprintln!(
+            "warning: None of the fonts '{}' for '{owner}' is installed; using '{}'",
+            choice.families.join("', '"),
+            choice.chosen
+        );
+    }
+    let mut runtime = RuntimeState::new();
+    runtime.replace_config(config);
+    let snapshot =
+        serde_json::to_string_pretty(&runtime.snapshot()).map_err(|e| format!("Failed encoding snapshot: {e}"))?;
+    println!("{snapshot}");
+    Ok(0)
+}
+
+/// Prints how long the config takes to load and what each update costs;
+/// fails when the profile has warnings.
+fn profile(args: &[String]) -> Result<i32, String> {
+    let [path] = args else {
+        return Err(USAGE.to_string());
+    };
+    let config_path = config_path(path)?;
+    let profile = profile_config(|| config::load_config_from_path(&config_path), TIMER_TICK)?;
+    let types: Vec<String> = profile
+        .components_by_type
+        .iter()
+        .map(|(component_type, count)| format!("{count} {component_type}"))
+        .collect();
+    println!("load: {:.1} ms", profile.parse_ms);
+    println!("components: {} ({})", profile.component_count, types.join(", "));
+    println!("groups: {}, layouts: {}", profile.group_count, profile.layout_count);
+    println!("snapshot: {:.1} KB", profile.snapshot_bytes as f64 / 1024.0);
+    println!(
+        "update: {:.2} ms ({:.2} ms build + {:.2} ms serialize), {:.1}% of the {:.0} ms loop",
+        profile.estimated_emit_ms,
+        profile.snapshot_ms,
+        profile.serialize_ms,
+        profile.tick_share * 100.0,
+        profile.tick_ms
+    );
+    for warning in &profile.warnings {
+        println!("warning: {warning}");
+    }
+    Ok(i32::from(!profile.warnings.is_empty()))
+}
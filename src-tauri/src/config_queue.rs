@@ -0,0 +1,138 @@
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Condvar, Mutex, PoisonError};
+
+/// A config change waiting for the apply worker.
+#[derive(Debug, Clone)]
+pub enum ConfigJob {
+    /// Loads a config file from the start and watches it for changes.
+    File(PathBuf),
+    /// Loads config text from the start; nothing is watched afterwards.
+    Text(String),
+    /// Reapplies the watched file after it changed, keeping values.
+    Reload,
+    /// Reapplies the current config with another of its layouts, keeping values.
+    Layout(Option<String>),
+}
+
+struct Pending {
+    job: ConfigJob,
+    reply: Sender<Result<(), String>>,
+}
+
+/// Orders every config apply through one worker, so a hot reload, a menu
+/// load, and a command load never run at the same time. Queued jobs that a
+/// newer one makes pointless are dropped: a file or text load replaces
+/// everything still waiting, and a reload replaces waiting reloads of the same
+/// file. A layout switch drops nothing, as it builds on whatever was applied
+/// before. Callers waiting on a dropped job get `SUPERSEDED` back.
+pub struct ConfigQueue {
+    pending: Mutex<VecDeque<Pending>>,
+    ready: Condvar,
+}
+
+/// The result of a job dropped in favour of a newer one.
+const SUPERSEDED: &str = "Config apply was superseded by a newer load";
+
+impl ConfigQueue {
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(VecDeque::new()),
+            ready: Condvar::new(),
+        }
+    }
+
+    /// Queues `job`; the result arrives on the returned receiver once it has
+    /// been applied. Dropping the receiver leaves errors to the worker to report.
+    pub fn submit(&self, job: ConfigJob) -> Receiver<Result<(), String>> {
+        let (reply, result) = mpsc::channel();
+        let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+        let superseded: fn(&ConfigJob) -> bool = match job {
+            ConfigJob::File(_) | ConfigJob::Text(_) => |_| true,
+            ConfigJob::Reload => |queued| matches!(queued, ConfigJob::Reload),
+            ConfigJob::Layout(_) => |_| false,
+        };
+        pending.retain(|queued| {
+            if !superseded(&queued.job) {
+                return true;
+            }
+            let _ = queued.reply.send(Err(SUPERSEDED.to_string()));
+            false
+        });
+        pending.push_back(Pending { job, reply });
+        self.ready.notify_one();
+        result
+    }
+
+    /// Waits for the next job, for the worker thread.
+    pub fn next(&self) -> (ConfigJob, Sender<Result<(), String>>) {
+        let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+        loop {
+            if let Some(Pending { job, reply }) = pending.pop_front() {
+                return (job, reply);
+            }
+            pending = self.ready.wait(pending).unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(name: &str) -> ConfigJob {
+        ConfigJob::File(PathBuf::from(name))
+    }
+
+    fn file_of(job: ConfigJob) -> PathBuf {
+        match job {
+            ConfigJob::File(path) => path,
+            other => panic!("expected a file load, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn collapses_waiting_loads_into_the_latest() {
+        let queue = ConfigQueue::new();
+        let _first = queue.submit(load("first.toml"));
+        let _reload = queue.submit(ConfigJob::Reload);
+        let _second = queue.submit(ConfigJob::Reload);
+        let _last = queue.submit(load("last.toml"));
+
+        assert_eq!(queue.pending.lock().unwrap().len(), 1);
+        assert_eq!(file_of(queue.next().0), PathBuf::from("last.toml"));
+    }
+
+    #[test]
+    fn keeps_layout_switches_when_a_reload_arrives() {
+        let queue = ConfigQueue::new();
+        let _reload = queue.submit(ConfigJob::Reload);
+        let _layout = queue.submit(ConfigJob::Layout(Some("wide".to_string())));
+        let _again = queue.submit(ConfigJob::Reload);
+
+        let jobs: Vec<_> = queue.pending.lock().unwrap().iter().map(|queued| queued.job.clone()).collect();
+        assert!(matches!(jobs.as_slice(), [ConfigJob::Layout(Some(_)), ConfigJob::Reload]));
+    }
+
+    #[test]
+    fn tells_waiters_their_load_was_superseded() {
+        let queue = ConfigQueue::new();
+        let first = queue.submit(load("first.toml"));
+        let _last = queue.submit(load("last.toml"));
+
+        assert_eq!(first.try_recv(), Ok(Err(SUPERSEDED.to_string())));
+    }
+
+    #[test]
+    fn passes_apply_errors_back_to_the_caller() {
+        let queue = ConfigQueue::new();
+        let result = queue.submit(load("broken.toml"));
+
+        let (job, reply) = queue.next();
+        let error = format!("Failed to parse {}", file_of(job).display());
+        reply.send(Err(error)).unwrap();
+
+        assert_eq!(result.recv(), Ok(Err("Failed to parse broken.toml".to_string())));
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// When each physical input last got a press through its binding's cooldown.
+/// Inputs are kept apart, so two controllers sharing a `Gamepad:<button>`
+/// binding, or two keys on one action, do not suppress each other.
+pub struct Cooldowns {
+    last_press: HashMap<String, Instant>,
+}
+
+impl Cooldowns {
+    pub fn new() -> Self {
+        Self {
+            last_press: HashMap::new(),
+        }
+    }
+
+    /// Checks a press of `input` at `now`. Returns the time since the last
+    /// press that went through when this one lands inside `cooldown`;
+    /// otherwise records it as the last press and returns `None`.
+    pub fn press(&mut self, input: &str, cooldown: Duration, now: Instant) -> Option<Duration> {
+        let elapsed = self
+            .last_press
+            .get(input)
+            .map(|previous| now.saturating_duration_since(*previous));
+        match elapsed {
+            Some(elapsed) if elapsed < cooldown => Some(elapsed),
+            _ => {
+                self.last_press.insert(input.to_string(), now);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COOLDOWN: Duration = Duration::from_millis(300);
+
+    #[test]
+    fn keeps_each_pad_on_a_shared_binding_apart() {
+        let mut cooldowns = Cooldowns::new();
+        let start = Instant::now();
+        assert_eq!(cooldowns.press("Gamepad0:X", COOLDOWN, start), None);
+        assert_eq!(cooldowns.press("Gamepad1:X", COOLDOWN, start + Duration::from_millis(50)), None);
+        assert_eq!(
+            cooldowns.press("Gamepad0:X", COOLDOWN, start + Duration::from_millis(100)),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(
+            cooldowns.press("Gamepad1:X", COOLDOWN, start + Duration::from_millis(150)),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(cooldowns.press("Gamepad0:X", COOLDOWN, start + Duration::from_millis(300)), None);
+    }
+
+    #[test]
+    fn measures_from_the_press_that_started_a_hold() {
+        // Held repeats never go through `press`, so only the presses themselves count.
+        let mut cooldowns = Cooldowns::new();
+        let start = Instant::now();
+        assert_eq!(cooldowns.press("Q", COOLDOWN, start), None);
+        assert_eq!(
+            cooldowns.press("Q", COOLDOWN, start + Duration::from_millis(200)),
+            Some(Duration::from_millis(200))
+        );
+        // The ignored press did not restart the cooldown.
+        assert_eq!(cooldowns.press("Q", COOLDOWN, start + Duration::from_millis(300)), None);
+        assert_eq!(
+            cooldowns.press("Q", COOLDOWN, start + Duration::from_millis(599)),
+            Some(Duration::from_millis(299))
+        );
+    }
+}
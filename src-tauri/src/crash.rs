@@ -0,0 +1,201 @@
+use crate::config::{serialize_config, CrashReportSettings, ScoreboardConfig};
+use serde::Serialize;
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::ffi::OsStr;
+use std::fs;
+use std::panic::{self, PanicHookInfo};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const MAX_LOG_LINES: usize = 200;
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(10);
+/// Extension of the file next to a report that still has to be posted; it
+/// holds the endpoint.
+const PENDING_EXTENSION: &str = "upload";
+
+/// Written as JSON to the crash report directory and, with an `endpoint`,
+/// posted there as well.
+#[derive(Debug, Clone, Serialize)]
+pub struct CrashReport {
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub unix_ms: u64,
+    pub thread: String,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+    pub recent_log: Vec<String>,
+    /// The active config with its secrets redacted.
+    pub config: Option<String>,
+}
+
+/// Keeps what a crash report needs ready ahead of time, since a panicking app
+/// cannot be trusted to gather it afterwards. Log lines are kept either way;
+/// reports are only written once a config opts in with `[global.crash_report]`.
+pub struct CrashReporter {
+    settings: Option<CrashReportSettings>,
+    report_dir: Option<PathBuf>,
+    started: Instant,
+    log: VecDeque<String>,
+    config: Option<String>,
+}
+
+impl CrashReporter {
+    pub fn new() -> Self {
+        Self {
+            settings: None,
+            report_dir: None,
+            started: Instant::now(),
+            log: VecDeque::new(),
+            config: None,
+        }
+    }
+
+    pub fn set_report_dir(&mut self, report_dir: PathBuf) {
+        self.report_dir = Some(report_dir);
+    }
+
+    pub fn configure(&mut self, config: &ScoreboardConfig) {
+        self.settings = config.global.crash_report.clone();
+        self.config = self
+            .settings
+            .as_ref()
+            .and_then(|_| serialize_config(&config.redacted()).ok());
+    }
+
+    /// Adds a line to the log included in reports, stamped with the time
+    /// since the app started.
+    pub fn log(&mut self, line: impl AsRef<str>) {
+        if self.log.len() == MAX_LOG_LINES {
+            self.log.pop_front();
+        }
+        let elapsed = self.started.elapsed();
+        self.log
+            .push_back(format!("+{}.{:03}s {}", elapsed.as_secs(), elapsed.subsec_millis(), line.as_ref()));
+    }
+
+    fn report(&self, info: &PanicHookInfo) -> Option<(CrashReport, Option<String>, PathBuf)> {
+        let settings = self.settings.as_ref()?;
+        let report_dir = self.report_dir.clone()?;
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panic with a non-text payload".to_string());
+        let report = CrashReport {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            unix_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_millis() as u64)
+                .unwrap_or_default(),
+            thread: thread::current().name().unwrap_or("unnamed").to_string(),
+            message,
+            location: info
+                .location()
+                .map(|location| format!("{}:{}:{}", location.file(), location.line(), location.column())),
+            backtrace: Backtrace::force_capture().to_string(),
+            recent_log: self.log.iter().cloned().collect(),
+            config: self.config.clone(),
+        };
+        Some((report, settings.endpoint.clone(), report_dir))
+    }
+}
+
+/// Saves a report for every panic once a config opts in. Posting it is left
+/// to a background thread, which also sends reports an earlier run saved but
+/// could not post, since the panicking thread may hold locks the rest of the
+/// app needs and must not wait on the network. The previous hook still runs
+/// first, so panics keep reaching stderr.
+pub fn install_panic_hook(reporter: Arc<Mutex<CrashReporter>>) {
+    let uploads = reporter
+        .lock()
+        .ok()
+        .and_then(|reporter| reporter.report_dir.clone())
+        .map(spawn_uploader);
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        previous(info);
+        // The panicking thread may be the one holding the reporter; never wait for it.
+        let report = match reporter.try_lock() {
+            Ok(reporter) => reporter.report(info),
+            Err(_) => None,
+        };
+        let Some((report, endpoint, report_dir)) = report else {
+            return;
+        };
+        let Ok(json) = serde_json::to_string_pretty(&report) else {
+            return;
+        };
+        let Ok(path) = write_report(&report_dir, report.unix_ms, &json) else {
+            return;
+        };
+        let Some(endpoint) = endpoint else {
+            return;
+        };
+        if fs::write(path.with_extension(PENDING_EXTENSION), endpoint).is_ok() {
+            if let Some(uploads) = &uploads {
+                let _ = uploads.send(());
+            }
+        }
+    }));
+}
+
+fn write_report(report_dir: &Path, unix_ms: u64, json: &str) -> Result<PathBuf, String> {
+    fs::create_dir_all(report_dir)
+        .map_err(|e| format!("Failed creating crash report directory {}: {e}", report_dir.display()))?;
+    let path = report_dir.join(format!("crash-{unix_ms}.json"));
+    fs::write(&path, json).map_err(|e| format!("Failed writing crash report {}: {e}", path.display()))?;
+    Ok(path)
+}
+
+/// Posts the reports waiting in `report_dir`, first the ones left from an
+/// earlier run and then again each time the panic hook saves one.
+fn spawn_uploader(report_dir: PathBuf) -> Sender<()> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        upload_pending(&report_dir);
+        while receiver.recv().is_ok() {
+            upload_pending(&report_dir);
+        }
+    });
+    sender
+}
+
+/// Posts each report that has a pending file next to it, and removes that
+/// file once the endpoint accepted the report. Reports stay on disk either
+/// way; a failed post is tried again on the next start.
+fn upload_pending(report_dir: &Path) {
+    let Ok(entries) = fs::read_dir(report_dir) else {
+        return;
+    };
+    let agent = ureq::AgentBuilder::new().timeout(UPLOAD_TIMEOUT).build();
+    for entry in entries.flatten() {
+        let pending = entry.path();
+        if pending.extension() != Some(OsStr::new(PENDING_EXTENSION)) {
+            continue;
+        }
+        let report = pending.with_extension("json");
+        let (Ok(endpoint), Ok(json)) = (fs::read_to_string(&pending), fs::read_to_string(&report)) else {
+            // A report that was deleted has nothing left to send.
+            if !report.exists() {
+                let _ = fs::remove_file(&pending);
+            }
+            continue;
+        };
+        let posted = agent
+            .post(endpoint.trim())
+            .set("Content-Type", "application/json")
+            .send_string(&json);
+        if posted.is_ok() {
+            let _ = fs::remove_file(&pending);
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use crate::config::{GlobalSettings, SyncRole, ANY_SYNC_PRIMARY};
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+
+/// mDNS service type of the HTTP remote (`[global.remote]`).
+pub const REMOTE_SERVICE: &str = "_aolot-remote._tcp.local.";
+/// mDNS service type of a sync primary (`[global.sync] listen`).
+pub const SYNC_SERVICE: &str = "_aolot-sync._tcp.local.";
+pub const DEFAULT_DISCOVERY_TIMEOUT: Duration = Duration::from_secs(2);
+pub const MAX_DISCOVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// One endpoint this board announces.
+#[derive(Debug, Clone, PartialEq)]
+struct Advert {
+    service: &'static str,
+    name: String,
+    /// Empty to announce every address of the machine.
+    address: String,
+    port: u16,
+    token_required: bool,
+}
+
+/// Payload of `discover_instances`: an endpoint another board announces.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiscoveredInstance {
+    pub name: String,
+    /// `remote` or `sync`.
+    pub service: &'static str,
+    pub host: String,
+    pub addresses: Vec<String>,
+    pub port: u16,
+    /// Whether calls need the endpoint's `token`.
+    pub token_required: bool,
+    pub version: Option<String>,
+}
+
+/// Announces the remote and sync endpoints over mDNS so controllers and
+/// replicas on the network can find this board by name.
+pub struct Advertiser {
+    daemon: Option<ServiceDaemon>,
+    adverts: Vec<Advert>,
+    registered: Vec<String>,
+}
+
+impl Advertiser {
+    pub fn new() -> Self {
+        Self {
+            daemon: None,
+            adverts: Vec::new(),
+            registered: Vec::new(),
+        }
+    }
+
+    /// Announces what `global` serves to the network, replacing earlier
+    /// announcements. Endpoints bound to loopback are not announced, and
+    /// `[global.discovery] advertise = false` announces nothing.
+    pub fn configure(&mut self, global: &GlobalSettings) -> Result<(), String> {
+        let adverts = adverts_for(global);
+        if adverts == self.adverts {
+            return Ok(());
+        }
+        if let Some(daemon) = &self.daemon {
+            for fullname in self.registered.drain(..) {
+                let _ = daemon.unregister(&fullname);
+            }
+        }
+        self.adverts = adverts;
+        if self.adverts.is_empty() {
+            return Ok(());
+        }
+        if self.daemon.is_none() {
+            self.daemon = Some(ServiceDaemon::new().map_err(|e| format!("Failed starting mDNS: {e}"))?);
+        }
+        let Some(daemon) = &self.daemon else {
+            return Ok(());
+        };
+        let host = format!("{}.local.", host_label());
+        for advert in &self.adverts {
+            let properties = [
+                ("version", env!("CARGO_PKG_VERSION")),
+                ("token", if advert.token_required { "required" } else { "none" }),
+            ];
+            let info = ServiceInfo::new(
+                advert.service,
+                &advert.name,
+                &host,
+                advert.address.as_str(),
+                advert.port,
+                &properties[..],
+            )
+            .map_err(|e| format!("Failed announcing '{}' over mDNS: {e}", advert.name))?;
+            let info = if advert.address.is_empty() {
+                info.enable_addr_auto()
+            } else {
+                info
+            };
+            let fullname = info.get_fullname().to_string();
+            daemon
+                .register(info)
+                .map_err(|e| format!("Failed announcing '{}' over mDNS: {e}", advert.name))?;
+            self.registered.push(fullname);
+        }
+        Ok(())
+    }
+}
+
+fn adverts_for(global: &GlobalSettings) -> Vec<Advert> {
+    if global.discovery.as_ref().is_some_and(|discovery| !discovery.advertise) {
+        return Vec::new();
+    }
+    let name = global
+        .discovery
+        .as_ref()
+        .and_then(|discovery| discovery.name.clone())
+        .unwrap_or_else(host_label);
+    let mut endpoints = Vec::new();
+    if let Some(remote) = &global.remote {
+        endpoints.push((REMOTE_SERVICE, &remote.bind, remote.port, remote.requires_token()));
+    }
+    if let Some(sync) = &global.sync {
+        if let SyncRole::Primary { bind, port } = &sync.role {
+            endpoints.push((SYNC_SERVICE, bind, *port, sync.token.is_some()));
+        }
+    }
+    endpoints
+        .into_iter()
+        .filter_map(|(service, bind, port, token_required)| {
+            let bind = bind.parse::<IpAddr>().ok()?;
+            if bind.is_loopback() {
+                return None;
+            }
+            Some(Advert {
+                service,
+                name: name.clone(),
+                address: if bind.is_unspecified() { String::new() } else { bind.to_string() },
+                port,
+                token_required,
+            })
+        })
+        .collect()
+}
+
+/// The computer's name as one DNS label.
+fn host_label() -> String {
+    let name = gethostname::gethostname().to_string_lossy().to_string();
+    let label: String = name
+        .split('.')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '-' })
+        .collect();
+    if label.is_empty() {
+        "scoreboard".to_string()
+    } else {
+        label
+    }
+}
+
+/// Listens for `timeout` and returns every remote and sync endpoint announced
+/// on the network, this board's own included.
+pub fn discover(timeout: Duration) -> Result<Vec<DiscoveredInstance>, String> {
+    let daemon = ServiceDaemon::new().map_err(|e| format!("Failed starting mDNS: {e}"))?;
+    let mut found: HashMap<String, DiscoveredInstance> = HashMap::new();
+    let receivers = [REMOTE_SERVICE, SYNC_SERVICE]
+        .into_iter()
+        .map(|service| {
+            daemon
+                .browse(service)
+                .map(|receiver| (service, receiver))
+                .map_err(|e| format!("Failed searching the network: {e}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        for (service, receiver) in &receivers {
+            while let Ok(event) = receiver.try_recv() {
+                if let ServiceEvent::ServiceResolved(info) = event {
+                    found.insert(info.get_fullname().to_string(), instance(service, &info));
+                }
+            }
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    let _ = daemon.shutdown();
+    let mut instances: Vec<DiscoveredInstance> = found.into_values().collect();
+    instances.sort_by(|a, b| (&a.name, a.service).cmp(&(&b.name, b.service)));
+    Ok(instances)
+}
+
+fn instance(service: &str, info: &ServiceInfo) -> DiscoveredInstance {
+    let mut addresses: Vec<IpAddr> = info.get_addresses().iter().copied().collect();
+    // IPv4 first, since that is what most venue networks route.
+    addresses.sort_by_key(|address| (address.is_ipv6(), *address));
+    let suffix = format!(".{service}");
+    DiscoveredInstance {
+        name: info
+            .get_fullname()
+            .strip_suffix(&suffix)
+            .unwrap_or(info.get_fullname())
+            .to_string(),
+        service: if service == SYNC_SERVICE { "sync" } else { "remote" },
+        host: info.get_hostname().trim_end_matches('.').to_string(),
+        addresses: addresses.iter().map(IpAddr::to_string).collect(),
+        port: info.get_port(),
+        token_required: info.get_property_val_str("token") == Some("required"),
+        version: info.get_property_val_str("version").map(str::to_string),
+    }
+}
+
+/// Looks up the sync primary announcing `name`, or any primary for `ANY_SYNC_PRIMARY`.
+pub fn find_sync_primary(name: &str) -> Result<SocketAddr, String> {
+    let primaries = discover(DEFAULT_DISCOVERY_TIMEOUT)?;
+    let primary = primaries
+        .iter()
+        .filter(|instance| instance.service == "sync")
+        .find(|instance| name == ANY_SYNC_PRIMARY || instance.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| match name {
+            ANY_SYNC_PRIMARY => "no primary announced on the network".to_string(),
+            name => format!("no primary named '{name}' announced on the network"),
+        })?;
+    let address = primary
+        .addresses
+        .first()
+        .and_then(|address| address.parse::<IpAddr>().ok())
+        .ok_or_else(|| format!("primary '{}' announced no address", primary.name))?;
+    Ok(SocketAddr::new(address, primary.port))
+}
@@ -0,0 +1,63 @@
+use crate::recording::csv_field;
+use crate::state::{LoggedEvent, PeriodScore};
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+
+const CSV_HEADER: &str = "timestamp_ms,action,component,value,period,clock";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventLogFormat {
+    Csv,
+    Json,
+}
+
+impl EventLogFormat {
+    /// Reads `csv` or `json`; without one, the file's extension decides and
+    /// anything but `.json` is CSV.
+    pub fn parse(value: Option<&str>, path: &Path) -> Result<Self, String> {
+        match value.map(str::trim) {
+            Some("csv") => Ok(Self::Csv),
+            Some("json") => Ok(Self::Json),
+            Some(other) => Err(format!("Unknown event log format '{other}', expected csv or json")),
+            None if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("json")) => Ok(Self::Json),
+            None => Ok(Self::Csv),
+        }
+    }
+}
+
+/// Writes the event log for a post-game audit. CSV has one row per action;
+/// JSON is `{"events": [...], "score_by_period": [...]}` with the entries
+/// `get_event_log` and `get_score_by_period` return, so only JSON carries
+/// the per-period breakdown.
+pub fn export_event_log(
+    events: &[LoggedEvent],
+    periods: &[PeriodScore],
+    path: &Path,
+    format: EventLogFormat,
+) -> Result<(), String> {
+    let content = match format {
+        EventLogFormat::Json => serde_json::to_string_pretty(&json!({ "events": events, "score_by_period": periods }))
+            .map_err(|e| format!("Failed encoding event log: {e}"))?,
+        EventLogFormat::Csv => {
+            let mut csv = format!("{CSV_HEADER}\n");
+            for event in events {
+                let fields = [
+                    Some(event.action.as_str()),
+                    event.component.as_deref(),
+                    event.value.as_deref(),
+                    event.period.as_deref(),
+                    event.clock.as_deref(),
+                ];
+                csv.push_str(&event.timestamp_ms.to_string());
+                for field in fields {
+                    csv.push(',');
+                    csv.push_str(&csv_field(field.unwrap_or_default()));
+                }
+                csv.push('\n');
+            }
+            csv
+        }
+    };
+    fs::write(path, content).map_err(|e| format!("Failed writing event log {}: {e}", path.display()))
+}
@@ -0,0 +1,379 @@
+use crate::config::FixtureSettings;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// One game from the calendar.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Fixture {
+    pub uid: String,
+    pub title: String,
+    pub start_unix_ms: i64,
+}
+
+impl Fixture {
+    /// Team names from a title such as `Lions vs Tigers` or `Lions - Tigers`,
+    /// home first.
+    pub fn teams(&self) -> Option<(String, String)> {
+        let lower = self.title.to_ascii_lowercase();
+        for separator in [" vs. ", " vs ", " v ", " - "] {
+            if let Some(index) = lower.find(separator) {
+                let home = self.title[..index].trim();
+                let away = self.title[index + separator.len()..].trim();
+                if !home.is_empty() && !away.is_empty() {
+                    return Some((home.to_string(), away.to_string()));
+                }
+            }
+        }
+        None
+    }
+
+    /// The first configured config whose keyword appears in the title.
+    pub fn config_path<'a>(&self, settings: &'a FixtureSettings) -> Option<&'a str> {
+        let title = self.title.to_lowercase();
+        settings
+            .configs
+            .iter()
+            .find(|(keyword, _)| title.contains(&keyword.to_lowercase()))
+            .map(|(_, path)| path.as_str())
+    }
+}
+
+/// Sent as `scoreboard://fixture-prepared` once a game's pre-game countdown starts.
+#[derive(Debug, Clone, Serialize)]
+pub struct FixturePrepared {
+    pub fixture: Fixture,
+    pub home: Option<String>,
+    pub away: Option<String>,
+    pub config_path: Option<String>,
+}
+
+/// Keeps the calendar of upcoming games and decides when the next one is due
+/// for its pre-game setup. Each game is prepared once per run of the app,
+/// even when preparing it loads a different config.
+pub struct FixtureScheduler {
+    settings: Option<FixtureSettings>,
+    fixtures: Vec<Fixture>,
+    last_fetch: Option<Instant>,
+    last_error: Option<String>,
+    prepared: Option<(String, i64)>,
+}
+
+impl FixtureScheduler {
+    pub fn new() -> Self {
+        Self {
+            settings: None,
+            fixtures: Vec::new(),
+            last_fetch: None,
+            last_error: None,
+            prepared: None,
+        }
+    }
+
+    /// Uses new settings, fetching the calendar again when its source changed.
+    pub fn configure(&mut self, settings: Option<FixtureSettings>) {
+        let same_source = match (&self.settings, &settings) {
+            (Some(previous), Some(next)) => previous.source == next.source && previous.utc_offset_minutes == next.utc_offset_minutes,
+            _ => false,
+        };
+        if !same_source {
+            self.fixtures.clear();
+            self.last_fetch = None;
+            self.last_error = None;
+        }
+        self.settings = settings;
+    }
+
+    pub fn settings(&self) -> Option<&FixtureSettings> {
+        self.settings.as_ref()
+    }
+
+    /// The calendar source and UTC offset when it is time to fetch it again.
+    pub fn due_fetch(&self, now: Instant) -> Option<(String, i32)> {
+        let settings = self.settings.as_ref()?;
+        let due = self
+            .last_fetch
+            .is_none_or(|last| now.duration_since(last).as_millis() as i64 >= settings.refresh_ms);
+        due.then(|| (settings.source.clone(), settings.utc_offset_minutes))
+    }
+
+    /// Stores a fetch result. Returns the error to report, once per distinct
+    /// failure, so an unreachable calendar does not flood the screen.
+    pub fn finish_fetch(&mut self, result: Result<Vec<Fixture>, String>, now: Instant) -> Option<String> {
+        self.last_fetch = Some(now);
+        match result {
+            Ok(fixtures) => {
+                self.fixtures = fixtures;
+                self.last_error = None;
+                None
+            }
+            Err(e) => {
+                // Keep the last good calendar; games rarely move at short notice.
+                let repeated = self.last_error.as_ref() == Some(&e);
+                self.last_error = Some(e.clone());
+                (!repeated).then_some(e)
+            }
+        }
+    }
+
+    /// The next game whose start is within `lead_ms` and that has not been
+    /// prepared yet. Marks it prepared.
+    pub fn take_due(&mut self, now_unix_ms: i64) -> Option<Fixture> {
+        let settings = self.settings.as_ref()?;
+        let next = self
+            .fixtures
+            .iter()
+            .filter(|fixture| fixture.start_unix_ms > now_unix_ms)
+            .min_by_key(|fixture| fixture.start_unix_ms)?;
+        if next.start_unix_ms - settings.lead_ms > now_unix_ms {
+            return None;
+        }
+        let key = (next.uid.clone(), next.start_unix_ms);
+        if self.prepared.as_ref() == Some(&key) {
+            return None;
+        }
+        self.prepared = Some(key);
+        Some(next.clone())
+    }
+
+    /// Upcoming games, soonest first.
+    pub fn upcoming(&self, now_unix_ms: i64) -> Vec<Fixture> {
+        let mut upcoming: Vec<Fixture> = self
+            .fixtures
+            .iter()
+            .filter(|fixture| fixture.start_unix_ms > now_unix_ms)
+            .cloned()
+            .collect();
+        upcoming.sort_by_key(|fixture| fixture.start_unix_ms);
+        upcoming
+    }
+}
+
+/// Reads the calendar from a file or downloads it.
+pub fn fetch_fixtures(source: &str, utc_offset_minutes: i32) -> Result<Vec<Fixture>, String> {
+    let content = if source.starts_with("http://") || source.starts_with("https://") {
+        ureq::AgentBuilder::new()
+            .timeout(FETCH_TIMEOUT)
+            .build()
+            .get(source)
+            .call()
+            .map_err(|e| format!("Failed downloading fixtures {source}: {e}"))?
+            .into_string()
+            .map_err(|e| format!("Failed reading fixtures {source}: {e}"))?
+    } else {
+        fs::read_to_string(Path::new(source)).map_err(|e| format!("Failed reading fixtures {source}: {e}"))?
+    };
+    parse_ics(&content, utc_offset_minutes).map_err(|e| format!("Invalid fixtures {source}: {e}"))
+}
+
+#[derive(Default)]
+struct RawEvent {
+    uid: Option<String>,
+    title: Option<String>,
+    start_unix_ms: Option<i64>,
+    cancelled: bool,
+}
+
+/// Events of an iCalendar file that have a start time. All-day and cancelled
+/// events are skipped, and so are events whose start time cannot be read.
+/// Times without a `Z` suffix are local to the calendar and shifted by
+/// `utc_offset_minutes`; a `TZID` parameter is ignored, since there is no
+/// time zone database to look it up in.
+pub fn parse_ics(content: &str, utc_offset_minutes: i32) -> Result<Vec<Fixture>, String> {
+    // Long lines are folded onto continuation lines starting with a space or tab.
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match line.strip_prefix([' ', '\t']) {
+            Some(rest) if !lines.is_empty() => lines.last_mut().unwrap().push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    if !lines.iter().any(|line| line.trim() == "BEGIN:VCALENDAR") {
+        return Err("not an iCalendar file".to_string());
+    }
+
+    let mut fixtures = Vec::new();
+    let mut event: Option<RawEvent> = None;
+    for line in &lines {
+        let line = line.trim_end();
+        if line == "BEGIN:VEVENT" {
+            event = Some(RawEvent::default());
+            continue;
+        }
+        if line == "END:VEVENT" {
+            if let Some(RawEvent {
+                uid,
+                title,
+                start_unix_ms: Some(start_unix_ms),
+                cancelled: false,
+            }) = event.take()
+            {
+                let title = title.unwrap_or_default();
+                fixtures.push(Fixture {
+                    uid: uid.unwrap_or_else(|| format!("{start_unix_ms}-{title}")),
+                    title,
+                    start_unix_ms,
+                });
+            }
+            continue;
+        }
+        let Some(event) = event.as_mut() else {
+            continue;
+        };
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let mut parts = name.split(';');
+        let property = parts.next().unwrap_or_default().to_ascii_uppercase();
+        let params: Vec<String> = parts.map(str::to_ascii_uppercase).collect();
+        match property.as_str() {
+            "UID" => event.uid = Some(value.to_string()),
+            "SUMMARY" => event.title = Some(unescape_text(value)),
+            "STATUS" => event.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+            "DTSTART" if !params.iter().any(|param| param == "VALUE=DATE") => {
+                event.start_unix_ms = parse_date_time(value, utc_offset_minutes).ok();
+            }
+            _ => {}
+        }
+    }
+    Ok(fixtures)
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut text = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => text.push(' '),
+            Some(other) => text.push(other),
+            None => {}
+        }
+    }
+    text.trim().to_string()
+}
+
+/// `YYYYMMDDTHHMMSS` with an optional `Z`, as Unix milliseconds.
+fn parse_date_time(value: &str, utc_offset_minutes: i32) -> Result<i64, String> {
+    let invalid = || format!("invalid DTSTART '{value}'");
+    let (local, utc) = match value.strip_suffix(['Z', 'z']) {
+        Some(local) => (local, true),
+        None => (value, false),
+    };
+    if local.len() != 15 || local.as_bytes()[8] != b'T' {
+        return Err(invalid());
+    }
+    let number = |range: std::ops::Range<usize>| local.get(range)?.parse::<i64>().ok();
+    let fields = [number(0..4), number(4..6), number(6..8), number(9..11), number(11..13), number(13..15)];
+    let [Some(year), Some(month), Some(day), Some(hour), Some(minute), Some(second)] = fields else {
+        return Err(invalid());
+    };
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return Err(invalid());
+    }
+    let mut seconds = days_from_civil(year, month, day) * 86_400 + hour * 3_600 + minute * 60 + second;
+    if !utc {
+        seconds -= i64::from(utc_offset_minutes) * 60;
+    }
+    Ok(seconds * 1000)
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's algorithm).
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_index = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+pub fn unix_time_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAME_START_MS: i64 = 1_772_393_400_000;
+
+    fn calendar(events: &str) -> String {
+        format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{events}END:VCALENDAR\r\n")
+    }
+
+    #[test]
+    fn parses_timed_events() {
+        let content = calendar(
+            "BEGIN:VEVENT\r\nUID:game-1\r\nSUMMARY:Lions vs Tigers\r\nDTSTART:20260301T193000Z\r\nEND:VEVENT\r\n",
+        );
+        assert_eq!(
+            parse_ics(&content, 0).unwrap(),
+            vec![Fixture {
+                uid: "game-1".to_string(),
+                title: "Lions vs Tigers".to_string(),
+                start_unix_ms: GAME_START_MS,
+            }]
+        );
+    }
+
+    #[test]
+    fn unfolds_and_unescapes_summaries() {
+        let content = calendar(concat!(
+            "BEGIN:VEVENT\r\nSUMMARY:Lions\\, Cubs vs \r\n Tigers\\nFinal\r\n",
+            "DTSTART;TZID=Local:20260301T193000\r\nEND:VEVENT\r\n",
+        ));
+        let fixtures = parse_ics(&content, 60).unwrap();
+        assert_eq!(fixtures[0].title, "Lions, Cubs vs Tigers Final");
+        // Local times are shifted by the offset; a missing UID falls back to start and title.
+        assert_eq!(fixtures[0].start_unix_ms, GAME_START_MS - 3_600_000);
+        assert_eq!(fixtures[0].uid, format!("{}-Lions, Cubs vs Tigers Final", GAME_START_MS - 3_600_000));
+    }
+
+    #[test]
+    fn skips_all_day_cancelled_and_untimed_events() {
+        let content = calendar(concat!(
+            "BEGIN:VEVENT\r\nSUMMARY:Tournament\r\nDTSTART;VALUE=DATE:20260301\r\nEND:VEVENT\r\n",
+            "BEGIN:VEVENT\r\nSUMMARY:Rained out\r\nSTATUS:CANCELLED\r\nDTSTART:20260301T193000Z\r\nEND:VEVENT\r\n",
+            "BEGIN:VEVENT\r\nSUMMARY:Someday\r\nEND:VEVENT\r\n",
+        ));
+        assert_eq!(parse_ics(&content, 0).unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn rejects_other_files_and_skips_bad_start_times() {
+        assert_eq!(parse_ics("<html></html>", 0).unwrap_err(), "not an iCalendar file");
+        let content = calendar(concat!(
+            "BEGIN:VEVENT\r\nSUMMARY:Typo\r\nDTSTART:20261301T193000Z\r\nEND:VEVENT\r\n",
+            "BEGIN:VEVENT\r\nSUMMARY:Lions vs Tigers\r\nDTSTART:20260301T193000Z\r\nEND:VEVENT\r\n",
+        ));
+        let fixtures = parse_ics(&content, 0).unwrap();
+        assert_eq!(fixtures.len(), 1);
+        assert_eq!(fixtures[0].start_unix_ms, GAME_START_MS);
+        assert!(parse_date_time("2026-03-01", 0).is_err());
+        assert_eq!(parse_date_time("20240229T000000Z", 0), Ok(1_709_164_800_000));
+    }
+
+    #[test]
+    fn splits_team_names_from_titles() {
+        let fixture = |title: &str| Fixture {
+            uid: String::new(),
+            title: title.to_string(),
+            start_unix_ms: 0,
+        };
+        let teams = |home: &str, away: &str| Some((home.to_string(), away.to_string()));
+        assert_eq!(fixture("Lions vs. Tigers").teams(), teams("Lions", "Tigers"));
+        assert_eq!(fixture("Lions - Tigers").teams(), teams("Lions", "Tigers"));
+        assert_eq!(fixture("Team meeting").teams(), None);
+    }
+}
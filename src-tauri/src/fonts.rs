@@ -0,0 +1,255 @@
+use crate::config::{Font, ScoreboardConfig};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::env;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// CSS generic families; the window always has a font for these.
+const GENERIC_FAMILIES: [&str; 10] = [
+    "serif",
+    "sans-serif",
+    "monospace",
+    "cursive",
+    "fantasy",
+    "system-ui",
+    "ui-serif",
+    "ui-sans-serif",
+    "ui-monospace",
+    "ui-rounded",
+];
+/// Folder next to the config whose fonts the window loads itself.
+const BUNDLED_FONTS_DIR: &str = "fonts";
+const MAX_DIR_DEPTH: usize = 8;
+const MAX_NAME_TABLE_LEN: u32 = 1 << 20;
+const NAME_ID_FAMILY: u16 = 1;
+const NAME_ID_TYPOGRAPHIC_FAMILY: u16 = 16;
+
+/// Which family a `font.family` list resolved to on this machine. Sent as
+/// `scoreboard://fonts` after each config load.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FontChoice {
+    /// The component, or `None` for `[global].font`.
+    pub component: Option<String>,
+    pub families: Vec<String>,
+    pub chosen: String,
+    /// False when none of `families` is installed and the first is used anyway.
+    pub installed: bool,
+}
+
+/// A font file in the `fonts` folder next to the config, such as one shipped
+/// in a template pack. The window loads it, so it needs no installing.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BundledFont {
+    pub family: String,
+    pub path: String,
+}
+
+/// Points every font with fallbacks at its first installed or bundled
+/// family. Fonts with a single family are left alone, so only configs using
+/// fallbacks pay for scanning the installed fonts. Components report a choice
+/// only when their list differs from the global one.
+pub fn choose_font_families(config: &mut ScoreboardConfig, bundled: &[BundledFont]) -> Vec<FontChoice> {
+    let bundled: HashSet<String> = bundled.iter().map(|font| font.family.trim().to_lowercase()).collect();
+    let mut choices = Vec::new();
+    choices.extend(choose(None, &mut config.global.font, &bundled));
+    let global_families = &config.global.font.families;
+    for component in &mut config.components {
+        let choice = choose(Some(component.id.clone()), &mut component.font, &bundled);
+        if component.font.families != *global_families {
+            choices.extend(choice);
+        }
+    }
+    choices
+}
+
+fn choose(component: Option<String>, font: &mut Font, bundled: &HashSet<String>) -> Option<FontChoice> {
+    if font.families.len() < 2 {
+        return None;
+    }
+    let installed = installed_families();
+    let chosen = font.families.iter().find(|family| {
+        let family = family.trim().to_lowercase();
+        GENERIC_FAMILIES.contains(&family.as_str()) || installed.contains(&family) || bundled.contains(&family)
+    });
+    font.family = chosen.unwrap_or(&font.families[0]).clone();
+    Some(FontChoice {
+        component,
+        families: font.families.clone(),
+        chosen: font.family.clone(),
+        installed: chosen.is_some(),
+    })
+}
+
+/// The fonts in `<base_dir>/fonts`, one entry per family a file provides.
+pub fn bundled_fonts(base_dir: &Path) -> Vec<BundledFont> {
+    let mut fonts = Vec::new();
+    let Ok(entries) = fs::read_dir(base_dir.join(BUNDLED_FONTS_DIR)) else {
+        return fonts;
+    };
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).filter(|path| is_font_file(path)).collect();
+    paths.sort();
+    for path in paths {
+        let Ok(mut file) = File::open(&path) else {
+            continue;
+        };
+        let mut families: Vec<String> = font_family_names(&mut file)
+            .unwrap_or_default()
+            .iter()
+            .map(|family| family.trim().to_string())
+            .collect();
+        families.sort();
+        families.dedup();
+        for family in families {
+            fonts.push(BundledFont {
+                family,
+                path: path.to_string_lossy().to_string(),
+            });
+        }
+    }
+    fonts
+}
+
+/// Lowercased family names of the fonts in the system and user font
+/// directories. Scanned once per run; fonts installed later need a restart.
+fn installed_families() -> &'static HashSet<String> {
+    static INSTALLED: OnceLock<HashSet<String>> = OnceLock::new();
+    INSTALLED.get_or_init(|| {
+        let mut families = HashSet::new();
+        for dir in font_dirs() {
+            scan_dir(&dir, 0, &mut families);
+        }
+        families
+    })
+}
+
+fn font_dirs() -> Vec<PathBuf> {
+    let home = env::var_os("HOME").map(PathBuf::from);
+    let mut dirs = Vec::new();
+    if cfg!(target_os = "windows") {
+        if let Some(windir) = env::var_os("WINDIR") {
+            dirs.push(PathBuf::from(windir).join("Fonts"));
+        }
+        if let Some(local) = env::var_os("LOCALAPPDATA") {
+            dirs.push(PathBuf::from(local).join("Microsoft").join("Windows").join("Fonts"));
+        }
+    } else if cfg!(target_os = "macos") {
+        dirs.extend(["/System/Library/Fonts", "/Library/Fonts"].map(PathBuf::from));
+        dirs.extend(home.map(|home| home.join("Library").join("Fonts")));
+    } else {
+        dirs.extend(["/usr/share/fonts", "/usr/local/share/fonts"].map(PathBuf::from));
+        let data_home = env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|home| home.join(".local").join("share")));
+        dirs.extend(data_home.map(|data_home| data_home.join("fonts")));
+        dirs.extend(home.map(|home| home.join(".fonts")));
+    }
+    dirs
+}
+
+fn scan_dir(dir: &Path, depth: usize, families: &mut HashSet<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if depth < MAX_DIR_DEPTH {
+                scan_dir(&path, depth + 1, families);
+            }
+            continue;
+        }
+        if !is_font_file(&path) {
+            continue;
+        }
+        if let Ok(mut file) = File::open(&path) {
+            let names = font_family_names(&mut file).unwrap_or_default();
+            families.extend(names.into_iter().map(|name| name.trim().to_lowercase()));
+        }
+    }
+}
+
+fn is_font_file(path: &Path) -> bool {
+    let extension = path.extension().and_then(|extension| extension.to_str()).map(str::to_ascii_lowercase);
+    matches!(extension.as_deref(), Some("ttf" | "otf" | "ttc" | "otc"))
+}
+
+/// Family names from the `name` table of a TrueType or OpenType font, or of
+/// every font in a collection. Only the headers are read, not the glyphs.
+fn font_family_names(file: &mut File) -> Option<Vec<String>> {
+    let header = read_at(file, 0, 12)?;
+    let offsets = if &header[..4] == b"ttcf" {
+        let count = u32_at(&header, 8)?.min(256);
+        let offsets = read_at(file, 12, count as usize * 4)?;
+        (0..count as usize).filter_map(|index| u32_at(&offsets, index * 4)).collect()
+    } else {
+        vec![0]
+    };
+    let mut names = Vec::new();
+    for offset in offsets {
+        names.extend(sfnt_family_names(file, u64::from(offset)).unwrap_or_default());
+    }
+    Some(names)
+}
+
+fn sfnt_family_names(file: &mut File, offset: u64) -> Option<Vec<String>> {
+    let header = read_at(file, offset, 12)?;
+    let table_count = u16_at(&header, 4)? as usize;
+    let records = read_at(file, offset + 12, table_count * 16)?;
+    let (name_offset, name_len) = records
+        .chunks_exact(16)
+        .find(|record| &record[..4] == b"name")
+        .and_then(|record| Some((u32_at(record, 8)?, u32_at(record, 12)?)))?;
+    if name_len > MAX_NAME_TABLE_LEN {
+        return None;
+    }
+    let table = read_at(file, u64::from(name_offset), name_len as usize)?;
+    let count = u16_at(&table, 2)? as usize;
+    let strings = u16_at(&table, 4)? as usize;
+
+    let mut names = Vec::new();
+    for index in 0..count {
+        let record = table.get(6 + index * 12..18 + index * 12)?;
+        let (platform, encoding, name_id) = (u16_at(record, 0)?, u16_at(record, 2)?, u16_at(record, 6)?);
+        if name_id != NAME_ID_FAMILY && name_id != NAME_ID_TYPOGRAPHIC_FAMILY {
+            continue;
+        }
+        let start = strings + u16_at(record, 10)? as usize;
+        let Some(bytes) = table.get(start..start + u16_at(record, 8)? as usize) else {
+            continue;
+        };
+        let name = match (platform, encoding) {
+            // Unicode and Windows names are UTF-16BE.
+            (0, _) | (3, 0..=1 | 10) => {
+                let units: Vec<u16> = bytes.chunks_exact(2).map(|unit| u16::from_be_bytes([unit[0], unit[1]])).collect();
+                String::from_utf16_lossy(&units)
+            }
+            // Mac Roman; the family names that matter are plain ASCII.
+            (1, 0) => bytes.iter().map(|byte| char::from(*byte)).collect(),
+            _ => continue,
+        };
+        if !name.trim().is_empty() {
+            names.push(name);
+        }
+    }
+    Some(names)
+}
+
+fn read_at(file: &mut File, offset: u64, len: usize) -> Option<Vec<u8>> {
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    let mut buffer = vec![0; len];
+    file.read_exact(&mut buffer).ok()?;
+    Some(buffer)
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> Option<u16> {
+    let bytes = bytes.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> Option<u32> {
+    let bytes = bytes.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
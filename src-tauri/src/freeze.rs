@@ -0,0 +1,87 @@
+use crate::state::{RuntimeState, UiSnapshot};
+use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Sent as `scoreboard://freeze` whenever the board freezes or goes live again.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FreezeStatus {
+    pub frozen: bool,
+    pub reason: Option<String>,
+    pub since_unix_ms: Option<u64>,
+}
+
+struct FrozenBoard {
+    runtime: RuntimeState,
+    reason: String,
+    since_unix_ms: u64,
+}
+
+/// Output continuity for `freeze_on_error`. When updating the live board
+/// fails, the last good copy of the board takes over the display: its clocks
+/// keep running on their own, away from the state that failed, and key
+/// presses are ignored until the operator restarts the app or resumes.
+pub struct FreezeMode {
+    enabled: bool,
+    frozen: Option<FrozenBoard>,
+}
+
+impl FreezeMode {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            frozen: None,
+        }
+    }
+
+    pub fn configure(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen.is_some()
+    }
+
+    /// Freezes on `last_good`. Returns false when freezing is off, nothing
+    /// good was seen yet, or the board is already frozen.
+    pub fn enter(&mut self, last_good: Option<RuntimeState>, reason: &str) -> bool {
+        if !self.enabled || self.frozen.is_some() {
+            return false;
+        }
+        let Some(runtime) = last_good else {
+            return false;
+        };
+        self.frozen = Some(FrozenBoard {
+            runtime,
+            reason: reason.to_string(),
+            since_unix_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_millis() as u64)
+                .unwrap_or_default(),
+        });
+        true
+    }
+
+    pub fn resume(&mut self) -> bool {
+        self.frozen.take().is_some()
+    }
+
+    /// Advances the frozen board's clocks. Returns a snapshot when the display
+    /// changed. Expiry sounds and relays stay with the live board.
+    pub fn tick(&mut self) -> Option<UiSnapshot> {
+        let frozen = self.frozen.as_mut()?;
+        frozen.runtime.tick_timers().changed.then(|| frozen.runtime.snapshot())
+    }
+
+    /// The frozen board as it looks now, to repaint right after freezing.
+    pub fn snapshot(&self) -> Option<UiSnapshot> {
+        self.frozen.as_ref().map(|frozen| frozen.runtime.snapshot())
+    }
+
+    pub fn status(&self) -> FreezeStatus {
+        FreezeStatus {
+            frozen: self.frozen.is_some(),
+            reason: self.frozen.as_ref().map(|frozen| frozen.reason.clone()),
+            since_unix_ms: self.frozen.as_ref().map(|frozen| frozen.since_unix_ms),
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use crate::config::{GamepadSettings, MAX_GAMEPADS};
+use gilrs::{Axis, Button, GamepadId};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const GAMEPAD_SLOTS_FILE_NAME: &str = "gamepads.json";
+
+/// A pressed direction is released once the axis falls back below this share
+/// of its threshold, so a stick resting near the edge does not chatter.
+const RELEASE_RATIO: f32 = 0.8;
+
+/// Turns stick, axis D-pad, and analog trigger movement into presses and
+/// releases of virtual buttons. Each press fires once when its threshold is
+/// crossed; it has to be released before it can fire again.
+pub struct AxisButtons {
+    pressed: HashSet<(GamepadId, &'static str)>,
+}
+
+impl AxisButtons {
+    pub fn new() -> Self {
+        Self { pressed: HashSet::new() }
+    }
+
+    /// Virtual buttons newly pressed (`true`) or released (`false`) by an
+    /// axis moving to `value`.
+    pub fn axis_changed(
+        &mut self,
+        gamepad: GamepadId,
+        axis: Axis,
+        value: f32,
+        settings: &GamepadSettings,
+    ) -> Vec<(&'static str, bool)> {
+        let (negative, positive, threshold) = match axis {
+            Axis::LeftStickX => ("LS_LEFT", "LS_RIGHT", settings.deadzone),
+            Axis::LeftStickY => ("LS_DOWN", "LS_UP", settings.deadzone),
+            Axis::RightStickX => ("RS_LEFT", "RS_RIGHT", settings.deadzone),
+            Axis::RightStickY => ("RS_DOWN", "RS_UP", settings.deadzone),
+            Axis::DPadX => ("DPAD_LEFT", "DPAD_RIGHT", settings.deadzone),
+            Axis::DPadY => ("DPAD_DOWN", "DPAD_UP", settings.deadzone),
+            // Some backends report analog triggers as Z axes instead of buttons.
+            Axis::LeftZ => return self.trigger(gamepad, "LT_FULL", value, settings),
+            Axis::RightZ => return self.trigger(gamepad, "RT_FULL", value, settings),
+            _ => return Vec::new(),
+        };
+        [(negative, -value), (positive, value)]
+            .into_iter()
+            .filter_map(|(name, travel)| Some((name, self.update(gamepad, name, travel, threshold)?)))
+            .collect()
+    }
+
+    /// Virtual buttons newly pressed or released by an analog button moving
+    /// to `value`.
+    pub fn button_changed(
+        &mut self,
+        gamepad: GamepadId,
+        button: Button,
+        value: f32,
+        settings: &GamepadSettings,
+    ) -> Vec<(&'static str, bool)> {
+        match button {
+            Button::LeftTrigger2 => self.trigger(gamepad, "LT_FULL", value, settings),
+            Button::RightTrigger2 => self.trigger(gamepad, "RT_FULL", value, settings),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Forgets what a disconnected gamepad was holding.
+    pub fn release_all(&mut self, gamepad: GamepadId) {
+        self.pressed.retain(|(id, _)| *id != gamepad);
+    }
+
+    fn trigger(
+        &mut self,
+        gamepad: GamepadId,
+        name: &'static str,
+        value: f32,
+        settings: &GamepadSettings,
+    ) -> Vec<(&'static str, bool)> {
+        self.update(gamepad, name, value, settings.trigger_threshold)
+            .map(|pressed| vec![(name, pressed)])
+            .unwrap_or_default()
+    }
+
+    /// Returns `Some(true)` when `name` goes from released to pressed and
+    /// `Some(false)` when it goes back.
+    fn update(&mut self, gamepad: GamepadId, name: &'static str, travel: f32, threshold: f32) -> Option<bool> {
+        let key = (gamepad, name);
+        if self.pressed.contains(&key) {
+            if travel < threshold * RELEASE_RATIO {
+                self.pressed.remove(&key);
+                return Some(false);
+            }
+            None
+        } else if travel >= threshold {
+            self.pressed.insert(key);
+            Some(true)
+        } else {
+            None
+        }
+    }
+}
+
+/// A controller model and the pad number it was last given.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct KnownPad {
+    /// The controller's SDL-style uuid as hex, the same for every pad of a model.
+    uuid: String,
+    number: usize,
+}
+
+/// Pad numbers, remembered per controller uuid in the app data dir so a
+/// controller keeps its number across reconnects and restarts, whatever
+/// order the OS reports pads in.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GamepadSlots {
+    #[serde(default)]
+    pads: Vec<KnownPad>,
+    /// Numbers held by the pads connected right now.
+    #[serde(skip)]
+    connected: HashMap<GamepadId, usize>,
+}
+
+impl GamepadSlots {
+    /// The number of the pad `id`, giving it one if it has none yet. Returns
+    /// whether the remembered numbers changed and should be saved.
+    pub fn assign(&mut self, id: GamepadId, uuid: [u8; 16]) -> (usize, bool) {
+        if let Some(number) = self.connected.get(&id) {
+            return (*number, false);
+        }
+        let uuid = uuid_hex(uuid);
+        let taken: HashSet<usize> = self.connected.values().copied().collect();
+        // Identical controllers share a uuid, and gilrs gives nothing more
+        // specific to tell them apart, so each takes the lowest free number
+        // remembered for the model; they can swap numbers across reconnects.
+        let remembered = self
+            .pads
+            .iter()
+            .filter(|pad| pad.uuid == uuid && !taken.contains(&pad.number))
+            .map(|pad| pad.number)
+            .min();
+        if let Some(number) = remembered {
+            self.connected.insert(id, number);
+            return (number, false);
+        }
+        let unused = (1..).find(|number| !self.pads.iter().any(|pad| pad.number == *number));
+        let number = match unused {
+            Some(number) if number <= usize::from(MAX_GAMEPADS) => number,
+            // Every number is remembered: hand over the lowest one whose
+            // controller is not plugged in.
+            _ => match (1..=usize::from(MAX_GAMEPADS)).find(|number| !taken.contains(number)) {
+                Some(number) => {
+                    self.pads.retain(|pad| pad.number != number);
+                    number
+                }
+                None => (1..).find(|number| !taken.contains(number)).unwrap_or_default(),
+            },
+        };
+        self.pads.push(KnownPad { uuid, number });
+        self.pads.sort_by_key(|pad| pad.number);
+        self.connected.insert(id, number);
+        (number, true)
+    }
+
+    /// Frees a disconnected pad's number for the next controller of its model.
+    pub fn release(&mut self, id: GamepadId) {
+        self.connected.remove(&id);
+    }
+
+    /// Forgets which pads are connected, for a fresh gilrs context whose ids
+    /// start over.
+    pub fn disconnect_all(&mut self) {
+        self.connected.clear();
+    }
+}
+
+fn uuid_hex(uuid: [u8; 16]) -> String {
+    uuid.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+pub fn gamepad_slots_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(GAMEPAD_SLOTS_FILE_NAME)
+}
+
+pub fn read_gamepad_slots(path: &Path) -> Result<GamepadSlots, String> {
+    if !path.exists() {
+        return Ok(GamepadSlots::default());
+    }
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed reading gamepad numbers {}: {e}", path.display()))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed parsing gamepad numbers {}: {e}", path.display()))
+}
+
+/// Writes through a temporary file, like the persistent values.
+pub fn write_gamepad_slots(path: &Path, slots: &GamepadSlots) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed creating gamepad numbers directory {}: {e}", parent.display()))?;
+    }
+    let content = serde_json::to_string(slots).map_err(|e| format!("Failed serializing gamepad numbers: {e}"))?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, content)
+        .map_err(|e| format!("Failed writing gamepad numbers {}: {e}", temp_path.display()))?;
+    fs::rename(&temp_path, path).map_err(|e| format!("Failed replacing gamepad numbers {}: {e}", path.display()))
+}
@@ -0,0 +1,99 @@
+use crate::config::{ComponentKind, ScoreboardConfig};
+use crate::state::UiSnapshot;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HistoryPoint {
+    pub unix_ms: u64,
+    pub value: i32,
+}
+
+struct TrackedNumber {
+    limit: usize,
+    points: VecDeque<HistoryPoint>,
+}
+
+/// Recent values of `number` components that set `history`, oldest first,
+/// for dashboards drawing score progression. Built from emitted snapshots, so
+/// every way a value can change is covered.
+pub struct NumberHistory {
+    tracked: HashMap<String, TrackedNumber>,
+}
+
+impl NumberHistory {
+    pub fn new() -> Self {
+        Self {
+            tracked: HashMap::new(),
+        }
+    }
+
+    /// Tracks the components in `limits`. With `keep` (hot reload), numbers
+    /// that were already tracked keep their points, trimmed to the new limit.
+    pub fn configure(&mut self, limits: HashMap<String, usize>, keep: bool) {
+        let mut previous = std::mem::take(&mut self.tracked);
+        for (id, limit) in limits {
+            let mut points = match previous.remove(&id) {
+                Some(tracked) if keep => tracked.points,
+                _ => VecDeque::new(),
+            };
+            while points.len() > limit {
+                points.pop_front();
+            }
+            self.tracked.insert(id, TrackedNumber { limit, points });
+        }
+    }
+
+    /// Records a point for each tracked number whose value differs from its last point.
+    pub fn observe(&mut self, snapshot: &UiSnapshot) {
+        if self.tracked.is_empty() {
+            return;
+        }
+        let unix_ms = unix_time_ms();
+        for component in &snapshot.components {
+            let Some(tracked) = self.tracked.get_mut(&component.id) else {
+                continue;
+            };
+            let Some(value) = component.text.as_deref().and_then(|text| text.parse().ok()) else {
+                continue;
+            };
+            if tracked.points.back().is_some_and(|point| point.value == value) {
+                continue;
+            }
+            if tracked.points.len() == tracked.limit {
+                tracked.points.pop_front();
+            }
+            tracked.points.push_back(HistoryPoint { unix_ms, value });
+        }
+    }
+
+    pub fn points(&self, id: &str) -> Result<Vec<HistoryPoint>, String> {
+        let tracked = self
+            .tracked
+            .get(id)
+            .ok_or_else(|| format!("Component '{id}' does not keep a history; set history on a number component"))?;
+        Ok(tracked.points.iter().cloned().collect())
+    }
+}
+
+/// The `history` limit of each number component that sets one.
+pub fn history_limits(config: &ScoreboardConfig) -> HashMap<String, usize> {
+    config
+        .components
+        .iter()
+        .filter_map(|component| match &component.kind {
+            ComponentKind::Number {
+                history: Some(limit), ..
+            } => Some((component.id.clone(), *limit)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn unix_time_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
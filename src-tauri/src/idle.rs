@@ -0,0 +1,86 @@
+use crate::config::IdleSettings;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+struct Inner {
+    settings: Option<IdleSettings>,
+    last_activity: Instant,
+    idle: bool,
+}
+
+/// Tracks input and running clocks to put a board that nobody uses into
+/// low-power mode, and wakes it again on the next input.
+pub struct IdleMonitor {
+    inner: Mutex<Inner>,
+    woken: Condvar,
+}
+
+impl IdleMonitor {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                settings: None,
+                last_activity: Instant::now(),
+                idle: false,
+            }),
+            woken: Condvar::new(),
+        }
+    }
+
+    /// Applies `[global.idle]`; loading a config counts as activity.
+    pub fn configure(&self, settings: Option<IdleSettings>) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.settings = settings;
+            inner.last_activity = Instant::now();
+            inner.idle = false;
+        }
+        self.woken.notify_all();
+    }
+
+    /// Records operator input. Returns true when it woke the board.
+    pub fn activity(&self) -> bool {
+        let woke = match self.inner.lock() {
+            Ok(mut inner) => {
+                inner.last_activity = Instant::now();
+                std::mem::take(&mut inner.idle)
+            }
+            Err(_) => false,
+        };
+        if woke {
+            self.woken.notify_all();
+        }
+        woke
+    }
+
+    /// Called on each tick with whether anything is counting down or playing,
+    /// which keeps the board awake. Returns true when the board just went idle.
+    pub fn update(&self, busy: bool, now: Instant) -> bool {
+        let Ok(mut inner) = self.inner.lock() else {
+            return false;
+        };
+        if busy {
+            inner.last_activity = now;
+            return false;
+        }
+        let Some(after_ms) = inner.settings.as_ref().map(|settings| settings.after_ms) else {
+            return false;
+        };
+        if inner.idle || now.saturating_duration_since(inner.last_activity) < Duration::from_millis(after_ms as u64) {
+            return false;
+        }
+        inner.idle = true;
+        true
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.inner.lock().is_ok_and(|inner| inner.idle)
+    }
+
+    /// Sleeps for up to `timeout`, returning as soon as input wakes the board.
+    pub fn wait(&self, timeout: Duration) {
+        let Ok(inner) = self.inner.lock() else {
+            return;
+        };
+        let _ = self.woken.wait_timeout_while(inner, timeout, |inner| inner.idle);
+    }
+}
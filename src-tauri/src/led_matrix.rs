@@ -0,0 +1,288 @@
+use crate::config::{LedMatrixItem, LedMatrixProtocol, LedMatrixSettings};
+use crate::state::UiSnapshot;
+use serialport::SerialPort;
+use std::io::Write;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Called with a message when the matrix cannot be reached.
+pub type ErrorSink = Arc<dyn Fn(String) + Send + Sync>;
+
+const GLYPH_WIDTH: u32 = 3;
+const GLYPH_HEIGHT: u32 = 5;
+const GLYPH_ADVANCE: u32 = GLYPH_WIDTH + 1;
+const GLYPH_LINE_HEIGHT: u32 = GLYPH_HEIGHT + 1;
+const WRITE_TIMEOUT: Duration = Duration::from_millis(500);
+/// The last frame is sent again at this interval so a board that lost power
+/// or was replugged shows the score again without waiting for a change.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+const REOPEN_DELAY: Duration = Duration::from_secs(2);
+
+enum LedMatrixCommand {
+    Configure {
+        settings: Option<LedMatrixSettings>,
+        on_error: ErrorSink,
+    },
+    Frame(UiSnapshot),
+}
+
+/// Feeds snapshots to a serial LED matrix on a dedicated thread, so a slow or
+/// disconnected board never holds up the UI.
+pub struct LedMatrixOutput {
+    commands: Mutex<Sender<LedMatrixCommand>>,
+}
+
+impl LedMatrixOutput {
+    pub fn new() -> Self {
+        let (commands, receiver) = mpsc::channel();
+        thread::spawn(move || run_output(receiver));
+        Self {
+            commands: Mutex::new(commands),
+        }
+    }
+
+    pub fn configure(&self, settings: Option<LedMatrixSettings>, on_error: ErrorSink) {
+        self.send(LedMatrixCommand::Configure { settings, on_error });
+    }
+
+    pub fn submit(&self, snapshot: &UiSnapshot) {
+        self.send(LedMatrixCommand::Frame(snapshot.clone()));
+    }
+
+    fn send(&self, command: LedMatrixCommand) {
+        if let Ok(commands) = self.commands.lock() {
+            let _ = commands.send(command);
+        }
+    }
+}
+
+struct OutputState {
+    settings: Option<LedMatrixSettings>,
+    on_error: Option<ErrorSink>,
+    port: Option<Box<dyn SerialPort>>,
+    retry_at: Option<Instant>,
+    error_reported: bool,
+    frame: Option<Vec<u8>>,
+}
+
+fn run_output(receiver: Receiver<LedMatrixCommand>) {
+    let mut output = OutputState {
+        settings: None,
+        on_error: None,
+        port: None,
+        retry_at: None,
+        error_reported: false,
+        frame: None,
+    };
+
+    loop {
+        let first = match receiver.recv_timeout(REFRESH_INTERVAL) {
+            Ok(command) => Some(command),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+
+        // Only the newest snapshot matters; skip frames that queued up behind it.
+        let mut latest = None;
+        for command in first.into_iter().chain(receiver.try_iter()) {
+            match command {
+                LedMatrixCommand::Configure { settings, on_error } => {
+                    output.settings = settings;
+                    output.on_error = Some(on_error);
+                    output.port = None;
+                    output.retry_at = None;
+                    output.error_reported = false;
+                    output.frame = None;
+                }
+                LedMatrixCommand::Frame(snapshot) => latest = Some(snapshot),
+            }
+        }
+
+        let Some(settings) = output.settings.clone() else {
+            continue;
+        };
+        if let Some(snapshot) = latest {
+            output.frame = Some(encode_frame(&settings, &render_pixels(&settings, &snapshot)));
+        }
+        write_frame(&mut output, &settings);
+    }
+}
+
+fn write_frame(output: &mut OutputState, settings: &LedMatrixSettings) {
+    let Some(frame) = output.frame.as_ref() else {
+        return;
+    };
+    if output.port.is_none() {
+        if output.retry_at.is_some_and(|retry_at| Instant::now() < retry_at) {
+            return;
+        }
+        match serialport::new(&settings.port, settings.baud_rate)
+            .timeout(WRITE_TIMEOUT)
+            .open()
+        {
+            Ok(port) => output.port = Some(port),
+            Err(e) => {
+                report_error(output, format!("Failed opening LED matrix port {}: {e}", settings.port));
+                return;
+            }
+        }
+    }
+
+    let result = output.port.as_mut().map(|port| port.write_all(frame));
+    match result {
+        Some(Ok(())) => output.error_reported = false,
+        Some(Err(e)) => {
+            output.port = None;
+            report_error(output, format!("Failed writing to LED matrix {}: {e}", settings.port));
+        }
+        None => {}
+    }
+}
+
+/// Reports the first failure of a streak; later retries stay quiet until a write succeeds.
+fn report_error(output: &mut OutputState, message: String) {
+    output.retry_at = Some(Instant::now() + REOPEN_DELAY);
+    if output.error_reported {
+        return;
+    }
+    output.error_reported = true;
+    if let Some(on_error) = &output.on_error {
+        on_error(message);
+    }
+}
+
+/// Draws the configured components into a row-major RGB buffer.
+fn render_pixels(settings: &LedMatrixSettings, snapshot: &UiSnapshot) -> Vec<u8> {
+    let mut pixels = vec![0u8; (settings.width * settings.height * 3) as usize];
+    for item in &settings.items {
+        let Some(component) = snapshot
+            .components
+            .iter()
+            .find(|c| c.id == item.component && c.visible)
+        else {
+            continue;
+        };
+        let Some(text) = component.text.as_deref() else {
+            continue;
+        };
+        let (r, g, b, alpha) = parse_color(item.color.as_deref().unwrap_or(&component.font_color));
+        // The panel is black where nothing is lit, so see-through text is drawn dimmer.
+        let level = settings.brightness * alpha * component.opacity.unwrap_or(1.0);
+        let color = [r, g, b].map(|channel| (channel as f32 * level).round() as u8);
+
+        // Multiline labels continue below; the rest of a line past the edge is cut off.
+        for (line_index, line) in text.lines().enumerate() {
+            let top = item.y + line_index as u32 * GLYPH_LINE_HEIGHT * item.scale;
+            draw_line(settings, &mut pixels, item, line, top, color);
+        }
+    }
+    pixels
+}
+
+fn draw_line(settings: &LedMatrixSettings, pixels: &mut [u8], item: &LedMatrixItem, line: &str, top: u32, color: [u8; 3]) {
+    let mut cursor_x = item.x;
+    for c in line.chars() {
+        let glyph = glyph(c);
+        for (row, bits) in glyph.iter().enumerate() {
+            for column in 0..GLYPH_WIDTH {
+                if bits & (0b100 >> column) == 0 {
+                    continue;
+                }
+                for dy in 0..item.scale {
+                    for dx in 0..item.scale {
+                        let x = cursor_x + column * item.scale + dx;
+                        let y = top + row as u32 * item.scale + dy;
+                        if x < settings.width && y < settings.height {
+                            let offset = ((y * settings.width + x) * 3) as usize;
+                            pixels[offset..offset + 3].copy_from_slice(&color);
+                        }
+                    }
+                }
+            }
+        }
+        cursor_x += GLYPH_ADVANCE * item.scale;
+        if cursor_x >= settings.width {
+            break;
+        }
+    }
+}
+
+fn encode_frame(settings: &LedMatrixSettings, pixels: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(pixels.len() + 6);
+    match settings.protocol {
+        LedMatrixProtocol::Glediator => frame.push(0x01),
+        LedMatrixProtocol::Adalight => {
+            let count = (settings.width * settings.height).saturating_sub(1);
+            let [hi, lo] = (count as u16).to_be_bytes();
+            frame.extend_from_slice(&[b'A', b'd', b'a', hi, lo, hi ^ lo ^ 0x55]);
+        }
+    }
+    frame.extend_from_slice(pixels);
+    frame
+}
+
+/// `#RRGGBB` or `#RRGGBBAA` as channels and an alpha from 0.0 to 1.0.
+fn parse_color(color: &str) -> (u8, u8, u8, f32) {
+    let hex = color.trim().trim_start_matches('#');
+    let channel = |range: std::ops::Range<usize>| {
+        hex.get(range)
+            .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+            .unwrap_or(0xFF)
+    };
+    (channel(0..2), channel(2..4), channel(4..6), channel(6..8) as f32 / 255.0)
+}
+
+/// 3x5 glyphs, one row per entry with the leftmost pixel in the high bit.
+/// Letters are drawn in upper case; unknown characters are left blank.
+fn glyph(c: char) -> [u8; 5] {
+    match c.to_ascii_uppercase() {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        '!' => [0b010, 0b010, 0b010, 0b000, 0b010],
+        '?' => [0b110, 0b001, 0b010, 0b000, 0b010],
+        '#' => [0b101, 0b111, 0b101, 0b111, 0b101],
+        '<' | '\u{25C0}' => [0b001, 0b011, 0b111, 0b011, 0b001],
+        '>' | '\u{25B6}' => [0b100, 0b110, 0b111, 0b110, 0b100],
+        _ => [0; GLYPH_HEIGHT as usize],
+    }
+}
@@ -1,54 +1,628 @@
-mod config;
-mod state;
-
-use crate::config::{load_config_from_path, load_config_from_str};
-use crate::state::{Action, RuntimeState, UiSnapshot};
-use gilrs::{Button, EventType, Gilrs};
+mod announcements;
+mod asset_cache;
+mod assets;
+mod aux_timers;
+mod audio;
+mod cli;
+mod config_queue;
+mod cooldown;
+mod crash;
+mod discovery;
+mod event_log;
+mod fixtures;
+mod fonts;
+mod freeze;
+mod gamepad;
+mod history;
+mod idle;
+mod led_matrix;
+mod lock;
+mod narration;
+mod obs;
+mod osc;
+mod package;
+mod persistence;
+mod player_select;
+mod playlist;
+mod profile;
+mod recording;
+mod relay;
+mod remote;
+mod repeat;
+mod schema;
+mod scoresheet;
+mod scripting;
+mod serial_input;
+mod session;
+mod summary;
+mod sync;
+mod updates;
+mod watchdog;
+mod webhooks;
+
+use scoreboard_core::{config, state};
+
+use crate::announcements::{Announcement, AnnouncementScheduler, AnnouncementUpdate};
+use crate::audio::{AudioDevice, AudioPlayer, SoundCue};
+use crate::aux_timers::{AuxTimerStatus, AuxTimers};
+use crate::playlist::{PlaylistPlayer, PlaylistStatus};
+use crate::crash::{install_panic_hook, CrashReporter};
+use crate::config_queue::{ConfigJob, ConfigQueue};
+use crate::cooldown::Cooldowns;
+use crate::discovery::{discover, Advertiser, DiscoveredInstance, DEFAULT_DISCOVERY_TIMEOUT, MAX_DISCOVERY_TIMEOUT};
+use crate::event_log::{export_event_log as write_event_log, EventLogFormat};
+use crate::config::{
+    diagnose_config_str, is_gamepad_shortcut, load_config_from_path, load_config_from_str, serialize_config,
+    ConfigDiagnostic, KeyRepeat, KeyboardMode, RemoteRole,
+};
+use crate::fixtures::{fetch_fixtures, unix_time_ms, Fixture, FixturePrepared, FixtureScheduler};
+use crate::asset_cache::{AssetCache, CacheUsage};
+use crate::assets::{
+    import_image as copy_image_to_assets, relativize_image_paths, validate_image_file, ASSETS_DIR, IMAGE_EXTENSIONS,
+};
+use crate::fonts::{bundled_fonts, choose_font_families, BundledFont, FontChoice};
+use crate::freeze::{FreezeMode, FreezeStatus};
+use crate::gamepad::{gamepad_slots_path, read_gamepad_slots, write_gamepad_slots, AxisButtons, GamepadSlots};
+use crate::history::{history_limits, HistoryPoint, NumberHistory};
+use crate::idle::IdleMonitor;
+use crate::led_matrix::LedMatrixOutput;
+use crate::lock::{BoardLock, LockStatus};
+use crate::narration::{default_narration_path, Narrator};
+use crate::obs::ObsClient;
+use crate::osc::{OscBridge, OscHandler};
+use crate::package::{extract_package, is_package, PACKAGE_EXTENSION};
+use crate::persistence::{persistent_file_path, read_persistent_store, write_persistent_store, PersistentStore};
+use crate::player_select::{PlayerSelect, PlayerSelectKey, PlayerSelectOutcome};
+use crate::profile::ConfigProfile;
+use crate::recording::{convert_recording_to_csv, default_recording_path, Recorder};
+use crate::relay::RelayController;
+use crate::remote::{RecentClients, RemoteError, RemoteHandler, RemoteRequest, RemoteServer};
+use crate::repeat::HeldInputs;
+use crate::schema::config_schema;
+use crate::scoresheet::write_scoresheet_pdf;
+use crate::scripting::{ScriptChange, ScriptHook, ScriptHost};
+use crate::serial_input::{ConsoleSink, SerialInput};
+use crate::session::{read_session, session_file_path, write_session, SessionInfo, SessionSnapshot};
+use crate::state::{Action, ExpireTrigger, HotkeyBinding, LoggedEvent, PeriodScore, RuntimeState, SnapshotClock, UiSnapshot};
+use crate::summary::{GameLog, SummaryFormat};
+use crate::sync::{SnapshotSink, SyncService, SyncStatus};
+use crate::updates::{check_for_update, UpdateInfo, DEFAULT_RELEASES_URL};
+use crate::watchdog::{HealthReport, LongOperation, Watchdog, WorkerThread};
+use crate::webhooks::WebhookDispatcher;
+use gilrs::{Button, EventType, GamepadId, Gilrs};
 use notify::{Event, EventKind, RecursiveMode, Watcher};
 use std::collections::HashMap;
 use std::path::Path;
 use std::path::PathBuf;
 use std::str::FromStr;
-use std::sync::{Arc, Mutex};
+use std::sync::mpsc;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, PoisonError};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use rfd::FileDialog;
+use serde::Serialize;
 use tauri::menu::{Menu, MenuItem, Submenu};
 use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
 const MENU_ITEM_LOAD_CONFIG: &str = "load_config";
+const MENU_ITEM_SAVE_CONFIG_AS: &str = "save_config_as";
+const MENU_ITEM_EXPORT_CONFIG_SCHEMA: &str = "export_config_schema";
+const MENU_ITEM_EXPORT_SCORESHEET: &str = "export_scoresheet";
+const MENU_ITEM_START_RECORDING: &str = "start_recording";
+const MENU_ITEM_STOP_RECORDING: &str = "stop_recording";
+const MENU_ITEM_EXPORT_RECORDING_CSV: &str = "export_recording_csv";
+const MENU_ITEM_START_NARRATION: &str = "start_narration";
+const MENU_ITEM_STOP_NARRATION: &str = "stop_narration";
+const MENU_ITEM_START_INPUT_TEST: &str = "start_input_test";
+const MENU_ITEM_STOP_INPUT_TEST: &str = "stop_input_test";
+const MAIN_WINDOW_LABEL: &str = "main";
 const EVENT_STATE_UPDATED: &str = "scoreboard://state-updated";
 const EVENT_ERROR: &str = "scoreboard://error";
+const EVENT_HEALTH: &str = "scoreboard://health";
+const EVENT_STATUS: &str = "scoreboard://status";
+const EVENT_CAPABILITIES: &str = "scoreboard://capabilities";
+const EVENT_TIMER_EXPIRED: &str = "scoreboard://timer-expired";
+const EVENT_VOICE_CUE: &str = "scoreboard://voice-cue";
+const EVENT_T_MINUS: &str = "scoreboard://t-minus";
+const EVENT_GAMEPAD_STATUS: &str = "scoreboard://gamepad-status";
+const EVENT_GAMEPAD_CONNECTED: &str = "scoreboard://gamepad-connected";
+const EVENT_GAMEPAD_DISCONNECTED: &str = "scoreboard://gamepad-disconnected";
+const EVENT_PLAYER_SELECT: &str = "scoreboard://player-select";
+const EVENT_PLAYER_ACTION: &str = "scoreboard://player-action";
+const EVENT_CONFIG_RELOADED: &str = "scoreboard://config-reloaded";
+const EVENT_LOCK_STATUS: &str = "scoreboard://lock-status";
+const EVENT_ACTION_SUPPRESSED: &str = "scoreboard://action-suppressed";
+const EVENT_FIXTURE_PREPARED: &str = "scoreboard://fixture-prepared";
+const EVENT_UPDATE_AVAILABLE: &str = "scoreboard://update-available";
+const EVENT_FREEZE: &str = "scoreboard://freeze";
+const EVENT_INPUT_TEST: &str = "scoreboard://input-test";
+const EVENT_INPUT_TEST_STATUS: &str = "scoreboard://input-test-status";
+const EVENT_FONTS: &str = "scoreboard://fonts";
+const EVENT_BUNDLED_FONTS: &str = "scoreboard://bundled-fonts";
+const EVENT_AUX_TIMERS: &str = "scoreboard://aux-timers";
+const EVENT_AUX_TIMER_EXPIRED: &str = "scoreboard://aux-timer-expired";
+const EVENT_PLAYLIST: &str = "scoreboard://playlist";
+const GAMEPAD_RETRY_INITIAL: Duration = Duration::from_secs(1);
+const GAMEPAD_RETRY_MAX: Duration = Duration::from_secs(30);
+pub(crate) const TIMER_TICK: Duration = Duration::from_millis(50);
+/// Below the watchdog's stall threshold for the timer thread.
+const IDLE_TIMER_TICK: Duration = Duration::from_millis(500);
+/// Watchdog ticks between status events while the board idles.
+const IDLE_STATUS_TICKS: u64 = 60;
+const DEFAULT_RELAY_PULSE: Duration = Duration::from_secs(1);
+const SESSION_AUTOSAVE_INTERVAL: Duration = Duration::from_secs(5);
+const CONFIG_RELOAD_DEBOUNCE: Duration = Duration::from_millis(300);
 const DEFAULT_CONFIG_NAME: &str = "basketball.toml";
+/// Folder in the app data directory holding unpacked packs and downloaded images.
+const ASSET_CACHE_DIR: &str = "asset-cache";
 
 #[derive(Clone)]
 struct AppState {
     runtime: Arc<Mutex<RuntimeState>>,
-    action_by_shortcut: Arc<Mutex<HashMap<String, Action>>>,
-    action_by_gamepad: Arc<Mutex<HashMap<String, Action>>>,
+    action_by_shortcut: Arc<Mutex<HashMap<String, HotkeyBinding>>>,
+    action_by_gamepad: Arc<Mutex<HashMap<String, HotkeyBinding>>>,
+    cooldowns: Arc<Mutex<Cooldowns>>,
+    /// Keys and buttons held on repeating bindings.
+    held_inputs: Arc<Mutex<HeldInputs>>,
     hotkeys_paused: Arc<Mutex<bool>>,
+    /// While set, key and button presses are reported on `EVENT_INPUT_TEST`
+    /// instead of being applied.
+    input_test: Arc<Mutex<bool>>,
     active_config_path: Arc<Mutex<Option<PathBuf>>>,
     config_watcher: Arc<Mutex<Option<notify::RecommendedWatcher>>>,
+    /// The watcher's last error, cleared when it is restarted.
+    config_watcher_error: Arc<Mutex<Option<String>>>,
+    config_queue: Arc<ConfigQueue>,
+    hot_reload_enabled: Arc<Mutex<bool>>,
+    watchdog: Arc<Mutex<Watchdog>>,
+    freeze: Arc<Mutex<FreezeMode>>,
+    /// How the active config's font fallback lists resolved.
+    font_choices: Arc<Mutex<Vec<FontChoice>>>,
+    /// Fonts from the `fonts` folder next to the active config.
+    bundled_fonts: Arc<Mutex<Vec<BundledFont>>>,
+    input_capabilities: Arc<Mutex<InputCapabilities>>,
+    audio: Arc<AudioPlayer>,
+    relays: Arc<RelayController>,
+    led_matrix: Arc<LedMatrixOutput>,
+    webhooks: Arc<WebhookDispatcher>,
+    osc: Arc<OscBridge>,
+    serial_input: Arc<Mutex<SerialInput>>,
+    obs: Arc<ObsClient>,
+    sync: Arc<SyncService>,
+    idle: Arc<IdleMonitor>,
+    gamepad_status: Arc<Mutex<GamepadStatus>>,
+    /// Connected gamepads, as last seen by the gamepad thread.
+    gamepads: Arc<Mutex<Vec<GamepadInfo>>>,
+    /// Thresholds for turning axes into presses, from the config's `[gamepad]`.
+    gamepad_settings: Arc<Mutex<config::GamepadSettings>>,
+    snapshot_clock: Arc<Mutex<SnapshotClock>>,
+    last_session: Arc<Mutex<Option<SessionSnapshot>>>,
+    persistent_store: Arc<Mutex<PersistentStore>>,
+    number_history: Arc<Mutex<NumberHistory>>,
+    game_log: Arc<Mutex<GameLog>>,
+    fixtures: Arc<Mutex<FixtureScheduler>>,
+    crash_reporter: Arc<Mutex<CrashReporter>>,
+    asset_cache: Arc<Mutex<AssetCache>>,
+    recorder: Arc<Mutex<Recorder>>,
+    narrator: Arc<Mutex<Narrator>>,
+    player_select: Arc<Mutex<PlayerSelect>>,
+    board_lock: Arc<Mutex<BoardLock>>,
+    announcements: Arc<Mutex<AnnouncementScheduler>>,
+    aux_timers: Arc<Mutex<AuxTimers>>,
+    playlist: Arc<PlaylistPlayer>,
+    remote: Arc<Mutex<RemoteServer>>,
+    /// Hosts using the HTTP remote or OSC, shared by both.
+    remote_clients: Arc<RecentClients>,
+    advertiser: Arc<Mutex<Advertiser>>,
+    /// The active config's rules script, if it has one.
+    scripts: Arc<Mutex<Option<ScriptHost>>>,
+}
+
+/// Payload of `EVENT_ACTION_SUPPRESSED`: a press dropped by its binding's cooldown.
+#[derive(Debug, Clone, Serialize)]
+struct SuppressedPress {
+    shortcut: String,
+    cooldown_ms: u64,
+    elapsed_ms: u64,
+}
+
+/// Payload of `EVENT_INPUT_TEST`: a press seen while the input test runs.
+#[derive(Debug, Clone, Serialize)]
+struct TestedInput {
+    /// `keyboard` or `gamepad`.
+    source: &'static str,
+    /// The shortcut or `gamepad:<BUTTON>` as written in keybinds.
+    input: String,
+    /// What the press would have done, as `<component>.<action>`; `None` when unbound.
+    action: Option<String>,
+}
+
+/// Payload of `EVENT_INPUT_TEST_STATUS`.
+#[derive(Debug, Clone, Serialize)]
+struct InputTestStatus {
+    active: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct GamepadStatus {
+    available: bool,
+    connected: usize,
+    /// The numbers of the connected pads, as remembered for their controllers.
+    pads: Vec<usize>,
+    error: Option<String>,
+}
+
+impl GamepadStatus {
+    fn available(pads: Vec<usize>) -> Self {
+        Self {
+            available: true,
+            connected: pads.len(),
+            pads,
+            error: None,
+        }
+    }
+
+    fn unavailable(error: String) -> Self {
+        Self {
+            available: false,
+            connected: 0,
+            pads: Vec::new(),
+            error: Some(error),
+        }
+    }
+}
+
+/// A gamepad as listed by `list_gamepads` and sent with the connect and
+/// disconnect events.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct GamepadInfo {
+    /// The pad's number, as used in `gamepad<n>:` keys.
+    id: usize,
+    name: String,
+}
+
+/// Payload of `get_app_status` and `EVENT_STATUS`, for the operator's status bar.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct AppStatus {
+    hotkeys_paused: bool,
+    config_path: Option<String>,
+    hot_reload_enabled: bool,
+    /// Whether the config file is being watched right now.
+    watching_config: bool,
+    watcher_error: Option<String>,
+    gamepads: Vec<GamepadInfo>,
+    /// Distinct hosts that used the HTTP remote or OSC in the last minute,
+    /// plus OSC subscribers.
+    remote_clients: usize,
+    /// Whether `[global.idle]` has the board in low-power mode.
+    idle: bool,
+    /// This board's part in `[global.sync]`, when set.
+    sync: Option<SyncStatus>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct TimerExpiredPayload {
+    id: String,
+}
+
+/// Payload of `EVENT_T_MINUS`. `expires_at_ms` is the Unix time at which the
+/// timer will reach zero if it keeps running.
+#[derive(Debug, Clone, Serialize)]
+struct TMinusPayload {
+    id: String,
+    offset_ms: i64,
+    remaining_ms: i64,
+    expires_at_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct VoiceCuePayload {
+    id: String,
+    text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct AuxTimerExpiredPayload {
+    name: String,
+    label: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct InputCapabilities {
+    global_shortcuts: bool,
+    keyboard_mode: &'static str,
+    reason: Option<String>,
+}
+
+impl InputCapabilities {
+    fn global() -> Self {
+        Self {
+            global_shortcuts: true,
+            keyboard_mode: "global",
+            reason: None,
+        }
+    }
+
+    fn focused(reason: String) -> Self {
+        Self {
+            global_shortcuts: false,
+            keyboard_mode: "focused",
+            reason: Some(format!(
+                "Keybinds only work while the window is focused ({reason})"
+            )),
+        }
+    }
 }
 
 #[tauri::command]
-fn load_config_from_file(app: AppHandle, state: tauri::State<AppState>, path: String) -> Result<(), String> {
+async fn load_config_from_file(state: tauri::State<'_, AppState>, path: String) -> Result<(), String> {
+    ensure_board_unlocked(&state)?;
     let resolved_path = resolve_config_path(Path::new(&path))?;
-    let config = load_config_from_path(&resolved_path)?;
-    apply_config(app.clone(), &state, config)?;
-    configure_config_hot_reload(&app, &state, Some(resolved_path))
+    wait_for_config_job(&state, ConfigJob::File(resolved_path)).await
+}
+
+#[tauri::command]
+async fn load_config_from_text(state: tauri::State<'_, AppState>, content: String) -> Result<(), String> {
+    ensure_board_unlocked(&state)?;
+    wait_for_config_job(&state, ConfigJob::Text(content)).await
+}
+
+/// Queues a config change and waits for it to be applied. The wait happens
+/// off the main thread, which the apply needs for registering shortcuts.
+async fn wait_for_config_job(state: &AppState, job: ConfigJob) -> Result<(), String> {
+    let result = state.config_queue.submit(job);
+    tauri::async_runtime::spawn_blocking(move || result.recv())
+        .await
+        .map_err(|e| format!("Config apply failed: {e}"))?
+        .map_err(|_| "Config apply was cancelled".to_string())?
+}
+
+/// Queues a config file without waiting, for callers on the main thread.
+/// Problems are reported as errors.
+fn queue_config_file(app: &AppHandle, path: &Path) {
+    let state = app.state::<AppState>();
+    match ensure_board_unlocked(&state).and_then(|()| resolve_config_path(path)) {
+        Ok(path) => {
+            let _ = state.config_queue.submit(ConfigJob::File(path));
+        }
+        Err(e) => emit_error(app, &e),
+    }
+}
+
+/// Applies queued config changes one at a time; see `ConfigQueue`.
+fn spawn_config_thread(app: AppHandle) {
+    thread::spawn(move || {
+        let state = app.state::<AppState>();
+        loop {
+            let (job, reply) = state.config_queue.next();
+            let result = run_config_job(&app, &state, job);
+            // Nobody waits for menu loads and hot reloads, so their errors are shown here.
+            if let Err(mpsc::SendError(Err(e))) = reply.send(result) {
+                emit_error(&app, &e);
+            }
+        }
+    });
+}
+
+fn run_config_job(app: &AppHandle, state: &tauri::State<AppState>, job: ConfigJob) -> Result<(), String> {
+    // Loading can take a while, and the worker threads wait on the locks it holds.
+    let _operation = LongOperation::begin(&state.watchdog, &[WorkerThread::Timer, WorkerThread::Gamepad]);
+    match job {
+        ConfigJob::File(path) => {
+            let config = load_config_file(app, &path)?;
+            apply_config(app.clone(), state, config, false, Some(&path))?;
+            configure_config_hot_reload(app, state, Some(path))
+        }
+        ConfigJob::Text(content) => {
+            let config = load_config_from_str(&content)?;
+            apply_config(app.clone(), state, config, false, None)?;
+            configure_config_hot_reload(app, state, None)
+        }
+        ConfigJob::Reload => reload_active_config(app),
+        ConfigJob::Layout(layout) => {
+            let config = state
+                .runtime
+                .lock()
+                .map_err(|_| "Runtime lock poisoned".to_string())?
+                .config
+                .as_ref()
+                .ok_or_else(|| "No config loaded".to_string())?
+                .with_layout(layout.as_deref())?;
+            apply_config(app.clone(), state, config, true, None)
+        }
+    }
+}
+
+/// Loads a config file, or the config inside a template pack. A pack stays
+/// the active path, so saving a new version of it hot-reloads the board.
+fn load_config_file(app: &AppHandle, path: &Path) -> Result<config::ScoreboardConfig, String> {
+    load_config_from_path(&unpacked_config_path(app, path)?)
 }
 
+/// The config file to read for `path`, unpacking it first when it is a pack.
+fn unpacked_config_path(app: &AppHandle, path: &Path) -> Result<PathBuf, String> {
+    if !is_package(path) {
+        return Ok(path.to_path_buf());
+    }
+    let cache_dir = app
+        .state::<AppState>()
+        .asset_cache
+        .lock()
+        .map_err(|_| "Asset cache lock poisoned".to_string())?
+        .package_dir()?;
+    extract_package(path, &cache_dir)
+}
+
+/// Measures how long a config takes to load and how much each board update
+/// costs, warning when it may strain the update loop. Profiles `content`,
+/// the file or pack at `path`, or else the active config file; the loaded
+/// board is not touched.
 #[tauri::command]
-fn load_config_from_text(
+async fn profile_config(
     app: AppHandle,
-    state: tauri::State<AppState>,
-    content: String,
-) -> Result<(), String> {
-    let config = load_config_from_str(&content)?;
-    apply_config(app.clone(), &state, config)?;
-    configure_config_hot_reload(&app, &state, None)
+    path: Option<String>,
+    content: Option<String>,
+) -> Result<ConfigProfile, String> {
+    let path = match (&content, path) {
+        (Some(_), _) => None,
+        (None, Some(path)) => Some(resolve_config_path(Path::new(&path))?),
+        (None, None) => Some(
+            app.state::<AppState>()
+                .active_config_path
+                .lock()
+                .map_err(|_| "Active config path lock poisoned".to_string())?
+                .clone()
+                .ok_or_else(|| "No config file loaded".to_string())?,
+        ),
+    };
+    tauri::async_runtime::spawn_blocking(move || match (content, path) {
+        (Some(content), _) => profile::profile_config(|| load_config_from_str(&content), TIMER_TICK),
+        (None, Some(path)) => {
+            let config_path = unpacked_config_path(&app, &path)?;
+            profile::profile_config(|| load_config_from_path(&config_path), TIMER_TICK)
+        }
+        (None, None) => Err("No config to profile".to_string()),
+    })
+    .await
+    .map_err(|e| format!("Profiling failed: {e}"))?
+}
+
+/// Payload of `list_layouts`.
+#[derive(Debug, Clone, Serialize)]
+struct LayoutList {
+    /// `None` while no layout is applied.
+    active: Option<String>,
+    layouts: Vec<String>,
+}
+
+#[tauri::command]
+fn list_layouts(state: tauri::State<AppState>) -> Result<LayoutList, String> {
+    let runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
+    let config = runtime.config.as_ref().ok_or_else(|| "No config loaded".to_string())?;
+    Ok(LayoutList {
+        active: config.layout.clone(),
+        layouts: config.layouts.clone(),
+    })
+}
+
+/// Switches to a `[layout.<name>]`, or to the positions outside any layout
+/// when `name` is omitted. Values are kept.
+#[tauri::command]
+async fn select_layout(state: tauri::State<'_, AppState>, name: Option<String>) -> Result<(), String> {
+    ensure_board_unlocked(&state)?;
+    wait_for_config_job(&state, ConfigJob::Layout(name)).await
+}
+
+/// Refuses inputs that would change the scoreboard while it is locked.
+fn ensure_board_unlocked(state: &AppState) -> Result<(), String> {
+    let locked = state
+        .board_lock
+        .lock()
+        .map_err(|_| "Board lock poisoned".to_string())?
+        .is_locked();
+    if locked {
+        return Err("The scoreboard is locked".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn lock_board(app: AppHandle, state: tauri::State<AppState>) -> Result<(), String> {
+    let configured = state
+        .runtime
+        .lock()
+        .map_err(|_| "Runtime lock poisoned".to_string())?
+        .config
+        .as_ref()
+        .is_some_and(|config| config.global.lock.is_some());
+    if !configured {
+        return Err("Locking requires [global.lock] with a pin".to_string());
+    }
+    let status = {
+        let mut board_lock = state.board_lock.lock().map_err(|_| "Board lock poisoned".to_string())?;
+        if !board_lock.lock() {
+            return Ok(());
+        }
+        board_lock.status()
+    };
+    app.emit(EVENT_LOCK_STATUS, status)
+        .map_err(|e| format!("Failed to emit lock status: {e}"))
+}
+
+/// Unlocks the board if `pin` matches `[global.lock].pin`.
+#[tauri::command]
+fn unlock_board(app: AppHandle, state: tauri::State<AppState>, pin: String) -> Result<(), String> {
+    let expected = state
+        .runtime
+        .lock()
+        .map_err(|_| "Runtime lock poisoned".to_string())?
+        .config
+        .as_ref()
+        .and_then(|config| config.global.lock.as_ref())
+        .map(|lock| lock.pin.clone());
+    let status = {
+        let mut board_lock = state.board_lock.lock().map_err(|_| "Board lock poisoned".to_string())?;
+        if !board_lock.is_locked() {
+            return Ok(());
+        }
+        match expected {
+            Some(expected) => board_lock.unlock(&expected, &pin, Instant::now())?,
+            None => {
+                board_lock.release();
+            }
+        }
+        board_lock.status()
+    };
+    app.emit(EVENT_LOCK_STATUS, status)
+        .map_err(|e| format!("Failed to emit lock status: {e}"))
+}
+
+#[tauri::command]
+fn get_lock_status(state: tauri::State<AppState>) -> Result<LockStatus, String> {
+    let board_lock = state.board_lock.lock().map_err(|_| "Board lock poisoned".to_string())?;
+    Ok(board_lock.status())
+}
+
+/// Checks config text without loading it and reports every problem found.
+#[tauri::command]
+fn validate_config_text(content: String) -> Vec<ConfigDiagnostic> {
+    diagnose_config_str(&content)
+}
+
+#[tauri::command]
+fn save_config_as(state: tauri::State<AppState>, path: String) -> Result<(), String> {
+    let resolved_path = resolve_config_path(Path::new(&path))?;
+    let config = {
+        let runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
+        runtime.export_config()
+    };
+    let mut config = config.ok_or_else(|| "No config loaded".to_string())?;
+    state
+        .asset_cache
+        .lock()
+        .map_err(|_| "Asset cache lock poisoned".to_string())?
+        .restore_urls(&mut config);
+    if let Some(base_dir) = resolved_path.parent() {
+        relativize_image_paths(&mut config, base_dir);
+    }
+    let content = serialize_config(&config)?;
+    std::fs::write(&resolved_path, content)
+        .map_err(|e| format!("Failed writing config {}: {e}", resolved_path.display()))
+}
+
+/// Returns the JSON Schema for config files, also writing it to `path` when given.
+#[tauri::command]
+fn export_config_schema(path: Option<String>) -> Result<String, String> {
+    let content = serde_json::to_string_pretty(&config_schema())
+        .map_err(|e| format!("Failed serializing config schema: {e}"))?;
+    if let Some(path) = path {
+        let resolved_path = resolve_config_path(Path::new(&path))?;
+        std::fs::write(&resolved_path, &content)
+            .map_err(|e| format!("Failed writing config schema {}: {e}", resolved_path.display()))?;
+    }
+    Ok(content)
 }
 
 #[tauri::command]
@@ -58,12 +632,176 @@ fn update_label_text(
     id: String,
     value: String,
 ) -> Result<(), String> {
+    ensure_board_unlocked(&state)?;
     let changed = {
         let mut runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
         runtime.set_label_value(&id, value)?
     };
     if changed {
-        emit_snapshot(&app, &state.runtime)?;
+        emit_snapshot(&app, &state)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn get_number_history(state: tauri::State<AppState>, id: String) -> Result<Vec<HistoryPoint>, String> {
+    state
+        .number_history
+        .lock()
+        .map_err(|_| "Number history lock poisoned".to_string())?
+        .points(&id)
+}
+
+/// Final score, line score, and game log as Markdown (the default) or plain
+/// text, laid out by the `[global.summary]` templates.
+#[tauri::command]
+fn generate_summary(state: tauri::State<AppState>, format: Option<String>) -> Result<String, String> {
+    let format = SummaryFormat::parse(format.as_deref())?;
+    let periods = state
+        .runtime
+        .lock()
+        .map_err(|_| "Runtime lock poisoned".to_string())?
+        .score_by_period()
+        .to_vec();
+    state
+        .game_log
+        .lock()
+        .map_err(|_| "Game log lock poisoned".to_string())?
+        .render(format, &periods)
+}
+
+/// Games in the `[global.fixtures]` calendar that have not started yet, soonest first.
+#[tauri::command]
+fn get_upcoming_fixtures(state: tauri::State<AppState>) -> Result<Vec<Fixture>, String> {
+    Ok(state
+        .fixtures
+        .lock()
+        .map_err(|_| "Fixture scheduler lock poisoned".to_string())?
+        .upcoming(unix_time_ms()))
+}
+
+/// Asks the releases URL for a newer version and emits
+/// `scoreboard://update-available` when there is one. Runs off the main thread
+/// since the request can take a while on venue networks.
+#[tauri::command(async)]
+fn check_for_updates(app: AppHandle) -> Result<Option<UpdateInfo>, String> {
+    let url = releases_url(&app)?;
+    let update = check_for_update(&url)?;
+    if let Some(update) = &update {
+        let _ = app.emit(EVENT_UPDATE_AVAILABLE, update);
+    }
+    Ok(update)
+}
+
+fn releases_url(app: &AppHandle) -> Result<String, String> {
+    let state = app.state::<AppState>();
+    let runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
+    Ok(runtime
+        .config
+        .as_ref()
+        .and_then(|config| config.global.updates.as_ref()?.url.clone())
+        .unwrap_or_else(|| DEFAULT_RELEASES_URL.to_string()))
+}
+
+/// Checks for updates in the background when the config loaded at startup
+/// sets `check_on_startup`. Failures stay quiet, since many venue machines
+/// are offline.
+fn spawn_startup_update_check(app: AppHandle) {
+    let enabled = app
+        .state::<AppState>()
+        .runtime
+        .lock()
+        .ok()
+        .and_then(|runtime| Some(runtime.config.as_ref()?.global.updates.as_ref()?.check_on_startup))
+        .unwrap_or(false);
+    if !enabled {
+        return;
+    }
+    thread::spawn(move || {
+        let update = releases_url(&app).and_then(|url| check_for_update(&url));
+        if let Ok(Some(update)) = update {
+            let _ = app.emit(EVENT_UPDATE_AVAILABLE, update);
+        }
+    });
+}
+
+/// Writes the game log, rosters, and team fouls and timeouts as a printable
+/// PDF scoresheet.
+#[tauri::command]
+fn generate_scoresheet(state: tauri::State<AppState>, path: String) -> Result<(), String> {
+    let (periods, events) = {
+        let runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
+        (runtime.score_by_period().to_vec(), runtime.event_log().iter().cloned().collect::<Vec<_>>())
+    };
+    let lines = state
+        .game_log
+        .lock()
+        .map_err(|_| "Game log lock poisoned".to_string())?
+        .scoresheet_lines(&periods, &events)?;
+    write_scoresheet_pdf(&resolve_config_path(Path::new(&path))?, &lines)
+}
+
+/// Every action applied since the config was loaded, oldest first, with the
+/// changed value, period, and game clock at the time.
+#[tauri::command]
+fn get_event_log(state: tauri::State<AppState>) -> Result<Vec<LoggedEvent>, String> {
+    let runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
+    Ok(runtime.event_log().iter().cloned().collect())
+}
+
+/// Points each `number` component gained per period, for announcers asking
+/// about a single quarter.
+#[tauri::command]
+fn get_score_by_period(state: tauri::State<AppState>) -> Result<Vec<PeriodScore>, String> {
+    let runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
+    Ok(runtime.score_by_period().to_vec())
+}
+
+/// Writes the event log to `path` as `csv` or `json`, with the per-period
+/// breakdown in JSON; without a format the file's extension decides.
+#[tauri::command]
+fn export_event_log(state: tauri::State<AppState>, path: String, format: Option<String>) -> Result<(), String> {
+    let path = resolve_config_path(Path::new(&path))?;
+    let format = EventLogFormat::parse(format.as_deref(), &path)?;
+    let (events, periods) = {
+        let runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
+        (runtime.event_log().iter().cloned().collect::<Vec<_>>(), runtime.score_by_period().to_vec())
+    };
+    write_event_log(&events, &periods, &path, format)
+}
+
+#[tauri::command]
+fn append_ticker_message(
+    app: AppHandle,
+    state: tauri::State<AppState>,
+    id: String,
+    message: String,
+) -> Result<(), String> {
+    ensure_board_unlocked(&state)?;
+    let changed = {
+        let mut runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
+        runtime.append_ticker_message(&id, message)?
+    };
+    if changed {
+        emit_snapshot(&app, &state)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn set_ticker_messages(
+    app: AppHandle,
+    state: tauri::State<AppState>,
+    id: String,
+    messages: Vec<String>,
+) -> Result<(), String> {
+    ensure_board_unlocked(&state)?;
+    let changed = {
+        let mut runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
+        runtime.set_ticker_messages(&id, messages)?
+    };
+    if changed {
+        emit_snapshot(&app, &state)?;
     }
     Ok(())
 }
@@ -74,8 +812,9 @@ fn pick_image_source(
     state: tauri::State<AppState>,
     id: String,
 ) -> Result<bool, String> {
+    ensure_board_unlocked(&state)?;
     let selected = FileDialog::new()
-        .add_filter("Image files", &["png", "jpg", "jpeg", "gif", "webp", "bmp"])
+        .add_filter("Image files", &IMAGE_EXTENSIONS)
         .set_title("Select Image Source")
         .pick_file();
 
@@ -83,18 +822,60 @@ fn pick_image_source(
         return Ok(false);
     };
 
+    let import = {
+        let runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
+        runtime.config.as_ref().is_some_and(|config| config.global.import_images)
+    };
+    set_image_source(&app, &state, &id, &path, import)
+}
+
+/// Copies an image into the assets folder next to the active config and
+/// shows it in the editable image `id`, whatever `import_images` says.
+#[tauri::command]
+fn import_image(app: AppHandle, state: tauri::State<AppState>, id: String, path: String) -> Result<bool, String> {
+    ensure_board_unlocked(&state)?;
+    set_image_source(&app, &state, &id, Path::new(&path), true)
+}
+
+fn set_image_source(app: &AppHandle, state: &tauri::State<AppState>, id: &str, path: &Path, import: bool) -> Result<bool, String> {
+    let path = if import {
+        copy_image_to_assets(path, &assets_dir(app, state)?)?
+    } else {
+        validate_image_file(path)?;
+        path.to_path_buf()
+    };
+
     let changed = {
         let mut runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
-        runtime.set_image_source(&id, path.to_string_lossy().to_string())?
+        runtime.set_image_source(id, path.to_string_lossy().to_string())?
     };
 
     if changed {
-        emit_snapshot(&app, &state.runtime)?;
+        emit_snapshot(app, state)?;
     }
 
     Ok(changed)
 }
 
+/// The `assets` folder next to the active config file, or in the app data
+/// directory for configs loaded from text.
+fn assets_dir(app: &AppHandle, state: &AppState) -> Result<PathBuf, String> {
+    let config_dir = state
+        .active_config_path
+        .lock()
+        .map_err(|_| "Active config path lock poisoned".to_string())?
+        .as_ref()
+        .and_then(|path| path.parent().map(Path::to_path_buf));
+    let base_dir = match config_dir {
+        Some(config_dir) => config_dir,
+        None => app
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("Failed resolving app data directory: {e}"))?,
+    };
+    Ok(base_dir.join(ASSETS_DIR))
+}
+
 #[tauri::command]
 fn set_hotkeys_paused(
     app: AppHandle,
@@ -118,322 +899,2745 @@ fn set_hotkeys_paused(
     Ok(())
 }
 
-fn apply_config(app: AppHandle, state: &tauri::State<AppState>, config: config::ScoreboardConfig) -> Result<(), String> {
-    let previous_runtime = {
-        let mut runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
-        let previous = runtime.clone();
-        runtime.replace_config(config);
-        previous
-    };
+/// Turns watching the active config file on or off. While off, edits to the
+/// file are ignored until hot reload is enabled again or a config is loaded.
+#[tauri::command]
+fn set_hot_reload_enabled(app: AppHandle, state: tauri::State<AppState>, enabled: bool) -> Result<(), String> {
+    {
+        let mut guard = state
+            .hot_reload_enabled
+            .lock()
+            .map_err(|_| "Hot reload lock poisoned".to_string())?;
+        *guard = enabled;
+    }
+    restart_config_watcher(&app, &state)
+}
 
-    let paused = *state
-        .hotkeys_paused
+/// Applies a `[global.theme.<name>]` palette, or the base colors when `name` is omitted.
+#[tauri::command]
+fn set_theme(app: AppHandle, state: tauri::State<AppState>, name: Option<String>) -> Result<(), String> {
+    ensure_board_unlocked(&state)?;
+    let changed = state
+        .runtime
         .lock()
-        .map_err(|_| "Hotkey pause lock poisoned".to_string())?;
-
-    let hotkey_result = if paused {
-        unregister_hotkeys(&app, &state)
-    } else {
-        register_hotkeys(&app, &state)
-    };
-
-    if let Err(error) = hotkey_result {
-        {
-            let mut runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
-            *runtime = previous_runtime;
-        }
-        if paused {
-            let _ = unregister_hotkeys(&app, &state);
-        } else {
-            let _ = register_hotkeys(&app, &state);
-        }
-        return Err(error);
+        .map_err(|_| "Runtime lock poisoned".to_string())?
+        .set_theme(name.as_deref())?;
+    if changed {
+        emit_snapshot(&app, &state)?;
     }
-
-    emit_snapshot(&app, &state.runtime)?;
     Ok(())
 }
 
-fn resolve_config_path(path: &Path) -> Result<PathBuf, String> {
-    if path.is_absolute() {
-        return Ok(path.to_path_buf());
+/// Leaves the `[global.splash]` scene for the live board, like `keybind.ready`.
+#[tauri::command]
+fn show_live_scene(app: AppHandle, state: tauri::State<AppState>) -> Result<(), String> {
+    ensure_board_unlocked(&state)?;
+    let changed = state
+        .runtime
+        .lock()
+        .map_err(|_| "Runtime lock poisoned".to_string())?
+        .show_live_scene();
+    if changed {
+        emit_snapshot(&app, &state)?;
     }
-    let cwd = std::env::current_dir().map_err(|e| format!("Failed to get current directory: {e}"))?;
-    Ok(cwd.join(path))
+    Ok(())
 }
 
-fn configure_config_hot_reload(
-    app: &AppHandle,
-    state: &tauri::State<AppState>,
-    path: Option<PathBuf>,
-) -> Result<(), String> {
-    {
-        let mut active_path = state
-            .active_config_path
+#[tauri::command]
+fn get_timer_display_offset(state: tauri::State<AppState>) -> Result<i64, String> {
+    Ok(state
+        .runtime
+        .lock()
+        .map_err(|_| "Runtime lock poisoned".to_string())?
+        .timer_display_offset())
+}
+
+/// Adjusts how far running timers are shown ahead, without editing the config.
+#[tauri::command]
+fn set_timer_display_offset(app: AppHandle, state: tauri::State<AppState>, offset_ms: i64) -> Result<(), String> {
+    ensure_board_unlocked(&state)?;
+    let changed = state
+        .runtime
+        .lock()
+        .map_err(|_| "Runtime lock poisoned".to_string())?
+        .set_timer_display_offset(offset_ms)?;
+    if changed {
+        emit_snapshot(&app, &state)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn toggle_fullscreen(app: AppHandle) -> Result<(), String> {
+    toggle_main_window_fullscreen(&app)
+}
+
+#[tauri::command]
+fn play_sound(state: tauri::State<AppState>, path: String, cue: Option<String>) -> Result<(), String> {
+    let cue = match cue.as_deref() {
+        Some(name) => Some(SoundCue::from_name(name).ok_or_else(|| format!("Unknown sound cue '{name}'"))?),
+        None => None,
+    };
+    if path.starts_with(config::SOUND_PATTERN_PREFIX) {
+        return state.audio.play(&path, cue);
+    }
+    let resolved_path = resolve_config_path(Path::new(&path))?;
+    state.audio.play(&resolved_path.to_string_lossy(), cue)
+}
+
+#[tauri::command]
+fn trigger_action(app: AppHandle, state: tauri::State<AppState>, name: String) -> Result<(), String> {
+    let action = resolve_named_action(&state, &name)?;
+    ensure_action_allowed(&state, &action)?;
+    dispatch_action(&app, &state, &action, false)
+}
+
+fn resolve_named_action(state: &AppState, name: &str) -> Result<Action, String> {
+    state
+        .runtime
+        .lock()
+        .map_err(|_| "Runtime lock poisoned".to_string())?
+        .resolve_action(name)
+        .ok_or_else(|| format!("Unknown action '{name}'"))
+}
+
+/// Refuses named actions while the board is locked or its output frozen. Key
+/// presses are dropped silently instead, but a caller that names an action is
+/// told why nothing happened.
+fn ensure_action_allowed(state: &AppState, action: &Action) -> Result<(), String> {
+    match action {
+        Action::ToggleFullscreen => Ok(()),
+        _ => {
+            ensure_board_live(state)?;
+            ensure_board_unlocked(state)
+        }
+    }
+}
+
+/// Refuses inputs that would change the live board while a frozen copy is on
+/// screen; the live board may be what failed.
+fn ensure_board_live(state: &AppState) -> Result<(), String> {
+    if is_frozen(state) {
+        return Err("The scoreboard output is frozen; resume live output first".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn list_actions(state: tauri::State<AppState>) -> Result<Vec<String>, String> {
+    let runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
+    Ok(runtime.action_names())
+}
+
+/// Answers the remote control server with the same lookups as `trigger_action`
+/// and `list_actions`.
+fn remote_handler(app: &AppHandle) -> RemoteHandler {
+    let app = app.clone();
+    Arc::new(move |request: RemoteRequest| {
+        let state = app.state::<AppState>();
+        match request {
+            RemoteRequest::Trigger(name) => {
+                let action = resolve_named_action(&state, name).map_err(RemoteError::NotFound)?;
+                ensure_action_allowed(&state, &action).map_err(RemoteError::Locked)?;
+                dispatch_action(&app, &state, &action, false).map_err(RemoteError::Failed)?;
+                Ok(serde_json::json!({ "action": name }))
+            }
+            RemoteRequest::ListActions(role) => {
+                let runtime = state
+                    .runtime
+                    .lock()
+                    .map_err(|_| RemoteError::Failed("Runtime lock poisoned".to_string()))?;
+                let names: Vec<String> = match role {
+                    RemoteRole::Full => runtime.action_names(),
+                    RemoteRole::Timer => runtime
+                        .action_names()
+                        .into_iter()
+                        .filter(|name| runtime.resolve_action(name).is_some_and(|action| action.is_timer()))
+                        .collect(),
+                    RemoteRole::Read => Vec::new(),
+                };
+                Ok(serde_json::json!(names))
+            }
+            RemoteRequest::Data => {
+                // While frozen, readers see the board that is on screen, not
+                // the live one that failed.
+                let frozen = state
+                    .freeze
+                    .lock()
+                    .map_err(|_| RemoteError::Failed("Freeze lock poisoned".to_string()))?
+                    .snapshot();
+                let snapshot = match frozen {
+                    Some(snapshot) => snapshot,
+                    None => state
+                        .runtime
+                        .lock()
+                        .map_err(|_| RemoteError::Failed("Runtime lock poisoned".to_string()))?
+                        .snapshot(),
+                };
+                let values: serde_json::Map<String, serde_json::Value> = snapshot
+                    .components
+                    .into_iter()
+                    .filter_map(|component| {
+                        let value = component.text.or(component.source)?;
+                        Some((component.id, serde_json::Value::String(value)))
+                    })
+                    .collect();
+                Ok(serde_json::Value::Object(values))
+            }
+        }
+    })
+}
+
+/// Applies values read from a hardware console. They go in even while the
+/// board is locked, since the console is the official record of the game,
+/// but not while its output is frozen; resuming applies every field again.
+fn console_sink(app: &AppHandle) -> ConsoleSink {
+    let app = app.clone();
+    Arc::new(move |values: Vec<(String, String)>| {
+        let state = app.state::<AppState>();
+        if is_frozen(&state) {
+            return;
+        }
+        let (changed, errors) = {
+            let Ok(mut runtime) = state.runtime.lock() else {
+                return;
+            };
+            let mut changed = false;
+            let mut errors = Vec::new();
+            for (id, text) in &values {
+                match runtime.set_console_value(id, text) {
+                    Ok(value_changed) => changed |= value_changed,
+                    Err(e) => errors.push(e),
+                }
+            }
+            (changed, errors)
+        };
+        for e in errors {
+            emit_error(&app, &e);
+        }
+        if changed {
+            wake_from_idle(&app, &state);
+            let _ = emit_snapshot(&app, &state);
+        }
+    })
+}
+
+/// Fires actions named by incoming OSC messages, as `trigger_action` does,
+/// limited by the sending host's role like a remote token.
+fn osc_handler(app: &AppHandle) -> OscHandler {
+    let app = app.clone();
+    Arc::new(move |name: &str, role: RemoteRole| {
+        let state = app.state::<AppState>();
+        let allowed = match role {
+            RemoteRole::Full => true,
+            RemoteRole::Timer => state
+                .runtime
+                .lock()
+                .map_err(|_| "Runtime lock poisoned".to_string())?
+                .resolve_action(name)
+                .is_some_and(|action| action.is_timer()),
+            RemoteRole::Read => false,
+        };
+        if !allowed {
+            return Err(format!("this host's '{}' role cannot fire '{name}'", role.as_str()));
+        }
+        trigger_action(app.clone(), state, name.to_string())
+    })
+}
+
+/// Draws boards mirrored from the sync primary, numbered in this window's
+/// own sequence so the frontend's ordering still holds.
+fn sync_sink(app: &AppHandle) -> SnapshotSink {
+    let app = app.clone();
+    Arc::new(move |mut snapshot: serde_json::Value| {
+        let state = app.state::<AppState>();
+        let mut clock = state.snapshot_clock.lock().unwrap_or_else(PoisonError::into_inner);
+        let (sequence, timestamp_ms) = clock.next_stamp();
+        snapshot["sequence"] = sequence.into();
+        snapshot["timestamp_ms"] = timestamp_ms.into();
+        let _ = app.emit(EVENT_STATE_UPDATED, snapshot);
+    })
+}
+
+#[tauri::command]
+fn list_audio_devices() -> Result<Vec<AudioDevice>, String> {
+    audio::list_devices()
+}
+
+#[tauri::command]
+fn list_gamepads(state: tauri::State<AppState>) -> Result<Vec<GamepadInfo>, String> {
+    let gamepads = state.gamepads.lock().map_err(|_| "Gamepad list lock poisoned".to_string())?;
+    Ok(gamepads.clone())
+}
+
+#[tauri::command]
+fn get_gamepad_status(state: tauri::State<AppState>) -> Result<GamepadStatus, String> {
+    let status = state
+        .gamepad_status
+        .lock()
+        .map_err(|_| "Gamepad status lock poisoned".to_string())?;
+    Ok(status.clone())
+}
+
+#[tauri::command]
+fn get_last_session(state: tauri::State<AppState>) -> Result<Option<SessionInfo>, String> {
+    let session = state
+        .last_session
+        .lock()
+        .map_err(|_| "Session lock poisoned".to_string())?;
+    Ok(session.as_ref().map(|session| session.info()))
+}
+
+#[tauri::command]
+async fn restore_last_session(app: AppHandle, state: tauri::State<'_, AppState>) -> Result<bool, String> {
+    ensure_board_unlocked(&state)?;
+    let session = {
+        let guard = state
+            .last_session
+            .lock()
+            .map_err(|_| "Session lock poisoned".to_string())?;
+        guard.clone()
+    };
+    let Some(session) = session else {
+        return Ok(false);
+    };
+
+    if let Some(config_path) = &session.config_path {
+        let active_path = state
+            .active_config_path
+            .lock()
+            .map_err(|_| "Active config path lock poisoned".to_string())?
+            .clone();
+        if active_path.as_deref() != Some(Path::new(config_path)) {
+            let resolved_path = resolve_config_path(Path::new(config_path))?;
+            wait_for_config_job(&state, ConfigJob::File(resolved_path)).await?;
+        }
+    }
+
+    {
+        let mut runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
+        if runtime.config.is_none() {
+            return Err("No config loaded to restore the session into".to_string());
+        }
+        runtime.restore_values(&session.values);
+    }
+    emit_snapshot(&app, &state)?;
+    Ok(true)
+}
+
+#[tauri::command]
+fn start_recording(app: AppHandle, state: tauri::State<AppState>, path: Option<String>) -> Result<String, String> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let app_data_dir = app
+                .path()
+                .app_data_dir()
+                .map_err(|e| format!("Failed resolving app data directory: {e}"))?;
+            default_recording_path(&app_data_dir)
+        }
+    };
+    state
+        .recorder
+        .lock()
+        .map_err(|_| "Recorder lock poisoned".to_string())?
+        .start(&path)?;
+    // Capture the current screen as the first frame.
+    if state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?.config.is_some() {
+        emit_snapshot(&app, &state)?;
+    }
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+fn stop_recording(state: tauri::State<AppState>) -> Result<Option<String>, String> {
+    let path = state
+        .recorder
+        .lock()
+        .map_err(|_| "Recorder lock poisoned".to_string())?
+        .stop()?;
+    Ok(path.map(|path| path.to_string_lossy().to_string()))
+}
+
+/// Starts appending plain-sentence descriptions of score changes to a text
+/// file. Returns the path being written.
+#[tauri::command]
+fn start_narration(app: AppHandle, state: tauri::State<AppState>, path: Option<String>) -> Result<String, String> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let app_data_dir = app
+                .path()
+                .app_data_dir()
+                .map_err(|e| format!("Failed resolving app data directory: {e}"))?;
+            default_narration_path(&app_data_dir)
+        }
+    };
+    state
+        .narrator
+        .lock()
+        .map_err(|_| "Narrator lock poisoned".to_string())?
+        .start(&path)?;
+    // Opens the narration with a summary of the current score.
+    if state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?.config.is_some() {
+        emit_snapshot(&app, &state)?;
+    }
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+fn stop_narration(state: tauri::State<AppState>) -> Result<Option<String>, String> {
+    let path = state
+        .narrator
+        .lock()
+        .map_err(|_| "Narrator lock poisoned".to_string())?
+        .stop()?;
+    Ok(path.map(|path| path.to_string_lossy().to_string()))
+}
+
+/// Queues a one-off announcement on the configured target label. It shows on
+/// the next scheduler tick unless something with a higher priority is showing.
+#[tauri::command]
+fn queue_announcement(
+    state: tauri::State<AppState>,
+    text: String,
+    sound: Option<String>,
+    priority: Option<i32>,
+    duration_ms: Option<u64>,
+) -> Result<(), String> {
+    ensure_board_unlocked(&state)?;
+    if duration_ms == Some(0) {
+        return Err("Announcement duration_ms must be > 0".to_string());
+    }
+    let mut announcements = state
+        .announcements
+        .lock()
+        .map_err(|_| "Announcement lock poisoned".to_string())?;
+    let duration = duration_ms
+        .map(Duration::from_millis)
+        .unwrap_or(announcements.default_duration());
+    announcements.enqueue(Announcement {
+        text,
+        sound,
+        priority: priority.unwrap_or(0),
+        duration,
+    })
+}
+
+#[tauri::command]
+fn trigger_announcement(state: tauri::State<AppState>, name: String) -> Result<(), String> {
+    ensure_board_unlocked(&state)?;
+    state
+        .announcements
+        .lock()
+        .map_err(|_| "Announcement lock poisoned".to_string())?
+        .enqueue_message(&name)
+}
+
+#[tauri::command]
+fn clear_announcements(app: AppHandle, state: tauri::State<AppState>) -> Result<(), String> {
+    ensure_board_unlocked(&state)?;
+    let target = state
+        .announcements
+        .lock()
+        .map_err(|_| "Announcement lock poisoned".to_string())?
+        .clear();
+    if let Some(target) = target {
+        apply_announcement_update(&app, &state, AnnouncementUpdate::Restore { target });
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn list_aux_timers(state: tauri::State<AppState>) -> Result<Vec<AuxTimerStatus>, String> {
+    let aux_timers = state.aux_timers.lock().map_err(|_| "Aux timer lock poisoned".to_string())?;
+    Ok(aux_timers.list(Instant::now()))
+}
+
+#[tauri::command]
+fn start_aux_timer(app: AppHandle, state: tauri::State<AppState>, name: String) -> Result<(), String> {
+    ensure_board_unlocked(&state)?;
+    update_aux_timer(&app, &state, |aux_timers| aux_timers.start(&name, Instant::now()))
+}
+
+#[tauri::command]
+fn stop_aux_timer(app: AppHandle, state: tauri::State<AppState>, name: String) -> Result<(), String> {
+    ensure_board_unlocked(&state)?;
+    update_aux_timer(&app, &state, |aux_timers| aux_timers.stop(&name, Instant::now()))
+}
+
+#[tauri::command]
+fn reset_aux_timer(app: AppHandle, state: tauri::State<AppState>, name: String) -> Result<(), String> {
+    ensure_board_unlocked(&state)?;
+    update_aux_timer(&app, &state, |aux_timers| aux_timers.reset(&name))
+}
+
+fn update_aux_timer(
+    app: &AppHandle,
+    state: &AppState,
+    update: impl FnOnce(&mut AuxTimers) -> Result<bool, String>,
+) -> Result<(), String> {
+    let changed = {
+        let mut aux_timers = state.aux_timers.lock().map_err(|_| "Aux timer lock poisoned".to_string())?;
+        update(&mut aux_timers)?
+    };
+    if changed {
+        emit_aux_timers(app, state);
+    }
+    Ok(())
+}
+
+fn emit_aux_timers(app: &AppHandle, state: &AppState) {
+    if let Ok(aux_timers) = state.aux_timers.lock() {
+        let _ = app.emit(EVENT_AUX_TIMERS, aux_timers.list(Instant::now()));
+    }
+}
+
+#[tauri::command]
+fn get_playlist_status(state: tauri::State<AppState>) -> PlaylistStatus {
+    state.playlist.status()
+}
+
+#[tauri::command]
+fn playlist_play(state: tauri::State<AppState>) -> Result<(), String> {
+    ensure_board_unlocked(&state)?;
+    state.playlist.play()
+}
+
+#[tauri::command]
+fn playlist_pause(state: tauri::State<AppState>) -> Result<(), String> {
+    ensure_board_unlocked(&state)?;
+    state.playlist.pause()
+}
+
+#[tauri::command]
+fn playlist_next(state: tauri::State<AppState>) -> Result<(), String> {
+    ensure_board_unlocked(&state)?;
+    state.playlist.next()
+}
+
+/// Passes on what the music thread reported: errors, and a status change
+/// that also updates the `now_playing` label.
+fn apply_playlist_update(app: &AppHandle, state: &AppState) {
+    let (status, errors) = state.playlist.take_update();
+    for e in errors {
+        emit_error(app, &e);
+    }
+    let Some(status) = status else {
+        return;
+    };
+    let _ = app.emit(EVENT_PLAYLIST, &status);
+    let changed = match state.runtime.lock() {
+        Ok(mut runtime) => {
+            let target = runtime
+                .config
+                .as_ref()
+                .and_then(|config| config.global.playlist.as_ref()?.now_playing.clone());
+            let text = status.track.filter(|_| status.playing);
+            target.is_some_and(|target| runtime.set_label_override(&target, text))
+        }
+        Err(_) => return,
+    };
+    if changed {
+        let _ = emit_snapshot(app, state);
+    }
+}
+
+#[tauri::command]
+fn export_recording_csv(input: String, output: String) -> Result<(), String> {
+    convert_recording_to_csv(Path::new(&input), Path::new(&output))
+}
+
+#[tauri::command]
+fn get_health(state: tauri::State<AppState>) -> Result<HealthReport, String> {
+    let watchdog = state.watchdog.lock().map_err(|_| "Watchdog lock poisoned".to_string())?;
+    Ok(watchdog.report())
+}
+
+#[tauri::command]
+fn get_freeze_status(state: tauri::State<AppState>) -> Result<FreezeStatus, String> {
+    Ok(state.freeze.lock().map_err(|_| "Freeze lock poisoned".to_string())?.status())
+}
+
+#[tauri::command]
+fn get_font_choices(state: tauri::State<AppState>) -> Result<Vec<FontChoice>, String> {
+    Ok(state
+        .font_choices
+        .lock()
+        .map_err(|_| "Font choices lock poisoned".to_string())?
+        .clone())
+}
+
+#[tauri::command]
+fn get_bundled_fonts(state: tauri::State<AppState>) -> Result<Vec<BundledFont>, String> {
+    Ok(state
+        .bundled_fonts
+        .lock()
+        .map_err(|_| "Bundled fonts lock poisoned".to_string())?
+        .clone())
+}
+
+/// Lists the remote and sync endpoints scoreboards announce on the network,
+/// listening for `timeout_ms` (2 seconds by default, at most 10).
+#[tauri::command]
+async fn discover_instances(timeout_ms: Option<u64>) -> Result<Vec<DiscoveredInstance>, String> {
+    let timeout = timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_DISCOVERY_TIMEOUT)
+        .min(MAX_DISCOVERY_TIMEOUT);
+    tauri::async_runtime::spawn_blocking(move || discover(timeout))
+        .await
+        .map_err(|e| format!("Discovery failed: {e}"))?
+}
+
+/// Removes unpacked packs and downloaded images the loaded config does not
+/// use, returning what was freed.
+#[tauri::command]
+fn clear_asset_cache(state: tauri::State<AppState>) -> Result<CacheUsage, String> {
+    Ok(state
+        .asset_cache
+        .lock()
+        .map_err(|_| "Asset cache lock poisoned".to_string())?
+        .clear())
+}
+
+#[tauri::command]
+fn get_asset_cache_usage(state: tauri::State<AppState>) -> Result<CacheUsage, String> {
+    Ok(state
+        .asset_cache
+        .lock()
+        .map_err(|_| "Asset cache lock poisoned".to_string())?
+        .usage())
+}
+
+/// Leaves freeze mode and shows the live board again. If the live board
+/// still cannot be shown, the board freezes again and the error is returned.
+#[tauri::command]
+fn resume_live_output(app: AppHandle, state: tauri::State<AppState>) -> Result<(), String> {
+    let resumed = state.freeze.lock().map_err(|_| "Freeze lock poisoned".to_string())?.resume();
+    if !resumed {
+        return Ok(());
+    }
+    if let Ok(serial_input) = state.serial_input.lock() {
+        serial_input.resend();
+    }
+    emit_freeze_status(&app, &state);
+    emit_snapshot(&app, &state)
+}
+
+/// The active config's `[global.notes]`, for the operator console.
+#[tauri::command]
+fn get_operator_notes(state: tauri::State<AppState>) -> Result<Option<config::OperatorNotes>, String> {
+    let runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
+    Ok(runtime.config.as_ref().and_then(|config| config.global.notes.clone()))
+}
+
+#[tauri::command]
+fn get_app_status(state: tauri::State<AppState>) -> Result<AppStatus, String> {
+    app_status(&state)
+}
+
+fn app_status(state: &AppState) -> Result<AppStatus, String> {
+    let hotkeys_paused = *state
+        .hotkeys_paused
+        .lock()
+        .map_err(|_| "Hotkey pause lock poisoned".to_string())?;
+    let config_path = state
+        .active_config_path
+        .lock()
+        .map_err(|_| "Active config path lock poisoned".to_string())?
+        .as_ref()
+        .map(|path| path.to_string_lossy().to_string());
+    let hot_reload_enabled = *state
+        .hot_reload_enabled
+        .lock()
+        .map_err(|_| "Hot reload lock poisoned".to_string())?;
+    let watching_config = state
+        .config_watcher
+        .lock()
+        .map_err(|_| "Config watcher lock poisoned".to_string())?
+        .is_some();
+    let watcher_error = state
+        .config_watcher_error
+        .lock()
+        .map_err(|_| "Config watcher lock poisoned".to_string())?
+        .clone();
+    let gamepads = state
+        .gamepads
+        .lock()
+        .map_err(|_| "Gamepad lock poisoned".to_string())?
+        .clone();
+    Ok(AppStatus {
+        hotkeys_paused,
+        config_path,
+        hot_reload_enabled,
+        watching_config,
+        watcher_error,
+        gamepads,
+        remote_clients: state.remote_clients.count(),
+        idle: state.idle.is_idle(),
+        sync: state.sync.status(),
+    })
+}
+
+#[tauri::command]
+fn get_input_capabilities(state: tauri::State<AppState>) -> Result<InputCapabilities, String> {
+    let capabilities = state
+        .input_capabilities
+        .lock()
+        .map_err(|_| "Input capabilities lock poisoned".to_string())?;
+    Ok(capabilities.clone())
+}
+
+#[tauri::command]
+fn handle_focused_key(app: AppHandle, state: tauri::State<AppState>, shortcut: String) -> Result<(), String> {
+    let global_shortcuts = state
+        .input_capabilities
+        .lock()
+        .map_err(|_| "Input capabilities lock poisoned".to_string())?
+        .global_shortcuts;
+    let Ok(shortcut) = Shortcut::from_str(&shortcut) else {
+        return Ok(());
+    };
+    let shortcut = shortcut.to_string();
+    // Global shortcuts already fire while focused; only the fallback path uses
+    // this, except that the input test also lists keys nothing is bound to.
+    if global_shortcuts {
+        let bound = state
+            .action_by_shortcut
+            .lock()
+            .map_err(|_| "Shortcut map lock poisoned".to_string())?
+            .contains_key(&shortcut);
+        if !bound {
+            report_test_input(&app, &state, "keyboard", &shortcut, None);
+        }
+        return Ok(());
+    }
+
+    handle_shortcut(&app, shortcut);
+    Ok(())
+}
+
+/// Ends the repeat of a held key on the fallback path; the window reports
+/// releases for every key it reported a press for.
+#[tauri::command]
+fn handle_focused_key_release(app: AppHandle, shortcut: String) -> Result<(), String> {
+    let Ok(shortcut) = Shortcut::from_str(&shortcut) else {
+        return Ok(());
+    };
+    release_input(&app, &shortcut.to_string());
+    Ok(())
+}
+
+/// Starts reporting key and button presses on `scoreboard://input-test`
+/// without applying them, so a console mapping can be checked safely.
+#[tauri::command]
+fn start_input_test(app: AppHandle, state: tauri::State<AppState>) -> Result<(), String> {
+    set_input_test(&app, &state, true)
+}
+
+#[tauri::command]
+fn stop_input_test(app: AppHandle, state: tauri::State<AppState>) -> Result<(), String> {
+    set_input_test(&app, &state, false)
+}
+
+fn set_input_test(app: &AppHandle, state: &AppState, active: bool) -> Result<(), String> {
+    let mut input_test = state
+        .input_test
+        .lock()
+        .map_err(|_| "Input test lock poisoned".to_string())?;
+    if *input_test != active {
+        *input_test = active;
+        let _ = app.emit(EVENT_INPUT_TEST_STATUS, InputTestStatus { active });
+    }
+    Ok(())
+}
+
+/// Reports a press while the input test runs. Returns false when no test is
+/// running and the press should be handled as usual.
+fn report_test_input(
+    app: &AppHandle,
+    state: &AppState,
+    source: &'static str,
+    input: &str,
+    binding: Option<&HotkeyBinding>,
+) -> bool {
+    let active = state.input_test.lock().map(|input_test| *input_test).unwrap_or(false);
+    if active {
+        let _ = app.emit(
+            EVENT_INPUT_TEST,
+            TestedInput {
+                source,
+                input: input.to_string(),
+                action: binding.map(|binding| binding.action.describe()),
+            },
+        );
+    }
+    active
+}
+
+/// Installs a config. With `preserve_values`, components that survive with the
+/// same id and type keep their current values (used for hot reload). Otherwise
+/// a config loaded from `persistent_scope` restores the `persistent` component
+/// values last saved for that path.
+fn apply_config(
+    app: AppHandle,
+    state: &tauri::State<AppState>,
+    mut config: config::ScoreboardConfig,
+    preserve_values: bool,
+    persistent_scope: Option<&Path>,
+) -> Result<(), String> {
+    // Nothing outside the runtime changes until the hotkeys for the new config
+    // are registered, so a config that is rolled back leaves no trace.
+    let cache_entries = state
+        .asset_cache
+        .lock()
+        .map_err(|_| "Asset cache lock poisoned".to_string())?
+        .prepare(&mut config)?;
+    let bundled_fonts = bundled_fonts(config.base_dir());
+    let font_choices = choose_font_families(&mut config, &bundled_fonts);
+    let announcement = config.global.announcement.clone();
+    let aux_timers = config.global.aux_timers.clone();
+    let horn_patterns = config.global.horn_patterns.clone();
+    let audio_routing = config.global.audio.clone();
+    let playlist = config.global.playlist.clone();
+    let remote = config.global.remote.clone();
+    let osc = config.global.osc.clone();
+    let serial_input = config.global.serial_input.clone();
+    let obs = config.global.obs.clone();
+    let sync = config.global.sync.clone();
+    let base_dir = config.base_dir().to_path_buf();
+    let idle = config.global.idle.clone();
+    let period_labels: HashMap<String, Vec<String>> = config
+        .components
+        .iter()
+        .filter_map(|component| match &component.kind {
+            config::ComponentKind::Period { labels, .. } => Some((component.id.clone(), labels.clone())),
+            _ => None,
+        })
+        .collect();
+    let led_matrix = config.global.led_matrix.clone();
+    let webhooks = config.global.webhooks.clone();
+    let history = history_limits(&config);
+    let summary = config.global.summary.clone();
+    let fixtures = config.global.fixtures.clone();
+    let gamepad_settings = config.gamepad;
+    // Read and compiled up front, so a broken script keeps the config from loading.
+    let script = config.global.script.as_ref().map(ScriptHost::load).transpose()?;
+    let load_line = format!(
+        "{} config with {} components{}",
+        if preserve_values { "reloaded" } else { "loaded" },
+        config.components.len(),
+        persistent_scope.map(|path| format!(" from {}", path.display())).unwrap_or_default()
+    );
+    let player_action_names = config
+        .global
+        .player_actions
+        .iter()
+        .map(|player_action| player_action.name.clone())
+        .collect();
+    // A board cannot stay locked once no config defines the PIN to unlock it.
+    let has_lock = config.global.lock.is_some();
+    let freeze_on_error = config.global.freeze_on_error;
+    let persistent_scope = persistent_scope.map(|path| path.to_string_lossy().to_string());
+    let saved = match &persistent_scope {
+        Some(scope) => state
+            .persistent_store
+            .lock()
+            .map_err(|_| "Persistent store lock poisoned".to_string())?
+            .configs
+            .get(scope)
+            .cloned(),
+        None => None,
+    };
+    let previous_runtime = {
+        let mut runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
+        let previous = runtime.clone();
+        if preserve_values {
+            runtime.reload_config(config);
+        } else {
+            runtime.replace_config(config);
+            if let Some(scope) = persistent_scope {
+                runtime.restore_persistent_values(scope, saved.as_ref());
+            }
+        }
+        previous
+    };
+
+    let paused = *state
+        .hotkeys_paused
+        .lock()
+        .map_err(|_| "Hotkey pause lock poisoned".to_string())?;
+
+    let hotkey_result = if paused {
+        unregister_hotkeys(&app, &state)
+    } else {
+        register_hotkeys(&app, &state)
+    };
+
+    if let Err(error) = hotkey_result {
+        {
+            let mut runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
+            *runtime = previous_runtime;
+        }
+        if paused {
+            let _ = unregister_hotkeys(&app, &state);
+        } else {
+            let _ = register_hotkeys(&app, &state);
+        }
+        return Err(error);
+    }
+
+    if let Ok(mut cache) = state.asset_cache.lock() {
+        cache.mark_in_use(cache_entries);
+    }
+    {
+        let runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
+        if let Some(config) = runtime.config.as_ref() {
+            // Like a taken remote port, a network without multicast should not keep the config from loading.
+            if let Ok(mut advertiser) = state.advertiser.lock() {
+                if let Err(e) = advertiser.configure(&config.global) {
+                    emit_error(&app, &e);
+                }
+            }
+            if let Ok(mut reporter) = state.crash_reporter.lock() {
+                reporter.configure(config);
+                reporter.log(load_line);
+            }
+        }
+    }
+    if let Ok(mut freeze) = state.freeze.lock() {
+        freeze.configure(freeze_on_error);
+    }
+    if !has_lock {
+        let released = state
+            .board_lock
+            .lock()
+            .map_err(|_| "Board lock poisoned".to_string())?
+            .release();
+        if released {
+            let _ = app.emit(EVENT_LOCK_STATUS, LockStatus { locked: false });
+        }
+    }
+    *state.scripts.lock().map_err(|_| "Script lock poisoned".to_string())? = script;
+    // Relay ports reopen on the next pulse with the new port settings.
+    state.relays.release_ports();
+    let error_app = app.clone();
+    state.led_matrix.configure(
+        led_matrix,
+        Arc::new(move |message: String| emit_error(&error_app, &message)),
+    );
+    // The config is installed by now, so a subsystem that fails to start is
+    // reported and the rest are still configured.
+    match app.path().app_data_dir() {
+        Ok(data_dir) => {
+            let error_app = app.clone();
+            state.webhooks.configure(
+                webhooks,
+                data_dir.join("webhooks"),
+                Arc::new(move |message: String| emit_error(&error_app, &message)),
+            );
+        }
+        Err(e) => emit_error(&app, &format!("Failed resolving app data directory: {e}")),
+    }
+    state
+        .announcements
+        .lock()
+        .map_err(|_| "Announcement lock poisoned".to_string())?
+        .configure(announcement.as_ref(), Instant::now());
+    state
+        .aux_timers
+        .lock()
+        .map_err(|_| "Aux timer lock poisoned".to_string())?
+        .configure(&aux_timers);
+    emit_aux_timers(&app, state);
+    state.audio.set_patterns(horn_patterns);
+    let music_device = audio_routing
+        .as_ref()
+        .and_then(|routing| routing.device_for(Some(SoundCue::Music.name())))
+        .map(str::to_string);
+    if let Err(e) = state.playlist.configure(playlist, music_device) {
+        emit_error(&app, &e);
+    }
+    state.audio.set_routing(audio_routing);
+    // A taken port should not keep the config from loading.
+    if let Err(e) = state
+        .remote
+        .lock()
+        .map_err(|_| "Remote control lock poisoned".to_string())?
+        .configure(remote.as_ref(), remote_handler(&app))
+    {
+        emit_error(&app, &e);
+    }
+    let error_app = app.clone();
+    state.osc.configure(
+        osc,
+        osc_handler(&app),
+        Arc::new(move |message: String| emit_error(&error_app, &message)),
+    );
+    let error_app = app.clone();
+    state
+        .serial_input
+        .lock()
+        .map_err(|_| "Serial input lock poisoned".to_string())?
+        .configure(
+            serial_input,
+            console_sink(&app),
+            Arc::new(move |message: String| emit_error(&error_app, &message)),
+        );
+    let error_app = app.clone();
+    state.obs.configure(
+        obs,
+        period_labels,
+        Arc::new(move |message: String| emit_error(&error_app, &message)),
+    );
+    let error_app = app.clone();
+    if let Err(e) = state.sync.configure(
+        sync,
+        Some(base_dir),
+        sync_sink(&app),
+        Arc::new(move |message: String| emit_error(&error_app, &message)),
+    ) {
+        emit_error(&app, &e);
+    }
+    state.idle.configure(idle);
+    state
+        .number_history
+        .lock()
+        .map_err(|_| "Number history lock poisoned".to_string())?
+        .configure(history, preserve_values);
+    state
+        .game_log
+        .lock()
+        .map_err(|_| "Game log lock poisoned".to_string())?
+        .configure(summary, player_action_names, preserve_values);
+    state
+        .fixtures
+        .lock()
+        .map_err(|_| "Fixture scheduler lock poisoned".to_string())?
+        .configure(fixtures);
+    *state
+        .gamepad_settings
+        .lock()
+        .map_err(|_| "Gamepad settings lock poisoned".to_string())? = gamepad_settings;
+    for choice in font_choices.iter().filter(|choice| !choice.installed) {
+        let owner = choice.component.as_deref().unwrap_or("global");
+        emit_error(
+            &app,
+            &format!(
+                "None of the fonts '{}' for '{owner}' is installed; using '{}'",
+                choice.families.join("', '"),
+                choice.chosen
+            ),
+        );
+    }
+    *state
+        .font_choices
+        .lock()
+        .map_err(|_| "Font choices lock poisoned".to_string())? = font_choices.clone();
+    let _ = app.emit(EVENT_FONTS, font_choices);
+    *state
+        .bundled_fonts
+        .lock()
+        .map_err(|_| "Bundled fonts lock poisoned".to_string())? = bundled_fonts.clone();
+    let _ = app.emit(EVENT_BUNDLED_FONTS, bundled_fonts);
+    emit_snapshot(&app, state)?;
+    Ok(())
+}
+
+fn setup_crash_reporter(app: &AppHandle) {
+    let state: tauri::State<AppState> = app.state();
+    if let (Ok(data_dir), Ok(mut reporter)) = (app.path().app_data_dir(), state.crash_reporter.lock()) {
+        reporter.set_report_dir(data_dir.join("crash-reports"));
+    }
+    install_panic_hook(state.crash_reporter.clone());
+}
+
+fn setup_asset_cache(app: &AppHandle) {
+    let Ok(data_dir) = app.path().app_data_dir() else {
+        return;
+    };
+    if let Ok(mut cache) = app.state::<AppState>().asset_cache.lock() {
+        cache.set_dir(data_dir.join(ASSET_CACHE_DIR));
+    };
+}
+
+fn resolve_config_path(path: &Path) -> Result<PathBuf, String> {
+    if path.is_absolute() {
+        return Ok(path.to_path_buf());
+    }
+    let cwd = std::env::current_dir().map_err(|e| format!("Failed to get current directory: {e}"))?;
+    Ok(cwd.join(path))
+}
+
+fn configure_config_hot_reload(
+    app: &AppHandle,
+    state: &tauri::State<AppState>,
+    path: Option<PathBuf>,
+) -> Result<(), String> {
+    {
+        let mut active_path = state
+            .active_config_path
+            .lock()
+            .map_err(|_| "Active config path lock poisoned".to_string())?;
+        *active_path = path;
+    }
+    restart_config_watcher(app, state)
+}
+
+/// Starts watching the active config, or stops if there is none or hot reload is off.
+///
+/// The parent directory is watched rather than the file, because editors that
+/// save by writing a temp file and renaming it over the config replace the
+/// watched file. Events are debounced so one save reloads once.
+fn restart_config_watcher(app: &AppHandle, state: &tauri::State<AppState>) -> Result<(), String> {
+    let mut watcher_slot = state
+        .config_watcher
+        .lock()
+        .map_err(|_| "Config watcher lock poisoned".to_string())?;
+    *watcher_slot = None;
+    if let Ok(mut error) = state.config_watcher_error.lock() {
+        *error = None;
+    }
+
+    let enabled = *state
+        .hot_reload_enabled
+        .lock()
+        .map_err(|_| "Hot reload lock poisoned".to_string())?;
+    let path = state
+        .active_config_path
+        .lock()
+        .map_err(|_| "Active config path lock poisoned".to_string())?
+        .clone();
+    let Some(path) = path.filter(|_| enabled) else {
+        return Ok(());
+    };
+    let Some(directory) = path.parent().map(Path::to_path_buf) else {
+        return Err(format!("Cannot watch config {}: it has no parent directory", path.display()));
+    };
+
+    // The debounce thread exits once the watcher, which owns the sender, is dropped.
+    let (changes, pending) = mpsc::channel::<()>();
+    let app_handle = app.clone();
+    thread::spawn(move || debounce_config_reloads(&app_handle, &pending));
+
+    let app_handle = app.clone();
+    let watched_path = path.clone();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<Event>| match result {
+        Ok(event) => {
+            if is_hot_reload_event(&event) && event.paths.iter().any(|changed| changed == &watched_path) {
+                let _ = changes.send(());
+            }
+        }
+        Err(e) => {
+            let message = format!("Config watcher error: {e}");
+            if let Some(state) = app_handle.try_state::<AppState>() {
+                if let Ok(mut error) = state.config_watcher_error.lock() {
+                    *error = Some(message.clone());
+                }
+            }
+            emit_error(&app_handle, &message);
+        }
+    })
+    .map_err(|e| record_watcher_error(state, format!("Failed to start config watcher: {e}")))?;
+
+    watcher
+        .watch(&directory, RecursiveMode::NonRecursive)
+        .map_err(|e| record_watcher_error(state, format!("Failed to watch config {}: {e}", path.display())))?;
+
+    *watcher_slot = Some(watcher);
+    Ok(())
+}
+
+/// Keeps a watcher failure for the status bar and passes it on.
+fn record_watcher_error(state: &AppState, message: String) -> String {
+    if let Ok(mut error) = state.config_watcher_error.lock() {
+        *error = Some(message.clone());
+    }
+    message
+}
+
+/// Reloads once the config has been quiet for `CONFIG_RELOAD_DEBOUNCE`.
+fn debounce_config_reloads(app: &AppHandle, pending: &mpsc::Receiver<()>) {
+    while pending.recv().is_ok() {
+        loop {
+            match pending.recv_timeout(CONFIG_RELOAD_DEBOUNCE) {
+                Ok(()) => continue,
+                Err(mpsc::RecvTimeoutError::Timeout) => break,
+                Err(mpsc::RecvTimeoutError::Disconnected) => return,
+            }
+        }
+        // Errors are reported by the config thread.
+        if let Some(state) = app.try_state::<AppState>() {
+            let _ = state.config_queue.submit(ConfigJob::Reload);
+        }
+    }
+}
+
+fn is_hot_reload_event(event: &Event) -> bool {
+    matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Any
+    )
+}
+
+fn reload_active_config(app: &AppHandle) -> Result<(), String> {
+    let Some(state) = app.try_state::<AppState>() else {
+        return Ok(());
+    };
+
+    let path = {
+        let guard = state
+            .active_config_path
             .lock()
             .map_err(|_| "Active config path lock poisoned".to_string())?;
-        *active_path = path.clone();
+        guard.clone()
+    };
+
+    let Some(path) = path else {
+        return Ok(());
+    };
+
+    let mut config = load_config_file(app, &path)?;
+    // A layout picked at runtime survives the reload while the file still defines it.
+    let active_layout = state
+        .runtime
+        .lock()
+        .map_err(|_| "Runtime lock poisoned".to_string())?
+        .config
+        .as_ref()
+        .and_then(|config| config.layout.clone());
+    let still_defined = active_layout
+        .as_ref()
+        .is_none_or(|name| config.layouts.contains(name));
+    if still_defined && active_layout != config.layout {
+        config = config.with_layout(active_layout.as_deref())?;
+    }
+    apply_config(app.clone(), &state, config, true, None)?;
+    app.emit(EVENT_CONFIG_RELOADED, path.to_string_lossy().to_string())
+        .map_err(|e| format!("Failed to emit config reload: {e}"))
+}
+
+/// Handles `validate` and `dump-snapshot` without opening a window; see [`cli::run`].
+pub fn run_cli(args: &[String]) -> Option<i32> {
+    cli::run(args)
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    let remote_clients = Arc::new(RecentClients::default());
+    tauri::Builder::default()
+        .manage(AppState {
+            runtime: Arc::new(Mutex::new(RuntimeState::new())),
+            action_by_shortcut: Arc::new(Mutex::new(HashMap::new())),
+            action_by_gamepad: Arc::new(Mutex::new(HashMap::new())),
+            cooldowns: Arc::new(Mutex::new(Cooldowns::new())),
+            held_inputs: Arc::new(Mutex::new(HeldInputs::new())),
+            hotkeys_paused: Arc::new(Mutex::new(false)),
+            input_test: Arc::new(Mutex::new(false)),
+            active_config_path: Arc::new(Mutex::new(None)),
+            config_watcher: Arc::new(Mutex::new(None)),
+            config_watcher_error: Arc::new(Mutex::new(None)),
+            config_queue: Arc::new(ConfigQueue::new()),
+            hot_reload_enabled: Arc::new(Mutex::new(true)),
+            watchdog: Arc::new(Mutex::new(Watchdog::new())),
+            freeze: Arc::new(Mutex::new(FreezeMode::new())),
+            font_choices: Arc::new(Mutex::new(Vec::new())),
+            bundled_fonts: Arc::new(Mutex::new(Vec::new())),
+            input_capabilities: Arc::new(Mutex::new(InputCapabilities::global())),
+            audio: Arc::new(AudioPlayer::new()),
+            relays: Arc::new(RelayController::new()),
+            led_matrix: Arc::new(LedMatrixOutput::new()),
+            webhooks: Arc::new(WebhookDispatcher::new()),
+            osc: Arc::new(OscBridge::new(Arc::clone(&remote_clients))),
+            serial_input: Arc::new(Mutex::new(SerialInput::new())),
+            obs: Arc::new(ObsClient::new()),
+            sync: Arc::new(SyncService::new()),
+            idle: Arc::new(IdleMonitor::new()),
+            gamepad_status: Arc::new(Mutex::new(GamepadStatus::available(Vec::new()))),
+            gamepads: Arc::new(Mutex::new(Vec::new())),
+            gamepad_settings: Arc::new(Mutex::new(config::GamepadSettings::default())),
+            snapshot_clock: Arc::new(Mutex::new(SnapshotClock::new())),
+            last_session: Arc::new(Mutex::new(None)),
+            persistent_store: Arc::new(Mutex::new(PersistentStore::default())),
+            number_history: Arc::new(Mutex::new(NumberHistory::new())),
+            game_log: Arc::new(Mutex::new(GameLog::new())),
+            fixtures: Arc::new(Mutex::new(FixtureScheduler::new())),
+            crash_reporter: Arc::new(Mutex::new(CrashReporter::new())),
+            asset_cache: Arc::new(Mutex::new(AssetCache::new())),
+            recorder: Arc::new(Mutex::new(Recorder::new())),
+            narrator: Arc::new(Mutex::new(Narrator::new())),
+            player_select: Arc::new(Mutex::new(PlayerSelect::new())),
+            board_lock: Arc::new(Mutex::new(BoardLock::new())),
+            announcements: Arc::new(Mutex::new(AnnouncementScheduler::new())),
+            aux_timers: Arc::new(Mutex::new(AuxTimers::new())),
+            playlist: Arc::new(PlaylistPlayer::new()),
+            remote: Arc::new(Mutex::new(RemoteServer::new(Arc::clone(&remote_clients)))),
+            remote_clients,
+            advertiser: Arc::new(Mutex::new(Advertiser::new())),
+            scripts: Arc::new(Mutex::new(None)),
+        })
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, shortcut, event| match event.state() {
+                    ShortcutState::Pressed => handle_shortcut(app, shortcut.to_string()),
+                    ShortcutState::Released => release_input(app, &shortcut.to_string()),
+                })
+                .build(),
+        )
+        .plugin(tauri_plugin_opener::init())
+        .setup(|app| {
+            setup_crash_reporter(app.handle());
+            setup_asset_cache(app.handle());
+            setup_menu(app)?;
+            // Read the previous session before autosave starts overwriting it.
+            load_previous_session(app.handle());
+            load_persistent_store(app.handle());
+            spawn_timer_thread(app.handle().clone());
+            spawn_gamepad_thread(app.handle().clone());
+            spawn_watchdog_thread(app.handle().clone());
+            spawn_freeze_thread(app.handle().clone());
+            spawn_autosave_thread(app.handle().clone());
+            spawn_fixture_thread(app.handle().clone());
+            spawn_config_thread(app.handle().clone());
+
+            let maybe_default_path = std::env::current_dir().ok().and_then(|dir| {
+                let local = dir.join(DEFAULT_CONFIG_NAME);
+                if local.exists() {
+                    return Some(local);
+                }
+                let parent = dir.parent().map(|p| p.join(DEFAULT_CONFIG_NAME));
+                parent.filter(|p| p.exists())
+            });
+            if let Some(path) = maybe_default_path {
+                queue_config_file(app.handle(), &path);
+            }
+            spawn_startup_update_check(app.handle().clone());
+
+            Ok(())
+        })
+        .on_menu_event(|app, event| match event.id().as_ref() {
+            MENU_ITEM_LOAD_CONFIG => {
+                let selected = FileDialog::new()
+                    .add_filter("Config or template pack", &["toml", PACKAGE_EXTENSION])
+                    .set_title("Load Scoreboard Config")
+                    .pick_file();
+                if let Some(path) = selected {
+                    queue_config_file(app, &path);
+                }
+            }
+            MENU_ITEM_SAVE_CONFIG_AS => {
+                let selected = FileDialog::new()
+                    .add_filter("TOML config", &["toml"])
+                    .set_title("Save Scoreboard Config")
+                    .set_file_name("scoreboard.toml")
+                    .save_file();
+                if let Some(path) = selected {
+                    let state: tauri::State<AppState> = app.state();
+                    if let Err(e) = save_config_as(state, path.to_string_lossy().to_string()) {
+                        emit_error(app, &e);
+                    }
+                }
+            }
+            MENU_ITEM_EXPORT_CONFIG_SCHEMA => {
+                let selected = FileDialog::new()
+                    .add_filter("JSON Schema", &["json"])
+                    .set_title("Export Config Schema")
+                    .set_file_name("scoreboard.schema.json")
+                    .save_file();
+                if let Some(path) = selected {
+                    if let Err(e) = export_config_schema(Some(path.to_string_lossy().to_string())) {
+                        emit_error(app, &e);
+                    }
+                }
+            }
+            MENU_ITEM_EXPORT_SCORESHEET => {
+                let selected = FileDialog::new()
+                    .add_filter("PDF", &["pdf"])
+                    .set_title("Export Scoresheet")
+                    .set_file_name("scoresheet.pdf")
+                    .save_file();
+                if let Some(path) = selected {
+                    let state: tauri::State<AppState> = app.state();
+                    if let Err(e) = generate_scoresheet(state, path.to_string_lossy().to_string()) {
+                        emit_error(app, &e);
+                    }
+                }
+            }
+            MENU_ITEM_START_RECORDING => {
+                let selected = FileDialog::new()
+                    .add_filter("Scoreboard recording", &["jsonl"])
+                    .set_title("Record Scoreboard Output")
+                    .set_file_name("recording.jsonl")
+                    .save_file();
+                if let Some(path) = selected {
+                    let state: tauri::State<AppState> = app.state();
+                    if let Err(e) = start_recording(app.clone(), state, Some(path.to_string_lossy().to_string())) {
+                        emit_error(app, &e);
+                    }
+                }
+            }
+            MENU_ITEM_STOP_RECORDING => {
+                let state: tauri::State<AppState> = app.state();
+                if let Err(e) = stop_recording(state) {
+                    emit_error(app, &e);
+                }
+            }
+            MENU_ITEM_START_NARRATION => {
+                let selected = FileDialog::new()
+                    .add_filter("Text", &["txt"])
+                    .set_title("Write Text Narration")
+                    .set_file_name("narration.txt")
+                    .save_file();
+                if let Some(path) = selected {
+                    let state: tauri::State<AppState> = app.state();
+                    if let Err(e) = start_narration(app.clone(), state, Some(path.to_string_lossy().to_string())) {
+                        emit_error(app, &e);
+                    }
+                }
+            }
+            MENU_ITEM_START_INPUT_TEST => {
+                let state: tauri::State<AppState> = app.state();
+                if let Err(e) = start_input_test(app.clone(), state) {
+                    emit_error(app, &e);
+                }
+            }
+            MENU_ITEM_STOP_INPUT_TEST => {
+                let state: tauri::State<AppState> = app.state();
+                if let Err(e) = stop_input_test(app.clone(), state) {
+                    emit_error(app, &e);
+                }
+            }
+            MENU_ITEM_STOP_NARRATION => {
+                let state: tauri::State<AppState> = app.state();
+                if let Err(e) = stop_narration(state) {
+                    emit_error(app, &e);
+                }
+            }
+            MENU_ITEM_EXPORT_RECORDING_CSV => {
+                let input = FileDialog::new()
+                    .add_filter("Scoreboard recording", &["jsonl"])
+                    .set_title("Select Recording")
+                    .pick_file();
+                let Some(input) = input else {
+                    return;
+                };
+                let output = FileDialog::new()
+                    .add_filter("CSV", &["csv"])
+                    .set_title("Export Recording as CSV")
+                    .set_file_name("recording.csv")
+                    .save_file();
+                if let Some(output) = output {
+                    if let Err(e) = convert_recording_to_csv(&input, &output) {
+                        emit_error(app, &e);
+                    }
+                }
+            }
+            _ => {}
+        })
+        .invoke_handler(tauri::generate_handler![
+            load_config_from_file,
+            load_config_from_text,
+            validate_config_text,
+            save_config_as,
+            export_config_schema,
+            update_label_text,
+            get_number_history,
+            generate_summary,
+            generate_scoresheet,
+            get_event_log,
+            get_score_by_period,
+            export_event_log,
+            get_upcoming_fixtures,
+            check_for_updates,
+            append_ticker_message,
+            set_ticker_messages,
+            pick_image_source,
+            import_image,
+            set_hotkeys_paused,
+            set_hot_reload_enabled,
+            set_theme,
+            show_live_scene,
+            get_timer_display_offset,
+            set_timer_display_offset,
+            lock_board,
+            unlock_board,
+            get_lock_status,
+            toggle_fullscreen,
+            play_sound,
+            get_last_session,
+            restore_last_session,
+            start_recording,
+            stop_recording,
+            export_recording_csv,
+            start_narration,
+            stop_narration,
+            queue_announcement,
+            trigger_announcement,
+            list_aux_timers,
+            list_audio_devices,
+            trigger_action,
+            list_actions,
+            get_playlist_status,
+            playlist_play,
+            playlist_pause,
+            playlist_next,
+            start_aux_timer,
+            stop_aux_timer,
+            reset_aux_timer,
+            clear_announcements,
+            get_health,
+            get_freeze_status,
+            get_font_choices,
+            get_bundled_fonts,
+            clear_asset_cache,
+            discover_instances,
+            profile_config,
+            get_asset_cache_usage,
+            resume_live_output,
+            get_gamepad_status,
+            list_gamepads,
+            get_app_status,
+            get_operator_notes,
+            list_layouts,
+            select_layout,
+            get_input_capabilities,
+            handle_focused_key,
+            handle_focused_key_release,
+            start_input_test,
+            stop_input_test
+        ])
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}
+
+fn setup_menu(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    let load_config = MenuItem::with_id(app, MENU_ITEM_LOAD_CONFIG, "Load Config...", true, None::<&str>)?;
+    let save_config_as = MenuItem::with_id(
+        app,
+        MENU_ITEM_SAVE_CONFIG_AS,
+        "Save Config As...",
+        true,
+        None::<&str>,
+    )?;
+    let export_config_schema = MenuItem::with_id(
+        app,
+        MENU_ITEM_EXPORT_CONFIG_SCHEMA,
+        "Export Config Schema...",
+        true,
+        None::<&str>,
+    )?;
+    let export_scoresheet = MenuItem::with_id(
+        app,
+        MENU_ITEM_EXPORT_SCORESHEET,
+        "Export Scoresheet PDF...",
+        true,
+        None::<&str>,
+    )?;
+    let start_input_test = MenuItem::with_id(app, MENU_ITEM_START_INPUT_TEST, "Test Input Mapping", true, None::<&str>)?;
+    let stop_input_test = MenuItem::with_id(app, MENU_ITEM_STOP_INPUT_TEST, "Stop Input Test", true, None::<&str>)?;
+    let file_submenu = Submenu::with_items(
+        app,
+        "File",
+        true,
+        &[
+            &load_config,
+            &save_config_as,
+            &export_config_schema,
+            &export_scoresheet,
+            &start_input_test,
+            &stop_input_test,
+        ],
+    )?;
+    let start_recording = MenuItem::with_id(
+        app,
+        MENU_ITEM_START_RECORDING,
+        "Start Recording...",
+        true,
+        None::<&str>,
+    )?;
+    let stop_recording = MenuItem::with_id(app, MENU_ITEM_STOP_RECORDING, "Stop Recording", true, None::<&str>)?;
+    let export_recording_csv = MenuItem::with_id(
+        app,
+        MENU_ITEM_EXPORT_RECORDING_CSV,
+        "Export Recording as CSV...",
+        true,
+        None::<&str>,
+    )?;
+    let start_narration = MenuItem::with_id(
+        app,
+        MENU_ITEM_START_NARRATION,
+        "Start Text Narration...",
+        true,
+        None::<&str>,
+    )?;
+    let stop_narration = MenuItem::with_id(app, MENU_ITEM_STOP_NARRATION, "Stop Text Narration", true, None::<&str>)?;
+    let recording_submenu = Submenu::with_items(
+        app,
+        "Recording",
+        true,
+        &[
+            &start_recording,
+            &stop_recording,
+            &export_recording_csv,
+            &start_narration,
+            &stop_narration,
+        ],
+    )?;
+    let menu = Menu::with_items(app, &[&file_submenu, &recording_submenu])?;
+    app.set_menu(menu)?;
+    Ok(())
+}
+
+fn handle_shortcut(app: &AppHandle, shortcut: String) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let paused = match state.hotkeys_paused.lock() {
+        Ok(g) => *g,
+        Err(_) => return,
+    };
+    if paused {
+        return;
+    }
+    wake_from_idle(app, &state);
+
+    let binding = {
+        let guard = match state.action_by_shortcut.lock() {
+            Ok(g) => g,
+            Err(_) => return,
+        };
+        guard.get(&shortcut).cloned()
+    };
+    if report_test_input(app, &state, "keyboard", &shortcut, binding.as_ref()) {
+        return;
+    }
+
+    if handle_player_select_key(app, &state, &shortcut) {
+        return;
+    }
+
+    let Some(binding) = binding else {
+        return;
+    };
+
+    dispatch_binding(app, &state, &shortcut, &binding);
+    start_repeat(app, &state, &shortcut, binding);
+}
+
+fn handle_gamepad_button(app: &AppHandle, pad: usize, button: &str) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let paused = match state.hotkeys_paused.lock() {
+        Ok(g) => *g,
+        Err(_) => return,
+    };
+    if paused {
+        return;
+    }
+    wake_from_idle(app, &state);
+
+    let binding = {
+        let guard = match state.action_by_gamepad.lock() {
+            Ok(g) => g,
+            Err(_) => return,
+        };
+        // A binding for this pad wins over one for any pad.
+        guard
+            .get(&format!("Gamepad{pad}:{button}"))
+            .or_else(|| guard.get(&format!("Gamepad:{button}")))
+            .cloned()
+    };
+    let input = format!("gamepad{pad}:{button}");
+    if report_test_input(app, &state, "gamepad", &input, binding.as_ref()) {
+        return;
+    }
+
+    let Some(binding) = binding else {
+        return;
+    };
+
+    dispatch_binding(app, &state, &input, &binding);
+    start_repeat(app, &state, &input, binding);
+}
+
+/// Repeats a held binding's action until `input` is released, for bindings
+/// with `repeat_ms`. Repeats skip the cooldown, which is meant for presses,
+/// and start even when the press itself was ignored by it.
+fn start_repeat(app: &AppHandle, state: &tauri::State<AppState>, input: &str, binding: HotkeyBinding) {
+    let Some(KeyRepeat { delay_ms, interval_ms }) = binding.repeat else {
+        return;
+    };
+    let Ok(mut held) = state.held_inputs.lock() else {
+        return;
+    };
+    let hold = held.press(input);
+    let app = app.clone();
+    let input = input.to_string();
+    thread::spawn(move || {
+        let mut wait = Duration::from_millis(delay_ms);
+        loop {
+            thread::sleep(wait);
+            let Some(state) = app.try_state::<AppState>() else {
+                return;
+            };
+            let held = state.held_inputs.lock().is_ok_and(|held| held.is_held(&input, hold));
+            let paused = state.hotkeys_paused.lock().map_or(true, |paused| *paused);
+            if !held || paused {
+                return;
+            }
+            run_binding(&app, &state, &binding);
+            wait = Duration::from_millis(interval_ms);
+        }
+    });
+}
+
+fn release_input(app: &AppHandle, input: &str) {
+    if let Some(state) = app.try_state::<AppState>() {
+        if let Ok(mut held) = state.held_inputs.lock() {
+            held.release(input);
+        }
     }
+}
 
-    let mut watcher_slot = state
-        .config_watcher
-        .lock()
-        .map_err(|_| "Config watcher lock poisoned".to_string())?;
-    *watcher_slot = None;
+/// Dispatches a bound key or button press unless it lands inside the
+/// binding's cooldown, which is measured from the last press of the same
+/// `input` that went through, so each controller on a `Gamepad:<button>`
+/// binding has its own.
+fn dispatch_binding(app: &AppHandle, state: &tauri::State<AppState>, input: &str, binding: &HotkeyBinding) {
+    if let Some(cooldown_ms) = binding.cooldown_ms {
+        let suppressed = match state.cooldowns.lock() {
+            Ok(mut cooldowns) => cooldowns.press(input, Duration::from_millis(cooldown_ms), Instant::now()),
+            Err(_) => return,
+        };
+        if let Some(elapsed) = suppressed {
+            let _ = app.emit(
+                EVENT_ACTION_SUPPRESSED,
+                SuppressedPress {
+                    shortcut: binding.shortcut.clone(),
+                    cooldown_ms,
+                    elapsed_ms: elapsed.as_millis() as u64,
+                },
+            );
+            return;
+        }
+    }
 
-    let Some(path) = path else {
+    run_binding(app, state, binding);
+}
+
+fn run_binding(app: &AppHandle, state: &tauri::State<AppState>, binding: &HotkeyBinding) {
+    run_key_press(app, state, &binding.action, &binding.shortcut, false);
+}
+
+/// Applies `action` for a key press, or a macro step acting as one. `source`
+/// names what pressed it when handling fails.
+fn run_key_press(app: &AppHandle, state: &tauri::State<AppState>, action: &Action, source: &str, from_script: bool) {
+    let dispatched = panic::catch_unwind(AssertUnwindSafe(|| dispatch_action(app, state, action, from_script)));
+    match dispatched {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => emit_error(app, &e),
+        Err(_) => {
+            let reason = format!("Handling {source} failed unexpectedly");
+            emit_error(app, &reason);
+            enter_freeze(app, state, &reason);
+        }
+    }
+}
+
+fn is_frozen(state: &AppState) -> bool {
+    state.freeze.lock().is_ok_and(|freeze| freeze.is_frozen())
+}
+
+/// Applies `action`; the error says why it could not be applied.
+/// `from_script` marks actions a rules script fired, directly or through a
+/// macro, so they do not run its hooks again and a script cannot keep
+/// triggering itself.
+fn dispatch_action(
+    app: &AppHandle,
+    state: &tauri::State<AppState>,
+    action: &Action,
+    from_script: bool,
+) -> Result<(), String> {
+    // Fullscreen only changes the window, not the board, so it stays
+    // available while the board is locked on purpose or frozen.
+    if let Action::ToggleFullscreen = action {
+        wake_from_idle(app, state);
+        return toggle_main_window_fullscreen(app);
+    }
+    // The live board may be what failed, so nothing changes it while the
+    // output is frozen. Like the lock below, named actions are refused
+    // before they get here.
+    if is_frozen(state) {
         return Ok(());
+    }
+    wake_from_idle(app, state);
+    if let Action::LockBoard = action {
+        return lock_board(app.clone(), state.clone());
+    }
+    if let Action::Macro { name } = action {
+        run_macro(app, state, name, from_script);
+        return Ok(());
+    }
+    // Keys are expected to be pressed while locked (that is what the lock is
+    // for), so they are dropped silently rather than reported as errors.
+    // Named actions are refused before they get here.
+    if ensure_board_unlocked(state).is_err() {
+        return Ok(());
+    }
+    let aux_timer_update = match action {
+        Action::AuxTimerStart { name } => Some(update_aux_timer(app, state, |t| t.start(name, Instant::now()))),
+        Action::AuxTimerStop { name } => Some(update_aux_timer(app, state, |t| t.stop(name, Instant::now()))),
+        Action::AuxTimerReset { name } => Some(update_aux_timer(app, state, |t| t.reset(name))),
+        _ => None,
+    };
+    if let Some(result) = aux_timer_update {
+        result?;
+        if let Ok(mut runtime) = state.runtime.lock() {
+            runtime.record_event(action);
+        }
+        return Ok(());
+    }
+    let playlist_update = match action {
+        Action::PlaylistPlay => Some(state.playlist.play()),
+        Action::PlaylistPause => Some(state.playlist.pause()),
+        Action::PlaylistNext => Some(state.playlist.next()),
+        _ => None,
+    };
+    if let Some(result) = playlist_update {
+        return result;
+    }
+    if let Action::PlayerSelect = action {
+        return begin_player_select(app, state);
+    }
+    if let Action::Announce { name } = action {
+        return state
+            .announcements
+            .lock()
+            .map_err(|_| "Announcement lock poisoned".to_string())?
+            .enqueue_message(name);
+    }
+
+    let (changed, committed, sound) = {
+        let mut runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
+        let changed = runtime.apply_action(action);
+        // An armed action plays its sound once it is confirmed, not when armed.
+        let committed = changed && !runtime.is_pending(action);
+        let sound = if committed { runtime.action_sound(action) } else { None };
+        (changed, committed, sound)
     };
 
-    let app_handle = app.clone();
-    let mut watcher = notify::recommended_watcher(move |result: notify::Result<Event>| match result {
-        Ok(event) => {
-            if !is_hot_reload_event(&event) {
+    if changed {
+        let _ = emit_snapshot(app, state);
+    }
+    // The action is applied by now, so a sound that fails is only reported.
+    if let Some(sound) = sound {
+        if let Err(e) = state.audio.play(&sound, Some(SoundCue::Action)) {
+            emit_error(app, &e);
+        }
+    }
+    if committed && !from_script {
+        run_script_hook(app, state, ScriptHook::Action(action.describe()));
+    }
+    Ok(())
+}
+
+/// Runs one of the rules script's hooks, if the config has a script, and
+/// applies the changes it asked for in order. A hook that fails changes nothing.
+fn run_script_hook(app: &AppHandle, state: &tauri::State<AppState>, hook: ScriptHook) {
+    let result = {
+        let mut scripts = match state.scripts.lock() {
+            Ok(g) => g,
+            Err(_) => return,
+        };
+        let Some(host) = scripts.as_mut() else {
+            return;
+        };
+        if matches!(hook, ScriptHook::Tick) && !host.tick_due(Instant::now()) {
+            return;
+        }
+        // Only the copy is made under the runtime lock; the script can run
+        // for a while and must not hold up the timer thread or commands.
+        let board = match state.runtime.lock() {
+            Ok(runtime) => host.prepare(&runtime, &hook),
+            Err(_) => return,
+        };
+        let Some(board) = board else {
+            return;
+        };
+        host.run(board, &hook)
+    };
+    let changes = match result {
+        Ok(changes) => changes,
+        Err(e) => {
+            emit_error(app, &e);
+            return;
+        }
+    };
+    if changes.is_empty() {
+        return;
+    }
+
+    let mut changed = false;
+    for change in changes {
+        let result = match change {
+            ScriptChange::Fire(name) => {
+                let action = state.runtime.lock().ok().and_then(|runtime| runtime.resolve_action(&name));
+                match action {
+                    Some(action) => dispatch_action(app, state, &action, true).map(|()| false),
+                    None => Err(format!("Script action '{name}' is not an action of the loaded config")),
+                }
+            }
+            // A locked or frozen board is left alone quietly, the way fire()
+            // drops an action like a key press would; on_tick would otherwise
+            // report it on every tick.
+            ScriptChange::Set(set) => match ensure_board_live(state).and_then(|()| ensure_board_unlocked(state)) {
+                Err(_) => Ok(false),
+                Ok(()) => state
+                    .runtime
+                    .lock()
+                    .map_err(|_| "Runtime lock poisoned".to_string())
+                    .and_then(|mut runtime| set.apply(&mut runtime)),
+            },
+        };
+        match result {
+            Ok(true) => changed = true,
+            Ok(false) => {}
+            Err(e) => emit_error(app, &e),
+        }
+    }
+    if changed {
+        let _ = emit_snapshot(app, state);
+    }
+}
+
+/// Fires a macro's steps in order, each as if its own key were pressed, so
+/// the lock, confirmations, and sounds apply to every step. Steps from the
+/// first delay on run on their own thread and are looked up again when they
+/// fire, in case the config changed in between. Steps still waiting when the
+/// board freezes are dropped.
+fn run_macro(app: &AppHandle, state: &tauri::State<AppState>, name: &str, from_script: bool) {
+    let steps = match state.runtime.lock() {
+        Ok(runtime) => runtime
+            .config
+            .as_ref()
+            .and_then(|config| config.macros.iter().find(|settings| settings.name == name))
+            .map(|settings| settings.steps.clone()),
+        Err(_) => return,
+    };
+    let Some(steps) = steps else {
+        emit_error(app, &format!("Unknown macro '{name}'"));
+        return;
+    };
+    let first_delay = steps.iter().position(|step| step.delay_ms > 0).unwrap_or(steps.len());
+    let (now, later) = steps.split_at(first_delay);
+    for step in now {
+        dispatch_macro_step(app, state, name, &step.action, from_script);
+    }
+    if later.is_empty() {
+        return;
+    }
+    let later = later.to_vec();
+    let name = name.to_string();
+    let app = app.clone();
+    thread::spawn(move || {
+        let state = app.state::<AppState>();
+        for step in later {
+            thread::sleep(Duration::from_millis(step.delay_ms));
+            if is_frozen(&state) {
                 return;
             }
-            if let Err(e) = reload_active_config(&app_handle) {
-                emit_error(&app_handle, &e);
+            dispatch_macro_step(&app, &state, &name, &step.action, from_script);
+        }
+    });
+}
+
+fn dispatch_macro_step(app: &AppHandle, state: &tauri::State<AppState>, name: &str, step: &str, from_script: bool) {
+    let action = state.runtime.lock().ok().and_then(|runtime| runtime.resolve_action(step));
+    match action {
+        // Config validation keeps macros out of macros; a reload cannot sneak one in either.
+        Some(action) if !matches!(action, Action::Macro { .. }) => {
+            run_key_press(app, state, &action, &format!("macro '{name}' step '{step}'"), from_script);
+        }
+        _ => emit_error(app, &format!("Macro '{name}' step '{step}' is not an action of the loaded config")),
+    }
+}
+
+fn begin_player_select(app: &AppHandle, state: &tauri::State<AppState>) -> Result<(), String> {
+    let player_actions = {
+        let runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
+        runtime
+            .config
+            .as_ref()
+            .map(|config| config.global.player_actions.clone())
+            .unwrap_or_default()
+    };
+
+    let mut keys = HashMap::new();
+    for digit in '0'..='9' {
+        for code in [format!("Digit{digit}"), format!("Numpad{digit}")] {
+            if let Ok(shortcut) = Shortcut::from_str(&code) {
+                keys.insert(shortcut.to_string(), PlayerSelectKey::Digit(digit));
             }
         }
-        Err(e) => {
-            emit_error(&app_handle, &format!("Config watcher error: {e}"));
+    }
+    if let Ok(shortcut) = Shortcut::from_str("Escape") {
+        keys.insert(shortcut.to_string(), PlayerSelectKey::Cancel);
+    }
+    for player_action in player_actions {
+        let raw = player_action.keybind.to_shortcut();
+        let shortcut = Shortcut::from_str(&raw).map_err(|e| format!("Invalid shortcut '{raw}': {e}"))?;
+        keys.insert(shortcut.to_string(), PlayerSelectKey::Action(player_action.name));
+    }
+
+    let global_shortcuts = state
+        .input_capabilities
+        .lock()
+        .map_err(|_| "Input capabilities lock poisoned".to_string())?
+        .global_shortcuts;
+
+    let mut player_select = state
+        .player_select
+        .lock()
+        .map_err(|_| "Player select lock poisoned".to_string())?;
+    if !player_select.is_active() && global_shortcuts {
+        // Mode keys are only grabbed while selecting so digits stay usable elsewhere.
+        let mut registered = Vec::new();
+        for shortcut in keys.keys() {
+            if app.global_shortcut().is_registered(shortcut.as_str()) {
+                continue;
+            }
+            match app.global_shortcut().register(shortcut.as_str()) {
+                Ok(()) => registered.push(shortcut.clone()),
+                Err(e) => emit_error(app, &format!("Failed to register '{shortcut}': {e}")),
+            }
         }
-    })
-    .map_err(|e| format!("Failed to start config watcher: {e}"))?;
+        player_select.set_registered(registered);
+    }
+    player_select.begin(keys);
+    app.emit(EVENT_PLAYER_SELECT, player_select.status())
+        .map_err(|e| format!("Failed to emit player select status: {e}"))
+}
 
-    watcher
-        .watch(&path, RecursiveMode::NonRecursive)
-        .map_err(|e| format!("Failed to watch config {}: {e}", path.display()))?;
+/// Routes a shortcut to player select mode. Returns true when the mode consumed it.
+fn handle_player_select_key(app: &AppHandle, state: &tauri::State<AppState>, shortcut: &str) -> bool {
+    let (outcome, status, released) = {
+        let mut player_select = match state.player_select.lock() {
+            Ok(g) => g,
+            Err(_) => return false,
+        };
+        let Some(key) = player_select.key_for(shortcut) else {
+            return false;
+        };
+        let outcome = player_select.press(key);
+        let released = match outcome {
+            PlayerSelectOutcome::Applied(_) | PlayerSelectOutcome::Cancelled => player_select.end(),
+            PlayerSelectOutcome::Updated | PlayerSelectOutcome::Ignored => Vec::new(),
+        };
+        (outcome, player_select.status(), released)
+    };
 
-    *watcher_slot = Some(watcher);
-    Ok(())
+    for shortcut in released {
+        let _ = app.global_shortcut().unregister(shortcut.as_str());
+    }
+    if !matches!(outcome, PlayerSelectOutcome::Ignored) {
+        let _ = app.emit(EVENT_PLAYER_SELECT, status);
+    }
+
+    if let PlayerSelectOutcome::Applied(event) = outcome {
+        let applied = state.runtime.lock().ok().and_then(|runtime| {
+            let config = runtime.config.as_ref()?;
+            let player_action = config
+                .global
+                .player_actions
+                .iter()
+                .find(|player_action| player_action.name == event.action)?;
+            let apply = player_action.apply.as_ref()?;
+            let action = runtime.resolve_component_action(&apply.component, &apply.action)?;
+            Some((apply.component.clone(), action))
+        });
+        if let Ok(mut game_log) = state.game_log.lock() {
+            game_log.record_player_action(&event, applied.as_ref().map(|(component, _)| component.as_str()));
+        }
+        let action = applied.map(|(_, action)| action);
+        let _ = app.emit(EVENT_PLAYER_ACTION, event);
+        if let Some(action) = action {
+            if let Err(e) = dispatch_action(app, state, &action, false) {
+                emit_error(app, &e);
+            }
+        }
+    }
+    true
 }
 
-fn is_hot_reload_event(event: &Event) -> bool {
-    matches!(
-        event.kind,
-        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Any
-    )
+fn toggle_main_window_fullscreen(app: &AppHandle) -> Result<(), String> {
+    let window = app
+        .get_webview_window(MAIN_WINDOW_LABEL)
+        .ok_or_else(|| "Main window is not available".to_string())?;
+    let fullscreen = window
+        .is_fullscreen()
+        .map_err(|e| format!("Failed to query fullscreen state: {e}"))?;
+    window
+        .set_fullscreen(!fullscreen)
+        .map_err(|e| format!("Failed to toggle fullscreen: {e}"))
 }
 
-fn reload_active_config(app: &AppHandle) -> Result<(), String> {
-    let Some(state) = app.try_state::<AppState>() else {
-        return Ok(());
+fn spawn_timer_thread(app: AppHandle) {
+    let Some(generation) = begin_worker_generation(&app, WorkerThread::Timer) else {
+        return;
     };
 
-    let path = {
-        let guard = state
-            .active_config_path
-            .lock()
-            .map_err(|_| "Active config path lock poisoned".to_string())?;
-        guard.clone()
+    thread::spawn(move || loop {
+        // Keep updates frequent enough for tenths-of-a-second display modes.
+        // An idle board has nothing counting down, so it ticks rarely until
+        // input wakes it.
+        match app.try_state::<AppState>() {
+            Some(state) if state.idle.is_idle() => state.idle.wait(IDLE_TIMER_TICK),
+            _ => thread::sleep(TIMER_TICK),
+        }
+        let Some(state) = app.try_state::<AppState>() else {
+            continue;
+        };
+        if !worker_heartbeat(&state, WorkerThread::Timer, generation) {
+            return;
+        }
+
+        let (tick, triggers, tick_sounds, sounds, timers_running) = {
+            let mut runtime = match state.runtime.lock() {
+                Ok(g) => g,
+                Err(_) => continue,
+            };
+            let tick = runtime.tick_timers();
+            let triggers: Vec<ExpireTrigger> = tick
+                .expired
+                .iter()
+                .filter_map(|id| runtime.timer_expire_trigger(id))
+                .collect();
+            let tick_sounds: Vec<Option<String>> =
+                tick.ticked.iter().map(|id| runtime.timer_tick_sound(id)).collect();
+            let sounds: Vec<String> = tick
+                .committed
+                .iter()
+                .filter_map(|action| runtime.action_sound(action))
+                .collect();
+            let music_clock = runtime
+                .config
+                .as_ref()
+                .and_then(|config| config.global.playlist.as_ref()?.clock.as_deref());
+            state
+                .playlist
+                .set_clock_running(music_clock.is_some_and(|clock| runtime.timer_running(clock)));
+            (tick, triggers, tick_sounds, sounds, runtime.any_timer_running())
+        };
+        // Sent before anything else so video servers get them as early as possible.
+        for cue in &tick.t_minus {
+            let payload = TMinusPayload {
+                id: cue.id.clone(),
+                offset_ms: cue.offset_ms,
+                remaining_ms: cue.remaining_ms,
+                expires_at_ms: unix_time_ms() + cue.remaining_ms,
+            };
+            let _ = app.emit(EVENT_T_MINUS, payload);
+            state.osc.t_minus(cue);
+        }
+        if tick.changed {
+            let _ = emit_snapshot(&app, &state);
+        }
+        for id in &tick.expired {
+            let _ = app.emit(EVENT_TIMER_EXPIRED, TimerExpiredPayload { id: id.clone() });
+            state.obs.timer_expired(id);
+        }
+        for (id, cue_ms) in &tick.voiced {
+            speak_voice_cue(&app, &state, id, *cue_ms);
+        }
+        let expired_aux_timers = match state.aux_timers.lock() {
+            Ok(mut aux_timers) => aux_timers.tick(Instant::now()),
+            Err(_) => Vec::new(),
+        };
+        if !expired_aux_timers.is_empty() {
+            emit_aux_timers(&app, &state);
+        }
+        for aux_timer in expired_aux_timers {
+            let label = aux_timer.label.unwrap_or_else(|| aux_timer.name.clone());
+            let _ = app.emit(EVENT_AUX_TIMER_EXPIRED, AuxTimerExpiredPayload { name: aux_timer.name, label });
+            if let Some(sound) = aux_timer.sound {
+                if let Err(e) = state.audio.play(&sound, Some(SoundCue::AuxTimer)) {
+                    emit_error(&app, &e);
+                }
+            }
+        }
+        apply_playlist_update(&app, &state);
+        let busy = timers_running
+            || state.aux_timers.lock().is_ok_and(|aux_timers| aux_timers.any_running())
+            || state.playlist.status().playing;
+        if state.idle.update(busy, Instant::now()) {
+            set_board_idle(&app, &state, true);
+        }
+        let announcement = match state.announcements.lock() {
+            Ok(mut announcements) => announcements.tick(Instant::now()),
+            Err(_) => None,
+        };
+        if let Some(update) = announcement {
+            apply_announcement_update(&app, &state, update);
+        }
+        for trigger in triggers {
+            fire_expire_trigger(&app, &state, &trigger);
+        }
+        {
+            let _operation = LongOperation::begin(&state.watchdog, &[WorkerThread::Timer]);
+            for action in &tick.committed {
+                run_script_hook(&app, &state, ScriptHook::Action(action.describe()));
+            }
+            for id in &tick.expired {
+                run_script_hook(&app, &state, ScriptHook::TimerExpire(id.clone()));
+            }
+            run_script_hook(&app, &state, ScriptHook::Tick);
+        }
+        for sound in tick_sounds {
+            let result = match sound {
+                Some(sound) => state.audio.play(&sound, Some(SoundCue::Tick)),
+                None => state.audio.play_beep(Some(SoundCue::Tick)),
+            };
+            if let Err(e) = result {
+                emit_error(&app, &e);
+            }
+        }
+        for sound in sounds {
+            if let Err(e) = state.audio.play(&sound, Some(SoundCue::Action)) {
+                emit_error(&app, &e);
+            }
+        }
+    });
+}
+
+/// Wakes the board from low-power mode on operator input.
+fn wake_from_idle(app: &AppHandle, state: &AppState) {
+    if state.idle.activity() {
+        set_board_idle(app, state, false);
+    }
+}
+
+fn set_board_idle(app: &AppHandle, state: &AppState, idle: bool) {
+    let changed = match state.runtime.lock() {
+        Ok(mut runtime) => runtime.set_idle(idle),
+        Err(_) => return,
     };
+    if changed {
+        let _ = emit_snapshot(app, state);
+    }
+    if let Ok(status) = app_status(state) {
+        let _ = app.emit(EVENT_STATUS, status);
+    }
+}
 
-    let Some(path) = path else {
-        return Ok(());
+/// Sends a countdown cue to the frontend to be spoken, and to the narration
+/// file when one is being written.
+fn speak_voice_cue(app: &AppHandle, state: &AppState, id: &str, cue_ms: i64) {
+    let text = narration::spoken_countdown(cue_ms);
+    if let Ok(mut narrator) = state.narrator.lock() {
+        if let Err(e) = narrator.say(&text) {
+            emit_error(app, &e);
+        }
+    }
+    let _ = app.emit(EVENT_VOICE_CUE, VoiceCuePayload { id: id.to_string(), text });
+}
+
+fn apply_announcement_update(app: &AppHandle, state: &AppState, update: AnnouncementUpdate) {
+    let (target, text, sound) = match update {
+        AnnouncementUpdate::Show { target, text, sound } => (target, Some(text), sound),
+        AnnouncementUpdate::Restore { target } => (target, None, None),
+    };
+    let changed = match state.runtime.lock() {
+        Ok(mut runtime) => runtime.set_label_override(&target, text),
+        Err(_) => return,
     };
+    if changed {
+        let _ = emit_snapshot(app, state);
+    }
+    if let Some(sound) = sound {
+        if let Err(e) = state.audio.play(&sound, Some(SoundCue::Announcement)) {
+            emit_error(app, &e);
+        }
+    }
+}
 
-    let config = load_config_from_path(&path)?;
-    apply_config(app.clone(), &state, config)
+fn fire_expire_trigger(app: &AppHandle, state: &AppState, trigger: &ExpireTrigger) {
+    let duration = trigger.duration_ms.map(Duration::from_millis);
+    if let Some(sound) = &trigger.sound {
+        if let Err(e) = state.audio.play_for(sound, duration, Some(SoundCue::Expire)) {
+            emit_error(app, &e);
+        }
+    }
+    if let Some(relay) = &trigger.relay {
+        if let Err(e) = state.relays.pulse(relay, duration.unwrap_or(DEFAULT_RELAY_PULSE)) {
+            emit_error(app, &e);
+        }
+    }
 }
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    tauri::Builder::default()
-        .manage(AppState {
-            runtime: Arc::new(Mutex::new(RuntimeState::new())),
-            action_by_shortcut: Arc::new(Mutex::new(HashMap::new())),
-            action_by_gamepad: Arc::new(Mutex::new(HashMap::new())),
-            hotkeys_paused: Arc::new(Mutex::new(false)),
-            active_config_path: Arc::new(Mutex::new(None)),
-            config_watcher: Arc::new(Mutex::new(None)),
-        })
-        .plugin(
-            tauri_plugin_global_shortcut::Builder::new()
-                .with_handler(|app, shortcut, event| {
-                    if event.state() != ShortcutState::Pressed {
+fn spawn_gamepad_thread(app: AppHandle) {
+    let Some(generation) = begin_worker_generation(&app, WorkerThread::Gamepad) else {
+        return;
+    };
+
+    thread::spawn(move || {
+        let mut retry_delay = GAMEPAD_RETRY_INITIAL;
+        let mut slots = load_gamepad_slots(&app);
+        loop {
+            let mut gilrs = match Gilrs::new() {
+                Ok(gilrs) => {
+                    retry_delay = GAMEPAD_RETRY_INITIAL;
+                    gilrs
+                }
+                Err(e) => {
+                    set_gamepad_status(&app, GamepadStatus::unavailable(format!("Gamepad input unavailable: {e}")));
+                    set_gamepads(&app, Vec::new());
+                    if !gamepad_idle(&app, generation, retry_delay) {
                         return;
                     }
-                    handle_shortcut(app, shortcut.to_string());
-                })
-                .build(),
-        )
-        .plugin(tauri_plugin_opener::init())
-        .setup(|app| {
-            setup_menu(app)?;
-            spawn_timer_thread(app.handle().clone());
-            spawn_gamepad_thread(app.handle().clone());
+                    retry_delay = (retry_delay * 2).min(GAMEPAD_RETRY_MAX);
+                    continue;
+                }
+            };
 
-            let maybe_default_path = std::env::current_dir().ok().and_then(|dir| {
-                let local = dir.join(DEFAULT_CONFIG_NAME);
-                if local.exists() {
-                    return Some(local);
+            slots.disconnect_all();
+            let mut connected = gilrs.gamepads().count();
+            set_gamepad_status(&app, GamepadStatus::available(connected_pads(&app, &gilrs, &mut slots)));
+            set_gamepads(&app, connected_gamepads(&app, &gilrs, &mut slots));
+            let mut axis_buttons = AxisButtons::new();
+
+            loop {
+                if !gamepad_idle(&app, generation, Duration::from_millis(8)) {
+                    return;
                 }
-                let parent = dir.parent().map(|p| p.join(DEFAULT_CONFIG_NAME));
-                parent.filter(|p| p.exists())
-            });
-            if let Some(path) = maybe_default_path {
-                let app_handle = app.handle().clone();
-                let state: tauri::State<AppState> = app.state();
-                if let Err(e) = load_config_from_file(app_handle.clone(), state, path.to_string_lossy().to_string()) {
-                    emit_error(&app_handle, &e);
+
+                let previously_connected = connected;
+                while let Some(event) = gilrs.next_event() {
+                    let pad = gamepad_number(&app, &gilrs, &mut slots, event.id);
+                    match event.event {
+                        EventType::ButtonPressed(button, _) => {
+                            if let Some(button_key) = map_gamepad_button(button) {
+                                handle_gamepad_button(&app, pad, button_key);
+                            }
+                        }
+                        EventType::ButtonReleased(button, _) => {
+                            if let Some(button_key) = map_gamepad_button(button) {
+                                release_input(&app, &format!("gamepad{pad}:{button_key}"));
+                            }
+                        }
+                        EventType::AxisChanged(axis, value, _) => {
+                            let settings = gamepad_settings(&app);
+                            for (button_key, pressed) in axis_buttons.axis_changed(event.id, axis, value, &settings) {
+                                handle_virtual_button(&app, pad, button_key, pressed);
+                            }
+                        }
+                        EventType::ButtonChanged(button, value, _) => {
+                            let settings = gamepad_settings(&app);
+                            for (button_key, pressed) in axis_buttons.button_changed(event.id, button, value, &settings) {
+                                handle_virtual_button(&app, pad, button_key, pressed);
+                            }
+                        }
+                        EventType::Connected | EventType::Disconnected => {
+                            axis_buttons.release_all(event.id);
+                            if let Some(state) = app.try_state::<AppState>() {
+                                if let Ok(mut held) = state.held_inputs.lock() {
+                                    held.release_prefix(&format!("gamepad{pad}:"));
+                                }
+                            }
+                            if event.event == EventType::Disconnected {
+                                slots.release(event.id);
+                            }
+                            connected = gilrs.gamepads().count();
+                            set_gamepad_status(&app, GamepadStatus::available(connected_pads(&app, &gilrs, &mut slots)));
+                            set_gamepads(&app, connected_gamepads(&app, &gilrs, &mut slots));
+                            let info = gamepad_info(pad, gilrs.gamepad(event.id).name());
+                            let event_name = match event.event {
+                                EventType::Connected => EVENT_GAMEPAD_CONNECTED,
+                                _ => EVENT_GAMEPAD_DISCONNECTED,
+                            };
+                            let _ = app.emit(event_name, info);
+                        }
+                        _ => {}
+                    }
+                }
+
+                // Some backends never report devices that return after the last one
+                // disappears, so start over with a fresh context.
+                if previously_connected > 0 && connected == 0 {
+                    break;
                 }
             }
+        }
+    });
+}
 
-            Ok(())
-        })
-        .on_menu_event(|app, event| {
-            if event.id().as_ref() == MENU_ITEM_LOAD_CONFIG {
-                let selected = FileDialog::new()
-                    .add_filter("TOML config", &["toml"])
-                    .set_title("Load Scoreboard Config")
-                    .pick_file();
-                if let Some(path) = selected {
-                    let state: tauri::State<AppState> = app.state();
-                    if let Err(e) = load_config_from_file(app.clone(), state, path.to_string_lossy().to_string()) {
-                        emit_error(app, &e);
-                    }
-                }
+/// The pad's number for `Gamepad<n>:` keys, as remembered for its controller
+/// in the app data dir. Saves the numbers when a new controller gets one.
+fn gamepad_number(app: &AppHandle, gilrs: &Gilrs, slots: &mut GamepadSlots, id: GamepadId) -> usize {
+    let (number, changed) = slots.assign(id, gilrs.gamepad(id).uuid());
+    if changed {
+        if let Ok(data_dir) = app.path().app_data_dir() {
+            if let Err(e) = write_gamepad_slots(&gamepad_slots_path(&data_dir), slots) {
+                emit_error(app, &e);
             }
-        })
-        .invoke_handler(tauri::generate_handler![
-            load_config_from_file,
-            load_config_from_text,
-            update_label_text,
-            pick_image_source,
-            set_hotkeys_paused
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        }
+    }
+    number
 }
 
-fn setup_menu(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
-    let load_config = MenuItem::with_id(app, MENU_ITEM_LOAD_CONFIG, "Load Config...", true, None::<&str>)?;
-    let file_submenu = Submenu::with_items(app, "File", true, &[&load_config])?;
-    let menu = Menu::with_items(app, &[&file_submenu])?;
-    app.set_menu(menu)?;
-    Ok(())
+fn load_gamepad_slots(app: &AppHandle) -> GamepadSlots {
+    let Ok(data_dir) = app.path().app_data_dir() else {
+        return GamepadSlots::default();
+    };
+    read_gamepad_slots(&gamepad_slots_path(&data_dir)).unwrap_or_else(|e| {
+        emit_error(app, &e);
+        GamepadSlots::default()
+    })
 }
 
-fn handle_shortcut(app: &AppHandle, shortcut: String) {
+fn connected_pads(app: &AppHandle, gilrs: &Gilrs, slots: &mut GamepadSlots) -> Vec<usize> {
+    let mut pads: Vec<usize> =
+        gilrs.gamepads().map(|(id, _)| gamepad_number(app, gilrs, slots, id)).collect();
+    pads.sort_unstable();
+    pads
+}
+
+fn handle_virtual_button(app: &AppHandle, pad: usize, button: &str, pressed: bool) {
+    if pressed {
+        handle_gamepad_button(app, pad, button);
+    } else {
+        release_input(app, &format!("gamepad{pad}:{button}"));
+    }
+}
+
+fn gamepad_settings(app: &AppHandle) -> config::GamepadSettings {
+    app.try_state::<AppState>()
+        .and_then(|state| state.gamepad_settings.lock().ok().map(|settings| *settings))
+        .unwrap_or_default()
+}
+
+/// Sleeps for `duration` while keeping the gamepad heartbeat alive. Returns false
+/// when this thread has been superseded and should exit.
+fn gamepad_idle(app: &AppHandle, generation: u64, duration: Duration) -> bool {
+    let step = Duration::from_millis(100);
+    let mut remaining = duration;
+    loop {
+        if let Some(state) = app.try_state::<AppState>() {
+            if !worker_heartbeat(&state, WorkerThread::Gamepad, generation) {
+                return false;
+            }
+        }
+        if remaining.is_zero() {
+            return true;
+        }
+        let slice = remaining.min(step);
+        thread::sleep(slice);
+        remaining -= slice;
+    }
+}
+
+fn gamepad_info(pad: usize, name: &str) -> GamepadInfo {
+    GamepadInfo {
+        id: pad,
+        name: name.to_string(),
+    }
+}
+
+fn connected_gamepads(app: &AppHandle, gilrs: &Gilrs, slots: &mut GamepadSlots) -> Vec<GamepadInfo> {
+    gilrs
+        .gamepads()
+        .map(|(id, gamepad)| gamepad_info(gamepad_number(app, gilrs, slots, id), gamepad.name()))
+        .collect()
+}
+
+fn set_gamepads(app: &AppHandle, gamepads: Vec<GamepadInfo>) {
     let Some(state) = app.try_state::<AppState>() else {
         return;
     };
-    let paused = match state.hotkeys_paused.lock() {
-        Ok(g) => *g,
-        Err(_) => return,
-    };
-    if paused {
+    let Ok(mut current) = state.gamepads.lock() else {
         return;
-    }
-
-    let action = {
-        let guard = match state.action_by_shortcut.lock() {
-            Ok(g) => g,
-            Err(_) => return,
-        };
-        guard.get(&shortcut).cloned()
     };
+    *current = gamepads;
+}
 
-    let Some(action) = action else {
+fn set_gamepad_status(app: &AppHandle, status: GamepadStatus) {
+    let Some(state) = app.try_state::<AppState>() else {
         return;
     };
-
-    let changed = {
-        let mut runtime = match state.runtime.lock() {
-            Ok(g) => g,
-            Err(_) => return,
-        };
-        runtime.apply_action(&action)
+    let changed = match state.gamepad_status.lock() {
+        Ok(mut current) => {
+            let changed = *current != status;
+            *current = status.clone();
+            changed
+        }
+        Err(_) => return,
     };
-
     if changed {
-        let _ = emit_snapshot(app, &state.runtime);
+        let _ = app.emit(EVENT_GAMEPAD_STATUS, status);
     }
 }
 
-fn handle_gamepad_button(app: &AppHandle, button: String) {
-    let Some(state) = app.try_state::<AppState>() else {
+fn load_previous_session(app: &AppHandle) {
+    let Ok(data_dir) = app.path().app_data_dir() else {
         return;
     };
-    let paused = match state.hotkeys_paused.lock() {
-        Ok(g) => *g,
-        Err(_) => return,
+    let session = match read_session(&session_file_path(&data_dir)) {
+        Ok(session) => session,
+        Err(e) => {
+            emit_error(app, &e);
+            return;
+        }
     };
-    if paused {
+    let state: tauri::State<AppState> = app.state();
+    let Ok(mut last_session) = state.last_session.lock() else {
         return;
-    }
-
-    let action = {
-        let guard = match state.action_by_gamepad.lock() {
-            Ok(g) => g,
-            Err(_) => return,
-        };
-        guard.get(&button).cloned()
     };
+    *last_session = session;
+}
 
-    let Some(action) = action else {
+fn load_persistent_store(app: &AppHandle) {
+    let Ok(data_dir) = app.path().app_data_dir() else {
         return;
     };
-
-    let changed = {
-        let mut runtime = match state.runtime.lock() {
-            Ok(g) => g,
-            Err(_) => return,
-        };
-        runtime.apply_action(&action)
+    let store = match read_persistent_store(&persistent_file_path(&data_dir)) {
+        Ok(store) => store,
+        Err(e) => {
+            emit_error(app, &e);
+            return;
+        }
     };
+    let state: tauri::State<AppState> = app.state();
+    let Ok(mut persistent_store) = state.persistent_store.lock() else {
+        return;
+    };
+    *persistent_store = store;
+}
 
-    if changed {
-        let _ = emit_snapshot(app, &state.runtime);
+/// Saves the `persistent` component values if they changed since the last save.
+fn save_persistent_values(state: &tauri::State<AppState>, path: &Path) -> Result<(), String> {
+    let current = state
+        .runtime
+        .lock()
+        .map_err(|_| "Runtime lock poisoned".to_string())?
+        .persistent_values();
+    let Some((scope, values)) = current else {
+        return Ok(());
+    };
+    let mut store = state
+        .persistent_store
+        .lock()
+        .map_err(|_| "Persistent store lock poisoned".to_string())?;
+    if store.configs.get(&scope) == Some(&values) {
+        return Ok(());
     }
+    // Keep the old values if the write fails, so the next autosave retries.
+    let mut updated = store.clone();
+    updated.configs.insert(scope, values);
+    write_persistent_store(path, &updated)?;
+    *store = updated;
+    Ok(())
 }
 
-fn spawn_timer_thread(app: AppHandle) {
+fn spawn_autosave_thread(app: AppHandle) {
+    let Ok(data_dir) = app.path().app_data_dir() else {
+        emit_error(&app, "Session autosave unavailable: no app data directory");
+        return;
+    };
+    let session_path = session_file_path(&data_dir);
+    let persistent_path = persistent_file_path(&data_dir);
+
+    thread::spawn(move || {
+        let mut last_saved = None;
+        loop {
+            thread::sleep(SESSION_AUTOSAVE_INTERVAL);
+            let Some(state) = app.try_state::<AppState>() else {
+                continue;
+            };
+            if let Err(e) = save_persistent_values(&state, &persistent_path) {
+                emit_error(&app, &e);
+            }
+
+            let values = {
+                let runtime = match state.runtime.lock() {
+                    Ok(g) => g,
+                    Err(_) => continue,
+                };
+                if runtime.config.is_none() {
+                    continue;
+                }
+                runtime.export_values()
+            };
+            if last_saved.as_ref() == Some(&values) {
+                continue;
+            }
+
+            let config_path = match state.active_config_path.lock() {
+                Ok(g) => g.as_ref().map(|p| p.to_string_lossy().to_string()),
+                Err(_) => continue,
+            };
+            let session = SessionSnapshot::new(config_path, values.clone());
+            match write_session(&session_path, &session) {
+                Ok(()) => last_saved = Some(values),
+                Err(e) => emit_error(&app, &e),
+            }
+        }
+    });
+}
+
+/// Keeps the fixture calendar fresh and sets up each game when it comes due.
+fn spawn_fixture_thread(app: AppHandle) {
     thread::spawn(move || loop {
-        // Keep updates frequent enough for tenths-of-a-second display modes.
-        thread::sleep(Duration::from_millis(50));
+        thread::sleep(Duration::from_secs(1));
         let Some(state) = app.try_state::<AppState>() else {
             continue;
         };
-
-        let changed = {
-            let mut runtime = match state.runtime.lock() {
-                Ok(g) => g,
+        let due_fetch = match state.fixtures.lock() {
+            Ok(scheduler) => scheduler.due_fetch(Instant::now()),
+            Err(_) => continue,
+        };
+        // Downloads can take a while, so the scheduler is not held meanwhile.
+        if let Some((source, utc_offset_minutes)) = due_fetch {
+            let result = fetch_fixtures(&source, utc_offset_minutes);
+            let error = match state.fixtures.lock() {
+                Ok(mut scheduler) => scheduler.finish_fetch(result, Instant::now()),
                 Err(_) => continue,
             };
-            runtime.tick_timers()
+            if let Some(e) = error {
+                emit_error(&app, &e);
+            }
+        }
+
+        let due = match state.fixtures.lock() {
+            Ok(mut scheduler) => scheduler.take_due(unix_time_ms()),
+            Err(_) => continue,
         };
-        if changed {
-            let _ = emit_snapshot(&app, &state.runtime);
+        if let Some(fixture) = due {
+            if let Err(e) = prepare_fixture(&app, &state, fixture) {
+                emit_error(&app, &e);
+            }
         }
     });
 }
 
-fn spawn_gamepad_thread(app: AppHandle) {
-    thread::spawn(move || {
-        let mut gilrs = match Gilrs::new() {
-            Ok(gilrs) => gilrs,
-            Err(e) => {
-                emit_error(&app, &format!("Gamepad input unavailable: {e}"));
-                return;
+/// Loads the fixture's config if one matches its title, fills in the team
+/// labels, and starts the countdown to kick-off.
+fn prepare_fixture(app: &AppHandle, state: &tauri::State<AppState>, fixture: Fixture) -> Result<(), String> {
+    let settings = |state: &tauri::State<AppState>| {
+        state
+            .fixtures
+            .lock()
+            .map(|scheduler| scheduler.settings().cloned())
+            .map_err(|_| "Fixture scheduler lock poisoned".to_string())
+    };
+    let Some(mut fixture_settings) = settings(state)? else {
+        return Ok(());
+    };
+
+    let config_path = fixture.config_path(&fixture_settings).map(PathBuf::from);
+    if let Some(path) = &config_path {
+        let active = state
+            .active_config_path
+            .lock()
+            .map_err(|_| "Active config path lock poisoned".to_string())?
+            .clone();
+        if active.as_ref() != Some(path) {
+            state
+                .config_queue
+                .submit(ConfigJob::File(path.clone()))
+                .recv()
+                .map_err(|_| "Config apply was cancelled".to_string())??;
+            fixture_settings = settings(state)?.ok_or_else(|| {
+                format!(
+                    "Config {} for '{}' has no [global.fixtures]; the countdown was not started",
+                    path.display(),
+                    fixture.title
+                )
+            })?;
+        }
+    }
+
+    let teams = fixture.teams();
+    {
+        let mut runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
+        if let Some((home, away)) = &teams {
+            for (label, name) in [(&fixture_settings.home_label, home), (&fixture_settings.away_label, away)] {
+                if let Some(label) = label {
+                    runtime.set_label_text(label, name.clone());
+                }
             }
-        };
+        }
+        runtime.start_countdown(&fixture_settings.countdown, fixture.start_unix_ms - unix_time_ms());
+    }
+    emit_snapshot(app, state)?;
+    let (home, away) = teams.unzip();
+    let _ = app.emit(
+        EVENT_FIXTURE_PREPARED,
+        FixturePrepared {
+            fixture,
+            home,
+            away,
+            config_path: config_path.map(|path| path.to_string_lossy().to_string()),
+        },
+    );
+    Ok(())
+}
 
+fn spawn_watchdog_thread(app: AppHandle) {
+    thread::spawn(move || {
+        let mut last_report: Option<HealthReport> = None;
+        let mut ticks: u64 = 0;
         loop {
-            while let Some(event) = gilrs.next_event() {
-                if let EventType::ButtonPressed(button, _) = event.event {
-                    if let Some(button_key) = map_gamepad_button(button) {
-                        handle_gamepad_button(&app, button_key.to_string());
-                    }
+            thread::sleep(Duration::from_millis(500));
+            let Some(state) = app.try_state::<AppState>() else {
+                continue;
+            };
+            ticks += 1;
+            // Sent every second even when unchanged, so a stalled status bar is
+            // noticed; an idle board only sends it now and then.
+            let status_ticks = if state.idle.is_idle() { IDLE_STATUS_TICKS } else { 2 };
+            if ticks.is_multiple_of(status_ticks) {
+                if let Ok(status) = app_status(&state) {
+                    let _ = app.emit(EVENT_STATUS, status);
                 }
             }
 
-            thread::sleep(Duration::from_millis(8));
+            let Some(check) = watchdog::check(&state.runtime, &state.watchdog) else {
+                continue;
+            };
+            if check.runtime_recovered {
+                let _ = emit_snapshot(&app, &state);
+            }
+            for worker in check.stalled {
+                match worker {
+                    WorkerThread::Timer => spawn_timer_thread(app.clone()),
+                    WorkerThread::Gamepad => spawn_gamepad_thread(app.clone()),
+                }
+            }
+            let report = check.report;
+            if last_report.as_ref() != Some(&report) {
+                let _ = app.emit(EVENT_HEALTH, report.clone());
+                last_report = Some(report);
+            }
         }
     });
 }
 
+fn begin_worker_generation(app: &AppHandle, worker: WorkerThread) -> Option<u64> {
+    let state = app.try_state::<AppState>()?;
+    let mut watchdog = state.watchdog.lock().ok()?;
+    Some(watchdog.begin_generation(worker))
+}
+
+/// Records a heartbeat and returns false when the calling thread has been
+/// superseded by a respawned one and should exit.
+fn worker_heartbeat(state: &AppState, worker: WorkerThread, generation: u64) -> bool {
+    state
+        .watchdog
+        .lock()
+        .map_or(true, |mut watchdog| watchdog.beat(worker, generation))
+}
+
 fn map_gamepad_button(button: Button) -> Option<&'static str> {
     match button {
         Button::South => Some("A"),
@@ -457,29 +3661,95 @@ fn map_gamepad_button(button: Button) -> Option<&'static str> {
     }
 }
 
+/// Why this session has no global shortcuts at all, if it has none. On Linux
+/// they are X11 key grabs, so they need an X display; a Wayland session with
+/// XWayland still has one and is tried like any other.
+fn missing_global_shortcuts() -> Option<String> {
+    if cfg!(target_os = "linux") && std::env::var_os("DISPLAY").is_none() {
+        Some("no X display to grab keys on".to_string())
+    } else {
+        None
+    }
+}
+
+/// What registering `attempted` keys left, given the errors of the ones that
+/// failed. Only some failing is a conflict with another program and fails the
+/// apply; all of them failing means the session cannot grab keys (such as
+/// Wayland without working XWayland grabs), so "auto" keeps the keys on the
+/// focused window instead.
+fn registered_capabilities(
+    keyboard_mode: KeyboardMode,
+    attempted: usize,
+    errors: &[String],
+) -> Result<InputCapabilities, String> {
+    if errors.is_empty() {
+        return Ok(InputCapabilities::global());
+    }
+    if errors.len() == attempted && keyboard_mode == KeyboardMode::Auto {
+        return Ok(InputCapabilities::focused(format!(
+            "no key could be registered globally: {}",
+            errors.join("; ")
+        )));
+    }
+    Err(errors.join("; "))
+}
+
 fn register_hotkeys(app: &AppHandle, state: &tauri::State<AppState>) -> Result<(), String> {
     unregister_hotkeys(app, state)?;
 
-    let bindings = {
+    let (bindings, keyboard_mode) = {
         let runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
-        runtime.collect_hotkeys()
+        let keyboard_mode = runtime
+            .config
+            .as_ref()
+            .map_or(KeyboardMode::Auto, |config| config.global.keyboard_mode);
+        (runtime.collect_hotkeys(), keyboard_mode)
     };
 
     let mut keyboard_action_map = HashMap::new();
     let mut gamepad_action_map = HashMap::new();
+    let mut keyboard_shortcuts = Vec::new();
     for binding in bindings {
-        if let Some(button) = binding.shortcut.strip_prefix("Gamepad:") {
-            gamepad_action_map.insert(button.to_string(), binding.action);
+        if is_gamepad_shortcut(&binding.shortcut) {
+            gamepad_action_map.insert(binding.shortcut.clone(), binding);
             continue;
         }
 
         let shortcut = Shortcut::from_str(&binding.shortcut)
             .map_err(|e| format!("Invalid shortcut '{}': {e}", binding.shortcut))?;
-        let shortcut_key = shortcut.to_string();
-        app.global_shortcut()
-            .register(shortcut)
-            .map_err(|e| format!("Failed to register '{}': {e}", binding.shortcut))?;
-        keyboard_action_map.insert(shortcut_key, binding.action);
+        keyboard_shortcuts.push((binding.shortcut.clone(), shortcut));
+        keyboard_action_map.insert(shortcut.to_string(), binding);
+    }
+
+    if !keyboard_shortcuts.is_empty() {
+        // Keys fall back to the focused window when the config asks for it or
+        // the session has no global shortcuts at all, which in "auto" is also
+        // assumed when not a single key registers.
+        let fallback = match keyboard_mode {
+            KeyboardMode::Focused => Some("keyboard_mode is \"focused\"".to_string()),
+            KeyboardMode::Auto => missing_global_shortcuts(),
+            KeyboardMode::Global => None,
+        };
+        let capabilities = match fallback {
+            Some(reason) => InputCapabilities::focused(reason),
+            None => {
+                let key_errors: Vec<String> = keyboard_shortcuts
+                    .iter()
+                    .filter_map(|(raw, shortcut)| {
+                        app.global_shortcut()
+                            .register(*shortcut)
+                            .err()
+                            .map(|e| format!("Failed to register '{raw}': {e}"))
+                    })
+                    .collect();
+                let capabilities = registered_capabilities(keyboard_mode, keyboard_shortcuts.len(), &key_errors);
+                if !matches!(&capabilities, Ok(capabilities) if capabilities.global_shortcuts) {
+                    let _ = app.global_shortcut().unregister_all();
+                }
+                capabilities?
+            }
+        };
+        set_input_capabilities(app, state, capabilities)?;
     }
 
     let mut keyboard_map = state
@@ -498,9 +3768,28 @@ fn register_hotkeys(app: &AppHandle, state: &tauri::State<AppState>) -> Result<(
 }
 
 fn unregister_hotkeys(app: &AppHandle, state: &tauri::State<AppState>) -> Result<(), String> {
-    app.global_shortcut()
-        .unregister_all()
-        .map_err(|e| format!("Failed to clear existing shortcuts: {e}"))?;
+    let global_shortcuts = state
+        .input_capabilities
+        .lock()
+        .map_err(|_| "Input capabilities lock poisoned".to_string())?
+        .global_shortcuts;
+    if let Err(e) = app.global_shortcut().unregister_all() {
+        if global_shortcuts {
+            return Err(format!("Failed to clear existing shortcuts: {e}"));
+        }
+    }
+
+    // unregister_all also dropped any keys grabbed by player select mode.
+    {
+        let mut player_select = state
+            .player_select
+            .lock()
+            .map_err(|_| "Player select lock poisoned".to_string())?;
+        if player_select.is_active() {
+            player_select.end();
+            let _ = app.emit(EVENT_PLAYER_SELECT, player_select.status());
+        }
+    }
 
     let mut map = state
         .action_by_shortcut
@@ -514,18 +3803,176 @@ fn unregister_hotkeys(app: &AppHandle, state: &tauri::State<AppState>) -> Result
         .map_err(|_| "Gamepad map lock poisoned".to_string())?;
     gamepad_map.clear();
 
+    // Running repeats hold on to the old bindings.
+    state
+        .held_inputs
+        .lock()
+        .map_err(|_| "Held inputs lock poisoned".to_string())?
+        .release_all();
+
+    Ok(())
+}
+
+fn set_input_capabilities(
+    app: &AppHandle,
+    state: &tauri::State<AppState>,
+    capabilities: InputCapabilities,
+) -> Result<(), String> {
+    let changed = {
+        let mut current = state
+            .input_capabilities
+            .lock()
+            .map_err(|_| "Input capabilities lock poisoned".to_string())?;
+        let changed = *current != capabilities;
+        *current = capabilities.clone();
+        changed
+    };
+    if changed {
+        app.emit(EVENT_CAPABILITIES, capabilities)
+            .map_err(|e| format!("Failed to emit input capabilities: {e}"))?;
+    }
     Ok(())
 }
 
-fn emit_snapshot(app: &AppHandle, runtime: &Arc<Mutex<RuntimeState>>) -> Result<(), String> {
-    let snapshot: UiSnapshot = {
-        let runtime = runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
+fn emit_snapshot(app: &AppHandle, state: &AppState) -> Result<(), String> {
+    // A frozen board keeps the display until the operator resumes.
+    if is_frozen(state) {
+        return Ok(());
+    }
+    emit_live_snapshot(app, state).inspect_err(|e| enter_freeze(app, state, e))
+}
+
+fn emit_live_snapshot(app: &AppHandle, state: &AppState) -> Result<(), String> {
+    // Holding the clock for the whole emit keeps sequence numbers in delivery order.
+    let mut clock = state
+        .snapshot_clock
+        .lock()
+        .map_err(|_| "Snapshot clock lock poisoned".to_string())?;
+    let mut snapshot: UiSnapshot = {
+        let runtime = state.runtime.lock().map_err(|_| "Runtime lock poisoned".to_string())?;
         runtime.snapshot()
     };
+    clock.stamp(&mut snapshot);
+    state.sync.publish(&snapshot);
+    state.led_matrix.submit(&snapshot);
+    state.webhooks.submit(&snapshot);
+    state.osc.submit(&snapshot);
+    state.obs.submit(&snapshot);
+    if let Ok(mut recorder) = state.recorder.lock() {
+        if let Err(e) = recorder.record(&snapshot) {
+            emit_error(app, &e);
+        }
+    }
+    if let Ok(mut narrator) = state.narrator.lock() {
+        if let Err(e) = narrator.narrate(&snapshot) {
+            emit_error(app, &e);
+        }
+    }
+    if let Ok(mut history) = state.number_history.lock() {
+        history.observe(&snapshot);
+    }
+    if let Ok(mut game_log) = state.game_log.lock() {
+        game_log.observe(&snapshot);
+    }
+    // A replica keeps the primary's board on screen, even while reconnecting.
+    if state.sync.is_mirroring() {
+        return Ok(());
+    }
     app.emit(EVENT_STATE_UPDATED, snapshot)
         .map_err(|e| format!("Failed to emit state update: {e}"))
 }
 
+/// With `freeze_on_error`, puts the last good board on screen in place of the
+/// live one and alerts the operator.
+fn enter_freeze(app: &AppHandle, state: &AppState, reason: &str) {
+    let last_good = watchdog::frozen_board(&state.runtime, &state.watchdog);
+    let snapshot = match state.freeze.lock() {
+        Ok(mut freeze) => {
+            if !freeze.enter(last_good, reason) {
+                return;
+            }
+            freeze.snapshot()
+        }
+        Err(_) => return,
+    };
+    if let Ok(mut reporter) = state.crash_reporter.lock() {
+        reporter.log(format!("froze output: {reason}"));
+    }
+    emit_freeze_status(app, state);
+    if let Some(snapshot) = snapshot {
+        emit_frozen_snapshot(app, state, snapshot);
+    }
+}
+
+fn emit_freeze_status(app: &AppHandle, state: &AppState) {
+    if let Ok(freeze) = state.freeze.lock() {
+        let _ = app.emit(EVENT_FREEZE, freeze.status());
+    }
+}
+
+/// Shows a frozen board without touching the live state, recorders, or
+/// observers, so nothing that may have failed is involved.
+fn emit_frozen_snapshot(app: &AppHandle, state: &AppState, mut snapshot: UiSnapshot) {
+    state
+        .snapshot_clock
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .stamp(&mut snapshot);
+    state.sync.publish(&snapshot);
+    state.led_matrix.submit(&snapshot);
+    if !state.sync.is_mirroring() {
+        let _ = app.emit(EVENT_STATE_UPDATED, snapshot);
+    }
+}
+
+/// Runs the frozen board's clocks, isolated from the timer thread that drives
+/// the live board.
+fn spawn_freeze_thread(app: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(Duration::from_millis(50));
+        let Some(state) = app.try_state::<AppState>() else {
+            continue;
+        };
+        let snapshot = match state.freeze.lock() {
+            Ok(mut freeze) => freeze.tick(),
+            Err(_) => continue,
+        };
+        if let Some(snapshot) = snapshot {
+            emit_frozen_snapshot(&app, &state, snapshot);
+        }
+    });
+}
+
 fn emit_error(app: &AppHandle, message: &str) {
+    if let Some(state) = app.try_state::<AppState>() {
+        if let Ok(mut reporter) = state.crash_reporter.lock() {
+            reporter.log(format!("error: {message}"));
+        }
+    }
     let _ = app.emit(EVENT_ERROR, message.to_string());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(count: usize) -> Vec<String> {
+        (0..count).map(|index| format!("Failed to register 'F{index}': taken")).collect()
+    }
+
+    #[test]
+    fn falls_back_to_the_focused_window_when_no_key_registers() {
+        let capabilities = registered_capabilities(KeyboardMode::Auto, 3, &errors(3)).unwrap();
+        assert!(!capabilities.global_shortcuts);
+        assert_eq!(capabilities.keyboard_mode, "focused");
+        assert!(capabilities.reason.unwrap().contains("'F2'"));
+    }
+
+    #[test]
+    fn fails_on_keys_another_program_holds() {
+        let error = registered_capabilities(KeyboardMode::Auto, 3, &errors(2)).unwrap_err();
+        assert!(error.contains("'F0'") && error.contains("'F1'"));
+        assert!(registered_capabilities(KeyboardMode::Global, 3, &errors(3)).is_err());
+        assert!(registered_capabilities(KeyboardMode::Auto, 3, &[]).unwrap().global_shortcuts);
+    }
+}
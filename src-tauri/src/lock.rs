@@ -0,0 +1,72 @@
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+const MAX_FAILED_ATTEMPTS: u32 = 3;
+const LOCKOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct LockStatus {
+    pub locked: bool,
+}
+
+/// Child lock for unattended boards. While locked, inputs that change the
+/// scoreboard are refused; the display itself keeps running. After
+/// `MAX_FAILED_ATTEMPTS` wrong PINs, further attempts are refused for `LOCKOUT`.
+#[derive(Debug, Default)]
+pub struct BoardLock {
+    locked: bool,
+    failed_attempts: u32,
+    retry_at: Option<Instant>,
+}
+
+impl BoardLock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    pub fn status(&self) -> LockStatus {
+        LockStatus { locked: self.locked }
+    }
+
+    /// Returns false if the board was already locked.
+    pub fn lock(&mut self) -> bool {
+        let changed = !self.locked;
+        self.locked = true;
+        changed
+    }
+
+    pub fn unlock(&mut self, pin: &str, attempt: &str, now: Instant) -> Result<(), String> {
+        if !self.locked {
+            return Ok(());
+        }
+        if let Some(retry_at) = self.retry_at {
+            if now < retry_at {
+                let seconds = retry_at.duration_since(now).as_secs().max(1);
+                return Err(format!("Too many incorrect PINs, try again in {seconds} s"));
+            }
+            self.retry_at = None;
+        }
+        if attempt.trim() != pin {
+            self.failed_attempts += 1;
+            if self.failed_attempts >= MAX_FAILED_ATTEMPTS {
+                self.failed_attempts = 0;
+                self.retry_at = Some(now + LOCKOUT);
+            }
+            return Err("Incorrect PIN".to_string());
+        }
+        self.locked = false;
+        self.failed_attempts = 0;
+        Ok(())
+    }
+
+    /// Unlocks without a PIN, for when the loaded config no longer defines one.
+    pub fn release(&mut self) -> bool {
+        let changed = self.locked;
+        *self = Self::default();
+        changed
+    }
+}
@@ -0,0 +1,252 @@
+use crate::state::{UiComponent, UiSnapshot};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// What was last narrated for a component, used to describe only changes.
+#[derive(Debug, Clone, PartialEq)]
+struct Narrated {
+    text: String,
+    bonus: bool,
+    double_bonus: bool,
+}
+
+struct ActiveNarration {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    previous: Option<HashMap<String, Narrated>>,
+}
+
+/// Writes scoreboard changes as plain sentences, one per line, for screen
+/// readers, braille displays, and venue systems that tail a text file.
+/// Images are skipped and timers are only mentioned on whole minutes and at zero.
+pub struct Narrator {
+    active: Option<ActiveNarration>,
+}
+
+impl Narrator {
+    pub fn new() -> Self {
+        Self { active: None }
+    }
+
+    /// Starts appending to `path`, so a venue system can keep following the
+    /// same file across restarts.
+    pub fn start(&mut self, path: &Path) -> Result<(), String> {
+        if let Some(active) = &self.active {
+            return Err(format!("Already narrating to {}", active.path.display()));
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed creating narration directory {}: {e}", parent.display()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Failed opening narration file {}: {e}", path.display()))?;
+        self.active = Some(ActiveNarration {
+            path: path.to_path_buf(),
+            writer: BufWriter::new(file),
+            previous: None,
+        });
+        Ok(())
+    }
+
+    /// Appends a line that is not derived from a snapshot, such as a spoken
+    /// countdown cue. Does nothing while not narrating.
+    pub fn say(&mut self, line: &str) -> Result<(), String> {
+        let Some(active) = self.active.as_mut() else {
+            return Ok(());
+        };
+        let result = writeln!(active.writer, "{line}").and_then(|_| active.writer.flush());
+        if let Err(e) = result {
+            let path = active.path.clone();
+            self.active = None;
+            return Err(format!("Narration stopped, failed writing {}: {e}", path.display()));
+        }
+        Ok(())
+    }
+
+    pub fn stop(&mut self) -> Result<Option<PathBuf>, String> {
+        let Some(mut active) = self.active.take() else {
+            return Ok(None);
+        };
+        active
+            .writer
+            .flush()
+            .map_err(|e| format!("Failed writing narration {}: {e}", active.path.display()))?;
+        Ok(Some(active.path))
+    }
+
+    /// Describes what changed since the previous snapshot. The first snapshot
+    /// after starting is summarized in a single line. A write failure ends the
+    /// narration so the error is reported once.
+    pub fn narrate(&mut self, snapshot: &UiSnapshot) -> Result<(), String> {
+        let Some(active) = self.active.as_mut() else {
+            return Ok(());
+        };
+
+        let current: HashMap<String, Narrated> = snapshot
+            .components
+            .iter()
+            .filter_map(|component| Some((component.id.clone(), narrated(component)?)))
+            .collect();
+        let lines = match &active.previous {
+            None => summary_line(snapshot, &current).into_iter().collect(),
+            Some(previous) => snapshot
+                .components
+                .iter()
+                .filter_map(|component| {
+                    let now = current.get(&component.id)?;
+                    Some(change_sentences(component, previous.get(&component.id), now))
+                })
+                .flatten()
+                .collect::<Vec<_>>(),
+        };
+        active.previous = Some(current);
+
+        let result = lines
+            .iter()
+            .try_for_each(|line| writeln!(active.writer, "{line}"))
+            .and_then(|_| active.writer.flush());
+        if let Err(e) = result {
+            let path = active.path.clone();
+            self.active = None;
+            return Err(format!("Narration stopped, failed writing {}: {e}", path.display()));
+        }
+        Ok(())
+    }
+}
+
+pub fn default_narration_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("narration.txt")
+}
+
+fn narrated(component: &UiComponent) -> Option<Narrated> {
+    Some(Narrated {
+        text: component.text.clone()?,
+        bonus: component.bonus,
+        double_bonus: component.double_bonus,
+    })
+}
+
+fn summary_line(snapshot: &UiSnapshot, current: &HashMap<String, Narrated>) -> Option<String> {
+    let parts: Vec<String> = snapshot
+        .components
+        .iter()
+        .filter_map(|component| {
+            let narrated = current.get(&component.id)?;
+            let text = narrated.text.trim();
+            if text.is_empty() {
+                return None;
+            }
+            let value = match component.component_type.as_str() {
+                "timer" => spoken_time(text),
+                "possession" => spoken_arrow(text).to_string(),
+                // Sponsor messages are not part of the score.
+                "ticker" => return None,
+                _ => text.to_string(),
+            };
+            Some(format!("{} {value}", spoken_name(&component.id)))
+        })
+        .collect();
+    if parts.is_empty() {
+        return None;
+    }
+    Some(format!("Scoreboard: {}.", parts.join(", ")))
+}
+
+fn change_sentences(component: &UiComponent, previous: Option<&Narrated>, now: &Narrated) -> Vec<String> {
+    let name = spoken_name(&component.id);
+    let mut sentences = Vec::new();
+    let text_changed = previous.is_none_or(|previous| previous.text != now.text);
+    let text = now.text.trim();
+
+    if text_changed {
+        match component.component_type.as_str() {
+            "timer" => {
+                let seconds = timer_seconds(text);
+                let previous_seconds = previous.and_then(|previous| timer_seconds(&previous.text));
+                match seconds {
+                    Some(0) if previous_seconds != Some(0) => sentences.push(format!("{name}: time expired.")),
+                    Some(seconds) if seconds % 60 == 0 && previous_seconds.is_some_and(|p| p > seconds) => {
+                        sentences.push(format!("{name}: {} left.", spoken_time(text)))
+                    }
+                    _ => {}
+                }
+            }
+            // Percentages shift every few seconds; they are only read in the summary.
+            "possession-time" => {}
+            "ticker" => {}
+            "label" | "derived" | "possession" if text.is_empty() => {}
+            "label" | "derived" => sentences.push(format!("{name}: {text}.")),
+            "possession" => sentences.push(format!("{name} is now {}.", spoken_arrow(text))),
+            _ => sentences.push(format!("{name} is now {text}.")),
+        }
+    }
+
+    let was_bonus = previous.is_some_and(|previous| previous.bonus);
+    let was_double_bonus = previous.is_some_and(|previous| previous.double_bonus);
+    if now.double_bonus && !was_double_bonus {
+        sentences.push(format!("{name} reached the double bonus."));
+    } else if now.bonus && !was_bonus {
+        sentences.push(format!("{name} reached the bonus."));
+    }
+    sentences
+}
+
+/// Turns a component id such as `home_score` into "Home score".
+fn spoken_name(id: &str) -> String {
+    let words = id.replace(['_', '-'], " ");
+    let mut chars = words.trim().chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => id.to_string(),
+    }
+}
+
+/// The default possession labels are arrows, which screen readers announce poorly.
+fn spoken_arrow(text: &str) -> &str {
+    match text {
+        "\u{25C0}" => "left",
+        "\u{25B6}" => "right",
+        other => other,
+    }
+}
+
+/// Reads a displayed timer value ("MM:SS", "H:MM:SS", or "S.t") as whole seconds.
+fn timer_seconds(text: &str) -> Option<u64> {
+    let whole = text.trim().split('.').next()?;
+    whole
+        .split(':')
+        .try_fold(0u64, |total, part| Some(total * 60 + part.parse::<u64>().ok()?))
+}
+
+fn spoken_time(text: &str) -> String {
+    match timer_seconds(text) {
+        Some(seconds) => spoken_seconds(seconds),
+        None => text.to_string(),
+    }
+}
+
+/// A timer's `voice_cues` entry as it is read out, e.g. "1 minute." or "30 seconds.".
+pub fn spoken_countdown(cue_ms: i64) -> String {
+    let mut sentence = spoken_seconds((cue_ms / 1000).max(0) as u64);
+    sentence.push('.');
+    sentence
+}
+
+fn spoken_seconds(seconds: u64) -> String {
+    let units = [("hour", seconds / 3600), ("minute", seconds % 3600 / 60), ("second", seconds % 60)];
+    let parts: Vec<String> = units
+        .iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(unit, count)| format!("{count} {unit}{}", if *count == 1 { "" } else { "s" }))
+        .collect();
+    if parts.is_empty() {
+        "0 seconds".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
@@ -0,0 +1,378 @@
+use crate::config::{ObsEvent, ObsSettings, ObsTrigger};
+use crate::led_matrix::ErrorSink;
+use crate::state::UiSnapshot;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tungstenite::{Message, WebSocket};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+const DEFAULT_OBS_PORT: u16 = 4455;
+/// obs-websocket 5's protocol version.
+const RPC_VERSION: u64 = 1;
+
+const OP_HELLO: u64 = 0;
+const OP_IDENTIFY: u64 = 1;
+const OP_IDENTIFIED: u64 = 2;
+const OP_REQUEST: u64 = 6;
+const OP_REQUEST_RESPONSE: u64 = 7;
+
+enum ObsCommand {
+    Configure {
+        settings: Option<ObsSettings>,
+        /// Labels of each period component in order, to tell an advance from an undo.
+        period_labels: HashMap<String, Vec<String>>,
+        on_error: ErrorSink,
+    },
+    Snapshot(UiSnapshot),
+    TimerExpired(String),
+}
+
+/// Drives OBS Studio over obs-websocket from a background thread: configured
+/// triggers switch the program scene or show and hide sources when a timer
+/// expires or a period advances.
+pub struct ObsClient {
+    commands: Mutex<Sender<ObsCommand>>,
+}
+
+impl ObsClient {
+    pub fn new() -> Self {
+        let (commands, receiver) = mpsc::channel();
+        thread::spawn(move || run_client(receiver));
+        Self {
+            commands: Mutex::new(commands),
+        }
+    }
+
+    pub fn configure(
+        &self,
+        settings: Option<ObsSettings>,
+        period_labels: HashMap<String, Vec<String>>,
+        on_error: ErrorSink,
+    ) {
+        self.send(ObsCommand::Configure {
+            settings,
+            period_labels,
+            on_error,
+        });
+    }
+
+    pub fn submit(&self, snapshot: &UiSnapshot) {
+        self.send(ObsCommand::Snapshot(snapshot.clone()));
+    }
+
+    pub fn timer_expired(&self, id: &str) {
+        self.send(ObsCommand::TimerExpired(id.to_string()));
+    }
+
+    fn send(&self, command: ObsCommand) {
+        if let Ok(commands) = self.commands.lock() {
+            let _ = commands.send(command);
+        }
+    }
+}
+
+enum ObsError {
+    /// The connection failed or dropped; worth one retry on a fresh one.
+    Connection(String),
+    /// OBS answered but refused the request, e.g. for a missing scene.
+    Refused(String),
+}
+
+struct Client {
+    settings: Option<ObsSettings>,
+    period_labels: HashMap<String, Vec<String>>,
+    on_error: Option<ErrorSink>,
+    connection: Option<Connection>,
+    /// Last label per period; `None` until the first snapshot after configuring,
+    /// so a reload does not count as an advance.
+    periods: Option<HashMap<String, String>>,
+    connection_error_reported: bool,
+}
+
+fn run_client(receiver: Receiver<ObsCommand>) {
+    let mut client = Client {
+        settings: None,
+        period_labels: HashMap::new(),
+        on_error: None,
+        connection: None,
+        periods: None,
+        connection_error_reported: false,
+    };
+    while let Ok(command) = receiver.recv() {
+        client.handle(command);
+    }
+}
+
+impl Client {
+    fn handle(&mut self, command: ObsCommand) {
+        match command {
+            ObsCommand::Configure {
+                settings,
+                period_labels,
+                on_error,
+            } => self.configure(settings, period_labels, on_error),
+            ObsCommand::Snapshot(snapshot) => self.check_periods(&snapshot),
+            ObsCommand::TimerExpired(id) => {
+                let triggers = self.triggers_where(|event| matches!(event, ObsEvent::TimerExpired { timer } if *timer == id));
+                for trigger in triggers {
+                    self.fire(&trigger);
+                }
+            }
+        }
+    }
+
+    fn configure(
+        &mut self,
+        settings: Option<ObsSettings>,
+        period_labels: HashMap<String, Vec<String>>,
+        on_error: ErrorSink,
+    ) {
+        let reconnect = settings.as_ref().map(|s| (&s.url, &s.password))
+            != self.settings.as_ref().map(|s| (&s.url, &s.password));
+        self.settings = settings;
+        self.period_labels = period_labels;
+        self.on_error = Some(on_error);
+        self.periods = None;
+        if !reconnect {
+            return;
+        }
+        self.connection = None;
+        self.connection_error_reported = false;
+        // Connecting right away shows a wrong address or password before the first cue.
+        if let Some(settings) = &self.settings {
+            match Connection::open(settings) {
+                Ok(connection) => self.connection = Some(connection),
+                Err(e) => self.report_connection_error(e),
+            }
+        }
+    }
+
+    fn check_periods(&mut self, snapshot: &UiSnapshot) {
+        if self.settings.is_none() {
+            return;
+        }
+        let current: HashMap<String, String> = snapshot
+            .components
+            .iter()
+            .filter(|component| self.period_labels.contains_key(&component.id))
+            .filter_map(|component| Some((component.id.clone(), component.text.clone()?)))
+            .collect();
+        let Some(previous) = self.periods.replace(current.clone()) else {
+            return;
+        };
+        for (period, label) in &current {
+            let Some(before) = previous.get(period).filter(|before| *before != label) else {
+                continue;
+            };
+            let labels = &self.period_labels[period];
+            let position = |label: &String| labels.iter().position(|candidate| candidate == label);
+            if position(label) <= position(before) {
+                continue;
+            }
+            let triggers = self.triggers_where(|event| match event {
+                ObsEvent::PeriodAdvanced { period: id, to } => id == period && to.as_ref().is_none_or(|to| to == label),
+                ObsEvent::TimerExpired { .. } => false,
+            });
+            for trigger in triggers {
+                self.fire(&trigger);
+            }
+        }
+    }
+
+    fn triggers_where(&self, matches: impl Fn(&ObsEvent) -> bool) -> Vec<ObsTrigger> {
+        self.settings
+            .iter()
+            .flat_map(|settings| &settings.triggers)
+            .filter(|trigger| matches(&trigger.event))
+            .cloned()
+            .collect()
+    }
+
+    fn fire(&mut self, trigger: &ObsTrigger) {
+        // OBS closing an idle connection is only noticed on use, so a cue
+        // that fails on an old connection is sent once more on a new one.
+        let reused = self.connection.is_some();
+        let mut result = self.fire_once(trigger);
+        if reused && matches!(result, Err(ObsError::Connection(_))) {
+            result = self.fire_once(trigger);
+        }
+        match result {
+            Ok(()) => self.connection_error_reported = false,
+            Err(ObsError::Connection(e)) => self.report_connection_error(e),
+            Err(ObsError::Refused(e)) => self.report(format!("OBS trigger '{}' failed: {e}", trigger.name)),
+        }
+    }
+
+    fn fire_once(&mut self, trigger: &ObsTrigger) -> Result<(), ObsError> {
+        let connection = match &mut self.connection {
+            Some(connection) => connection,
+            None => {
+                let Some(settings) = &self.settings else {
+                    return Ok(());
+                };
+                let connection = Connection::open(settings).map_err(ObsError::Connection)?;
+                self.connection.insert(connection)
+            }
+        };
+        let result = connection.apply(trigger);
+        if matches!(result, Err(ObsError::Connection(_))) {
+            self.connection = None;
+        }
+        result
+    }
+
+    /// Reported once until OBS answers again, since every cue retries.
+    fn report_connection_error(&mut self, message: String) {
+        if !self.connection_error_reported {
+            self.connection_error_reported = true;
+            self.report(message);
+        }
+    }
+
+    fn report(&self, message: String) {
+        if let Some(on_error) = &self.on_error {
+            on_error(message);
+        }
+    }
+}
+
+struct Connection {
+    socket: WebSocket<TcpStream>,
+    next_request: u64,
+}
+
+impl Connection {
+    /// Connects and identifies, answering the password challenge when OBS sends one.
+    fn open(settings: &ObsSettings) -> Result<Self, String> {
+        let failed = |e: String| format!("Failed connecting to OBS at {}: {e}", settings.url);
+        let stream = connect(&settings.url).map_err(failed)?;
+        let (socket, _) = tungstenite::client(settings.url.as_str(), stream).map_err(|e| failed(e.to_string()))?;
+        let mut connection = Self {
+            socket,
+            next_request: 0,
+        };
+
+        let hello = connection.read_op(OP_HELLO).map_err(failed)?;
+        let mut identify = json!({ "rpcVersion": RPC_VERSION, "eventSubscriptions": 0 });
+        if let Some(challenge) = hello.get("authentication") {
+            let Some(password) = &settings.password else {
+                return Err(failed("OBS requires a password; set 'global.obs' password".to_string()));
+            };
+            let salt = challenge.get("salt").and_then(Value::as_str).unwrap_or_default();
+            let challenge = challenge.get("challenge").and_then(Value::as_str).unwrap_or_default();
+            identify["authentication"] = json!(authentication(password, salt, challenge));
+        }
+        connection.write(OP_IDENTIFY, identify).map_err(failed)?;
+        // OBS closes the connection instead of answering a wrong password.
+        connection
+            .read_op(OP_IDENTIFIED)
+            .map_err(|_| failed("OBS rejected the connection; check the password".to_string()))?;
+        Ok(connection)
+    }
+
+    /// Shows or hides the source first, so the new scene cuts in already set up.
+    fn apply(&mut self, trigger: &ObsTrigger) -> Result<(), ObsError> {
+        if let Some(source) = &trigger.source {
+            let item = self.request(
+                "GetSceneItemId",
+                json!({ "sceneName": source.scene, "sourceName": source.source }),
+            )?;
+            let item_id = item.get("sceneItemId").and_then(Value::as_i64).ok_or_else(|| {
+                ObsError::Refused(format!("no source '{}' in scene '{}'", source.source, source.scene))
+            })?;
+            self.request(
+                "SetSceneItemEnabled",
+                json!({ "sceneName": source.scene, "sceneItemId": item_id, "sceneItemEnabled": source.visible }),
+            )?;
+        }
+        if let Some(scene) = &trigger.scene {
+            self.request("SetCurrentProgramScene", json!({ "sceneName": scene }))?;
+        }
+        Ok(())
+    }
+
+    fn request(&mut self, request_type: &str, data: Value) -> Result<Value, ObsError> {
+        self.next_request += 1;
+        let request_id = self.next_request.to_string();
+        self.write(
+            OP_REQUEST,
+            json!({ "requestType": request_type, "requestId": request_id, "requestData": data }),
+        )
+        .map_err(ObsError::Connection)?;
+        loop {
+            let response = self.read_op(OP_REQUEST_RESPONSE).map_err(ObsError::Connection)?;
+            if response.get("requestId").and_then(Value::as_str) != Some(request_id.as_str()) {
+                continue;
+            }
+            let status = response.get("requestStatus");
+            if status.and_then(|status| status.get("result")).and_then(Value::as_bool) == Some(true) {
+                return Ok(response.get("responseData").cloned().unwrap_or(Value::Null));
+            }
+            let comment = status
+                .and_then(|status| status.get("comment"))
+                .and_then(Value::as_str)
+                .unwrap_or("no reason given");
+            return Err(ObsError::Refused(format!("{request_type}: {comment}")));
+        }
+    }
+
+    fn write(&mut self, op: u64, data: Value) -> Result<(), String> {
+        let message = json!({ "op": op, "d": data }).to_string();
+        self.socket.send(Message::text(message)).map_err(|e| e.to_string())
+    }
+
+    /// Reads until a message with `op` arrives and returns its `d`; others,
+    /// such as events, are skipped.
+    fn read_op(&mut self, op: u64) -> Result<Value, String> {
+        loop {
+            let message = self.socket.read().map_err(|e| e.to_string())?;
+            let Message::Text(text) = message else {
+                continue;
+            };
+            let Ok(parsed) = serde_json::from_str::<Value>(text.as_str()) else {
+                continue;
+            };
+            if parsed.get("op").and_then(Value::as_u64) == Some(op) {
+                return Ok(parsed.get("d").cloned().unwrap_or(Value::Null));
+            }
+        }
+    }
+}
+
+/// Opens the TCP connection for a `ws://host[:port][/path]` URL with timeouts,
+/// so a missing OBS cannot hold up the cues behind it.
+fn connect(url: &str) -> Result<TcpStream, String> {
+    let authority = url
+        .strip_prefix("ws://")
+        .and_then(|rest| rest.split('/').next())
+        .ok_or_else(|| "the url must start with ws://".to_string())?;
+    let host_port = if authority.rsplit_once(':').is_some_and(|(_, port)| port.parse::<u16>().is_ok()) {
+        authority.to_string()
+    } else {
+        format!("{authority}:{DEFAULT_OBS_PORT}")
+    };
+    let address = host_port
+        .to_socket_addrs()
+        .map_err(|e| e.to_string())?
+        .next()
+        .ok_or_else(|| format!("no address found for {host_port}"))?;
+    let stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT).map_err(|e| e.to_string())?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT)).map_err(|e| e.to_string())?;
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT)).map_err(|e| e.to_string())?;
+    Ok(stream)
+}
+
+/// obs-websocket's challenge answer: base64(sha256(base64(sha256(password + salt)) + challenge)).
+fn authentication(password: &str, salt: &str, challenge: &str) -> String {
+    let secret = BASE64.encode(Sha256::digest(format!("{password}{salt}")));
+    BASE64.encode(Sha256::digest(format!("{secret}{challenge}")))
+}
@@ -0,0 +1,505 @@
+use crate::config::{OscSettings, RemoteRole};
+use crate::led_matrix::ErrorSink;
+use crate::remote::RecentClients;
+use crate::state::{TMinusCue, UiSnapshot};
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, SocketAddr, UdpSocket};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// How long the bridge waits for a packet before checking for new snapshots.
+const RECEIVE_POLL: Duration = Duration::from_millis(20);
+const MAX_PACKET_BYTES: usize = 4096;
+const MAX_BUNDLE_DEPTH: usize = 4;
+const MAX_SUBSCRIBERS: usize = 16;
+const SUBSCRIBE: &str = "subscribe";
+const UNSUBSCRIBE: &str = "unsubscribe";
+
+/// Fires the action with the given `trigger_action` name if the sender's role may.
+pub type OscHandler = Arc<dyn Fn(&str, RemoteRole) -> Result<(), String> + Send + Sync>;
+
+enum OscCommand {
+    Configure {
+        settings: Option<OscSettings>,
+        handler: OscHandler,
+        on_error: ErrorSink,
+    },
+    Snapshot(UiSnapshot),
+    TMinus(TMinusCue),
+}
+
+/// Speaks OSC over UDP from a background thread: messages under the
+/// configured prefix fire actions, and every change to a component's text is
+/// sent to the `send_to` targets and to hosts that subscribed at runtime.
+pub struct OscBridge {
+    commands: Mutex<Sender<OscCommand>>,
+}
+
+impl OscBridge {
+    pub fn new(clients: Arc<RecentClients>) -> Self {
+        let (commands, receiver) = mpsc::channel();
+        thread::spawn(move || run_bridge(receiver, clients));
+        Self {
+            commands: Mutex::new(commands),
+        }
+    }
+
+    pub fn configure(&self, settings: Option<OscSettings>, handler: OscHandler, on_error: ErrorSink) {
+        self.send(OscCommand::Configure {
+            settings,
+            handler,
+            on_error,
+        });
+    }
+
+    pub fn submit(&self, snapshot: &UiSnapshot) {
+        self.send(OscCommand::Snapshot(snapshot.clone()));
+    }
+
+    pub fn t_minus(&self, cue: &TMinusCue) {
+        self.send(OscCommand::TMinus(cue.clone()));
+    }
+
+    fn send(&self, command: OscCommand) {
+        if let Ok(commands) = self.commands.lock() {
+            let _ = commands.send(command);
+        }
+    }
+}
+
+struct Bridge {
+    settings: Option<OscSettings>,
+    socket: Option<UdpSocket>,
+    handler: Option<OscHandler>,
+    on_error: Option<ErrorSink>,
+    /// Hosts added with `<prefix>/subscribe`; kept until the socket is rebound.
+    subscribers: Vec<SocketAddr>,
+    /// Last text sent per component.
+    values: HashMap<String, String>,
+    send_error_reported: bool,
+    /// Senders outside `allow` already reported under the current config.
+    refused: HashSet<IpAddr>,
+    clients: Arc<RecentClients>,
+}
+
+fn run_bridge(receiver: Receiver<OscCommand>, clients: Arc<RecentClients>) {
+    let mut bridge = Bridge {
+        settings: None,
+        socket: None,
+        handler: None,
+        on_error: None,
+        subscribers: Vec::new(),
+        values: HashMap::new(),
+        send_error_reported: false,
+        refused: HashSet::new(),
+        clients,
+    };
+
+    loop {
+        // Without a socket there is nothing to poll, so wait for the next command.
+        let command = if bridge.socket.is_some() {
+            match receiver.try_recv() {
+                Ok(command) => Some(command),
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => return,
+            }
+        } else {
+            match receiver.recv() {
+                Ok(command) => Some(command),
+                Err(_) => return,
+            }
+        };
+        match command {
+            Some(command) => bridge.handle(command),
+            None => bridge.receive(),
+        }
+    }
+}
+
+impl Bridge {
+    fn handle(&mut self, command: OscCommand) {
+        match command {
+            OscCommand::Configure {
+                settings,
+                handler,
+                on_error,
+            } => {
+                self.handler = Some(handler);
+                self.on_error = Some(on_error);
+                self.configure(settings);
+            }
+            OscCommand::Snapshot(snapshot) => self.send_changes(&snapshot),
+            OscCommand::TMinus(cue) => self.send_t_minus(&cue),
+        }
+    }
+
+    fn configure(&mut self, settings: Option<OscSettings>) {
+        // A reload resends every value, so consoles pick up renamed or new components.
+        self.values.clear();
+        self.send_error_reported = false;
+        self.refused.clear();
+        let same_socket = self.socket.is_some()
+            && match (&self.settings, &settings) {
+                (Some(current), Some(next)) => current.bind == next.bind && current.port == next.port,
+                _ => false,
+            };
+        self.settings = settings;
+        if same_socket {
+            return;
+        }
+
+        self.socket = None;
+        self.subscribers.clear();
+        self.clients.set_subscribed(Vec::new());
+        let Some(settings) = &self.settings else {
+            return;
+        };
+        let address = format!("{}:{}", settings.bind, settings.port.unwrap_or(0));
+        let socket = UdpSocket::bind(&address).and_then(|socket| {
+            socket.set_read_timeout(Some(RECEIVE_POLL))?;
+            Ok(socket)
+        });
+        match socket {
+            Ok(socket) => self.socket = Some(socket),
+            Err(e) => self.report(format!("Failed opening OSC on {address}: {e}")),
+        }
+    }
+
+    fn send_changes(&mut self, snapshot: &UiSnapshot) {
+        let Some(settings) = &self.settings else {
+            return;
+        };
+        let changes: Vec<(String, String)> = snapshot
+            .components
+            .iter()
+            .filter_map(|component| {
+                let text = component.text.as_ref()?;
+                (self.values.get(&component.id) != Some(text)).then(|| (component.id.clone(), text.clone()))
+            })
+            .collect();
+        let targets: Vec<SocketAddr> = settings.send_to.iter().chain(&self.subscribers).copied().collect();
+        let packets: Vec<Vec<u8>> = changes
+            .iter()
+            .map(|(id, text)| value_message(&settings.prefix, id, text))
+            .collect();
+        self.values.extend(changes);
+        for packet in packets {
+            for target in &targets {
+                self.send_packet(&packet, *target);
+            }
+        }
+    }
+
+    fn send_t_minus(&mut self, cue: &TMinusCue) {
+        let Some(settings) = &self.settings else {
+            return;
+        };
+        let packet = t_minus_message(&settings.prefix, cue);
+        let targets: Vec<SocketAddr> = settings.send_to.iter().chain(&self.subscribers).copied().collect();
+        for target in targets {
+            self.send_packet(&packet, target);
+        }
+    }
+
+    fn send_packet(&mut self, packet: &[u8], target: SocketAddr) {
+        let Some(socket) = &self.socket else {
+            return;
+        };
+        if let Err(e) = socket.send_to(packet, target) {
+            // Reported once per config, since every value change would repeat it.
+            if !self.send_error_reported {
+                self.send_error_reported = true;
+                self.report(format!("Failed sending OSC to {target}: {e}"));
+            }
+        }
+    }
+
+    fn receive(&mut self) {
+        let Some(socket) = &self.socket else {
+            return;
+        };
+        let mut buffer = [0u8; MAX_PACKET_BYTES];
+        let Ok((length, sender)) = socket.recv_from(&mut buffer) else {
+            return;
+        };
+        let Some(settings) = &self.settings else {
+            return;
+        };
+        if settings.port.is_none() {
+            return;
+        }
+        // Subscribing is refused too, so updates only go to hosts the config trusts
+        // even when a packet's sender address is forged.
+        let Some(role) = settings.role_for(sender.ip()) else {
+            if self.refused.insert(sender.ip()) {
+                self.report(format!("OSC from {} ignored; it is not listed in allow", sender.ip()));
+            }
+            return;
+        };
+        let prefix = format!("{}/", settings.prefix);
+
+        let mut messages = Vec::new();
+        parse_packet(&buffer[..length], 0, &mut messages);
+        for message in messages {
+            // Other OSC traffic on the network is not for the board.
+            let Some(path) = message.address.strip_prefix(&prefix) else {
+                continue;
+            };
+            self.clients.record(sender.ip());
+            match path {
+                SUBSCRIBE => self.subscribe(subscriber_address(sender, message.number)),
+                UNSUBSCRIBE => {
+                    let address = subscriber_address(sender, message.number);
+                    self.subscribers.retain(|subscriber| *subscriber != address);
+                    self.publish_subscribers();
+                }
+                // Buttons on control surfaces send 1 when pressed and 0 when released.
+                _ if message.number == Some(0.0) => {}
+                _ => {
+                    let name = action_name(path);
+                    let result = match &self.handler {
+                        Some(handler) => handler(&name, role),
+                        None => Ok(()),
+                    };
+                    if let Err(e) = result {
+                        self.report(format!("OSC {}: {e}", message.address));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Adds a host and sends it every current value.
+    fn subscribe(&mut self, address: SocketAddr) {
+        if !self.subscribers.contains(&address) {
+            if self.subscribers.len() >= MAX_SUBSCRIBERS {
+                self.report(format!(
+                    "OSC subscribe from {address} ignored; at most {MAX_SUBSCRIBERS} hosts can subscribe"
+                ));
+                return;
+            }
+            self.subscribers.push(address);
+            self.publish_subscribers();
+        }
+        let Some(settings) = &self.settings else {
+            return;
+        };
+        let packets: Vec<Vec<u8>> = self
+            .values
+            .iter()
+            .map(|(id, text)| value_message(&settings.prefix, id, text))
+            .collect();
+        for packet in packets {
+            self.send_packet(&packet, address);
+        }
+    }
+
+    fn publish_subscribers(&self) {
+        self.clients
+            .set_subscribed(self.subscribers.iter().map(SocketAddr::ip).collect());
+    }
+
+    fn report(&self, message: String) {
+        if let Some(on_error) = &self.on_error {
+            on_error(message);
+        }
+    }
+}
+
+/// The sender's address, on the port given as the message's argument if any.
+fn subscriber_address(sender: SocketAddr, port: Option<f64>) -> SocketAddr {
+    match port {
+        Some(port) if (1.0..=65535.0).contains(&port) => SocketAddr::new(sender.ip(), port as u16),
+        _ => sender,
+    }
+}
+
+/// Turns `home_score/+1` into `home_score.increase`, the name `trigger_action` takes.
+fn action_name(path: &str) -> String {
+    path.split('/')
+        .map(|segment| match segment {
+            "+1" => "increase",
+            "-1" => "decrease",
+            segment => segment,
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+struct OscMessage {
+    address: String,
+    /// The first argument, when it is a number or boolean.
+    number: Option<f64>,
+}
+
+fn parse_packet(data: &[u8], depth: usize, messages: &mut Vec<OscMessage>) {
+    if let Some(elements) = data.strip_prefix(b"#bundle\0") {
+        if depth >= MAX_BUNDLE_DEPTH || elements.len() < 8 {
+            return;
+        }
+        // Bundles are applied at once; the time tag is not honored.
+        let mut rest = &elements[8..];
+        while rest.len() >= 4 {
+            let size = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+            let Some(element) = rest.get(4..4 + size) else {
+                return;
+            };
+            parse_packet(element, depth + 1, messages);
+            rest = &rest[4 + size..];
+        }
+        return;
+    }
+
+    let mut position = 0;
+    let Some(address) = read_string(data, &mut position) else {
+        return;
+    };
+    if !address.starts_with('/') {
+        return;
+    }
+    let number = read_string(data, &mut position)
+        .and_then(|tags| tags.strip_prefix(',')?.chars().next())
+        .and_then(|tag| {
+            let bytes = data.get(position..position + 4);
+            match tag {
+                'i' => bytes.map(|b| i32::from_be_bytes([b[0], b[1], b[2], b[3]]).into()),
+                'f' => bytes.map(|b| f32::from_be_bytes([b[0], b[1], b[2], b[3]]).into()),
+                'T' => Some(1.0),
+                'F' => Some(0.0),
+                _ => None,
+            }
+        });
+    messages.push(OscMessage {
+        address: address.to_string(),
+        number,
+    });
+}
+
+/// Reads a null-terminated string padded to four bytes.
+fn read_string<'a>(data: &'a [u8], position: &mut usize) -> Option<&'a str> {
+    let rest = data.get(*position..)?;
+    let end = rest.iter().position(|byte| *byte == 0)?;
+    let text = std::str::from_utf8(&rest[..end]).ok()?;
+    *position += (end + 4) & !3;
+    Some(text)
+}
+
+/// `<prefix>/<id>` with the text as its argument; whole numbers go out as
+/// integers so consoles can use them directly.
+fn value_message(prefix: &str, id: &str, text: &str) -> Vec<u8> {
+    let mut packet = Vec::new();
+    write_string(&mut packet, &format!("{prefix}/{id}"));
+    match text.parse::<i32>() {
+        Ok(number) => {
+            write_string(&mut packet, ",i");
+            packet.extend_from_slice(&number.to_be_bytes());
+        }
+        Err(_) => {
+            write_string(&mut packet, ",s");
+            write_string(&mut packet, text);
+        }
+    }
+    packet
+}
+
+/// `<prefix>/<id>/t_minus` with the offset and the time actually left, in ms.
+fn t_minus_message(prefix: &str, cue: &TMinusCue) -> Vec<u8> {
+    let mut packet = Vec::new();
+    write_string(&mut packet, &format!("{prefix}/{}/t_minus", cue.id));
+    write_string(&mut packet, ",ii");
+    for value in [cue.offset_ms, cue.remaining_ms] {
+        packet.extend_from_slice(&(value.clamp(0, i32::MAX.into()) as i32).to_be_bytes());
+    }
+    packet
+}
+
+fn write_string(packet: &mut Vec<u8>, text: &str) {
+    packet.extend_from_slice(text.as_bytes());
+    packet.push(0);
+    while !packet.len().is_multiple_of(4) {
+        packet.push(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(address: &str, tags: &str, argument: &[u8]) -> Vec<u8> {
+        let mut packet = Vec::new();
+        write_string(&mut packet, address);
+        write_string(&mut packet, tags);
+        packet.extend_from_slice(argument);
+        packet
+    }
+
+    fn bundle(elements: &[Vec<u8>]) -> Vec<u8> {
+        let mut packet = b"#bundle\0".to_vec();
+        packet.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 1]);
+        for element in elements {
+            packet.extend_from_slice(&(element.len() as u32).to_be_bytes());
+            packet.extend_from_slice(element);
+        }
+        packet
+    }
+
+    fn parse(data: &[u8]) -> Vec<(String, Option<f64>)> {
+        let mut messages = Vec::new();
+        parse_packet(data, 0, &mut messages);
+        messages.into_iter().map(|message| (message.address, message.number)).collect()
+    }
+
+    #[test]
+    fn reads_the_first_numeric_argument() {
+        assert_eq!(parse(&message("/home_score/+1", ",", &[])), vec![("/home_score/+1".to_string(), None)]);
+        assert_eq!(parse(&message("/a", ",i", &(-3i32).to_be_bytes())), vec![("/a".to_string(), Some(-3.0))]);
+        assert_eq!(parse(&message("/a", ",f", &1.5f32.to_be_bytes())), vec![("/a".to_string(), Some(1.5))]);
+        assert_eq!(parse(&message("/a", ",T", &[])), vec![("/a".to_string(), Some(1.0))]);
+        assert_eq!(parse(&message("/a", ",F", &[])), vec![("/a".to_string(), Some(0.0))]);
+        assert_eq!(parse(&value_message("/board", "clock", "12:00")), vec![("/board/clock".to_string(), None)]);
+    }
+
+    #[test]
+    fn ignores_malformed_messages() {
+        assert!(parse(&message("home", ",", &[])).is_empty());
+        assert!(parse(b"/unterminated").is_empty());
+        // A truncated integer leaves the message without a number.
+        assert_eq!(parse(&message("/a", ",i", &[0, 1])), vec![("/a".to_string(), None)]);
+    }
+
+    #[test]
+    fn unpacks_bundles() {
+        let packet = bundle(&[
+            message("/home_score/+1", ",", &[]),
+            bundle(&[message("/clock/start", ",", &[])]),
+        ]);
+        let addresses: Vec<String> = parse(&packet).into_iter().map(|(address, _)| address).collect();
+        assert_eq!(addresses, vec!["/home_score/+1", "/clock/start"]);
+    }
+
+    #[test]
+    fn limits_bundle_depth_and_size() {
+        let mut packet = message("/deep", ",", &[]);
+        for _ in 0..MAX_BUNDLE_DEPTH {
+            packet = bundle(&[packet]);
+        }
+        assert_eq!(parse(&packet).len(), 1);
+        assert!(parse(&bundle(&[packet])).is_empty());
+
+        // An element claiming more bytes than the bundle holds stops parsing.
+        let mut truncated = bundle(&[message("/a", ",", &[])]);
+        truncated.extend_from_slice(&u32::MAX.to_be_bytes());
+        truncated.extend_from_slice(&message("/b", ",", &[]));
+        assert_eq!(parse(&truncated).len(), 1);
+        assert!(parse(b"#bundle\0\0\0").is_empty());
+    }
+
+    #[test]
+    fn names_actions_from_paths() {
+        assert_eq!(action_name("home_score/+1"), "home_score.increase");
+        assert_eq!(action_name("home_score/-1"), "home_score.decrease");
+        assert_eq!(action_name("clock/start"), "clock.start");
+    }
+}
@@ -0,0 +1,115 @@
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Extension of template packs: a zip of a config and the files it uses.
+pub const PACKAGE_EXTENSION: &str = "scoreboardpkg";
+/// Config file a pack with several `.toml` files is loaded from.
+const PACKAGE_CONFIG_NAME: &str = "scoreboard.toml";
+const MAX_PACKAGE_ENTRIES: usize = 10_000;
+const MAX_PACKAGE_BYTES: u64 = 1024 * 1024 * 1024;
+
+pub fn is_package(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case(PACKAGE_EXTENSION))
+}
+
+/// Unpacks `package` under `cache_dir` and returns the config inside it, so
+/// relative paths in the config resolve to the pack's own files. Each version
+/// of a pack is unpacked once, to a folder named after its contents; older
+/// versions of the same pack are removed.
+pub fn extract_package(package: &Path, cache_dir: &Path) -> Result<PathBuf, String> {
+    let bytes = fs::read(package).map_err(|e| format!("Failed reading pack {}: {e}", package.display()))?;
+    let stem = package
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("pack")
+        .replace(|c: char| !c.is_ascii_alphanumeric() && c != '_', "_");
+    let digest = Sha256::digest(&bytes);
+    let hash: String = digest[..8].iter().map(|byte| format!("{byte:02x}")).collect();
+    let target = cache_dir.join(format!("{stem}-{hash}"));
+
+    if !target.is_dir() {
+        fs::create_dir_all(cache_dir)
+            .map_err(|e| format!("Failed creating pack cache {}: {e}", cache_dir.display()))?;
+        remove_old_versions(cache_dir, &stem);
+        // Unpacked beside the target and renamed, so a failed unpack is never reused.
+        let partial = cache_dir.join(format!("{stem}-{hash}.partial"));
+        let _ = fs::remove_dir_all(&partial);
+        if let Err(e) = unpack(&bytes, &partial) {
+            let _ = fs::remove_dir_all(&partial);
+            return Err(format!("Invalid pack {}: {e}", package.display()));
+        }
+        fs::rename(&partial, &target).map_err(|e| format!("Failed unpacking pack {}: {e}", package.display()))?;
+    }
+    find_config(&target).ok_or_else(|| {
+        format!(
+            "Pack {} needs a {PACKAGE_CONFIG_NAME}, or a single .toml config, at its top level",
+            package.display()
+        )
+    })
+}
+
+fn remove_old_versions(cache_dir: &Path, stem: &str) {
+    let Ok(entries) = fs::read_dir(cache_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let name = name.strip_suffix(".partial").unwrap_or(&name);
+        if name.rsplit_once('-').is_some_and(|(entry_stem, _)| entry_stem == stem) {
+            let _ = fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+fn unpack(bytes: &[u8], target: &Path) -> Result<(), String> {
+    let mut archive = zip::ZipArchive::new(io::Cursor::new(bytes)).map_err(|e| e.to_string())?;
+    if archive.len() > MAX_PACKAGE_ENTRIES {
+        return Err(format!("more than {MAX_PACKAGE_ENTRIES} files"));
+    }
+    let mut budget = MAX_PACKAGE_BYTES;
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(|e| e.to_string())?;
+        // Refuses names that would land outside the pack, such as `../x` or `/x`.
+        let relative = entry
+            .enclosed_name()
+            .ok_or_else(|| format!("'{}' points outside the pack", entry.name()))?;
+        let path = target.join(relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&path).map_err(|e| e.to_string())?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let mut file = File::create(&path).map_err(|e| e.to_string())?;
+        // Sizes in the archive can lie, so the limit is enforced while copying.
+        let copied = io::copy(&mut (&mut entry).take(budget + 1), &mut file).map_err(|e| e.to_string())?;
+        if copied > budget {
+            return Err(format!("more than {} MB unpacked", MAX_PACKAGE_BYTES / (1024 * 1024)));
+        }
+        budget -= copied;
+    }
+    Ok(())
+}
+
+/// The pack's config at its top level, or inside its only folder, since
+/// zipping a folder usually puts everything in one.
+fn find_config(dir: &Path) -> Option<PathBuf> {
+    let entries: Vec<PathBuf> = fs::read_dir(dir).ok()?.flatten().map(|entry| entry.path()).collect();
+    let configs: Vec<&PathBuf> = entries
+        .iter()
+        .filter(|path| path.is_file() && path.extension().is_some_and(|extension| extension == "toml"))
+        .collect();
+    if let Some(config) = configs.iter().find(|path| path.file_name().is_some_and(|name| name == PACKAGE_CONFIG_NAME)) {
+        return Some(config.to_path_buf());
+    }
+    match (configs.as_slice(), entries.as_slice()) {
+        ([config], _) => Some(config.to_path_buf()),
+        ([], [only]) if only.is_dir() => find_config(only),
+        _ => None,
+    }
+}
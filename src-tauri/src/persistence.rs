@@ -0,0 +1,44 @@
+use crate::state::RuntimeValues;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PERSISTENT_FILE_NAME: &str = "persistent.json";
+
+/// Values of `persistent = true` components, kept across app restarts and
+/// keyed by the path of the config that defines them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PersistentStore {
+    #[serde(default)]
+    pub configs: HashMap<String, RuntimeValues>,
+}
+
+pub fn persistent_file_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(PERSISTENT_FILE_NAME)
+}
+
+pub fn read_persistent_store(path: &Path) -> Result<PersistentStore, String> {
+    if !path.exists() {
+        return Ok(PersistentStore::default());
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed reading persistent values {}: {e}", path.display()))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed parsing persistent values {}: {e}", path.display()))
+}
+
+/// Writes through a temporary file, like the session, so a crash mid-write
+/// never loses the values saved before it.
+pub fn write_persistent_store(path: &Path, store: &PersistentStore) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed creating persistent values directory {}: {e}", parent.display()))?;
+    }
+    let content =
+        serde_json::to_string(store).map_err(|e| format!("Failed serializing persistent values: {e}"))?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, content)
+        .map_err(|e| format!("Failed writing persistent values {}: {e}", temp_path.display()))?;
+    fs::rename(&temp_path, path)
+        .map_err(|e| format!("Failed replacing persistent values {}: {e}", path.display()))
+}
@@ -0,0 +1,106 @@
+use serde::Serialize;
+use std::collections::HashMap;
+
+const JERSEY_DIGITS: usize = 2;
+
+/// A key that means something only while player select mode is active.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlayerSelectKey {
+    Digit(char),
+    Action(String),
+    Cancel,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PlayerSelectStatus {
+    pub active: bool,
+    pub jersey: String,
+    pub awaiting_action: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayerActionEvent {
+    pub player: String,
+    pub action: String,
+}
+
+pub enum PlayerSelectOutcome {
+    Updated,
+    Applied(PlayerActionEvent),
+    Cancelled,
+    Ignored,
+}
+
+/// Input mode for the jersey quick-select flow: the select key enters the
+/// mode, two digit keys choose a jersey number, and an action key applies a
+/// player action to it. Escape cancels at any point.
+#[derive(Debug, Default)]
+pub struct PlayerSelect {
+    active: bool,
+    jersey: String,
+    keys: HashMap<String, PlayerSelectKey>,
+    registered: Vec<String>,
+}
+
+impl PlayerSelect {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Enters the mode with the given shortcut map. Pressing the select key
+    /// again while active just clears the digits entered so far.
+    pub fn begin(&mut self, keys: HashMap<String, PlayerSelectKey>) {
+        self.active = true;
+        self.jersey.clear();
+        self.keys = keys;
+    }
+
+    pub fn key_for(&self, shortcut: &str) -> Option<PlayerSelectKey> {
+        if !self.active {
+            return None;
+        }
+        self.keys.get(shortcut).cloned()
+    }
+
+    pub fn press(&mut self, key: PlayerSelectKey) -> PlayerSelectOutcome {
+        match key {
+            PlayerSelectKey::Digit(digit) if self.jersey.len() < JERSEY_DIGITS => {
+                self.jersey.push(digit);
+                PlayerSelectOutcome::Updated
+            }
+            PlayerSelectKey::Action(action) if self.jersey.len() == JERSEY_DIGITS => {
+                PlayerSelectOutcome::Applied(PlayerActionEvent {
+                    player: self.jersey.clone(),
+                    action,
+                })
+            }
+            PlayerSelectKey::Cancel => PlayerSelectOutcome::Cancelled,
+            _ => PlayerSelectOutcome::Ignored,
+        }
+    }
+
+    /// Records which shortcuts the mode registered globally so `end` can release them.
+    pub fn set_registered(&mut self, shortcuts: Vec<String>) {
+        self.registered = shortcuts;
+    }
+
+    /// Leaves the mode and returns the shortcuts that should be unregistered.
+    pub fn end(&mut self) -> Vec<String> {
+        self.active = false;
+        self.jersey.clear();
+        self.keys.clear();
+        std::mem::take(&mut self.registered)
+    }
+
+    pub fn status(&self) -> PlayerSelectStatus {
+        PlayerSelectStatus {
+            active: self.active,
+            jersey: self.jersey.clone(),
+            awaiting_action: self.active && self.jersey.len() == JERSEY_DIGITS,
+        }
+    }
+}
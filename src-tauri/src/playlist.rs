@@ -0,0 +1,485 @@
+use crate::audio::open_output;
+use crate::config::{PlaylistClockAction, PlaylistSettings};
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const MUSIC_EXTENSIONS: [&str; 4] = ["wav", "mp3", "ogg", "flac"];
+const STEP: Duration = Duration::from_millis(50);
+/// How long the music takes to duck and come back up.
+const DUCK_FADE: Duration = Duration::from_millis(500);
+
+/// The playlist as reported by `get_playlist_status` and `scoreboard://playlist`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PlaylistStatus {
+    pub playing: bool,
+    /// File name of the current track, without its extension.
+    pub track: Option<String>,
+    pub ducked: bool,
+    pub tracks: usize,
+}
+
+enum PlaylistCommand {
+    Configure {
+        settings: Option<Box<PlaylistSettings>>,
+        device: Option<String>,
+    },
+    Play,
+    Pause,
+    Next,
+    ClockRunning(bool),
+}
+
+#[derive(Default)]
+struct Shared {
+    status: PlaylistStatus,
+    changed: bool,
+    errors: Vec<String>,
+}
+
+/// Plays background music on its own thread, which owns the output stream
+/// like the sound effects thread does. Changes and errors are collected for
+/// the app to pick up with `take_update`.
+pub struct PlaylistPlayer {
+    commands: Mutex<Sender<PlaylistCommand>>,
+    shared: Arc<Mutex<Shared>>,
+    clock_running: Mutex<bool>,
+}
+
+impl PlaylistPlayer {
+    pub fn new() -> Self {
+        let (commands, receiver) = mpsc::channel::<PlaylistCommand>();
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let thread_shared = Arc::clone(&shared);
+        thread::spawn(move || {
+            let mut player = Player::new();
+            let mut last_step = Instant::now();
+            loop {
+                match receiver.recv_timeout(STEP) {
+                    Ok(command) => player.handle(command),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+                let now = Instant::now();
+                player.step(now.saturating_duration_since(last_step));
+                last_step = now;
+
+                let status = player.status();
+                let Ok(mut shared) = thread_shared.lock() else {
+                    continue;
+                };
+                if shared.status != status {
+                    shared.status = status;
+                    shared.changed = true;
+                }
+                shared.errors.append(&mut player.errors);
+            }
+        });
+
+        Self {
+            commands: Mutex::new(commands),
+            shared,
+            clock_running: Mutex::new(false),
+        }
+    }
+
+    /// Applies new settings. Music keeps playing through a reload unless
+    /// the folder, shuffle, or output device changed.
+    pub fn configure(&self, settings: Option<PlaylistSettings>, device: Option<String>) -> Result<(), String> {
+        self.send(PlaylistCommand::Configure {
+            settings: settings.map(Box::new),
+            device,
+        })
+    }
+
+    pub fn play(&self) -> Result<(), String> {
+        self.send(PlaylistCommand::Play)
+    }
+
+    pub fn pause(&self) -> Result<(), String> {
+        self.send(PlaylistCommand::Pause)
+    }
+
+    pub fn next(&self) -> Result<(), String> {
+        self.send(PlaylistCommand::Next)
+    }
+
+    /// Reports whether the playlist's clock runs; only changes are passed on.
+    pub fn set_clock_running(&self, running: bool) {
+        let Ok(mut clock_running) = self.clock_running.lock() else {
+            return;
+        };
+        if *clock_running != running {
+            *clock_running = running;
+            let _ = self.send(PlaylistCommand::ClockRunning(running));
+        }
+    }
+
+    pub fn status(&self) -> PlaylistStatus {
+        self.shared.lock().map(|shared| shared.status.clone()).unwrap_or_default()
+    }
+
+    /// Returns the status if it changed since the last call, and the
+    /// playback errors since then.
+    pub fn take_update(&self) -> (Option<PlaylistStatus>, Vec<String>) {
+        let Ok(mut shared) = self.shared.lock() else {
+            return (None, Vec::new());
+        };
+        let status = std::mem::take(&mut shared.changed).then(|| shared.status.clone());
+        (status, std::mem::take(&mut shared.errors))
+    }
+
+    fn send(&self, command: PlaylistCommand) -> Result<(), String> {
+        self.commands
+            .lock()
+            .map_err(|_| "Playlist command lock poisoned".to_string())?
+            .send(command)
+            .map_err(|_| "Playlist thread is not running".to_string())
+    }
+}
+
+struct Track {
+    sink: Sink,
+    name: String,
+    duration: Option<Duration>,
+    /// Crossfade level from 0 to 1, on top of the playlist volume.
+    gain: f32,
+}
+
+struct Player {
+    settings: Option<PlaylistSettings>,
+    device: Option<String>,
+    output: Option<(OutputStream, OutputStreamHandle)>,
+    tracks: Vec<PathBuf>,
+    /// Indexes into `tracks` in playing order, reshuffled on every pass.
+    order: Vec<usize>,
+    position: usize,
+    current: Option<Track>,
+    fading_out: Vec<Track>,
+    playing: bool,
+    ducked: bool,
+    /// The playlist volume with ducking applied, moved toward its target gradually.
+    level: f32,
+    seed: u64,
+    errors: Vec<String>,
+}
+
+impl Player {
+    fn new() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or_default();
+        Self {
+            settings: None,
+            device: None,
+            output: None,
+            tracks: Vec::new(),
+            order: Vec::new(),
+            position: 0,
+            current: None,
+            fading_out: Vec::new(),
+            playing: false,
+            ducked: false,
+            level: 0.0,
+            // xorshift never leaves zero.
+            seed: seed | 1,
+            errors: Vec::new(),
+        }
+    }
+
+    fn handle(&mut self, command: PlaylistCommand) {
+        match command {
+            PlaylistCommand::Configure { settings, device } => self.configure(settings.map(|settings| *settings), device),
+            PlaylistCommand::Play => self.play(),
+            PlaylistCommand::Pause => self.pause(),
+            PlaylistCommand::Next => self.next(),
+            PlaylistCommand::ClockRunning(running) => {
+                let Some(settings) = &self.settings else {
+                    return;
+                };
+                if settings.clock.is_none() {
+                    return;
+                }
+                match settings.on_clock_start {
+                    PlaylistClockAction::Duck => self.ducked = running,
+                    PlaylistClockAction::Stop if running => self.pause(),
+                    PlaylistClockAction::Stop => {}
+                }
+            }
+        }
+    }
+
+    fn configure(&mut self, settings: Option<PlaylistSettings>, device: Option<String>) {
+        let keeps_tracks = match (&self.settings, &settings) {
+            (Some(old), Some(new)) => old.folder == new.folder && old.shuffle == new.shuffle && self.device == device,
+            _ => false,
+        };
+        self.settings = settings;
+        if keeps_tracks {
+            return;
+        }
+
+        self.stop_all();
+        self.output = None;
+        self.device = device;
+        self.ducked = false;
+        self.tracks = match &self.settings {
+            Some(settings) => match scan_folder(Path::new(&settings.folder)) {
+                Ok(tracks) => tracks,
+                Err(e) => {
+                    self.errors.push(e);
+                    Vec::new()
+                }
+            },
+            None => Vec::new(),
+        };
+        self.position = 0;
+        self.reorder();
+    }
+
+    fn play(&mut self) {
+        if self.settings.is_none() {
+            self.errors.push("No playlist is configured".to_string());
+            return;
+        }
+        if self.playing {
+            return;
+        }
+        match &self.current {
+            Some(track) => {
+                track.sink.play();
+                self.playing = true;
+            }
+            None => self.playing = self.start_track(),
+        }
+    }
+
+    fn pause(&mut self) {
+        if let Some(track) = &self.current {
+            track.sink.pause();
+        }
+        for track in self.fading_out.drain(..) {
+            track.sink.stop();
+        }
+        self.playing = false;
+    }
+
+    /// Skips to the next track: with a crossfade while playing, otherwise
+    /// it is queued for the next `play`.
+    fn next(&mut self) {
+        if self.tracks.is_empty() {
+            return;
+        }
+        self.advance();
+        if self.playing {
+            if !self.start_track() {
+                self.playing = false;
+            }
+        } else if let Some(track) = self.current.take() {
+            track.sink.stop();
+        }
+    }
+
+    fn advance(&mut self) {
+        self.position += 1;
+        if self.position >= self.order.len() {
+            self.position = 0;
+            self.reorder();
+        }
+    }
+
+    fn reorder(&mut self) {
+        self.order = (0..self.tracks.len()).collect();
+        if self.settings.as_ref().is_some_and(|settings| settings.shuffle) {
+            for index in (1..self.order.len()).rev() {
+                let other = (self.random() % (index as u64 + 1)) as usize;
+                self.order.swap(index, other);
+            }
+        }
+    }
+
+    fn random(&mut self) -> u64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        self.seed
+    }
+
+    /// Starts the track at `position`, fading out the one playing. Returns
+    /// false when nothing could be played.
+    fn start_track(&mut self) -> bool {
+        let Some(&index) = self.order.get(self.position) else {
+            self.errors.push("The playlist folder has no audio files".to_string());
+            return false;
+        };
+        let path = self.tracks[index].clone();
+        let track = match self.open_track(&path) {
+            Ok(track) => track,
+            Err(e) => {
+                self.errors.push(e);
+                return false;
+            }
+        };
+        if let Some(previous) = self.current.replace(track) {
+            if self.crossfade().is_zero() {
+                previous.sink.stop();
+            } else {
+                self.fading_out.push(previous);
+            }
+        }
+        self.apply_volumes();
+        true
+    }
+
+    fn open_track(&mut self, path: &Path) -> Result<Track, String> {
+        if self.output.is_none() {
+            self.output = Some(open_output(self.device.as_deref())?);
+        }
+        let Some((_, handle)) = self.output.as_ref() else {
+            return Err("No audio output device available".to_string());
+        };
+        let file = File::open(path).map_err(|e| format!("Failed opening track {}: {e}", path.display()))?;
+        let decoder =
+            Decoder::new(BufReader::new(file)).map_err(|e| format!("Failed decoding track {}: {e}", path.display()))?;
+        let duration = decoder.total_duration();
+        let sink = match Sink::try_new(handle) {
+            Ok(sink) => sink,
+            Err(e) => {
+                // Reopen the device on the next track, it may have been unplugged.
+                self.output = None;
+                return Err(format!("Failed starting music playback: {e}"));
+            }
+        };
+        let crossfades = !self.crossfade().is_zero() && self.current.is_some();
+        sink.set_volume(0.0);
+        sink.append(decoder);
+        Ok(Track {
+            sink,
+            name: path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            duration,
+            gain: if crossfades { 0.0 } else { 1.0 },
+        })
+    }
+
+    fn crossfade(&self) -> Duration {
+        Duration::from_millis(self.settings.as_ref().map(|settings| settings.crossfade_ms).unwrap_or(0))
+    }
+
+    fn target_level(&self) -> f32 {
+        let Some(settings) = &self.settings else {
+            return 0.0;
+        };
+        if self.ducked {
+            settings.volume * settings.duck_volume
+        } else {
+            settings.volume
+        }
+    }
+
+    fn step(&mut self, elapsed: Duration) {
+        let target = self.target_level();
+        let duck_step = elapsed.as_secs_f32() / DUCK_FADE.as_secs_f32();
+        self.level = if self.level < target {
+            (self.level + duck_step).min(target)
+        } else {
+            (self.level - duck_step).max(target)
+        };
+
+        if !self.playing {
+            return;
+        }
+        let crossfade = self.crossfade();
+        let fade_step = if crossfade.is_zero() {
+            1.0
+        } else {
+            elapsed.as_secs_f32() / crossfade.as_secs_f32()
+        };
+        if let Some(track) = &mut self.current {
+            track.gain = (track.gain + fade_step).min(1.0);
+        }
+        for track in &mut self.fading_out {
+            track.gain = (track.gain - fade_step).max(0.0);
+        }
+        self.fading_out.retain(|track| {
+            let done = track.gain <= 0.0 || track.sink.empty();
+            if done {
+                track.sink.stop();
+            }
+            !done
+        });
+        self.apply_volumes();
+
+        let Some(track) = &self.current else {
+            return;
+        };
+        // Start the next track early enough to crossfade into it; tracks
+        // without a known length just follow on when they end.
+        let ending = track.sink.empty()
+            || track
+                .duration
+                .is_some_and(|duration| !crossfade.is_zero() && track.sink.get_pos() + crossfade >= duration);
+        if ending {
+            self.advance();
+            if !self.start_track() {
+                self.stop_all();
+                self.playing = false;
+            }
+        }
+    }
+
+    fn apply_volumes(&self) {
+        if let Some(track) = &self.current {
+            track.sink.set_volume(self.level * track.gain);
+        }
+        for track in &self.fading_out {
+            track.sink.set_volume(self.level * track.gain);
+        }
+    }
+
+    fn stop_all(&mut self) {
+        if let Some(track) = self.current.take() {
+            track.sink.stop();
+        }
+        for track in self.fading_out.drain(..) {
+            track.sink.stop();
+        }
+        self.playing = false;
+    }
+
+    fn status(&self) -> PlaylistStatus {
+        PlaylistStatus {
+            playing: self.playing,
+            track: self.current.as_ref().map(|track| track.name.clone()),
+            ducked: self.ducked,
+            tracks: self.tracks.len(),
+        }
+    }
+}
+
+/// Audio files directly in `folder`, sorted by name.
+fn scan_folder(folder: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries =
+        fs::read_dir(folder).map_err(|e| format!("Failed reading playlist folder {}: {e}", folder.display()))?;
+    let mut tracks: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(|extension| extension.to_str())
+                    .is_some_and(|extension| MUSIC_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str()))
+        })
+        .collect();
+    tracks.sort();
+    Ok(tracks)
+}
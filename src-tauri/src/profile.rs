@@ -0,0 +1,129 @@
+use crate::config::ScoreboardConfig;
+use crate::state::RuntimeState;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// Parses are repeated this often and the fastest kept, so a cold disk
+/// cache or a busy machine does not decide the result.
+const PARSE_RUNS: usize = 3;
+/// Snapshots built and serialized to average the per-tick cost over.
+const SNAPSHOT_RUNS: u32 = 20;
+/// Share of a tick one snapshot may take before it is worth a warning.
+const EMIT_BUDGET_SHARE: f64 = 0.25;
+const LARGE_SNAPSHOT_BYTES: usize = 256 * 1024;
+const MANY_COMPONENTS: usize = 300;
+const SLOW_PARSE_MS: f64 = 500.0;
+
+/// Payload of `profile_config`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigProfile {
+    /// Fastest of several loads of the config, from reading it to a validated config.
+    pub parse_ms: f64,
+    pub component_count: usize,
+    /// Component count per `type`.
+    pub components_by_type: BTreeMap<String, usize>,
+    pub group_count: usize,
+    pub layout_count: usize,
+    /// Size of one snapshot as sent to the window.
+    pub snapshot_bytes: usize,
+    /// Average time to build one snapshot from the board's state.
+    pub snapshot_ms: f64,
+    /// Average time to serialize one snapshot.
+    pub serialize_ms: f64,
+    /// What one update costs the timer thread: building plus serializing a
+    /// snapshot. It is paid on every tick while a clock runs.
+    pub estimated_emit_ms: f64,
+    pub tick_ms: f64,
+    /// Share of each tick `estimated_emit_ms` takes, from 0.0.
+    pub tick_share: f64,
+    pub warnings: Vec<String>,
+}
+
+/// Loads a config with `load` and measures what it costs the board, so a
+/// large config can be tuned before it goes on air. `tick` is the update
+/// loop's interval.
+pub fn profile_config(
+    load: impl Fn() -> Result<ScoreboardConfig, String>,
+    tick: Duration,
+) -> Result<ConfigProfile, String> {
+    let mut parse = Duration::MAX;
+    let mut config = None;
+    for _ in 0..PARSE_RUNS {
+        let started = Instant::now();
+        let loaded = load()?;
+        parse = parse.min(started.elapsed());
+        config = Some(loaded);
+    }
+    let config = config.ok_or_else(|| "Config did not load".to_string())?;
+    let component_count = config.components.len();
+    let group_count = config.groups.len();
+    let layout_count = config.layouts.len();
+
+    let mut runtime = RuntimeState::new();
+    runtime.replace_config(config);
+    let mut build = Duration::ZERO;
+    let mut serialize = Duration::ZERO;
+    let mut snapshot_bytes = 0;
+    let mut components_by_type = BTreeMap::new();
+    for run in 0..SNAPSHOT_RUNS {
+        let started = Instant::now();
+        let snapshot = runtime.snapshot();
+        build += started.elapsed();
+        let started = Instant::now();
+        let json = serde_json::to_string(&snapshot).map_err(|e| format!("Failed encoding snapshot: {e}"))?;
+        serialize += started.elapsed();
+        snapshot_bytes = json.len();
+        if run == 0 {
+            for component in &snapshot.components {
+                *components_by_type.entry(component.component_type.clone()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let millis = |duration: Duration| duration.as_secs_f64() * 1000.0;
+    let snapshot_ms = millis(build) / f64::from(SNAPSHOT_RUNS);
+    let serialize_ms = millis(serialize) / f64::from(SNAPSHOT_RUNS);
+    let estimated_emit_ms = snapshot_ms + serialize_ms;
+    let tick_ms = millis(tick);
+    let tick_share = estimated_emit_ms / tick_ms;
+    let parse_ms = millis(parse);
+
+    let mut warnings = Vec::new();
+    if tick_share > EMIT_BUDGET_SHARE {
+        warnings.push(format!(
+            "Each update takes about {estimated_emit_ms:.1} ms, {:.0}% of the {tick_ms:.0} ms update loop; clocks may stutter while they run",
+            tick_share * 100.0
+        ));
+    }
+    if snapshot_bytes > LARGE_SNAPSHOT_BYTES {
+        warnings.push(format!(
+            "Each update sends {} KB to the window; remove components the board does not need",
+            snapshot_bytes / 1024
+        ));
+    }
+    if component_count > MANY_COMPONENTS {
+        warnings.push(format!(
+            "{component_count} components; consider splitting the config into layouts or separate files"
+        ));
+    }
+    if parse_ms > SLOW_PARSE_MS {
+        warnings.push(format!(
+            "Loading takes {parse_ms:.0} ms, so hot reloads and layout switches will lag"
+        ));
+    }
+    Ok(ConfigProfile {
+        parse_ms,
+        component_count,
+        components_by_type,
+        group_count,
+        layout_count,
+        snapshot_bytes,
+        snapshot_ms,
+        serialize_ms,
+        estimated_emit_ms,
+        tick_ms,
+        tick_share,
+        warnings,
+    })
+}
@@ -0,0 +1,200 @@
+use crate::state::UiSnapshot;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+const RECORDING_FORMAT_VERSION: u32 = 1;
+
+/// First line of a recording file. Frame times are relative to `started_at_unix_ms`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordingHeader {
+    version: u32,
+    started_at_unix_ms: u64,
+}
+
+/// One emitted snapshot, reduced to the displayed value of each component.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedFrame {
+    t_ms: u64,
+    sequence: u64,
+    values: BTreeMap<String, String>,
+}
+
+struct ActiveRecording {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    started: Instant,
+}
+
+/// Appends every emitted snapshot to a JSON Lines file while a recording is active.
+pub struct Recorder {
+    active: Option<ActiveRecording>,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self { active: None }
+    }
+
+    pub fn start(&mut self, path: &Path) -> Result<(), String> {
+        if let Some(active) = &self.active {
+            return Err(format!("Already recording to {}", active.path.display()));
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed creating recording directory {}: {e}", parent.display()))?;
+        }
+        let file = File::create(path).map_err(|e| format!("Failed creating recording {}: {e}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        let header = RecordingHeader {
+            version: RECORDING_FORMAT_VERSION,
+            started_at_unix_ms: unix_time_ms(),
+        };
+        write_line(&mut writer, &header).map_err(|e| format!("Failed writing recording {}: {e}", path.display()))?;
+
+        self.active = Some(ActiveRecording {
+            path: path.to_path_buf(),
+            writer,
+            started: Instant::now(),
+        });
+        Ok(())
+    }
+
+    /// Finishes the active recording and returns its path, if one was running.
+    pub fn stop(&mut self) -> Result<Option<PathBuf>, String> {
+        let Some(mut active) = self.active.take() else {
+            return Ok(None);
+        };
+        active
+            .writer
+            .flush()
+            .map_err(|e| format!("Failed writing recording {}: {e}", active.path.display()))?;
+        Ok(Some(active.path))
+    }
+
+    /// Writes a frame for the snapshot. A write failure ends the recording so
+    /// the error is reported once instead of on every tick.
+    pub fn record(&mut self, snapshot: &UiSnapshot) -> Result<(), String> {
+        let Some(active) = self.active.as_mut() else {
+            return Ok(());
+        };
+        let frame = RecordedFrame {
+            t_ms: active.started.elapsed().as_millis() as u64,
+            sequence: snapshot.sequence,
+            values: snapshot
+                .components
+                .iter()
+                .filter_map(|component| {
+                    let value = component.text.clone().or_else(|| component.source.clone())?;
+                    Some((component.id.clone(), value))
+                })
+                .collect(),
+        };
+        // Flush per frame so a crash loses at most the frame being written.
+        let result = write_line(&mut active.writer, &frame).and_then(|_| active.writer.flush());
+        if let Err(e) = result {
+            let path = active.path.clone();
+            self.active = None;
+            return Err(format!("Recording stopped, failed writing {}: {e}", path.display()));
+        }
+        Ok(())
+    }
+}
+
+/// Converts a recording into a CSV with one row per second of recording time.
+/// Each row holds the values that were on screen at that second.
+pub fn convert_recording_to_csv(input: &Path, output: &Path) -> Result<(), String> {
+    let file = File::open(input).map_err(|e| format!("Failed opening recording {}: {e}", input.display()))?;
+    let mut lines = BufReader::new(file).lines();
+
+    let header_line = lines
+        .next()
+        .ok_or_else(|| format!("Recording {} is empty", input.display()))?
+        .map_err(|e| format!("Failed reading recording {}: {e}", input.display()))?;
+    let header: RecordingHeader = serde_json::from_str(&header_line)
+        .map_err(|e| format!("Invalid recording header in {}: {e}", input.display()))?;
+    if header.version != RECORDING_FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported recording version {} in {}",
+            header.version,
+            input.display()
+        ));
+    }
+
+    let mut frames = Vec::new();
+    let mut columns: Vec<String> = Vec::new();
+    for (index, line) in lines.enumerate() {
+        let line = line.map_err(|e| format!("Failed reading recording {}: {e}", input.display()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let frame: RecordedFrame = serde_json::from_str(&line)
+            .map_err(|e| format!("Invalid frame on line {} of {}: {e}", index + 2, input.display()))?;
+        for id in frame.values.keys() {
+            if !columns.contains(id) {
+                columns.push(id.clone());
+            }
+        }
+        frames.push(frame);
+    }
+
+    let mut csv = String::from("second,timecode");
+    for id in &columns {
+        csv.push(',');
+        csv.push_str(&csv_field(id));
+    }
+    csv.push('\n');
+
+    let last_ms = frames.last().map(|frame| frame.t_ms).unwrap_or(0);
+    let mut next_frame = 0;
+    let mut current: Option<&RecordedFrame> = None;
+    for second in 0..=last_ms / 1000 {
+        while next_frame < frames.len() && frames[next_frame].t_ms <= second * 1000 {
+            current = Some(&frames[next_frame]);
+            next_frame += 1;
+        }
+        csv.push_str(&format!("{second},{}", format_timecode(second)));
+        for id in &columns {
+            csv.push(',');
+            if let Some(value) = current.and_then(|frame| frame.values.get(id)) {
+                csv.push_str(&csv_field(value));
+            }
+        }
+        csv.push('\n');
+    }
+
+    fs::write(output, csv).map_err(|e| format!("Failed writing CSV {}: {e}", output.display()))
+}
+
+pub fn default_recording_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir
+        .join("recordings")
+        .join(format!("recording-{}.jsonl", unix_time_ms()))
+}
+
+fn write_line<T: Serialize>(writer: &mut BufWriter<File>, value: &T) -> std::io::Result<()> {
+    serde_json::to_writer(&mut *writer, value)?;
+    writer.write_all(b"\n")
+}
+
+fn format_timecode(second: u64) -> String {
+    format!("{:02}:{:02}:{:02}", second / 3600, (second / 60) % 60, second % 60)
+}
+
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn unix_time_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
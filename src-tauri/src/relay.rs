@@ -0,0 +1,90 @@
+use crate::config::RelayOutput;
+use serialport::SerialPort;
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+type SharedPort = Arc<Mutex<Box<dyn SerialPort>>>;
+type PortCache = Arc<Mutex<HashMap<String, SharedPort>>>;
+
+const RELAY_WRITE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Drives serial relay boards. Ports are opened on first use and kept open,
+/// since several relays are often channels on the same board.
+pub struct RelayController {
+    ports: PortCache,
+}
+
+impl RelayController {
+    pub fn new() -> Self {
+        Self {
+            ports: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Closes the relay and opens it again after `duration` on a background
+    /// thread. Returns once the relay has been switched on.
+    pub fn pulse(&self, output: &RelayOutput, duration: Duration) -> Result<(), String> {
+        let port = self.port(output)?;
+        self.write(output, &port, &output.on)?;
+
+        let output = output.clone();
+        let ports = self.ports.clone();
+        thread::spawn(move || {
+            thread::sleep(duration);
+            let result = port
+                .lock()
+                .map_err(|_| "Relay port lock poisoned".to_string())
+                .and_then(|mut port| {
+                    port.write_all(&output.off)
+                        .map_err(|e| format!("Failed switching off relay '{}': {e}", output.name))
+                });
+            if result.is_err() {
+                if let Ok(mut ports) = ports.lock() {
+                    ports.remove(&output.port);
+                }
+            }
+        });
+        Ok(())
+    }
+
+    pub fn release_ports(&self) {
+        if let Ok(mut ports) = self.ports.lock() {
+            ports.clear();
+        }
+    }
+
+    fn port(&self, output: &RelayOutput) -> Result<SharedPort, String> {
+        let mut ports = self
+            .ports
+            .lock()
+            .map_err(|_| "Relay port lock poisoned".to_string())?;
+        if let Some(port) = ports.get(&output.port) {
+            return Ok(port.clone());
+        }
+        let port = serialport::new(&output.port, output.baud_rate)
+            .timeout(RELAY_WRITE_TIMEOUT)
+            .open()
+            .map_err(|e| format!("Failed opening relay port {}: {e}", output.port))?;
+        let port = Arc::new(Mutex::new(port));
+        ports.insert(output.port.clone(), port.clone());
+        Ok(port)
+    }
+
+    fn write(&self, output: &RelayOutput, port: &SharedPort, bytes: &[u8]) -> Result<(), String> {
+        let result = port
+            .lock()
+            .map_err(|_| "Relay port lock poisoned".to_string())?
+            .write_all(bytes);
+        if let Err(e) = result {
+            // Forget the port so the next pulse reopens it, e.g. after the board is replugged.
+            if let Ok(mut ports) = self.ports.lock() {
+                ports.remove(&output.port);
+            }
+            return Err(format!("Failed switching on relay '{}': {e}", output.name));
+        }
+        Ok(())
+    }
+}
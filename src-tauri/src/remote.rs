@@ -0,0 +1,668 @@
+use crate::config::{RemoteRole, RemoteSettings};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tungstenite::handshake::derive_accept_key;
+use tungstenite::protocol::{Role, WebSocketConfig};
+use tungstenite::{Message, WebSocket};
+
+const ACCEPT_POLL: Duration = Duration::from_millis(50);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_HEADER_LINES: usize = 64;
+/// Longest request or header line read, so a client cannot make the server
+/// buffer an endless line.
+const MAX_LINE: usize = 8 * 1024;
+/// Requests served at once; each holds a thread until it is answered.
+const MAX_CONNECTIONS: usize = 32;
+/// Open WebSockets at once; each holds a thread.
+const MAX_SOCKETS: usize = 16;
+/// Action names are short, so larger socket messages are refused.
+const MAX_SOCKET_MESSAGE: usize = 4 * 1024;
+/// How often an open WebSocket checks whether the server stopped.
+const SOCKET_POLL: Duration = Duration::from_millis(250);
+/// The phone-sized control page served at `/remote`.
+const REMOTE_PAGE: &str = include_str!("remote.html");
+/// How long a host counts as connected after its last request.
+const CLIENT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Hosts recently seen by the HTTP remote and OSC, for the status bar.
+#[derive(Default)]
+pub struct RecentClients {
+    seen: Mutex<HashMap<IpAddr, Instant>>,
+    /// OSC subscribers count for as long as they stay subscribed.
+    subscribed: Mutex<Vec<IpAddr>>,
+}
+
+impl RecentClients {
+    pub fn record(&self, address: IpAddr) {
+        if let Ok(mut seen) = self.seen.lock() {
+            seen.insert(address, Instant::now());
+        }
+    }
+
+    pub fn set_subscribed(&self, addresses: Vec<IpAddr>) {
+        if let Ok(mut subscribed) = self.subscribed.lock() {
+            *subscribed = addresses;
+        }
+    }
+
+    /// Distinct hosts seen within the last minute or subscribed now.
+    pub fn count(&self) -> usize {
+        let mut hosts: Vec<IpAddr> = match self.seen.lock() {
+            Ok(mut seen) => {
+                seen.retain(|_, last| last.elapsed() < CLIENT_WINDOW);
+                seen.keys().copied().collect()
+            }
+            Err(_) => Vec::new(),
+        };
+        if let Ok(subscribed) = self.subscribed.lock() {
+            hosts.extend(subscribed.iter());
+        }
+        hosts.sort_unstable();
+        hosts.dedup();
+        hosts.len()
+    }
+}
+
+/// What a caller asked the remote endpoint for.
+pub enum RemoteRequest<'a> {
+    Trigger(&'a str),
+    /// The action names a caller with the role may fire.
+    ListActions(RemoteRole),
+    /// The board's values as a flat object of component id to displayed text.
+    Data,
+}
+
+/// Why a remote request failed, which decides the status it is answered with.
+#[derive(Debug)]
+pub enum RemoteError {
+    /// An unknown action or data that is not available: `404`.
+    NotFound(String),
+    /// The board is locked: `423`.
+    Locked(String),
+    /// The action was found but could not be applied: `409`.
+    Failed(String),
+}
+
+impl RemoteError {
+    fn status(&self) -> u16 {
+        match self {
+            Self::NotFound(_) => 404,
+            Self::Locked(_) => 423,
+            Self::Failed(_) => 409,
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            Self::NotFound(message) | Self::Locked(message) | Self::Failed(message) => message,
+        }
+    }
+}
+
+pub type RemoteHandler = Arc<dyn Fn(RemoteRequest) -> Result<Value, RemoteError> + Send + Sync>;
+
+struct Running {
+    address: SocketAddr,
+    settings: Arc<Mutex<RemoteSettings>>,
+    stop: Arc<AtomicBool>,
+}
+
+/// A small HTTP server for control surfaces that cannot send global
+/// shortcuts: `POST /action/<name>` fires an action, `GET /actions` lists
+/// them, and `/ws` keeps a WebSocket open for firing them.
+pub struct RemoteServer {
+    running: Option<Running>,
+    clients: Arc<RecentClients>,
+}
+
+impl RemoteServer {
+    pub fn new(clients: Arc<RecentClients>) -> Self {
+        Self { running: None, clients }
+    }
+
+    /// Starts, stops, or moves the server to match `settings`. Token changes
+    /// apply to the running server without dropping it.
+    pub fn configure(&mut self, settings: Option<&RemoteSettings>, handler: RemoteHandler) -> Result<(), String> {
+        let address = match settings {
+            Some(settings) => Some(
+                format!("{}:{}", settings.bind, settings.port)
+                    .parse::<SocketAddr>()
+                    .map_err(|e| format!("Invalid remote address: {e}"))?,
+            ),
+            None => None,
+        };
+        if let (Some(running), Some(address), Some(settings)) = (&self.running, address, settings) {
+            if running.address == address {
+                if let Ok(mut current) = running.settings.lock() {
+                    *current = settings.clone();
+                }
+                return Ok(());
+            }
+        }
+
+        if let Some(running) = self.running.take() {
+            running.stop.store(true, Ordering::Relaxed);
+        }
+        let (Some(address), Some(settings)) = (address, settings) else {
+            return Ok(());
+        };
+        let listener =
+            TcpListener::bind(address).map_err(|e| format!("Failed starting remote control on {address}: {e}"))?;
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| format!("Failed starting remote control on {address}: {e}"))?;
+
+        let settings = Arc::new(Mutex::new(settings.clone()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_settings = Arc::clone(&settings);
+        let thread_stop = Arc::clone(&stop);
+        let clients = Arc::clone(&self.clients);
+        thread::spawn(move || {
+            let server = Arc::new(Server {
+                settings: thread_settings,
+                handler,
+                stop: Arc::clone(&thread_stop),
+                sockets: Arc::new(AtomicUsize::new(0)),
+            });
+            let connections = Arc::new(AtomicUsize::new(0));
+            while !thread_stop.load(Ordering::Relaxed) {
+                let (stream, peer) = match listener.accept() {
+                    Ok(accepted) => accepted,
+                    Err(_) => {
+                        thread::sleep(ACCEPT_POLL);
+                        continue;
+                    }
+                };
+                // A slow client must not hold up the others, so each request
+                // gets its own thread. Past the cap, connections are dropped
+                // unanswered rather than left waiting on this thread.
+                if connections.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                    connections.fetch_sub(1, Ordering::SeqCst);
+                    continue;
+                }
+                let server = Arc::clone(&server);
+                let clients = Arc::clone(&clients);
+                let connections = Arc::clone(&connections);
+                thread::spawn(move || {
+                    if let Ok(true) = server.serve(stream) {
+                        clients.record(peer.ip());
+                    }
+                    connections.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+        self.running = Some(Running { address, settings, stop });
+        Ok(())
+    }
+}
+
+/// What the accept loop shares with the requests it serves.
+struct Server {
+    settings: Arc<Mutex<RemoteSettings>>,
+    handler: RemoteHandler,
+    stop: Arc<AtomicBool>,
+    sockets: Arc<AtomicUsize>,
+}
+
+#[derive(Debug, Default)]
+struct Request {
+    method: String,
+    path: String,
+    query: Option<String>,
+    authorization: Option<String>,
+    host: Option<String>,
+    origin: Option<String>,
+    websocket_key: Option<String>,
+    /// Bytes read past the headers, such as the first frame a WebSocket
+    /// client sends without waiting for the upgrade answer.
+    rest: Vec<u8>,
+}
+
+impl Request {
+    /// Whether the request came from no web page or from the `/remote` page
+    /// itself. Browsers send `Origin` on POSTs and WebSockets, so this keeps
+    /// any other site open in a browser that can reach the board from
+    /// firing actions through it.
+    fn same_origin(&self) -> bool {
+        match (&self.origin, &self.host) {
+            (None, _) => true,
+            (Some(origin), Some(host)) => origin.eq_ignore_ascii_case(&format!("http://{host}")),
+            (Some(_), None) => false,
+        }
+    }
+}
+
+impl Server {
+    /// Answers one request; returns whether it was an authorized call.
+    fn serve(&self, mut stream: TcpStream) -> io::Result<bool> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+        stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+        let Some(request) = read_request(&stream)? else {
+            respond(&mut stream, 400, &json!({ "error": "Malformed request" }))?;
+            return Ok(false);
+        };
+        if !request.same_origin() {
+            respond(&mut stream, 403, &json!({ "error": "Requests from other sites are not allowed" }))?;
+            return Ok(false);
+        }
+        // The page holds no data, so it loads without the token and passes on
+        // the one in its own URL to the calls it makes.
+        if request.method == "GET" && matches!(request.path.as_str(), "/remote" | "/remote/") {
+            respond_with(&mut stream, 200, "text/html; charset=utf-8", REMOTE_PAGE)?;
+            return Ok(false);
+        }
+        let settings = self
+            .settings
+            .lock()
+            .map(|settings| settings.clone())
+            .map_err(|_| io::Error::other("Remote settings lock poisoned"))?;
+        let Some(role) = caller_role(&request, &settings) else {
+            respond(&mut stream, 401, &json!({ "error": "Missing or wrong token" }))?;
+            return Ok(false);
+        };
+
+        let handler = &self.handler;
+        let (status, body) = match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/actions") => reply(handler(RemoteRequest::ListActions(role))),
+            // POST only: any page can make a browser GET a URL, for example as an image.
+            ("POST", path) if path.starts_with("/action/") => {
+                fire(handler, role, &percent_decode(&path["/action/".len()..]))
+            }
+            ("GET", "/ws") if request.websocket_key.is_some() => {
+                self.open_socket(stream, request)?;
+                return Ok(true);
+            }
+            // Flat values for broadcast graphics data sources such as vMix.
+            ("GET", "/data.json") => reply(handler(RemoteRequest::Data)),
+            ("GET", "/data.xml") => match handler(RemoteRequest::Data) {
+                Ok(data) => {
+                    respond_with(&mut stream, 200, "application/xml", &data_xml(&data))?;
+                    return Ok(true);
+                }
+                Err(e) => (e.status(), json!({ "error": e.message() })),
+            },
+            ("GET", "/ws") => (400, json!({ "error": "Expected a WebSocket upgrade" })),
+            (_, "/actions" | "/data.json" | "/data.xml" | "/remote" | "/remote/" | "/ws") => {
+                (405, json!({ "error": "Use GET" }))
+            }
+            (_, path) if path.starts_with("/action/") => (405, json!({ "error": "Use POST" })),
+            _ => (404, json!({ "error": "Not found" })),
+        };
+        respond(&mut stream, status, &body)?;
+        Ok(true)
+    }
+
+    /// Upgrades `stream` to a WebSocket on its own thread. Each text message
+    /// is an action name and is answered like `POST /action/<name>`. The
+    /// token is checked again for every message, so revoking it also cuts
+    /// off open sockets.
+    fn open_socket(&self, mut stream: TcpStream, mut request: Request) -> io::Result<()> {
+        if self.sockets.fetch_add(1, Ordering::SeqCst) >= MAX_SOCKETS {
+            self.sockets.fetch_sub(1, Ordering::SeqCst);
+            return respond(&mut stream, 503, &json!({ "error": "Too many open WebSockets" }));
+        }
+        let key = request.websocket_key.as_deref().unwrap_or_default();
+        let opened = write!(
+            stream,
+            "HTTP/1.1 101 Switching Protocols\r\n\
+             Upgrade: websocket\r\n\
+             Connection: Upgrade\r\n\
+             Sec-WebSocket-Accept: {}\r\n\r\n",
+            derive_accept_key(key.as_bytes())
+        )
+        .and_then(|_| stream.flush())
+        .and_then(|_| stream.set_read_timeout(Some(SOCKET_POLL)));
+        if let Err(e) = opened {
+            self.sockets.fetch_sub(1, Ordering::SeqCst);
+            return Err(e);
+        }
+
+        let settings = Arc::clone(&self.settings);
+        let handler = Arc::clone(&self.handler);
+        let stop = Arc::clone(&self.stop);
+        let sockets = Arc::clone(&self.sockets);
+        thread::spawn(move || {
+            let config = WebSocketConfig::default()
+                .max_message_size(Some(MAX_SOCKET_MESSAGE))
+                .max_frame_size(Some(MAX_SOCKET_MESSAGE));
+            let rest = std::mem::take(&mut request.rest);
+            let mut socket = WebSocket::from_partially_read(stream, rest, Role::Server, Some(config));
+            while !stop.load(Ordering::Relaxed) {
+                let name = match socket.read() {
+                    Ok(Message::Text(name)) => name,
+                    Ok(Message::Close(_)) => break,
+                    Ok(_) => continue,
+                    Err(tungstenite::Error::Io(e))
+                        if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) =>
+                    {
+                        continue
+                    }
+                    Err(_) => break,
+                };
+                let role = settings.lock().ok().and_then(|settings| caller_role(&request, &settings));
+                let Some(role) = role else {
+                    let _ = socket.send(Message::text(json!({ "error": "Missing or wrong token" }).to_string()));
+                    break;
+                };
+                let (_, body) = fire(&handler, role, name.trim());
+                if socket.send(Message::text(body.to_string())).is_err() {
+                    break;
+                }
+            }
+            let _ = socket.close(None);
+            let _ = socket.flush();
+            sockets.fetch_sub(1, Ordering::SeqCst);
+        });
+        Ok(())
+    }
+}
+
+/// The role of the token the request carries, or `None` when a token is
+/// required and it is missing or unknown.
+fn caller_role(request: &Request, settings: &RemoteSettings) -> Option<RemoteRole> {
+    // Only a loopback `bind` may go without a token. Its callers must also
+    // name the board by a loopback host, or a site that points its own
+    // domain at 127.0.0.1 would pass the origin check.
+    if !settings.requires_token() {
+        return request.host.as_deref().is_some_and(loopback_host).then_some(RemoteRole::Full);
+    }
+    let bearer = request.authorization.as_deref().and_then(|value| value.strip_prefix("Bearer "));
+    let query_token = request.query.as_deref().and_then(|query| query_param(query, "token"));
+    bearer
+        .and_then(|token| settings.role_for(token))
+        .or_else(|| query_token.and_then(|token| settings.role_for(&token)))
+}
+
+/// Fires `name` when `role` may, otherwise answers `403`.
+fn fire(handler: &RemoteHandler, role: RemoteRole, name: &str) -> (u16, Value) {
+    let allowed = role == RemoteRole::Full
+        || handler(RemoteRequest::ListActions(role))
+            .is_ok_and(|actions| actions.as_array().is_some_and(|actions| actions.contains(&json!(name))));
+    if allowed {
+        reply(handler(RemoteRequest::Trigger(name)))
+    } else {
+        (403, json!({ "error": format!("This token cannot fire '{name}'") }))
+    }
+}
+
+/// Whether a `Host` header names this machine: `localhost` or a loopback
+/// address, with or without a port.
+fn loopback_host(host: &str) -> bool {
+    let name = match host.strip_prefix('[') {
+        Some(bracketed) => bracketed.split(']').next().unwrap_or_default(),
+        None => host.rsplit_once(':').map_or(host, |(name, _)| name),
+    };
+    name.eq_ignore_ascii_case("localhost") || name.parse::<IpAddr>().is_ok_and(|address| address.is_loopback())
+}
+
+fn reply(result: Result<Value, RemoteError>) -> (u16, Value) {
+    match result {
+        Ok(value) => (200, value),
+        Err(e) => (e.status(), json!({ "error": e.message() })),
+    }
+}
+
+/// Reads the request line and headers; bodies are not used.
+fn read_request(stream: impl Read) -> io::Result<Option<Request>> {
+    let mut reader = BufReader::new(stream);
+    let Some(line) = read_line(&mut reader)? else {
+        return Ok(None);
+    };
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Ok(None);
+    };
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), Some(query.to_string())),
+        None => (target.to_string(), None),
+    };
+    let mut request = Request {
+        method: method.to_ascii_uppercase(),
+        path,
+        query,
+        ..Request::default()
+    };
+
+    for _ in 0..MAX_HEADER_LINES {
+        let Some(header) = read_line(&mut reader)? else {
+            return Ok(None);
+        };
+        if header.trim().is_empty() {
+            request.rest = reader.buffer().to_vec();
+            return Ok(Some(request));
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            let value = Some(value.trim().to_string());
+            match name.trim().to_ascii_lowercase().as_str() {
+                "authorization" => request.authorization = value,
+                "host" => request.host = value,
+                "origin" => request.origin = value,
+                "sec-websocket-key" => request.websocket_key = value,
+                _ => {}
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// One line of at most `MAX_LINE` bytes, or `None` when it is longer.
+fn read_line(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut line = Vec::new();
+    reader.take(MAX_LINE as u64).read_until(b'\n', &mut line)?;
+    if line.len() == MAX_LINE && !line.ends_with(b"\n") {
+        return Ok(None);
+    }
+    Ok(Some(String::from_utf8_lossy(&line).into_owned()))
+}
+
+fn respond(stream: &mut TcpStream, status: u16, body: &Value) -> io::Result<()> {
+    respond_with(stream, status, "application/json", &body.to_string())
+}
+
+fn respond_with(stream: &mut TcpStream, status: u16, content_type: &str, body: &str) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        409 => "Conflict",
+        423 => "Locked",
+        503 => "Service Unavailable",
+        _ => "Method Not Allowed",
+    };
+    // No CORS headers: only the `/remote` page, served from here, may call in.
+    write!(
+        stream,
+        "HTTP/1.1 {status} {reason}\r\n\
+         Content-Type: {content_type}\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+/// Renders `/data.json`'s object as `<scoreboard><id>text</id>...</scoreboard>`.
+fn data_xml(data: &Value) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<scoreboard>\n");
+    if let Some(values) = data.as_object() {
+        for (id, value) in values {
+            let name = xml_name(id);
+            let text = value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string());
+            xml.push_str(&format!("  <{name}>{}</{name}>\n", xml_escape(&text)));
+        }
+    }
+    xml.push_str("</scoreboard>\n");
+    xml
+}
+
+/// Component ids can hold characters element names cannot; those become `_`.
+fn xml_name(id: &str) -> String {
+    let mut name: String = id
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '_' | '-' | '.') { c } else { '_' })
+        .collect();
+    if !name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        name.insert(0, '_');
+    }
+    name
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn query_param(query: &str, key: &str) -> Option<String> {
+    query.split('&').find_map(|pair| {
+        let (name, value) = pair.split_once('=')?;
+        (name == key).then(|| percent_decode(value))
+    })
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| text.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RemoteToken;
+
+    fn request(raw: &str) -> Option<Request> {
+        read_request(raw.as_bytes()).unwrap()
+    }
+
+    fn settings(token: Option<&str>) -> RemoteSettings {
+        RemoteSettings {
+            bind: "127.0.0.1".to_string(),
+            port: 8080,
+            token: token.map(str::to_string),
+            tokens: vec![RemoteToken {
+                name: "display".to_string(),
+                token: "read-only".to_string(),
+                role: RemoteRole::Read,
+            }],
+        }
+    }
+
+    #[test]
+    fn reads_the_request_line_and_headers() {
+        let request = request(
+            "post /action/home_score.increase?token=a%20b HTTP/1.1\r\n\
+             Host: 127.0.0.1:8080\r\n\
+             ORIGIN: http://127.0.0.1:8080\r\n\
+             Authorization: Bearer secret\r\n\
+             Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+             X-Other: ignored\r\n\r\n",
+        )
+        .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/action/home_score.increase");
+        assert_eq!(request.query.as_deref().and_then(|query| query_param(query, "token")).as_deref(), Some("a b"));
+        assert_eq!(request.host.as_deref(), Some("127.0.0.1:8080"));
+        assert_eq!(request.origin.as_deref(), Some("http://127.0.0.1:8080"));
+        assert_eq!(request.authorization.as_deref(), Some("Bearer secret"));
+        assert_eq!(request.websocket_key.as_deref(), Some("dGhlIHNhbXBsZSBub25jZQ=="));
+    }
+
+    #[test]
+    fn keeps_bytes_sent_after_the_headers() {
+        let upgrade = request("GET /ws HTTP/1.1\r\nHost: localhost\r\n\r\n\x01\x05hello").unwrap();
+        assert_eq!(upgrade.rest, b"\x01\x05hello");
+        let plain = request("GET /actions HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        assert!(plain.rest.is_empty());
+    }
+
+    #[test]
+    fn rejects_incomplete_requests() {
+        assert!(request("").is_none());
+        assert!(request("GET\r\n\r\n").is_none());
+        let endless_headers = format!("GET / HTTP/1.1\r\n{}\r\n", "X-Filler: 1\r\n".repeat(MAX_HEADER_LINES));
+        assert!(request(&endless_headers).is_none());
+    }
+
+    #[test]
+    fn caps_line_length() {
+        let long_target = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_LINE));
+        assert!(request(&long_target).is_none());
+        let long_header = format!("GET / HTTP/1.1\r\nX-Filler: {}\r\n\r\n", "a".repeat(MAX_LINE));
+        assert!(request(&long_header).is_none());
+        let fits = format!("GET / HTTP/1.1\r\nX-Filler: {}\r\n\r\n", "a".repeat(MAX_LINE - 100));
+        assert!(request(&fits).is_some());
+    }
+
+    #[test]
+    fn accepts_only_its_own_origin() {
+        let with = |host: Option<&str>, origin: Option<&str>| Request {
+            host: host.map(str::to_string),
+            origin: origin.map(str::to_string),
+            ..Request::default()
+        };
+        assert!(with(Some("board:8080"), None).same_origin());
+        assert!(with(Some("board:8080"), Some("http://BOARD:8080")).same_origin());
+        assert!(!with(Some("board:8080"), Some("https://evil.example")).same_origin());
+        assert!(!with(None, Some("http://board:8080")).same_origin());
+    }
+
+    #[test]
+    fn recognizes_loopback_hosts() {
+        for host in ["localhost", "LOCALHOST:8080", "127.0.0.1", "127.0.0.1:8080", "[::1]:8080"] {
+            assert!(loopback_host(host), "{host}");
+        }
+        for host in ["evil.example", "evil.example:8080", "192.168.1.10:8080", "[::2]:8080"] {
+            assert!(!loopback_host(host), "{host}");
+        }
+    }
+
+    #[test]
+    fn decodes_query_parameters() {
+        assert_eq!(query_param("a=1&token=x%2By%20z", "token").as_deref(), Some("x+y z"));
+        assert_eq!(query_param("a=1", "token"), None);
+    }
+
+    #[test]
+    fn resolves_caller_roles() {
+        let open = RemoteSettings {
+            tokens: Vec::new(),
+            ..settings(None)
+        };
+        let loopback = request("GET / HTTP/1.1\r\nHost: localhost:8080\r\n\r\n").unwrap();
+        let rebound = request("GET / HTTP/1.1\r\nHost: evil.example:8080\r\n\r\n").unwrap();
+        assert_eq!(caller_role(&loopback, &open), Some(RemoteRole::Full));
+        assert_eq!(caller_role(&rebound, &open), None);
+
+        let secured = settings(Some("secret"));
+        let bearer = request("GET / HTTP/1.1\r\nAuthorization: Bearer secret\r\n\r\n").unwrap();
+        let query = request("GET /?token=read-only HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(caller_role(&bearer, &secured), Some(RemoteRole::Full));
+        assert_eq!(caller_role(&query, &secured), Some(RemoteRole::Read));
+        assert_eq!(caller_role(&loopback, &secured), None);
+    }
+}
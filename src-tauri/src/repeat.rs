@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+
+/// Keys and buttons currently held on bindings that repeat. Each press gets
+/// its own hold id, so the repeat loop of an earlier press stops once its
+/// input is released, even if the input is pressed again right away.
+pub struct HeldInputs {
+    next_hold: u64,
+    held: HashMap<String, u64>,
+}
+
+impl HeldInputs {
+    pub fn new() -> Self {
+        Self {
+            next_hold: 0,
+            held: HashMap::new(),
+        }
+    }
+
+    /// Records `input` as held and returns the id of this hold.
+    pub fn press(&mut self, input: &str) -> u64 {
+        self.next_hold += 1;
+        self.held.insert(input.to_string(), self.next_hold);
+        self.next_hold
+    }
+
+    pub fn release(&mut self, input: &str) {
+        self.held.remove(input);
+    }
+
+    /// Releases every input whose name starts with `prefix`, e.g. all
+    /// buttons of a gamepad that was unplugged.
+    pub fn release_prefix(&mut self, prefix: &str) {
+        self.held.retain(|input, _| !input.starts_with(prefix));
+    }
+
+    pub fn release_all(&mut self) {
+        self.held.clear();
+    }
+
+    pub fn is_held(&self, input: &str, hold: u64) -> bool {
+        self.held.get(input) == Some(&hold)
+    }
+}
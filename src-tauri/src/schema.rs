@@ -0,0 +1,1332 @@
+use crate::config::{
+    DEFAULT_CANVAS_HEIGHT, DEFAULT_CANVAS_WIDTH, DEFAULT_GAMEPAD_DEADZONE, DEFAULT_GAMEPAD_TRIGGER_THRESHOLD,
+    DEFAULT_OBS_URL, DEFAULT_OSC_BIND, DEFAULT_OSC_PREFIX, DEFAULT_REMOTE_BIND, DEFAULT_REPEAT_DELAY_MS,
+    DEFAULT_SERIAL_INPUT_BAUD_RATE, DEFAULT_SYNC_BIND, DEFAULT_TICKER_SPEED, LAYOUT_KEYS, MAX_CANVAS_SIDE, MAX_CROSSFADE_MS, MAX_DISCOVERY_NAME_BYTES, MAX_MACRO_DELAY_MS, MAX_MACRO_STEPS, DEFAULT_SCRIPT_TICK_MS, MIN_SCRIPT_TICK_MS, MAX_SCRIPT_TICK_MS,
+    MAX_EDIT_RATE_LIMIT_MS, MAX_GAMEPAD_THRESHOLD, MAX_GROUP_SCALE, MAX_HISTORY_POINTS, MAX_KEYBIND_COOLDOWN_MS,
+    MAX_LAYER, MAX_LED_MATRIX_SCALE, MAX_LED_MATRIX_SIDE, MAX_LETTER_SPACING, MAX_NOTE_CHECKLIST_ITEMS,
+    MAX_OSC_TARGETS, MAX_OUTLINE_WIDTH, MAX_REPEAT_MS, MAX_ROTATION_INTERVAL_MS, MAX_SERIAL_FIELD_LENGTH,
+    MAX_SHADOW_BLUR, MAX_SHADOW_OFFSET, MAX_TICKER_MESSAGES, MAX_TICKER_SPEED, MAX_TIMER_DISPLAY_OFFSET_MS,
+    MIN_GAMEPAD_THRESHOLD, MIN_GROUP_SCALE, MIN_REPEAT_MS, MIN_ROTATION_INTERVAL_MS, SOUND_CUES, VISIBILITY_ACTION,
+};
+use scoreboard_core::expr::MAX_EXPRESSION_LENGTH;
+use serde_json::{json, Map, Value};
+
+/// Component types with the action names their `keybind`, `sound`, and
+/// `confirm.actions` tables accept. Must match `config::component_action_names`.
+const COMPONENT_TYPES: [(&str, &[&str]); 12] = [
+    ("number", &["increase", "decrease", "reset"]),
+    ("fouls", &["increase", "decrease", "reset"]),
+    ("timer", &["start", "stop", "reset", "increase", "decrease"]),
+    ("label", &[]),
+    ("image", &[]),
+    // `start` and `stop` also need `interval_ms`, which the loader checks.
+    ("image-toggle", &["forward", "backward", "start", "stop"]),
+    ("period", &["forward", "backward"]),
+    ("possession", &["toggle"]),
+    ("possession-time", &["home", "away", "reset"]),
+    ("ticker", &[]),
+    ("video", &["play", "pause"]),
+    ("derived", &[]),
+];
+
+/// Fields only some component types accept; each type rejects the ones it does not list.
+const TYPE_SPECIFIC_KEYS: [&str; 39] = [
+    "alignment",
+    "edit",
+    "source",
+    "sources",
+    "size",
+    "opacity",
+    "rounding",
+    "on_expire_sound",
+    "on_expire_relay",
+    "on_expire_duration_ms",
+    "tick_below",
+    "tick_sound",
+    "freeze_on_stop",
+    "voice_cues",
+    "t_minus",
+    "period_budget",
+    "bonus",
+    "double_bonus",
+    "reset_period",
+    "mirror",
+    "clock",
+    "labels",
+    "wrap",
+    "on_advance",
+    "multiline",
+    "max_width",
+    "messages",
+    "speed",
+    "width",
+    "expr",
+    "rate_limit_ms",
+    "interval_ms",
+    "persistent",
+    "fit",
+    "monospace_digits",
+    "history",
+    "score",
+    "loop",
+    "mute",
+];
+
+const TIMER_ROUNDING: [&str; 2] = ["standard", "basketball"];
+const TIMER_PATTERN: &str = "^\\d{2,}:[0-5]\\d:[0-5]\\d$";
+const COLOR_PATTERN: &str = "^#[0-9A-Fa-f]{6}([0-9A-Fa-f]{2})?$";
+const OPAQUE_COLOR_PATTERN: &str = "^#[0-9A-Fa-f]{6}$";
+const BACKGROUND_PATTERN: &str = "^(#[0-9A-Fa-f]{6}([0-9A-Fa-f]{2})?|transparent)$";
+const PERCENT_PATTERN: &str = "^\\s*(100(\\.0+)?|\\d{1,2}(\\.\\d+)?)\\s*%\\s*$";
+const HEX_BYTES_PATTERN: &str = "^\\s*((0[xX])?[0-9A-Fa-f]{1,2}\\s*)+$";
+
+/// Builds a JSON Schema (draft-07) for the scoreboard TOML format, for use
+/// with editors that validate TOML against JSON Schema.
+pub fn config_schema() -> Value {
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "AOLOT scoreboard config",
+        "description": "Top-level tables are components keyed by id, except [global], [group], [gamepad], [layout], and [macro].",
+        "type": "object",
+        "properties": {
+            "global": { "$ref": "#/definitions/global" },
+            "group": {
+                "type": "object",
+                "description": "Named blocks of components that move, scale, and hide together.",
+                "additionalProperties": { "$ref": "#/definitions/group" }
+            },
+            "gamepad": gamepad_schema(),
+            "layout": {
+                "type": "object",
+                "description": "Named layout variants that move and resize components for another display.",
+                "additionalProperties": layout_schema()
+            },
+            "macro": {
+                "type": "object",
+                "description": "Named sequences of actions fired by one keybind or by `macro.<name>`.",
+                "additionalProperties": macro_schema()
+            }
+        },
+        "additionalProperties": { "$ref": "#/definitions/component" },
+        "definitions": {
+            "keybind": keybind_schema(),
+            "font": font_schema(),
+            "timer_duration": {
+                "type": "string",
+                "pattern": TIMER_PATTERN,
+                "description": "Duration as HH:MM:SS."
+            },
+            "coordinate": {
+                "oneOf": [
+                    { "type": "integer" },
+                    { "type": "string", "pattern": PERCENT_PATTERN, "description": "Percentage of the canvas side, such as \"50%\"." }
+                ]
+            },
+            "roster": {
+                "type": "object",
+                "description": "Player names keyed by jersey number.",
+                "propertyNames": { "pattern": "^\\s*\\d{1,2}\\s*$" },
+                "additionalProperties": { "type": "string", "minLength": 1 }
+            },
+            "global": global_schema(),
+            "group": {
+                "type": "object",
+                "properties": {
+                    "offset": {
+                        "type": "object",
+                        "required": ["x", "y"],
+                        "properties": {
+                            "x": { "$ref": "#/definitions/coordinate" },
+                            "y": { "$ref": "#/definitions/coordinate" }
+                        },
+                        "additionalProperties": false
+                    },
+                    "visible": { "type": "boolean", "default": true },
+                    "scale": {
+                        "type": "number",
+                        "minimum": MIN_GROUP_SCALE,
+                        "maximum": MAX_GROUP_SCALE,
+                        "default": 1.0
+                    }
+                },
+                "additionalProperties": false
+            },
+            "component": component_schema()
+        }
+    })
+}
+
+fn gamepad_schema() -> Value {
+    json!({
+        "type": "object",
+        "description": "When sticks, axis D-pads, and analog triggers press their virtual buttons.",
+        "properties": {
+            "deadzone": {
+                "type": "number",
+                "minimum": MIN_GAMEPAD_THRESHOLD,
+                "maximum": MAX_GAMEPAD_THRESHOLD,
+                "default": DEFAULT_GAMEPAD_DEADZONE,
+                "description": "Share of full travel a stick or axis D-pad must move (LS_UP, DPAD_LEFT, ...)."
+            },
+            "trigger_threshold": {
+                "type": "number",
+                "minimum": MIN_GAMEPAD_THRESHOLD,
+                "maximum": MAX_GAMEPAD_THRESHOLD,
+                "default": DEFAULT_GAMEPAD_TRIGGER_THRESHOLD,
+                "description": "Share of full travel an analog trigger must be pulled (LT_FULL, RT_FULL)."
+            }
+        },
+        "additionalProperties": false
+    })
+}
+
+fn font_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "family": {
+                "oneOf": [
+                    { "type": "string", "minLength": 1 },
+                    { "type": "array", "items": { "type": "string", "minLength": 1 }, "minItems": 1 }
+                ],
+                "description": "A family, or families in order of preference; the first installed one is used."
+            },
+            "size": { "type": "integer", "minimum": 1 },
+            "color": { "type": "string", "pattern": COLOR_PATTERN },
+            "weight": {
+                "oneOf": [
+                    { "type": "string", "enum": ["normal", "bold"] },
+                    { "type": "integer", "minimum": 100, "maximum": 900, "multipleOf": 100 }
+                ],
+                "default": "bold"
+            },
+            "italic": { "type": "boolean", "default": false },
+            "letter_spacing": {
+                "type": "integer",
+                "minimum": -MAX_LETTER_SPACING,
+                "maximum": MAX_LETTER_SPACING,
+                "default": 0,
+                "description": "Extra pixels between characters."
+            },
+            "outline": {
+                "type": "object",
+                "required": ["color", "width"],
+                "properties": {
+                    "color": { "type": "string", "pattern": COLOR_PATTERN },
+                    "width": { "type": "integer", "minimum": 1, "maximum": MAX_OUTLINE_WIDTH }
+                },
+                "additionalProperties": false
+            },
+            "shadow": {
+                "type": "object",
+                "required": ["color"],
+                "properties": {
+                    "color": { "type": "string", "pattern": COLOR_PATTERN },
+                    "x": { "type": "integer", "minimum": -MAX_SHADOW_OFFSET, "maximum": MAX_SHADOW_OFFSET, "default": 2 },
+                    "y": { "type": "integer", "minimum": -MAX_SHADOW_OFFSET, "maximum": MAX_SHADOW_OFFSET, "default": 2 },
+                    "blur": { "type": "integer", "minimum": 0, "maximum": MAX_SHADOW_BLUR, "default": 0 }
+                },
+                "additionalProperties": false
+            }
+        },
+        "additionalProperties": false
+    })
+}
+
+fn keybind_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["key"],
+        "properties": {
+            "key": {
+                "type": "string",
+                "minLength": 1,
+                "description": "Keyboard key (e.g. \"A\", \"F1\", \"Space\") or gamepad button (e.g. \"Gamepad:A\", \"Gamepad:DPAD_UP\", \"Gamepad:LS_LEFT\", \"Gamepad:RT_FULL\"). \"Gamepad2:A\" only reacts to the second pad."
+            },
+            "ctrl": { "type": "boolean", "default": false },
+            "alt": { "type": "boolean", "default": false },
+            "shift": { "type": "boolean", "default": false },
+            "win": { "type": "boolean", "default": false },
+            "cooldown_ms": {
+                "type": "integer",
+                "minimum": 0,
+                "maximum": MAX_KEYBIND_COOLDOWN_MS,
+                "description": "Ignore repeat presses of this binding within this many milliseconds of the last one that applied."
+            },
+            "debounce_ms": {
+                "type": "integer",
+                "minimum": 0,
+                "maximum": MAX_KEYBIND_COOLDOWN_MS,
+                "description": "Another name for cooldown_ms; set one or the other."
+            },
+            "repeat_ms": {
+                "type": "integer",
+                "minimum": MIN_REPEAT_MS,
+                "maximum": MAX_REPEAT_MS,
+                "description": "Repeat the action every this many milliseconds while the key or button is held; increase, decrease, forward, and backward only."
+            },
+            "repeat_delay_ms": {
+                "type": "integer",
+                "minimum": MIN_REPEAT_MS,
+                "maximum": MAX_REPEAT_MS,
+                "default": DEFAULT_REPEAT_DELAY_MS,
+                "description": "How long the key or button must be held before repeating starts."
+            }
+        },
+        "additionalProperties": false
+    })
+}
+
+fn global_schema() -> Value {
+    let mut schema = json!({
+        "type": "object",
+        "properties": {
+            "canvas": {
+                "type": "object",
+                "required": ["width", "height"],
+                "description": "Design size in pixels; the frontend scales it to fit the window.",
+                "properties": {
+                    "width": { "type": "integer", "minimum": 1, "maximum": MAX_CANVAS_SIDE, "default": DEFAULT_CANVAS_WIDTH },
+                    "height": { "type": "integer", "minimum": 1, "maximum": MAX_CANVAS_SIDE, "default": DEFAULT_CANVAS_HEIGHT }
+                },
+                "additionalProperties": false
+            },
+            "background_color": {
+                "type": "string",
+                "pattern": BACKGROUND_PATTERN,
+                "description": "#RRGGBB, or \"transparent\" to render the window with alpha, e.g. as an OBS overlay."
+            },
+            "timer_display_offset_ms": {
+                "type": "integer",
+                "minimum": -MAX_TIMER_DISPLAY_OFFSET_MS,
+                "maximum": MAX_TIMER_DISPLAY_OFFSET_MS,
+                "default": 0,
+                "description": "Milliseconds subtracted from running timers before display, to offset video latency."
+            },
+            "font": { "$ref": "#/definitions/font" },
+            "keybind": action_keybinds(&["fullscreen", "player_select", "theme", "lock", "ready"]),
+            "player_action": {
+                "type": "object",
+                "additionalProperties": {
+                    "type": "object",
+                    "required": ["keybind"],
+                    "properties": {
+                        "keybind": { "$ref": "#/definitions/keybind" },
+                        "apply": {
+                            "type": "string",
+                            "pattern": "^[^.]+\\.[^.]+$",
+                            "description": "Component action to run as \"<component>.<action>\"."
+                        }
+                    },
+                    "additionalProperties": false
+                }
+            },
+            "announcement": {
+                "type": "object",
+                "required": ["target"],
+                "properties": {
+                    "target": { "type": "string", "description": "Id of the label component announcements replace." },
+                    "duration_ms": { "type": "integer", "minimum": 1, "default": 5000 },
+                    "message": {
+                        "type": "object",
+                        "additionalProperties": {
+                            "type": "object",
+                            "required": ["text"],
+                            "properties": {
+                                "text": { "type": "string" },
+                                "sound": { "type": "string", "minLength": 1 },
+                                "priority": { "type": "integer", "default": 0 },
+                                "duration_ms": { "type": "integer", "minimum": 1 },
+                                "interval": { "$ref": "#/definitions/timer_duration" },
+                                "keybind": { "$ref": "#/definitions/keybind" }
+                            },
+                            "additionalProperties": false
+                        }
+                    }
+                },
+                "additionalProperties": false
+            },
+            "relay": {
+                "type": "object",
+                "additionalProperties": {
+                    "type": "object",
+                    "required": ["port", "on", "off"],
+                    "properties": {
+                        "port": { "type": "string", "minLength": 1 },
+                        "baud_rate": { "type": "integer", "minimum": 1, "default": 9600 },
+                        "on": { "type": "string", "pattern": HEX_BYTES_PATTERN },
+                        "off": { "type": "string", "pattern": HEX_BYTES_PATTERN }
+                    },
+                    "additionalProperties": false
+                }
+            },
+            "webhook": {
+                "type": "object",
+                "additionalProperties": {
+                    "type": "object",
+                    "required": ["url"],
+                    "properties": {
+                        "url": { "type": "string", "pattern": "^https?://" },
+                        "format": { "type": "string", "enum": ["json", "discord"], "default": "json" },
+                        "batch_ms": { "type": "integer", "minimum": 1, "default": 2000 }
+                    },
+                    "additionalProperties": false
+                }
+            },
+            "summary": summary_schema(),
+            "lock": {
+                "type": "object",
+                "required": ["pin"],
+                "properties": {
+                    "pin": { "type": "string", "pattern": "^\\s*\\d{4,12}\\s*$", "description": "Digits that unlock a locked board." }
+                },
+                "additionalProperties": false
+            },
+            "theme": {
+                "type": "object",
+                "description": "Named palettes the theme keybind and set_theme command switch between.",
+                "additionalProperties": {
+                    "type": "object",
+                    "required": ["colors"],
+                    "properties": {
+                        "colors": {
+                            "type": "object",
+                            "description": "Maps a configured #RRGGBB color to its replacement.",
+                            "propertyNames": { "pattern": COLOR_PATTERN },
+                            "additionalProperties": { "type": "string", "pattern": COLOR_PATTERN }
+                        }
+                    },
+                    "additionalProperties": false
+                }
+            },
+            "led_matrix": {
+                "type": "object",
+                "required": ["port", "width", "height"],
+                "properties": {
+                    "port": { "type": "string", "minLength": 1 },
+                    "baud_rate": { "type": "integer", "minimum": 1, "default": 115200 },
+                    "width": { "type": "integer", "minimum": 1, "maximum": MAX_LED_MATRIX_SIDE },
+                    "height": { "type": "integer", "minimum": 1, "maximum": MAX_LED_MATRIX_SIDE },
+                    "protocol": { "type": "string", "enum": ["glediator", "adalight"], "default": "glediator" },
+                    "brightness": { "type": "number", "minimum": 0.0, "maximum": 1.0, "default": 1.0 },
+                    "component": {
+                        "type": "object",
+                        "description": "Components to draw, keyed by component id.",
+                        "additionalProperties": {
+                            "type": "object",
+                            "required": ["x", "y"],
+                            "properties": {
+                                "x": { "type": "integer", "minimum": 0 },
+                                "y": { "type": "integer", "minimum": 0 },
+                                "color": { "type": "string", "pattern": COLOR_PATTERN },
+                                "scale": { "type": "integer", "minimum": 1, "maximum": MAX_LED_MATRIX_SCALE, "default": 1 }
+                            },
+                            "additionalProperties": false
+                        }
+                    }
+                },
+                "additionalProperties": false
+            }
+        },
+        "additionalProperties": false
+    });
+    if let Value::Object(properties) = &mut schema["properties"] {
+        properties.insert(
+            "rotate".to_string(),
+            json!({
+                "type": "integer",
+                "enum": [0, 90, 180, 270],
+                "default": 0,
+                "description": "Clockwise degrees to turn the output by, for a display mounted on its side."
+            }),
+        );
+        properties.insert(
+            "chroma_key".to_string(),
+            json!({
+                "type": "string",
+                "pattern": OPAQUE_COLOR_PATTERN,
+                "description": "Key color filling the whole window for chroma-key capture; replaces background_color and ignores themes."
+            }),
+        );
+        properties.insert("fixtures".to_string(), fixtures_schema());
+        properties.insert("aux_timer".to_string(), aux_timer_schema());
+        properties.insert("horn_pattern".to_string(), horn_pattern_schema());
+        properties.insert("audio".to_string(), audio_schema());
+        properties.insert("playlist".to_string(), playlist_schema());
+        properties.insert("remote".to_string(), remote_schema());
+        properties.insert("osc".to_string(), osc_schema());
+        properties.insert("serial_input".to_string(), serial_input_schema());
+        properties.insert("obs".to_string(), obs_schema());
+        properties.insert("sync".to_string(), sync_schema());
+        properties.insert(
+            "discovery".to_string(),
+            json!({
+                "type": "object",
+                "description": "How the remote and sync endpoints are announced over mDNS.",
+                "properties": {
+                    "name": { "type": "string", "minLength": 1, "maxLength": MAX_DISCOVERY_NAME_BYTES, "pattern": "^[^.]+$", "description": "Name controllers see; the computer's name by default." },
+                    "advertise": { "type": "boolean", "default": true }
+                },
+                "additionalProperties": false
+            }),
+        );
+        properties.insert(
+            "updates".to_string(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "url": { "type": "string", "pattern": "^https?://", "description": "Releases API URL; defaults to the project's GitHub releases." },
+                    "check_on_startup": { "type": "boolean", "default": false }
+                },
+                "additionalProperties": false
+            }),
+        );
+        properties.insert(
+            "crash_report".to_string(),
+            json!({
+                "type": "object",
+                "description": "Opts in to saving a report when the app crashes.",
+                "properties": {
+                    "endpoint": { "type": "string", "pattern": "^https?://", "description": "Also posts each report here as JSON." }
+                },
+                "additionalProperties": false
+            }),
+        );
+        properties.insert(
+            "splash".to_string(),
+            json!({
+                "type": "object",
+                "description": "Scene shown when the config loads, until `keybind.ready` switches to the live board.",
+                "properties": {
+                    "image": { "type": "string", "minLength": 1, "description": "Image covering the canvas; a path or http(s) URL." },
+                    "group": { "type": "string", "minLength": 1, "description": "Group shown only while the splash is up." }
+                },
+                "minProperties": 1,
+                "additionalProperties": false
+            }),
+        );
+        properties.insert(
+            "notes".to_string(),
+            json!({
+                "type": "object",
+                "description": "Run-of-show notes for the operator console; never shown on the output.",
+                "anyOf": [{ "required": ["text"] }, { "required": ["checklist"] }],
+                "properties": {
+                    "title": { "type": "string", "minLength": 1 },
+                    "text": { "type": "string", "minLength": 1, "description": "Free text; line breaks are kept." },
+                    "checklist": {
+                        "type": "array",
+                        "items": { "type": "string", "minLength": 1 },
+                        "maxItems": MAX_NOTE_CHECKLIST_ITEMS
+                    }
+                },
+                "additionalProperties": false
+            }),
+        );
+        properties.insert(
+            "idle".to_string(),
+            json!({
+                "type": "object",
+                "description": "Low-power mode for boards left on around the clock.",
+                "required": ["after"],
+                "properties": {
+                    "after": {
+                        "$ref": "#/definitions/timer_duration",
+                        "description": "How long without input, and with nothing counting down or playing, before the board idles."
+                    },
+                    "dim": { "type": "number", "minimum": 0.0, "maximum": 1.0, "default": 0.5, "description": "Opacity of the whole board while idle." },
+                    "hide": {
+                        "type": "array",
+                        "items": { "type": "string", "minLength": 1 },
+                        "description": "Component ids hidden while idle."
+                    }
+                },
+                "additionalProperties": false
+            }),
+        );
+        properties.insert(
+            "script".to_string(),
+            json!({
+                "type": "object",
+                "description": "Rhai rules script whose on_action, on_timer_expire, and on_tick functions run on those events.",
+                "required": ["file"],
+                "properties": {
+                    "file": { "type": "string", "minLength": 1, "description": "Path of the .rhai file, relative to the config." },
+                    "tick_ms": {
+                        "type": "integer",
+                        "minimum": MIN_SCRIPT_TICK_MS,
+                        "maximum": MAX_SCRIPT_TICK_MS,
+                        "default": DEFAULT_SCRIPT_TICK_MS,
+                        "description": "How often on_tick runs."
+                    }
+                },
+                "additionalProperties": false
+            }),
+        );
+        properties.insert(
+            "layout".to_string(),
+            json!({ "type": "string", "minLength": 1, "description": "Name of the [layout.<name>] applied when the config loads." }),
+        );
+        properties.insert(
+            "freeze_on_error".to_string(),
+            json!({
+                "type": "boolean",
+                "default": false,
+                "description": "Keeps the last good board on screen, clocks running, when updating it fails."
+            }),
+        );
+        properties.insert(
+            "import_images".to_string(),
+            json!({
+                "type": "boolean",
+                "default": false,
+                "description": "Copies images picked for editable images into the config's assets folder."
+            }),
+        );
+        properties.insert(
+            "keyboard_mode".to_string(),
+            json!({
+                "type": "string",
+                "enum": ["auto", "global", "focused"],
+                "default": "auto",
+                "description": "Where keybinds are listened for: global shortcuts, only while the window is focused, or auto."
+            }),
+        );
+    }
+    schema
+}
+
+fn aux_timer_schema() -> Value {
+    json!({
+        "type": "object",
+        "description": "Countdowns kept off the canvas, keyed by name, driven by their keybinds and the aux timer commands.",
+        "additionalProperties": {
+            "type": "object",
+            "required": ["duration"],
+            "properties": {
+                "duration": { "$ref": "#/definitions/timer_duration" },
+                "label": { "type": "string", "minLength": 1 },
+                "sound": { "type": "string", "minLength": 1, "description": "Played when the countdown reaches zero." },
+                "keybind": {
+                    "type": "object",
+                    "properties": {
+                        "start": { "$ref": "#/definitions/keybind" },
+                        "stop": { "$ref": "#/definitions/keybind" },
+                        "reset": { "$ref": "#/definitions/keybind" }
+                    },
+                    "additionalProperties": false
+                }
+            },
+            "additionalProperties": false
+        }
+    })
+}
+
+fn audio_schema() -> Value {
+    let devices: Map<String, Value> = SOUND_CUES
+        .iter()
+        .map(|cue| (cue.to_string(), json!({ "type": "string", "minLength": 1 })))
+        .collect();
+    json!({
+        "type": "object",
+        "description": "Output devices for sounds, named as list_audio_devices reports them.",
+        "properties": {
+            "output_device": { "type": "string", "minLength": 1, "description": "Used by every sound without its own device; the system default when unset." },
+            "device": {
+                "type": "object",
+                "description": "Per-cue overrides of output_device.",
+                "properties": devices,
+                "additionalProperties": false
+            }
+        },
+        "additionalProperties": false
+    })
+}
+
+fn playlist_schema() -> Value {
+    json!({
+        "type": "object",
+        "description": "Background music played from the audio files in a folder.",
+        "required": ["folder"],
+        "properties": {
+            "folder": { "type": "string", "minLength": 1 },
+            "shuffle": { "type": "boolean", "default": false },
+            "crossfade_ms": { "type": "integer", "minimum": 0, "maximum": MAX_CROSSFADE_MS, "default": 0 },
+            "volume": { "type": "number", "minimum": 0.0, "maximum": 1.0, "default": 1.0 },
+            "clock": { "type": "string", "description": "Id of the timer whose start ducks or stops the music." },
+            "on_clock_start": { "type": "string", "enum": ["duck", "stop"], "default": "duck" },
+            "duck_volume": { "type": "number", "minimum": 0.0, "maximum": 1.0, "default": 0.2, "description": "Share of volume kept while ducked." },
+            "now_playing": { "type": "string", "description": "Id of the label component showing the track that is playing." },
+            "keybind": {
+                "type": "object",
+                "properties": {
+                    "play": { "$ref": "#/definitions/keybind" },
+                    "pause": { "$ref": "#/definitions/keybind" },
+                    "next": { "$ref": "#/definitions/keybind" }
+                },
+                "additionalProperties": false
+            }
+        },
+        "additionalProperties": false
+    })
+}
+
+fn remote_schema() -> Value {
+    json!({
+        "type": "object",
+        "description": "HTTP endpoint that fires actions by name, for Stream Deck, Companion, and custom panels.",
+        "required": ["port"],
+        "properties": {
+            "bind": { "type": "string", "minLength": 1, "default": DEFAULT_REMOTE_BIND, "description": "IP address to listen on; 0.0.0.0 accepts other machines." },
+            "port": { "type": "integer", "minimum": 1, "maximum": 65535 },
+            "token": { "type": "string", "minLength": 1, "description": "Required as a bearer token or `?token=` when set; grants full control. A bind other machines can reach needs this or `tokens`." },
+            "tokens": {
+                "type": "object",
+                "description": "Further tokens by name, each limited to a role.",
+                "additionalProperties": {
+                    "type": "object",
+                    "required": ["token", "role"],
+                    "properties": {
+                        "token": { "type": "string", "minLength": 1 },
+                        "role": { "enum": ["read", "timer", "full"], "description": "`read` only reads values, `timer` also fires timer and aux timer actions, `full` fires every action." }
+                    },
+                    "additionalProperties": false
+                }
+            }
+        },
+        "additionalProperties": false
+    })
+}
+
+fn macro_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["actions"],
+        "properties": {
+            "actions": {
+                "type": "array",
+                "minItems": 1,
+                "maxItems": MAX_MACRO_STEPS,
+                "description": "Action names as `list_actions` reports them, fired in order. A table adds a wait before its step.",
+                "items": {
+                    "oneOf": [
+                        { "type": "string", "minLength": 1, "not": { "pattern": "^macro\\." } },
+                        {
+                            "type": "object",
+                            "required": ["action"],
+                            "properties": {
+                                "action": { "type": "string", "minLength": 1, "not": { "pattern": "^macro\\." } },
+                                "delay_ms": { "type": "integer", "minimum": 0, "maximum": MAX_MACRO_DELAY_MS, "description": "Wait after the previous step." }
+                            },
+                            "additionalProperties": false
+                        }
+                    ]
+                }
+            },
+            "keybind": { "$ref": "#/definitions/keybind" }
+        },
+        "additionalProperties": false
+    })
+}
+
+fn sync_schema() -> Value {
+    json!({
+        "type": "object",
+        "description": "Mirrors one board onto others: the primary sets `listen`, replicas set `primary`.",
+        "oneOf": [{ "required": ["listen"] }, { "required": ["primary"] }],
+        "properties": {
+            "listen": { "type": "integer", "minimum": 1, "maximum": 65535, "description": "TCP port the primary serves replicas on." },
+            "bind": { "type": "string", "minLength": 1, "default": DEFAULT_SYNC_BIND, "description": "IP address the primary listens on." },
+            "primary": { "type": "string", "minLength": 1, "maxLength": MAX_DISCOVERY_NAME_BYTES, "description": "`host:port` of the primary on a replica, the name it announces over mDNS, or `auto` for the first one found." },
+            "token": { "type": "string", "minLength": 1, "description": "Shared secret replicas must present when set." }
+        },
+        "additionalProperties": false
+    })
+}
+
+fn osc_schema() -> Value {
+    json!({
+        "type": "object",
+        "description": "OSC over UDP: `<prefix>/<id>/<action>` fires actions, and value changes are sent as `<prefix>/<id>`.",
+        "anyOf": [{ "required": ["port"] }, { "required": ["send_to"] }],
+        "properties": {
+            "bind": { "type": "string", "minLength": 1, "default": DEFAULT_OSC_BIND },
+            "port": { "type": "integer", "minimum": 1, "maximum": 65535, "description": "UDP port to listen on; without it the board only sends." },
+            "prefix": { "type": "string", "pattern": "^/", "default": DEFAULT_OSC_PREFIX },
+            "send_to": {
+                "type": "array",
+                "description": "Hosts that receive every value change, as `IP:port`.",
+                "items": { "type": "string", "minLength": 1 },
+                "maxItems": MAX_OSC_TARGETS
+            },
+            "allow": {
+                "type": "object",
+                "description": "Hosts by IP address that may send, each with a role. Required when listening on an address other machines can reach.",
+                "additionalProperties": { "enum": ["read", "timer", "full"] }
+            }
+        },
+        "additionalProperties": false
+    })
+}
+
+fn serial_input_schema() -> Value {
+    json!({
+        "type": "object",
+        "description": "A hardware scoreboard console read over a serial port; `map` ties its fields to component ids.",
+        "required": ["port", "protocol", "map"],
+        "properties": {
+            "port": { "type": "string", "minLength": 1, "description": "e.g. COM3 or /dev/ttyUSB0." },
+            "baud_rate": { "type": "integer", "minimum": 1, "default": DEFAULT_SERIAL_INPUT_BAUD_RATE },
+            "protocol": { "type": "string", "enum": ["daktronics", "lines"] },
+            "map": {
+                "type": "object",
+                "description": "Keyed by number, fouls, timer, label, or period component id.",
+                "minProperties": 1,
+                "additionalProperties": {
+                    "type": "object",
+                    "properties": {
+                        "offset": { "type": "integer", "minimum": 0, "description": "daktronics: 0-based position of the field in the sport's data." },
+                        "length": { "type": "integer", "minimum": 1, "maximum": MAX_SERIAL_FIELD_LENGTH, "description": "daktronics: number of characters." },
+                        "field": { "type": "string", "minLength": 1, "description": "lines: the name before '='." }
+                    },
+                    "additionalProperties": false
+                }
+            }
+        },
+        "additionalProperties": false
+    })
+}
+
+fn obs_schema() -> Value {
+    json!({
+        "type": "object",
+        "description": "obs-websocket connection whose triggers switch OBS scenes or toggle sources on game events.",
+        "required": ["trigger"],
+        "properties": {
+            "url": { "type": "string", "pattern": "^ws://", "default": DEFAULT_OBS_URL },
+            "password": { "type": "string", "minLength": 1, "description": "The server password set in OBS under Tools > WebSocket Server Settings." },
+            "trigger": {
+                "type": "object",
+                "minProperties": 1,
+                "additionalProperties": {
+                    "type": "object",
+                    "oneOf": [{ "required": ["on_expire"] }, { "required": ["on_period"] }],
+                    "anyOf": [{ "required": ["scene"] }, { "required": ["source"] }],
+                    "dependencies": { "source": ["source_scene"], "source_scene": ["source"], "visible": ["source"], "to": ["on_period"] },
+                    "properties": {
+                        "on_expire": { "type": "string", "minLength": 1, "description": "Timer whose expiry fires the trigger." },
+                        "on_period": { "type": "string", "minLength": 1, "description": "Period whose advance fires the trigger." },
+                        "to": { "type": "string", "minLength": 1, "description": "Only fire when the period advances to this label." },
+                        "scene": { "type": "string", "minLength": 1, "description": "Scene to switch the program to." },
+                        "source": { "type": "string", "minLength": 1 },
+                        "source_scene": { "type": "string", "minLength": 1, "description": "Scene the source is in." },
+                        "visible": { "type": "boolean", "default": true }
+                    },
+                    "additionalProperties": false
+                }
+            }
+        },
+        "additionalProperties": false
+    })
+}
+
+fn horn_pattern_schema() -> Value {
+    json!({
+        "type": "object",
+        "description": "Horn blast sequences keyed by name; any sound setting written as `pattern:<name>` plays one.",
+        "additionalProperties": {
+            "type": "object",
+            "required": ["blasts_ms"],
+            "properties": {
+                "sound": { "type": "string", "minLength": 1, "description": "Looped for each blast; a generated tone when unset." },
+                "blasts_ms": {
+                    "type": "array",
+                    "items": { "type": "integer", "minimum": 1, "maximum": 10000 },
+                    "minItems": 1,
+                    "maxItems": 16
+                },
+                "gap_ms": { "type": "integer", "minimum": 0, "maximum": 10000, "default": 250 }
+            },
+            "additionalProperties": false
+        }
+    })
+}
+
+fn fixtures_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["source", "countdown"],
+        "properties": {
+            "source": { "type": "string", "minLength": 1, "description": "Path or http(s) URL of an iCalendar (.ics) file with the games." },
+            "countdown": { "type": "string", "description": "Id of the timer counting down to the start of the game." },
+            "lead": { "$ref": "#/definitions/timer_duration", "default": "00:30:00" },
+            "refresh": { "$ref": "#/definitions/timer_duration", "default": "00:15:00" },
+            "utc_offset": { "type": "string", "pattern": "^[+-]\\d{2}:\\d{2}$", "default": "+00:00", "description": "Offset of calendar times that are not in UTC." },
+            "home_label": { "type": "string", "description": "Id of the label component set to the home team." },
+            "away_label": { "type": "string", "description": "Id of the label component set to the away team." },
+            "config": {
+                "type": "object",
+                "description": "Maps a keyword in game titles to the config to load for those games.",
+                "additionalProperties": { "type": "string", "minLength": 1 }
+            }
+        },
+        "additionalProperties": false
+    })
+}
+
+fn summary_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["home", "away"],
+        "properties": {
+            "home": { "type": "string", "description": "Id of the home score number component." },
+            "away": { "type": "string", "description": "Id of the away score number component." },
+            "home_name": { "type": "string", "minLength": 1, "default": "Home" },
+            "away_name": { "type": "string", "minLength": 1, "default": "Away" },
+            "period": { "type": "string", "description": "Id of the period component the line score follows." },
+            "clock": { "type": "string", "description": "Id of the timer whose time stamps game log entries." },
+            "home_fouls": { "type": "string", "description": "Id of the home team fouls component." },
+            "away_fouls": { "type": "string", "description": "Id of the away team fouls component." },
+            "home_timeouts": { "type": "string", "description": "Id of the home timeouts remaining number component." },
+            "away_timeouts": { "type": "string", "description": "Id of the away timeouts remaining number component." },
+            "home_roster": { "$ref": "#/definitions/roster" },
+            "away_roster": { "$ref": "#/definitions/roster" },
+            "markdown": { "type": "string", "description": "Template for Markdown summaries." },
+            "text": { "type": "string", "description": "Template for plain-text summaries." }
+        },
+        "additionalProperties": false
+    })
+}
+
+fn component_schema() -> Value {
+    let type_names: Vec<&str> = COMPONENT_TYPES.iter().map(|(name, _)| *name).collect();
+    let variants: Vec<Value> = COMPONENT_TYPES
+        .iter()
+        .map(|(name, actions)| component_variant(name, actions))
+        .collect();
+
+    let mut schema = json!({
+        "type": "object",
+        "required": ["type", "position"],
+        "properties": {
+            "type": {
+                "oneOf": [
+                    { "type": "string", "enum": type_names },
+                    {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string", "enum": type_names },
+                            "kind": { "type": "string", "enum": type_names },
+                            "rounding": { "type": "string", "enum": TIMER_ROUNDING }
+                        },
+                        "additionalProperties": false
+                    }
+                ]
+            },
+            "font": { "$ref": "#/definitions/font" },
+            "default": {},
+            "keybind": { "type": "object" },
+            "sound": { "type": "object" },
+            "confirm": { "type": "object" },
+            "edit": { "type": "boolean" },
+            "source": { "type": "string", "minLength": 1 },
+            "sources": { "type": "array", "items": { "type": "string", "minLength": 1 }, "minItems": 1 },
+            "size": {
+                "type": "object",
+                "required": ["width", "height"],
+                "properties": {
+                    "width": { "type": "integer", "minimum": 1 },
+                    "height": { "type": "integer", "minimum": 1 }
+                },
+                "additionalProperties": false
+            },
+            "opacity": { "type": "number", "minimum": 0.0, "maximum": 1.0 },
+            "rounding": { "type": "string", "enum": TIMER_ROUNDING },
+            "on_expire_sound": { "type": "string", "minLength": 1, "description": "Sound file, or `pattern:<name>` for a horn pattern." },
+            "on_expire_relay": { "type": "string" },
+            "on_expire_duration_ms": { "type": "integer", "minimum": 1 },
+            "tick_below": { "$ref": "#/definitions/timer_duration" },
+            "tick_sound": { "type": "string", "minLength": 1 },
+            "period_budget": {
+                "type": "object",
+                "required": ["period", "values"],
+                "properties": {
+                    "period": { "type": "string" },
+                    "values": { "type": "object", "additionalProperties": { "type": "integer" } }
+                },
+                "additionalProperties": false
+            },
+            "bonus": { "type": "integer", "minimum": 1 },
+            "double_bonus": { "type": "integer", "minimum": 1 },
+            "reset_period": { "type": "string" },
+            "clock": {
+                "type": "string",
+                "description": "Id of the game clock timer; possession time only counts while it runs."
+            },
+            "mirror": {
+                "type": "string",
+                "description": "Id of a timer to mirror; this timer shows its time and has no value or actions of its own."
+            },
+            "labels": { "type": "array", "items": { "type": "string" }, "minItems": 1 },
+            "wrap": { "type": "boolean" },
+            "messages": {
+                "type": "array",
+                "items": { "type": "string", "minLength": 1 },
+                "maxItems": MAX_TICKER_MESSAGES
+            },
+            "speed": { "type": "integer", "minimum": 1, "maximum": MAX_TICKER_SPEED, "default": DEFAULT_TICKER_SPEED },
+            "width": { "type": "integer", "minimum": 1, "description": "Width of the visible strip in pixels." },
+            "on_advance": {
+                "type": "object",
+                "required": ["reset_timer"],
+                "properties": {
+                    "reset_timer": { "type": "string" },
+                    "durations": { "type": "array", "items": { "$ref": "#/definitions/timer_duration" } }
+                },
+                "additionalProperties": false
+            }
+        },
+        "additionalProperties": false,
+        "allOf": variants
+    });
+    if let Value::Object(properties) = &mut schema["properties"] {
+        properties.extend(placement_properties());
+        properties.insert(
+            "interval_ms".to_string(),
+            json!({
+                "type": "integer",
+                "minimum": MIN_ROTATION_INTERVAL_MS,
+                "maximum": MAX_ROTATION_INTERVAL_MS,
+                "description": "Advance to the next source automatically every this many milliseconds; enables the start and stop actions."
+            }),
+        );
+        properties.insert(
+            "expr".to_string(),
+            json!({
+                "type": "string",
+                "minLength": 1,
+                "maxLength": MAX_EXPRESSION_LENGTH,
+                "description": "Expression over number, fouls, timer, period, and label ids, e.g. `home_score - away_score` or `if home_fouls >= 7 then \"BONUS\" else \"\"`."
+            }),
+        );
+        properties.insert(
+            "rate_limit_ms".to_string(),
+            json!({
+                "type": "integer",
+                "minimum": 1,
+                "maximum": MAX_EDIT_RATE_LIMIT_MS,
+                "description": "Refuse runtime edits that arrive sooner than this after the last applied one."
+            }),
+        );
+        properties.insert(
+            "freeze_on_stop".to_string(),
+            json!({
+                "type": "boolean",
+                "default": false,
+                "description": "Keep showing the value the timer stopped at until it is started or reset, even if it is adjusted in the meantime."
+            }),
+        );
+        properties.insert(
+            "voice_cues".to_string(),
+            json!({
+                "type": "array",
+                "items": { "$ref": "#/definitions/timer_duration" },
+                "description": "Remaining times at which the countdown is spoken, e.g. \"00:01:00\" for \"1 minute\"."
+            }),
+        );
+        properties.insert(
+            "t_minus".to_string(),
+            json!({
+                "type": "array",
+                "items": { "$ref": "#/definitions/timer_duration" },
+                "description": "Remaining times at which a scoreboard://t-minus event (and OSC message) is sent, for video servers timing bumpers to the horn."
+            }),
+        );
+        properties.insert(
+            "monospace_digits".to_string(),
+            json!({
+                "type": "boolean",
+                "default": false,
+                "description": "Give every digit the same width so changing values do not shift the text."
+            }),
+        );
+        properties.insert(
+            "fit".to_string(),
+            json!({
+                "type": "object",
+                "description": "Shrink the text to stay inside this box. The font size is the largest size used.",
+                "properties": {
+                    "max_width": { "type": "integer", "minimum": 1, "maximum": MAX_CANVAS_SIDE },
+                    "max_height": { "type": "integer", "minimum": 1, "maximum": MAX_CANVAS_SIDE }
+                },
+                "minProperties": 1,
+                "additionalProperties": false
+            }),
+        );
+        properties.insert(
+            "multiline".to_string(),
+            json!({
+                "type": "boolean",
+                "default": false,
+                "description": "Allow line breaks in the label text."
+            }),
+        );
+        properties.insert(
+            "max_width".to_string(),
+            json!({
+                "type": "integer",
+                "minimum": 1,
+                "maximum": MAX_CANVAS_SIDE,
+                "description": "Wrap lines longer than this many pixels. Requires `multiline`."
+            }),
+        );
+        properties.insert(
+            "persistent".to_string(),
+            json!({
+                "type": "boolean",
+                "default": false,
+                "description": "Restore the last value when this config is loaded again, even after a restart."
+            }),
+        );
+        properties.insert(
+            "history".to_string(),
+            json!({
+                "type": "integer",
+                "minimum": 1,
+                "maximum": MAX_HISTORY_POINTS,
+                "description": "Number of past values kept with timestamps for `get_number_history`."
+            }),
+        );
+        properties.insert(
+            "score".to_string(),
+            json!({
+                "type": "boolean",
+                "default": false,
+                "description": "Count this number's changes as points in `get_score_by_period` and the line score."
+            }),
+        );
+        properties.insert(
+            "loop".to_string(),
+            json!({ "type": "boolean", "default": false, "description": "Start the video over when it ends." }),
+        );
+        properties.insert(
+            "mute".to_string(),
+            json!({ "type": "boolean", "default": false, "description": "Play the video without sound." }),
+        );
+    }
+    schema
+}
+
+/// One `[layout.<name>]`: a canvas plus placement overrides keyed by component id.
+fn layout_schema() -> Value {
+    let overrides: Map<String, Value> = LAYOUT_KEYS
+        .iter()
+        .map(|key| {
+            let schema = match *key {
+                "font" => json!({ "type": "object", "description": "Merged into the component's font, so `size` alone is enough." }),
+                _ => json!({ "$ref": format!("#/definitions/component/properties/{key}") }),
+            };
+            (key.to_string(), schema)
+        })
+        .collect();
+    json!({
+        "type": "object",
+        "properties": {
+            "canvas": { "$ref": "#/definitions/global/properties/canvas" },
+            "rotate": { "$ref": "#/definitions/global/properties/rotate" }
+        },
+        "additionalProperties": {
+            "type": "object",
+            "properties": overrides,
+            "additionalProperties": false
+        }
+    })
+}
+
+/// Where and whether a component is drawn; shared by every component type.
+fn placement_properties() -> Map<String, Value> {
+    let placement = json!({
+        "position": {
+            "type": "object",
+            "required": ["x", "y"],
+            "description": "Offset inside [global.canvas], in pixels or as a percentage of its width and height.",
+            "properties": {
+                "x": { "$ref": "#/definitions/coordinate" },
+                "y": { "$ref": "#/definitions/coordinate" }
+            },
+            "additionalProperties": false
+        },
+        "alignment": { "type": "string", "enum": ["center", "left", "right", "top", "bottom", "baseline"] },
+        "visible": { "type": "boolean", "default": true },
+        "group": {
+            "type": "string",
+            "description": "Name of a [group.<name>]; position becomes relative to the group's offset."
+        },
+        "layer": {
+            "type": "integer",
+            "minimum": -MAX_LAYER,
+            "maximum": MAX_LAYER,
+            "description": "Stacking order; higher layers draw on top. Defaults to 10 for images and 30 for text."
+        }
+    });
+    match placement {
+        Value::Object(properties) => properties,
+        _ => Map::new(),
+    }
+}
+
+/// Narrows the shared component properties for one `type`: which fields it
+/// requires or accepts, and which action names its keybinds and sounds use.
+fn component_variant(component_type: &str, actions: &[&str]) -> Value {
+    let (required, allowed): (&[&str], &[&str]) = match component_type {
+        "number" => (
+            &["default"],
+            &["alignment", "opacity", "fit", "monospace_digits", "period_budget", "persistent", "history", "score"],
+        ),
+        "fouls" => (&[], &["alignment", "opacity", "fit", "monospace_digits", "bonus", "double_bonus", "reset_period"]),
+        // Timers need a `default` unless they mirror another timer; see the `oneOf` below.
+        "timer" => (
+            &[],
+            &[
+                "alignment",
+                "opacity",
+                "fit",
+                "monospace_digits",
+                "mirror",
+                "rounding",
+                "on_expire_sound",
+                "on_expire_relay",
+                "on_expire_duration_ms",
+                "tick_below",
+                "tick_sound",
+                "freeze_on_stop",
+                "voice_cues",
+                "t_minus",
+            ],
+        ),
+        "label" => (
+            &["default"],
+            &[
+                "alignment",
+                "opacity",
+                "fit",
+                "monospace_digits",
+                "edit",
+                "rate_limit_ms",
+                "persistent",
+                "multiline",
+                "max_width",
+            ],
+        ),
+        "image" => (&["source", "size"], &["opacity", "edit", "rate_limit_ms"]),
+        "image-toggle" => (&["sources", "size"], &["opacity", "interval_ms"]),
+        "period" => (&[], &["alignment", "opacity", "fit", "monospace_digits", "labels", "wrap", "on_advance"]),
+        "possession" => (&[], &["alignment", "labels", "sources", "size", "opacity", "fit", "monospace_digits"]),
+        "possession-time" => (&["clock"], &["alignment", "opacity", "fit", "monospace_digits"]),
+        "ticker" => (&["width"], &["opacity", "messages", "speed", "rate_limit_ms", "monospace_digits"]),
+        "video" => (&["source", "size"], &["opacity", "loop", "mute"]),
+        "derived" => (&["expr"], &["alignment", "opacity", "fit", "monospace_digits"]),
+        _ => (&[], &[]),
+    };
+
+    let mut properties = Map::new();
+    properties.insert("default".to_string(), default_schema(component_type));
+    // Every type can bind `visibility`, even those without actions of their own.
+    let keybind_actions: Vec<&str> = actions.iter().copied().chain([VISIBILITY_ACTION]).collect();
+    let mut keybind = action_keybinds(&keybind_actions);
+    if component_type == "image-toggle" {
+        keybind["patternProperties"] = json!({ "^set_[0-9]+$": { "$ref": "#/definitions/keybind" } });
+    }
+    properties.insert("keybind".to_string(), keybind);
+    if actions.is_empty() {
+        for key in ["sound", "confirm"] {
+            properties.insert(key.to_string(), json!(false));
+        }
+    } else {
+        properties.insert("sound".to_string(), action_sounds(actions));
+        properties.insert("confirm".to_string(), confirm_schema(actions));
+    }
+    for key in TYPE_SPECIFIC_KEYS {
+        if !required.contains(&key) && !allowed.contains(&key) {
+            properties.insert(key.to_string(), json!(false));
+        }
+    }
+
+    let mut then = json!({
+        "required": required,
+        "properties": properties
+    });
+    if component_type == "timer" {
+        then["oneOf"] = json!([{ "required": ["default"] }, { "required": ["mirror"] }]);
+    }
+
+    json!({
+        "if": {
+            "properties": {
+                "type": {
+                    "anyOf": [
+                        { "const": component_type },
+                        {
+                            "type": "object",
+                            "anyOf": [
+                                { "properties": { "name": { "const": component_type } }, "required": ["name"] },
+                                { "properties": { "kind": { "const": component_type } }, "required": ["kind"] }
+                            ]
+                        }
+                    ]
+                }
+            }
+        },
+        "then": then
+    })
+}
+
+fn default_schema(component_type: &str) -> Value {
+    match component_type {
+        "number" | "fouls" => json!({ "type": "integer" }),
+        "timer" => json!({ "$ref": "#/definitions/timer_duration" }),
+        "label" => json!({ "type": "string" }),
+        "image-toggle" => json!({ "type": "integer", "minimum": 0, "description": "0-based source index." }),
+        "period" => json!({ "type": "integer", "minimum": 1, "description": "1-based label index." }),
+        "possession" => json!({ "type": "integer", "enum": [1, 2] }),
+        _ => json!(false),
+    }
+}
+
+fn action_keybinds(actions: &[&str]) -> Value {
+    let properties: Map<String, Value> = actions
+        .iter()
+        .map(|action| (action.to_string(), json!({ "$ref": "#/definitions/keybind" })))
+        .collect();
+    json!({ "type": "object", "properties": properties, "additionalProperties": false })
+}
+
+fn action_sounds(actions: &[&str]) -> Value {
+    let properties: Map<String, Value> = actions
+        .iter()
+        .map(|action| (action.to_string(), json!({ "type": "string", "minLength": 1 })))
+        .collect();
+    json!({ "type": "object", "properties": properties, "additionalProperties": false })
+}
+
+fn confirm_schema(actions: &[&str]) -> Value {
+    let table = json!({
+        "type": "object",
+        "required": ["actions"],
+        "properties": {
+            "actions": { "type": "array", "items": { "type": "string", "enum": actions } },
+            "timeout_ms": { "type": "integer", "minimum": 1, "default": 3000 },
+            "on_timeout": { "type": "string", "enum": ["cancel", "commit"], "default": "cancel" }
+        },
+        "additionalProperties": false
+    });
+    if !actions.contains(&"reset") {
+        return table;
+    }
+    json!({
+        "oneOf": [
+            { "type": "boolean", "description": "true: reset needs a second press within 2 seconds." },
+            table
+        ]
+    })
+}
@@ -0,0 +1,141 @@
+use std::fs;
+use std::path::Path;
+
+// A4 portrait in points, with Courier at 9 pt: 0.6 em per character gives
+// 95 characters per line inside the margins.
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 40.0;
+const FONT_SIZE: f32 = 9.0;
+const LEADING: f32 = 12.0;
+const CHARS_PER_LINE: usize = 95;
+/// Leaves room at the bottom of each page for the page number.
+const LINES_PER_PAGE: usize = ((PAGE_HEIGHT - 2.0 * MARGIN) / LEADING) as usize - 2;
+
+/// One line of the scoresheet. Headings are set in bold.
+#[derive(Debug, Clone)]
+pub struct SheetLine {
+    pub text: String,
+    pub bold: bool,
+}
+
+impl SheetLine {
+    pub fn heading(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            bold: true,
+        }
+    }
+
+    pub fn text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            bold: false,
+        }
+    }
+
+    pub fn blank() -> Self {
+        Self::text("")
+    }
+}
+
+/// Writes `lines` as a PDF in monospaced type, so the padded tables keep their
+/// columns. Uses the standard PDF fonts, which every viewer has, so nothing
+/// needs to be embedded; characters outside Latin-1 print as `?`.
+pub fn write_scoresheet_pdf(path: &Path, lines: &[SheetLine]) -> Result<(), String> {
+    let mut wrapped = Vec::new();
+    for line in lines {
+        let chars: Vec<char> = line.text.chars().collect();
+        if chars.is_empty() {
+            wrapped.push(line.clone());
+        }
+        for chunk in chars.chunks(CHARS_PER_LINE) {
+            wrapped.push(SheetLine {
+                text: chunk.iter().collect(),
+                bold: line.bold,
+            });
+        }
+    }
+    let pages: Vec<&[SheetLine]> = wrapped.chunks(LINES_PER_PAGE).collect();
+    let page_count = pages.len().max(1);
+
+    // Objects 1-4 are the catalog, page tree, and the two fonts; each page
+    // then takes two objects, the page and its content stream.
+    let mut objects: Vec<Vec<u8>> = Vec::new();
+    objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+    let kids: Vec<String> = (0..page_count).map(|index| format!("{} 0 R", 5 + index * 2)).collect();
+    objects.push(format!("<< /Type /Pages /Kids [{}] /Count {page_count} >>", kids.join(" ")).into_bytes());
+    for font in ["Courier", "Courier-Bold"] {
+        objects.push(
+            format!("<< /Type /Font /Subtype /Type1 /BaseFont /{font} /Encoding /WinAnsiEncoding >>").into_bytes(),
+        );
+    }
+    for index in 0..page_count {
+        let mut content = Vec::new();
+        let page_lines = pages.get(index).copied().unwrap_or_default();
+        for (row, line) in page_lines.iter().enumerate() {
+            let y = PAGE_HEIGHT - MARGIN - FONT_SIZE - row as f32 * LEADING;
+            push_text(&mut content, line, MARGIN, y);
+        }
+        let footer = SheetLine::text(format!("Page {} of {page_count}", index + 1));
+        let footer_x = PAGE_WIDTH - MARGIN - footer.text.len() as f32 * FONT_SIZE * 0.6;
+        push_text(&mut content, &footer, footer_x, MARGIN);
+
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                6 + index * 2
+            )
+            .into_bytes(),
+        );
+        let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+        stream.extend(content);
+        stream.extend(b"\nendstream");
+        objects.push(stream);
+    }
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::new();
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend(format!("{} 0 obj\n", index + 1).into_bytes());
+        pdf.extend(object);
+        pdf.extend(b"\nendobj\n");
+    }
+    let xref_offset = pdf.len();
+    pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+    for offset in offsets {
+        pdf.extend(format!("{offset:010} 00000 n \n").into_bytes());
+    }
+    pdf.extend(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n",
+            objects.len() + 1
+        )
+        .into_bytes(),
+    );
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed creating scoresheet directory {}: {e}", parent.display()))?;
+    }
+    fs::write(path, pdf).map_err(|e| format!("Failed writing scoresheet {}: {e}", path.display()))
+}
+
+fn push_text(content: &mut Vec<u8>, line: &SheetLine, x: f32, y: f32) {
+    if line.text.is_empty() {
+        return;
+    }
+    let font = if line.bold { "F2" } else { "F1" };
+    content.extend(format!("BT /{font} {FONT_SIZE} Tf {x:.1} {y:.1} Td (").into_bytes());
+    for c in line.text.chars() {
+        match c {
+            '(' | ')' | '\\' => content.extend([b'\\', c as u8]),
+            ' '..='~' => content.push(c as u8),
+            '\u{A0}'..='\u{FF}' => content.push(c as u32 as u8),
+            _ => content.push(b'?'),
+        }
+    }
+    content.extend(b") Tj ET\n");
+}
@@ -0,0 +1,390 @@
+use crate::config::{ComponentKind, ScriptSettings};
+use crate::state::RuntimeState;
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{CallFnOptions, Dynamic, Engine, EvalAltResult, Scope, AST, INT};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Work one hook may do before it is stopped, so a stuck loop cannot hang the board.
+const MAX_OPERATIONS: u64 = 1_000_000;
+/// `on_tick` runs on the timer thread between clock updates, so it may do far
+/// less before it would hold up the clock.
+const MAX_TICK_OPERATIONS: u64 = 20_000;
+/// `set` and `fire` calls one hook may queue.
+const MAX_CHANGES: usize = 1_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_STRING_SIZE: usize = 10_000;
+const MAX_COLLECTION_SIZE: usize = 10_000;
+
+/// A board event a rules script can handle.
+#[derive(Debug, Clone)]
+pub enum ScriptHook {
+    /// An action that changed the board, by its `list_actions` name.
+    Action(String),
+    TimerExpire(String),
+    Tick,
+}
+
+impl ScriptHook {
+    fn function_name(&self) -> &'static str {
+        match self {
+            ScriptHook::Action(_) => "on_action",
+            ScriptHook::TimerExpire(_) => "on_timer_expire",
+            ScriptHook::Tick => "on_tick",
+        }
+    }
+
+    fn argument(&self) -> Option<&str> {
+        match self {
+            ScriptHook::Action(name) | ScriptHook::TimerExpire(name) => Some(name),
+            ScriptHook::Tick => None,
+        }
+    }
+}
+
+/// A change a hook asked for, applied to the board once the hook returns.
+#[derive(Debug, Clone)]
+pub enum ScriptChange {
+    Set(ScriptSet),
+    /// An action name to run as if its key had been pressed.
+    Fire(String),
+}
+
+/// A component value set by a script.
+#[derive(Debug, Clone)]
+pub enum ScriptSet {
+    /// A number or period value, as text `set_console_value` accepts.
+    Value { id: String, text: String },
+    Label { id: String, text: String },
+    /// A timer's remaining time; a running timer keeps running.
+    Timer { id: String, remaining_ms: i64 },
+}
+
+impl ScriptSet {
+    /// Returns whether the board changed. Changes go in the event log.
+    pub fn apply(self, runtime: &mut RuntimeState) -> Result<bool, String> {
+        let (id, changed) = match self {
+            // Logged by `set_console_value` itself.
+            ScriptSet::Value { id, text } => return runtime.set_console_value(&id, &text),
+            ScriptSet::Label { id, text } => {
+                let changed = runtime.set_label_text(&id, text);
+                (id, changed)
+            }
+            ScriptSet::Timer { id, remaining_ms } => {
+                let changed = runtime.set_timer_remaining(&id, remaining_ms)?;
+                (id, changed)
+            }
+        };
+        if changed {
+            runtime.record_set(&id);
+        }
+        Ok(changed)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum ScriptKind {
+    Number,
+    Timer,
+    Period(Vec<String>),
+    Label,
+}
+
+/// The board as a hook sees it: values read when the hook starts, updated by
+/// its own `set` calls so later reads in the same hook see them.
+#[derive(Default)]
+pub struct ScriptBoard {
+    kinds: HashMap<String, ScriptKind>,
+    values: HashMap<String, Dynamic>,
+    running: HashSet<String>,
+    changes: Vec<ScriptChange>,
+}
+
+impl ScriptBoard {
+    fn read(runtime: &RuntimeState) -> Self {
+        let mut board = ScriptBoard::default();
+        let Some(config) = &runtime.config else {
+            return board;
+        };
+        let values = runtime.export_values();
+        for component in &config.components {
+            let id = component.id.clone();
+            let (kind, value) = match &component.kind {
+                ComponentKind::Number { .. } | ComponentKind::Fouls { .. } => {
+                    let Some(&value) = values.numbers.get(&id) else {
+                        continue;
+                    };
+                    (ScriptKind::Number, Dynamic::from_int(INT::from(value)))
+                }
+                ComponentKind::Timer { .. } => {
+                    // Mirrors have no value of their own.
+                    let Some(&remaining_ms) = values.timers.get(&id) else {
+                        continue;
+                    };
+                    if runtime.timer_running(&id) {
+                        board.running.insert(id.clone());
+                    }
+                    (ScriptKind::Timer, Dynamic::from_int(remaining_ms))
+                }
+                ComponentKind::Period { labels, .. } => {
+                    let index = values.period_indices.get(&id).copied().unwrap_or(0);
+                    let label = labels.get(index).cloned().unwrap_or_default();
+                    (ScriptKind::Period(labels.clone()), Dynamic::from(label))
+                }
+                ComponentKind::Label { .. } => {
+                    let text = values.labels.get(&id).cloned().unwrap_or_default();
+                    (ScriptKind::Label, Dynamic::from(text))
+                }
+                _ => continue,
+            };
+            board.kinds.insert(id.clone(), kind);
+            board.values.insert(id, value);
+        }
+        board
+    }
+
+    fn get(&self, id: &str) -> Result<Dynamic, Box<EvalAltResult>> {
+        self.values
+            .get(id)
+            .cloned()
+            .ok_or_else(|| format!("get('{id}'): not a number, fouls, timer, period, or label component").into())
+    }
+
+    fn set(&mut self, id: &str, value: Dynamic) -> Result<(), Box<EvalAltResult>> {
+        let Some(kind) = self.kinds.get(id) else {
+            return Err(format!("set('{id}'): not a number, fouls, timer, period, or label component").into());
+        };
+        let (stored, set) = match kind {
+            ScriptKind::Number => {
+                let value = value
+                    .as_int()
+                    .ok()
+                    .filter(|value| i32::try_from(*value).is_ok())
+                    .ok_or_else(|| format!("set('{id}'): expected a whole number"))?;
+                let set = ScriptSet::Value { id: id.to_string(), text: value.to_string() };
+                (Dynamic::from_int(value), set)
+            }
+            ScriptKind::Timer => {
+                let remaining_ms = value
+                    .as_int()
+                    .ok()
+                    .filter(|remaining_ms| *remaining_ms >= 0)
+                    .ok_or_else(|| format!("set('{id}'): expected milliseconds, zero or more"))?;
+                if remaining_ms == 0 {
+                    self.running.remove(id);
+                }
+                let set = ScriptSet::Timer { id: id.to_string(), remaining_ms };
+                (Dynamic::from_int(remaining_ms), set)
+            }
+            ScriptKind::Period(labels) => {
+                // A label, or a 1-based position as a console would send it.
+                let index = match value.as_int() {
+                    Ok(position) => usize::try_from(position)
+                        .ok()
+                        .filter(|position| (1..=labels.len()).contains(position))
+                        .map(|position| position - 1),
+                    Err(_) => {
+                        let label = value.to_string();
+                        labels.iter().position(|candidate| *candidate == label)
+                    }
+                };
+                let index = index.ok_or_else(|| format!("set('{id}'): '{value}' is not one of its periods"))?;
+                let label = labels[index].clone();
+                let set = ScriptSet::Value { id: id.to_string(), text: label.clone() };
+                (Dynamic::from(label), set)
+            }
+            ScriptKind::Label => {
+                let text = value.to_string();
+                let set = ScriptSet::Label { id: id.to_string(), text: text.clone() };
+                (Dynamic::from(text), set)
+            }
+        };
+        self.push(ScriptChange::Set(set))?;
+        self.values.insert(id.to_string(), stored);
+        Ok(())
+    }
+
+    fn push(&mut self, change: ScriptChange) -> Result<(), Box<EvalAltResult>> {
+        if self.changes.len() >= MAX_CHANGES {
+            return Err(format!("more than {MAX_CHANGES} set() and fire() calls in one hook").into());
+        }
+        self.changes.push(change);
+        Ok(())
+    }
+}
+
+/// The active config's rules script, compiled once when the config loads.
+pub struct ScriptHost {
+    file: String,
+    engine: Engine,
+    ast: AST,
+    board: Arc<Mutex<ScriptBoard>>,
+    /// Operations the running hook may still use; see `build_engine`.
+    budget: Arc<AtomicU64>,
+    tick_interval: Duration,
+    last_tick: Option<Instant>,
+}
+
+impl ScriptHost {
+    pub fn load(settings: &ScriptSettings) -> Result<Self, String> {
+        let file = settings.file.clone();
+        let source = fs::read_to_string(&file).map_err(|e| format!("Failed reading script {file}: {e}"))?;
+        Self::compile(file, &source, Duration::from_millis(settings.tick_ms))
+    }
+
+    fn compile(file: String, source: &str, tick_interval: Duration) -> Result<Self, String> {
+        let board = Arc::new(Mutex::new(ScriptBoard::default()));
+        let budget = Arc::new(AtomicU64::new(MAX_OPERATIONS));
+        let engine = build_engine(&board, &budget);
+        let ast = engine.compile(source).map_err(|e| format!("Script {file} has an error: {e}"))?;
+        Ok(Self {
+            file,
+            engine,
+            ast,
+            board,
+            budget,
+            tick_interval,
+            last_tick: None,
+        })
+    }
+
+    /// Whether `on_tick` is due, starting the next interval when it is.
+    pub fn tick_due(&mut self, now: Instant) -> bool {
+        if self.last_tick.is_some_and(|last| now.duration_since(last) < self.tick_interval) {
+            return false;
+        }
+        self.last_tick = Some(now);
+        true
+    }
+
+    /// Copies what a hook reads from the board, or `None` when the script
+    /// does not define the hook's function. This is the only step that needs
+    /// the runtime, so the lock can be let go before the script runs.
+    pub fn prepare(&self, runtime: &RuntimeState, hook: &ScriptHook) -> Option<ScriptBoard> {
+        let name = hook.function_name();
+        let arity = usize::from(hook.argument().is_some());
+        self.ast
+            .iter_functions()
+            .any(|f| f.name == name && f.params.len() == arity)
+            .then(|| ScriptBoard::read(runtime))
+    }
+
+    /// Runs `hook` against a board from `prepare` and returns the changes it
+    /// asked for, without applying them.
+    pub fn run(&self, board: ScriptBoard, hook: &ScriptHook) -> Result<Vec<ScriptChange>, String> {
+        let name = hook.function_name();
+        let budget = match hook {
+            ScriptHook::Tick => MAX_TICK_OPERATIONS,
+            ScriptHook::Action(_) | ScriptHook::TimerExpire(_) => MAX_OPERATIONS,
+        };
+        self.budget.store(budget, Ordering::Relaxed);
+        *self.board.lock().map_err(|_| "Script board lock poisoned".to_string())? = board;
+        let options = CallFnOptions::new().eval_ast(false);
+        let mut scope = Scope::new();
+        let result = match hook.argument() {
+            Some(argument) => {
+                let args = (argument.to_string(),);
+                self.engine.call_fn_with_options::<Dynamic>(options, &mut scope, &self.ast, name, args)
+            }
+            None => self.engine.call_fn_with_options::<Dynamic>(options, &mut scope, &self.ast, name, ()),
+        };
+        let changes = std::mem::take(&mut self.board.lock().map_err(|_| "Script board lock poisoned".to_string())?.changes);
+        match result {
+            Ok(_) => Ok(changes),
+            Err(e) if matches!(*e, EvalAltResult::ErrorTerminated(..)) => Err(format!(
+                "Script {} was stopped in {name} after {budget} operations",
+                self.file
+            )),
+            Err(e) => Err(format!("Script {} failed in {name}: {e}", self.file)),
+        }
+    }
+}
+
+/// An engine with only the board API and no file or network access (`import`
+/// finds no modules), limited so a runaway script is stopped instead of
+/// stalling the board. A hook is stopped once it uses more operations than
+/// `budget` holds when it starts.
+fn build_engine(board: &Arc<Mutex<ScriptBoard>>, budget: &Arc<AtomicU64>) -> Engine {
+    let mut engine = Engine::new();
+    let progress_budget = Arc::clone(budget);
+    engine
+        .set_module_resolver(DummyModuleResolver::new())
+        .on_progress(move |operations| {
+            (operations > progress_budget.load(Ordering::Relaxed)).then_some(Dynamic::UNIT)
+        })
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_string_size(MAX_STRING_SIZE)
+        .set_max_array_size(MAX_COLLECTION_SIZE)
+        .set_max_map_size(MAX_COLLECTION_SIZE)
+        .disable_symbol("eval");
+
+    let reader = Arc::clone(board);
+    engine.register_fn("get", move |id: &str| -> Result<Dynamic, Box<EvalAltResult>> {
+        reader.lock().map_err(|_| "Script board lock poisoned")?.get(id)
+    });
+    let writer = Arc::clone(board);
+    engine.register_fn("set", move |id: &str, value: Dynamic| -> Result<(), Box<EvalAltResult>> {
+        writer.lock().map_err(|_| "Script board lock poisoned")?.set(id, value)
+    });
+    let timers = Arc::clone(board);
+    engine.register_fn("running", move |id: &str| -> Result<bool, Box<EvalAltResult>> {
+        let board = timers.lock().map_err(|_| "Script board lock poisoned")?;
+        match board.kinds.get(id) {
+            Some(ScriptKind::Timer) => Ok(board.running.contains(id)),
+            _ => Err(format!("running('{id}'): not a timer").into()),
+        }
+    });
+    let actions = Arc::clone(board);
+    engine.register_fn("fire", move |action: &str| -> Result<(), Box<EvalAltResult>> {
+        let mut board = actions.lock().map_err(|_| "Script board lock poisoned")?;
+        board.push(ScriptChange::Fire(action.to_string()))
+    });
+    engine
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(source: &str) -> ScriptHost {
+        ScriptHost::compile("rules.rhai".to_string(), source, Duration::from_millis(500)).unwrap()
+    }
+
+    fn run(host: &ScriptHost, hook: ScriptHook) -> Result<Vec<ScriptChange>, String> {
+        host.run(ScriptBoard::default(), &hook)
+    }
+
+    #[test]
+    fn cannot_import_files() {
+        let module = std::env::temp_dir().join(format!("aolot-import-{}.rhai", std::process::id()));
+        fs::write(&module, "fn secret() { 42 }").unwrap();
+        let path = module.to_string_lossy().replace('\\', "/");
+        let host = host(&format!("fn on_tick() {{ import \"{path}\" as m; fire(\"x\"); }}"));
+        let result = run(&host, ScriptHook::Tick);
+        let _ = fs::remove_file(&module);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn stops_a_hook_that_queues_too_many_changes() {
+        let host = host("fn on_action(action) { for i in 0..2000 { fire(action); } }");
+        let error = run(&host, ScriptHook::Action("home.increase".to_string())).unwrap_err();
+        assert!(error.contains("more than 1000"), "{error}");
+
+        let host = self::host("fn on_action(action) { for i in 0..10 { fire(action); } }");
+        assert_eq!(run(&host, ScriptHook::Action("home.increase".to_string())).unwrap().len(), 10);
+    }
+
+    #[test]
+    fn gives_tick_hooks_a_smaller_budget() {
+        let source = "fn on_tick() { let n = 0; while n < 100000 { n += 1; } }
+            fn on_action(action) { let n = 0; while n < 100000 { n += 1; } }";
+        let host = host(source);
+        let error = run(&host, ScriptHook::Tick).unwrap_err();
+        assert!(error.contains("after 20000 operations"), "{error}");
+        assert!(run(&host, ScriptHook::Action("home.increase".to_string())).is_ok());
+    }
+}
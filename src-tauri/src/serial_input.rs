@@ -0,0 +1,275 @@
+use crate::config::{SerialField, SerialFieldSource, SerialInputSettings, SerialProtocol};
+use crate::led_matrix::ErrorSink;
+use std::collections::HashMap;
+use std::io::{ErrorKind, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const READ_TIMEOUT: Duration = Duration::from_millis(100);
+const REOPEN_DELAY: Duration = Duration::from_secs(2);
+/// Unframed bytes kept while waiting for the end of a packet or line.
+const MAX_PENDING_BYTES: usize = 1024;
+/// Highest `daktronics` data position kept, well past any sport's layout.
+const MAX_FRAME_BYTES: usize = 4096;
+
+const SYN: u8 = 0x16;
+const SOH: u8 = 0x01;
+const STX: u8 = 0x02;
+const EOT: u8 = 0x04;
+
+/// Receives `(component id, text)` pairs for fields whose value changed.
+pub type ConsoleSink = Arc<dyn Fn(Vec<(String, String)>) + Send + Sync>;
+
+/// Turns the bytes of one console protocol into field values.
+trait Decoder: Send {
+    /// Consumes bytes read from the port; returns true if any value may have changed.
+    fn feed(&mut self, bytes: &[u8]) -> bool;
+    /// The field's current text, once the console has sent it.
+    fn read(&self, source: &SerialFieldSource) -> Option<String>;
+}
+
+fn decoder_for(protocol: SerialProtocol) -> Box<dyn Decoder> {
+    match protocol {
+        SerialProtocol::Daktronics => Box::new(DaktronicsDecoder::default()),
+        SerialProtocol::Lines => Box::new(LinesDecoder::default()),
+    }
+}
+
+/// Reads a hardware scoreboard console on a background thread, reopening the
+/// port after it disappears (e.g. a USB adapter unplugged mid-game).
+pub struct SerialInput {
+    running: Option<Running>,
+}
+
+struct Running {
+    settings: SerialInputSettings,
+    stop: Arc<AtomicBool>,
+    /// Set on a reload, which may have reset the board, or once a frozen board
+    /// goes live again, so every field is applied again.
+    resend: Arc<AtomicBool>,
+}
+
+impl SerialInput {
+    pub fn new() -> Self {
+        Self { running: None }
+    }
+
+    /// Starts, stops, or restarts reading to match `settings`. Unchanged
+    /// settings keep the port open across a reload.
+    pub fn configure(&mut self, settings: Option<SerialInputSettings>, sink: ConsoleSink, on_error: ErrorSink) {
+        if let Some(running) = &self.running {
+            if Some(&running.settings) == settings.as_ref() {
+                running.resend.store(true, Ordering::Relaxed);
+                return;
+            }
+        }
+        if let Some(running) = self.running.take() {
+            running.stop.store(true, Ordering::Relaxed);
+        }
+        let Some(settings) = settings else {
+            return;
+        };
+        let running = Running {
+            settings: settings.clone(),
+            stop: Arc::new(AtomicBool::new(false)),
+            resend: Arc::new(AtomicBool::new(false)),
+        };
+        let stop = Arc::clone(&running.stop);
+        let resend = Arc::clone(&running.resend);
+        thread::spawn(move || run_reader(settings, stop, resend, sink, on_error));
+        self.running = Some(running);
+    }
+
+    /// Applies every field again with the next data read, for a board that
+    /// skipped some values.
+    pub fn resend(&self) {
+        if let Some(running) = &self.running {
+            running.resend.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+fn run_reader(
+    settings: SerialInputSettings,
+    stop: Arc<AtomicBool>,
+    resend: Arc<AtomicBool>,
+    sink: ConsoleSink,
+    on_error: ErrorSink,
+) {
+    let mut decoder = decoder_for(settings.protocol);
+    let mut sent: HashMap<String, String> = HashMap::new();
+    let mut buffer = [0u8; 256];
+    // Reported once until the port works again, since it is retried every few seconds.
+    let mut error_reported = false;
+
+    while !stop.load(Ordering::Relaxed) {
+        let mut port = match serialport::new(&settings.port, settings.baud_rate)
+            .timeout(READ_TIMEOUT)
+            .open()
+        {
+            Ok(port) => port,
+            Err(e) => {
+                if !error_reported {
+                    error_reported = true;
+                    on_error(format!("Failed opening console port {}: {e}", settings.port));
+                }
+                thread::sleep(REOPEN_DELAY);
+                continue;
+            }
+        };
+        error_reported = false;
+
+        while !stop.load(Ordering::Relaxed) {
+            let length = match port.read(&mut buffer) {
+                Ok(length) => length,
+                Err(e) if e.kind() == ErrorKind::TimedOut => continue,
+                Err(e) => {
+                    error_reported = true;
+                    on_error(format!("Lost console port {}: {e}", settings.port));
+                    thread::sleep(REOPEN_DELAY);
+                    break;
+                }
+            };
+            let resend_all = resend.swap(false, Ordering::Relaxed);
+            if resend_all {
+                sent.clear();
+            }
+            if !decoder.feed(&buffer[..length]) && !resend_all {
+                continue;
+            }
+            let changes = changed_fields(decoder.as_ref(), &settings.fields, &mut sent);
+            if !changes.is_empty() {
+                sink(changes);
+            }
+        }
+    }
+}
+
+fn changed_fields(
+    decoder: &dyn Decoder,
+    fields: &[SerialField],
+    sent: &mut HashMap<String, String>,
+) -> Vec<(String, String)> {
+    let mut changes = Vec::new();
+    for field in fields {
+        let Some(text) = decoder.read(&field.source) else {
+            continue;
+        };
+        if sent.get(&field.component) != Some(&text) {
+            sent.insert(field.component.clone(), text.clone());
+            changes.push((field.component.clone(), text));
+        }
+    }
+    changes
+}
+
+/// Daktronics All Sport real-time data. Each packet is
+/// `SYN SOH <header> STX <text> EOT <checksum> ETB`; the last four digits of
+/// the header give the position the text belongs at in the sport's layout,
+/// so the decoder keeps a frame of everything sent so far.
+#[derive(Default)]
+struct DaktronicsDecoder {
+    pending: Vec<u8>,
+    /// Layout text by position; `None` until the console has sent it.
+    frame: Vec<Option<u8>>,
+}
+
+impl Decoder for DaktronicsDecoder {
+    fn feed(&mut self, bytes: &[u8]) -> bool {
+        self.pending.extend_from_slice(bytes);
+        let mut updated = false;
+        loop {
+            let Some(start) = self.pending.windows(2).position(|pair| pair == [SYN, SOH]) else {
+                // Keep a trailing SYN, which may start the next packet.
+                let keep = usize::from(self.pending.last() == Some(&SYN));
+                self.pending.drain(..self.pending.len() - keep);
+                break;
+            };
+            self.pending.drain(..start);
+            let Some(end) = self.pending.iter().position(|byte| *byte == EOT) else {
+                if self.pending.len() > MAX_PENDING_BYTES {
+                    self.pending.clear();
+                }
+                break;
+            };
+            let packet: Vec<u8> = self.pending.drain(..=end).collect();
+            updated |= self.apply(&packet[2..packet.len() - 1]);
+        }
+        updated
+    }
+
+    fn read(&self, source: &SerialFieldSource) -> Option<String> {
+        let SerialFieldSource::Position { offset, length } = source else {
+            return None;
+        };
+        let bytes: Option<Vec<u8>> = self.frame.get(*offset..offset + length)?.iter().copied().collect();
+        Some(String::from_utf8_lossy(&bytes?).to_string())
+    }
+}
+
+impl DaktronicsDecoder {
+    /// Writes one packet's text into the frame. `body` runs from the header to
+    /// just before EOT.
+    fn apply(&mut self, body: &[u8]) -> bool {
+        let Some(text_start) = body.iter().position(|byte| *byte == STX) else {
+            return false;
+        };
+        let header = &body[..text_start];
+        let Some(position) = header
+            .get(header.len().saturating_sub(4)..)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| digits.parse::<usize>().ok())
+        else {
+            return false;
+        };
+        let text = &body[text_start + 1..];
+        let end = position + text.len();
+        if end > MAX_FRAME_BYTES {
+            return false;
+        }
+        if self.frame.len() < end {
+            self.frame.resize(end, None);
+        }
+        for (slot, byte) in self.frame[position..end].iter_mut().zip(text) {
+            *slot = Some(*byte);
+        }
+        true
+    }
+}
+
+/// `name=value` lines, ending in `\n` or `\r`.
+#[derive(Default)]
+struct LinesDecoder {
+    pending: Vec<u8>,
+    values: HashMap<String, String>,
+}
+
+impl Decoder for LinesDecoder {
+    fn feed(&mut self, bytes: &[u8]) -> bool {
+        let mut updated = false;
+        for &byte in bytes {
+            if byte != b'\n' && byte != b'\r' {
+                if self.pending.len() < MAX_PENDING_BYTES {
+                    self.pending.push(byte);
+                }
+                continue;
+            }
+            let line = String::from_utf8_lossy(&self.pending).to_string();
+            self.pending.clear();
+            if let Some((name, value)) = line.split_once('=') {
+                self.values.insert(name.trim().to_string(), value.trim().to_string());
+                updated = true;
+            }
+        }
+        updated
+    }
+
+    fn read(&self, source: &SerialFieldSource) -> Option<String> {
+        let SerialFieldSource::Name(name) = source else {
+            return None;
+        };
+        self.values.get(name).cloned()
+    }
+}
@@ -0,0 +1,71 @@
+use crate::state::RuntimeValues;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SESSION_FILE_NAME: &str = "session.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    pub config_path: Option<String>,
+    pub saved_at_unix_ms: u64,
+    pub values: RuntimeValues,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionInfo {
+    pub config_path: Option<String>,
+    pub saved_at_unix_ms: u64,
+}
+
+impl SessionSnapshot {
+    pub fn new(config_path: Option<String>, values: RuntimeValues) -> Self {
+        let saved_at_unix_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        Self {
+            config_path,
+            saved_at_unix_ms,
+            values,
+        }
+    }
+
+    pub fn info(&self) -> SessionInfo {
+        SessionInfo {
+            config_path: self.config_path.clone(),
+            saved_at_unix_ms: self.saved_at_unix_ms,
+        }
+    }
+}
+
+pub fn session_file_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(SESSION_FILE_NAME)
+}
+
+pub fn read_session(path: &Path) -> Result<Option<SessionSnapshot>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed reading session {}: {e}", path.display()))?;
+    let session = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed parsing session {}: {e}", path.display()))?;
+    Ok(Some(session))
+}
+
+/// Writes through a temporary file so a crash mid-write never leaves a
+/// truncated session behind.
+pub fn write_session(path: &Path, session: &SessionSnapshot) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed creating session directory {}: {e}", parent.display()))?;
+    }
+    let content =
+        serde_json::to_string(session).map_err(|e| format!("Failed serializing session: {e}"))?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, content)
+        .map_err(|e| format!("Failed writing session {}: {e}", temp_path.display()))?;
+    fs::rename(&temp_path, path).map_err(|e| format!("Failed replacing session {}: {e}", path.display()))
+}
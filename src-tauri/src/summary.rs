@@ -0,0 +1,513 @@
+use crate::config::SummarySettings;
+use crate::player_select::PlayerActionEvent;
+use crate::scoresheet::SheetLine;
+use crate::state::{LoggedEvent, PeriodScore, UiSnapshot};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Oldest entries are dropped beyond this, so a board left running for days
+/// does not grow without bound.
+const MAX_SUMMARY_EVENTS: usize = 1_000;
+
+pub const DEFAULT_MARKDOWN_TEMPLATE: &str =
+    "## {home_name} {home}, {away_name} {away}\n\n{line_score}\n\n### Game log\n\n{events}\n";
+pub const DEFAULT_TEXT_TEMPLATE: &str = "{home_name} {home}, {away_name} {away}\n\n{line_score}\n\nGame log:\n{events}\n";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SummaryFormat {
+    Markdown,
+    Text,
+}
+
+impl SummaryFormat {
+    pub fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.map(str::trim) {
+            None | Some("markdown") => Ok(Self::Markdown),
+            Some("text") => Ok(Self::Text),
+            Some(other) => Err(format!("Unknown summary format '{other}', expected markdown or text")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Team {
+    Home,
+    Away,
+}
+
+struct GameEvent {
+    period: Option<String>,
+    clock: Option<String>,
+    text: String,
+}
+
+struct PlayerEntry {
+    team: Option<Team>,
+    player: String,
+    action: String,
+}
+
+/// Notable moments, player actions, and team fouls and timeouts of the
+/// current game, built from emitted snapshots like the number history, for the
+/// `generate_summary` report and the `generate_scoresheet` PDF. Points per
+/// period and the scoring progression come from the runtime's
+/// `score_by_period` and event log instead.
+pub struct GameLog {
+    settings: Option<SummarySettings>,
+    player_actions: Vec<String>,
+    home: i64,
+    away: i64,
+    leader: Option<Ordering>,
+    clock: Option<String>,
+    fouls: (Option<i64>, Option<i64>),
+    timeouts: (Option<i64>, Option<i64>),
+    /// Period labels in the order they first appeared.
+    periods: Vec<String>,
+    players: VecDeque<PlayerEntry>,
+    events: VecDeque<GameEvent>,
+}
+
+impl GameLog {
+    pub fn new() -> Self {
+        Self {
+            settings: None,
+            player_actions: Vec::new(),
+            home: 0,
+            away: 0,
+            leader: None,
+            clock: None,
+            fouls: (None, None),
+            timeouts: (None, None),
+            periods: Vec::new(),
+            players: VecDeque::new(),
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Starts a new game log, or with `keep` (hot reload) carries on the
+    /// current one under the new settings. `player_actions` are the configured
+    /// action names, in the order the scoresheet lists them.
+    pub fn configure(&mut self, settings: Option<SummarySettings>, player_actions: Vec<String>, keep: bool) {
+        let previous = std::mem::replace(self, Self::new());
+        if keep {
+            *self = previous;
+        }
+        self.settings = settings;
+        self.player_actions = player_actions;
+    }
+
+    pub fn observe(&mut self, snapshot: &UiSnapshot) {
+        let Some(settings) = &self.settings else {
+            return;
+        };
+        let text_of = |id: Option<&String>| {
+            let id = id?;
+            let component = snapshot.components.iter().find(|component| &component.id == id)?;
+            component.text.clone().filter(|text| !text.trim().is_empty())
+        };
+        let count_of = |id: Option<&String>| text_of(id)?.trim().parse::<i64>().ok();
+        let (Some(home), Some(away)) = (count_of(Some(&settings.home)), count_of(Some(&settings.away))) else {
+            return;
+        };
+        let period = text_of(settings.period.as_ref());
+        let clock = text_of(settings.clock.as_ref());
+        let fouls = (
+            count_of(settings.home_fouls.as_ref()),
+            count_of(settings.away_fouls.as_ref()),
+        );
+        let timeouts = (
+            count_of(settings.home_timeouts.as_ref()),
+            count_of(settings.away_timeouts.as_ref()),
+        );
+        let home_name = settings.home_name.clone();
+        let away_name = settings.away_name.clone();
+        let first = self.leader.is_none();
+
+        let mut events = Vec::new();
+        let mut period_changed = false;
+        if let Some(label) = &period {
+            // Going back to an earlier label undoes a mistaken advance, so
+            // moving on again starts that next period anew.
+            if let Some(index) = self.periods.iter().position(|seen| seen == label) {
+                period_changed = index + 1 != self.periods.len();
+                self.periods.truncate(index + 1);
+            } else {
+                period_changed = true;
+                self.periods.push(label.clone());
+                events.push(format!("Start of period {label}"));
+            }
+        }
+
+        let leader = home.cmp(&away);
+        if !first && self.leader != Some(leader) {
+            events.push(match leader {
+                Ordering::Greater => format!("{home_name} takes the lead, {home}-{away}"),
+                Ordering::Less => format!("{away_name} takes the lead, {away}-{home}"),
+                Ordering::Equal => format!("Tied at {home}-{away}"),
+            });
+        }
+        // Period budgets refill timeouts as the period changes; only drops
+        // within a period are timeouts being called.
+        if !first && !period_changed {
+            for (name, previous, now) in [
+                (&home_name, self.timeouts.0, timeouts.0),
+                (&away_name, self.timeouts.1, timeouts.1),
+            ] {
+                if let (Some(previous), Some(now)) = (previous, now) {
+                    if now < previous {
+                        events.push(format!("{name} timeout, {now} left"));
+                    }
+                }
+            }
+        }
+        self.leader = Some(leader);
+        self.home = home;
+        self.away = away;
+        self.clock = clock;
+        self.fouls = fouls;
+        self.timeouts = timeouts;
+
+        for text in events {
+            self.push_event(text);
+        }
+    }
+
+    /// Credits a player action to a team: the team whose score, fouls, or
+    /// timeouts the action changes, otherwise the only roster listing the jersey.
+    pub fn record_player_action(&mut self, event: &PlayerActionEvent, applies_to: Option<&str>) {
+        let Some(settings) = &self.settings else {
+            return;
+        };
+        let home_ids = [Some(&settings.home), settings.home_fouls.as_ref(), settings.home_timeouts.as_ref()];
+        let away_ids = [Some(&settings.away), settings.away_fouls.as_ref(), settings.away_timeouts.as_ref()];
+        let owned_by = |ids: [Option<&String>; 3]| applies_to.is_some_and(|id| ids.into_iter().flatten().any(|owned| owned == id));
+        let team = if owned_by(home_ids) {
+            Some(Team::Home)
+        } else if owned_by(away_ids) {
+            Some(Team::Away)
+        } else {
+            match (
+                settings.home_roster.contains_key(&event.player),
+                settings.away_roster.contains_key(&event.player),
+            ) {
+                (true, false) => Some(Team::Home),
+                (false, true) => Some(Team::Away),
+                _ => None,
+            }
+        };
+        let player = match team {
+            Some(team) => {
+                let (name, roster) = match team {
+                    Team::Home => (&settings.home_name, &settings.home_roster),
+                    Team::Away => (&settings.away_name, &settings.away_roster),
+                };
+                match roster.get(&event.player) {
+                    Some(player) => format!("{name} #{} {player}", event.player),
+                    None => format!("{name} #{}", event.player),
+                }
+            }
+            None => format!("#{}", event.player),
+        };
+        if self.players.len() == MAX_SUMMARY_EVENTS {
+            self.players.pop_front();
+        }
+        self.players.push_back(PlayerEntry {
+            team,
+            player: event.player.clone(),
+            action: event.action.clone(),
+        });
+        self.push_event(format!("{player}: {}", event.action));
+    }
+
+    fn push_event(&mut self, text: String) {
+        if self.events.len() == MAX_SUMMARY_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(GameEvent {
+            period: self.periods.last().cloned(),
+            clock: self.clock.clone(),
+            text,
+        });
+    }
+
+    fn settings(&self) -> Result<&SummarySettings, String> {
+        self.settings
+            .as_ref()
+            .ok_or_else(|| "No game summary configured; add [global.summary] to the config".to_string())
+    }
+
+    /// `periods` is the runtime's `score_by_period`, which the line score reads.
+    pub fn render(&self, format: SummaryFormat, periods: &[PeriodScore]) -> Result<String, String> {
+        let settings = self.settings()?;
+        let template = match format {
+            SummaryFormat::Markdown => settings.markdown.as_deref().unwrap_or(DEFAULT_MARKDOWN_TEMPLATE),
+            SummaryFormat::Text => settings.text.as_deref().unwrap_or(DEFAULT_TEXT_TEMPLATE),
+        };
+        let period = self.periods.last().map(String::as_str).unwrap_or_default();
+        let line_score = self.line_score_rows(settings, periods);
+        let line_score = match format {
+            SummaryFormat::Markdown => markdown_table(&line_score),
+            SummaryFormat::Text => text_table(&line_score, 1).join("\n"),
+        };
+        Ok(template
+            .replace("{home_name}", &settings.home_name)
+            .replace("{away_name}", &settings.away_name)
+            .replace("{home}", &self.home.to_string())
+            .replace("{away}", &self.away.to_string())
+            .replace("{period}", period)
+            .replace("{line_score}", &line_score)
+            .replace("{events}", &self.event_lines(format)))
+    }
+
+    /// The scoresheet as monospaced lines: header, line score, team fouls and
+    /// timeouts, a table per roster, the scoring progression, and the game log.
+    /// The line score reads `periods` (the runtime's `score_by_period`) and the
+    /// scoring progression reads `log` (its event log).
+    pub fn scoresheet_lines(&self, periods: &[PeriodScore], log: &[LoggedEvent]) -> Result<Vec<SheetLine>, String> {
+        let settings = self.settings()?;
+        let mut lines = vec![
+            SheetLine::heading("OFFICIAL SCORESHEET"),
+            SheetLine::text(format!("{} vs {}", settings.home_name, settings.away_name)),
+            SheetLine::text(format!("Generated {}", utc_timestamp())),
+            SheetLine::blank(),
+            SheetLine::heading(format!(
+                "FINAL  {} {}  {} {}",
+                settings.home_name, self.home, settings.away_name, self.away
+            )),
+            SheetLine::blank(),
+            SheetLine::heading("LINE SCORE"),
+        ];
+        lines.extend(text_table(&self.line_score_rows(settings, periods), 1).into_iter().map(SheetLine::text));
+
+        let show = |value: Option<i64>| value.map(|value| value.to_string()).unwrap_or_else(|| "-".to_string());
+        lines.push(SheetLine::blank());
+        lines.push(SheetLine::heading("TEAM FOULS AND TIMEOUTS"));
+        let team_rows = vec![
+            vec![String::new(), "Fouls".to_string(), "Timeouts left".to_string()],
+            vec![settings.home_name.clone(), show(self.fouls.0), show(self.timeouts.0)],
+            vec![settings.away_name.clone(), show(self.fouls.1), show(self.timeouts.1)],
+        ];
+        lines.extend(text_table(&team_rows, 1).into_iter().map(SheetLine::text));
+
+        for (team, name, roster) in [
+            (Some(Team::Home), &settings.home_name, &settings.home_roster),
+            (Some(Team::Away), &settings.away_name, &settings.away_roster),
+            (None, &"Unassigned".to_string(), &BTreeMap::new()),
+        ] {
+            let mut jerseys: Vec<&String> = roster.keys().collect();
+            for entry in self.players.iter().filter(|entry| entry.team == team) {
+                if !jerseys.contains(&&entry.player) {
+                    jerseys.push(&entry.player);
+                }
+            }
+            if jerseys.is_empty() {
+                continue;
+            }
+            jerseys.sort();
+            let mut header = vec!["No.".to_string(), "Name".to_string()];
+            header.extend(self.player_actions.iter().cloned());
+            let mut rows = vec![header];
+            for jersey in jerseys {
+                let mut row = vec![jersey.clone(), roster.get(jersey).cloned().unwrap_or_default()];
+                for action in &self.player_actions {
+                    let count = self
+                        .players
+                        .iter()
+                        .filter(|entry| entry.team == team && &entry.player == jersey && &entry.action == action)
+                        .count();
+                    row.push(count.to_string());
+                }
+                rows.push(row);
+            }
+            lines.push(SheetLine::blank());
+            lines.push(SheetLine::heading(name.to_uppercase()));
+            lines.extend(text_table(&rows, 2).into_iter().map(SheetLine::text));
+        }
+
+        lines.push(SheetLine::blank());
+        lines.push(SheetLine::heading("SCORING PROGRESSION"));
+        let scoring = scoring_rows(settings, log);
+        if scoring.is_empty() {
+            lines.push(SheetLine::text("No scoring recorded."));
+        } else {
+            let mut rows = vec![vec![
+                "Period".to_string(),
+                "Clock".to_string(),
+                "Team".to_string(),
+                "Score".to_string(),
+            ]];
+            rows.extend(scoring);
+            lines.extend(text_table(&rows, 3).into_iter().map(SheetLine::text));
+        }
+
+        lines.push(SheetLine::blank());
+        lines.push(SheetLine::heading("GAME LOG"));
+        lines.extend(self.event_lines(SummaryFormat::Text).lines().map(SheetLine::text));
+        Ok(lines)
+    }
+
+    /// A column per period seen on the board, scoreless ones included, then
+    /// any other period `periods` has points for.
+    fn line_score_rows(&self, settings: &SummarySettings, periods: &[PeriodScore]) -> Vec<Vec<String>> {
+        let mut labels: Vec<&String> = self.periods.iter().collect();
+        for score in periods {
+            if !labels.contains(&&score.period) {
+                labels.push(&score.period);
+            }
+        }
+        let points = |label: &String, id: &String| {
+            periods
+                .iter()
+                .find(|score| &score.period == label)
+                .and_then(|score| score.points.get(id))
+                .copied()
+                .unwrap_or(0)
+        };
+        let mut header = vec![String::new()];
+        let mut home_row = vec![settings.home_name.clone()];
+        let mut away_row = vec![settings.away_name.clone()];
+        for label in labels {
+            header.push(label.clone());
+            home_row.push(points(label, &settings.home).to_string());
+            away_row.push(points(label, &settings.away).to_string());
+        }
+        header.push("T".to_string());
+        home_row.push(self.home.to_string());
+        away_row.push(self.away.to_string());
+        vec![header, home_row, away_row]
+    }
+
+    fn event_lines(&self, format: SummaryFormat) -> String {
+        if self.events.is_empty() {
+            return match format {
+                SummaryFormat::Markdown => "_No notable moments._".to_string(),
+                SummaryFormat::Text => "No notable moments.".to_string(),
+            };
+        }
+        self.events
+            .iter()
+            .map(|event| {
+                let when: Vec<&str> = [event.period.as_deref(), event.clock.as_deref()]
+                    .into_iter()
+                    .flatten()
+                    .collect();
+                if when.is_empty() {
+                    format!("- {}", event.text)
+                } else if format == SummaryFormat::Markdown {
+                    format!("- **{}** {}", when.join(" "), event.text)
+                } else {
+                    format!("- [{}] {}", when.join(" "), event.text)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Period, clock, team, and new score for each logged change to either
+/// team's score.
+fn scoring_rows(settings: &SummarySettings, log: &[LoggedEvent]) -> Vec<Vec<String>> {
+    let mut last: (Option<&str>, Option<&str>) = (None, None);
+    let mut rows = Vec::new();
+    for event in log {
+        let (Some(component), Some(value)) = (&event.component, &event.value) else {
+            continue;
+        };
+        let (name, previous) = if component == &settings.home {
+            (&settings.home_name, &mut last.0)
+        } else if component == &settings.away {
+            (&settings.away_name, &mut last.1)
+        } else {
+            continue;
+        };
+        // An increase at the maximum still logs the action; only changes count.
+        if previous.replace(value.as_str()) == Some(value.as_str()) {
+            continue;
+        }
+        rows.push(vec![
+            event.period.clone().unwrap_or_default(),
+            event.clock.clone().unwrap_or_default(),
+            name.clone(),
+            value.clone(),
+        ]);
+    }
+    rows
+}
+
+/// A Markdown table whose first row is the header and whose first column is
+/// left-aligned; the other columns hold numbers and are right-aligned.
+fn markdown_table(rows: &[Vec<String>]) -> String {
+    let row = |cells: &[String]| {
+        let cells: Vec<String> = cells.iter().map(|cell| cell.replace('|', "\\|")).collect();
+        format!("| {} |", cells.join(" | "))
+    };
+    let columns = rows.first().map_or(0, Vec::len);
+    let mut divider = vec!["---".to_string()];
+    divider.extend(std::iter::repeat_n("---:".to_string(), columns.saturating_sub(1)));
+    let mut lines = Vec::new();
+    for (index, cells) in rows.iter().enumerate() {
+        lines.push(row(cells));
+        if index == 0 {
+            lines.push(format!("|{}|", divider.join("|")));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Pads the columns of `rows` to a common width for monospaced output. The
+/// first `left_columns` hold text and are left-aligned; the rest hold numbers
+/// and are right-aligned.
+fn text_table(rows: &[Vec<String>], left_columns: usize) -> Vec<String> {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|column| {
+            rows.iter()
+                .filter_map(|row| row.get(column))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    rows.iter()
+        .map(|cells| {
+            let padded: Vec<String> = cells
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(column, (cell, width))| {
+                    if column < left_columns {
+                        format!("{cell:<width$}")
+                    } else {
+                        format!("{cell:>width$}")
+                    }
+                })
+                .collect();
+            padded.join("  ").trim_end().to_string()
+        })
+        .collect()
+}
+
+/// Current time as `YYYY-MM-DD HH:MM UTC`.
+fn utc_timestamp() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default() as i64;
+    let (days, day_seconds) = (seconds.div_euclid(86_400), seconds.rem_euclid(86_400));
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02} UTC",
+        day_seconds / 3_600,
+        (day_seconds / 60) % 60
+    )
+}
@@ -0,0 +1,470 @@
+use crate::config::{tokens_match, SyncRole, SyncSettings};
+use crate::discovery::find_sync_primary;
+use crate::led_matrix::ErrorSink;
+use crate::state::UiSnapshot;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const ACCEPT_POLL: Duration = Duration::from_millis(50);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+/// How long a connection may take to send its whole hello.
+const HELLO_TIMEOUT: Duration = Duration::from_secs(2);
+/// A hello only carries the token.
+const MAX_HELLO_BYTES: usize = 4 * 1024;
+/// Connections a primary serves at once, including ones still sending their hello.
+const MAX_REPLICAS: usize = 16;
+/// Longest board line a replica accepts before dropping the connection.
+const MAX_MESSAGE_BYTES: usize = 8 * 1024 * 1024;
+/// The primary writes an empty line this often when the board is quiet.
+const HEARTBEAT: Duration = Duration::from_secs(2);
+/// A replica gives up on a primary it has not heard from for this long.
+const SILENCE_LIMIT: Duration = Duration::from_secs(6);
+const READ_POLL: Duration = Duration::from_millis(500);
+const RECONNECT_MIN: Duration = Duration::from_secs(1);
+const RECONNECT_MAX: Duration = Duration::from_secs(10);
+
+/// Receives each board mirrored from the primary, as the primary serialized it.
+pub type SnapshotSink = Arc<dyn Fn(Value) + Send + Sync>;
+
+/// Payload of `AppStatus.sync`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "role", rename_all = "lowercase")]
+pub enum SyncStatus {
+    Primary { replicas: usize },
+    Replica { primary: String, connected: bool },
+}
+
+struct Running {
+    settings: SyncSettings,
+    stop: Arc<AtomicBool>,
+}
+
+/// Keeps several boards showing the same thing: the primary streams every
+/// snapshot it draws as newline-delimited JSON over TCP, and replicas draw
+/// those in place of their own, reconnecting whenever the link drops.
+pub struct SyncService {
+    running: Mutex<Option<Running>>,
+    /// The last line published, sent to each replica as it connects. Held
+    /// while publishing so a new replica never sees an older board after a newer one.
+    latest: Arc<Mutex<Option<String>>>,
+    replicas: Arc<Mutex<Vec<Sender<String>>>>,
+    connected: Arc<AtomicBool>,
+    /// Set once a replica has drawn a board from the primary. It keeps that
+    /// board, rather than its own, on screen while reconnecting.
+    mirrored: Arc<AtomicBool>,
+    /// The loaded config's folder: sent by the primary, and where a replica
+    /// looks for the primary's images.
+    base_dir: Arc<Mutex<Option<PathBuf>>>,
+}
+
+impl SyncService {
+    pub fn new() -> Self {
+        Self {
+            running: Mutex::new(None),
+            latest: Arc::new(Mutex::new(None)),
+            replicas: Arc::new(Mutex::new(Vec::new())),
+            connected: Arc::new(AtomicBool::new(false)),
+            mirrored: Arc::new(AtomicBool::new(false)),
+            base_dir: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Starts, stops, or switches roles to match `settings`; unchanged
+    /// settings keep the running link, so a reload does not drop replicas.
+    pub fn configure(
+        &self,
+        settings: Option<SyncSettings>,
+        base_dir: Option<PathBuf>,
+        on_snapshot: SnapshotSink,
+        on_error: ErrorSink,
+    ) -> Result<(), String> {
+        if let Ok(mut current) = self.base_dir.lock() {
+            *current = base_dir;
+        }
+        let mut running = self.running.lock().map_err(|_| "Sync lock poisoned".to_string())?;
+        if running.as_ref().map(|running| &running.settings) == settings.as_ref() {
+            return Ok(());
+        }
+        if let Some(previous) = running.take() {
+            previous.stop.store(true, Ordering::Relaxed);
+        }
+        // Dropping the senders ends every replica connection.
+        if let Ok(mut replicas) = self.replicas.lock() {
+            replicas.clear();
+        }
+        if let Ok(mut latest) = self.latest.lock() {
+            *latest = None;
+        }
+        self.connected.store(false, Ordering::Relaxed);
+        self.mirrored.store(false, Ordering::Relaxed);
+        let Some(settings) = settings else {
+            return Ok(());
+        };
+
+        let stop = Arc::new(AtomicBool::new(false));
+        match &settings.role {
+            SyncRole::Primary { bind, port } => {
+                let address = format!("{bind}:{port}")
+                    .parse::<SocketAddr>()
+                    .map_err(|e| format!("Invalid sync address: {e}"))?;
+                let listener =
+                    TcpListener::bind(address).map_err(|e| format!("Failed starting sync on {address}: {e}"))?;
+                listener
+                    .set_nonblocking(true)
+                    .map_err(|e| format!("Failed starting sync on {address}: {e}"))?;
+                let primary = Primary {
+                    token: settings.token.clone(),
+                    latest: Arc::clone(&self.latest),
+                    replicas: Arc::clone(&self.replicas),
+                    stop: Arc::clone(&stop),
+                };
+                thread::spawn(move || primary.accept(listener));
+            }
+            SyncRole::Replica { primary } => {
+                let replica = Replica {
+                    primary: primary.clone(),
+                    token: settings.token.clone(),
+                    stop: Arc::clone(&stop),
+                    connected: Arc::clone(&self.connected),
+                    mirrored: Arc::clone(&self.mirrored),
+                    base_dir: Arc::clone(&self.base_dir),
+                    on_snapshot,
+                    on_error,
+                };
+                thread::spawn(move || replica.run());
+            }
+        }
+        *running = Some(Running { settings, stop });
+        Ok(())
+    }
+
+    /// Whether the window shows the primary's board instead of this one's.
+    pub fn is_mirroring(&self) -> bool {
+        self.mirrored.load(Ordering::Relaxed)
+    }
+
+    /// Sends a board drawn on the primary to every replica; does nothing in
+    /// other roles.
+    pub fn publish(&self, snapshot: &UiSnapshot) {
+        let is_primary = self.running.lock().is_ok_and(|running| {
+            running
+                .as_ref()
+                .is_some_and(|running| matches!(running.settings.role, SyncRole::Primary { .. }))
+        });
+        if !is_primary {
+            return;
+        }
+        let base_dir = self.base_dir.lock().ok().and_then(|base_dir| base_dir.clone());
+        let message = json!({
+            "base_dir": base_dir.map(|dir| dir.to_string_lossy().to_string()),
+            "snapshot": snapshot,
+        });
+        let Ok(line) = serde_json::to_string(&message) else {
+            return;
+        };
+        let Ok(mut latest) = self.latest.lock() else {
+            return;
+        };
+        if let Ok(mut replicas) = self.replicas.lock() {
+            replicas.retain(|replica| replica.send(line.clone()).is_ok());
+        }
+        *latest = Some(line);
+    }
+
+    pub fn status(&self) -> Option<SyncStatus> {
+        let running = self.running.lock().ok()?;
+        match &running.as_ref()?.settings.role {
+            SyncRole::Primary { .. } => Some(SyncStatus::Primary {
+                replicas: self.replicas.lock().map(|replicas| replicas.len()).unwrap_or(0),
+            }),
+            SyncRole::Replica { primary } => Some(SyncStatus::Replica {
+                primary: primary.clone(),
+                connected: self.connected.load(Ordering::Relaxed),
+            }),
+        }
+    }
+}
+
+struct Primary {
+    token: Option<String>,
+    latest: Arc<Mutex<Option<String>>>,
+    replicas: Arc<Mutex<Vec<Sender<String>>>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Primary {
+    fn accept(self, listener: TcpListener) {
+        let primary = Arc::new(self);
+        let connections = Arc::new(AtomicUsize::new(0));
+        while !primary.stop.load(Ordering::Relaxed) {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    // Past the cap, connections are closed unanswered.
+                    if connections.fetch_add(1, Ordering::SeqCst) >= MAX_REPLICAS {
+                        connections.fetch_sub(1, Ordering::SeqCst);
+                        continue;
+                    }
+                    let primary = Arc::clone(&primary);
+                    let connections = Arc::clone(&connections);
+                    thread::spawn(move || {
+                        let _ = primary.serve(stream);
+                        connections.fetch_sub(1, Ordering::SeqCst);
+                    });
+                }
+                Err(_) => thread::sleep(ACCEPT_POLL),
+            }
+        }
+    }
+
+    /// Checks a replica's hello line, then streams boards to it until it
+    /// disconnects or sync is reconfigured.
+    fn serve(&self, mut stream: TcpStream) -> io::Result<()> {
+        stream.set_nonblocking(false)?;
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+        let hello = read_hello(&stream)?;
+        let token = serde_json::from_slice::<Value>(&hello)
+            .ok()
+            .and_then(|hello| hello.get("token").and_then(Value::as_str).map(str::to_string));
+        if let Some(expected) = &self.token {
+            if !token.is_some_and(|token| tokens_match(expected, &token)) {
+                writeln!(stream, "{}", json!({ "error": "Wrong sync token" }))?;
+                return Ok(());
+            }
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let latest = {
+            let latest = self.latest.lock().map_err(|_| io::Error::other("Sync lock poisoned"))?;
+            if let Ok(mut replicas) = self.replicas.lock() {
+                replicas.push(sender);
+            }
+            latest.clone()
+        };
+        if let Some(line) = latest {
+            writeln!(stream, "{line}")?;
+        }
+        while !self.stop.load(Ordering::Relaxed) {
+            match receiver.recv_timeout(HEARTBEAT) {
+                Ok(line) => writeln!(stream, "{line}")?,
+                Err(RecvTimeoutError::Timeout) => writeln!(stream)?,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Replica {
+    primary: String,
+    token: Option<String>,
+    stop: Arc<AtomicBool>,
+    connected: Arc<AtomicBool>,
+    mirrored: Arc<AtomicBool>,
+    base_dir: Arc<Mutex<Option<PathBuf>>>,
+    on_snapshot: SnapshotSink,
+    on_error: ErrorSink,
+}
+
+impl Replica {
+    /// Connects, mirrors, and reconnects with backoff until reconfigured.
+    /// A failure that repeats on every retry is reported once.
+    fn run(self) {
+        let mut backoff = RECONNECT_MIN;
+        let mut reported: Option<String> = None;
+        while !self.stop.load(Ordering::Relaxed) {
+            let result = self.mirror(&mut || {
+                backoff = RECONNECT_MIN;
+                reported = None;
+            });
+            self.connected.store(false, Ordering::Relaxed);
+            if self.stop.load(Ordering::Relaxed) {
+                break;
+            }
+            let error = result
+                .err()
+                .unwrap_or_else(|| format!("Sync: primary {} closed the connection", self.primary));
+            if reported.as_ref() != Some(&error) {
+                (self.on_error)(error.clone());
+                reported = Some(error);
+            }
+            self.sleep(backoff);
+            backoff = (backoff * 2).min(RECONNECT_MAX);
+        }
+    }
+
+    /// One connection to the primary. `on_mirrored` runs for each board received.
+    fn mirror(&self, on_mirrored: &mut dyn FnMut()) -> Result<(), String> {
+        let unreachable = |e: String| format!("Sync: cannot reach primary {}: {e}", self.primary);
+        // A primary without a port is looked up by the name it announces.
+        let address = if self.primary.contains(':') {
+            self.primary
+                .to_socket_addrs()
+                .map_err(|e| unreachable(e.to_string()))?
+                .next()
+                .ok_or_else(|| unreachable("no address".to_string()))?
+        } else {
+            find_sync_primary(&self.primary).map_err(unreachable)?
+        };
+        let mut stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT).map_err(|e| unreachable(e.to_string()))?;
+        let lost = |e: io::Error| format!("Sync: lost connection to primary {}: {e}", self.primary);
+        stream.set_read_timeout(Some(READ_POLL)).map_err(lost)?;
+        stream.set_write_timeout(Some(WRITE_TIMEOUT)).map_err(lost)?;
+        writeln!(stream, "{}", json!({ "token": self.token })).map_err(lost)?;
+
+        let mut reader = BufReader::new(stream);
+        let mut line = Vec::new();
+        let mut last_heard = Instant::now();
+        while !self.stop.load(Ordering::Relaxed) {
+            if line.len() >= MAX_MESSAGE_BYTES {
+                return Err(format!(
+                    "Sync: primary {} sent a line over {MAX_MESSAGE_BYTES} bytes",
+                    self.primary
+                ));
+            }
+            // A timed-out read keeps what it got so far in `line`.
+            let limit = (MAX_MESSAGE_BYTES - line.len()) as u64;
+            match reader.by_ref().take(limit).read_until(b'\n', &mut line) {
+                Ok(0) => return Ok(()),
+                Ok(_) if line.ends_with(b"\n") => {}
+                Ok(_) => continue,
+                Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {
+                    if last_heard.elapsed() > SILENCE_LIMIT {
+                        return Err(format!("Sync: primary {} stopped responding", self.primary));
+                    }
+                    continue;
+                }
+                Err(e) => return Err(lost(e)),
+            }
+            last_heard = Instant::now();
+            let message = std::mem::take(&mut line);
+            if message.trim_ascii().is_empty() {
+                continue;
+            }
+            let Ok(mut message) = serde_json::from_slice::<Value>(&message) else {
+                continue;
+            };
+            if let Some(error) = message.get("error").and_then(Value::as_str) {
+                return Err(format!("Sync: primary {} refused this board: {error}", self.primary));
+            }
+            let Some(mut snapshot) = message.get_mut("snapshot").map(Value::take) else {
+                continue;
+            };
+            let local_base = self.base_dir.lock().ok().and_then(|base_dir| base_dir.clone());
+            if let (Some(from), Some(to)) = (message.get("base_dir").and_then(Value::as_str), local_base) {
+                relocate_assets(&mut snapshot, from, &to);
+            }
+            self.connected.store(true, Ordering::Relaxed);
+            self.mirrored.store(true, Ordering::Relaxed);
+            on_mirrored();
+            (self.on_snapshot)(snapshot);
+        }
+        Ok(())
+    }
+
+    fn sleep(&self, duration: Duration) {
+        let until = Instant::now() + duration;
+        while !self.stop.load(Ordering::Relaxed) && Instant::now() < until {
+            thread::sleep(ACCEPT_POLL);
+        }
+    }
+}
+
+/// Reads the hello line, giving up once `HELLO_TIMEOUT` has passed in total,
+/// so a connection that trickles bytes cannot keep its slot any longer than
+/// one that sends nothing. Bytes are read one at a time so nothing past the
+/// line is taken from the stream.
+fn read_hello(mut stream: &TcpStream) -> io::Result<Vec<u8>> {
+    let deadline = Instant::now() + HELLO_TIMEOUT;
+    let mut hello = Vec::new();
+    let mut byte = [0; 1];
+    while hello.len() < MAX_HELLO_BYTES {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        stream.set_read_timeout(Some(remaining))?;
+        if stream.read(&mut byte)? == 0 || byte[0] == b'\n' {
+            break;
+        }
+        hello.push(byte[0]);
+    }
+    Ok(hello)
+}
+
+/// Points image paths under the primary's config folder at the same files
+/// under this board's, since the machines rarely share a folder layout.
+/// A path that would leave this board's folder, through `..` or a drive or
+/// root of its own, is dropped instead.
+fn relocate_assets(snapshot: &mut Value, from: &str, to: &Path) {
+    let relocate = |path: &mut Value| {
+        let Some(relative) = path.as_str().and_then(|path| path.strip_prefix(from)) else {
+            return;
+        };
+        // The primary may use the other kind of separator.
+        let parts: Vec<&str> = relative.split(['/', '\\']).filter(|part| !part.is_empty()).collect();
+        let enclosed = parts.iter().all(|part| {
+            let mut components = Path::new(part).components();
+            matches!((components.next(), components.next()), (Some(Component::Normal(_)), None))
+        });
+        *path = if enclosed {
+            let relocated = parts.iter().fold(to.to_path_buf(), |path, part| path.join(part));
+            Value::String(relocated.to_string_lossy().to_string())
+        } else {
+            Value::Null
+        };
+    };
+    if let Some(components) = snapshot.get_mut("components").and_then(Value::as_array_mut) {
+        components
+            .iter_mut()
+            .filter_map(|component| component.get_mut("source"))
+            .for_each(relocate);
+    }
+    if let Some(image) = snapshot.get_mut("splash").and_then(|splash| splash.get_mut("image")) {
+        relocate(image);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_tokens_in_full() {
+        assert!(tokens_match("courtside", "courtside"));
+        assert!(!tokens_match("courtside", "courtsidf"));
+        assert!(!tokens_match("courtside", "court"));
+        assert!(!tokens_match("courtside", "courtside2"));
+        assert!(!tokens_match("courtside", ""));
+    }
+
+    #[test]
+    fn relocates_only_paths_inside_the_config_folder() {
+        let to = Path::new("replica").join("config");
+        let mut snapshot = json!({
+            "components": [
+                { "source": "/primary/config/images\\logo.png" },
+                { "source": "/primary/config/../../etc/passwd" },
+                { "source": "/primary/config/./logo.png" },
+                { "source": "/elsewhere/logo.png" },
+            ],
+            "splash": { "image": "/primary/config/splash.png" },
+        });
+        relocate_assets(&mut snapshot, "/primary/config", &to);
+        let sources: Vec<&Value> =
+            snapshot["components"].as_array().unwrap().iter().map(|component| &component["source"]).collect();
+        assert_eq!(sources[0], &json!(to.join("images").join("logo.png").to_string_lossy()));
+        assert_eq!(sources[1], &Value::Null);
+        assert_eq!(sources[2], &Value::Null);
+        assert_eq!(sources[3], &json!("/elsewhere/logo.png"));
+        assert_eq!(snapshot["splash"]["image"], json!(to.join("splash.png").to_string_lossy()));
+    }
+}
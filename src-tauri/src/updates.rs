@@ -0,0 +1,88 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::time::Duration;
+
+/// The project's own releases, used when `[global.updates]` sets no `url`.
+pub const DEFAULT_RELEASES_URL: &str = "https://api.github.com/repos/kai-65537/AOLOT-scoreboard/releases/latest";
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Sent as `scoreboard://update-available` when a newer release exists.
+/// Installing it stays manual; `url` points at the release page.
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateInfo {
+    pub current_version: String,
+    pub version: String,
+    pub notes: String,
+    pub url: Option<String>,
+}
+
+/// Asks `releases_url` for the latest release. Returns it when it is newer
+/// than this build.
+pub fn check_for_update(releases_url: &str) -> Result<Option<UpdateInfo>, String> {
+    let body = ureq::AgentBuilder::new()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .get(releases_url)
+        .set("Accept", "application/vnd.github+json")
+        .set("User-Agent", concat!("aolot-scoreboard/", env!("CARGO_PKG_VERSION")))
+        .call()
+        .map_err(|e| format!("Failed checking for updates at {releases_url}: {e}"))?
+        .into_string()
+        .map_err(|e| format!("Failed reading release information from {releases_url}: {e}"))?;
+    let release = latest_release(&body).map_err(|e| format!("Invalid release information from {releases_url}: {e}"))?;
+
+    let current_version = env!("CARGO_PKG_VERSION");
+    let newer = compare_versions(&release.version, current_version)
+        .ok_or_else(|| format!("Release version '{}' from {releases_url} is not a version number", release.version))?
+        == Ordering::Greater;
+    Ok(newer.then(|| UpdateInfo {
+        current_version: current_version.to_string(),
+        ..release
+    }))
+}
+
+/// Reads a GitHub release, or the first published one of a release list.
+/// Self-hosted feeds can also use `version`, `notes`, and `url`.
+fn latest_release(body: &str) -> Result<UpdateInfo, String> {
+    let value: Value = serde_json::from_str(body).map_err(|e| e.to_string())?;
+    let release = match &value {
+        Value::Array(releases) => releases
+            .iter()
+            .find(|release| !flag(release, "draft") && !flag(release, "prerelease"))
+            .ok_or_else(|| "no published release".to_string())?,
+        _ => &value,
+    };
+    let text = |keys: [&str; 2]| keys.iter().find_map(|key| release.get(key)?.as_str()).map(str::to_string);
+    Ok(UpdateInfo {
+        current_version: String::new(),
+        version: text(["tag_name", "version"]).ok_or_else(|| "release has no tag_name or version".to_string())?,
+        notes: text(["body", "notes"]).unwrap_or_default(),
+        url: text(["html_url", "url"]),
+    })
+}
+
+fn flag(release: &Value, key: &str) -> bool {
+    release.get(key).and_then(Value::as_bool).unwrap_or(false)
+}
+
+/// Compares dotted versions such as `v1.2` and `1.2.0`, ignoring a leading
+/// `v` and any `-beta` or `+build` suffix. `None` when either is not a version.
+fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    let (a, b) = (version_numbers(a)?, version_numbers(b)?);
+    let len = a.len().max(b.len());
+    let part = |numbers: &[u64], index: usize| numbers.get(index).copied().unwrap_or(0);
+    Some(
+        (0..len)
+            .map(|index| part(&a, index).cmp(&part(&b, index)))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal),
+    )
+}
+
+fn version_numbers(version: &str) -> Option<Vec<u64>> {
+    let version = version.trim();
+    let version = version.strip_prefix(['v', 'V']).unwrap_or(version);
+    let core = version.split(['-', '+']).next()?;
+    core.split('.').map(|part| part.parse().ok()).collect()
+}
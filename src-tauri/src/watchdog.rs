@@ -0,0 +1,385 @@
+use crate::config::ScoreboardConfig;
+use crate::state::{RuntimeState, RuntimeValues};
+use serde::Serialize;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+// The timer loop beats every 50 ms and the gamepad loop every 8 ms, so these
+// thresholds only trip when a thread is wedged or has died.
+const TIMER_STALL_THRESHOLD: Duration = Duration::from_millis(1_000);
+const GAMEPAD_STALL_THRESHOLD: Duration = Duration::from_millis(2_000);
+/// How long a thread may go without beating while a known long operation
+/// runs, such as a script hook or a config apply it may be waiting on.
+const BUSY_STALL_THRESHOLD: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkerThread {
+    Timer,
+    Gamepad,
+}
+
+impl WorkerThread {
+    fn name(self) -> &'static str {
+        match self {
+            WorkerThread::Timer => "timer",
+            WorkerThread::Gamepad => "gamepad",
+        }
+    }
+
+    fn stall_threshold(self) -> Duration {
+        match self {
+            WorkerThread::Timer => TIMER_STALL_THRESHOLD,
+            WorkerThread::Gamepad => GAMEPAD_STALL_THRESHOLD,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HealthReport {
+    pub timer_thread_alive: bool,
+    pub gamepad_thread_alive: bool,
+    pub runtime_lock_healthy: bool,
+    pub recoveries: u32,
+    pub last_recovery: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Heartbeat {
+    generation: u64,
+    last_beat: Instant,
+    alive: bool,
+    /// Known long operations the thread is in or waiting on; see `LongOperation`.
+    busy: u32,
+}
+
+impl Heartbeat {
+    fn new() -> Self {
+        Self {
+            generation: 0,
+            last_beat: Instant::now(),
+            alive: true,
+            busy: 0,
+        }
+    }
+}
+
+/// What one watchdog check found, for the app to act on.
+pub struct Check {
+    /// The runtime lock was poisoned and the board values were put back, so
+    /// the board needs drawing again.
+    pub runtime_recovered: bool,
+    /// Worker threads that stopped beating and need a new thread.
+    pub stalled: Vec<WorkerThread>,
+    pub report: HealthReport,
+}
+
+/// The board's values as last seen behind a healthy lock. It stays small
+/// enough to take every check, unlike the whole runtime with its event log
+/// and history.
+#[derive(Debug, Clone)]
+pub struct RecoveryImage {
+    values: RuntimeValues,
+    running_timers: Vec<String>,
+    active_theme: Option<String>,
+    timer_display_offset_ms: i64,
+    splash_showing: bool,
+}
+
+impl RecoveryImage {
+    pub fn capture(runtime: &RuntimeState) -> Self {
+        let values = runtime.export_values();
+        let running_timers = values
+            .timers
+            .keys()
+            .filter(|id| runtime.timer_running(id))
+            .cloned()
+            .collect();
+        Self {
+            values,
+            running_timers,
+            active_theme: runtime.active_theme().map(str::to_string),
+            timer_display_offset_ms: runtime.timer_display_offset(),
+            splash_showing: runtime.splash_showing(),
+        }
+    }
+
+    /// A new runtime for `config` showing these values, for a board that has
+    /// to run apart from the live one.
+    pub fn to_runtime(&self, config: ScoreboardConfig) -> RuntimeState {
+        let mut runtime = RuntimeState::new();
+        runtime.replace_config(config);
+        self.restore(&mut runtime);
+        let _ = runtime.set_theme(self.active_theme.as_deref());
+        let _ = runtime.set_timer_display_offset(self.timer_display_offset_ms);
+        runtime
+    }
+
+    /// Puts the values back into a runtime whose lock was poisoned. Its config
+    /// is only ever replaced whole, so it is kept; the values a panic may have
+    /// left half-updated are overwritten, and running timers start again. A
+    /// splash that was up stays up, since nothing was reloaded.
+    pub fn restore(&self, runtime: &mut RuntimeState) {
+        runtime.restore_values(&self.values);
+        runtime.set_splash_showing(self.splash_showing);
+        for id in &self.running_timers {
+            if let Some(&remaining_ms) = self.values.timers.get(id) {
+                runtime.start_countdown(id, remaining_ms);
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Watchdog {
+    timer: Heartbeat,
+    gamepad: Heartbeat,
+    last_good: Option<RecoveryImage>,
+    runtime_lock_healthy: bool,
+    recoveries: u32,
+    last_recovery: Option<String>,
+}
+
+impl Watchdog {
+    pub fn new() -> Self {
+        Self {
+            timer: Heartbeat::new(),
+            gamepad: Heartbeat::new(),
+            last_good: None,
+            runtime_lock_healthy: true,
+            recoveries: 0,
+            last_recovery: None,
+        }
+    }
+
+    /// Starts a new generation for a worker thread. Threads from older
+    /// generations see `is_current` return false and exit on their next loop.
+    pub fn begin_generation(&mut self, thread: WorkerThread) -> u64 {
+        let heartbeat = self.heartbeat_mut(thread);
+        heartbeat.generation += 1;
+        heartbeat.last_beat = Instant::now();
+        heartbeat.alive = true;
+        heartbeat.generation
+    }
+
+    /// Records a heartbeat. Returns false when the thread has been superseded
+    /// by a respawned one and should exit.
+    pub fn beat(&mut self, thread: WorkerThread, generation: u64) -> bool {
+        let heartbeat = self.heartbeat_mut(thread);
+        if heartbeat.generation != generation {
+            return false;
+        }
+        heartbeat.last_beat = Instant::now();
+        heartbeat.alive = true;
+        true
+    }
+
+    /// Returns true, and counts a recovery, when the thread has not beaten
+    /// within its stall threshold and has to be replaced. While a known long
+    /// operation runs, a thread waiting on it gets `BUSY_STALL_THRESHOLD`.
+    fn check_stalled(&mut self, thread: WorkerThread, now: Instant) -> bool {
+        let heartbeat = self.heartbeat_mut(thread);
+        let threshold = if heartbeat.busy > 0 {
+            BUSY_STALL_THRESHOLD
+        } else {
+            thread.stall_threshold()
+        };
+        if now.duration_since(heartbeat.last_beat) <= threshold {
+            return false;
+        }
+        heartbeat.alive = false;
+        self.record_recovery(format!("Restarted stalled {} thread", thread.name()));
+        true
+    }
+
+    pub fn report(&self) -> HealthReport {
+        HealthReport {
+            timer_thread_alive: self.timer.alive,
+            gamepad_thread_alive: self.gamepad.alive,
+            runtime_lock_healthy: self.runtime_lock_healthy,
+            recoveries: self.recoveries,
+            last_recovery: self.last_recovery.clone(),
+        }
+    }
+
+    fn begin_busy(&mut self, thread: WorkerThread) {
+        self.heartbeat_mut(thread).busy += 1;
+    }
+
+    /// Ends a long operation. The thread gets a fresh threshold to beat
+    /// again, rather than being judged by the time it spent in it.
+    fn end_busy(&mut self, thread: WorkerThread) {
+        let heartbeat = self.heartbeat_mut(thread);
+        heartbeat.busy = heartbeat.busy.saturating_sub(1);
+        heartbeat.last_beat = Instant::now();
+    }
+
+    fn record_recovery(&mut self, message: String) {
+        self.recoveries += 1;
+        self.last_recovery = Some(message);
+    }
+
+    fn heartbeat_mut(&mut self, thread: WorkerThread) -> &mut Heartbeat {
+        match thread {
+            WorkerThread::Timer => &mut self.timer,
+            WorkerThread::Gamepad => &mut self.gamepad,
+        }
+    }
+}
+
+/// Marks a known long operation, such as a script hook or a config apply,
+/// until dropped, so the worker `threads` running it or held up by it are
+/// not restarted as stalled. A thread that never comes back is still
+/// restarted after `BUSY_STALL_THRESHOLD`.
+pub struct LongOperation<'a> {
+    watchdog: &'a Mutex<Watchdog>,
+    threads: &'static [WorkerThread],
+}
+
+impl<'a> LongOperation<'a> {
+    pub fn begin(watchdog: &'a Mutex<Watchdog>, threads: &'static [WorkerThread]) -> Self {
+        let mut guard = watchdog.lock().unwrap_or_else(PoisonError::into_inner);
+        for &thread in threads {
+            guard.begin_busy(thread);
+        }
+        Self { watchdog, threads }
+    }
+}
+
+impl Drop for LongOperation<'_> {
+    fn drop(&mut self) {
+        let mut watchdog = self.watchdog.lock().unwrap_or_else(PoisonError::into_inner);
+        for &thread in self.threads {
+            watchdog.end_busy(thread);
+        }
+    }
+}
+
+/// Runs one check: the runtime lock first, then each worker thread. Returns
+/// `None` when the watchdog's own lock is poisoned.
+pub fn check(runtime: &Mutex<RuntimeState>, watchdog: &Mutex<Watchdog>) -> Option<Check> {
+    let runtime_recovered = check_runtime_lock(runtime, watchdog);
+    let mut watchdog = watchdog.lock().ok()?;
+    let now = Instant::now();
+    let stalled = [WorkerThread::Timer, WorkerThread::Gamepad]
+        .into_iter()
+        .filter(|&thread| watchdog.check_stalled(thread, now))
+        .collect();
+    Some(Check {
+        runtime_recovered,
+        stalled,
+        report: watchdog.report(),
+    })
+}
+
+/// Takes a recovery image behind a healthy runtime lock. Behind a poisoned
+/// one, puts the last image back and clears the poison; returns true then.
+fn check_runtime_lock(runtime: &Mutex<RuntimeState>, watchdog: &Mutex<Watchdog>) -> bool {
+    let image = match runtime.lock() {
+        Ok(runtime) => RecoveryImage::capture(&runtime),
+        Err(poisoned) => {
+            let mut board = poisoned.into_inner();
+            if let Ok(mut watchdog) = watchdog.lock() {
+                if let Some(last_good) = &watchdog.last_good {
+                    last_good.restore(&mut board);
+                }
+                watchdog.runtime_lock_healthy = true;
+                watchdog.record_recovery("Restored board values from the last good check".to_string());
+            }
+            drop(board);
+            runtime.clear_poison();
+            return true;
+        }
+    };
+    if let Ok(mut watchdog) = watchdog.lock() {
+        watchdog.last_good = Some(image);
+        watchdog.runtime_lock_healthy = true;
+    }
+    false
+}
+
+/// The board to freeze the output on: the values from the watchdog's last
+/// good check on the live config, so the runtime with its event log and
+/// history is never copied. Before the first check, the values are taken
+/// from the runtime as it is now.
+pub fn frozen_board(runtime: &Mutex<RuntimeState>, watchdog: &Mutex<Watchdog>) -> Option<RuntimeState> {
+    // The config is only ever replaced whole, so it is sound even behind a
+    // poisoned lock.
+    let runtime = runtime.lock().unwrap_or_else(PoisonError::into_inner);
+    let config = runtime.config.clone()?;
+    let watchdog = watchdog.lock().unwrap_or_else(PoisonError::into_inner);
+    Some(match &watchdog.last_good {
+        Some(image) => image.to_runtime(config),
+        None => RecoveryImage::capture(&runtime).to_runtime(config),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::load_config_from_str;
+
+    #[test]
+    fn restarts_a_thread_that_stops_beating() {
+        let mut watchdog = Watchdog::new();
+        let generation = watchdog.begin_generation(WorkerThread::Timer);
+        assert!(watchdog.beat(WorkerThread::Timer, generation));
+        let later = Instant::now() + Duration::from_secs(2);
+        assert!(watchdog.check_stalled(WorkerThread::Timer, later));
+        assert_eq!(watchdog.report().recoveries, 1);
+    }
+
+    #[test]
+    fn waits_for_a_thread_held_up_by_a_long_operation() {
+        let watchdog = Mutex::new(Watchdog::new());
+        let later = Instant::now() + Duration::from_secs(2);
+        {
+            let _operation = LongOperation::begin(&watchdog, &[WorkerThread::Timer]);
+            let mut watchdog = watchdog.lock().unwrap();
+            assert!(!watchdog.check_stalled(WorkerThread::Timer, later));
+            assert!(watchdog.check_stalled(WorkerThread::Gamepad, later + Duration::from_secs(1)));
+            assert!(watchdog.check_stalled(WorkerThread::Timer, later + BUSY_STALL_THRESHOLD));
+        }
+        let mut watchdog = watchdog.lock().unwrap();
+        assert_eq!(watchdog.timer.busy, 0);
+        assert!(!watchdog.check_stalled(WorkerThread::Timer, Instant::now() + Duration::from_millis(500)));
+        assert!(watchdog.check_stalled(WorkerThread::Timer, later));
+    }
+
+    #[test]
+    fn keeps_the_splash_up_when_restoring_values() {
+        let config = load_config_from_str(
+            r#"
+[global.splash]
+group = "splash"
+
+[group.splash]
+offset = { x = 0, y = 0 }
+
+[sponsor]
+type = "label"
+default = "Starting soon"
+group = "splash"
+position = { x = 10, y = 10 }
+
+[home_score]
+type = "number"
+default = 0
+position = { x = 10, y = 40 }
+"#,
+        )
+        .unwrap();
+        let mut runtime = RuntimeState::new();
+        runtime.replace_config(config);
+        assert!(runtime.splash_showing());
+        let image = RecoveryImage::capture(&runtime);
+        image.restore(&mut runtime);
+        assert!(runtime.splash_showing());
+
+        runtime.show_live_scene();
+        let live = RecoveryImage::capture(&runtime);
+        image.restore(&mut runtime);
+        assert!(runtime.splash_showing());
+        live.restore(&mut runtime);
+        assert!(!runtime.splash_showing());
+    }
+}
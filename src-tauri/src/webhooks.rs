@@ -0,0 +1,357 @@
+use crate::config::{WebhookFormat, WebhookSettings};
+use crate::led_matrix::ErrorSink;
+use crate::state::UiSnapshot;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const RETRY_INITIAL: Duration = Duration::from_secs(2);
+const RETRY_MAX: Duration = Duration::from_secs(60);
+const IDLE_WAKE: Duration = Duration::from_secs(1);
+/// Events per request. Discord rejects messages over 2000 characters, so its batches are smaller.
+const MAX_BATCH_EVENTS: usize = 100;
+const MAX_DISCORD_BATCH_EVENTS: usize = 20;
+/// Oldest events are dropped beyond this, so a long outage cannot fill the disk.
+const MAX_QUEUED_EVENTS: usize = 10_000;
+
+/// One component value change, as queued on disk and posted to webhooks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEvent {
+    pub unix_ms: u64,
+    pub sequence: u64,
+    pub component: String,
+    pub component_type: String,
+    pub previous: String,
+    pub value: String,
+}
+
+enum WebhookCommand {
+    Configure {
+        webhooks: Vec<WebhookSettings>,
+        queue_dir: PathBuf,
+        on_error: ErrorSink,
+    },
+    Snapshot(UiSnapshot),
+}
+
+/// Posts score changes to configured webhooks from a background thread.
+/// Changes are written to a per-webhook queue file before sending, so nothing
+/// is lost while the venue is offline or the app restarts; the backlog is
+/// flushed in batches once the endpoint answers again.
+pub struct WebhookDispatcher {
+    commands: Mutex<Sender<WebhookCommand>>,
+}
+
+impl WebhookDispatcher {
+    pub fn new() -> Self {
+        let (commands, receiver) = mpsc::channel();
+        thread::spawn(move || run_dispatcher(receiver));
+        Self {
+            commands: Mutex::new(commands),
+        }
+    }
+
+    pub fn configure(&self, webhooks: Vec<WebhookSettings>, queue_dir: PathBuf, on_error: ErrorSink) {
+        self.send(WebhookCommand::Configure {
+            webhooks,
+            queue_dir,
+            on_error,
+        });
+    }
+
+    pub fn submit(&self, snapshot: &UiSnapshot) {
+        self.send(WebhookCommand::Snapshot(snapshot.clone()));
+    }
+
+    fn send(&self, command: WebhookCommand) {
+        if let Ok(commands) = self.commands.lock() {
+            let _ = commands.send(command);
+        }
+    }
+}
+
+struct WebhookQueue {
+    settings: WebhookSettings,
+    path: PathBuf,
+    pending: VecDeque<WebhookEvent>,
+    /// When the next request may be sent: the end of the batch window, or of the retry delay.
+    send_at: Option<Instant>,
+    retry_delay: Duration,
+    error_reported: bool,
+}
+
+struct Dispatcher {
+    agent: ureq::Agent,
+    queues: Vec<WebhookQueue>,
+    on_error: Option<ErrorSink>,
+    /// Last value per text component; `None` until the first snapshot after configuring.
+    values: Option<HashMap<String, String>>,
+}
+
+fn run_dispatcher(receiver: Receiver<WebhookCommand>) {
+    let mut dispatcher = Dispatcher {
+        agent: ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build(),
+        queues: Vec::new(),
+        on_error: None,
+        values: None,
+    };
+
+    loop {
+        let now = Instant::now();
+        let wait = dispatcher
+            .queues
+            .iter()
+            .filter_map(|queue| queue.send_at)
+            .min()
+            .map(|send_at| send_at.saturating_duration_since(now))
+            .unwrap_or(IDLE_WAKE)
+            .min(IDLE_WAKE);
+        match receiver.recv_timeout(wait) {
+            Ok(command) => dispatcher.handle(command),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+        for command in receiver.try_iter() {
+            dispatcher.handle(command);
+        }
+        dispatcher.send_due(Instant::now());
+    }
+}
+
+impl Dispatcher {
+    fn handle(&mut self, command: WebhookCommand) {
+        match command {
+            WebhookCommand::Configure {
+                webhooks,
+                queue_dir,
+                on_error,
+            } => {
+                self.on_error = Some(on_error);
+                self.values = None;
+                self.queues = webhooks
+                    .into_iter()
+                    .map(|settings| {
+                        let path = queue_dir.join(format!("{}.jsonl", settings.name));
+                        let pending = match read_queue(&path) {
+                            Ok(pending) => pending,
+                            Err(e) => {
+                                self.report(e);
+                                VecDeque::new()
+                            }
+                        };
+                        WebhookQueue {
+                            // Anything left over from a previous run goes out right away.
+                            send_at: (!pending.is_empty()).then(Instant::now),
+                            settings,
+                            path,
+                            pending,
+                            retry_delay: RETRY_INITIAL,
+                            error_reported: false,
+                        }
+                    })
+                    .collect();
+            }
+            WebhookCommand::Snapshot(snapshot) => {
+                if self.queues.is_empty() {
+                    return;
+                }
+                let events = self.diff(&snapshot);
+                if events.is_empty() {
+                    return;
+                }
+                let mut errors = Vec::new();
+                for queue in &mut self.queues {
+                    if let Err(e) = queue.push(&events) {
+                        errors.push(e);
+                    }
+                }
+                for e in errors {
+                    self.report(e);
+                }
+            }
+        }
+    }
+
+    /// Changes to text components since the previous snapshot. Timers and
+    /// possession time are left out since they change every tick, and tickers
+    /// since they are not score updates.
+    fn diff(&mut self, snapshot: &UiSnapshot) -> Vec<WebhookEvent> {
+        let current: HashMap<String, String> = snapshot
+            .components
+            .iter()
+            .filter(|component| !matches!(component.component_type.as_str(), "timer" | "possession-time" | "ticker"))
+            .filter_map(|component| Some((component.id.clone(), component.text.clone()?)))
+            .collect();
+        let Some(previous) = self.values.replace(current.clone()) else {
+            return Vec::new();
+        };
+
+        let unix_ms = unix_time_ms();
+        snapshot
+            .components
+            .iter()
+            .filter_map(|component| {
+                let value = current.get(&component.id)?;
+                // Components added by a reload start without a notification.
+                let before = previous.get(&component.id)?;
+                (before != value).then(|| WebhookEvent {
+                    unix_ms,
+                    sequence: snapshot.sequence,
+                    component: component.id.clone(),
+                    component_type: component.component_type.clone(),
+                    previous: before.clone(),
+                    value: value.clone(),
+                })
+            })
+            .collect()
+    }
+
+    fn send_due(&mut self, now: Instant) {
+        let mut errors = Vec::new();
+        for queue in &mut self.queues {
+            if queue.send_at.is_none_or(|send_at| now < send_at) {
+                continue;
+            }
+            match queue.send_batch(&self.agent) {
+                Ok(()) => {
+                    queue.retry_delay = RETRY_INITIAL;
+                    queue.error_reported = false;
+                    // A backlog left after an outage is flushed without waiting for a new batch window.
+                    queue.send_at = (!queue.pending.is_empty()).then_some(now);
+                }
+                Err(e) => {
+                    queue.send_at = Some(now + queue.retry_delay);
+                    queue.retry_delay = (queue.retry_delay * 2).min(RETRY_MAX);
+                    // Reported once per outage; the queue keeps retrying quietly.
+                    if !queue.error_reported {
+                        queue.error_reported = true;
+                        errors.push(e);
+                    }
+                }
+            }
+        }
+        for e in errors {
+            self.report(e);
+        }
+    }
+
+    fn report(&self, message: String) {
+        if let Some(on_error) = &self.on_error {
+            on_error(message);
+        }
+    }
+}
+
+impl WebhookQueue {
+    fn push(&mut self, events: &[WebhookEvent]) -> Result<(), String> {
+        if self.pending.is_empty() && self.send_at.is_none() {
+            self.send_at = Some(Instant::now() + Duration::from_millis(self.settings.batch_ms));
+        }
+        self.pending.extend(events.iter().cloned());
+        if self.pending.len() > MAX_QUEUED_EVENTS {
+            let overflow = self.pending.len() - MAX_QUEUED_EVENTS;
+            self.pending.drain(..overflow);
+            return self.persist();
+        }
+        append_queue(&self.path, events)
+    }
+
+    fn send_batch(&mut self, agent: &ureq::Agent) -> Result<(), String> {
+        let limit = match self.settings.format {
+            WebhookFormat::Json => MAX_BATCH_EVENTS,
+            WebhookFormat::Discord => MAX_DISCORD_BATCH_EVENTS,
+        };
+        let count = self.pending.len().min(limit);
+        if count == 0 {
+            return Ok(());
+        }
+        let batch: Vec<&WebhookEvent> = self.pending.iter().take(count).collect();
+        let body = match self.settings.format {
+            WebhookFormat::Json => serde_json::json!({ "events": batch }),
+            WebhookFormat::Discord => {
+                let lines: Vec<String> = batch
+                    .iter()
+                    .map(|event| format!("{}: {} \u{2192} {}", event.component, event.previous, event.value))
+                    .collect();
+                serde_json::json!({ "content": lines.join("\n") })
+            }
+        };
+
+        agent
+            .post(&self.settings.url)
+            .set("Content-Type", "application/json")
+            .send_string(&body.to_string())
+            .map_err(|e| format!("Webhook '{}' is unreachable, queueing updates: {e}", self.settings.name))?;
+
+        self.pending.drain(..count);
+        self.persist()
+    }
+
+    /// Rewrites the queue file with what is still pending.
+    fn persist(&self) -> Result<(), String> {
+        if self.pending.is_empty() {
+            return match fs::remove_file(&self.path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    Err(format!("Failed clearing webhook queue {}: {e}", self.path.display()))
+                }
+                _ => Ok(()),
+            };
+        }
+        let temp_path = self.path.with_extension("jsonl.tmp");
+        write_queue(&temp_path, &self.pending)
+            .and_then(|_| fs::rename(&temp_path, &self.path))
+            .map_err(|e| format!("Failed writing webhook queue {}: {e}", self.path.display()))
+    }
+}
+
+fn read_queue(path: &Path) -> Result<VecDeque<WebhookEvent>, String> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(VecDeque::new()),
+        Err(e) => return Err(format!("Failed reading webhook queue {}: {e}", path.display())),
+    };
+    // A line cut short by a crash is skipped rather than discarding the whole queue.
+    Ok(BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect())
+}
+
+fn append_queue(path: &Path, events: &[WebhookEvent]) -> Result<(), String> {
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| OpenOptions::new().create(true).append(true).open(path))
+        .and_then(|mut file| {
+            for event in events {
+                serde_json::to_writer(&mut file, event)?;
+                file.write_all(b"\n")?;
+            }
+            Ok(())
+        });
+    result.map_err(|e| format!("Failed writing webhook queue {}: {e}", path.display()))
+}
+
+fn write_queue(path: &Path, events: &VecDeque<WebhookEvent>) -> std::io::Result<()> {
+    let mut file = File::create(path)?;
+    for event in events {
+        serde_json::to_writer(&mut file, event)?;
+        file.write_all(b"\n")?;
+    }
+    file.sync_all()
+}
+
+fn unix_time_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
@@ -5,11 +5,26 @@ const root = document.querySelector("#scoreboard-root");
 const errorBanner = document.querySelector("#error-banner");
 const hotkeyToggle = document.querySelector("#hotkey-toggle");
 const hotkeyToggleHotspot = document.querySelector("#hotkey-toggle-hotspot");
+const sessionRestore = document.querySelector("#session-restore");
+const updateNotice = document.querySelector("#update-notice");
+const playerSelect = document.querySelector("#player-select");
+const inputTestPanel = document.querySelector("#input-test");
+const inputTestLog = document.querySelector("#input-test-log");
+const inputTestStop = document.querySelector("#input-test-stop");
+const freezeBanner = document.querySelector("#freeze-banner");
+const freezeMessage = document.querySelector("#freeze-message");
+const freezeResume = document.querySelector("#freeze-resume");
 const editDialog = document.querySelector("#label-edit-dialog");
 const editForm = document.querySelector("#label-edit-form");
 const editInput = document.querySelector("#label-edit-input");
+const editTextarea = document.querySelector("#label-edit-textarea");
 const editTitle = document.querySelector("#label-edit-title");
 const editCancel = document.querySelector("#label-edit-cancel");
+const unlockButton = document.querySelector("#unlock-board");
+const unlockDialog = document.querySelector("#unlock-dialog");
+const unlockForm = document.querySelector("#unlock-form");
+const unlockInput = document.querySelector("#unlock-input");
+const unlockCancel = document.querySelector("#unlock-cancel");
 
 let editingLabelId = null;
 let editingImageId = null;
@@ -18,13 +33,34 @@ let appliedHotkeysPaused = false;
 let isWindowActive = document.hasFocus();
 let isHotkeyToggleHotspotHovered = false;
 let isHotkeyToggleHovered = false;
+let focusedKeyCapture = false;
+// Shortcuts sent for keys still held, by key code, so the release matches
+// the press even when a modifier is let go first.
+const heldFocusedKeys = new Map();
+let inputTestActive = false;
+const INPUT_TEST_LOG_SIZE = 8;
+let lastSnapshotSequence = 0;
+let canvasWidth = 640;
+let canvasHeight = 480;
+let canvasRotation = 0;
+
+const MODIFIER_CODES = new Set([
+  "ControlLeft",
+  "ControlRight",
+  "AltLeft",
+  "AltRight",
+  "ShiftLeft",
+  "ShiftRight",
+  "MetaLeft",
+  "MetaRight",
+]);
 
 async function setHotkeysPaused(paused) {
   await invoke("set_hotkeys_paused", { paused });
 }
 
 function shouldPauseHotkeys() {
-  return manualHotkeysPaused || editingLabelId !== null || editingImageId !== null;
+  return manualHotkeysPaused || editingLabelId !== null || editingImageId !== null || unlockDialog.open;
 }
 
 function updateHotkeyToggleUi() {
@@ -61,13 +97,18 @@ async function openLabelEditor(item) {
     return;
   }
 
+  // Multiline labels edit in a textarea, where Enter adds a line break.
+  const multiline = item.wrap != null;
+  editInput.hidden = multiline;
+  editTextarea.hidden = !multiline;
+  const field = multiline ? editTextarea : editInput;
   editTitle.textContent = `Edit ${item.id}`;
-  editInput.value = item.text ?? "";
+  field.value = item.text ?? "";
   if (!editDialog.open) {
     editDialog.showModal();
   }
-  editInput.focus();
-  editInput.select();
+  field.focus();
+  field.select();
 }
 
 async function openImageEditor(item) {
@@ -95,30 +136,364 @@ async function openImageEditor(item) {
   }
 }
 
+function applyInputCapabilities(capabilities) {
+  focusedKeyCapture = capabilities?.global_shortcuts === false;
+  if (focusedKeyCapture && capabilities.reason) {
+    showError(capabilities.reason);
+  }
+}
+
+function shortcutFromKeyEvent(event) {
+  if (MODIFIER_CODES.has(event.code)) {
+    return null;
+  }
+
+  const parts = [];
+  if (event.ctrlKey) parts.push("Ctrl");
+  if (event.altKey) parts.push("Alt");
+  if (event.shiftKey) parts.push("Shift");
+  if (event.metaKey) parts.push("Super");
+  parts.push(event.code);
+  return parts.join("+");
+}
+
+async function handleFocusedKeyDown(event) {
+  // The input test also lists keys that no global shortcut picks up.
+  if ((!focusedKeyCapture && !inputTestActive) || event.repeat || editDialog.open || unlockDialog.open || shouldPauseHotkeys()) {
+    return;
+  }
+
+  const shortcut = shortcutFromKeyEvent(event);
+  if (!shortcut) {
+    return;
+  }
+
+  event.preventDefault();
+  heldFocusedKeys.set(event.code, shortcut);
+  try {
+    await invoke("handle_focused_key", { shortcut });
+  } catch (error) {
+    showError(String(error));
+  }
+}
+
+async function releaseFocusedKey(code) {
+  const shortcut = heldFocusedKeys.get(code);
+  if (!shortcut) {
+    return;
+  }
+
+  heldFocusedKeys.delete(code);
+  try {
+    await invoke("handle_focused_key_release", { shortcut });
+  } catch (error) {
+    showError(String(error));
+  }
+}
+
+async function offerSessionRestore() {
+  const session = await invoke("get_last_session");
+  if (!session) {
+    return;
+  }
+
+  const savedAt = new Date(session.saved_at_unix_ms).toLocaleTimeString();
+  sessionRestore.textContent = `Restore Last Session (${savedAt})`;
+  sessionRestore.hidden = false;
+}
+
+let updateUrl = null;
+
+function showUpdateNotice(update) {
+  updateUrl = update.url ?? null;
+  updateNotice.textContent = `Update available: ${update.version} (running ${update.current_version})`;
+  updateNotice.title = update.notes ?? "";
+  updateNotice.hidden = false;
+}
+
+function renderLockStatus(status) {
+  const locked = status?.locked === true;
+  unlockButton.hidden = !locked;
+  if (!locked && unlockDialog.open) {
+    unlockDialog.close();
+  }
+}
+
+async function openUnlockDialog() {
+  unlockInput.value = "";
+  if (!unlockDialog.open) {
+    unlockDialog.showModal();
+  }
+  // Keeps digit hotkeys from swallowing the PIN while it is typed.
+  await syncHotkeyPauseState().catch(() => {});
+  unlockInput.focus();
+}
+
+function renderPlayerSelect(status) {
+  if (!status?.active) {
+    playerSelect.hidden = true;
+    playerSelect.textContent = "";
+    return;
+  }
+
+  const jersey = status.jersey.padEnd(2, "_");
+  playerSelect.textContent = status.awaiting_action
+    ? `Player #${jersey}: choose action`
+    : `Player #${jersey}`;
+  playerSelect.hidden = false;
+}
+
+function renderInputTestStatus(status) {
+  inputTestActive = status?.active === true;
+  inputTestPanel.hidden = !inputTestActive;
+  if (!inputTestActive) {
+    inputTestLog.replaceChildren();
+  }
+}
+
+function logTestedInput(press) {
+  const entry = document.createElement("li");
+  entry.textContent = `${press.source}: ${press.input} → ${press.action ?? "(unbound)"}`;
+  entry.classList.toggle("input-test-unbound", press.action == null);
+  inputTestLog.prepend(entry);
+  while (inputTestLog.children.length > INPUT_TEST_LOG_SIZE) {
+    inputTestLog.lastElementChild.remove();
+  }
+}
+
+function renderFreezeStatus(status) {
+  if (!status?.frozen) {
+    freezeBanner.hidden = true;
+    freezeMessage.textContent = "";
+    return;
+  }
+
+  freezeMessage.textContent = `Output frozen: ${status.reason}. Clocks keep running; restart the app when safe.`;
+  freezeBanner.hidden = false;
+}
+
+function fitCanvas() {
+  root.style.width = `${canvasWidth}px`;
+  root.style.height = `${canvasHeight}px`;
+  // A quarter turn swaps which side of the canvas meets which side of the window.
+  const sideways = canvasRotation === 90 || canvasRotation === 270;
+  const fitWidth = sideways ? canvasHeight : canvasWidth;
+  const fitHeight = sideways ? canvasWidth : canvasHeight;
+  const scale = Math.min(window.innerWidth / fitWidth, window.innerHeight / fitHeight);
+  root.style.setProperty("--canvas-scale", String(scale > 0 ? scale : 1));
+  root.style.setProperty("--canvas-rotation", `${canvasRotation}deg`);
+}
+
+// Offsets that put the configured point of a text component at its position.
+const ALIGNMENT_TRANSFORMS = {
+  center: "translate(-50%, -50%)",
+  left: "translate(0, -50%)",
+  right: "translate(-100%, -50%)",
+  top: "translate(-50%, 0)",
+  bottom: "translate(-50%, -100%)",
+};
+
+const MULTILINE_TEXT_ALIGN = {
+  center: "center",
+  right: "right",
+  top: "center",
+  bottom: "center",
+};
+
+// Shrinks the font until the rendered text fits `item.fit`. The first step
+// scales by the overflow ratio; the loop then covers rounding and wrapping.
+function fitText(node, item) {
+  const fits = () =>
+    (!item.fit.max_width || node.scrollWidth <= item.fit.max_width) &&
+    (!item.fit.max_height || node.scrollHeight <= item.fit.max_height);
+  if (fits()) return;
+
+  const ratio = Math.min(
+    item.fit.max_width ? item.fit.max_width / node.scrollWidth : 1,
+    item.fit.max_height ? item.fit.max_height / node.scrollHeight : 1,
+  );
+  let size = Math.max(1, Math.floor(item.font_size * ratio));
+  node.style.fontSize = `${size}px`;
+  while (size > 1 && !fits()) {
+    size -= 1;
+    node.style.fontSize = `${size}px`;
+  }
+}
+
+// CSS has no transform for the baseline, so measure it with an empty inline
+// block, which sits on the baseline of the text it follows.
+function alignToBaseline(node) {
+  const probe = document.createElement("span");
+  probe.style.display = "inline-block";
+  probe.style.width = "0";
+  probe.style.height = "0";
+  node.appendChild(probe);
+  const baseline = probe.offsetTop;
+  probe.remove();
+  node.style.transform = `translate(-50%, -${baseline}px)`;
+}
+
+// Scroll position comes from the wall clock rather than a CSS animation, so
+// rebuilding the board on every snapshot does not restart the tickers.
+function scrollTickers(now) {
+  for (const track of root.querySelectorAll(".ticker-track")) {
+    const strip = track.parentElement.clientWidth;
+    const distance = strip + track.offsetWidth;
+    const offset = ((now / 1000) * Number(track.dataset.speed)) % distance;
+    track.style.transform = `translateX(${strip - offset}px)`;
+  }
+  requestAnimationFrame(scrollTickers);
+}
+
+function renderTicker(node, item) {
+  node.style.width = `${item.width}px`;
+  const track = document.createElement("div");
+  track.className = "ticker-track";
+  track.dataset.speed = String(item.ticker?.speed ?? 0);
+  for (const message of item.ticker?.messages ?? []) {
+    const span = document.createElement("span");
+    span.className = "ticker-message";
+    span.textContent = message;
+    track.appendChild(span);
+  }
+  node.appendChild(track);
+}
+
+// Video elements outlive the board rebuild on every snapshot, so playback
+// carries on instead of starting over. Moving a playing video within the same
+// task does not pause it.
+const videoNodes = new Map();
+
+function renderVideo(item) {
+  let node = videoNodes.get(item.id);
+  if (!node) {
+    node = document.createElement("video");
+    node.playsInline = true;
+    videoNodes.set(item.id, node);
+  }
+  const srcValue = item.source ?? "";
+  if (node.dataset.source !== srcValue) {
+    node.dataset.source = srcValue;
+    const convertFileSrc = window.__TAURI__.core?.convertFileSrc;
+    node.src = typeof convertFileSrc === "function" ? convertFileSrc(srcValue) : srcValue;
+  }
+  node.loop = item.video?.looped ?? false;
+  node.muted = item.video?.muted ?? false;
+  const restarts = String(item.video?.restarts ?? 0);
+  if (node.dataset.restarts !== restarts) {
+    node.dataset.restarts = restarts;
+    node.currentTime = 0;
+  }
+  if (item.video?.playing) {
+    if (node.paused) {
+      node.play().catch((error) => console.warn(`Could not play video ${item.id}`, error));
+    }
+  } else if (!node.paused) {
+    node.pause();
+  }
+  return node;
+}
+
+// A transparent background clears the window behind the canvas too, so
+// capture software sees only the components; a chroma key fills all of it.
+function applyWindowBackground(snapshot) {
+  const transparent = snapshot?.background_color === "transparent";
+  document.documentElement.classList.toggle("window-transparent", transparent);
+  document.documentElement.style.background = snapshot?.chroma_key ?? "";
+  document.body.style.background = snapshot?.chroma_key ?? "";
+}
+
+// Fonts shipped in the config's `fonts` folder, e.g. by a template pack,
+// work without being installed.
+function applyBundledFonts(fonts) {
+  let style = document.getElementById("bundled-fonts");
+  if (!style) {
+    style = document.createElement("style");
+    style.id = "bundled-fonts";
+    document.head.appendChild(style);
+  }
+  const convertFileSrc = window.__TAURI__.core?.convertFileSrc;
+  style.textContent = (fonts ?? [])
+    .map((font) => {
+      const source = typeof convertFileSrc === "function" ? convertFileSrc(font.path) : font.path;
+      return `@font-face { font-family: ${JSON.stringify(font.family)}; src: url(${JSON.stringify(source)}); }`;
+    })
+    .join("\n");
+}
+
+// The splash image fills the canvas beneath the splash group's components.
+function renderSplashImage(source) {
+  const node = document.createElement("img");
+  const convertFileSrc = window.__TAURI__.core?.convertFileSrc;
+  node.className = "splash-image";
+  node.src = typeof convertFileSrc === "function" ? convertFileSrc(source) : source;
+  node.alt = "";
+  return node;
+}
+
 function renderSnapshot(snapshot) {
   root.innerHTML = "";
   root.style.backgroundColor = snapshot?.background_color ?? "#000000";
+  applyWindowBackground(snapshot);
+  canvasWidth = snapshot?.canvas_width ?? 640;
+  canvasHeight = snapshot?.canvas_height ?? 480;
+  canvasRotation = snapshot?.rotate ?? 0;
+  fitCanvas();
+  // Set while the board idles in low-power mode.
+  root.style.opacity = snapshot?.dim == null ? "" : String(snapshot.dim);
+
+  if (snapshot?.splash?.image) {
+    root.appendChild(renderSplashImage(snapshot.splash.image));
+  }
 
+  // Components arrive topmost first; appending bottom first keeps ties in that order.
   const components = snapshot?.components ?? [];
   const editableImageHitAreas = [];
+  const shownVideos = new Set(
+    components.filter((item) => item.visible && item.component_type === "video").map((item) => item.id),
+  );
+  for (const [id, video] of videoNodes) {
+    if (!shownVideos.has(id)) {
+      video.pause();
+      videoNodes.delete(id);
+    }
+  }
   for (const item of [...components].reverse()) {
-    const node =
-      item.component_type === "image" || item.component_type === "image-toggle"
+    if (!item.visible) continue;
+    const isImage =
+      item.component_type === "image" ||
+      item.component_type === "image-toggle" ||
+      (item.component_type === "possession" && item.source != null);
+    const isVideo = item.component_type === "video";
+    const node = isVideo
+      ? renderVideo(item)
+      : isImage
         ? document.createElement("img")
         : document.createElement("div");
 
     node.className = `score-item score-item-${item.component_type}`;
     node.dataset.componentId = item.id;
+    if (item.double_bonus) {
+      node.classList.add("score-item-double-bonus");
+    } else if (item.bonus) {
+      node.classList.add("score-item-bonus");
+    }
+    if (item.pending) {
+      node.classList.add("score-item-pending");
+      node.dataset.pending = item.pending;
+      node.title = `Press ${item.pending} again to confirm`;
+    }
     node.style.left = `${item.x}px`;
     node.style.top = `${item.y}px`;
-    const centered =
-      item.alignment === "center" &&
-      (item.component_type === "number" ||
-        item.component_type === "timer" ||
-        item.component_type === "label");
-    node.style.transform = centered ? "translate(-50%, -50%)" : "";
-
-    if (item.component_type === "image" || item.component_type === "image-toggle") {
+    node.style.zIndex = String(item.layer);
+    const alignment = isImage || isVideo ? null : item.alignment;
+    node.style.transform = ALIGNMENT_TRANSFORMS[alignment] ?? "";
+
+    if (isVideo) {
+      node.style.width = `${item.width}px`;
+      node.style.height = `${item.height}px`;
+      node.style.opacity = String(item.opacity ?? 1);
+    } else if (isImage) {
       if (item.width) node.style.width = `${item.width}px`;
       if (item.height) node.style.height = `${item.height}px`;
       if (item.opacity != null) node.style.opacity = String(item.opacity);
@@ -135,7 +510,33 @@ function renderSnapshot(snapshot) {
       node.style.fontFamily = item.font_family;
       node.style.fontSize = `${item.font_size}px`;
       node.style.color = item.font_color;
-      node.textContent = item.text ?? "";
+      node.style.opacity = String(item.opacity ?? 1);
+      node.style.fontWeight = String(item.font_weight ?? 700);
+      node.style.fontStyle = item.font_italic ? "italic" : "";
+      node.style.letterSpacing = item.letter_spacing ? `${item.letter_spacing}px` : "";
+      node.style.fontVariantNumeric = item.monospace_digits ? "tabular-nums" : "";
+      // The stroke is centered on the glyph edge and painted under the fill,
+      // so doubling it leaves the configured width visible outside the text.
+      node.style.webkitTextStroke = item.outline ? `${item.outline.width * 2}px ${item.outline.color}` : "";
+      node.style.paintOrder = item.outline ? "stroke fill" : "";
+      node.style.textShadow = item.shadow
+        ? `${item.shadow.x}px ${item.shadow.y}px ${item.shadow.blur}px ${item.shadow.color}`
+        : "";
+      if (item.component_type === "ticker") {
+        renderTicker(node, item);
+      } else {
+        node.textContent = item.text ?? "";
+      }
+      if (item.wrap) {
+        // Lines line up on the side the component is anchored at.
+        node.style.textAlign = MULTILINE_TEXT_ALIGN[alignment] ?? "left";
+        if (item.wrap.max_width) {
+          node.style.whiteSpace = "pre-wrap";
+          node.style.overflowWrap = "break-word";
+          node.style.width = "max-content";
+          node.style.maxWidth = `${item.wrap.max_width}px`;
+        }
+      }
 
       if (item.component_type === "label" && item.editable) {
         node.style.cursor = "pointer";
@@ -147,6 +548,12 @@ function renderSnapshot(snapshot) {
     }
 
     root.appendChild(node);
+    if (item.fit && !isImage && !isVideo) {
+      fitText(node, item);
+    }
+    if (alignment === "baseline") {
+      alignToBaseline(node);
+    }
   }
 
   for (const item of editableImageHitAreas) {
@@ -155,6 +562,7 @@ function renderSnapshot(snapshot) {
     hitArea.dataset.componentId = item.id;
     hitArea.style.left = `${item.x}px`;
     hitArea.style.top = `${item.y}px`;
+    hitArea.style.zIndex = String(item.layer);
     if (item.width) hitArea.style.width = `${item.width}px`;
     if (item.height) hitArea.style.height = `${item.height}px`;
     hitArea.title = `Click to edit ${item.id}`;
@@ -171,6 +579,14 @@ function showError(message) {
   errorBanner.hidden = false;
 }
 
+// Countdown cues use the system voice; webviews without speech synthesis stay silent.
+function speak(text) {
+  if (!("speechSynthesis" in window)) {
+    return;
+  }
+  window.speechSynthesis.speak(new SpeechSynthesisUtterance(text));
+}
+
 function hideError() {
   errorBanner.hidden = true;
   errorBanner.textContent = "";
@@ -220,12 +636,90 @@ window.addEventListener("DOMContentLoaded", async () => {
   });
 
   window.addEventListener("blur", () => {
+    // Keys released while another window has focus never report a keyup.
+    for (const code of [...heldFocusedKeys.keys()]) {
+      void releaseFocusedKey(code);
+    }
     isWindowActive = false;
     isHotkeyToggleHotspotHovered = false;
     isHotkeyToggleHovered = false;
     updateHotkeyToggleUi();
   });
 
+  sessionRestore.addEventListener("click", async () => {
+    try {
+      await invoke("restore_last_session");
+      sessionRestore.hidden = true;
+      hideError();
+    } catch (error) {
+      showError(String(error));
+    }
+  });
+
+  updateNotice.addEventListener("click", async () => {
+    updateNotice.hidden = true;
+    const openUrl = window.__TAURI__.opener?.openUrl;
+    if (updateUrl && typeof openUrl === "function") {
+      try {
+        await openUrl(updateUrl);
+      } catch (error) {
+        showError(String(error));
+      }
+    }
+  });
+
+  inputTestStop.addEventListener("click", async () => {
+    try {
+      await invoke("stop_input_test");
+    } catch (error) {
+      showError(String(error));
+    }
+  });
+
+  freezeResume.addEventListener("click", async () => {
+    try {
+      await invoke("resume_live_output");
+    } catch (error) {
+      showError(String(error));
+    }
+  });
+
+  window.addEventListener("resize", fitCanvas);
+  fitCanvas();
+
+  window.addEventListener("keydown", (event) => {
+    void handleFocusedKeyDown(event);
+  });
+
+  window.addEventListener("keyup", (event) => {
+    void releaseFocusedKey(event.code);
+  });
+
+  unlockButton.addEventListener("click", () => {
+    void openUnlockDialog();
+  });
+
+  unlockCancel.addEventListener("click", () => {
+    unlockDialog.close();
+  });
+
+  unlockDialog.addEventListener("close", () => {
+    unlockInput.value = "";
+    void syncHotkeyPauseState().catch(() => {});
+  });
+
+  unlockForm.addEventListener("submit", async (event) => {
+    event.preventDefault();
+    try {
+      await invoke("unlock_board", { pin: unlockInput.value });
+      unlockDialog.close();
+      hideError();
+    } catch (error) {
+      unlockInput.value = "";
+      showError(String(error));
+    }
+  });
+
   editCancel.addEventListener("click", () => {
     editDialog.close();
   });
@@ -250,7 +744,7 @@ window.addEventListener("DOMContentLoaded", async () => {
     try {
       await invoke("update_label_text", {
         id: editingLabelId,
-        value: editInput.value,
+        value: editTextarea.hidden ? editInput.value : editTextarea.value,
       });
       editingLabelId = null;
       editDialog.close();
@@ -260,7 +754,14 @@ window.addEventListener("DOMContentLoaded", async () => {
     }
   });
 
+  requestAnimationFrame(scrollTickers);
+
   await listen("scoreboard://state-updated", (event) => {
+    const sequence = event.payload?.sequence ?? 0;
+    if (sequence <= lastSnapshotSequence) {
+      return;
+    }
+    lastSnapshotSequence = sequence;
     hideError();
     renderSnapshot(event.payload);
   });
@@ -268,4 +769,69 @@ window.addEventListener("DOMContentLoaded", async () => {
   await listen("scoreboard://error", (event) => {
     showError(String(event.payload));
   });
+
+  await listen("scoreboard://gamepad-status", (event) => {
+    const status = event.payload;
+    if (status && !status.available && status.error) {
+      showError(status.error);
+    }
+  });
+
+  await listen("scoreboard://gamepad-disconnected", (event) => {
+    const gamepad = event.payload;
+    showError(`Gamepad ${gamepad.id} (${gamepad.name}) disconnected`);
+  });
+
+  await listen("scoreboard://player-select", (event) => {
+    renderPlayerSelect(event.payload);
+  });
+
+  await listen("scoreboard://lock-status", (event) => {
+    renderLockStatus(event.payload);
+  });
+
+  await listen("scoreboard://capabilities", (event) => {
+    applyInputCapabilities(event.payload);
+  });
+
+  await listen("scoreboard://input-test-status", (event) => {
+    renderInputTestStatus(event.payload);
+  });
+
+  await listen("scoreboard://input-test", (event) => {
+    logTestedInput(event.payload);
+  });
+
+  await listen("scoreboard://freeze", (event) => {
+    renderFreezeStatus(event.payload);
+  });
+
+  await listen("scoreboard://voice-cue", (event) => {
+    speak(event.payload.text);
+  });
+
+  await listen("scoreboard://update-available", (event) => {
+    showUpdateNotice(event.payload);
+  });
+
+  await listen("scoreboard://bundled-fonts", (event) => {
+    applyBundledFonts(event.payload);
+  });
+
+  await listen("scoreboard://action-suppressed", (event) => {
+    const press = event.payload;
+    console.info(
+      `Ignored ${press.shortcut}: ${press.elapsed_ms} ms since last press (cooldown ${press.cooldown_ms} ms)`,
+    );
+  });
+
+  try {
+    applyInputCapabilities(await invoke("get_input_capabilities"));
+    renderLockStatus(await invoke("get_lock_status"));
+    renderFreezeStatus(await invoke("get_freeze_status"));
+    applyBundledFonts(await invoke("get_bundled_fonts"));
+    await offerSessionRestore();
+  } catch (error) {
+    showError(String(error));
+  }
 });